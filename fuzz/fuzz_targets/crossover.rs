@@ -0,0 +1,6 @@
+#![no_main]
+
+use genetic_algorithm_fn::fuzzing;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| fuzzing::crossover(data));
@@ -0,0 +1,451 @@
+use crate::rng::SplitMix64;
+use crate::solution::{self, Solution};
+use rand::RngCore;
+use std::collections::hash_map::Iter;
+use std::collections::HashMap;
+
+/// A single evaluation of an individual.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Evaluation {
+    /// The fidelity the individual was evaluated at, `1.0` being exact.
+    pub fidelity: f64,
+    /// The fitness at that fidelity.
+    pub fitness: f64,
+}
+
+/// Every evaluation of every individual during a run, so it can be analysed afterwards which
+/// individuals were only evaluated cheaply and which at full fidelity.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Archive {
+    entries: HashMap<Solution, Vec<Evaluation>>,
+    /// Bounds the number of individuals, `None` keeps every individual.
+    reservoir: Option<Reservoir>,
+}
+
+/// The state of the reservoir sampling of a bounded archive.
+#[derive(Debug, Clone, PartialEq)]
+struct Reservoir {
+    /// The maximal number of individuals kept.
+    max_entries: usize,
+    /// How many distinct individuals were offered to the archive.
+    n_seen: usize,
+    /// The kept individuals, so a random one can be replaced.
+    kept: Vec<Solution>,
+    /// Decides which individuals are kept.
+    rng: SplitMix64,
+}
+
+impl Archive {
+    /// Create an empty archive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::archive::Archive;
+    ///
+    /// assert!(Archive::new().is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Archive::default()
+    }
+    /// Create an archive that keeps at most `max_entries` individuals. Once more distinct
+    /// individuals were recorded, a uniform sample of them is kept (reservoir sampling) together
+    /// with all their evaluations.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_entries` - The maximal number of individuals kept.
+    /// * `seed` - The seed of the sampling.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::archive::{Archive, Evaluation};
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let mut archive = Archive::bounded(10, 42);
+    /// for x in 0..100 {
+    ///     archive.record(
+    ///         Solution::new(vec![x as f64]),
+    ///         Evaluation { fidelity: 1.0, fitness: 0.0 },
+    ///     );
+    /// }
+    /// assert_eq!(archive.len(), 10);
+    /// assert_eq!(archive.n_seen(), 100);
+    /// ```
+    pub fn bounded(max_entries: usize, seed: u64) -> Self {
+        Archive {
+            entries: HashMap::new(),
+            reservoir: Some(Reservoir {
+                max_entries,
+                n_seen: 0,
+                kept: Vec::new(),
+                rng: SplitMix64::new(seed),
+            }),
+        }
+    }
+    /// Record an evaluation of an individual.
+    ///
+    /// # Arguments
+    ///
+    /// * `solution` - The individual that was evaluated.
+    /// * `evaluation` - The fidelity and the resulting fitness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::archive::{Archive, Evaluation};
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let mut archive = Archive::new();
+    /// archive.record(Solution::new(vec![1.0]), Evaluation { fidelity: 0.5, fitness: 2.0 });
+    /// assert_eq!(archive.len(), 1);
+    /// ```
+    pub fn record(&mut self, solution: Solution, evaluation: Evaluation) {
+        self.record_all(solution, vec![evaluation]);
+    }
+    /// Record evaluations of an individual, sampling the individual if the archive is full.
+    ///
+    /// # Arguments
+    ///
+    /// * `solution` - The individual that was evaluated.
+    /// * `evaluations` - Its evaluations in the order they happened.
+    fn record_all(&mut self, solution: Solution, evaluations: Vec<Evaluation>) {
+        if let Some(kept) = self.entries.get_mut(&solution) {
+            kept.extend(evaluations);
+            return;
+        }
+        if let Some(reservoir) = &mut self.reservoir {
+            reservoir.n_seen += 1;
+            if reservoir.kept.len() < reservoir.max_entries {
+                reservoir.kept.push(solution.clone());
+            } else {
+                let index = (reservoir.rng.next_u64() % reservoir.n_seen as u64) as usize;
+                if index >= reservoir.max_entries {
+                    return;
+                }
+                let replaced = std::mem::replace(&mut reservoir.kept[index], solution.clone());
+                self.entries.remove(&replaced);
+            }
+        }
+        self.entries.insert(solution, evaluations);
+    }
+    /// Return all evaluations of an individual in the order they happened.
+    ///
+    /// # Arguments
+    ///
+    /// * `solution` - The individual to look up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::archive::{Archive, Evaluation};
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let mut archive = Archive::new();
+    /// archive.record(Solution::new(vec![1.0]), Evaluation { fidelity: 1.0, fitness: 2.0 });
+    /// assert_eq!(archive.evaluations(&Solution::new(vec![1.0])).len(), 1);
+    /// assert!(archive.evaluations(&Solution::new(vec![2.0])).is_empty());
+    /// ```
+    pub fn evaluations(&self, solution: &Solution) -> &[Evaluation] {
+        self.entries
+            .get(solution)
+            .map(|evaluations| evaluations.as_slice())
+            .unwrap_or(&[])
+    }
+    /// Return the fidelities an individual was evaluated at.
+    ///
+    /// # Arguments
+    ///
+    /// * `solution` - The individual to look up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::archive::{Archive, Evaluation};
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let mut archive = Archive::new();
+    /// archive.record(Solution::new(vec![1.0]), Evaluation { fidelity: 0.1, fitness: 1.5 });
+    /// archive.record(Solution::new(vec![1.0]), Evaluation { fidelity: 1.0, fitness: 2.0 });
+    /// assert_eq!(archive.fidelities(&Solution::new(vec![1.0])), vec![0.1, 1.0]);
+    /// ```
+    pub fn fidelities(&self, solution: &Solution) -> Vec<f64> {
+        self.evaluations(solution)
+            .iter()
+            .map(|evaluation| evaluation.fidelity)
+            .collect()
+    }
+    /// Return the highest fidelity an individual was evaluated at, `None` if it never was.
+    ///
+    /// # Arguments
+    ///
+    /// * `solution` - The individual to look up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::archive::{Archive, Evaluation};
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let mut archive = Archive::new();
+    /// archive.record(Solution::new(vec![1.0]), Evaluation { fidelity: 0.3, fitness: 1.5 });
+    /// assert_eq!(archive.max_fidelity(&Solution::new(vec![1.0])), Some(0.3));
+    /// ```
+    pub fn max_fidelity(&self, solution: &Solution) -> Option<f64> {
+        self.evaluations(solution)
+            .iter()
+            .map(|evaluation| evaluation.fidelity)
+            .max_by(f64::total_cmp)
+    }
+    /// Add all evaluations of another archive, e.g. the one of another island. If this archive
+    /// is bounded, it keeps a uniform sample of the individuals both archives saw: every kept
+    /// individual is drawn from one of the archives with a probability proportional to the
+    /// number of individuals that archive saw but which wasn't drawn yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The archive whose evaluations should be added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::archive::{Archive, Evaluation};
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let mut archive = Archive::new();
+    /// let mut other = Archive::new();
+    /// other.record(Solution::new(vec![1.0]), Evaluation { fidelity: 1.0, fitness: 2.0 });
+    /// archive.merge(other);
+    /// assert_eq!(archive.len(), 1);
+    /// ```
+    pub fn merge(&mut self, other: Archive) {
+        let n_seen_other = other.n_seen();
+        let Archive { entries, reservoir } = self;
+        let Some(reservoir) = reservoir else {
+            for (solution, evaluations) in other.entries {
+                self.record_all(solution, evaluations);
+            }
+            return;
+        };
+        // Individuals both archives kept are only seen once.
+        let mut n_other = n_seen_other;
+        let mut others = Vec::new();
+        for (solution, evaluations) in other.entries {
+            match entries.get_mut(&solution) {
+                Some(kept) => {
+                    kept.extend(evaluations);
+                    n_other = n_other.saturating_sub(1);
+                }
+                None => others.push((solution, evaluations)),
+            }
+        }
+        // Seeded runs must not depend on the iteration order of the `HashMap`.
+        others.sort_by(|(solution_a, _), (solution_b, _)| {
+            solution::compare_arguments(solution_a, solution_b)
+        });
+        let mut n_self = reservoir.n_seen;
+        let n_seen = n_self + n_other;
+        let mut own = std::mem::take(&mut reservoir.kept);
+        let mut merged = HashMap::new();
+        while reservoir.kept.len() < reservoir.max_entries && !(own.is_empty() && others.is_empty())
+        {
+            let from_own = match (own.is_empty(), others.is_empty()) {
+                (false, true) => true,
+                (true, false) => false,
+                _ => reservoir.rng.next_u64() % ((n_self + n_other).max(1) as u64) < n_self as u64,
+            };
+            let (solution, evaluations) = if from_own {
+                n_self = n_self.saturating_sub(1);
+                let solution = own.swap_remove(reservoir.rng.next_u64() as usize % own.len());
+                let evaluations = entries.remove(&solution).unwrap_or_default();
+                (solution, evaluations)
+            } else {
+                n_other = n_other.saturating_sub(1);
+                others.swap_remove(reservoir.rng.next_u64() as usize % others.len())
+            };
+            reservoir.kept.push(solution.clone());
+            merged.insert(solution, evaluations);
+        }
+        *entries = merged;
+        reservoir.n_seen = n_seen;
+    }
+    /// Return the number of distinct individuals in the archive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::archive::Archive;
+    ///
+    /// assert_eq!(Archive::new().len(), 0);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    /// Return how many distinct individuals were recorded, including the ones a bounded
+    /// archive dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::archive::{Archive, Evaluation};
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let mut archive = Archive::bounded(1, 0);
+    /// archive.record(Solution::new(vec![1.0]), Evaluation { fidelity: 1.0, fitness: 2.0 });
+    /// archive.record(Solution::new(vec![2.0]), Evaluation { fidelity: 1.0, fitness: 3.0 });
+    /// assert_eq!((archive.len(), archive.n_seen()), (1, 2));
+    /// ```
+    pub fn n_seen(&self) -> usize {
+        self.reservoir
+            .as_ref()
+            .map_or(self.entries.len(), |reservoir| reservoir.n_seen)
+    }
+    /// Return whether the archive contains no individual.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::archive::Archive;
+    ///
+    /// assert!(Archive::new().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    /// Iterate over all individuals and their evaluations, in no particular order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::archive::{Archive, Evaluation};
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let mut archive = Archive::new();
+    /// archive.record(Solution::new(vec![1.0]), Evaluation { fidelity: 1.0, fitness: 2.0 });
+    /// for (solution, evaluations) in archive.iter() {
+    ///     println!("{} was evaluated {} times", solution, evaluations.len());
+    /// }
+    /// ```
+    pub fn iter(&self) -> Iter<'_, Solution, Vec<Evaluation>> {
+        self.entries.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_keeps_evaluations_of_both() {
+        let mut archive = Archive::new();
+        archive.record(
+            Solution::new(vec![1.0]),
+            Evaluation {
+                fidelity: 0.5,
+                fitness: 1.0,
+            },
+        );
+        let mut other = Archive::new();
+        other.record(
+            Solution::new(vec![1.0]),
+            Evaluation {
+                fidelity: 1.0,
+                fitness: 2.0,
+            },
+        );
+        other.record(
+            Solution::new(vec![2.0]),
+            Evaluation {
+                fidelity: 1.0,
+                fitness: 3.0,
+            },
+        );
+        archive.merge(other);
+        assert_eq!(archive.len(), 2);
+        assert_eq!(
+            archive.fidelities(&Solution::new(vec![1.0])),
+            vec![0.5, 1.0]
+        );
+        assert_eq!(archive.max_fidelity(&Solution::new(vec![2.0])), Some(1.0));
+        assert_eq!(archive.max_fidelity(&Solution::new(vec![3.0])), None);
+    }
+    #[test]
+    fn bounded_archive_samples_uniformly() {
+        // Every individual should end up in the reservoir with probability 1/10.
+        let mut kept_early = 0;
+        for seed in 0..200 {
+            let mut archive = Archive::bounded(5, seed);
+            for x in 0..50 {
+                archive.record(
+                    Solution::new(vec![x as f64]),
+                    Evaluation {
+                        fidelity: 1.0,
+                        fitness: 0.0,
+                    },
+                );
+            }
+            assert_eq!(archive.len(), 5);
+            kept_early += (0..25)
+                .filter(|x| {
+                    !archive
+                        .evaluations(&Solution::new(vec![*x as f64]))
+                        .is_empty()
+                })
+                .count();
+        }
+        // Half of the individuals were recorded early, so about half of the 1000 kept are.
+        assert!((400..600).contains(&kept_early), "{}", kept_early);
+    }
+    #[test]
+    fn bounded_archive_keeps_evaluations_of_kept_individuals() {
+        let mut archive = Archive::bounded(1, 0);
+        for fitness in [1.0, 2.0] {
+            archive.record(
+                Solution::new(vec![1.0]),
+                Evaluation {
+                    fidelity: 1.0,
+                    fitness,
+                },
+            );
+        }
+        assert_eq!(archive.evaluations(&Solution::new(vec![1.0])).len(), 2);
+        assert_eq!(archive.n_seen(), 1);
+    }
+    #[test]
+    fn bounded_merge_weights_the_archives_by_what_they_saw() {
+        let archive = |xs: std::ops::Range<usize>, seed| {
+            let mut archive = Archive::bounded(10, seed);
+            for x in xs {
+                archive.record(
+                    Solution::new(vec![x as f64]),
+                    Evaluation {
+                        fidelity: 1.0,
+                        fitness: 0.0,
+                    },
+                );
+            }
+            archive
+        };
+        // The small archive saw 10 of 1010 individuals, so it should keep about 0.1 of them.
+        let mut kept_small = 0;
+        for seed in 0..100 {
+            let mut merged = archive(0..10, seed);
+            merged.merge(archive(10..1010, seed + 1000));
+            assert_eq!((merged.len(), merged.n_seen()), (10, 1010));
+            kept_small += (0..10)
+                .filter(|x| {
+                    !merged
+                        .evaluations(&Solution::new(vec![*x as f64]))
+                        .is_empty()
+                })
+                .count();
+        }
+        assert!(kept_small < 50, "{}", kept_small);
+        // Individuals kept by both archives are merged and counted once.
+        let mut merged = archive(0..5, 0);
+        merged.merge(archive(3..8, 1));
+        assert_eq!((merged.len(), merged.n_seen()), (8, 8));
+        assert_eq!(merged.evaluations(&Solution::new(vec![4.0])).len(), 2);
+    }
+}
@@ -0,0 +1,446 @@
+use crate::function::FunctionError;
+use std::cmp::Ordering;
+use std::iter::Sum;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// The operations an objective written for `Differentiable` may use. It is implemented for
+/// `f64`, to compute the function value, and for `Dual`, to compute its derivatives at the
+/// same time.
+pub trait Float:
+    Copy
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + Sum
+{
+    /// A constant, whose derivative is `0`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value of the constant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::autodiff::{Dual, Float};
+    ///
+    /// assert_eq!(Dual::constant(2.0).derivative, 0.0);
+    /// ```
+    fn constant(value: f64) -> Self;
+    /// The value without any derivative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::autodiff::{Dual, Float};
+    ///
+    /// assert_eq!(Dual::variable(2.0).value(), 2.0);
+    /// ```
+    fn value(self) -> f64;
+    /// Raise to an integer power.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The exponent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::autodiff::{Dual, Float};
+    ///
+    /// assert_eq!(Dual::variable(3.0).powi(2).derivative, 6.0);
+    /// ```
+    fn powi(self, n: i32) -> Self;
+    /// Raise to a floating point power.
+    ///
+    /// # Arguments
+    ///
+    /// * `exponent` - The exponent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::autodiff::{Dual, Float};
+    ///
+    /// assert_eq!(Dual::variable(4.0).powf(0.5).derivative, 0.25);
+    /// ```
+    fn powf(self, exponent: f64) -> Self;
+    /// The square root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::autodiff::{Dual, Float};
+    ///
+    /// assert_eq!(Dual::variable(4.0).sqrt().derivative, 0.25);
+    /// ```
+    fn sqrt(self) -> Self;
+    /// The exponential function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::autodiff::{Dual, Float};
+    ///
+    /// assert_eq!(Dual::variable(0.0).exp().derivative, 1.0);
+    /// ```
+    fn exp(self) -> Self;
+    /// The natural logarithm.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::autodiff::{Dual, Float};
+    ///
+    /// assert_eq!(Dual::variable(2.0).ln().derivative, 0.5);
+    /// ```
+    fn ln(self) -> Self;
+    /// The sine.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::autodiff::{Dual, Float};
+    ///
+    /// assert_eq!(Dual::variable(0.0).sin().derivative, 1.0);
+    /// ```
+    fn sin(self) -> Self;
+    /// The cosine.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::autodiff::{Dual, Float};
+    ///
+    /// assert_eq!(Dual::variable(0.0).cos().derivative, 0.0);
+    /// ```
+    fn cos(self) -> Self;
+    /// The absolute value, its derivative at `0` is `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::autodiff::{Dual, Float};
+    ///
+    /// assert_eq!(Dual::variable(-2.0).abs().derivative, -1.0);
+    /// ```
+    fn abs(self) -> Self;
+}
+
+impl Float for f64 {
+    fn constant(value: f64) -> Self {
+        value
+    }
+    fn value(self) -> f64 {
+        self
+    }
+    fn powi(self, n: i32) -> Self {
+        f64::powi(self, n)
+    }
+    fn powf(self, exponent: f64) -> Self {
+        f64::powf(self, exponent)
+    }
+    fn sqrt(self) -> Self {
+        f64::sqrt(self)
+    }
+    fn exp(self) -> Self {
+        f64::exp(self)
+    }
+    fn ln(self) -> Self {
+        f64::ln(self)
+    }
+    fn sin(self) -> Self {
+        f64::sin(self)
+    }
+    fn cos(self) -> Self {
+        f64::cos(self)
+    }
+    fn abs(self) -> Self {
+        f64::abs(self)
+    }
+}
+
+/// A dual number for forward-mode automatic differentiation: a value and its derivative with
+/// respect to one variable, which every operation carries along by the chain rule. Duals are
+/// compared by their values only.
+#[derive(Debug, Clone, Copy)]
+pub struct Dual {
+    /// The value.
+    pub value: f64,
+    /// The derivative of the value with respect to the variable.
+    pub derivative: f64,
+}
+
+impl Dual {
+    /// The variable that is differentiated for, whose derivative is `1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value of the variable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::autodiff::Dual;
+    ///
+    /// let x = Dual::variable(3.0);
+    /// assert_eq!((x * x).derivative, 6.0);
+    /// ```
+    pub fn variable(value: f64) -> Self {
+        Dual {
+            value,
+            derivative: 1.0,
+        }
+    }
+    /// Apply a function with the given value and derivative at `self.value`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value of the function.
+    /// * `derivative` - The derivative of the function.
+    fn chain(self, value: f64, derivative: f64) -> Self {
+        Dual {
+            value,
+            derivative: derivative * self.derivative,
+        }
+    }
+}
+
+impl PartialEq for Dual {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl PartialOrd for Dual {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl Add for Dual {
+    type Output = Dual;
+    fn add(self, other: Dual) -> Dual {
+        Dual {
+            value: self.value + other.value,
+            derivative: self.derivative + other.derivative,
+        }
+    }
+}
+
+impl Sub for Dual {
+    type Output = Dual;
+    fn sub(self, other: Dual) -> Dual {
+        Dual {
+            value: self.value - other.value,
+            derivative: self.derivative - other.derivative,
+        }
+    }
+}
+
+impl Mul for Dual {
+    type Output = Dual;
+    fn mul(self, other: Dual) -> Dual {
+        Dual {
+            value: self.value * other.value,
+            derivative: self.derivative * other.value + self.value * other.derivative,
+        }
+    }
+}
+
+impl Div for Dual {
+    type Output = Dual;
+    fn div(self, other: Dual) -> Dual {
+        Dual {
+            value: self.value / other.value,
+            derivative: (self.derivative * other.value - self.value * other.derivative)
+                / (other.value * other.value),
+        }
+    }
+}
+
+impl Neg for Dual {
+    type Output = Dual;
+    fn neg(self) -> Dual {
+        Dual {
+            value: -self.value,
+            derivative: -self.derivative,
+        }
+    }
+}
+
+impl Sum for Dual {
+    fn sum<I: Iterator<Item = Dual>>(iter: I) -> Dual {
+        iter.fold(Dual::constant(0.0), Add::add)
+    }
+}
+
+impl Float for Dual {
+    fn constant(value: f64) -> Self {
+        Dual {
+            value,
+            derivative: 0.0,
+        }
+    }
+    fn value(self) -> f64 {
+        self.value
+    }
+    fn powi(self, n: i32) -> Self {
+        self.chain(self.value.powi(n), f64::from(n) * self.value.powi(n - 1))
+    }
+    fn powf(self, exponent: f64) -> Self {
+        self.chain(
+            self.value.powf(exponent),
+            exponent * self.value.powf(exponent - 1.0),
+        )
+    }
+    fn sqrt(self) -> Self {
+        let sqrt = self.value.sqrt();
+        self.chain(sqrt, 0.5 / sqrt)
+    }
+    fn exp(self) -> Self {
+        let exp = self.value.exp();
+        self.chain(exp, exp)
+    }
+    fn ln(self) -> Self {
+        self.chain(self.value.ln(), 1.0 / self.value)
+    }
+    fn sin(self) -> Self {
+        self.chain(self.value.sin(), self.value.cos())
+    }
+    fn cos(self) -> Self {
+        self.chain(self.value.cos(), -self.value.sin())
+    }
+    fn abs(self) -> Self {
+        let sign = if self.value == 0.0 {
+            0.0
+        } else {
+            self.value.signum()
+        };
+        self.chain(self.value.abs(), sign)
+    }
+}
+
+/// An objective written generically over `Float`, so it can be evaluated with `f64` and
+/// differentiated with `Dual`, see `Function::new_autodiff`.
+pub trait Differentiable: Send + Sync + 'static {
+    /// Compute the function value.
+    ///
+    /// # Arguments
+    ///
+    /// * `arguments` - The arguments the function value should be computed for.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::autodiff::{Differentiable, Float};
+    /// use genetic_algorithm_fn::function::FunctionError;
+    ///
+    /// struct Sphere;
+    /// impl Differentiable for Sphere {
+    ///     fn evaluate<T: Float>(&self, arguments: &[T]) -> Result<T, FunctionError> {
+    ///         Ok(-arguments.iter().map(|x| x.powi(2)).sum::<T>())
+    ///     }
+    /// }
+    /// assert_eq!(Sphere.evaluate(&[1.0, 2.0]), Ok(-5.0));
+    /// ```
+    fn evaluate<T: Float>(&self, arguments: &[T]) -> Result<T, FunctionError>;
+}
+
+/// Compute the function value of `objective` and its gradient, with one forward pass per
+/// argument.
+///
+/// # Arguments
+///
+/// * `objective` - The objective to differentiate.
+/// * `arguments` - The arguments to differentiate at.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::autodiff::{self, Differentiable, Float};
+/// use genetic_algorithm_fn::function::FunctionError;
+///
+/// struct Product;
+/// impl Differentiable for Product {
+///     fn evaluate<T: Float>(&self, arguments: &[T]) -> Result<T, FunctionError> {
+///         Ok(arguments[0] * arguments[1])
+///     }
+/// }
+/// assert_eq!(
+///     autodiff::gradient(&Product, &[2.0, 3.0]),
+///     Ok((6.0, vec![3.0, 2.0]))
+/// );
+/// ```
+pub fn gradient<D>(objective: &D, arguments: &[f64]) -> Result<(f64, Vec<f64>), FunctionError>
+where
+    D: Differentiable + ?Sized,
+{
+    if arguments.is_empty() {
+        return Ok((objective.evaluate(arguments)?, Vec::new()));
+    }
+    let mut value = 0.0;
+    let mut gradient = Vec::with_capacity(arguments.len());
+    for variable in 0..arguments.len() {
+        let duals = arguments
+            .iter()
+            .enumerate()
+            .map(|(idx, argument)| {
+                if idx == variable {
+                    Dual::variable(*argument)
+                } else {
+                    Dual::constant(*argument)
+                }
+            })
+            .collect::<Vec<Dual>>();
+        let result = objective.evaluate(&duals)?;
+        value = result.value;
+        gradient.push(result.derivative);
+    }
+    Ok((value, gradient))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{gradient, Differentiable, Dual, Float};
+    use crate::function::FunctionError;
+
+    /// The Rosenbrock function, negated to be maximized.
+    struct Rosenbrock;
+
+    impl Differentiable for Rosenbrock {
+        fn evaluate<T: Float>(&self, x: &[T]) -> Result<T, FunctionError> {
+            let one = T::constant(1.0);
+            Ok(-((one - x[0]).powi(2) + T::constant(100.0) * (x[1] - x[0].powi(2)).powi(2)))
+        }
+    }
+
+    #[test]
+    fn gradient_matches_the_analytical_one() {
+        let (x, y) = (0.5, -1.5);
+        let (value, gradient) = gradient(&Rosenbrock, &[x, y]).unwrap();
+        assert_eq!(value, Rosenbrock.evaluate(&[x, y]).unwrap());
+        let expected = [
+            -(-2.0 * (1.0 - x) - 400.0 * x * (y - x * x)),
+            -(200.0 * (y - x * x)),
+        ];
+        for (actual, expected) in gradient.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-9);
+        }
+    }
+    #[test]
+    fn dual_operations_follow_the_chain_rule() {
+        let x = Dual::variable(2.0);
+        let derivative = |dual: Dual| dual.derivative;
+        assert_eq!(derivative(x / Dual::constant(4.0)), 0.25);
+        assert_eq!(derivative(Dual::constant(1.0) / x), -0.25);
+        assert_eq!(derivative(-x.sin()), -2.0_f64.cos());
+        assert_eq!(derivative(x.exp().ln()), 1.0);
+        assert_eq!(derivative([x, x, x].into_iter().sum()), 3.0);
+        assert_eq!(derivative(Dual::constant(0.0).abs()), 0.0);
+        assert!(Dual::constant(1.0) < x);
+    }
+}
@@ -0,0 +1,216 @@
+use crate::solution::Solution;
+use rand::Rng;
+use std::ops::Range;
+
+/// What happens to arguments of offspring outside of their bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoundaryPolicy {
+    /// Offspring are kept as the stages of the pipeline return them, the bounds only scale the
+    /// mutation step size and `Pipeline::reproduce_checked` reports children outside of them.
+    #[default]
+    Ignore,
+    /// Arguments are set to the nearest bound. Offspring pile up on the boundary, which suits
+    /// optima that lie on it.
+    Clamp,
+    /// Arguments are mirrored at the bound they crossed, as often as needed to land within
+    /// the bounds, so the distance they overshot is kept as distance from the boundary.
+    Reflect,
+    /// Arguments are drawn anew uniformly from their bounds.
+    Resample,
+}
+
+/// The bounds of every argument of the search space and how offspring are kept within them.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Bounds {
+    /// The bounds of every argument, arguments beyond the end are unbounded.
+    ranges: Vec<Range<f64>>,
+    /// What happens to arguments outside of their bounds.
+    policy: BoundaryPolicy,
+}
+
+impl From<Vec<Range<f64>>> for Bounds {
+    fn from(ranges: Vec<Range<f64>>) -> Self {
+        Bounds::new(ranges)
+    }
+}
+
+impl Bounds {
+    /// Create bounds that are not enforced, see `BoundaryPolicy::Ignore`.
+    ///
+    /// # Arguments
+    ///
+    /// * `ranges` - The bounds of every argument, arguments beyond the end are unbounded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::bounds::{BoundaryPolicy, Bounds};
+    ///
+    /// let bounds = Bounds::new(vec![0.0..1.0, -5.0..5.0]);
+    /// assert_eq!(bounds.policy(), BoundaryPolicy::Ignore);
+    /// ```
+    pub fn new(ranges: Vec<Range<f64>>) -> Self {
+        Bounds {
+            ranges,
+            policy: BoundaryPolicy::default(),
+        }
+    }
+    /// Set what happens to arguments outside of their bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The new policy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::bounds::{BoundaryPolicy, Bounds};
+    ///
+    /// let bounds = Bounds::new(vec![0.0..1.0]).with_policy(BoundaryPolicy::Reflect);
+    /// assert_eq!(bounds.policy(), BoundaryPolicy::Reflect);
+    /// ```
+    pub fn with_policy(mut self, policy: BoundaryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+    /// The bounds of every argument.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::bounds::Bounds;
+    ///
+    /// assert_eq!(Bounds::new(vec![0.0..1.0]).ranges(), &[0.0..1.0]);
+    /// ```
+    pub fn ranges(&self) -> &[Range<f64>] {
+        &self.ranges
+    }
+    /// What happens to arguments outside of their bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::bounds::{BoundaryPolicy, Bounds};
+    ///
+    /// assert_eq!(Bounds::default().policy(), BoundaryPolicy::Ignore);
+    /// ```
+    pub fn policy(&self) -> BoundaryPolicy {
+        self.policy
+    }
+    /// Return whether every argument of `solution` lies within its bounds, including their
+    /// ends.
+    ///
+    /// # Arguments
+    ///
+    /// * `solution` - The solution to check.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::bounds::Bounds;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let bounds = Bounds::new(vec![0.0..1.0]);
+    /// assert!(bounds.contains(&Solution::new(vec![1.0, 100.0])));
+    /// assert!(!bounds.contains(&Solution::new(vec![1.5])));
+    /// ```
+    pub fn contains(&self, solution: &Solution) -> bool {
+        solution
+            .get_arguments()
+            .iter()
+            .zip(&self.ranges)
+            .all(|(argument, range)| (range.start..=range.end).contains(argument))
+    }
+    /// Bring the arguments of `solution` outside of their bounds back according to the policy.
+    /// Arguments within their bounds and step sizes are left alone.
+    ///
+    /// # Arguments
+    ///
+    /// * `solution` - The solution to confine.
+    /// * `rng` - The random number generator `BoundaryPolicy::Resample` draws from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::bounds::{BoundaryPolicy, Bounds};
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let bounds = Bounds::new(vec![0.0..10.0; 2]).with_policy(BoundaryPolicy::Reflect);
+    /// let confined = bounds.confine(Solution::new(vec![12.0, -3.0]), &mut rand::thread_rng());
+    /// assert_eq!(confined.get_arguments(), vec![8.0, 3.0]);
+    /// ```
+    pub fn confine<G>(&self, solution: Solution, rng: &mut G) -> Solution
+    where
+        G: Rng + ?Sized,
+    {
+        if self.policy == BoundaryPolicy::Ignore || self.contains(&solution) {
+            return solution;
+        }
+        let mut arguments = solution.get_arguments();
+        for (argument, range) in arguments.iter_mut().zip(&self.ranges) {
+            if (range.start..=range.end).contains(argument) {
+                continue;
+            }
+            let width = range.end - range.start;
+            *argument = match self.policy {
+                BoundaryPolicy::Reflect if argument.is_finite() && width > 0.0 => {
+                    let offset = (*argument - range.start).rem_euclid(2.0 * width);
+                    range.start + width - (offset - width).abs()
+                }
+                BoundaryPolicy::Resample => sample(range, rng),
+                _ => argument.max(range.start).min(range.end),
+            };
+        }
+        solution.with_arguments(arguments)
+    }
+}
+
+/// Draw a value uniformly from `range`, or its start if it is empty.
+///
+/// # Arguments
+///
+/// * `range` - The range to draw from.
+/// * `rng` - The random number generator to sample with.
+pub(crate) fn sample<G>(range: &Range<f64>, rng: &mut G) -> f64
+where
+    G: Rng + ?Sized,
+{
+    if range.start < range.end {
+        rng.gen_range(range.clone())
+    } else {
+        range.start
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::SplitMix64;
+
+    #[test]
+    fn policies_bring_arguments_back_into_the_bounds() {
+        let mut rng = SplitMix64::new(3);
+        let bounds = Bounds::new(vec![0.0..10.0; 4]);
+        let solution =
+            Solution::new(vec![5.0, 13.0, -24.0, f64::INFINITY]).with_sigmas(vec![0.1; 4]);
+        assert_eq!(bounds.confine(solution.clone(), &mut rng), solution);
+        let clamped = bounds
+            .clone()
+            .with_policy(BoundaryPolicy::Clamp)
+            .confine(solution.clone(), &mut rng);
+        assert_eq!(clamped.get_arguments(), vec![5.0, 10.0, 0.0, 10.0]);
+        assert_eq!(clamped.get_sigmas(), Some(vec![0.1; 4]));
+        // -24 is mirrored at 0 to 24, at 10 to -4 and at 0 again to 4.
+        let reflected = bounds
+            .clone()
+            .with_policy(BoundaryPolicy::Reflect)
+            .confine(solution.clone(), &mut rng);
+        assert_eq!(reflected.get_arguments(), vec![5.0, 7.0, 4.0, 10.0]);
+        let policy = bounds.with_policy(BoundaryPolicy::Resample);
+        for _ in 0..20 {
+            let resampled = policy.confine(solution.clone(), &mut rng);
+            assert!(policy.contains(&resampled));
+            assert_eq!(resampled.get_arguments()[0], 5.0);
+        }
+    }
+}
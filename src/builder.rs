@@ -0,0 +1,506 @@
+use crate::function::Function;
+use crate::pipeline::Pipeline;
+use crate::preset::Preset;
+use crate::runner::{
+    OptimizationResult, Parallelism, Patience, PopulationSize, Runner, RunnerConfig, Schedule,
+    SurvivorSelection,
+};
+use crate::solutions::Solutions;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::ops::Range;
+
+/// The stream of a seeded run the initial population is drawn from, distinct from the streams
+/// of the runner.
+const INITIAL_POPULATION_STREAM: u64 = 0;
+
+/// The entry point for optimizing a function: set the knobs that matter, leave the others at
+/// their defaults and `run`. Everything else `RunnerConfig` offers can be set through
+/// `with_config`, the operators through `with_preset`.
+#[derive(Debug, Clone)]
+pub struct GeneticAlgorithmBuilder {
+    /// The settings of the run.
+    config: RunnerConfig,
+    /// The number of arguments of the function.
+    dimension: usize,
+    /// The range the arguments of the initial population are drawn from.
+    initial_range: Range<f64>,
+    /// The preset whose pipeline produces the offspring, the default pipeline if `None`.
+    preset: Option<Preset>,
+}
+
+impl GeneticAlgorithmBuilder {
+    /// Start configuring the optimization of a function with `dimension` arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `dimension` - The number of arguments of the function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::builder::GeneticAlgorithmBuilder;
+    /// use genetic_algorithm_fn::function::Function;
+    ///
+    /// let result = GeneticAlgorithmBuilder::new(2)
+    ///     .with_population_size(20)
+    ///     .with_generations(30)
+    ///     .with_mutation_rate(0.3)
+    ///     .with_initial_range(-5.0..5.0)
+    ///     .run(&Function::new(|x| Ok(-(x[0] - 1.0).powi(2) - (x[1] + 2.0).powi(2))));
+    /// let (solution, fitness) = result.best.unwrap();
+    /// println!("Best solution: {}, fitness: {}", solution, fitness);
+    /// ```
+    pub fn new(dimension: usize) -> Self {
+        GeneticAlgorithmBuilder {
+            config: RunnerConfig::default(),
+            dimension,
+            initial_range: 1.0..10.0,
+            preset: None,
+        }
+    }
+    /// Start from a complete config instead of the default one. The knobs of the builder
+    /// override its fields afterwards. Use `with_preset` to start from a preset, its config
+    /// alone lacks the operators of the preset.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The settings of the run.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::builder::GeneticAlgorithmBuilder;
+    /// use genetic_algorithm_fn::runner::RunnerConfig;
+    ///
+    /// let builder = GeneticAlgorithmBuilder::new(3)
+    ///     .with_config(RunnerConfig {
+    ///         max_lifespan: Some(10),
+    ///         ..RunnerConfig::default()
+    ///     })
+    ///     .with_generations(20);
+    /// assert_eq!(builder.config().max_lifespan, Some(10));
+    /// ```
+    pub fn with_config(mut self, config: RunnerConfig) -> Self {
+        self.config = config;
+        self
+    }
+    /// Start from the config of a preset and reproduce with its pipeline. The knobs of the
+    /// builder override the fields of the config afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `preset` - The preset the run starts from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::builder::GeneticAlgorithmBuilder;
+    /// use genetic_algorithm_fn::preset::Preset;
+    ///
+    /// let builder = GeneticAlgorithmBuilder::new(3)
+    ///     .with_preset(Preset::FastConverging)
+    ///     .with_generations(20);
+    /// assert_eq!(
+    ///     builder.config().size_generation,
+    ///     Preset::FastConverging.config().size_generation
+    /// );
+    /// ```
+    pub fn with_preset(mut self, preset: Preset) -> Self {
+        self.config = preset.config();
+        self.preset = Some(preset);
+        self
+    }
+    /// Set how many individuals survive every generation, see `RunnerConfig::size_generation`.
+    ///
+    /// # Arguments
+    ///
+    /// * `population_size` - The number of individuals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::builder::GeneticAlgorithmBuilder;
+    ///
+    /// let builder = GeneticAlgorithmBuilder::new(3).with_population_size(50);
+    /// ```
+    pub fn with_population_size(mut self, population_size: usize) -> Self {
+        self.config.size_generation = population_size;
+        self.config.population_size = PopulationSize::Fixed;
+        self
+    }
+    /// Set how many generations are evolved, see `RunnerConfig::n_generations`.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_generations` - The number of generations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::builder::GeneticAlgorithmBuilder;
+    ///
+    /// let builder = GeneticAlgorithmBuilder::new(3).with_generations(200);
+    /// ```
+    pub fn with_generations(mut self, n_generations: usize) -> Self {
+        self.config.n_generations = n_generations;
+        self
+    }
+    /// Set the probability of mutating an offspring, see `RunnerConfig::mutate_prob`.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutation_rate` - The probability in `[0, 1]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::builder::GeneticAlgorithmBuilder;
+    ///
+    /// let builder = GeneticAlgorithmBuilder::new(3).with_mutation_rate(0.1);
+    /// ```
+    pub fn with_mutation_rate(mut self, mutation_rate: f32) -> Self {
+        self.config.mutate_prob = mutation_rate;
+        self
+    }
+    /// Set the probability that a pair of parents is crossed over, see
+    /// `RunnerConfig::crossover_prob`.
+    ///
+    /// # Arguments
+    ///
+    /// * `crossover_rate` - The probability in `[0, 1]`, `1` by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::builder::GeneticAlgorithmBuilder;
+    ///
+    /// let builder = GeneticAlgorithmBuilder::new(3).with_crossover_rate(0.8);
+    /// assert_eq!(builder.config().crossover_prob, 0.8);
+    /// ```
+    pub fn with_crossover_rate(mut self, crossover_rate: f32) -> Self {
+        self.config.crossover_prob = crossover_rate;
+        self
+    }
+    /// Set how many of the fittest individuals survive every generation unchanged, see
+    /// `RunnerConfig::elitism`.
+    ///
+    /// # Arguments
+    ///
+    /// * `elitism` - The number of elites, `0` by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::builder::GeneticAlgorithmBuilder;
+    ///
+    /// let builder = GeneticAlgorithmBuilder::new(3).with_elitism(2);
+    /// assert_eq!(builder.config().elitism, 2);
+    /// ```
+    pub fn with_elitism(mut self, elitism: usize) -> Self {
+        self.config.elitism = elitism;
+        self
+    }
+    /// Set how many islands evolve in parallel, see `RunnerConfig::n_jobs`.
+    ///
+    /// # Arguments
+    ///
+    /// * `parallelism` - The number of islands.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::builder::GeneticAlgorithmBuilder;
+    /// use genetic_algorithm_fn::runner::Parallelism;
+    ///
+    /// let builder = GeneticAlgorithmBuilder::new(3).with_parallelism(Parallelism::Auto);
+    /// ```
+    pub fn with_parallelism(mut self, parallelism: Parallelism) -> Self {
+        self.config.n_jobs = parallelism;
+        self
+    }
+    /// Set the range the arguments of the initial population are drawn from. The evolution
+    /// may leave it.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_range` - The range, `1.0..10.0` by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::builder::GeneticAlgorithmBuilder;
+    ///
+    /// let builder = GeneticAlgorithmBuilder::new(3).with_initial_range(-100.0..100.0);
+    /// ```
+    pub fn with_initial_range(mut self, initial_range: Range<f64>) -> Self {
+        self.initial_range = initial_range;
+        self
+    }
+    /// Make the run reproducible, including its initial population, see
+    /// `RunnerConfig::seed`.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed of all random numbers of the run.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::builder::GeneticAlgorithmBuilder;
+    /// use genetic_algorithm_fn::function::Function;
+    ///
+    /// let function = Function::new(|x| Ok(-x[0].powi(2)));
+    /// let builder = GeneticAlgorithmBuilder::new(1).with_seed(7);
+    /// assert_eq!(
+    ///     builder.run(&function).best,
+    ///     builder.run(&function).best
+    /// );
+    /// ```
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.config.seed = Some(seed);
+        self
+    }
+    /// Set how the survivors of every generation are chosen, see
+    /// `RunnerConfig::survivor_selection`.
+    ///
+    /// # Arguments
+    ///
+    /// * `survivor_selection` - How the survivors are chosen, the fittest ones by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::builder::GeneticAlgorithmBuilder;
+    /// use genetic_algorithm_fn::runner::SurvivorSelection;
+    /// use genetic_algorithm_fn::selection::TournamentSelection;
+    ///
+    /// let tournament = SurvivorSelection::Tournament(TournamentSelection { size: 3 });
+    /// let builder = GeneticAlgorithmBuilder::new(3).with_survivor_selection(tournament.clone());
+    /// assert_eq!(builder.config().survivor_selection, tournament);
+    /// ```
+    pub fn with_survivor_selection(mut self, survivor_selection: SurvivorSelection) -> Self {
+        self.config.survivor_selection = survivor_selection;
+        self
+    }
+    /// Change the probability of mutating an offspring over the generations instead of
+    /// mutating with the mutation rate in every generation, see
+    /// `RunnerConfig::mutate_schedule`.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutate_schedule` - The probability of mutating an offspring in every generation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::builder::GeneticAlgorithmBuilder;
+    /// use genetic_algorithm_fn::runner::Schedule;
+    ///
+    /// let builder = GeneticAlgorithmBuilder::new(3).with_mutation_schedule(Schedule::Linear {
+    ///     start: 0.9,
+    ///     end: 0.05,
+    /// });
+    /// ```
+    pub fn with_mutation_schedule(mut self, mutate_schedule: Schedule) -> Self {
+        self.config.mutate_schedule = Some(mutate_schedule);
+        self
+    }
+    /// Stop as soon as the best individual reaches `target_value`, see
+    /// `RunnerConfig::target_value`. `OptimizationResult::n_evolved_generations` tells how many
+    /// generations it took.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_value` - The function value that is good enough.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::builder::GeneticAlgorithmBuilder;
+    /// use genetic_algorithm_fn::function::Function;
+    ///
+    /// let result = GeneticAlgorithmBuilder::new(3)
+    ///     .with_population_size(10)
+    ///     .with_generations(1_000)
+    ///     .with_target(-1.0)
+    ///     .run(&Function::new(|x| Ok(-x.iter().map(|x| x * x).sum::<f64>())));
+    /// assert!(result.n_evolved_generations < 1_000);
+    /// ```
+    pub fn with_target(mut self, target_value: f64) -> Self {
+        self.config.target_value = Some(target_value);
+        self
+    }
+    /// Stop once the best fitness didn't improve by more than `epsilon` for `patience`
+    /// generations, see `RunnerConfig::patience`. `OptimizationResult::n_evolved_generations`
+    /// tells how many generations were evolved.
+    ///
+    /// # Arguments
+    ///
+    /// * `patience` - For how many generations without improvement the population keeps
+    ///   evolving.
+    /// * `epsilon` - By how much the best fitness has to improve to count as an improvement.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::builder::GeneticAlgorithmBuilder;
+    /// use genetic_algorithm_fn::function::Function;
+    ///
+    /// let result = GeneticAlgorithmBuilder::new(3)
+    ///     .with_population_size(10)
+    ///     .with_generations(1_000)
+    ///     .with_patience(10, 1e-6)
+    ///     .run(&Function::new(|x| Ok(-x.iter().map(|x| x * x).sum::<f64>())));
+    /// assert!(result.n_evolved_generations < 1_000);
+    /// ```
+    pub fn with_patience(mut self, patience: usize, epsilon: f64) -> Self {
+        self.config.patience = Some(Patience {
+            generations: patience,
+            epsilon,
+        });
+        self
+    }
+    /// The settings the run will use.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::builder::GeneticAlgorithmBuilder;
+    ///
+    /// let builder = GeneticAlgorithmBuilder::new(3).with_generations(20);
+    /// assert_eq!(builder.config().n_generations, 20);
+    /// ```
+    pub fn config(&self) -> &RunnerConfig {
+        &self.config
+    }
+    /// The pipeline the run will reproduce with, the one of the preset if there is one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::builder::GeneticAlgorithmBuilder;
+    /// use genetic_algorithm_fn::preset::Preset;
+    ///
+    /// let pipeline = GeneticAlgorithmBuilder::new(3)
+    ///     .with_preset(Preset::Explorative)
+    ///     .pipeline();
+    /// ```
+    pub fn pipeline(&self) -> Pipeline {
+        self.preset
+            .map_or_else(Pipeline::default, |preset| preset.pipeline())
+    }
+    /// Draw the initial population and evolve it.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The function whose value should be maximized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::builder::GeneticAlgorithmBuilder;
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_traits::Population;
+    ///
+    /// let result = GeneticAlgorithmBuilder::new(3)
+    ///     .with_population_size(10)
+    ///     .run(&Function::new(|x| Ok(x.iter().product())));
+    /// assert_eq!(result.population.iter().count(), 10);
+    /// ```
+    pub fn run(&self, function: &Function) -> OptimizationResult {
+        let size_generation = self.config.size_generation_for(self.dimension);
+        let initial_population = match self.config.seed {
+            Some(seed) => Solutions::random_with_rng(
+                size_generation,
+                self.initial_range.clone(),
+                self.dimension,
+                &mut StdRng::seed_from_u64(crate::rng::stream_seed(
+                    seed,
+                    &[INITIAL_POPULATION_STREAM],
+                )),
+            ),
+            None => Solutions::random(size_generation, self.initial_range.clone(), self.dimension),
+        };
+        Runner::new(self.config.clone())
+            .with_pipeline(self.pipeline())
+            .run(initial_population, function)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GeneticAlgorithmBuilder;
+    use crate::function::Function;
+    use crate::pipeline::Pipeline;
+    use crate::preset::Preset;
+    use crate::rng::SplitMix64;
+    use crate::runner::Parallelism;
+    use crate::solution::Solution;
+    use crate::test_objects;
+    use genetic_algorithm_traits::Population;
+
+    #[test]
+    fn builder_sets_the_knobs_of_the_run() {
+        let result = GeneticAlgorithmBuilder::new(3)
+            .with_population_size(6)
+            .with_generations(4)
+            .with_mutation_rate(0.9)
+            .with_crossover_rate(0.5)
+            .with_parallelism(Parallelism::Jobs(2))
+            .with_initial_range(1.0..2.0)
+            .with_seed(3)
+            .run(&Function::new(test_objects::triple_multiplication()));
+        assert_eq!(result.config.size_generation, 6);
+        assert_eq!(result.config.n_generations, 4);
+        assert_eq!(result.config.mutate_prob, 0.9);
+        assert_eq!(result.config.crossover_prob, 0.5);
+        assert_eq!(result.config.n_jobs, Parallelism::Jobs(2));
+        assert!(result.best.is_some());
+    }
+    #[test]
+    fn presets_keep_their_pipeline() {
+        let population = [
+            (Solution::new(vec![1.0, 2.0]), Some(1.0)),
+            (Solution::new(vec![3.0, 5.0]), Some(2.0)),
+        ];
+        let offspring = |pipeline: Pipeline| {
+            pipeline.reproduce(&population, 1.0, &mut SplitMix64::new(4), None)
+        };
+        let builder = GeneticAlgorithmBuilder::new(2)
+            .with_preset(Preset::Explorative)
+            .with_generations(3);
+        assert_eq!(builder.config().n_generations, 3);
+        assert_eq!(
+            builder.config().size_generation,
+            Preset::Explorative.config().size_generation
+        );
+        assert_eq!(
+            offspring(builder.pipeline()),
+            offspring(Preset::Explorative.pipeline())
+        );
+        assert_ne!(
+            offspring(builder.pipeline()),
+            offspring(GeneticAlgorithmBuilder::new(2).pipeline())
+        );
+    }
+    #[test]
+    fn parents_are_copied_unless_crossed_over() {
+        let function = Function::new(test_objects::triple_multiplication());
+        let builder = GeneticAlgorithmBuilder::new(3)
+            .with_population_size(6)
+            .with_generations(5)
+            .with_mutation_rate(0.0)
+            .with_seed(3);
+        let initial = builder
+            .clone()
+            .with_generations(0)
+            .run(&function)
+            .population;
+        let is_initial = |solution: &Solution| initial.iter().any(|initial| initial == solution);
+        let copied = builder.clone().with_crossover_rate(0.0).run(&function);
+        assert!(copied.population.iter().all(is_initial));
+        let crossed = builder.run(&function);
+        assert!(!crossed.population.iter().all(is_initial));
+    }
+}
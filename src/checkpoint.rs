@@ -0,0 +1,1202 @@
+use crate::config;
+use crate::evaluation::EvaluationOutcomes;
+use crate::json::{Json, JsonError};
+use crate::rng::RunRngState;
+use crate::runner::{GenerationReport, IslandState, OptimizationResult, RunnerConfig};
+use crate::schema::{self, SchemaWarning, SCHEMA_VERSION};
+use crate::solution::Solution;
+use crate::solutions::Solutions;
+use crate::stats::RunningStats;
+use genetic_algorithm_traits::Population;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+
+/// Error that can occur when writing or reading a checkpoint.
+#[derive(Debug)]
+pub enum CheckpointError {
+    /// The checkpoint file could not be written or read.
+    Io(io::Error),
+    /// The checkpoint file is not valid JSON.
+    Json(JsonError),
+    /// The checkpoint is valid JSON, but a field is missing or has the wrong type.
+    InvalidField(String),
+    /// The stored config could not be read.
+    Config(config::ConfigError),
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CheckpointError::Io(error) => write!(f, "Could not access the checkpoint: {}", error),
+            CheckpointError::Json(error) => write!(f, "Could not parse the checkpoint: {}", error),
+            CheckpointError::InvalidField(field) => {
+                write!(f, "The checkpoint field `{}` is missing or invalid", field)
+            }
+            CheckpointError::Config(error) => {
+                write!(f, "Could not read the config of the checkpoint: {}", error)
+            }
+        }
+    }
+}
+
+impl From<io::Error> for CheckpointError {
+    fn from(error: io::Error) -> Self {
+        CheckpointError::Io(error)
+    }
+}
+
+impl From<config::ConfigError> for CheckpointError {
+    fn from(error: config::ConfigError) -> Self {
+        CheckpointError::Config(error)
+    }
+}
+
+impl From<JsonError> for CheckpointError {
+    fn from(error: JsonError) -> Self {
+        CheckpointError::Json(error)
+    }
+}
+
+/// The state of a run that allows continuing it later, stored as JSON. The final result of a
+/// run is stored the same way, with `interrupted` being `false`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    /// The settings of the run.
+    pub config: RunnerConfig,
+    /// Whether the run was interrupted before all generations were evolved.
+    pub interrupted: bool,
+    /// How many generations were evolved before the checkpoint was written, summed over all
+    /// islands.
+    pub completed_generations: usize,
+    /// The population to continue from.
+    pub population: Solutions,
+    /// The fittest individual found so far and its fitness.
+    pub best: Option<(Solution, f64)>,
+    /// The reports of the generations evolved so far.
+    pub generations: Vec<GenerationReport>,
+    /// The states of the random number generators, so continuing the run draws the random
+    /// numbers it would have drawn without the interruption.
+    pub rng_state: RunRngState,
+    /// The states of the islands, so continuing the run starts every island where it stopped,
+    /// see `Runner::with_island_states`.
+    pub islands: Vec<IslandState>,
+}
+
+/// Write the arguments of a solution as a JSON array.
+///
+/// # Arguments
+///
+/// * `solution` - The solution to write.
+fn solution_to_json(solution: &Solution) -> Json {
+    Json::Array(
+        solution
+            .get_arguments()
+            .into_iter()
+            .map(Json::Number)
+            .collect(),
+    )
+}
+
+/// Write a generation report as a JSON object.
+///
+/// # Arguments
+///
+/// * `report` - The report to write.
+fn report_to_json(report: &GenerationReport) -> Json {
+    Json::Object(vec![
+        ("island".to_string(), Json::from(report.island)),
+        ("generation".to_string(), Json::from(report.generation)),
+        ("n_evaluated".to_string(), Json::from(report.n_evaluated)),
+        (
+            "n_unevaluated".to_string(),
+            Json::from(report.n_unevaluated),
+        ),
+        (
+            "deadline_exceeded".to_string(),
+            Json::from(report.deadline_exceeded),
+        ),
+        ("fitness".to_string(), report.fitness.to_json()),
+        ("age".to_string(), report.age.to_json()),
+        ("n_retired".to_string(), Json::from(report.n_retired)),
+        (
+            "n_accepted_worse".to_string(),
+            Json::from(report.n_accepted_worse),
+        ),
+        ("evaluations".to_string(), report.evaluations.to_json()),
+        ("best_fitness".to_string(), Json::from(report.best_fitness)),
+        (
+            "rung_sizes".to_string(),
+            Json::Array(report.rung_sizes.iter().copied().map(Json::from).collect()),
+        ),
+        ("pipeline".to_string(), Json::from(report.pipeline)),
+        ("restarted".to_string(), Json::from(report.restarted)),
+        ("n_frozen".to_string(), Json::from(report.n_frozen)),
+        (
+            "bounds".to_string(),
+            report.bounds.as_ref().map_or(Json::Null, |bounds| {
+                Json::Array(
+                    bounds
+                        .iter()
+                        .map(|bound| Json::Array(vec![bound.start.into(), bound.end.into()]))
+                        .collect(),
+                )
+            }),
+        ),
+    ])
+}
+
+/// Read a generation report from a JSON object.
+///
+/// # Arguments
+///
+/// * `value` - The JSON object.
+fn report_from_json(value: &Json) -> Option<GenerationReport> {
+    let usize_field = |name| value.get(name).and_then(Json::as_usize);
+    Some(GenerationReport {
+        island: usize_field("island")?,
+        generation: usize_field("generation")?,
+        n_evaluated: usize_field("n_evaluated")?,
+        n_unevaluated: usize_field("n_unevaluated")?,
+        deadline_exceeded: value.get("deadline_exceeded")?.as_bool()?,
+        // Checkpoints written before the statistics were added don't have them.
+        fitness: match value.get("fitness") {
+            Some(fitness) => RunningStats::from_json(fitness)?,
+            None => RunningStats::new(),
+        },
+        age: match value.get("age") {
+            Some(age) => RunningStats::from_json(age)?,
+            None => RunningStats::new(),
+        },
+        n_retired: usize_field("n_retired").unwrap_or(0),
+        n_accepted_worse: usize_field("n_accepted_worse").unwrap_or(0),
+        evaluations: match value.get("evaluations") {
+            Some(evaluations) => EvaluationOutcomes::from_json(evaluations)?,
+            None => EvaluationOutcomes::default(),
+        },
+        best_fitness: match value.get("best_fitness")? {
+            Json::Null => None,
+            best_fitness => Some(best_fitness.as_f64()?),
+        },
+        rung_sizes: value
+            .get("rung_sizes")?
+            .as_array()?
+            .iter()
+            .map(Json::as_usize)
+            .collect::<Option<Vec<usize>>>()?,
+        pipeline: usize_field("pipeline").unwrap_or(0),
+        restarted: value
+            .get("restarted")
+            .and_then(Json::as_bool)
+            .unwrap_or(false),
+        n_frozen: usize_field("n_frozen").unwrap_or(0),
+        bounds: match value.get("bounds") {
+            None | Some(Json::Null) => None,
+            Some(bounds) => Some(
+                bounds
+                    .as_array()?
+                    .iter()
+                    .map(|bound| match bound.as_array()? {
+                        [start, end] => Some(start.as_f64()?..end.as_f64()?),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<Range<f64>>>>()?,
+            ),
+        },
+    })
+}
+
+/// Write the state of an island as a JSON object.
+///
+/// # Arguments
+///
+/// * `state` - The state to write.
+fn island_to_json(state: &IslandState) -> Json {
+    Json::Object(vec![
+        (
+            "population".to_string(),
+            Json::Array(
+                state
+                    .population
+                    .iter()
+                    .map(|(solution, _)| solution_to_json(solution))
+                    .collect(),
+            ),
+        ),
+        (
+            "fitness".to_string(),
+            Json::Array(
+                state
+                    .population
+                    .iter()
+                    .map(|(_, fitness)| Json::from(*fitness))
+                    .collect(),
+            ),
+        ),
+        (
+            "ages".to_string(),
+            Json::Array(state.ages.iter().copied().map(Json::from).collect()),
+        ),
+        ("generation".to_string(), Json::from(state.generation)),
+    ])
+}
+
+/// Read the state of an island from a JSON object.
+///
+/// # Arguments
+///
+/// * `value` - The JSON object.
+fn island_from_json(value: &Json) -> Option<IslandState> {
+    let solutions = value
+        .get("population")?
+        .as_array()?
+        .iter()
+        .map(|solution| solution_from_json(solution, "islands").ok())
+        .collect::<Option<Vec<Solution>>>()?;
+    let fitness = value
+        .get("fitness")?
+        .as_array()?
+        .iter()
+        .map(|fitness| match fitness {
+            Json::Null => Some(None),
+            fitness => fitness.as_f64().map(Some),
+        })
+        .collect::<Option<Vec<Option<f64>>>>()?;
+    let ages = value
+        .get("ages")?
+        .as_array()?
+        .iter()
+        .map(Json::as_usize)
+        .collect::<Option<Vec<usize>>>()?;
+    if fitness.len() != solutions.len() || ages.len() != solutions.len() {
+        return None;
+    }
+    Some(IslandState {
+        population: solutions.into_iter().zip(fitness).collect(),
+        ages,
+        generation: value.get("generation")?.as_usize()?,
+    })
+}
+
+/// Read a solution from a JSON array of its arguments.
+///
+/// # Arguments
+///
+/// * `value` - The JSON array.
+/// * `field` - The name of the field, used in the error.
+fn solution_from_json(value: &Json, field: &str) -> Result<Solution, CheckpointError> {
+    value
+        .as_array()
+        .and_then(|arguments| arguments.iter().map(Json::as_f64).collect())
+        .map(Solution::new)
+        .ok_or_else(|| CheckpointError::InvalidField(field.to_string()))
+}
+
+impl Checkpoint {
+    /// Create a checkpoint from the (partial) result of a run.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The result of `Runner::run`.
+    /// * `config` - The settings the run was started with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::checkpoint::Checkpoint;
+    /// use genetic_algorithm_fn::function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let config = RunnerConfig {
+    ///     n_generations: 2,
+    ///     ..RunnerConfig::default()
+    /// };
+    /// let result = Runner::new(config.clone()).run(
+    ///     Solutions::random(5, 1.0..10.0, 2),
+    ///     &function::Function::new(|x| Ok(x.iter().sum())),
+    /// );
+    /// assert_eq!(Checkpoint::from_result(&result, &config).completed_generations, 2);
+    /// ```
+    pub fn from_result(result: &OptimizationResult, config: &RunnerConfig) -> Self {
+        Checkpoint {
+            config: config.clone(),
+            interrupted: result.interrupted,
+            completed_generations: result.n_evolved_generations,
+            population: result.population.clone(),
+            best: result.best.clone(),
+            generations: result.generations.clone(),
+            rng_state: result.rng_state.clone(),
+            islands: result.islands.clone(),
+        }
+    }
+    /// Serialize the checkpoint as JSON.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::checkpoint::Checkpoint;
+    /// use genetic_algorithm_fn::rng::RunRngState;
+    /// use genetic_algorithm_fn::runner::RunnerConfig;
+    /// use genetic_algorithm_fn::solution::Solution;
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let checkpoint = Checkpoint {
+    ///     config: RunnerConfig::default(),
+    ///     interrupted: true,
+    ///     completed_generations: 3,
+    ///     population: Solutions::from(vec![Solution::new(vec![1.0, 2.0])]),
+    ///     best: Some((Solution::new(vec![1.0, 2.0]), 3.0)),
+    ///     generations: Vec::new(),
+    ///     rng_state: RunRngState::default(),
+    ///     islands: Vec::new(),
+    /// };
+    /// assert_eq!(Checkpoint::from_json(&checkpoint.to_json()).unwrap(), checkpoint);
+    /// ```
+    pub fn to_json(&self) -> String {
+        self.to_json_value().to_string()
+    }
+    /// Serialize the checkpoint as a JSON value, e.g. to embed it in a larger document.
+    pub(crate) fn to_json_value(&self) -> Json {
+        Json::Object(vec![
+            ("schema_version".to_string(), Json::from(SCHEMA_VERSION)),
+            (
+                "config".to_string(),
+                Json::Object(
+                    config::FIELDS
+                        .iter()
+                        .map(|field| {
+                            (
+                                field.to_string(),
+                                Json::from(
+                                    config::field_value(&self.config, field)
+                                        .unwrap_or_default()
+                                        .as_str(),
+                                ),
+                            )
+                        })
+                        .collect(),
+                ),
+            ),
+            ("interrupted".to_string(), Json::from(self.interrupted)),
+            (
+                "completed_generations".to_string(),
+                Json::from(self.completed_generations),
+            ),
+            (
+                "population".to_string(),
+                Json::Array(self.population.iter().map(solution_to_json).collect()),
+            ),
+            (
+                "best".to_string(),
+                match &self.best {
+                    Some((solution, fitness)) => Json::Object(vec![
+                        ("arguments".to_string(), solution_to_json(solution)),
+                        ("fitness".to_string(), Json::from(*fitness)),
+                    ]),
+                    None => Json::Null,
+                },
+            ),
+            (
+                "generations".to_string(),
+                Json::Array(self.generations.iter().map(report_to_json).collect()),
+            ),
+            ("rng_state".to_string(), self.rng_state.to_json_value()),
+            (
+                "islands".to_string(),
+                Json::Array(self.islands.iter().map(island_to_json).collect()),
+            ),
+        ])
+    }
+    /// Deserialize a checkpoint written by `Checkpoint::to_json`. Fields that are missing in
+    /// checkpoints written by older versions (`config`, `interrupted`, `generations`,
+    /// `rng_state` and `islands`) are set to their defaults.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON document.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::checkpoint::Checkpoint;
+    ///
+    /// let checkpoint = Checkpoint::from_json(
+    ///     r#"{"completed_generations": 4, "population": [[1.0, 2.0]], "best": null}"#,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(checkpoint.completed_generations, 4);
+    /// ```
+    pub fn from_json(input: &str) -> Result<Self, CheckpointError> {
+        Checkpoint::from_json_with_warnings(input).map(|(checkpoint, _)| checkpoint)
+    }
+    /// Deserialize a checkpoint like `Checkpoint::from_json` and also return what was
+    /// tolerated: a missing or newer schema version and the fields that were missing and set
+    /// to their defaults. Config fields a newer version wrote are skipped with a warning,
+    /// for documents of this or older versions they are an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON document.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::checkpoint::Checkpoint;
+    /// use genetic_algorithm_fn::schema::SchemaWarning;
+    ///
+    /// let (checkpoint, warnings) = Checkpoint::from_json_with_warnings(
+    ///     r#"{"completed_generations": 4, "population": [[1.0, 2.0]], "best": null}"#,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(checkpoint.completed_generations, 4);
+    /// assert_eq!(warnings[0], SchemaWarning::Unversioned);
+    /// ```
+    pub fn from_json_with_warnings(
+        input: &str,
+    ) -> Result<(Self, Vec<SchemaWarning>), CheckpointError> {
+        Checkpoint::from_json_value(&Json::parse(input)?)
+    }
+    /// Deserialize a checkpoint from a JSON value like `Checkpoint::from_json_with_warnings`,
+    /// e.g. one embedded in a larger document.
+    ///
+    /// # Arguments
+    ///
+    /// * `document` - The JSON value.
+    pub(crate) fn from_json_value(
+        document: &Json,
+    ) -> Result<(Self, Vec<SchemaWarning>), CheckpointError> {
+        let invalid = |field: &str| CheckpointError::InvalidField(field.to_string());
+        let version = match document.get("schema_version") {
+            None => None,
+            Some(version) => Some(
+                version
+                    .as_usize()
+                    .ok_or_else(|| invalid("schema_version"))?,
+            ),
+        };
+        let is_newer = version.is_some_and(|version| version > SCHEMA_VERSION);
+        let mut warnings = schema::check_version(version)
+            .into_iter()
+            .collect::<Vec<_>>();
+        let mut run_config = RunnerConfig::default();
+        match document.get("config") {
+            None => warnings.push(SchemaWarning::MissingField("config".to_string())),
+            Some(Json::Object(fields)) => {
+                for (field, value) in fields {
+                    let value = value.as_str().ok_or_else(|| invalid("config"))?;
+                    match config::set_field(&mut run_config, field, value) {
+                        Err(config::ConfigError::UnknownField(field)) if is_newer => {
+                            warnings.push(SchemaWarning::UnknownField(format!("config.{}", field)))
+                        }
+                        outcome => outcome?,
+                    }
+                }
+                warnings.extend(
+                    config::FIELDS
+                        .iter()
+                        .filter(|field| !fields.iter().any(|(name, _)| name == *field))
+                        .map(|field| SchemaWarning::MissingField(format!("config.{}", field))),
+                );
+            }
+            Some(_) => return Err(invalid("config")),
+        }
+        let interrupted = match document.get("interrupted") {
+            None => {
+                warnings.push(SchemaWarning::MissingField("interrupted".to_string()));
+                false
+            }
+            Some(interrupted) => interrupted
+                .as_bool()
+                .ok_or_else(|| invalid("interrupted"))?,
+        };
+        let generations = match document.get("generations") {
+            None => {
+                warnings.push(SchemaWarning::MissingField("generations".to_string()));
+                Vec::new()
+            }
+            Some(generations) => generations
+                .as_array()
+                .and_then(|generations| generations.iter().map(report_from_json).collect())
+                .ok_or_else(|| invalid("generations"))?,
+        };
+        let rng_state = match document.get("rng_state") {
+            None => {
+                warnings.push(SchemaWarning::MissingField("rng_state".to_string()));
+                RunRngState::default()
+            }
+            Some(rng_state) => {
+                RunRngState::from_json_value(rng_state).ok_or_else(|| invalid("rng_state"))?
+            }
+        };
+        let islands = match document.get("islands") {
+            None => {
+                warnings.push(SchemaWarning::MissingField("islands".to_string()));
+                Vec::new()
+            }
+            Some(islands) => islands
+                .as_array()
+                .and_then(|islands| islands.iter().map(island_from_json).collect())
+                .ok_or_else(|| invalid("islands"))?,
+        };
+        let completed_generations = document
+            .get("completed_generations")
+            .and_then(Json::as_usize)
+            .ok_or_else(|| CheckpointError::InvalidField("completed_generations".to_string()))?;
+        let population = document
+            .get("population")
+            .and_then(Json::as_array)
+            .ok_or_else(|| CheckpointError::InvalidField("population".to_string()))?
+            .iter()
+            .map(|solution| solution_from_json(solution, "population"))
+            .collect::<Result<Vec<Solution>, CheckpointError>>()?;
+        let best = match document.get("best") {
+            None | Some(Json::Null) => None,
+            Some(best) => Some((
+                solution_from_json(
+                    best.get("arguments").unwrap_or(&Json::Null),
+                    "best.arguments",
+                )?,
+                best.get("fitness")
+                    .and_then(Json::as_f64)
+                    .ok_or_else(|| CheckpointError::InvalidField("best.fitness".to_string()))?,
+            )),
+        };
+        Ok((
+            Checkpoint {
+                config: run_config,
+                interrupted,
+                completed_generations,
+                population: Solutions::from(population),
+                best,
+                generations,
+                rng_state,
+                islands,
+            },
+            warnings,
+        ))
+    }
+    /// Write the checkpoint to a file. The file is written to a temporary file first and then
+    /// renamed, so an existing checkpoint is never left half-written.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Where the checkpoint should be written to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::checkpoint::Checkpoint;
+    /// use genetic_algorithm_fn::rng::RunRngState;
+    /// use genetic_algorithm_fn::runner::RunnerConfig;
+    /// use genetic_algorithm_fn::solution::Solution;
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let path = std::env::temp_dir().join("genetic_algorithm_fn_save_example.json");
+    /// let checkpoint = Checkpoint {
+    ///     config: RunnerConfig::default(),
+    ///     interrupted: false,
+    ///     completed_generations: 1,
+    ///     population: Solutions::from(vec![Solution::new(vec![1.0])]),
+    ///     best: None,
+    ///     generations: Vec::new(),
+    ///     rng_state: RunRngState::default(),
+    ///     islands: Vec::new(),
+    /// };
+    /// checkpoint.save(&path).unwrap();
+    /// assert_eq!(Checkpoint::load(&path).unwrap(), checkpoint);
+    /// ```
+    pub fn save(&self, path: &Path) -> Result<(), CheckpointError> {
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        fs::write(&temporary, self.to_json())?;
+        fs::rename(&temporary, path)?;
+        Ok(())
+    }
+    /// Read a checkpoint from a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file written by `Checkpoint::save`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::checkpoint::Checkpoint;
+    ///
+    /// assert!(Checkpoint::load(std::path::Path::new("does/not/exist.json")).is_err());
+    /// ```
+    pub fn load(path: &Path) -> Result<Self, CheckpointError> {
+        Checkpoint::from_json(&fs::read_to_string(path)?)
+    }
+    /// Read a checkpoint from a file, see `Checkpoint::from_json_with_warnings`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file written by `Checkpoint::save`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::checkpoint::Checkpoint;
+    ///
+    /// let path = std::path::Path::new("does/not/exist.json");
+    /// assert!(Checkpoint::load_with_warnings(path).is_err());
+    /// ```
+    pub fn load_with_warnings(path: &Path) -> Result<(Self, Vec<SchemaWarning>), CheckpointError> {
+        Checkpoint::from_json_with_warnings(&fs::read_to_string(path)?)
+    }
+    /// Return the settings for continuing the run, i.e. with the generations that are left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::checkpoint::Checkpoint;
+    /// use genetic_algorithm_fn::rng::RunRngState;
+    /// use genetic_algorithm_fn::runner::RunnerConfig;
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let checkpoint = Checkpoint {
+    ///     config: RunnerConfig::default(),
+    ///     interrupted: true,
+    ///     completed_generations: 30,
+    ///     population: Solutions::from(Vec::new()),
+    ///     best: None,
+    ///     generations: Vec::new(),
+    ///     rng_state: RunRngState::default(),
+    ///     islands: Vec::new(),
+    /// };
+    /// assert_eq!(checkpoint.remaining_config().n_generations, 70);
+    /// ```
+    pub fn remaining_config(&self) -> RunnerConfig {
+        RunnerConfig {
+            n_generations: self
+                .config
+                .n_generations
+                .saturating_sub(self.completed_generations),
+            ..self.config.clone()
+        }
+    }
+    /// Add the result of continuing the run from this checkpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The result of running `remaining_config` from `population`, continuing
+    ///   `rng_state` and `islands`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::checkpoint::Checkpoint;
+    /// use genetic_algorithm_fn::function;
+    /// use genetic_algorithm_fn::rng::RunRngState;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let checkpoint = Checkpoint {
+    ///     config: RunnerConfig {
+    ///         n_generations: 5,
+    ///         ..RunnerConfig::default()
+    ///     },
+    ///     interrupted: true,
+    ///     completed_generations: 2,
+    ///     population: Solutions::random(5, 1.0..10.0, 2),
+    ///     best: None,
+    ///     generations: Vec::new(),
+    ///     rng_state: RunRngState::default(),
+    ///     islands: Vec::new(),
+    /// };
+    /// let result = Runner::new(checkpoint.remaining_config()).run(
+    ///     checkpoint.population.clone(),
+    ///     &function::Function::new(|x| Ok(x.iter().sum())),
+    /// );
+    /// let finished = checkpoint.continue_with(&result);
+    /// assert_eq!(finished.completed_generations, 5);
+    /// assert!(!finished.interrupted);
+    /// ```
+    pub fn continue_with(self, result: &OptimizationResult) -> Checkpoint {
+        let mut generations = self.generations;
+        // Restored islands continue the numbering themselves. Otherwise the old reports may be
+        // truncated, so the numbering continues after the latest one.
+        let restored_islands = !self.islands.is_empty();
+        let n_old_generations = |island| match restored_islands {
+            true => 0,
+            false => generations
+                .iter()
+                .filter(|report: &&GenerationReport| report.island == island)
+                .map(|report| report.generation + 1)
+                .max()
+                .unwrap_or(0),
+        };
+        let new_generations = result
+            .generations
+            .iter()
+            .map(|report| GenerationReport {
+                generation: report.generation + n_old_generations(report.island),
+                ..report.clone()
+            })
+            .collect::<Vec<GenerationReport>>();
+        generations.extend(new_generations);
+        if let Some(max_history) = self.config.max_history {
+            // Keep the latest `max_history` reports of every island.
+            let mut n_newer = HashMap::new();
+            let mut kept = generations
+                .into_iter()
+                .rev()
+                .filter(|report| {
+                    let n_newer = n_newer.entry(report.island).or_insert(0);
+                    *n_newer += 1;
+                    *n_newer <= max_history
+                })
+                .collect::<Vec<GenerationReport>>();
+            kept.reverse();
+            generations = kept;
+        }
+        let best = match (self.best, result.best.clone()) {
+            (Some(old), Some(new)) => Some(if new.1 >= old.1 { new } else { old }),
+            (old, new) => new.or(old),
+        };
+        Checkpoint {
+            config: self.config,
+            interrupted: result.interrupted,
+            completed_generations: self.completed_generations + result.n_evolved_generations,
+            population: result.population.clone(),
+            best,
+            generations,
+            rng_state: result.rng_state.clone(),
+            islands: result.islands.clone(),
+        }
+    }
+}
+
+/// How many of the latest generation reports are shown by the `Display` implementation.
+const SHOWN_GENERATIONS: usize = 10;
+
+impl fmt::Display for Checkpoint {
+    /// A human readable summary: the status, the best individual, the config and the latest
+    /// generations.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Status: {}",
+            if self.interrupted {
+                "interrupted"
+            } else {
+                "finished"
+            }
+        )?;
+        writeln!(
+            f,
+            "Generations: {} of {}",
+            self.completed_generations, self.config.n_generations
+        )?;
+        writeln!(
+            f,
+            "Population: {} individuals",
+            self.population.iter().count()
+        )?;
+        match &self.best {
+            Some((solution, fitness)) => {
+                writeln!(f, "Best: {} with fitness {}", solution, fitness)?
+            }
+            None => writeln!(f, "Best: none")?,
+        }
+        let mut evaluations = EvaluationOutcomes::default();
+        for report in &self.generations {
+            evaluations.merge(&report.evaluations);
+        }
+        writeln!(f, "Evaluations: {}", evaluations)?;
+        writeln!(f, "Config:")?;
+        for field in config::FIELDS {
+            writeln!(
+                f,
+                "  {} = {}",
+                field,
+                config::field_value(&self.config, field).unwrap_or_default()
+            )?;
+        }
+        writeln!(f, "Progress:")?;
+        let n_shown = self.generations.len().min(SHOWN_GENERATIONS);
+        // Reports dropped because of `max_history` are counted as well.
+        let n_hidden = self.completed_generations.max(self.generations.len()) - n_shown;
+        if n_hidden > 0 {
+            writeln!(f, "  ... {} earlier generations", n_hidden)?;
+        }
+        for report in &self.generations[self.generations.len() - n_shown..] {
+            writeln!(
+                f,
+                "  island {}, generation {}: best fitness {}, {} evaluated, {} unevaluated",
+                report.island,
+                report.generation,
+                report
+                    .best_fitness
+                    .map_or_else(|| "none".to_string(), |fitness| fitness.to_string()),
+                report.n_evaluated,
+                report.n_unevaluated
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::function::Function;
+    use crate::rng::SplitMix64;
+    use crate::runner::{Generation, Parallelism, Runner};
+    use crate::solution::Precision;
+    use crate::stats::RunStatistics;
+    use std::collections::HashSet;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn round_trip_keeps_non_finite_fitness() {
+        let checkpoint = Checkpoint {
+            config: RunnerConfig {
+                seed: Some(3),
+                merge_policy: crate::solutions::MergePolicy::GlobalTopK(4),
+                ..RunnerConfig::default()
+            },
+            interrupted: true,
+            completed_generations: 1,
+            population: Solutions::from(vec![
+                Solution::new(vec![1.0, -2.5]),
+                Solution::new(vec![0.1, 3.0]),
+            ]),
+            best: Some((Solution::new(vec![0.1, 3.0]), f64::INFINITY)),
+            generations: vec![GenerationReport {
+                island: 0,
+                generation: 0,
+                n_evaluated: 1,
+                n_unevaluated: 1,
+                deadline_exceeded: true,
+                fitness: {
+                    let mut fitness = RunningStats::new();
+                    fitness.push(0.5);
+                    fitness.push(2.25);
+                    fitness
+                },
+                age: RunningStats::new(),
+                n_retired: 2,
+                n_accepted_worse: 1,
+                evaluations: EvaluationOutcomes {
+                    n_ok: 3,
+                    n_timeout: 1,
+                    ..EvaluationOutcomes::default()
+                },
+                best_fitness: None,
+                rung_sizes: vec![4, 2],
+                pipeline: 1,
+                restarted: false,
+                n_frozen: 0,
+                bounds: None,
+            }],
+            rng_state: RunRngState::default(),
+            islands: Vec::new(),
+        };
+        assert_eq!(
+            Checkpoint::from_json(&checkpoint.to_json()).unwrap(),
+            checkpoint
+        );
+    }
+    #[test]
+    fn summary_shows_latest_generations() {
+        let report = |generation| GenerationReport {
+            island: 0,
+            generation,
+            n_evaluated: 2,
+            n_unevaluated: 0,
+            deadline_exceeded: false,
+            fitness: RunningStats::new(),
+            age: RunningStats::new(),
+            n_retired: 0,
+            n_accepted_worse: 0,
+            evaluations: EvaluationOutcomes {
+                n_ok: 2,
+                ..EvaluationOutcomes::default()
+            },
+            best_fitness: Some(1.5),
+            rung_sizes: Vec::new(),
+            pipeline: 0,
+            restarted: false,
+            n_frozen: 0,
+            bounds: None,
+        };
+        let summary = Checkpoint {
+            config: RunnerConfig::default(),
+            interrupted: true,
+            completed_generations: 12,
+            population: Solutions::from(vec![Solution::new(vec![1.0])]),
+            best: None,
+            generations: (0..12).map(report).collect(),
+            rng_state: RunRngState::default(),
+            islands: Vec::new(),
+        }
+        .to_string();
+        assert!(summary.contains("Status: interrupted"));
+        assert!(summary.contains("Generations: 12 of 100"));
+        assert!(summary.contains("... 2 earlier generations"));
+        assert!(summary.contains("Evaluations: 24 ok, 0 wrong arity"));
+        assert!(summary.contains("generation 11: best fitness 1.5"));
+        assert!(!summary.contains("generation 1: "));
+    }
+    #[test]
+    fn continue_with_renumbers_generations() {
+        let report = |generation, best_fitness| GenerationReport {
+            island: 0,
+            generation,
+            n_evaluated: 1,
+            n_unevaluated: 0,
+            deadline_exceeded: false,
+            fitness: RunningStats::new(),
+            age: RunningStats::new(),
+            n_retired: 0,
+            n_accepted_worse: 0,
+            evaluations: EvaluationOutcomes::default(),
+            best_fitness: Some(best_fitness),
+            rung_sizes: Vec::new(),
+            pipeline: 0,
+            restarted: false,
+            n_frozen: 0,
+            bounds: None,
+        };
+        let checkpoint = Checkpoint {
+            config: RunnerConfig::default(),
+            interrupted: true,
+            completed_generations: 1,
+            population: Solutions::from(vec![Solution::new(vec![2.0])]),
+            best: Some((Solution::new(vec![2.0]), 2.0)),
+            generations: vec![report(0, 2.0)],
+            rng_state: RunRngState::default(),
+            islands: Vec::new(),
+        };
+        let continued = checkpoint.continue_with(&OptimizationResult {
+            population: Solutions::from(vec![Solution::new(vec![1.0])]),
+            best: Some((Solution::new(vec![1.0]), 1.0)),
+            generations: vec![report(0, 1.0)],
+            n_evolved_generations: 1,
+            statistics: RunStatistics::new(0.1),
+            archive: Default::default(),
+            stopped_early: false,
+            interrupted: false,
+            hall_of_fame: Default::default(),
+            evaluations: Default::default(),
+            reconfigurations: Vec::new(),
+            polish: None,
+            rng_state: RunRngState::default(),
+            islands: Vec::new(),
+            precision: Precision::default(),
+            config: RunnerConfig::default(),
+        });
+        assert_eq!(continued.completed_generations, 2);
+        assert_eq!(continued.generations[1].generation, 1);
+        // The best individual of the earlier part of the run is kept.
+        assert_eq!(continued.best, Some((Solution::new(vec![2.0]), 2.0)));
+    }
+    #[test]
+    fn continue_with_keeps_the_latest_reports() {
+        let report = |generation| GenerationReport {
+            island: 0,
+            generation,
+            n_evaluated: 1,
+            n_unevaluated: 0,
+            deadline_exceeded: false,
+            fitness: RunningStats::new(),
+            age: RunningStats::new(),
+            n_retired: 0,
+            n_accepted_worse: 0,
+            evaluations: EvaluationOutcomes::default(),
+            best_fitness: Some(1.0),
+            rung_sizes: Vec::new(),
+            pipeline: 0,
+            restarted: false,
+            n_frozen: 0,
+            bounds: None,
+        };
+        let checkpoint = Checkpoint {
+            config: RunnerConfig {
+                max_history: Some(2),
+                ..RunnerConfig::default()
+            },
+            interrupted: true,
+            // Generations 0 to 2 were dropped from the history already.
+            completed_generations: 5,
+            population: Solutions::from(vec![Solution::new(vec![1.0])]),
+            best: None,
+            generations: vec![report(3), report(4)],
+            rng_state: RunRngState::default(),
+            islands: Vec::new(),
+        };
+        let continued = checkpoint.continue_with(&OptimizationResult {
+            population: Solutions::from(vec![Solution::new(vec![1.0])]),
+            best: None,
+            generations: vec![report(0)],
+            n_evolved_generations: 1,
+            statistics: RunStatistics::new(0.1),
+            archive: Default::default(),
+            stopped_early: false,
+            interrupted: false,
+            hall_of_fame: Default::default(),
+            evaluations: Default::default(),
+            reconfigurations: Vec::new(),
+            polish: None,
+            rng_state: RunRngState::default(),
+            islands: Vec::new(),
+            precision: Precision::default(),
+            config: RunnerConfig::default(),
+        });
+        assert_eq!(continued.completed_generations, 6);
+        assert_eq!(
+            continued
+                .generations
+                .iter()
+                .map(|report| report.generation)
+                .collect::<Vec<usize>>(),
+            vec![4, 5]
+        );
+        assert!(continued.to_string().contains("... 4 earlier generations"));
+    }
+    #[test]
+    fn invalid_fields_are_reported() {
+        match Checkpoint::from_json(r#"{"completed_generations": 1, "population": [["a"]]}"#) {
+            Err(CheckpointError::InvalidField(field)) => assert_eq!(field, "population"),
+            other => panic!("Unexpected result {:?}", other),
+        }
+        assert!(matches!(
+            Checkpoint::from_json("[1"),
+            Err(CheckpointError::Json(_))
+        ));
+        assert!(matches!(
+            Checkpoint::from_json(
+                r#"{"config": {"n_jobs": "two"}, "completed_generations": 1, "population": []}"#
+            ),
+            Err(CheckpointError::Config(_))
+        ));
+    }
+    #[test]
+    fn other_schema_versions_are_read_with_warnings() {
+        let checkpoint = Checkpoint {
+            config: RunnerConfig::default(),
+            interrupted: false,
+            completed_generations: 1,
+            population: Solutions::from(vec![Solution::new(vec![1.0])]),
+            best: None,
+            generations: Vec::new(),
+            rng_state: RunRngState::default(),
+            islands: Vec::new(),
+        };
+        let (read, warnings) = Checkpoint::from_json_with_warnings(&checkpoint.to_json()).unwrap();
+        assert_eq!((read, warnings), (checkpoint, Vec::new()));
+        let newer = |version| {
+            format!(
+                r#"{{"schema_version": {}, "config": {{"seed": "3", "warp_drive": "on"}},
+                    "interrupted": false, "completed_generations": 1, "population": [],
+                    "generations": []}}"#,
+                version
+            )
+        };
+        let (read, warnings) =
+            Checkpoint::from_json_with_warnings(&newer(SCHEMA_VERSION + 1)).unwrap();
+        assert_eq!(read.config.seed, Some(3));
+        assert_eq!(
+            warnings[..2],
+            [
+                SchemaWarning::NewerVersion(SCHEMA_VERSION + 1),
+                SchemaWarning::UnknownField("config.warp_drive".to_string())
+            ]
+        );
+        assert!(warnings.contains(&SchemaWarning::MissingField("config.n_jobs".to_string())));
+        assert!(matches!(
+            Checkpoint::from_json(&newer(SCHEMA_VERSION)),
+            Err(CheckpointError::Config(config::ConfigError::UnknownField(
+                _
+            )))
+        ));
+    }
+    #[test]
+    fn resumed_runs_continue_the_random_streams() {
+        let function = Function::new(|x| Ok(-x.iter().map(|x| x * x).sum::<f64>()));
+        let config = |n_generations| RunnerConfig {
+            n_generations,
+            seed: Some(2021),
+            ..RunnerConfig::default()
+        };
+        let initial_population =
+            Solutions::random_with_rng(10, -5.0..5.0, 3, &mut SplitMix64::new(1));
+        let uninterrupted = Runner::new(config(6)).run(initial_population.clone(), &function);
+        let first = Runner::new(config(3)).run(initial_population, &function);
+        let checkpoint =
+            Checkpoint::from_json(&Checkpoint::from_result(&first, &config(6)).to_json()).unwrap();
+        assert_eq!(checkpoint.rng_state, first.rng_state);
+        let resumed = Runner::new(checkpoint.remaining_config())
+            .with_rng_state(checkpoint.rng_state.clone())
+            .with_island_states(checkpoint.islands.clone())
+            .run(checkpoint.population.clone(), &function);
+        let continued = checkpoint.continue_with(&resumed);
+        assert_eq!(continued.population, uninterrupted.population);
+        assert_eq!(continued.best, uninterrupted.best);
+        assert_eq!(continued.rng_state, uninterrupted.rng_state);
+        assert_eq!(continued.islands, uninterrupted.islands);
+        // Without the state the resumed run draws other random numbers.
+        let reseeded = Runner::new(config(3)).run(first.population.clone(), &function);
+        assert_ne!(reseeded.population, uninterrupted.population);
+    }
+    #[test]
+    fn interrupted_islands_resume_where_they_stopped() {
+        let function = Function::new(|x| Ok(-x.iter().map(|x| x * x).sum::<f64>()));
+        let config = RunnerConfig {
+            n_generations: 12,
+            n_jobs: Parallelism::Jobs(3),
+            seed: Some(2021),
+            ..RunnerConfig::default()
+        };
+        let initial_population =
+            Solutions::random_with_rng(10, -5.0..5.0, 3, &mut SplitMix64::new(1));
+        let uninterrupted = Runner::new(config.clone()).run(initial_population.clone(), &function);
+        let interrupt = Arc::new(AtomicBool::new(false));
+        let interrupt_in_generation = Arc::clone(&interrupt);
+        let first = Runner::new(config.clone())
+            .with_interrupt(interrupt)
+            .with_stopping_predicate(move |generation: &Generation| {
+                if generation.index == 1 {
+                    interrupt_in_generation.store(true, Ordering::SeqCst);
+                }
+                false
+            })
+            .run(initial_population, &function);
+        assert!(first.interrupted);
+        let checkpoint =
+            Checkpoint::from_json(&Checkpoint::from_result(&first, &config).to_json()).unwrap();
+        assert_eq!(checkpoint.islands, first.islands);
+        let resumed = Runner::new(checkpoint.remaining_config())
+            .with_rng_state(checkpoint.rng_state.clone())
+            .with_island_states(checkpoint.islands.clone())
+            .run(checkpoint.population.clone(), &function);
+        let continued = checkpoint.continue_with(&resumed);
+        assert_eq!(
+            continued.completed_generations,
+            uninterrupted.n_evolved_generations
+        );
+        assert_eq!(continued.islands, uninterrupted.islands);
+        assert_eq!(continued.population, uninterrupted.population);
+        assert_eq!(continued.best, uninterrupted.best);
+        assert_eq!(continued.rng_state, uninterrupted.rng_state);
+        assert_eq!(
+            continued
+                .generations
+                .iter()
+                .map(|report| (report.island, report.generation))
+                .collect::<HashSet<_>>(),
+            uninterrupted
+                .generations
+                .iter()
+                .map(|report| (report.island, report.generation))
+                .collect::<HashSet<_>>()
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The source of the current time of time budgets and benchmarks. Replace the `SystemClock`
+/// by a `ManualClock` to test time-based behaviour without sleeping.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// The current point in time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::clock::{Clock, SystemClock};
+    ///
+    /// let clock = SystemClock;
+    /// assert!(clock.now() <= clock.now());
+    /// ```
+    fn now(&self) -> Instant;
+    /// The time passed since `earlier`, zero if `earlier` is in the future.
+    ///
+    /// # Arguments
+    ///
+    /// * `earlier` - A point in time returned by `now`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::clock::{Clock, ManualClock};
+    /// use std::time::Duration;
+    ///
+    /// let clock = ManualClock::new();
+    /// let start = clock.now();
+    /// clock.advance(Duration::from_secs(2));
+    /// assert_eq!(clock.elapsed_since(start), Duration::from_secs(2));
+    /// ```
+    fn elapsed_since(&self, earlier: Instant) -> Duration {
+        self.now().saturating_duration_since(earlier)
+    }
+}
+
+/// The real time, as told by `Instant::now`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when it is told to, also from other threads.
+#[derive(Debug)]
+pub struct ManualClock {
+    /// The point in time the clock started at.
+    start: Instant,
+    /// How far the clock was advanced.
+    elapsed: Mutex<Duration>,
+}
+
+impl ManualClock {
+    /// Create a clock standing at the current time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::clock::ManualClock;
+    /// use std::time::Duration;
+    ///
+    /// assert_eq!(ManualClock::new().elapsed(), Duration::ZERO);
+    /// ```
+    pub fn new() -> Self {
+        ManualClock {
+            start: Instant::now(),
+            elapsed: Mutex::new(Duration::ZERO),
+        }
+    }
+    /// Move the clock forward.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - How far the clock is moved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::clock::ManualClock;
+    /// use std::time::Duration;
+    ///
+    /// let clock = ManualClock::new();
+    /// clock.advance(Duration::from_millis(5));
+    /// clock.advance(Duration::from_millis(5));
+    /// assert_eq!(clock.elapsed(), Duration::from_millis(10));
+    /// ```
+    pub fn advance(&self, duration: Duration) {
+        *self.lock() += duration;
+    }
+    /// How far the clock was advanced since it was created.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::clock::ManualClock;
+    /// use std::time::Duration;
+    ///
+    /// let clock = ManualClock::new();
+    /// clock.advance(Duration::from_secs(1));
+    /// assert_eq!(clock.elapsed(), Duration::from_secs(1));
+    /// ```
+    pub fn elapsed(&self) -> Duration {
+        *self.lock()
+    }
+    /// Lock the elapsed time, a panic of another thread holding the lock can't leave it
+    /// inconsistent.
+    fn lock(&self) -> std::sync::MutexGuard<'_, Duration> {
+        self.elapsed
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        ManualClock::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+}
@@ -0,0 +1,1134 @@
+use crate::polish::{Polish, PolishMethod};
+use crate::runner::{
+    Annealing, DedupPrecision, Freeze, ObjectiveDirection, OffspringPriority, Parallelism,
+    Patience, PopulationSize, ReplacementPolicy, Restart, RunnerConfig, Schedule, SteadyState,
+    SuccessiveHalving, SurvivorSelection, Zoom,
+};
+use crate::schema::{self, SchemaWarning, SCHEMA_VERSION};
+use crate::selection::TournamentSelection;
+use crate::solution::Precision;
+use crate::solutions::MergePolicy;
+use std::fmt;
+use std::time::Duration;
+
+/// The prefix of the environment variables overriding `RunnerConfig` fields, e.g.
+/// `GA_FN_N_GENERATIONS=10`.
+pub const ENV_PREFIX: &str = "GA_FN_";
+
+/// The names of all `RunnerConfig` fields that can be set from strings.
+pub const FIELDS: &[&str] = &[
+    "n_generations",
+    "size_generation",
+    "mutate_prob",
+    "crossover_prob",
+    "n_jobs",
+    "merge_policy",
+    "generation_time_budget",
+    "evaluation_chunk_size",
+    "seed",
+    "individual_rng_streams",
+    "successive_halving",
+    "record_archive",
+    "max_archive_entries",
+    "max_history",
+    "ema_smoothing",
+    "survivor_selection",
+    "steady_state",
+    "max_lifespan",
+    "distinct_parents",
+    "check_operators",
+    "evaluation_quota",
+    "offspring_priority",
+    "hall_of_fame_size",
+    "threads_per_evaluation",
+    "max_threads",
+    "size_to_time_budget",
+    "annealing",
+    "fitness_window",
+    "failure_penalty",
+    "population_size",
+    "restart",
+    "polish",
+    "freeze",
+    "elitism",
+    "zoom",
+    "mutate_schedule",
+    "direction",
+    "overlap_batch_size",
+    "target_value",
+    "patience",
+    "dedup_precision",
+    "max_evaluations",
+];
+
+/// Error that can occur when setting a config field from a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// There is no config field with this name.
+    UnknownField(String),
+    /// The value can't be parsed for the field.
+    InvalidValue {
+        /// The name of the field.
+        field: String,
+        /// The value that could not be parsed.
+        value: String,
+    },
+    /// A flag was given without a value.
+    MissingValue(String),
+    /// A line of a config file is not a `field = value` assignment.
+    InvalidLine {
+        /// The number of the line, starting at 1.
+        number: usize,
+        /// The content of the line.
+        line: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::UnknownField(field) => write!(
+                f,
+                "Unknown config field `{}`, expected one of {}",
+                field,
+                FIELDS.join(", ")
+            ),
+            ConfigError::InvalidValue { field, value } => {
+                write!(f, "Invalid value `{}` for config field `{}`", value, field)
+            }
+            ConfigError::MissingValue(flag) => write!(f, "The flag `{}` requires a value", flag),
+            ConfigError::InvalidLine { number, line } => write!(
+                f,
+                "Line {} `{}` of the config file is not a `field = value` assignment",
+                number, line
+            ),
+        }
+    }
+}
+
+/// Parse an optional value, `none` meaning `None`.
+///
+/// # Arguments
+///
+/// * `value` - The value to parse.
+/// * `parse` - How to parse the value if it is not `none`.
+fn parse_optional<T>(value: &str, parse: impl Fn(&str) -> Option<T>) -> Option<Option<T>> {
+    match value {
+        "none" => Some(None),
+        value => parse(value).map(Some),
+    }
+}
+
+/// Parse a boolean, also accepting `1`/`0` and `yes`/`no` as they are common in environment
+/// variables.
+///
+/// # Arguments
+///
+/// * `value` - The value to parse.
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" | "1" | "yes" => Some(true),
+        "false" | "0" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parse a merge policy written as `concatenate`, `global-top-k:<k>`, `per-island-top-k:<k>`
+/// or `weighted-sampling:<k>`.
+///
+/// # Arguments
+///
+/// * `value` - The value to parse.
+fn parse_merge_policy(value: &str) -> Option<MergePolicy> {
+    let (name, k) = match value.split_once(':') {
+        Some((name, k)) => (name, Some(k.parse::<usize>().ok()?)),
+        None => (value, None),
+    };
+    match (name, k) {
+        ("concatenate", None) => Some(MergePolicy::Concatenate),
+        ("global-top-k", Some(k)) => Some(MergePolicy::GlobalTopK(k)),
+        ("per-island-top-k", Some(k)) => Some(MergePolicy::PerIslandTopK(k)),
+        ("weighted-sampling", Some(k)) => Some(MergePolicy::WeightedSampling(k)),
+        _ => None,
+    }
+}
+
+/// Parse a survivor selection written as `truncation`, `stratified:<weight>,<weight>,...` or
+/// `tournament:<size>`, e.g. `stratified:0.6,0.3,0.1`.
+///
+/// # Arguments
+///
+/// * `value` - The value to parse.
+fn parse_survivor_selection(value: &str) -> Option<SurvivorSelection> {
+    match value.split_once(':') {
+        None if value == "truncation" => Some(SurvivorSelection::Truncation),
+        Some(("stratified", weights)) => weights
+            .split(',')
+            .map(|weight| {
+                weight
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|weight| *weight >= 0.0)
+            })
+            .collect::<Option<Vec<f64>>>()
+            .map(SurvivorSelection::Stratified),
+        Some(("tournament", size)) => size
+            .parse()
+            .ok()
+            .map(|size| SurvivorSelection::Tournament(TournamentSelection { size })),
+        _ => None,
+    }
+}
+
+/// Parse the annealing schedule written as `<initial_temperature>:<cooling_rate>`, e.g.
+/// `10:0.95`.
+///
+/// # Arguments
+///
+/// * `value` - The value to parse.
+fn parse_annealing(value: &str) -> Option<Annealing> {
+    let (initial_temperature, cooling_rate) = value.split_once(':')?;
+    Some(Annealing {
+        initial_temperature: initial_temperature.parse().ok()?,
+        cooling_rate: cooling_rate.parse().ok()?,
+    })
+}
+
+/// Parse the mutation schedule written as `<kind>:<start>:<end>`, where the kind is `linear`
+/// or `exponential`, e.g. `exponential:0.9:0.05`.
+///
+/// # Arguments
+///
+/// * `value` - The value to parse.
+fn parse_schedule(value: &str) -> Option<Schedule> {
+    let mut parts = value.split(':');
+    let kind = parts.next()?;
+    let start = parts.next()?.parse().ok()?;
+    let end = parts.next()?.parse().ok()?;
+    let schedule = match kind {
+        "linear" => Schedule::Linear { start, end },
+        "exponential" => Schedule::Exponential { start, end },
+        _ => return None,
+    };
+    parts.next().is_none().then_some(schedule)
+}
+
+/// Parse the freeze written as `<after_generations>:<min_std>`, e.g. `50:0.001`.
+///
+/// # Arguments
+///
+/// * `value` - The value to parse.
+fn parse_freeze(value: &str) -> Option<Freeze> {
+    let (after_generations, min_std) = value.split_once(':')?;
+    Some(Freeze {
+        after_generations: after_generations.parse().ok()?,
+        min_std: min_std.parse().ok()?,
+    })
+}
+
+/// Parse the zoom written as `<every_generations>:<factor>:<min_width>:<expand_after>`, e.g.
+/// `5:0.8:0.01:20`.
+///
+/// # Arguments
+///
+/// * `value` - The value to parse.
+fn parse_zoom(value: &str) -> Option<Zoom> {
+    let mut parts = value.split(':');
+    let zoom = Zoom {
+        every_generations: parts.next()?.parse().ok()?,
+        factor: parts.next()?.parse().ok()?,
+        min_width: parts.next()?.parse().ok()?,
+        expand_after: parts.next()?.parse().ok()?,
+    };
+    parts.next().is_none().then_some(zoom)
+}
+
+/// Parse the convergence criterion written as `<generations>:<epsilon>`, e.g. `20:1e-6`.
+///
+/// # Arguments
+///
+/// * `value` - The value to parse.
+fn parse_patience(value: &str) -> Option<Patience> {
+    let mut parts = value.split(':');
+    let patience = Patience {
+        generations: parts.next()?.parse().ok()?,
+        epsilon: parts.next()?.parse().ok()?,
+    };
+    parts.next().is_none().then_some(patience)
+}
+
+/// Parse the dedup precision written as `default`, `auto` or
+/// `<significant_digits>:<absolute_floor>`, e.g. `6:1e-4`.
+///
+/// # Arguments
+///
+/// * `value` - The value to parse.
+fn parse_dedup_precision(value: &str) -> Option<DedupPrecision> {
+    match value {
+        "default" => Some(DedupPrecision::Default),
+        "auto" => Some(DedupPrecision::Auto),
+        _ => {
+            let (significant_digits, absolute_floor) = value.split_once(':')?;
+            Some(DedupPrecision::Fixed(Precision {
+                significant_digits: significant_digits.parse().ok()?,
+                absolute_floor: absolute_floor.parse().ok()?,
+            }))
+        }
+    }
+}
+
+/// Parse the restart strategy written as
+/// `<stagnation_generations>:<n_elites>:<quasi_opposition>`, e.g. `20:2:0.5`.
+///
+/// # Arguments
+///
+/// * `value` - The value to parse.
+fn parse_restart(value: &str) -> Option<Restart> {
+    let mut parts = value.split(':');
+    let restart = Restart {
+        stagnation_generations: parts.next()?.parse().ok()?,
+        n_elites: parts.next()?.parse().ok()?,
+        quasi_opposition: parts.next()?.parse().ok()?,
+    };
+    parts.next().is_none().then_some(restart)
+}
+
+/// Parse the final polish written as `<share>:<n_candidates>:<step>:<method>`, where the
+/// method is `pattern-search` or `trust-region`, e.g. `0.1:3:0.05:trust-region`. Without a
+/// method the pattern search is used.
+///
+/// # Arguments
+///
+/// * `value` - The value to parse.
+fn parse_polish(value: &str) -> Option<Polish> {
+    let mut parts = value.split(':');
+    let polish = Polish {
+        share: parts.next()?.parse().ok()?,
+        n_candidates: parts.next()?.parse().ok()?,
+        step: parts.next()?.parse().ok()?,
+        method: match parts.next() {
+            None | Some("pattern-search") => PolishMethod::PatternSearch,
+            Some("trust-region") => PolishMethod::TrustRegion,
+            Some(_) => return None,
+        },
+    };
+    parts.next().is_none().then_some(polish)
+}
+
+/// Parse the steady-state mode written as `<n_offspring>:<replacement>`, where the
+/// replacement is `replace-worst`, `replace-oldest` or `replace-most-similar`, e.g.
+/// `2:replace-oldest`.
+///
+/// # Arguments
+///
+/// * `value` - The value to parse.
+fn parse_steady_state(value: &str) -> Option<SteadyState> {
+    let (n_offspring, replacement) = value.split_once(':')?;
+    Some(SteadyState {
+        n_offspring: n_offspring.parse().ok()?,
+        replacement: match replacement {
+            "replace-worst" => ReplacementPolicy::ReplaceWorst,
+            "replace-oldest" => ReplacementPolicy::ReplaceOldest,
+            "replace-most-similar" => ReplacementPolicy::ReplaceMostSimilar,
+            _ => return None,
+        },
+    })
+}
+
+/// Parse successive halving written as `<fidelity>,<fidelity>,...:<reduction_factor>`, e.g.
+/// `0.1,0.3,1:3`.
+///
+/// # Arguments
+///
+/// * `value` - The value to parse.
+fn parse_successive_halving(value: &str) -> Option<SuccessiveHalving> {
+    let (fidelities, reduction_factor) = value.split_once(':')?;
+    Some(SuccessiveHalving {
+        fidelities: fidelities
+            .split(',')
+            .map(|fidelity| fidelity.trim().parse::<f64>().ok())
+            .collect::<Option<Vec<f64>>>()?,
+        reduction_factor: reduction_factor.parse().ok()?,
+    })
+}
+
+/// Set a field of `config` from its string representation.
+///
+/// # Arguments
+///
+/// * `config` - The config to change.
+/// * `field` - The name of the field, see `FIELDS`.
+/// * `value` - The new value of the field.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::config;
+/// use genetic_algorithm_fn::runner::RunnerConfig;
+/// use genetic_algorithm_fn::solutions::MergePolicy;
+///
+/// let mut runner_config = RunnerConfig::default();
+/// config::set_field(&mut runner_config, "n_generations", "10").unwrap();
+/// config::set_field(&mut runner_config, "merge_policy", "global-top-k:5").unwrap();
+/// assert_eq!(runner_config.n_generations, 10);
+/// assert_eq!(runner_config.merge_policy, MergePolicy::GlobalTopK(5));
+/// assert!(config::set_field(&mut runner_config, "n_generation", "10").is_err());
+/// ```
+pub fn set_field(config: &mut RunnerConfig, field: &str, value: &str) -> Result<(), ConfigError> {
+    let invalid = || ConfigError::InvalidValue {
+        field: field.to_string(),
+        value: value.to_string(),
+    };
+    let value = value.trim();
+    match field {
+        "n_generations" => config.n_generations = value.parse().map_err(|_| invalid())?,
+        "size_generation" => config.size_generation = value.parse().map_err(|_| invalid())?,
+        "mutate_prob" => config.mutate_prob = value.parse().map_err(|_| invalid())?,
+        "crossover_prob" => config.crossover_prob = value.parse().map_err(|_| invalid())?,
+        "n_jobs" => {
+            config.n_jobs = match value {
+                "auto" => Parallelism::Auto,
+                value => Parallelism::Jobs(value.parse().map_err(|_| invalid())?),
+            }
+        }
+        "merge_policy" => config.merge_policy = parse_merge_policy(value).ok_or_else(invalid)?,
+        "generation_time_budget" => {
+            config.generation_time_budget = parse_optional(value, |seconds| {
+                Duration::try_from_secs_f64(seconds.parse().ok()?).ok()
+            })
+            .ok_or_else(invalid)?
+        }
+        "evaluation_chunk_size" => {
+            config.evaluation_chunk_size = value.parse().map_err(|_| invalid())?
+        }
+        "seed" => {
+            config.seed = parse_optional(value, |seed| seed.parse().ok()).ok_or_else(invalid)?
+        }
+        "individual_rng_streams" => {
+            config.individual_rng_streams = parse_bool(value).ok_or_else(invalid)?
+        }
+        "successive_halving" => {
+            config.successive_halving =
+                parse_optional(value, parse_successive_halving).ok_or_else(invalid)?
+        }
+        "record_archive" => config.record_archive = parse_bool(value).ok_or_else(invalid)?,
+        "max_archive_entries" => {
+            config.max_archive_entries =
+                parse_optional(value, |max| max.parse().ok()).ok_or_else(invalid)?
+        }
+        "max_history" => {
+            config.max_history =
+                parse_optional(value, |max| max.parse().ok()).ok_or_else(invalid)?
+        }
+        "ema_smoothing" => config.ema_smoothing = value.parse().map_err(|_| invalid())?,
+        "survivor_selection" => {
+            config.survivor_selection = parse_survivor_selection(value).ok_or_else(invalid)?
+        }
+        "steady_state" => {
+            config.steady_state = parse_optional(value, parse_steady_state).ok_or_else(invalid)?
+        }
+        "max_lifespan" => {
+            config.max_lifespan =
+                parse_optional(value, |max| max.parse().ok()).ok_or_else(invalid)?
+        }
+        "distinct_parents" => {
+            config.distinct_parents =
+                parse_optional(value, |epsilon| epsilon.parse().ok()).ok_or_else(invalid)?
+        }
+        "check_operators" => config.check_operators = parse_bool(value).ok_or_else(invalid)?,
+        "hall_of_fame_size" => config.hall_of_fame_size = value.parse().map_err(|_| invalid())?,
+        "elitism" => config.elitism = value.parse().map_err(|_| invalid())?,
+        "annealing" => {
+            config.annealing = parse_optional(value, parse_annealing).ok_or_else(invalid)?
+        }
+        "size_to_time_budget" => {
+            config.size_to_time_budget = parse_bool(value).ok_or_else(invalid)?
+        }
+        "fitness_window" => {
+            config.fitness_window =
+                parse_optional(value, |window| window.parse().ok()).ok_or_else(invalid)?
+        }
+        "failure_penalty" => {
+            config.failure_penalty =
+                parse_optional(value, |penalty| penalty.parse().ok()).ok_or_else(invalid)?
+        }
+        "population_size" => {
+            config.population_size = match value.split_once(':') {
+                None if value == "fixed" => PopulationSize::Fixed,
+                None if value == "auto" => PopulationSize::Auto(1.0),
+                Some(("auto", factor)) => {
+                    PopulationSize::Auto(factor.parse().map_err(|_| invalid())?)
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        "restart" => config.restart = parse_optional(value, parse_restart).ok_or_else(invalid)?,
+        "polish" => config.polish = parse_optional(value, parse_polish).ok_or_else(invalid)?,
+        "freeze" => config.freeze = parse_optional(value, parse_freeze).ok_or_else(invalid)?,
+        "zoom" => config.zoom = parse_optional(value, parse_zoom).ok_or_else(invalid)?,
+        "mutate_schedule" => {
+            config.mutate_schedule = parse_optional(value, parse_schedule).ok_or_else(invalid)?
+        }
+        "threads_per_evaluation" => {
+            config.threads_per_evaluation = value.parse().map_err(|_| invalid())?
+        }
+        "max_threads" => {
+            config.max_threads =
+                parse_optional(value, |max| max.parse().ok()).ok_or_else(invalid)?
+        }
+        "evaluation_quota" => {
+            config.evaluation_quota =
+                parse_optional(value, |quota| quota.parse().ok()).ok_or_else(invalid)?
+        }
+        "target_value" => {
+            config.target_value =
+                parse_optional(value, |target| target.parse().ok()).ok_or_else(invalid)?
+        }
+        "patience" => {
+            config.patience = parse_optional(value, parse_patience).ok_or_else(invalid)?
+        }
+        "dedup_precision" => {
+            config.dedup_precision = parse_dedup_precision(value).ok_or_else(invalid)?
+        }
+        "max_evaluations" => {
+            config.max_evaluations =
+                parse_optional(value, |max| max.parse().ok()).ok_or_else(invalid)?
+        }
+        "overlap_batch_size" => {
+            config.overlap_batch_size =
+                parse_optional(value, |size| size.parse().ok()).ok_or_else(invalid)?
+        }
+        "direction" => {
+            config.direction = match value {
+                "maximize" => ObjectiveDirection::Maximize,
+                "minimize" => ObjectiveDirection::Minimize,
+                _ => return Err(invalid()),
+            }
+        }
+        "offspring_priority" => {
+            config.offspring_priority = match value {
+                "produced" => OffspringPriority::Produced,
+                "random" => OffspringPriority::Random,
+                "novelty" => OffspringPriority::Novelty,
+                "near-best" => OffspringPriority::NearBest,
+                _ => return Err(invalid()),
+            }
+        }
+        _ => return Err(ConfigError::UnknownField(field.to_string())),
+    }
+    Ok(())
+}
+
+/// Write the value of a field of `config` the way `set_field` reads it. Returns `None` for
+/// unknown fields.
+///
+/// # Arguments
+///
+/// * `config` - The config to read from.
+/// * `field` - The name of the field, see `FIELDS`.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::config;
+/// use genetic_algorithm_fn::runner::RunnerConfig;
+///
+/// let runner_config = RunnerConfig::default();
+/// assert_eq!(config::field_value(&runner_config, "seed"), Some("none".to_string()));
+/// for field in config::FIELDS {
+///     let mut copy = RunnerConfig::default();
+///     let value = config::field_value(&runner_config, field).unwrap();
+///     config::set_field(&mut copy, field, &value).unwrap();
+///     assert_eq!(copy, runner_config);
+/// }
+/// ```
+pub fn field_value(config: &RunnerConfig, field: &str) -> Option<String> {
+    let optional = |value: Option<String>| value.unwrap_or_else(|| "none".to_string());
+    Some(match field {
+        "n_generations" => config.n_generations.to_string(),
+        "size_generation" => config.size_generation.to_string(),
+        "mutate_prob" => config.mutate_prob.to_string(),
+        "crossover_prob" => config.crossover_prob.to_string(),
+        "n_jobs" => match config.n_jobs {
+            Parallelism::Jobs(n_jobs) => n_jobs.to_string(),
+            Parallelism::Auto => "auto".to_string(),
+        },
+        "merge_policy" => match config.merge_policy {
+            MergePolicy::Concatenate => "concatenate".to_string(),
+            MergePolicy::GlobalTopK(k) => format!("global-top-k:{}", k),
+            MergePolicy::PerIslandTopK(k) => format!("per-island-top-k:{}", k),
+            MergePolicy::WeightedSampling(k) => format!("weighted-sampling:{}", k),
+        },
+        "generation_time_budget" => optional(
+            config
+                .generation_time_budget
+                .map(|budget| budget.as_secs_f64().to_string()),
+        ),
+        "evaluation_chunk_size" => config.evaluation_chunk_size.to_string(),
+        "seed" => optional(config.seed.map(|seed| seed.to_string())),
+        "individual_rng_streams" => config.individual_rng_streams.to_string(),
+        "successive_halving" => optional(config.successive_halving.as_ref().map(|halving| {
+            format!(
+                "{}:{}",
+                halving
+                    .fidelities
+                    .iter()
+                    .map(|fidelity| fidelity.to_string())
+                    .collect::<Vec<String>>()
+                    .join(","),
+                halving.reduction_factor
+            )
+        })),
+        "record_archive" => config.record_archive.to_string(),
+        "max_archive_entries" => optional(config.max_archive_entries.map(|max| max.to_string())),
+        "max_history" => optional(config.max_history.map(|max| max.to_string())),
+        "ema_smoothing" => config.ema_smoothing.to_string(),
+        "survivor_selection" => match &config.survivor_selection {
+            SurvivorSelection::Truncation => "truncation".to_string(),
+            SurvivorSelection::Stratified(weights) => format!(
+                "stratified:{}",
+                weights
+                    .iter()
+                    .map(|weight| weight.to_string())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+            SurvivorSelection::Tournament(tournament) => format!("tournament:{}", tournament.size),
+        },
+        "steady_state" => optional(config.steady_state.map(|steady_state| {
+            format!(
+                "{}:{}",
+                steady_state.n_offspring,
+                match steady_state.replacement {
+                    ReplacementPolicy::ReplaceWorst => "replace-worst",
+                    ReplacementPolicy::ReplaceOldest => "replace-oldest",
+                    ReplacementPolicy::ReplaceMostSimilar => "replace-most-similar",
+                }
+            )
+        })),
+        "max_lifespan" => optional(config.max_lifespan.map(|max| max.to_string())),
+        "distinct_parents" => optional(config.distinct_parents.map(|epsilon| epsilon.to_string())),
+        "check_operators" => config.check_operators.to_string(),
+        "evaluation_quota" => optional(config.evaluation_quota.map(|quota| quota.to_string())),
+        "overlap_batch_size" => optional(config.overlap_batch_size.map(|size| size.to_string())),
+        "target_value" => optional(config.target_value.map(|target| target.to_string())),
+        "patience" => optional(
+            config
+                .patience
+                .map(|patience| format!("{}:{}", patience.generations, patience.epsilon)),
+        ),
+        "dedup_precision" => match config.dedup_precision {
+            DedupPrecision::Default => "default".to_string(),
+            DedupPrecision::Auto => "auto".to_string(),
+            DedupPrecision::Fixed(precision) => format!(
+                "{}:{}",
+                precision.significant_digits, precision.absolute_floor
+            ),
+        },
+        "max_evaluations" => optional(config.max_evaluations.map(|max| max.to_string())),
+        "direction" => match config.direction {
+            ObjectiveDirection::Maximize => "maximize",
+            ObjectiveDirection::Minimize => "minimize",
+        }
+        .to_string(),
+        "offspring_priority" => match config.offspring_priority {
+            OffspringPriority::Produced => "produced",
+            OffspringPriority::Random => "random",
+            OffspringPriority::Novelty => "novelty",
+            OffspringPriority::NearBest => "near-best",
+        }
+        .to_string(),
+        "hall_of_fame_size" => config.hall_of_fame_size.to_string(),
+        "elitism" => config.elitism.to_string(),
+        "threads_per_evaluation" => config.threads_per_evaluation.to_string(),
+        "size_to_time_budget" => config.size_to_time_budget.to_string(),
+        "annealing" => optional(config.annealing.map(|annealing| {
+            format!(
+                "{}:{}",
+                annealing.initial_temperature, annealing.cooling_rate
+            )
+        })),
+        "max_threads" => optional(config.max_threads.map(|max| max.to_string())),
+        "fitness_window" => optional(config.fitness_window.map(|window| window.to_string())),
+        "failure_penalty" => optional(config.failure_penalty.map(|penalty| penalty.to_string())),
+        "population_size" => match config.population_size {
+            PopulationSize::Fixed => "fixed".to_string(),
+            PopulationSize::Auto(factor) => format!("auto:{}", factor),
+        },
+        "restart" => optional(config.restart.map(|restart| {
+            format!(
+                "{}:{}:{}",
+                restart.stagnation_generations, restart.n_elites, restart.quasi_opposition
+            )
+        })),
+        "polish" => optional(config.polish.map(|polish| {
+            format!(
+                "{}:{}:{}:{}",
+                polish.share,
+                polish.n_candidates,
+                polish.step,
+                match polish.method {
+                    PolishMethod::PatternSearch => "pattern-search",
+                    PolishMethod::TrustRegion => "trust-region",
+                }
+            )
+        })),
+        "freeze" => optional(
+            config
+                .freeze
+                .map(|freeze| format!("{}:{}", freeze.after_generations, freeze.min_std)),
+        ),
+        "zoom" => optional(config.zoom.map(|zoom| {
+            format!(
+                "{}:{}:{}:{}",
+                zoom.every_generations, zoom.factor, zoom.min_width, zoom.expand_after
+            )
+        })),
+        "mutate_schedule" => optional(config.mutate_schedule.map(|schedule| match schedule {
+            Schedule::Linear { start, end } => format!("linear:{}:{}", start, end),
+            Schedule::Exponential { start, end } => format!("exponential:{}:{}", start, end),
+        })),
+        _ => return None,
+    })
+}
+
+/// Set the fields of `config` from `GA_FN_*` environment variables, e.g. `GA_FN_SEED=42`.
+/// Variables without the prefix are ignored, prefixed variables that don't name a field are
+/// an error so typos don't go unnoticed.
+///
+/// # Arguments
+///
+/// * `config` - The config to change.
+/// * `vars` - The environment, typically `std::env::vars()`.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::config;
+/// use genetic_algorithm_fn::runner::RunnerConfig;
+///
+/// let mut runner_config = RunnerConfig::default();
+/// config::apply_env(
+///     &mut runner_config,
+///     vec![
+///         ("GA_FN_SEED".to_string(), "42".to_string()),
+///         ("HOME".to_string(), "/root".to_string()),
+///     ],
+/// )
+/// .unwrap();
+/// assert_eq!(runner_config.seed, Some(42));
+/// ```
+pub fn apply_env<I>(config: &mut RunnerConfig, vars: I) -> Result<(), ConfigError>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    for (name, value) in vars {
+        if let Some(field) = name.strip_prefix(ENV_PREFIX) {
+            set_field(config, &field.to_lowercase(), &value)?;
+        }
+    }
+    Ok(())
+}
+
+/// Set the fields of `config` from command line flags, written as `--n-generations 10` or
+/// `--n-generations=10`. Returns the arguments that are not config flags, in order.
+///
+/// # Arguments
+///
+/// * `config` - The config to change.
+/// * `args` - The command line arguments.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::config;
+/// use genetic_algorithm_fn::runner::{Parallelism, RunnerConfig};
+///
+/// let mut runner_config = RunnerConfig::default();
+/// let rest = config::apply_args(
+///     &mut runner_config,
+///     &["--n-jobs=4", "--checkpoint", "run.json", "--seed", "3"].map(String::from),
+/// )
+/// .unwrap();
+/// assert_eq!((runner_config.n_jobs, runner_config.seed), (Parallelism::Jobs(4), Some(3)));
+/// assert_eq!(rest, vec!["--checkpoint", "run.json"]);
+/// ```
+pub fn apply_args(config: &mut RunnerConfig, args: &[String]) -> Result<Vec<String>, ConfigError> {
+    let mut rest = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let Some(flag) = arg.strip_prefix("--") else {
+            rest.push(arg.clone());
+            continue;
+        };
+        let (name, inline_value) = match flag.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (flag, None),
+        };
+        let field = name.replace('-', "_");
+        if !FIELDS.contains(&field.as_str()) {
+            rest.push(arg.clone());
+            continue;
+        }
+        let value = match inline_value {
+            Some(value) => value,
+            None => args
+                .next()
+                .cloned()
+                .ok_or_else(|| ConfigError::MissingValue(arg.clone()))?,
+        };
+        set_field(config, &field, &value)?;
+    }
+    Ok(rest)
+}
+
+/// Write all fields of `config` as a flat TOML document, one `field = value` line per field
+/// after the `schema_version`. Numbers and booleans are written bare, everything else as a
+/// string.
+///
+/// # Arguments
+///
+/// * `config` - The config to write.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::config;
+/// use genetic_algorithm_fn::runner::RunnerConfig;
+///
+/// let toml = config::to_toml(&RunnerConfig::default());
+/// assert!(toml.contains("seed = \"none\"\n"));
+/// assert!(toml.contains("mutate_prob = 0.5\n"));
+/// ```
+pub fn to_toml(config: &RunnerConfig) -> String {
+    let fields = FIELDS
+        .iter()
+        .map(|field| {
+            let value = field_value(config, field).expect("Every field has a value");
+            // TOML integers are 64 bit signed, larger seeds are written as strings.
+            let is_bare = value == "true"
+                || value == "false"
+                || value.parse::<i64>().is_ok()
+                || (value.contains('.') && value.parse::<f64>().is_ok_and(f64::is_finite));
+            if is_bare {
+                format!("{} = {}\n", field, value)
+            } else {
+                format!("{} = \"{}\"\n", field, value)
+            }
+        })
+        .collect::<String>();
+    format!("schema_version = {}\n{}", SCHEMA_VERSION, fields)
+}
+
+/// Set the fields of `config` from a flat TOML document as written by `to_toml`. Empty lines
+/// and comments are skipped, string values may be quoted or bare. Unknown fields are an
+/// error, unless the document declares a newer `schema_version`; then they are skipped and
+/// returned as warnings. Files without a version are taken as hand-written for this version.
+///
+/// # Arguments
+///
+/// * `config` - The config to change.
+/// * `toml` - The content of the config file.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::config;
+/// use genetic_algorithm_fn::runner::RunnerConfig;
+///
+/// let mut runner_config = RunnerConfig::default();
+/// config::apply_toml(
+///     &mut runner_config,
+///     "# Shared with a colleague\nseed = 42\nmerge_policy = \"global-top-k:8\" # the best 8\n",
+/// )
+/// .unwrap();
+/// assert_eq!(runner_config.seed, Some(42));
+/// ```
+pub fn apply_toml(
+    config: &mut RunnerConfig,
+    toml: &str,
+) -> Result<Vec<SchemaWarning>, ConfigError> {
+    let mut assignments = Vec::new();
+    for (index, line) in toml.lines().enumerate() {
+        let invalid_line = || ConfigError::InvalidLine {
+            number: index + 1,
+            line: line.to_string(),
+        };
+        let content = line.trim();
+        if content.is_empty() || content.starts_with('#') {
+            continue;
+        }
+        let (field, value) = content.split_once('=').ok_or_else(invalid_line)?;
+        let value = value.trim();
+        let value = match value.strip_prefix('"') {
+            Some(quoted) => {
+                let (value, rest) = quoted.split_once('"').ok_or_else(invalid_line)?;
+                let rest = rest.trim();
+                if !rest.is_empty() && !rest.starts_with('#') {
+                    return Err(invalid_line());
+                }
+                value
+            }
+            None => value.split('#').next().unwrap_or_default().trim(),
+        };
+        assignments.push((index + 1, line, field.trim(), value));
+    }
+    let mut version = None;
+    for (number, line, field, value) in &assignments {
+        if *field == "schema_version" {
+            version = Some(value.parse().map_err(|_| ConfigError::InvalidLine {
+                number: *number,
+                line: line.to_string(),
+            })?);
+        }
+    }
+    let mut warnings = Vec::new();
+    let is_newer = version.is_some_and(|version| version > SCHEMA_VERSION);
+    if is_newer {
+        warnings.extend(schema::check_version(version));
+    }
+    for (_, _, field, value) in assignments {
+        if field == "schema_version" {
+            continue;
+        }
+        match set_field(config, field, value) {
+            Err(ConfigError::UnknownField(field)) if is_newer => {
+                warnings.push(SchemaWarning::UnknownField(field))
+            }
+            outcome => outcome?,
+        }
+    }
+    Ok(warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_field_can_be_set() {
+        let values = [
+            "7",
+            "9",
+            "0.25",
+            "0.75",
+            "2",
+            "weighted-sampling:4",
+            "1.5",
+            "3",
+            "11",
+            "yes",
+            "0.2,1:5",
+            "true",
+            "1000",
+            "none",
+            "0.5",
+            "stratified:0.5,0.5",
+            "2:replace-most-similar",
+            "4",
+            "0.001",
+            "true",
+            "12",
+            "near-best",
+            "5",
+            "4",
+            "16",
+            "true",
+            "10:0.95",
+            "3",
+            "-1000.5",
+            "auto:2",
+            "15:2:0.5",
+            "0.1:2:0.05:trust-region",
+            "40:0.001",
+            "2",
+            "4:0.5:0.05:10",
+            "exponential:0.9:0.05",
+            "minimize",
+            "64",
+            "-0.5",
+            "20:0.001",
+            "6:0.0001",
+            "5000",
+        ];
+        let mut config = RunnerConfig::default();
+        for (field, value) in FIELDS.iter().zip(values) {
+            set_field(&mut config, field, value).unwrap();
+        }
+        assert_eq!(
+            config,
+            RunnerConfig {
+                n_generations: 7,
+                size_generation: 9,
+                mutate_prob: 0.25,
+                crossover_prob: 0.75,
+                n_jobs: Parallelism::Jobs(2),
+                merge_policy: MergePolicy::WeightedSampling(4),
+                generation_time_budget: Some(Duration::from_millis(1500)),
+                evaluation_chunk_size: 3,
+                seed: Some(11),
+                individual_rng_streams: true,
+                successive_halving: Some(SuccessiveHalving {
+                    fidelities: vec![0.2, 1.0],
+                    reduction_factor: 5,
+                }),
+                record_archive: true,
+                max_archive_entries: Some(1000),
+                max_history: None,
+                ema_smoothing: 0.5,
+                survivor_selection: SurvivorSelection::Stratified(vec![0.5, 0.5]),
+                steady_state: Some(SteadyState {
+                    n_offspring: 2,
+                    replacement: ReplacementPolicy::ReplaceMostSimilar,
+                }),
+                max_lifespan: Some(4),
+                distinct_parents: Some(0.001),
+                check_operators: true,
+                evaluation_quota: Some(12),
+                offspring_priority: OffspringPriority::NearBest,
+                hall_of_fame_size: 5,
+                threads_per_evaluation: 4,
+                max_threads: Some(16),
+                size_to_time_budget: true,
+                annealing: Some(Annealing {
+                    initial_temperature: 10.0,
+                    cooling_rate: 0.95,
+                }),
+                fitness_window: Some(3),
+                failure_penalty: Some(-1000.5),
+                population_size: PopulationSize::Auto(2.0),
+                restart: Some(Restart {
+                    stagnation_generations: 15,
+                    n_elites: 2,
+                    quasi_opposition: 0.5,
+                }),
+                polish: Some(Polish {
+                    share: 0.1,
+                    n_candidates: 2,
+                    step: 0.05,
+                    method: PolishMethod::TrustRegion,
+                }),
+                freeze: Some(Freeze {
+                    after_generations: 40,
+                    min_std: 0.001,
+                }),
+                elitism: 2,
+                zoom: Some(Zoom {
+                    every_generations: 4,
+                    factor: 0.5,
+                    min_width: 0.05,
+                    expand_after: 10,
+                }),
+                mutate_schedule: Some(Schedule::Exponential {
+                    start: 0.9,
+                    end: 0.05,
+                }),
+                direction: ObjectiveDirection::Minimize,
+                overlap_batch_size: Some(64),
+                target_value: Some(-0.5),
+                patience: Some(Patience {
+                    generations: 20,
+                    epsilon: 0.001,
+                }),
+                dedup_precision: DedupPrecision::Fixed(Precision {
+                    significant_digits: 6,
+                    absolute_floor: 0.0001,
+                }),
+                max_evaluations: Some(5000),
+            }
+        );
+        let mut copy = RunnerConfig::default();
+        for field in FIELDS {
+            set_field(&mut copy, field, &field_value(&config, field).unwrap()).unwrap();
+        }
+        assert_eq!(copy, config);
+        set_field(&mut config, "seed", "none").unwrap();
+        assert_eq!(config.seed, None);
+    }
+    #[test]
+    fn toml_round_trips_every_field() {
+        let config = RunnerConfig {
+            seed: Some(7),
+            merge_policy: MergePolicy::GlobalTopK(3),
+            generation_time_budget: Some(Duration::from_millis(250)),
+            successive_halving: Some(SuccessiveHalving {
+                fidelities: vec![0.5, 1.0],
+                reduction_factor: 2,
+            }),
+            ..RunnerConfig::default()
+        };
+        let mut copy = RunnerConfig::default();
+        apply_toml(&mut copy, &to_toml(&config)).unwrap();
+        assert_eq!(copy, config);
+        assert_eq!(
+            apply_toml(&mut copy, "seed = 1\n[runner]\n"),
+            Err(ConfigError::InvalidLine {
+                number: 2,
+                line: "[runner]".to_string()
+            })
+        );
+        // Nothing is applied from an invalid file.
+        assert_eq!(copy.seed, Some(7));
+    }
+    #[test]
+    fn toml_from_newer_versions_skips_unknown_fields() {
+        let mut config = RunnerConfig::default();
+        assert_eq!(
+            apply_toml(&mut config, "seed = 3\nwarp_drive = true\n"),
+            Err(ConfigError::UnknownField("warp_drive".to_string()))
+        );
+        let newer = format!(
+            "seed = 3\nwarp_drive = true\nschema_version = {}\n",
+            SCHEMA_VERSION + 1
+        );
+        assert_eq!(
+            apply_toml(&mut config, &newer),
+            Ok(vec![
+                SchemaWarning::NewerVersion(SCHEMA_VERSION + 1),
+                SchemaWarning::UnknownField("warp_drive".to_string())
+            ])
+        );
+        assert_eq!(config.seed, Some(3));
+    }
+    #[test]
+    fn flags_take_precedence_over_env() {
+        let mut config = RunnerConfig::default();
+        apply_env(
+            &mut config,
+            vec![
+                ("GA_FN_N_JOBS".to_string(), "2".to_string()),
+                ("GA_FN_SEED".to_string(), "1".to_string()),
+            ],
+        )
+        .unwrap();
+        let rest = apply_args(&mut config, &["--seed".to_string(), "5".to_string()]).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(
+            (config.n_jobs, config.seed),
+            (Parallelism::Jobs(2), Some(5))
+        );
+    }
+    #[test]
+    fn errors_are_reported() {
+        let mut config = RunnerConfig::default();
+        assert_eq!(
+            apply_env(
+                &mut config,
+                vec![("GA_FN_N_GENERATOINS".to_string(), "2".to_string())]
+            ),
+            Err(ConfigError::UnknownField("n_generatoins".to_string()))
+        );
+        assert_eq!(
+            apply_args(&mut config, &["--mutate-prob".to_string()]),
+            Err(ConfigError::MissingValue("--mutate-prob".to_string()))
+        );
+        assert_eq!(
+            set_field(&mut config, "merge_policy", "global-top-k"),
+            Err(ConfigError::InvalidValue {
+                field: "merge_policy".to_string(),
+                value: "global-top-k".to_string()
+            })
+        );
+    }
+}
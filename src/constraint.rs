@@ -0,0 +1,204 @@
+use crate::solution::{compare_fitness, Solution};
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::Arc;
+
+/// A constraint on the arguments of a solution, e.g. `x0 + x1 <= 1`.
+pub trait Constraint: Send + Sync {
+    /// Return by how much `solution` violates the constraint, `0` or less if it satisfies it.
+    /// For `g(x) <= 0` this is `g(x)`, for `h(x) = 0` e.g. `|h(x)| - tolerance`.
+    ///
+    /// # Arguments
+    ///
+    /// * `solution` - The solution to check.
+    fn violation(&self, solution: &Solution) -> f64;
+}
+
+impl<F> Constraint for F
+where
+    F: Fn(&Solution) -> f64 + Send + Sync,
+{
+    fn violation(&self, solution: &Solution) -> f64 {
+        self(solution)
+    }
+}
+
+/// The constraints a solution has to satisfy to be feasible, see `Solutions::with_constraints`.
+#[derive(Clone, Default)]
+pub struct Constraints {
+    /// The constraints, all of them have to be satisfied.
+    constraints: Vec<Arc<dyn Constraint>>,
+}
+
+impl fmt::Debug for Constraints {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("Constraints")
+            .field("n_constraints", &self.constraints.len())
+            .finish()
+    }
+}
+
+impl Constraints {
+    /// Create an empty set of constraints, every solution is feasible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::constraint::Constraints;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// assert!(Constraints::new().is_feasible(&Solution::new(vec![1.0])));
+    /// ```
+    pub fn new() -> Self {
+        Constraints::default()
+    }
+    /// Add a constraint.
+    ///
+    /// # Arguments
+    ///
+    /// * `constraint` - Returns by how much a solution violates it, see `Constraint`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::constraint::Constraints;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// // x0 + x1 <= 1
+    /// let constraints = Constraints::new()
+    ///     .with_constraint(|solution: &Solution| solution.get_arguments().iter().sum::<f64>() - 1.0);
+    /// assert!(!constraints.is_feasible(&Solution::new(vec![1.0, 1.0])));
+    /// ```
+    pub fn with_constraint<C>(mut self, constraint: C) -> Self
+    where
+        C: Constraint + 'static,
+    {
+        self.constraints.push(Arc::new(constraint));
+        self
+    }
+    /// The total violation of all constraints, `0` if `solution` is feasible. A constraint that
+    /// can't be evaluated, i.e. returns `NaN`, counts as infinitely violated.
+    ///
+    /// # Arguments
+    ///
+    /// * `solution` - The solution to check.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::constraint::Constraints;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let constraints = Constraints::new()
+    ///     .with_constraint(|solution: &Solution| solution.get_arguments()[0] - 1.0)
+    ///     .with_constraint(|solution: &Solution| -solution.get_arguments()[0]);
+    /// assert_eq!(constraints.violation(&Solution::new(vec![3.0])), 2.0);
+    /// assert_eq!(constraints.violation(&Solution::new(vec![0.5])), 0.0);
+    /// ```
+    pub fn violation(&self, solution: &Solution) -> f64 {
+        self.constraints
+            .iter()
+            .map(|constraint| match constraint.violation(solution) {
+                violation if violation.is_nan() => f64::INFINITY,
+                violation => violation.max(0.0),
+            })
+            .sum()
+    }
+    /// Return whether `solution` satisfies all constraints.
+    ///
+    /// # Arguments
+    ///
+    /// * `solution` - The solution to check.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::constraint::Constraints;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let constraints =
+    ///     Constraints::new().with_constraint(|solution: &Solution| -solution.get_arguments()[0]);
+    /// assert!(constraints.is_feasible(&Solution::new(vec![0.0])));
+    /// assert!(!constraints.is_feasible(&Solution::new(vec![-0.1])));
+    /// ```
+    pub fn is_feasible(&self, solution: &Solution) -> bool {
+        self.violation(solution) == 0.0
+    }
+}
+
+/// Order solutions by Deb's feasibility rules, from the best to the worst one: a feasible
+/// solution beats an infeasible one, feasible solutions are ordered by their fitness like
+/// `Population::get_n_fittest` and infeasible ones by their constraint violation, the smaller
+/// the better. Remaining ties are broken by fitness and then by arguments.
+///
+/// # Arguments
+///
+/// * `(solution_a, fitness_a, violation_a)` - The first solution, its fitness and its total
+///   constraint violation, see `Constraints::violation`.
+/// * `(solution_b, fitness_b, violation_b)` - The second solution, its fitness and violation.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::constraint::compare_feasibility;
+/// use genetic_algorithm_fn::solution::Solution;
+/// use std::cmp::Ordering;
+///
+/// let feasible = Solution::new(vec![0.0]);
+/// let infeasible = Solution::new(vec![1.0]);
+/// // A feasible solution beats a fitter infeasible one.
+/// assert_eq!(
+///     compare_feasibility((&feasible, 1.0, 0.0), (&infeasible, 100.0, 0.5)),
+///     Ordering::Less
+/// );
+/// ```
+pub fn compare_feasibility(
+    (solution_a, fitness_a, violation_a): (&Solution, f64, f64),
+    (solution_b, fitness_b, violation_b): (&Solution, f64, f64),
+) -> Ordering {
+    match (violation_a > 0.0, violation_b > 0.0) {
+        (false, false) => Ordering::Equal,
+        (true, true) => violation_a.total_cmp(&violation_b),
+        (infeasible_a, infeasible_b) => infeasible_a.cmp(&infeasible_b),
+    }
+    .then_with(|| compare_fitness((solution_a, fitness_a), (solution_b, fitness_b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn feasibility_rules_order_solutions() {
+        let constraints = Constraints::new()
+            .with_constraint(|solution: &Solution| solution.get_arguments()[0] - 2.0)
+            .with_constraint(|_: &Solution| -1.0);
+        let mut solutions = [5.0, 0.0, 3.0, 1.0, 2.0]
+            .iter()
+            .map(|x| {
+                let solution = Solution::new(vec![*x]);
+                let violation = constraints.violation(&solution);
+                (solution, *x, violation)
+            })
+            .collect::<Vec<(Solution, f64, f64)>>();
+        solutions.sort_by(
+            |(solution_a, fitness_a, violation_a), (solution_b, fitness_b, violation_b)| {
+                compare_feasibility(
+                    (solution_a, *fitness_a, *violation_a),
+                    (solution_b, *fitness_b, *violation_b),
+                )
+            },
+        );
+        // The fittest feasible solution first, then the least violating infeasible one.
+        assert_eq!(
+            solutions
+                .iter()
+                .map(|(solution, _, _)| solution.get_arguments()[0])
+                .collect::<Vec<f64>>(),
+            vec![2.0, 1.0, 0.0, 3.0, 5.0]
+        );
+        let broken = Constraints::new().with_constraint(|_: &Solution| f64::NAN);
+        assert_eq!(broken.violation(&Solution::new(vec![0.0])), f64::INFINITY);
+    }
+}
@@ -0,0 +1,494 @@
+use crate::evaluation::{CountingEvaluator, Evaluator};
+use crate::executor::{self, ScopedThreads};
+use crate::runner::{self, RunnerConfig};
+use crate::solution::Solution;
+use crate::solutions::Solutions;
+use genetic_algorithm_traits::Population;
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+
+/// An optimization algorithm that can be advanced step by step, so that several algorithms can
+/// share one evaluation budget in an `Ensemble`.
+pub trait Algorithm: Send {
+    /// The name of the algorithm as it appears in the traces.
+    fn name(&self) -> String;
+    /// Advance the algorithm until it spent (about) `n_evaluations` more evaluations and return
+    /// the best solution it found so far. The algorithm may overshoot by the evaluations of its
+    /// last step.
+    ///
+    /// # Arguments
+    ///
+    /// * `evaluator` - The evaluator whose fitness should be maximized.
+    /// * `n_evaluations` - How many evaluations the algorithm may spend.
+    fn advance(
+        &mut self,
+        evaluator: &dyn Evaluator,
+        n_evaluations: usize,
+    ) -> Option<(Solution, f64)>;
+}
+
+/// The genetic algorithm of this crate as an `Algorithm`, advanced one generation at a time.
+/// The evaluated population is kept between generations, so parents are never re-evaluated.
+#[derive(Debug)]
+pub struct GeneticAlgorithmArm {
+    name: String,
+    config: RunnerConfig,
+    rng: ChaCha12Rng,
+    population: Vec<(Solution, Option<f64>)>,
+    generation: usize,
+    best: Option<(Solution, f64)>,
+}
+
+impl GeneticAlgorithmArm {
+    /// Create a new genetic algorithm arm.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the arm in the traces.
+    /// * `config` - The settings of the genetic algorithm. `n_generations` and `n_jobs` are
+    ///   ignored, the ensemble decides how long every arm runs.
+    /// * `initial_population` - The population the arm starts from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::ensemble::GeneticAlgorithmArm;
+    /// use genetic_algorithm_fn::runner::RunnerConfig;
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let arm = GeneticAlgorithmArm::new(
+    ///     "ga",
+    ///     RunnerConfig::default(),
+    ///     Solutions::random(10, -1.0..1.0, 3),
+    /// );
+    /// ```
+    pub fn new(name: &str, config: RunnerConfig, initial_population: Solutions) -> Self {
+        GeneticAlgorithmArm {
+            name: name.to_string(),
+            rng: ChaCha12Rng::seed_from_u64(config.seed.unwrap_or_else(rand::random)),
+            config,
+            population: initial_population
+                .iter()
+                .map(|solution| (solution.clone(), None))
+                .collect(),
+            generation: 0,
+            best: None,
+        }
+    }
+}
+
+impl Algorithm for GeneticAlgorithmArm {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+    fn advance(
+        &mut self,
+        evaluator: &dyn Evaluator,
+        n_evaluations: usize,
+    ) -> Option<(Solution, f64)> {
+        let counting = CountingEvaluator::new(evaluator);
+        while counting.count() < n_evaluations {
+            let before = counting.count();
+            let result = runner::step(
+                std::mem::take(&mut self.population),
+                self.generation,
+                &self.config,
+                &mut self.rng,
+                &counting,
+            )
+            .unwrap_or_else(|error| panic!("{}", error));
+            self.population = result.population;
+            self.generation += 1;
+            if let Some((solution, fitness)) = runner::fittest(&self.population) {
+                if self
+                    .best
+                    .as_ref()
+                    .is_none_or(|(_, best_fitness)| fitness > *best_fitness)
+                {
+                    self.best = Some((solution, fitness));
+                }
+            }
+            if counting.count() == before {
+                // Nothing left to evaluate, e.g. the population is empty.
+                break;
+            }
+        }
+        self.best.clone()
+    }
+}
+
+/// The settings of an `Ensemble`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnsembleConfig {
+    /// The number of evaluations all algorithms together may spend.
+    pub total_evaluations: usize,
+    /// The number of evaluations distributed among the algorithms per round.
+    pub evaluations_per_round: usize,
+    /// The share of every round that is split evenly among all algorithms. The rest goes to
+    /// the algorithm that progressed the most in the previous round.
+    pub exploration: f64,
+}
+
+impl Default for EnsembleConfig {
+    fn default() -> Self {
+        EnsembleConfig {
+            total_evaluations: 100_000,
+            evaluations_per_round: 5_000,
+            exploration: 0.2,
+        }
+    }
+}
+
+/// The state of a single algorithm after one round of an `Ensemble`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TracePoint {
+    /// The round of the ensemble.
+    pub round: usize,
+    /// The evaluations the algorithm was granted in this round.
+    pub allocated_evaluations: usize,
+    /// The evaluations the algorithm spent in total so far.
+    pub evaluations: usize,
+    /// The best fitness of the algorithm so far.
+    pub best_fitness: Option<f64>,
+}
+
+/// The progress of a single algorithm during an ensemble run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlgorithmTrace {
+    /// The name of the algorithm.
+    pub name: String,
+    /// One point per round.
+    pub points: Vec<TracePoint>,
+}
+
+/// The outcome of `Ensemble::run`.
+#[derive(Debug, Clone)]
+pub struct EnsembleResult {
+    /// The best solution over all algorithms and its fitness.
+    pub best: Option<(Solution, f64)>,
+    /// The name of the algorithm that found `best`.
+    pub best_algorithm: Option<String>,
+    /// The traces of all algorithms, in the order they were added.
+    pub traces: Vec<AlgorithmTrace>,
+}
+
+/// Runs several algorithms concurrently on a shared evaluation budget. The budget is handed
+/// out in rounds by an epsilon-greedy bandit: every round, `exploration` of the round's
+/// evaluations is split evenly and the rest goes to the algorithm whose best fitness improved
+/// the most per evaluation in the previous round.
+pub struct Ensemble {
+    config: EnsembleConfig,
+    algorithms: Vec<Box<dyn Algorithm>>,
+}
+
+impl Ensemble {
+    /// Create an ensemble without any algorithms.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The settings of the ensemble.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::ensemble::{Ensemble, EnsembleConfig};
+    ///
+    /// let ensemble = Ensemble::new(EnsembleConfig::default());
+    /// ```
+    pub fn new(config: EnsembleConfig) -> Self {
+        Ensemble {
+            config,
+            algorithms: Vec::new(),
+        }
+    }
+    /// Add an algorithm to the ensemble.
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The algorithm that should compete for the budget.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::ensemble::{Ensemble, EnsembleConfig, GeneticAlgorithmArm};
+    /// use genetic_algorithm_fn::runner::RunnerConfig;
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let ensemble = Ensemble::new(EnsembleConfig::default()).with_algorithm(
+    ///     GeneticAlgorithmArm::new("ga", RunnerConfig::default(), Solutions::random(10, -1.0..1.0, 3)),
+    /// );
+    /// ```
+    pub fn with_algorithm<A>(mut self, algorithm: A) -> Self
+    where
+        A: Algorithm + 'static,
+    {
+        self.algorithms.push(Box::new(algorithm));
+        self
+    }
+    /// Split the evaluations of a round among the algorithms.
+    ///
+    /// # Arguments
+    ///
+    /// * `rewards` - The progress per evaluation of every algorithm in the previous round,
+    ///   `None` before the first round.
+    /// * `round_budget` - The evaluations of this round.
+    fn allocate(&self, rewards: Option<&[f64]>, round_budget: usize) -> Vec<usize> {
+        let n_algorithms = self.algorithms.len();
+        // Without any progress in the previous round, all algorithms keep exploring evenly.
+        let leader = rewards.and_then(|rewards| {
+            rewards
+                .iter()
+                .enumerate()
+                .filter(|(_, reward)| **reward > 0.0)
+                .max_by(|(_, reward_a), (_, reward_b)| reward_a.total_cmp(reward_b))
+                .map(|(idx, _)| idx)
+        });
+        match leader {
+            None => (0..n_algorithms)
+                .map(|idx| {
+                    round_budget / n_algorithms + usize::from(idx < round_budget % n_algorithms)
+                })
+                .collect(),
+            Some(leader) => {
+                let exploration = self.config.exploration.clamp(0.0, 1.0);
+                let explored = (round_budget as f64 * exploration) as usize;
+                let mut allocation = (0..n_algorithms)
+                    .map(|_| explored / n_algorithms)
+                    .collect::<Vec<usize>>();
+                allocation[leader] += round_budget - allocation.iter().sum::<usize>();
+                allocation
+            }
+        }
+    }
+    /// Run all algorithms until the evaluation budget is spent.
+    ///
+    /// # Arguments
+    ///
+    /// * `evaluator` - The evaluator whose fitness should be maximized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::ensemble::{Ensemble, EnsembleConfig, GeneticAlgorithmArm};
+    /// use genetic_algorithm_fn::function;
+    /// use genetic_algorithm_fn::runner::RunnerConfig;
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let function_to_optimize = function::Function::new(|x| Ok(-x.iter().map(|v| v * v).sum::<f64>()));
+    /// let config = RunnerConfig {
+    ///     size_generation: 5,
+    ///     ..RunnerConfig::default()
+    /// };
+    /// let result = Ensemble::new(EnsembleConfig {
+    ///     total_evaluations: 500,
+    ///     evaluations_per_round: 100,
+    ///     ..EnsembleConfig::default()
+    /// })
+    /// .with_algorithm(GeneticAlgorithmArm::new("slow", RunnerConfig { mutate_prob: 0.1, ..config.clone() }, Solutions::random(5, -1.0..1.0, 2)))
+    /// .with_algorithm(GeneticAlgorithmArm::new("fast", RunnerConfig { mutate_prob: 0.9, ..config }, Solutions::random(5, -1.0..1.0, 2)))
+    /// .run(&function_to_optimize);
+    /// println!("{:?} found {:?}", result.best_algorithm, result.best);
+    /// ```
+    pub fn run<E>(mut self, evaluator: &E) -> EnsembleResult
+    where
+        E: Evaluator,
+    {
+        let mut traces = self
+            .algorithms
+            .iter()
+            .map(|algorithm| AlgorithmTrace {
+                name: algorithm.name(),
+                points: Vec::new(),
+            })
+            .collect::<Vec<AlgorithmTrace>>();
+        let mut bests: Vec<Option<(Solution, f64)>> = vec![None; self.algorithms.len()];
+        let mut spent = vec![0; self.algorithms.len()];
+        let mut rewards: Option<Vec<f64>> = None;
+        let mut total_spent = 0;
+        let mut round = 0;
+
+        while !self.algorithms.is_empty() && total_spent < self.config.total_evaluations {
+            let round_budget = self
+                .config
+                .evaluations_per_round
+                .max(1)
+                .min(self.config.total_evaluations - total_spent);
+            let allocation = self.allocate(rewards.as_deref(), round_budget);
+            let outcomes = executor::run_all(
+                &ScopedThreads,
+                self.algorithms.iter_mut().zip(allocation.iter()).map(
+                    |(algorithm, n_evaluations)| {
+                        move || {
+                            if *n_evaluations == 0 {
+                                return (None, 0);
+                            }
+                            let counting = CountingEvaluator::new(evaluator);
+                            let best = algorithm.advance(&counting, *n_evaluations);
+                            (best, counting.count())
+                        }
+                    },
+                ),
+            );
+
+            let mut round_rewards = Vec::with_capacity(outcomes.len());
+            for (idx, (best, n_spent)) in outcomes.into_iter().enumerate() {
+                let previous = bests[idx].as_ref().map(|(_, fitness)| *fitness);
+                if let Some((solution, fitness)) = best {
+                    if previous.is_none_or(|previous| fitness > previous) {
+                        bests[idx] = Some((solution, fitness));
+                    }
+                }
+                let current = bests[idx].as_ref().map(|(_, fitness)| *fitness);
+                round_rewards.push(match (previous, current) {
+                    (Some(previous), Some(current)) if n_spent > 0 => {
+                        (current - previous) / n_spent as f64
+                    }
+                    _ => 0.0,
+                });
+                spent[idx] += n_spent;
+                total_spent += n_spent;
+                traces[idx].points.push(TracePoint {
+                    round,
+                    allocated_evaluations: allocation[idx],
+                    evaluations: spent[idx],
+                    best_fitness: current,
+                });
+            }
+            if round_rewards.is_empty() || total_spent == 0 {
+                break;
+            }
+            rewards = Some(round_rewards);
+            round += 1;
+        }
+
+        let winner = bests
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, best)| best.as_ref().map(|(_, fitness)| (idx, *fitness)))
+            .max_by(|(_, fitness_a), (_, fitness_b)| fitness_a.total_cmp(fitness_b))
+            .map(|(idx, _)| idx);
+        EnsembleResult {
+            best: winner.and_then(|idx| bests[idx].clone()),
+            best_algorithm: winner.map(|idx| traces[idx].name.clone()),
+            traces,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::function::{Function, FunctionError};
+    use crate::test_objects;
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+
+    /// An algorithm that always returns the same solution and spends its whole allocation.
+    struct Constant {
+        fitness: f64,
+        step: f64,
+    }
+    impl Algorithm for Constant {
+        fn name(&self) -> String {
+            format!("constant-{}", self.fitness)
+        }
+        fn advance(
+            &mut self,
+            evaluator: &dyn Evaluator,
+            n_evaluations: usize,
+        ) -> Option<(Solution, f64)> {
+            evaluator.evaluate_chunk(&vec![Solution::new(vec![1.0, 1.0, 1.0]); n_evaluations]);
+            self.fitness += self.step;
+            Some((Solution::new(vec![self.fitness]), self.fitness))
+        }
+    }
+    #[test]
+    fn budget_shifts_to_best_progressing() {
+        let result = Ensemble::new(EnsembleConfig {
+            total_evaluations: 300,
+            evaluations_per_round: 100,
+            exploration: 0.2,
+        })
+        .with_algorithm(Constant {
+            fitness: 10.0,
+            step: 0.0,
+        })
+        .with_algorithm(Constant {
+            fitness: 0.0,
+            step: 1.0,
+        })
+        .run(&Function::new(test_objects::triple_multiplication()));
+        // Nobody has progressed during the first two rounds.
+        assert_eq!(result.traces[0].points[0].allocated_evaluations, 50);
+        assert_eq!(result.traces[0].points[1].allocated_evaluations, 50);
+        assert_eq!(result.traces[1].points[2].allocated_evaluations, 90);
+        assert_eq!(result.traces[0].points[2].allocated_evaluations, 10);
+        assert_eq!(result.best_algorithm, Some(String::from("constant-10")));
+        let total = result
+            .traces
+            .iter()
+            .map(|trace| trace.points.last().unwrap().evaluations)
+            .sum::<usize>();
+        assert_eq!(total, 300);
+    }
+    #[test]
+    fn genetic_algorithm_arm_improves() {
+        let function = Function::new(test_objects::triple_multiplication());
+        let result = Ensemble::new(EnsembleConfig {
+            total_evaluations: 400,
+            evaluations_per_round: 200,
+            exploration: 0.5,
+        })
+        .with_algorithm(GeneticAlgorithmArm::new(
+            "ga",
+            RunnerConfig {
+                size_generation: 5,
+                ..RunnerConfig::default()
+            },
+            Solutions::random(5, 1.0..2.0, 3),
+        ))
+        .run(&function);
+        let points = &result.traces[0].points;
+        assert_eq!(points.len(), 2);
+        assert!(points[1].best_fitness >= points[0].best_fitness);
+        assert!(result.best.is_some());
+    }
+    /// Evaluates `triple_multiplication` and records every solution it evaluates.
+    #[derive(Default)]
+    struct Recording {
+        evaluated: Mutex<Vec<Solution>>,
+    }
+    impl Evaluator for Recording {
+        fn evaluate_chunk(&self, chunk: &[Solution]) -> Vec<Result<f64, FunctionError>> {
+            self.evaluated.lock().unwrap().extend_from_slice(chunk);
+            Function::new(test_objects::triple_multiplication()).evaluate_chunk(chunk)
+        }
+    }
+    #[test]
+    fn genetic_algorithm_arm_evaluates_every_individual_once() {
+        let recording = Recording::default();
+        let mut arm = GeneticAlgorithmArm::new(
+            "ga",
+            RunnerConfig {
+                size_generation: 5,
+                seed: Some(7),
+                ..RunnerConfig::default()
+            },
+            Solutions::random(5, 1.0..2.0, 3),
+        );
+        arm.advance(&recording, 50);
+        arm.advance(&recording, 50);
+        let evaluated = recording.evaluated.into_inner().unwrap();
+        assert!(evaluated.len() >= 100);
+        // Parents that survive a generation keep their fitness.
+        let unique = evaluated.iter().collect::<HashSet<&Solution>>();
+        assert_eq!(unique.len(), evaluated.len());
+    }
+    #[test]
+    fn empty_ensemble() {
+        let result = Ensemble::new(EnsembleConfig::default())
+            .run(&Function::new(test_objects::triple_multiplication()));
+        assert!(result.best.is_none());
+        assert!(result.traces.is_empty());
+    }
+}
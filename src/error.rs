@@ -0,0 +1,135 @@
+use crate::checkpoint::CheckpointError;
+use crate::config::ConfigError;
+use crate::evaluation::EvaluationError;
+use crate::function::FunctionError;
+use crate::pipeline::OperatorError;
+use std::fmt;
+use std::io;
+
+/// Error returned by the fallible APIs of the crate, e.g. `Solution::try_crossover` or
+/// `Solutions::try_random_with_rng`. The more specific errors of the modules convert into it,
+/// so `?` works across them.
+#[derive(Debug)]
+pub enum Error {
+    /// A setting is unknown or has an invalid value.
+    Config(ConfigError),
+    /// The fitness of a solution could not be computed.
+    Evaluation(EvaluationError),
+    /// A file could not be written or read.
+    Io(io::Error),
+    /// A document, e.g. a checkpoint, could not be parsed or lacks a field.
+    Serialization(String),
+    /// A stage of the reproduction pipeline broke an invariant.
+    Operator(OperatorError),
+    /// The computation of a fitness took longer than allowed.
+    Timeout(String),
+    /// Two solutions that have to have the same number of arguments don't.
+    LengthMismatch {
+        /// The number of arguments of the first solution.
+        expected: usize,
+        /// The number of arguments of the second solution.
+        actual: usize,
+    },
+    /// Arguments should be drawn from an empty range.
+    EmptyRange,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Config(error) => write!(f, "{}", error),
+            Error::Evaluation(error) => write!(f, "{}", error),
+            Error::Io(error) => write!(f, "{}", error),
+            Error::Serialization(message) => write!(f, "{}", message),
+            Error::Operator(error) => write!(f, "{}", error),
+            Error::Timeout(message) => write!(f, "The operation timed out: {}", message),
+            Error::LengthMismatch { expected, actual } => write!(
+                f,
+                "Expected a solution with {} arguments, but got {}",
+                expected, actual
+            ),
+            Error::EmptyRange => write!(f, "Cannot draw arguments from an empty range"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ConfigError> for Error {
+    fn from(error: ConfigError) -> Self {
+        Error::Config(error)
+    }
+}
+
+impl From<EvaluationError> for Error {
+    fn from(error: EvaluationError) -> Self {
+        Error::Evaluation(error)
+    }
+}
+
+/// Timeouts become `Error::Timeout`, the other failures `Error::Evaluation`.
+impl From<FunctionError> for Error {
+    fn from(error: FunctionError) -> Self {
+        match error {
+            FunctionError::Timeout(message) => Error::Timeout(message),
+            error => Error::Evaluation(EvaluationError::Function(error)),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<OperatorError> for Error {
+    fn from(error: OperatorError) -> Self {
+        Error::Operator(error)
+    }
+}
+
+impl From<CheckpointError> for Error {
+    fn from(error: CheckpointError) -> Self {
+        match error {
+            CheckpointError::Io(error) => Error::Io(error),
+            CheckpointError::Config(error) => Error::Config(error),
+            error => Error::Serialization(error.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::function::Function;
+    use crate::solution::Solution;
+
+    #[test]
+    fn errors_of_the_modules_convert() {
+        assert!(matches!(
+            Error::from(FunctionError::Timeout("too slow".to_string())),
+            Error::Timeout(message) if message == "too slow"
+        ));
+        let function = Function::new(|_| Err(FunctionError::Timeout("too slow".to_string())));
+        assert!(matches!(
+            Solution::new(vec![1.0]).try_fitness(&function),
+            Err(Error::Timeout(message)) if message == "too slow"
+        ));
+        assert!(matches!(
+            Error::from(FunctionError::ExternalEvaluation("down".to_string())),
+            Error::Evaluation(EvaluationError::Function(_))
+        ));
+        let error = Error::from(CheckpointError::InvalidField("population".to_string()));
+        assert_eq!(
+            error.to_string(),
+            "The checkpoint field `population` is missing or invalid"
+        );
+        assert!(matches!(
+            Error::from(CheckpointError::Io(io::Error::from(
+                io::ErrorKind::NotFound
+            ))),
+            Error::Io(_)
+        ));
+    }
+}
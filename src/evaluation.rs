@@ -0,0 +1,427 @@
+use crate::function::{FunctionError, MultiFidelityFunction};
+use crate::json::Json;
+use crate::solution::Solution;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Computes the fitness of solutions. The `Runner` hands the individuals of a generation to
+/// the evaluator in chunks of `RunnerConfig::evaluation_chunk_size`, so an evaluator that needs
+/// to acquire resources (a process, a connection, a license seat) can do so once per chunk.
+pub trait Evaluator: Sync {
+    /// Compute the fitness of every solution in the chunk, in the same order. The `Runner`
+    /// treats every solution of a chunk as failed if the evaluator doesn't return exactly one
+    /// result per solution, see `RunnerConfig::failure_penalty`.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - The solutions that should be evaluated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::evaluation::Evaluator;
+    /// use genetic_algorithm_fn::function;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let function_to_optimize = function::Function::new(|x| Ok(x.iter().sum()));
+    /// assert_eq!(
+    ///     function_to_optimize.evaluate_chunk(&[Solution::new(vec![1.0, 2.0])]),
+    ///     vec![Ok(3.0)]
+    /// );
+    /// ```
+    fn evaluate_chunk(&self, chunk: &[Solution]) -> Vec<Result<f64, FunctionError>>;
+    /// Compute the fitness of every solution in the chunk at a fidelity in `(0, 1]`. Used by
+    /// successive halving to evaluate candidates cheaply first. Evaluators without a notion of
+    /// fidelity evaluate exactly.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - The solutions that should be evaluated.
+    /// * `fidelity` - How accurate (and expensive) the evaluation should be.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::evaluation::Evaluator;
+    /// use genetic_algorithm_fn::function;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let function_to_optimize =
+    ///     function::Function::with_fidelity(|x, fidelity| Ok(x.iter().sum::<f64>() * fidelity));
+    /// assert_eq!(
+    ///     function_to_optimize.evaluate_chunk_at_fidelity(&[Solution::new(vec![1.0, 3.0])], 0.5),
+    ///     vec![Ok(2.0)]
+    /// );
+    /// ```
+    fn evaluate_chunk_at_fidelity(
+        &self,
+        chunk: &[Solution],
+        _fidelity: f64,
+    ) -> Vec<Result<f64, FunctionError>> {
+        self.evaluate_chunk(chunk)
+    }
+}
+
+/// A `Function` (or any other `MultiFidelityFunction`) evaluates every solution of the chunk
+/// one after the other.
+impl<F> Evaluator for F
+where
+    F: MultiFidelityFunction + Sync,
+{
+    fn evaluate_chunk(&self, chunk: &[Solution]) -> Vec<Result<f64, FunctionError>> {
+        self.evaluate_chunk_at_fidelity(chunk, 1.0)
+    }
+    fn evaluate_chunk_at_fidelity(
+        &self,
+        chunk: &[Solution],
+        fidelity: f64,
+    ) -> Vec<Result<f64, FunctionError>> {
+        chunk
+            .iter()
+            .map(|solution| self.evaluate_at_fidelity(solution.get_arguments(), fidelity))
+            .collect()
+    }
+}
+
+/// Wraps an evaluator and counts how many solutions it evaluated, also across threads.
+#[derive(Debug)]
+pub struct CountingEvaluator<'a, E: ?Sized> {
+    evaluator: &'a E,
+    count: AtomicUsize,
+}
+
+impl<'a, E> CountingEvaluator<'a, E>
+where
+    E: Evaluator + ?Sized,
+{
+    /// Start counting the evaluations of `evaluator`.
+    ///
+    /// # Arguments
+    ///
+    /// * `evaluator` - The evaluator whose evaluations should be counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::evaluation::{CountingEvaluator, Evaluator};
+    /// use genetic_algorithm_fn::function;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let function_to_optimize = function::Function::new(|x| Ok(x.iter().sum()));
+    /// let counting = CountingEvaluator::new(&function_to_optimize);
+    /// counting.evaluate_chunk(&[Solution::new(vec![1.0]), Solution::new(vec![2.0])]);
+    /// assert_eq!(counting.count(), 2);
+    /// ```
+    pub fn new(evaluator: &'a E) -> Self {
+        CountingEvaluator {
+            evaluator,
+            count: AtomicUsize::new(0),
+        }
+    }
+    /// Return how many solutions have been evaluated so far.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::evaluation::CountingEvaluator;
+    /// use genetic_algorithm_fn::function;
+    ///
+    /// let function_to_optimize = function::Function::new(|x| Ok(x.iter().sum()));
+    /// assert_eq!(CountingEvaluator::new(&function_to_optimize).count(), 0);
+    /// ```
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+}
+
+impl<E> Evaluator for CountingEvaluator<'_, E>
+where
+    E: Evaluator + ?Sized,
+{
+    fn evaluate_chunk(&self, chunk: &[Solution]) -> Vec<Result<f64, FunctionError>> {
+        self.count.fetch_add(chunk.len(), Ordering::Relaxed);
+        self.evaluator.evaluate_chunk(chunk)
+    }
+    fn evaluate_chunk_at_fidelity(
+        &self,
+        chunk: &[Solution],
+        fidelity: f64,
+    ) -> Vec<Result<f64, FunctionError>> {
+        self.count.fetch_add(chunk.len(), Ordering::Relaxed);
+        self.evaluator.evaluate_chunk_at_fidelity(chunk, fidelity)
+    }
+}
+
+/// Why an individual didn't get a usable fitness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FailureKind {
+    /// The objective was given the wrong number of arguments.
+    WrongArity,
+    /// The objective returned `NaN` or an infinite value.
+    NonFinite,
+    /// The evaluation took longer than allowed.
+    Timeout,
+    /// The evaluator panicked.
+    Panic,
+    /// Any other error, e.g. a failed external evaluation.
+    Other,
+}
+
+impl FailureKind {
+    /// Categorize an error returned by an evaluator.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The error of the evaluation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::evaluation::FailureKind;
+    /// use genetic_algorithm_fn::function::FunctionError;
+    ///
+    /// assert_eq!(
+    ///     FailureKind::of(&FunctionError::Timeout("after 30s".to_string())),
+    ///     FailureKind::Timeout
+    /// );
+    /// ```
+    pub fn of(error: &FunctionError) -> Self {
+        match error {
+            FunctionError::WrongNumberOfEntries { .. } => FailureKind::WrongArity,
+            FunctionError::Timeout(_) => FailureKind::Timeout,
+            FunctionError::ExternalEvaluation(_) => FailureKind::Other,
+        }
+    }
+}
+
+/// Why the evaluation of a particular individual failed, passed to the callback of
+/// `Runner::with_on_evaluation_error`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvaluationError {
+    /// The evaluator returned an error.
+    Function(FunctionError),
+    /// The evaluator returned `NaN` or an infinite value.
+    NonFinite(f64),
+    /// The evaluator panicked while evaluating the chunk containing the individual, with the
+    /// message of the panic if it had one.
+    Panic(Option<String>),
+}
+
+impl EvaluationError {
+    /// Categorize the error like the counters of `EvaluationOutcomes`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::evaluation::{EvaluationError, FailureKind};
+    ///
+    /// assert_eq!(EvaluationError::NonFinite(f64::NAN).kind(), FailureKind::NonFinite);
+    /// ```
+    pub fn kind(&self) -> FailureKind {
+        match self {
+            EvaluationError::Function(error) => FailureKind::of(error),
+            EvaluationError::NonFinite(_) => FailureKind::NonFinite,
+            EvaluationError::Panic(_) => FailureKind::Panic,
+        }
+    }
+}
+
+impl fmt::Display for EvaluationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvaluationError::Function(error) => write!(f, "{}", error),
+            EvaluationError::NonFinite(value) => write!(f, "The fitness {} is not finite", value),
+            EvaluationError::Panic(Some(message)) => {
+                write!(f, "The evaluation panicked: {}", message)
+            }
+            EvaluationError::Panic(None) => write!(f, "The evaluation panicked"),
+        }
+    }
+}
+
+/// How many evaluations succeeded and how many failed for which reason.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EvaluationOutcomes {
+    /// Evaluations that returned a finite fitness.
+    pub n_ok: usize,
+    /// Evaluations given the wrong number of arguments.
+    pub n_wrong_arity: usize,
+    /// Evaluations that returned `NaN` or an infinite value.
+    pub n_non_finite: usize,
+    /// Evaluations that timed out.
+    pub n_timeout: usize,
+    /// Evaluations whose evaluator panicked.
+    pub n_panicked: usize,
+    /// Evaluations that failed for any other reason.
+    pub n_other: usize,
+}
+
+impl EvaluationOutcomes {
+    /// Count the outcome of an evaluation.
+    ///
+    /// # Arguments
+    ///
+    /// * `failure` - Why the evaluation failed, `None` if it succeeded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::evaluation::{EvaluationOutcomes, FailureKind};
+    ///
+    /// let mut outcomes = EvaluationOutcomes::default();
+    /// outcomes.record(None);
+    /// outcomes.record(Some(FailureKind::Panic));
+    /// assert_eq!((outcomes.n_ok, outcomes.n_panicked), (1, 1));
+    /// ```
+    pub fn record(&mut self, failure: Option<FailureKind>) {
+        *match failure {
+            None => &mut self.n_ok,
+            Some(FailureKind::WrongArity) => &mut self.n_wrong_arity,
+            Some(FailureKind::NonFinite) => &mut self.n_non_finite,
+            Some(FailureKind::Timeout) => &mut self.n_timeout,
+            Some(FailureKind::Panic) => &mut self.n_panicked,
+            Some(FailureKind::Other) => &mut self.n_other,
+        } += 1;
+    }
+    /// Add the counts of other evaluations, e.g. of another generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The counts to add.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::evaluation::EvaluationOutcomes;
+    ///
+    /// let mut outcomes = EvaluationOutcomes { n_ok: 2, ..EvaluationOutcomes::default() };
+    /// outcomes.merge(&EvaluationOutcomes { n_timeout: 1, ..EvaluationOutcomes::default() });
+    /// assert_eq!(outcomes.n_evaluations(), 3);
+    /// ```
+    pub fn merge(&mut self, other: &EvaluationOutcomes) {
+        self.n_ok += other.n_ok;
+        self.n_wrong_arity += other.n_wrong_arity;
+        self.n_non_finite += other.n_non_finite;
+        self.n_timeout += other.n_timeout;
+        self.n_panicked += other.n_panicked;
+        self.n_other += other.n_other;
+    }
+    /// The number of failed evaluations, for any reason.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::evaluation::EvaluationOutcomes;
+    ///
+    /// let outcomes = EvaluationOutcomes { n_ok: 8, n_non_finite: 2, ..EvaluationOutcomes::default() };
+    /// assert_eq!(outcomes.n_failed(), 2);
+    /// ```
+    pub fn n_failed(&self) -> usize {
+        self.n_wrong_arity + self.n_non_finite + self.n_timeout + self.n_panicked + self.n_other
+    }
+    /// The number of evaluations, successful or not.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::evaluation::EvaluationOutcomes;
+    ///
+    /// let outcomes = EvaluationOutcomes { n_ok: 8, n_panicked: 2, ..EvaluationOutcomes::default() };
+    /// assert_eq!(outcomes.n_evaluations(), 10);
+    /// ```
+    pub fn n_evaluations(&self) -> usize {
+        self.n_ok + self.n_failed()
+    }
+    /// The share of evaluations that succeeded, `None` without evaluations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::evaluation::EvaluationOutcomes;
+    ///
+    /// let outcomes = EvaluationOutcomes { n_ok: 3, n_timeout: 1, ..EvaluationOutcomes::default() };
+    /// assert_eq!(outcomes.success_rate(), Some(0.75));
+    /// assert_eq!(EvaluationOutcomes::default().success_rate(), None);
+    /// ```
+    pub fn success_rate(&self) -> Option<f64> {
+        let n_evaluations = self.n_evaluations();
+        (n_evaluations > 0).then(|| self.n_ok as f64 / n_evaluations as f64)
+    }
+    /// Serialize the counts.
+    pub(crate) fn to_json(self) -> Json {
+        Json::Object(vec![
+            ("ok".to_string(), Json::from(self.n_ok)),
+            ("wrong_arity".to_string(), Json::from(self.n_wrong_arity)),
+            ("non_finite".to_string(), Json::from(self.n_non_finite)),
+            ("timeout".to_string(), Json::from(self.n_timeout)),
+            ("panicked".to_string(), Json::from(self.n_panicked)),
+            ("other".to_string(), Json::from(self.n_other)),
+        ])
+    }
+    /// Restore the counts written by `to_json`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The JSON object.
+    pub(crate) fn from_json(value: &Json) -> Option<Self> {
+        let count = |name| value.get(name)?.as_usize();
+        Some(EvaluationOutcomes {
+            n_ok: count("ok")?,
+            n_wrong_arity: count("wrong_arity")?,
+            n_non_finite: count("non_finite")?,
+            n_timeout: count("timeout")?,
+            n_panicked: count("panicked")?,
+            n_other: count("other")?,
+        })
+    }
+}
+
+impl fmt::Display for EvaluationOutcomes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} ok, {} wrong arity, {} non-finite, {} timed out, {} panicked, {} other failures",
+            self.n_ok,
+            self.n_wrong_arity,
+            self.n_non_finite,
+            self.n_timeout,
+            self.n_panicked,
+            self.n_other
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::function::Function;
+    use crate::test_objects;
+
+    #[test]
+    fn function_evaluates_in_order() {
+        assert_eq!(
+            Function::new(test_objects::triple_multiplication()).evaluate_chunk(&[
+                Solution::new(vec![1.0, 2.0, 3.0]),
+                Solution::new(vec![1.0, 2.0]),
+            ]),
+            vec![
+                Ok(6.0),
+                Err(FunctionError::WrongNumberOfEntries {
+                    expected_number_of_entries: 3,
+                    actual_number_of_entries: 2
+                })
+            ]
+        );
+    }
+    #[test]
+    fn counting_evaluator_counts_solutions() {
+        let function = Function::new(test_objects::triple_multiplication());
+        let counting = CountingEvaluator::new(&function);
+        counting.evaluate_chunk(&[Solution::new(vec![1.0, 2.0, 3.0])]);
+        counting.evaluate_chunk(&[
+            Solution::new(vec![1.0, 2.0, 3.0]),
+            Solution::new(vec![3.0, 2.0, 1.0]),
+        ]);
+        assert_eq!(counting.count(), 3);
+    }
+}
@@ -0,0 +1,134 @@
+use crossbeam_utils::thread;
+use std::fmt;
+use std::panic;
+
+/// A unit of work handed to an `Executor`, it may borrow from the caller.
+pub(crate) type Task<'a> = Box<dyn FnOnce() + Send + 'a>;
+
+/// Runs independent tasks, e.g. the islands of a run or the algorithms of an ensemble, and
+/// returns once all of them finished. Keeping the parallelism behind this trait lets tests run
+/// the logic around it, i.e. merging islands and the shared hall of fame, on the deterministic
+/// `Sequential` executor and compare it with the threaded one.
+pub(crate) trait Executor: fmt::Debug + Send + Sync {
+    /// Run all tasks and return once all of them finished. A panic of a task is resumed
+    /// after the other tasks finished.
+    ///
+    /// # Arguments
+    ///
+    /// * `tasks` - The tasks to run, in no particular order.
+    fn execute<'a>(&self, tasks: Vec<Task<'a>>);
+}
+
+/// Runs every task on a scoped thread of its own, a single task on the calling thread.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ScopedThreads;
+
+impl Executor for ScopedThreads {
+    fn execute<'a>(&self, tasks: Vec<Task<'a>>) {
+        if tasks.len() < 2 {
+            return Sequential.execute(tasks);
+        }
+        let panics = thread::scope(|s| {
+            let handles = tasks
+                .into_iter()
+                .map(|task| s.spawn(move |_| task()))
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .filter_map(|handle| handle.join().err())
+                .collect::<Vec<_>>()
+        })
+        .expect("All threads of the scope were joined");
+        if let Some(payload) = panics.into_iter().next() {
+            panic::resume_unwind(payload);
+        }
+    }
+}
+
+/// Runs the tasks one after another on the calling thread, in order.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Sequential;
+
+impl Executor for Sequential {
+    fn execute<'a>(&self, tasks: Vec<Task<'a>>) {
+        let panics = tasks
+            .into_iter()
+            .filter_map(|task| panic::catch_unwind(panic::AssertUnwindSafe(task)).err())
+            .collect::<Vec<_>>();
+        if let Some(payload) = panics.into_iter().next() {
+            panic::resume_unwind(payload);
+        }
+    }
+}
+
+/// Run `jobs` on `executor` and return their results in the order of `jobs`.
+///
+/// # Arguments
+///
+/// * `executor` - Runs the jobs.
+/// * `jobs` - The jobs to run.
+pub(crate) fn run_all<T, F, J>(executor: &dyn Executor, jobs: J) -> Vec<T>
+where
+    T: Send,
+    F: FnOnce() -> T + Send,
+    J: IntoIterator<Item = F>,
+{
+    let jobs = jobs.into_iter().collect::<Vec<F>>();
+    let mut results = jobs.iter().map(|_| None).collect::<Vec<Option<T>>>();
+    let tasks = jobs
+        .into_iter()
+        .zip(results.iter_mut())
+        .map(|(job, result)| Box::new(move || *result = Some(job())) as Task)
+        .collect();
+    executor.execute(tasks);
+    results
+        .into_iter()
+        .map(|result| result.expect("Every task ran"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn executors_return_the_results_in_order() {
+        let n_runs = AtomicUsize::new(0);
+        let inputs = [3, 1, 4, 1, 5];
+        for executor in [&ScopedThreads as &dyn Executor, &Sequential] {
+            let results = run_all(
+                executor,
+                inputs.iter().map(|input| {
+                    let n_runs = &n_runs;
+                    move || {
+                        n_runs.fetch_add(1, Ordering::SeqCst);
+                        input * 2
+                    }
+                }),
+            );
+            assert_eq!(results, vec![6, 2, 8, 2, 10]);
+        }
+        assert_eq!(n_runs.load(Ordering::SeqCst), 10);
+        // A panicking task doesn't keep the others from finishing.
+        for executor in [&ScopedThreads as &dyn Executor, &Sequential] {
+            let n_finished = AtomicUsize::new(0);
+            let outcome = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                run_all(
+                    executor,
+                    (0..3).map(|index| {
+                        let n_finished = &n_finished;
+                        move || {
+                            if index == 0 {
+                                panic!("task {} failed", index);
+                            }
+                            n_finished.fetch_add(1, Ordering::SeqCst);
+                        }
+                    }),
+                )
+            }));
+            assert!(outcome.is_err());
+            assert_eq!(n_finished.load(Ordering::SeqCst), 2);
+        }
+    }
+}
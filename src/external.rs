@@ -0,0 +1,1023 @@
+use crate::evaluation::Evaluator;
+use crate::function::FunctionError;
+use crate::json::Json;
+use crate::solution::Solution;
+use std::io::{BufRead, Read, Write};
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The `RunnerConfig::failure_penalty` of `ga-fn serve` unless the config sets one. It is worse
+/// than any fitness, so a candidate whose entry of a batch failed is merely dropped by the
+/// selection instead of ending the run.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::external;
+/// use genetic_algorithm_fn::runner::RunnerConfig;
+///
+/// let config = RunnerConfig {
+///     failure_penalty: Some(external::FAILURE_PENALTY),
+///     ..RunnerConfig::default()
+/// };
+/// ```
+pub const FAILURE_PENALTY: f64 = f64::MIN;
+
+/// Write a batch of candidates as a single JSON line.
+///
+/// # Arguments
+///
+/// * `id` - The number of the batch, echoed by the evaluator.
+/// * `chunk` - The candidates to evaluate.
+/// * `fidelity` - The fidelity the candidates should be evaluated at.
+pub(crate) fn batch_to_json(id: usize, chunk: &[Solution], fidelity: f64) -> Json {
+    Json::Object(vec![
+        ("id".to_string(), Json::from(id)),
+        ("fidelity".to_string(), Json::from(fidelity)),
+        (
+            "candidates".to_string(),
+            Json::Array(
+                chunk
+                    .iter()
+                    .map(|solution| {
+                        Json::Array(
+                            solution
+                                .get_arguments()
+                                .into_iter()
+                                .map(Json::Number)
+                                .collect(),
+                        )
+                    })
+                    .collect(),
+            ),
+        ),
+    ])
+}
+
+/// Read the fitnesses of a batch, either a bare array `[1.0, 2.0]` or an object
+/// `{"id": 0, "fitnesses": [1.0, 2.0]}`. An entry that is no number, e.g.
+/// `{"error": "diverged"}`, fails only its candidate.
+///
+/// # Arguments
+///
+/// * `response` - The response to parse.
+/// * `id` - The number of the batch the response belongs to.
+/// * `n_candidates` - How many candidates the batch contained.
+pub(crate) fn fitnesses_from_json(
+    response: &str,
+    id: usize,
+    n_candidates: usize,
+) -> Result<Vec<Result<f64, FunctionError>>, FunctionError> {
+    let external = |message: String| FunctionError::ExternalEvaluation(message);
+    let response = Json::parse(response).map_err(|error| external(error.to_string()))?;
+    if let Some(response_id) = response.get("id") {
+        if response_id.as_usize() != Some(id) {
+            return Err(external(format!(
+                "Expected the response to batch {}, got {}",
+                id, response_id
+            )));
+        }
+    }
+    let fitnesses = match &response {
+        Json::Array(fitnesses) => fitnesses,
+        response => response
+            .get("fitnesses")
+            .and_then(Json::as_array)
+            .ok_or_else(|| external("The response contains no `fitnesses`".to_string()))?,
+    };
+    if fitnesses.len() != n_candidates {
+        return Err(external(format!(
+            "Expected {} fitnesses, got {}",
+            n_candidates,
+            fitnesses.len()
+        )));
+    }
+    Ok(fitnesses
+        .iter()
+        .map(|fitness| {
+            fitness
+                .as_f64()
+                .ok_or_else(|| external(format!("`{}` is no fitness", fitness)))
+        })
+        .collect())
+}
+
+/// Evaluates candidates in another process that speaks JSON lines: every chunk is written as
+/// one line `{"id": 0, "fidelity": 1.0, "candidates": [[1.0, 2.0], ...]}` and the fitnesses
+/// are read back as one line `{"id": 0, "fitnesses": [3.0, ...]}` (or just `[3.0, ...]`).
+/// This lets evaluation scripts in any language participate without bindings.
+#[derive(Debug)]
+pub struct JsonLinesEvaluator<R, W> {
+    /// The channel to the evaluating process and the number of the next batch. Shared by
+    /// all islands, so batches are sent one after the other.
+    channel: Mutex<(R, W, usize)>,
+}
+
+impl<R, W> JsonLinesEvaluator<R, W>
+where
+    R: BufRead + Send,
+    W: Write + Send,
+{
+    /// Create an evaluator that writes batches to `writer` and reads fitnesses from `reader`.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - Where the fitnesses are read from, e.g. `BufReader::new(io::stdin())`.
+    /// * `writer` - Where the batches are written to, e.g. `io::stdout()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::evaluation::Evaluator;
+    /// use genetic_algorithm_fn::external::JsonLinesEvaluator;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let responses = "{\"id\": 0, \"fitnesses\": [3.0]}\n".as_bytes();
+    /// let evaluator = JsonLinesEvaluator::new(responses, Vec::new());
+    /// assert_eq!(
+    ///     evaluator.evaluate_chunk(&[Solution::new(vec![1.0, 2.0])]),
+    ///     vec![Ok(3.0)]
+    /// );
+    /// let (_, requests) = evaluator.into_inner();
+    /// assert_eq!(
+    ///     String::from_utf8(requests).unwrap(),
+    ///     "{\"id\":0,\"fidelity\":1,\"candidates\":[[1,2]]}\n"
+    /// );
+    /// ```
+    pub fn new(reader: R, writer: W) -> Self {
+        JsonLinesEvaluator {
+            channel: Mutex::new((reader, writer, 0)),
+        }
+    }
+    /// Return the reader and the writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::external::JsonLinesEvaluator;
+    ///
+    /// let (reader, writer) = JsonLinesEvaluator::new("".as_bytes(), Vec::new()).into_inner();
+    /// assert!(writer.is_empty());
+    /// ```
+    pub fn into_inner(self) -> (R, W) {
+        let (reader, writer, _) = self
+            .channel
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        (reader, writer)
+    }
+    /// Tell the evaluating process that the run is over by writing
+    /// `{"done": true, "best": {"arguments": [...], "fitness": ...}}` (`"best": null` if no
+    /// candidate was evaluated), so it can shut down.
+    ///
+    /// # Arguments
+    ///
+    /// * `best` - The fittest candidate of the run and its fitness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::external::JsonLinesEvaluator;
+    ///
+    /// let evaluator = JsonLinesEvaluator::new("".as_bytes(), Vec::new());
+    /// evaluator.finish(None).unwrap();
+    /// let (_, requests) = evaluator.into_inner();
+    /// assert_eq!(String::from_utf8(requests).unwrap(), "{\"done\":true,\"best\":null}\n");
+    /// ```
+    pub fn finish(&self, best: Option<&(Solution, f64)>) -> std::io::Result<()> {
+        let best = match best {
+            Some((solution, fitness)) => Json::Object(vec![
+                (
+                    "arguments".to_string(),
+                    Json::Array(
+                        solution
+                            .get_arguments()
+                            .into_iter()
+                            .map(Json::Number)
+                            .collect(),
+                    ),
+                ),
+                ("fitness".to_string(), Json::from(*fitness)),
+            ]),
+            None => Json::Null,
+        };
+        let mut channel = self
+            .channel
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let writer = &mut channel.1;
+        writeln!(
+            writer,
+            "{}",
+            Json::Object(vec![
+                ("done".to_string(), Json::from(true)),
+                ("best".to_string(), best),
+            ])
+        )?;
+        writer.flush()
+    }
+    /// Send a batch and wait for its fitnesses.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - The candidates to evaluate.
+    /// * `fidelity` - The fidelity the candidates should be evaluated at.
+    fn exchange(
+        &self,
+        chunk: &[Solution],
+        fidelity: f64,
+    ) -> Result<Vec<Result<f64, FunctionError>>, FunctionError> {
+        let io_error = |error: std::io::Error| FunctionError::ExternalEvaluation(error.to_string());
+        let mut channel = self
+            .channel
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let (reader, writer, next_id) = &mut *channel;
+        let id = *next_id;
+        *next_id += 1;
+        writeln!(writer, "{}", batch_to_json(id, chunk, fidelity)).map_err(io_error)?;
+        writer.flush().map_err(io_error)?;
+        let mut response = String::new();
+        if reader.read_line(&mut response).map_err(io_error)? == 0 {
+            return Err(FunctionError::ExternalEvaluation(
+                "The evaluator closed its output".to_string(),
+            ));
+        }
+        fitnesses_from_json(&response, id, chunk.len())
+    }
+}
+
+impl<R, W> Evaluator for JsonLinesEvaluator<R, W>
+where
+    R: BufRead + Send,
+    W: Write + Send,
+{
+    fn evaluate_chunk(&self, chunk: &[Solution]) -> Vec<Result<f64, FunctionError>> {
+        self.evaluate_chunk_at_fidelity(chunk, 1.0)
+    }
+    fn evaluate_chunk_at_fidelity(
+        &self,
+        chunk: &[Solution],
+        fidelity: f64,
+    ) -> Vec<Result<f64, FunctionError>> {
+        self.exchange(chunk, fidelity)
+            .unwrap_or_else(|error| chunk.iter().map(|_| Err(error.clone())).collect())
+    }
+}
+
+/// The parts of an `http://host:port/path` URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Url {
+    /// The host, IPv6 addresses without their brackets.
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl Url {
+    /// The host and port as sent in the `Host` header, IPv6 addresses in brackets.
+    fn authority(&self) -> String {
+        match self.host.contains(':') {
+            true => format!("[{}]:{}", self.host, self.port),
+            false => format!("{}:{}", self.host, self.port),
+        }
+    }
+}
+
+/// Split a plain HTTP URL into host, port and path. IPv6 hosts are written in brackets, e.g.
+/// `http://[::1]:8080/evaluate`.
+///
+/// # Arguments
+///
+/// * `url` - The URL, e.g. `http://localhost:8080/evaluate`.
+fn parse_url(url: &str) -> Result<Url, FunctionError> {
+    let invalid = |message: &str| {
+        FunctionError::ExternalEvaluation(format!("Invalid URL `{}`: {}", url, message))
+    };
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| invalid("only `http://` is supported"))?;
+    let (authority, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+    let (host, port) = match authority.strip_prefix('[') {
+        Some(bracketed) => {
+            let (host, after_host) = bracketed
+                .split_once(']')
+                .ok_or_else(|| invalid("the IPv6 address is missing its `]`"))?;
+            let port = match after_host {
+                "" => None,
+                after_host => Some(
+                    after_host
+                        .strip_prefix(':')
+                        .ok_or_else(|| invalid("the IPv6 address isn't followed by a port"))?,
+                ),
+            };
+            (host, port)
+        }
+        None => match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        },
+    };
+    let port = match port {
+        Some(port) => port.parse().map_err(|_| invalid("the port is no number"))?,
+        None => 80,
+    };
+    if host.is_empty() {
+        return Err(invalid("the host is missing"));
+    }
+    Ok(Url {
+        host: host.to_string(),
+        port,
+        path: path.to_string(),
+    })
+}
+
+/// Decode a body sent with `Transfer-Encoding: chunked`.
+///
+/// # Arguments
+///
+/// * `body` - The raw body.
+fn decode_chunked(mut body: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+    loop {
+        let line_end = body.windows(2).position(|window| window == b"\r\n")?;
+        let size = std::str::from_utf8(&body[..line_end]).ok()?;
+        let size = usize::from_str_radix(size.split(';').next()?.trim(), 16).ok()?;
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Some(decoded);
+        }
+        decoded.extend_from_slice(body.get(..size)?);
+        body = body.get(size + 2..)?;
+    }
+}
+
+/// Read the delay in seconds of a `Retry-After` header. Dates are not supported.
+///
+/// # Arguments
+///
+/// * `head` - The lowercased status line and headers of a response.
+fn retry_after(head: &str) -> Option<Duration> {
+    head.lines()
+        .find_map(|line| line.strip_prefix("retry-after:"))
+        .and_then(|seconds| seconds.trim().parse().ok())
+        .map(Duration::from_secs)
+}
+
+/// Why a request failed, decides whether it is retried.
+#[derive(Debug)]
+enum RequestError {
+    /// The service could not be reached or is temporarily unavailable, worth retrying. The
+    /// service may ask to wait for some time with a `Retry-After` header.
+    Transient(String, Option<Duration>),
+    /// Connecting, sending or receiving took longer than the timeout, worth retrying.
+    Timeout(String),
+    /// The service rejected the request, retrying won't help.
+    Permanent(String),
+}
+
+/// A counting semaphore limiting how many requests are in flight at once.
+#[derive(Debug)]
+struct Semaphore {
+    available: Mutex<usize>,
+    released: Condvar,
+}
+
+impl Semaphore {
+    fn acquire(&self) -> SemaphoreGuard<'_> {
+        let mut available = self
+            .available
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        while *available == 0 {
+            available = self
+                .released
+                .wait(available)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+        *available -= 1;
+        SemaphoreGuard { semaphore: self }
+    }
+}
+
+/// Spaces requests evenly so that at most a given number of requests is started per second.
+#[derive(Debug)]
+struct RateLimiter {
+    /// The time between the starts of two requests.
+    interval: Duration,
+    /// When the next request may be started.
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// Block until the next request may be started.
+    fn wait(&self) {
+        let now = Instant::now();
+        let start = {
+            let mut next = self
+                .next
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let start = (*next).max(now);
+            *next = start + self.interval;
+            start
+        };
+        thread::sleep(start - now);
+    }
+}
+
+/// Releases its permit when dropped.
+struct SemaphoreGuard<'a> {
+    semaphore: &'a Semaphore,
+}
+
+impl Drop for SemaphoreGuard<'_> {
+    fn drop(&mut self) {
+        *self
+            .semaphore
+            .available
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) += 1;
+        self.semaphore.released.notify_one();
+    }
+}
+
+/// Evaluates candidates by POSTing every chunk to an HTTP endpoint, e.g. a model served by ML
+/// infrastructure. The request body is the batch the `JsonLinesEvaluator` writes, the
+/// response body is read like its responses. Only plain `http://` is supported, TLS can be
+/// terminated by a local proxy. Once its retries are exhausted, every candidate of the batch
+/// fails, so runs against an endpoint that may fail need a `RunnerConfig::failure_penalty`,
+/// e.g. `FAILURE_PENALTY`.
+#[derive(Debug)]
+pub struct HttpEvaluator {
+    url: Url,
+    /// How often a failed request is retried.
+    max_retries: usize,
+    /// How long to wait before the first retry, the wait doubles with every further retry.
+    initial_backoff: Duration,
+    /// The longest wait between two attempts.
+    max_backoff: Duration,
+    /// Limits how many requests are started per second.
+    rate_limit: Option<RateLimiter>,
+    /// The timeout of connecting, writing and reading.
+    timeout: Duration,
+    /// Limits the concurrent requests of all islands.
+    in_flight: Semaphore,
+    /// The number of the next batch.
+    next_id: AtomicUsize,
+}
+
+impl HttpEvaluator {
+    /// Create an evaluator for the endpoint at `url`. By default, failed requests are retried
+    /// twice, at most four requests are in flight, the number of requests per second is not
+    /// limited and requests time out after 30 seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The endpoint, e.g. `http://localhost:8080/evaluate`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::external::HttpEvaluator;
+    ///
+    /// assert!(HttpEvaluator::new("http://localhost:8080/evaluate").is_ok());
+    /// assert!(HttpEvaluator::new("https://localhost/evaluate").is_err());
+    /// ```
+    pub fn new(url: &str) -> Result<Self, FunctionError> {
+        Ok(HttpEvaluator {
+            url: parse_url(url)?,
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(30),
+            rate_limit: None,
+            timeout: Duration::from_secs(30),
+            in_flight: Semaphore {
+                available: Mutex::new(4),
+                released: Condvar::new(),
+            },
+            next_id: AtomicUsize::new(0),
+        })
+    }
+    /// Set how often a request is retried after a connection error, a timeout or a `429` or
+    /// `5xx` response. The first retry waits `initial_backoff`, every further retry waits
+    /// twice as long as the one before, up to the maximal backoff. A longer `Retry-After`
+    /// the service responds with is respected.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_retries` - How often a failed request is retried.
+    /// * `initial_backoff` - How long to wait before the first retry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::external::HttpEvaluator;
+    /// use std::time::Duration;
+    ///
+    /// let evaluator = HttpEvaluator::new("http://localhost:8080/evaluate")
+    ///     .unwrap()
+    ///     .with_retries(5, Duration::from_secs(1));
+    /// ```
+    pub fn with_retries(mut self, max_retries: usize, initial_backoff: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.initial_backoff = initial_backoff;
+        self
+    }
+    /// Set the longest time to wait between two attempts, including waits requested with
+    /// `Retry-After`. Defaults to 30 seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_backoff` - The longest wait between two attempts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::external::HttpEvaluator;
+    /// use std::time::Duration;
+    ///
+    /// let evaluator = HttpEvaluator::new("http://localhost:8080/evaluate")
+    ///     .unwrap()
+    ///     .with_retries(10, Duration::from_millis(50))
+    ///     .with_max_backoff(Duration::from_secs(5));
+    /// ```
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+    /// Start at most `requests_per_second` requests per second, retries included. The requests
+    /// are spaced evenly instead of being sent in bursts. Returns an error if the rate is not
+    /// positive or so small that the time between two requests can't be represented.
+    ///
+    /// # Arguments
+    ///
+    /// * `requests_per_second` - The maximal number of requests started per second.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::external::HttpEvaluator;
+    ///
+    /// let evaluator = HttpEvaluator::new("http://localhost:8080/evaluate")
+    ///     .unwrap()
+    ///     .with_rate_limit(2.5)
+    ///     .unwrap();
+    /// assert!(HttpEvaluator::new("http://localhost:8080/evaluate")
+    ///     .unwrap()
+    ///     .with_rate_limit(0.0)
+    ///     .is_err());
+    /// ```
+    pub fn with_rate_limit(mut self, requests_per_second: f64) -> Result<Self, FunctionError> {
+        let interval = Some(requests_per_second)
+            .filter(|requests_per_second| *requests_per_second > 0.0)
+            .and_then(|requests_per_second| {
+                Duration::try_from_secs_f64(1.0 / requests_per_second).ok()
+            })
+            .ok_or_else(|| {
+                FunctionError::ExternalEvaluation(format!(
+                    "Invalid rate limit of {} requests per second",
+                    requests_per_second
+                ))
+            })?;
+        self.rate_limit = Some(RateLimiter {
+            interval,
+            next: Mutex::new(Instant::now()),
+        });
+        Ok(self)
+    }
+    /// How long to wait before retry number `attempt`, starting at 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `attempt` - The number of the retry.
+    /// * `retry_after` - How long the service asked to wait.
+    fn backoff(&self, attempt: usize, retry_after: Option<Duration>) -> Duration {
+        let exponential = self
+            .initial_backoff
+            .saturating_mul(1 << (attempt - 1).min(31));
+        exponential
+            .max(retry_after.unwrap_or(Duration::ZERO))
+            .min(self.max_backoff)
+    }
+    /// Set how many requests may be in flight at once, across all islands. `0` is treated as
+    /// `1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_in_flight` - The maximal number of concurrent requests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::external::HttpEvaluator;
+    ///
+    /// let evaluator = HttpEvaluator::new("http://localhost:8080/evaluate")
+    ///     .unwrap()
+    ///     .with_max_in_flight(16);
+    /// ```
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.in_flight = Semaphore {
+            available: Mutex::new(max_in_flight.max(1)),
+            released: Condvar::new(),
+        };
+        self
+    }
+    /// Set the timeout of connecting to the endpoint and of sending and receiving a batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The timeout of every step of a request.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::external::HttpEvaluator;
+    /// use std::time::Duration;
+    ///
+    /// let evaluator = HttpEvaluator::new("http://localhost:8080/evaluate")
+    ///     .unwrap()
+    ///     .with_timeout(Duration::from_secs(300));
+    /// ```
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    /// Send a single request and return the body of the response.
+    ///
+    /// # Arguments
+    ///
+    /// * `body` - The JSON body to POST.
+    fn post(&self, body: &str) -> Result<String, RequestError> {
+        let transient = |error: std::io::Error| match error.kind() {
+            std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock => {
+                RequestError::Timeout(error.to_string())
+            }
+            _ => RequestError::Transient(error.to_string(), None),
+        };
+        let address =
+            std::net::ToSocketAddrs::to_socket_addrs(&(self.url.host.as_str(), self.url.port))
+                .map_err(transient)?
+                .next()
+                .ok_or_else(|| {
+                    RequestError::Transient(format!("Could not resolve {}", self.url.host), None)
+                })?;
+        let mut stream = TcpStream::connect_timeout(&address, self.timeout).map_err(transient)?;
+        stream
+            .set_read_timeout(Some(self.timeout))
+            .map_err(transient)?;
+        stream
+            .set_write_timeout(Some(self.timeout))
+            .map_err(transient)?;
+        write!(
+            stream,
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.url.path,
+            self.url.authority(),
+            body.len(),
+            body
+        )
+        .map_err(transient)?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).map_err(transient)?;
+
+        let header_end = response
+            .windows(4)
+            .position(|window| window == b"\r\n\r\n")
+            .ok_or_else(|| RequestError::Transient("Incomplete response".to_string(), None))?;
+        let head = String::from_utf8_lossy(&response[..header_end]).to_lowercase();
+        let body = &response[header_end + 4..];
+        let status = head
+            .split_whitespace()
+            .nth(1)
+            .and_then(|status| status.parse::<u16>().ok())
+            .ok_or_else(|| RequestError::Transient("Invalid status line".to_string(), None))?;
+        let body = if head.contains("transfer-encoding: chunked") {
+            decode_chunked(body)
+                .ok_or_else(|| RequestError::Transient("Invalid chunked body".to_string(), None))?
+        } else {
+            body.to_vec()
+        };
+        let body = String::from_utf8_lossy(&body).into_owned();
+        match status {
+            200..=299 => Ok(body),
+            429 | 500..=599 => Err(RequestError::Transient(
+                format!("The endpoint answered {}: {}", status, body),
+                retry_after(&head),
+            )),
+            _ => Err(RequestError::Permanent(format!(
+                "The endpoint answered {}: {}",
+                status, body
+            ))),
+        }
+    }
+    /// Send a batch, retrying transient failures, and return its fitnesses.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - The candidates to evaluate.
+    /// * `fidelity` - The fidelity the candidates should be evaluated at.
+    fn exchange(
+        &self,
+        chunk: &[Solution],
+        fidelity: f64,
+    ) -> Result<Vec<Result<f64, FunctionError>>, FunctionError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let body = batch_to_json(id, chunk, fidelity).to_string();
+        let mut attempt = 0;
+        loop {
+            if let Some(rate_limit) = &self.rate_limit {
+                rate_limit.wait();
+            }
+            let response = {
+                let _permit = self.in_flight.acquire();
+                self.post(&body)
+            };
+            match response {
+                Ok(response) => return fitnesses_from_json(&response, id, chunk.len()),
+                Err(RequestError::Transient(_, retry_after)) if attempt < self.max_retries => {
+                    attempt += 1;
+                    thread::sleep(self.backoff(attempt, retry_after));
+                }
+                Err(RequestError::Timeout(_)) if attempt < self.max_retries => {
+                    attempt += 1;
+                    thread::sleep(self.backoff(attempt, None));
+                }
+                Err(RequestError::Timeout(message)) => return Err(FunctionError::Timeout(message)),
+                Err(RequestError::Transient(message, _) | RequestError::Permanent(message)) => {
+                    return Err(FunctionError::ExternalEvaluation(message))
+                }
+            }
+        }
+    }
+}
+
+impl Evaluator for HttpEvaluator {
+    fn evaluate_chunk(&self, chunk: &[Solution]) -> Vec<Result<f64, FunctionError>> {
+        self.evaluate_chunk_at_fidelity(chunk, 1.0)
+    }
+    fn evaluate_chunk_at_fidelity(
+        &self,
+        chunk: &[Solution],
+        fidelity: f64,
+    ) -> Vec<Result<f64, FunctionError>> {
+        self.exchange(chunk, fidelity)
+            .unwrap_or_else(|error| chunk.iter().map(|_| Err(error.clone())).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{Runner, RunnerConfig};
+    use crate::solutions::Solutions;
+    use std::net::TcpListener;
+
+    /// Serve the given raw responses, one per connection, and return the address.
+    fn serve(responses: Vec<&'static str>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0; 4096];
+                // Read until the body, which always ends with `}`, is complete.
+                while !request.ends_with(b"}") {
+                    let n = stream.read(&mut buffer).unwrap();
+                    request.extend_from_slice(&buffer[..n]);
+                }
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        format!("http://{}/evaluate", address)
+    }
+
+    #[test]
+    fn bare_arrays_and_failed_entries() {
+        let responses = "[1.5, {\"error\": \"diverged\"}]\n".as_bytes();
+        let results = JsonLinesEvaluator::new(responses, Vec::new())
+            .evaluate_chunk(&[Solution::new(vec![1.0]), Solution::new(vec![2.0])]);
+        assert_eq!(results[0], Ok(1.5));
+        assert!(results[1].is_err());
+    }
+    #[test]
+    fn failed_entries_only_cost_their_candidates() {
+        let responses = "[{\"error\": \"diverged\"}]\n".repeat(5);
+        let evaluator = JsonLinesEvaluator::new(responses.as_bytes(), Vec::new());
+        let result = Runner::new(RunnerConfig {
+            n_generations: 3,
+            size_generation: 4,
+            evaluation_chunk_size: 1,
+            failure_penalty: Some(FAILURE_PENALTY),
+            ..RunnerConfig::default()
+        })
+        .run(Solutions::random(4, 0.0..1.0, 2), &evaluator);
+        // The evaluator fails the first entries and then closes its output.
+        assert_eq!(result.evaluations.n_ok, 0);
+        assert!(result.evaluations.n_other > 5);
+        assert_eq!(
+            result.best.map(|(_, fitness)| fitness),
+            Some(FAILURE_PENALTY)
+        );
+    }
+    #[test]
+    fn mismatched_responses_fail_the_batch() {
+        for responses in [
+            "{\"id\": 3, \"fitnesses\": [1.0]}\n",
+            "[1.0, 2.0]\n",
+            "not json\n",
+            "",
+        ] {
+            let results = JsonLinesEvaluator::new(responses.as_bytes(), Vec::new())
+                .evaluate_chunk(&[Solution::new(vec![1.0])]);
+            assert!(
+                matches!(results[..], [Err(FunctionError::ExternalEvaluation(_))]),
+                "{}",
+                responses
+            );
+        }
+    }
+    #[test]
+    fn batches_are_numbered() {
+        let evaluator = JsonLinesEvaluator::new("[1.0]\n[2.0]\n".as_bytes(), Vec::new());
+        evaluator.evaluate_chunk(&[Solution::new(vec![1.0])]);
+        evaluator.evaluate_chunk_at_fidelity(&[Solution::new(vec![2.0])], 0.5);
+        let (_, requests) = evaluator.into_inner();
+        let requests = String::from_utf8(requests).unwrap();
+        let lines = requests.lines().collect::<Vec<&str>>();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].starts_with("{\"id\":1,\"fidelity\":0.5"));
+    }
+    #[test]
+    fn parse_url_splits_host_port_and_path() {
+        assert_eq!(
+            parse_url("http://example.com:8080/a/b").unwrap(),
+            Url {
+                host: "example.com".to_string(),
+                port: 8080,
+                path: "/a/b".to_string()
+            }
+        );
+        assert_eq!(parse_url("http://example.com").unwrap().port, 80);
+        assert!(parse_url("http://:80/").is_err());
+        assert!(parse_url("http://example.com:http/").is_err());
+        let ipv6 = parse_url("http://[::1]:8080/f").unwrap();
+        assert_eq!(
+            ipv6,
+            Url {
+                host: "::1".to_string(),
+                port: 8080,
+                path: "/f".to_string()
+            }
+        );
+        assert_eq!(ipv6.authority(), "[::1]:8080");
+        assert_eq!(
+            parse_url("http://[::1]/f").unwrap(),
+            Url {
+                host: "::1".to_string(),
+                port: 80,
+                path: "/f".to_string()
+            }
+        );
+        assert!(parse_url("http://[::1:8080/f").is_err());
+        assert!(parse_url("http://[::1]8080/f").is_err());
+        assert!(parse_url("http://[]:8080/f").is_err());
+    }
+    #[test]
+    fn decode_chunked_body() {
+        assert_eq!(
+            decode_chunked(b"4\r\n[1.0\r\n1\r\n]\r\n0\r\n\r\n"),
+            Some(b"[1.0]".to_vec())
+        );
+    }
+    #[test]
+    fn http_evaluator_retries_unavailable_endpoint() {
+        let url = serve(vec![
+            "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n{\"id\": 0, \"fitnesses\": [2.5]}",
+        ]);
+        let evaluator = HttpEvaluator::new(&url)
+            .unwrap()
+            .with_retries(1, Duration::ZERO);
+        assert_eq!(
+            evaluator.evaluate_chunk(&[Solution::new(vec![1.0])]),
+            vec![Ok(2.5)]
+        );
+    }
+    #[test]
+    fn endpoints_that_keep_failing_only_cost_their_candidates() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/evaluate", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut buffer = [0; 4096];
+                let _ = stream.read(&mut buffer);
+                let _ = stream
+                    .write_all(b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n");
+            }
+        });
+        let evaluator = HttpEvaluator::new(&url)
+            .unwrap()
+            .with_retries(1, Duration::ZERO);
+        let result = Runner::new(RunnerConfig {
+            n_generations: 2,
+            size_generation: 4,
+            failure_penalty: Some(FAILURE_PENALTY),
+            ..RunnerConfig::default()
+        })
+        .run(Solutions::random(4, 0.0..1.0, 2), &evaluator);
+        assert_eq!(result.evaluations.n_ok, 0);
+        assert!(result.evaluations.n_other >= 4);
+        assert_eq!(result.n_evolved_generations, 2);
+    }
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let evaluator = HttpEvaluator::new("http://localhost/")
+            .unwrap()
+            .with_retries(10, Duration::from_millis(100))
+            .with_max_backoff(Duration::from_millis(500));
+        assert_eq!(evaluator.backoff(1, None), Duration::from_millis(100));
+        assert_eq!(evaluator.backoff(3, None), Duration::from_millis(400));
+        assert_eq!(evaluator.backoff(4, None), Duration::from_millis(500));
+        assert_eq!(
+            evaluator.backoff(1, Some(Duration::from_millis(300))),
+            Duration::from_millis(300)
+        );
+        assert_eq!(
+            evaluator.backoff(1, Some(Duration::from_secs(60))),
+            Duration::from_millis(500)
+        );
+    }
+    #[test]
+    fn retry_after_is_read_from_the_headers() {
+        assert_eq!(
+            retry_after("http/1.1 429 too many requests\r\nretry-after: 2\r\n"),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(retry_after("http/1.1 503 service unavailable\r\n"), None);
+    }
+    #[test]
+    fn rate_limit_spaces_requests() {
+        let evaluator = HttpEvaluator::new("http://localhost/")
+            .unwrap()
+            .with_rate_limit(50.0)
+            .unwrap();
+        let start = Instant::now();
+        for _ in 0..3 {
+            evaluator.rate_limit.as_ref().unwrap().wait();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(40));
+    }
+    #[test]
+    fn invalid_rate_limits_are_rejected() {
+        for requests_per_second in [0.0, -1.0, f64::NAN, 1e-300] {
+            assert!(
+                matches!(
+                    HttpEvaluator::new("http://localhost/")
+                        .unwrap()
+                        .with_rate_limit(requests_per_second),
+                    Err(FunctionError::ExternalEvaluation(_))
+                ),
+                "{}",
+                requests_per_second
+            );
+        }
+    }
+    #[test]
+    fn unanswered_requests_time_out() {
+        // The connection is accepted by the backlog, but never answered.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let evaluator = HttpEvaluator::new(&format!(
+            "http://{}/evaluate",
+            listener.local_addr().unwrap()
+        ))
+        .unwrap()
+        .with_timeout(Duration::from_millis(50))
+        .with_retries(0, Duration::ZERO);
+        assert!(matches!(
+            evaluator.evaluate_chunk(&[Solution::new(vec![1.0])])[..],
+            [Err(FunctionError::Timeout(_))]
+        ));
+    }
+    #[test]
+    fn http_evaluator_does_not_retry_rejected_requests() {
+        let url = serve(vec![
+            "HTTP/1.1 400 Bad Request\r\nContent-Length: 3\r\n\r\nbad",
+        ]);
+        let evaluator = HttpEvaluator::new(&url)
+            .unwrap()
+            .with_retries(3, Duration::ZERO);
+        assert!(matches!(
+            evaluator.evaluate_chunk(&[Solution::new(vec![1.0])])[..],
+            [Err(FunctionError::ExternalEvaluation(_))]
+        ));
+    }
+}
@@ -1,6 +1,12 @@
+#[cfg(feature = "autodiff")]
+use crate::autodiff::{self, Differentiable};
+use crate::error::Error;
+use std::collections::HashMap;
 use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 /// Custom error that can occur with the Function class defined below.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum FunctionError {
     /// The Function could not compute the function value because the
     /// Vector provided does not have the right number of arguments.
@@ -10,6 +16,12 @@ pub enum FunctionError {
         /// Actual number of arguments.
         actual_number_of_entries: usize,
     },
+    /// An external evaluator (another process or a service) failed or answered with something
+    /// that is not a fitness.
+    ExternalEvaluation(String),
+    /// The evaluation took longer than allowed, e.g. an external evaluator didn't answer in
+    /// time.
+    Timeout(String),
 }
 impl fmt::Display for FunctionError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -22,6 +34,187 @@ impl fmt::Display for FunctionError {
                 "Expected {} entries, but got {}",
                 expected_number_of_entries, actual_number_of_entries
             ),
+            FunctionError::ExternalEvaluation(message) => {
+                write!(f, "External evaluation failed: {}", message)
+            }
+            FunctionError::Timeout(message) => write!(f, "Evaluation timed out: {}", message),
+        }
+    }
+}
+
+/// An objective that can be computed at different fidelities, trading accuracy for cost.
+/// Every `MultiFidelityFunction` that is `Sync` is an `Evaluator` and can be optimized by the
+/// `Runner`, e.g. with `RunnerConfig::successive_halving`.
+pub trait MultiFidelityFunction {
+    /// Compute the function value at a fidelity in `(0, 1]`, `1.0` being exact.
+    ///
+    /// # Arguments
+    ///
+    /// * `arguments` - The arguments the function value should be computed for.
+    /// * `fidelity` - How accurate (and expensive) the computation should be.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::{FunctionError, MultiFidelityFunction};
+    ///
+    /// // A simulation whose number of time steps is controlled by the fidelity.
+    /// struct Simulation {
+    ///     max_steps: usize,
+    /// }
+    /// impl MultiFidelityFunction for Simulation {
+    ///     fn evaluate_at_fidelity(
+    ///         &self,
+    ///         arguments: Vec<f64>,
+    ///         fidelity: f64,
+    ///     ) -> Result<f64, FunctionError> {
+    ///         let steps = (self.max_steps as f64 * fidelity).ceil() as usize;
+    ///         Ok((0..steps).map(|_| arguments.iter().sum::<f64>()).sum::<f64>() / steps as f64)
+    ///     }
+    /// }
+    /// assert_eq!(
+    ///     Simulation { max_steps: 10 }.evaluate_at_fidelity(vec![1.0, 2.0], 0.5),
+    ///     Ok(3.0)
+    /// );
+    /// ```
+    fn evaluate_at_fidelity(
+        &self,
+        arguments: Vec<f64>,
+        fidelity: f64,
+    ) -> Result<f64, FunctionError>;
+}
+
+/// The user-supplied objective of a `Function`. Closures may capture their environment, e.g.
+/// a dataset, so they are boxed.
+enum Objective {
+    /// An objective that is always computed exactly.
+    Plain(Box<dyn Fn(Vec<f64>) -> Result<f64, FunctionError> + Send + Sync>),
+    /// An objective that can be computed at a fidelity in `(0, 1]`, `1.0` being exact.
+    Fidelity(Box<dyn Fn(Vec<f64>, f64) -> Result<f64, FunctionError> + Send + Sync>),
+    /// An objective written generically over `autodiff::Float`, which also computes its
+    /// value and gradient.
+    #[cfg(feature = "autodiff")]
+    Differentiable {
+        /// Computes the function value.
+        value: Box<dyn Fn(Vec<f64>) -> Result<f64, FunctionError> + Send + Sync>,
+        /// Computes the function value and its gradient, shared with gradient-based mutations.
+        gradient: Gradient,
+    },
+    /// An objective made of test cases, e.g. the residuals of a curve fit, whose value is the
+    /// negative sum of the errors on the cases.
+    Cases {
+        /// Computes the errors on the cases.
+        errors: CaseErrors,
+        /// The errors computed by the latest evaluations.
+        recent: Arc<RecentCaseErrors>,
+    },
+}
+
+/// Computes the function value and the gradient of an objective, e.g. by automatic
+/// differentiation, see `Function::new_autodiff`, or by finite differences, see
+/// `sensitivity::FiniteDifference::gradient_fn`.
+pub type Gradient = Arc<dyn Fn(&[f64]) -> Result<(f64, Vec<f64>), FunctionError> + Send + Sync>;
+
+/// Computes the errors of a solution on the test cases of an objective, the smaller the
+/// better, see `Function::with_case_errors`.
+pub type CaseErrors = Arc<dyn Fn(&[f64]) -> Result<Vec<f64>, FunctionError> + Send + Sync>;
+
+/// The errors on the test cases by the bits of the arguments they were computed at.
+type ErrorsByArguments = HashMap<Vec<u64>, Option<Vec<f64>>>;
+
+/// The errors on the test cases of the latest evaluations of a `Function::with_case_errors`,
+/// so that a selection comparing the cases, e.g. `selection::EpsilonLexicaseSelection`, reuses
+/// them instead of computing them once more. The errors of arguments that fail are remembered
+/// as `None`. Once `capacity` arguments are remembered, those that weren't looked up since the
+/// previous time are forgotten.
+#[derive(Debug)]
+pub(crate) struct RecentCaseErrors {
+    /// The errors by the bits of the arguments, the latest ones and the ones before.
+    errors: Mutex<[ErrorsByArguments; 2]>,
+    /// How many arguments are remembered before older ones are forgotten.
+    capacity: AtomicUsize,
+    /// The number of calls of the function, which the errors computed anew count toward.
+    n_calls: Arc<AtomicUsize>,
+}
+
+impl RecentCaseErrors {
+    /// The smallest number of arguments that are remembered.
+    const MIN_CAPACITY: usize = 1024;
+
+    /// Remember the latest errors of the function counting its calls in `n_calls`.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_calls` - The number of calls of the function.
+    fn new(n_calls: Arc<AtomicUsize>) -> Self {
+        RecentCaseErrors {
+            errors: Mutex::new([HashMap::new(), HashMap::new()]),
+            capacity: AtomicUsize::new(Self::MIN_CAPACITY),
+            n_calls,
+        }
+    }
+    /// Remember at least `n` arguments, e.g. four times the size of the population a
+    /// selection looks at, so that those evaluated for it aren't forgotten in the meantime.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - How many arguments should at least be remembered.
+    pub(crate) fn reserve(&self, n: usize) {
+        self.capacity.fetch_max(n, Ordering::Relaxed);
+    }
+    /// Remember the errors of `arguments`.
+    ///
+    /// # Arguments
+    ///
+    /// * `arguments` - The arguments the errors were computed at.
+    /// * `errors` - Their errors, `None` if they couldn't be computed.
+    fn record(&self, arguments: &[f64], errors: Option<Vec<f64>>) {
+        let mut recent = self
+            .errors
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if recent[0].len() >= self.capacity.load(Ordering::Relaxed) {
+            recent[1] = std::mem::take(&mut recent[0]);
+        }
+        recent[0].insert(arguments.iter().map(|x| x.to_bits()).collect(), errors);
+    }
+    /// The errors of `arguments`, computed with `case_errors` and counted as a call of the
+    /// function unless they are remembered.
+    ///
+    /// # Arguments
+    ///
+    /// * `case_errors` - Computes the errors of arguments that aren't remembered.
+    /// * `arguments` - The arguments whose errors are wanted.
+    pub(crate) fn errors(&self, case_errors: &CaseErrors, arguments: &[f64]) -> Option<Vec<f64>> {
+        let key = arguments.iter().map(|x| x.to_bits()).collect::<Vec<u64>>();
+        {
+            let mut recent = self
+                .errors
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            if let Some(errors) = recent[0].get(&key) {
+                return errors.clone();
+            }
+            if let Some(errors) = recent[1].remove(&key) {
+                recent[0].insert(key, errors.clone());
+                return errors;
+            }
+        }
+        self.n_calls.fetch_add(1, Ordering::Relaxed);
+        let errors = case_errors(arguments).ok();
+        self.record(arguments, errors.clone());
+        errors
+    }
+}
+
+impl fmt::Debug for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Objective::Plain(_) => write!(f, "Plain"),
+            Objective::Fidelity(_) => write!(f, "Fidelity"),
+            #[cfg(feature = "autodiff")]
+            Objective::Differentiable { .. } => write!(f, "Differentiable"),
+            Objective::Cases { .. } => write!(f, "Cases"),
         }
     }
 }
@@ -29,11 +222,15 @@ impl fmt::Display for FunctionError {
 /// A representation of a f64 based distance matrix.
 #[derive(Debug)]
 pub struct Function {
-    fun: fn(Vec<f64>) -> Result<f64, FunctionError>,
+    fun: Objective,
+    /// How often the function value was computed, also across threads.
+    n_calls: Arc<AtomicUsize>,
 }
 
 impl Function {
-    /// Create a new function.
+    /// Create a new function. Besides plain functions this accepts closures that capture
+    /// their environment, e.g. a dataset the objective is computed on, as long as they can be
+    /// shared between the threads of a multi-threaded run.
     ///
     /// # Arguments
     ///
@@ -54,9 +251,140 @@ impl Function {
     ///     }
     /// );
     ///
+    /// // The least squares fit of a line through a captured dataset.
+    /// let points = vec![(0.0, 1.0), (1.0, 3.0), (2.0, 5.0)];
+    /// let line_fit = function::Function::new(move |x| {
+    ///     Ok(-points
+    ///         .iter()
+    ///         .map(|(input, output)| (x[0] * input + x[1] - output).powi(2))
+    ///         .sum::<f64>())
+    /// });
+    /// assert_eq!(line_fit.get_function_value(vec![2.0, 1.0]), Ok(0.0));
+    /// ```
+    pub fn new<F>(fun: F) -> Self
+    where
+        F: Fn(Vec<f64>) -> Result<f64, FunctionError> + Send + Sync + 'static,
+    {
+        Function {
+            fun: Objective::Plain(Box::new(fun)),
+            n_calls: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+    /// Create a new function whose accuracy (and cost) can be controlled, e.g. the number of
+    /// training epochs of a model. The fidelity is in `(0, 1]`, `1.0` being the exact value.
+    ///
+    /// # Arguments
+    ///
+    /// * `fun` - The function that should be computed at the given fidelity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function;
+    ///
+    /// // A noisy estimate at low fidelity, the exact value at full fidelity.
+    /// let function_to_optimize = function::Function::with_fidelity(|x, fidelity| {
+    ///     Ok(x.iter().sum::<f64>() + (1.0 - fidelity))
+    /// });
+    /// assert_eq!(function_to_optimize.get_function_value(vec![1.0, 2.0]), Ok(3.0));
+    /// ```
+    pub fn with_fidelity<F>(fun: F) -> Self
+    where
+        F: Fn(Vec<f64>, f64) -> Result<f64, FunctionError> + Send + Sync + 'static,
+    {
+        Function {
+            fun: Objective::Fidelity(Box::new(fun)),
+            n_calls: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+    /// Create a new function made of test cases, e.g. the data points of a curve fit, from
+    /// the errors of a solution on every case, the smaller the better. The function value is
+    /// the negative sum of the errors, so maximizing it minimizes the total error, while
+    /// selections like `selection::EpsilonLexicaseSelection` look at every case on its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `errors` - Computes the error on every case, e.g. the absolute residuals.
+    ///
+    /// # Examples
+    ///
     /// ```
-    pub fn new(fun: fn(Vec<f64>) -> Result<f64, FunctionError>) -> Self {
-        Function { fun }
+    /// use genetic_algorithm_fn::function::Function;
+    ///
+    /// // The absolute residuals of a line through a captured dataset.
+    /// let points = vec![(0.0, 1.0), (1.0, 3.0), (2.0, 5.0)];
+    /// let line_fit = Function::with_case_errors(move |x: &[f64]| {
+    ///     Ok(points
+    ///         .iter()
+    ///         .map(|(input, output)| (x[0] * input + x[1] - output).abs())
+    ///         .collect())
+    /// });
+    /// assert_eq!(line_fit.get_function_value(vec![2.0, 0.0]), Ok(-3.0));
+    /// assert_eq!(line_fit.case_errors(&[2.0, 0.0]), Some(Ok(vec![1.0, 1.0, 1.0])));
+    /// ```
+    pub fn with_case_errors<F>(errors: F) -> Self
+    where
+        F: Fn(&[f64]) -> Result<Vec<f64>, FunctionError> + Send + Sync + 'static,
+    {
+        let n_calls = Arc::new(AtomicUsize::new(0));
+        Function {
+            fun: Objective::Cases {
+                errors: Arc::new(errors),
+                recent: Arc::new(RecentCaseErrors::new(Arc::clone(&n_calls))),
+            },
+            n_calls,
+        }
+    }
+    /// Combine several objectives into a single one, the weighted sum of their values, so they
+    /// can be optimized together by the single-objective `Runner`. The fidelity is passed on
+    /// to every objective. If an objective fails, the sum fails with its error.
+    ///
+    /// # Arguments
+    ///
+    /// * `objectives` - The objectives that should be combined.
+    /// * `weights` - The weight of every objective, in the same order. A negative weight
+    ///   minimizes its objective while the sum is maximized.
+    ///
+    /// Returns `Error::LengthMismatch` if there isn't exactly one weight per objective.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::error::Error;
+    /// use genetic_algorithm_fn::function::Function;
+    ///
+    /// // Maximize the sum of the arguments while keeping them close to each other.
+    /// let function = Function::weighted_sum(
+    ///     vec![
+    ///         Function::new(|x| Ok(x.iter().sum())),
+    ///         Function::new(|x| Ok((x[0] - x[1]).abs())),
+    ///     ],
+    ///     vec![1.0, -2.0],
+    /// )
+    /// .unwrap();
+    /// assert_eq!(function.get_function_value(vec![3.0, 1.0]), Ok(0.0));
+    /// assert!(matches!(
+    ///     Function::weighted_sum(vec![Function::new(|x| Ok(x[0]))], vec![1.0, 2.0]),
+    ///     Err(Error::LengthMismatch { expected: 1, actual: 2 })
+    /// ));
+    /// ```
+    pub fn weighted_sum(objectives: Vec<Function>, weights: Vec<f64>) -> Result<Self, Error> {
+        if objectives.len() != weights.len() {
+            return Err(Error::LengthMismatch {
+                expected: objectives.len(),
+                actual: weights.len(),
+            });
+        }
+        Ok(Function::with_fidelity(move |arguments, fidelity| {
+            objectives
+                .iter()
+                .zip(&weights)
+                .try_fold(0.0, |sum, (objective, weight)| {
+                    let value =
+                        objective.get_function_value_at_fidelity(arguments.clone(), fidelity)?;
+                    Ok(sum + weight * value)
+                })
+        }))
     }
     /// Compute the function value for a Solution.
     ///
@@ -82,7 +410,196 @@ impl Function {
     ///
     /// ```
     pub fn get_function_value(&self, function_values: Vec<f64>) -> Result<f64, FunctionError> {
-        (self.fun)(function_values)
+        self.get_function_value_at_fidelity(function_values, 1.0)
+    }
+    /// Compute the function value for a Solution at a fidelity in `(0, 1]`. Functions created
+    /// with `Function::new` ignore the fidelity and are always exact.
+    ///
+    /// # Arguments
+    ///
+    /// * `function_values` - The arguments the function value should be computed for.
+    /// * `fidelity` - How accurate (and expensive) the computation should be.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function;
+    ///
+    /// let function_to_optimize = function::Function::with_fidelity(|x, fidelity| {
+    ///     Ok(x.iter().sum::<f64>() * fidelity)
+    /// });
+    /// assert_eq!(
+    ///     function_to_optimize.get_function_value_at_fidelity(vec![1.0, 3.0], 0.5),
+    ///     Ok(2.0)
+    /// );
+    /// ```
+    pub fn get_function_value_at_fidelity(
+        &self,
+        function_values: Vec<f64>,
+        fidelity: f64,
+    ) -> Result<f64, FunctionError> {
+        self.n_calls.fetch_add(1, Ordering::Relaxed);
+        match &self.fun {
+            Objective::Plain(fun) => fun(function_values),
+            Objective::Fidelity(fun) => fun(function_values, fidelity),
+            #[cfg(feature = "autodiff")]
+            Objective::Differentiable { value, .. } => value(function_values),
+            Objective::Cases { errors, recent } => {
+                let errors = errors(&function_values);
+                recent.record(&function_values, errors.as_ref().ok().cloned());
+                errors.map(|errors| -errors.iter().sum::<f64>())
+            }
+        }
+    }
+    /// How often the function value was computed so far with `get_function_value` or
+    /// `get_function_value_at_fidelity`, summed over all threads, e.g. to check how much of
+    /// `RunnerConfig::max_evaluations` a run spent. Failed computations count as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    ///
+    /// let function_to_optimize = Function::new(|x| Ok(x.iter().sum()));
+    /// assert_eq!(function_to_optimize.n_calls(), 0);
+    /// function_to_optimize.get_function_value(vec![1.0, 2.0]).unwrap();
+    /// function_to_optimize.get_function_value_at_fidelity(vec![1.0], 0.5).unwrap();
+    /// assert_eq!(function_to_optimize.n_calls(), 2);
+    /// ```
+    pub fn n_calls(&self) -> usize {
+        self.n_calls.load(Ordering::Relaxed)
+    }
+    /// Compute the errors on the test cases of the function, `None` if it wasn't created with
+    /// `Function::with_case_errors`.
+    ///
+    /// # Arguments
+    ///
+    /// * `arguments` - The arguments the errors should be computed at.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    ///
+    /// assert_eq!(Function::new(|x| Ok(x[0])).case_errors(&[1.0]), None);
+    /// ```
+    pub fn case_errors(&self, arguments: &[f64]) -> Option<Result<Vec<f64>, FunctionError>> {
+        self.case_errors_fn().map(|errors| errors(arguments))
+    }
+    /// The function computing the errors on the test cases of the function, `None` if it
+    /// wasn't created with `Function::with_case_errors`. It can be kept beyond the lifetime of
+    /// the function, e.g. by a selection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    ///
+    /// let function = Function::with_case_errors(|x: &[f64]| Ok(x.to_vec()));
+    /// let errors = function.case_errors_fn().unwrap();
+    /// assert_eq!(errors(&[1.0, 2.0]), Ok(vec![1.0, 2.0]));
+    /// ```
+    pub fn case_errors_fn(&self) -> Option<CaseErrors> {
+        match &self.fun {
+            Objective::Cases { errors, .. } => Some(Arc::clone(errors)),
+            _ => None,
+        }
+    }
+    /// The errors on the test cases of the latest evaluations of the function, `None` if it
+    /// wasn't created with `Function::with_case_errors`.
+    pub(crate) fn recent_case_errors(&self) -> Option<Arc<RecentCaseErrors>> {
+        match &self.fun {
+            Objective::Cases { recent, .. } => Some(Arc::clone(recent)),
+            _ => None,
+        }
+    }
+    /// Create a new function from an objective written generically over `autodiff::Float`.
+    /// Besides its value, its gradient is computed by forward-mode automatic differentiation,
+    /// e.g. for a `pipeline::GradientMutation`, without writing the gradient by hand.
+    ///
+    /// # Arguments
+    ///
+    /// * `objective` - The objective that should be computed in this struct.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::autodiff::{Differentiable, Float};
+    /// use genetic_algorithm_fn::function::{Function, FunctionError};
+    ///
+    /// struct Paraboloid;
+    /// impl Differentiable for Paraboloid {
+    ///     fn evaluate<T: Float>(&self, x: &[T]) -> Result<T, FunctionError> {
+    ///         Ok(-(x[0] - T::constant(1.0)).powi(2) - x[1].powi(2))
+    ///     }
+    /// }
+    /// let function = Function::new_autodiff(Paraboloid);
+    /// assert_eq!(function.get_function_value(vec![1.0, 2.0]), Ok(-4.0));
+    /// assert_eq!(function.gradient(&[1.0, 2.0]), Some(Ok(vec![0.0, -4.0])));
+    /// ```
+    #[cfg(feature = "autodiff")]
+    pub fn new_autodiff<D>(objective: D) -> Self
+    where
+        D: Differentiable,
+    {
+        let objective = Arc::new(objective);
+        let differentiated = Arc::clone(&objective);
+        Function {
+            fun: Objective::Differentiable {
+                value: Box::new(move |arguments| objective.evaluate(&arguments)),
+                gradient: Arc::new(move |arguments| {
+                    autodiff::gradient(differentiated.as_ref(), arguments)
+                }),
+            },
+            n_calls: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+    /// Compute the gradient of the function, `None` if it wasn't created with
+    /// `Function::new_autodiff`.
+    ///
+    /// # Arguments
+    ///
+    /// * `arguments` - The arguments the gradient should be computed at.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    ///
+    /// assert_eq!(Function::new(|x| Ok(x[0])).gradient(&[1.0]), None);
+    /// ```
+    #[cfg(feature = "autodiff")]
+    pub fn gradient(&self, arguments: &[f64]) -> Option<Result<Vec<f64>, FunctionError>> {
+        self.gradient_fn()
+            .map(|gradient| gradient(arguments).map(|(_, gradient)| gradient))
+    }
+    /// The function computing the value and the gradient of the function, `None` if it wasn't
+    /// created with `Function::new_autodiff`. It can be kept beyond the lifetime of the
+    /// function, e.g. by a mutation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    ///
+    /// assert!(Function::new(|x| Ok(x[0])).gradient_fn().is_none());
+    /// ```
+    #[cfg(feature = "autodiff")]
+    pub fn gradient_fn(&self) -> Option<Gradient> {
+        match &self.fun {
+            Objective::Differentiable { gradient, .. } => Some(Arc::clone(gradient)),
+            _ => None,
+        }
+    }
+}
+
+impl MultiFidelityFunction for Function {
+    fn evaluate_at_fidelity(
+        &self,
+        arguments: Vec<f64>,
+        fidelity: f64,
+    ) -> Result<f64, FunctionError> {
+        self.get_function_value_at_fidelity(arguments, fidelity)
     }
 }
 
@@ -117,4 +634,64 @@ mod test_distance_mat {
             })
         );
     }
+    #[test]
+    fn test_capturing_closure() {
+        let offsets = vec![1.0, 2.0, 3.0];
+        let my_func =
+            Function::new(move |x| Ok(x.iter().zip(&offsets).map(|(x, offset)| x + offset).sum()));
+        assert_eq!(my_func.get_function_value(vec![1.0, 1.0, 1.0]), Ok(9.0));
+    }
+    #[test]
+    fn test_fidelity_is_passed() {
+        let my_func = Function::with_fidelity(|x, fidelity| Ok(x[0] * fidelity));
+        assert_eq!(
+            my_func.get_function_value_at_fidelity(vec![4.0], 0.25),
+            Ok(1.0)
+        );
+        assert_eq!(my_func.get_function_value(vec![4.0]), Ok(4.0));
+    }
+    #[test]
+    fn test_plain_function_ignores_fidelity() {
+        let my_func = Function::new(test_objects::triple_multiplication());
+        assert_eq!(
+            my_func.get_function_value_at_fidelity(vec![1.0, 2.0, 3.0], 0.1),
+            Ok(6.0)
+        );
+    }
+    #[test]
+    fn test_weighted_sum() {
+        let my_func = Function::weighted_sum(
+            vec![
+                Function::new(test_objects::triple_multiplication()),
+                Function::with_fidelity(|x, fidelity| Ok(x[0] * fidelity)),
+            ],
+            vec![0.5, -1.0],
+        )
+        .unwrap();
+        assert_eq!(
+            my_func.get_function_value_at_fidelity(vec![1.0, 2.0, 3.0], 0.5),
+            Ok(2.5)
+        );
+        // The error of a failing objective is passed on.
+        assert_eq!(
+            my_func.get_function_value(vec![1.0]),
+            Err(FunctionError::WrongNumberOfEntries {
+                expected_number_of_entries: 3,
+                actual_number_of_entries: 1
+            })
+        );
+        assert_eq!(
+            Function::weighted_sum(Vec::new(), Vec::new())
+                .unwrap()
+                .get_function_value(vec![1.0]),
+            Ok(0.0)
+        );
+        assert!(matches!(
+            Function::weighted_sum(Vec::new(), vec![1.0]),
+            Err(Error::LengthMismatch {
+                expected: 0,
+                actual: 1
+            })
+        ));
+    }
 }
@@ -0,0 +1,275 @@
+use crate::checkpoint::Checkpoint;
+use crate::config;
+use crate::rng::SplitMix64;
+use crate::runner::RunnerConfig;
+use crate::solution::{CrossoverOperator, MutationOperator, Solution};
+use std::ops::Range;
+
+/// Reads the values of a fuzz input one after another, missing bytes count as zeros.
+struct Input<'a> {
+    /// The bytes not read yet.
+    data: &'a [u8],
+}
+
+impl<'a> Input<'a> {
+    /// Read the next `N` bytes.
+    fn bytes<const N: usize>(&mut self) -> [u8; N] {
+        let mut bytes = [0; N];
+        let n_read = N.min(self.data.len());
+        bytes[..n_read].copy_from_slice(&self.data[..n_read]);
+        self.data = &self.data[n_read..];
+        bytes
+    }
+    /// Read the next byte.
+    fn u8(&mut self) -> u8 {
+        self.bytes::<1>()[0]
+    }
+    /// Read the next `u64`.
+    fn u64(&mut self) -> u64 {
+        u64::from_le_bytes(self.bytes())
+    }
+    /// Read the next `f64`, any bit pattern including `NaN` and the infinities.
+    fn f64(&mut self) -> f64 {
+        f64::from_le_bytes(self.bytes())
+    }
+    /// Read `n` `f64` values.
+    fn f64s(&mut self, n: usize) -> Vec<f64> {
+        (0..n).map(|_| self.f64()).collect()
+    }
+    /// Read the next `f32`.
+    fn f32(&mut self) -> f32 {
+        f32::from_le_bytes(self.bytes())
+    }
+    /// The bytes not read yet, as text.
+    fn rest(self) -> String {
+        String::from_utf8_lossy(self.data).into_owned()
+    }
+}
+
+/// Read two parents with the same, small number of arguments and maybe step sizes.
+///
+/// # Arguments
+///
+/// * `input` - The fuzz input to read from.
+fn parents(input: &mut Input) -> (Solution, Solution) {
+    let n_arguments = usize::from(input.u8() % 9);
+    let mut parent = || {
+        let solution = Solution::new(input.f64s(n_arguments));
+        match input.u8() % 2 {
+            0 => solution,
+            _ => solution.with_sigmas(input.f64s(n_arguments)),
+        }
+    };
+    (parent(), parent())
+}
+
+/// Recombine two arbitrary parents with an arbitrary `CrossoverOperator`, including
+/// parents of different lengths and parameters that are `NaN` or infinite. The target
+/// passes as long as nothing panics.
+///
+/// # Arguments
+///
+/// * `data` - The fuzz input.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::fuzzing;
+///
+/// fuzzing::crossover(&[]);
+/// fuzzing::crossover(&[3, 255, 255, 255, 255, 255, 255, 255, 127]);
+/// ```
+pub fn crossover(data: &[u8]) {
+    let mut input = Input { data };
+    let mut rng = SplitMix64::new(input.u64());
+    let operator = match input.u8() % 5 {
+        0 => CrossoverOperator::Average,
+        1 => CrossoverOperator::Blend { alpha: input.f64() },
+        2 => CrossoverOperator::Uniform {
+            swap_prob: input.f64(),
+        },
+        3 => CrossoverOperator::KPoint {
+            k: usize::from(input.u8()),
+        },
+        _ => CrossoverOperator::Arithmetic {
+            per_gene: input.u8().is_multiple_of(2),
+        },
+    };
+    let (parent_a, parent_b) = parents(&mut input);
+    if let Ok(child) = parent_a.try_crossover_with_operator(&parent_b, operator, &mut rng) {
+        assert_eq!(child.get_arguments().len(), parent_a.get_arguments().len());
+    }
+    let n_arguments = usize::from(input.u8() % 9);
+    let mismatched = Solution::new(input.f64s(n_arguments));
+    if mismatched.get_arguments().len() != parent_a.get_arguments().len() {
+        assert!(parent_a
+            .try_crossover_with_operator(&mismatched, operator, &mut rng)
+            .is_err());
+    }
+}
+
+/// Mutate an arbitrary solution with an arbitrary `MutationOperator`, an arbitrary jitter
+/// and polynomial mutation within arbitrary bounds. The target passes as long as nothing panics.
+///
+/// # Arguments
+///
+/// * `data` - The fuzz input.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::fuzzing;
+///
+/// fuzzing::mutation(&[]);
+/// fuzzing::mutation(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+/// ```
+pub fn mutation(data: &[u8]) {
+    let mut input = Input { data };
+    let mut rng = SplitMix64::new(input.u64());
+    let prob = input.f32();
+    let operator = match input.u8() % 4 {
+        0 => MutationOperator::Multiplicative,
+        1 => MutationOperator::Gaussian {
+            sigma: input.f64(),
+            gene_prob: input.f64(),
+        },
+        2 => MutationOperator::Cauchy {
+            scale: input.f64(),
+            gene_prob: input.f64(),
+        },
+        _ => MutationOperator::SelfAdaptive {
+            initial_sigma: input.f64(),
+            min_sigma: input.f64(),
+        },
+    };
+    let (solution, _) = parents(&mut input);
+    let n_arguments = solution.get_arguments().len();
+    let mutated = solution
+        .mutate_with_operator(operator, prob, &mut rng)
+        .mutate_with_jitter(prob, input.f64(), &mut rng);
+    assert_eq!(mutated.get_arguments().len(), n_arguments);
+    let eta_m = input.f64();
+    let bounds = (0..n_arguments)
+        .map(|_| input.f64()..input.f64())
+        .collect::<Vec<Range<f64>>>();
+    let mutated = mutated.mutate_polynomial(prob, eta_m, &bounds, &mut rng);
+    assert_eq!(mutated.get_arguments().len(), n_arguments);
+}
+
+/// Read an arbitrary checkpoint document. Documents that are read successfully have to
+/// survive being written and read again. The target passes as long as nothing panics.
+///
+/// # Arguments
+///
+/// * `data` - The fuzz input, read as UTF-8 with invalid sequences replaced.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::fuzzing;
+///
+/// fuzzing::checkpoint(b"{\"population\": [[1e999]]}");
+/// fuzzing::checkpoint("[".repeat(100_000).as_bytes());
+/// ```
+pub fn checkpoint(data: &[u8]) {
+    let input = Input { data }.rest();
+    if let Ok(checkpoint) = Checkpoint::from_json(&input) {
+        assert!(Checkpoint::from_json(&checkpoint.to_json()).is_ok());
+    }
+}
+
+/// Apply an arbitrary config file and arbitrary `field=value` settings. The target passes as
+/// long as nothing panics.
+///
+/// # Arguments
+///
+/// * `data` - The fuzz input, read as UTF-8 with invalid sequences replaced.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::fuzzing;
+///
+/// fuzzing::config(b"seed = 99999999999999999999999\nmerge_policy = \"global-top-k:\"");
+/// ```
+pub fn config(data: &[u8]) {
+    let input = Input { data }.rest();
+    let mut runner_config = RunnerConfig::default();
+    if config::apply_toml(&mut runner_config, &input).is_ok() {
+        let mut reread = RunnerConfig::default();
+        assert!(config::apply_toml(&mut reread, &config::to_toml(&runner_config)).is_ok());
+    }
+    for line in input.lines() {
+        if let Some((field, value)) = line.split_once('=') {
+            let _ = config::set_field(&mut runner_config, field.trim(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::RunRngState;
+    use crate::solutions::Solutions;
+    use rand::{Rng, RngCore};
+
+    #[test]
+    fn targets_survive_random_inputs() {
+        let mut rng = SplitMix64::new(2020);
+        for n_bytes in 0..2_000 {
+            let mut data = vec![0; n_bytes % 200];
+            rng.fill_bytes(&mut data);
+            crossover(&data);
+            mutation(&data);
+            checkpoint(&data);
+            config(&data);
+        }
+    }
+    #[test]
+    fn targets_survive_corrupted_documents() {
+        let documents = [
+            Checkpoint {
+                config: RunnerConfig::default(),
+                interrupted: true,
+                completed_generations: 3,
+                population: Solutions::from(vec![Solution::new(vec![1.0, -2.5e-3])]),
+                best: Some((Solution::new(vec![1.0, -2.5e-3]), 3.0)),
+                generations: Vec::new(),
+                rng_state: RunRngState::default(),
+                islands: Vec::new(),
+            }
+            .to_json(),
+            config::to_toml(&RunnerConfig::default()),
+        ];
+        let alphabet = b"[]{}\",:=#-+.0123456789eE\n\\u ";
+        let mut rng = SplitMix64::new(2020);
+        for _ in 0..500 {
+            for document in &documents {
+                let mut data = document.as_bytes().to_vec();
+                for _ in 0..rng.gen_range(1..4) {
+                    let idx = rng.gen_range(0..data.len());
+                    match rng.gen_range(0..3) {
+                        0 => data[idx] = alphabet[rng.gen_range(0..alphabet.len())],
+                        1 => data.truncate(idx + 1),
+                        _ => data.insert(idx, alphabet[rng.gen_range(0..alphabet.len())]),
+                    }
+                }
+                checkpoint(&data);
+                config(&data);
+            }
+        }
+    }
+    #[test]
+    fn operators_survive_degenerate_solutions() {
+        let mut rng = SplitMix64::new(2020);
+        let empty = Solution::new(Vec::new());
+        assert_eq!(empty.clone().mutate_with_jitter(1.0, 0.1, &mut rng), empty);
+        let infinite = Solution::new(vec![f64::INFINITY]);
+        assert_eq!(
+            infinite.clone().mutate_with_jitter(1.0, 0.1, &mut rng),
+            infinite
+        );
+        let shifted = Solution::new(vec![0.0]).mutate_with_jitter(1.0, f64::INFINITY, &mut rng);
+        assert_ne!(shifted.get_arguments()[0], 0.0);
+    }
+}
@@ -0,0 +1,196 @@
+use crate::solution::{compare_fitness, Solution};
+use std::cmp::Ordering;
+use std::time::Duration;
+
+/// A new best individual found during a run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Improvement {
+    /// The island that found the individual.
+    pub island: usize,
+    /// The generation of the island the individual was found in.
+    pub generation: usize,
+    /// The time since the start of the run.
+    pub elapsed: Duration,
+    /// The new best individual.
+    pub solution: Solution,
+    /// The fitness of the new best individual.
+    pub fitness: f64,
+}
+
+/// The fittest individuals a run has seen, including the ones that didn't survive, and the
+/// history of improvements of the best one.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HallOfFame {
+    /// How many individuals are kept.
+    max_entries: usize,
+    /// The individuals from the fittest to the least fit one.
+    entries: Vec<(Solution, f64)>,
+    /// Every time the best individual changed, in order.
+    improvements: Vec<Improvement>,
+}
+
+impl HallOfFame {
+    /// Create an empty hall of fame.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_entries` - How many individuals are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::hall_of_fame::HallOfFame;
+    ///
+    /// let hall_of_fame = HallOfFame::new(10);
+    /// assert!(hall_of_fame.is_empty());
+    /// ```
+    pub fn new(max_entries: usize) -> Self {
+        HallOfFame {
+            max_entries,
+            ..HallOfFame::default()
+        }
+    }
+    /// Add an individual if it is among the `max_entries` fittest ones. Individuals that are
+    /// already in the hall of fame are ignored. Returns whether the individual was added.
+    ///
+    /// # Arguments
+    ///
+    /// * `solution` - The individual.
+    /// * `fitness` - Its fitness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::hall_of_fame::HallOfFame;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let mut hall_of_fame = HallOfFame::new(1);
+    /// assert!(hall_of_fame.offer(&Solution::new(vec![1.0]), 1.0));
+    /// assert!(!hall_of_fame.offer(&Solution::new(vec![0.0]), 0.0));
+    /// ```
+    pub fn offer(&mut self, solution: &Solution, fitness: f64) -> bool {
+        if self.entries.iter().any(|(entry, _)| entry == solution) {
+            return false;
+        }
+        let index = self
+            .entries
+            .iter()
+            .position(|(entry, entry_fitness)| {
+                compare_fitness((solution, fitness), (entry, *entry_fitness)) == Ordering::Less
+            })
+            .unwrap_or(self.entries.len());
+        if index >= self.max_entries {
+            return false;
+        }
+        self.entries.insert(index, (solution.clone(), fitness));
+        self.entries.truncate(self.max_entries);
+        true
+    }
+    /// Record that the best individual changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `improvement` - The new best individual and when it was found.
+    pub(crate) fn record_improvement(&mut self, improvement: Improvement) {
+        self.improvements.push(improvement);
+    }
+    /// The fittest individual and its fitness, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::hall_of_fame::HallOfFame;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let mut hall_of_fame = HallOfFame::new(2);
+    /// hall_of_fame.offer(&Solution::new(vec![1.0]), 1.0);
+    /// hall_of_fame.offer(&Solution::new(vec![2.0]), 2.0);
+    /// assert_eq!(hall_of_fame.best(), Some((&Solution::new(vec![2.0]), 2.0)));
+    /// ```
+    pub fn best(&self) -> Option<(&Solution, f64)> {
+        self.entries
+            .first()
+            .map(|(solution, fitness)| (solution, *fitness))
+    }
+    /// The individuals from the fittest to the least fit one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::hall_of_fame::HallOfFame;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let mut hall_of_fame = HallOfFame::new(2);
+    /// hall_of_fame.offer(&Solution::new(vec![1.0]), 1.0);
+    /// assert_eq!(hall_of_fame.entries(), &[(Solution::new(vec![1.0]), 1.0)]);
+    /// ```
+    pub fn entries(&self) -> &[(Solution, f64)] {
+        &self.entries
+    }
+    /// Every time a run found a new best individual, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let result = Runner::new(RunnerConfig::default()).run(
+    ///     Solutions::random(5, 1.0..10.0, 2),
+    ///     &Function::new(|x| Ok(x.iter().sum())),
+    /// );
+    /// for improvement in result.hall_of_fame.improvements() {
+    ///     println!("{} in generation {}", improvement.fitness, improvement.generation);
+    /// }
+    /// ```
+    pub fn improvements(&self) -> &[Improvement] {
+        &self.improvements
+    }
+    /// The number of individuals kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::hall_of_fame::HallOfFame;
+    ///
+    /// assert_eq!(HallOfFame::new(3).len(), 0);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    /// Whether no individual is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::hall_of_fame::HallOfFame;
+    ///
+    /// assert!(HallOfFame::new(3).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_fittest_unique_entries() {
+        let mut hall_of_fame = HallOfFame::new(2);
+        for (argument, fitness) in [(1.0, 1.0), (3.0, 3.0), (3.0, 3.0), (2.0, 2.0), (0.5, 0.5)] {
+            hall_of_fame.offer(&Solution::new(vec![argument]), fitness);
+        }
+        assert_eq!(
+            hall_of_fame.entries(),
+            &[
+                (Solution::new(vec![3.0]), 3.0),
+                (Solution::new(vec![2.0]), 2.0)
+            ]
+        );
+        assert!(!hall_of_fame.offer(&Solution::new(vec![f64::NAN]), f64::NAN));
+        assert!(HallOfFame::new(0).entries().is_empty());
+    }
+}
@@ -0,0 +1,637 @@
+use crate::pipeline::OperatorError;
+use crate::runner::{OptimizationResult, Reconfiguration};
+use crate::solution::{compare_fitness, Solution};
+use std::cmp::Ordering as CmpOrdering;
+use std::fmt;
+use std::panic;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+
+/// How far a spawned run got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// How many generations were evolved so far, summed over all islands.
+    pub n_evolved_generations: usize,
+    /// How many generations the run evolves, `RunnerConfig::n_generations`.
+    pub n_generations: usize,
+    /// Whether the run is paused, or will pause after the current generation.
+    pub paused: bool,
+    /// Whether the run ended, because all generations were evolved or it was stopped.
+    pub finished: bool,
+}
+
+impl Progress {
+    /// The share of the generations evolved so far, in `[0, 1]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::handle::Progress;
+    ///
+    /// let progress = Progress {
+    ///     n_evolved_generations: 25,
+    ///     n_generations: 100,
+    ///     paused: false,
+    ///     finished: false,
+    /// };
+    /// assert_eq!(progress.fraction(), 0.25);
+    /// ```
+    pub fn fraction(&self) -> f64 {
+        if self.n_generations == 0 {
+            return 1.0;
+        }
+        (self.n_evolved_generations as f64 / self.n_generations as f64).min(1.0)
+    }
+}
+
+/// Why `RunHandle::reconfigure` refused a reconfiguration.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReconfigurationError {
+    /// The probability of mutating an offspring isn't within `[0, 1]`.
+    MutateProb(f32),
+    /// The run has no pipeline with this index.
+    Pipeline {
+        /// The requested pipeline.
+        pipeline: usize,
+        /// The number of pipelines of the run, including the fallback pipelines.
+        n_pipelines: usize,
+    },
+}
+
+impl fmt::Display for ReconfigurationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReconfigurationError::MutateProb(mutate_prob) => write!(
+                f,
+                "The mutation probability must be within [0, 1], got {}",
+                mutate_prob
+            ),
+            ReconfigurationError::Pipeline {
+                pipeline,
+                n_pipelines,
+            } => write!(
+                f,
+                "There is no pipeline {}, the run has {} pipelines",
+                pipeline, n_pipelines
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReconfigurationError {}
+
+/// The state a spawned run shares with its `RunHandle`. Islands report to it after every
+/// generation and wait there while the run is paused.
+#[derive(Debug)]
+pub(crate) struct RunControl {
+    /// How many generations the run evolves.
+    n_generations: usize,
+    /// The number of pipelines of the run, including the fallback pipelines.
+    n_pipelines: usize,
+    /// How many generations were evolved so far, summed over all islands.
+    n_evolved_generations: AtomicUsize,
+    /// The fittest individual of all islands so far.
+    best: Mutex<Option<(Solution, f64)>>,
+    /// Whether the islands should wait after their current generation.
+    paused: Mutex<bool>,
+    /// How many islands the run evolves, `0` until they were created.
+    n_islands: AtomicUsize,
+    /// How many islands wait for the run to be resumed, only changed while `paused` is locked.
+    n_waiting: AtomicUsize,
+    /// How many islands evolved all of their generations.
+    n_ended: AtomicUsize,
+    /// Wakes up waiting islands once the run is resumed or stopped.
+    resumed: Condvar,
+    /// Whether the run should end after the current generation.
+    stopped: AtomicBool,
+    /// Whether the run ended.
+    finished: AtomicBool,
+    /// The reconfigurations requested so far, every island applies them in order.
+    reconfigurations: Mutex<Vec<Reconfiguration>>,
+}
+
+impl RunControl {
+    /// Create the control of a run that didn't start yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_generations` - How many generations the run evolves.
+    /// * `n_pipelines` - The number of pipelines of the run, including the fallback pipelines.
+    pub(crate) fn new(n_generations: usize, n_pipelines: usize) -> Self {
+        RunControl {
+            n_generations,
+            n_pipelines,
+            n_evolved_generations: AtomicUsize::new(0),
+            best: Mutex::new(None),
+            paused: Mutex::new(false),
+            n_islands: AtomicUsize::new(0),
+            n_waiting: AtomicUsize::new(0),
+            n_ended: AtomicUsize::new(0),
+            resumed: Condvar::new(),
+            stopped: AtomicBool::new(false),
+            finished: AtomicBool::new(false),
+            reconfigurations: Mutex::new(Vec::new()),
+        }
+    }
+    /// Record that an island evolved a generation, wait while the run is paused and return
+    /// whether the run should go on.
+    ///
+    /// # Arguments
+    ///
+    /// * `best` - The fittest individual of the island after the generation, if any.
+    pub(crate) fn generation_finished(&self, best: Option<(Solution, f64)>) -> bool {
+        self.n_evolved_generations.fetch_add(1, Ordering::SeqCst);
+        if let Some((solution, fitness)) = best {
+            let mut current = lock(&self.best);
+            let improves = current.as_ref().is_none_or(|(best, best_fitness)| {
+                compare_fitness((&solution, fitness), (best, *best_fitness)) == CmpOrdering::Less
+            });
+            if improves {
+                *current = Some((solution, fitness));
+            }
+        }
+        let mut paused = lock(&self.paused);
+        if *paused && !self.is_stopped() {
+            self.n_waiting.fetch_add(1, Ordering::SeqCst);
+            while *paused && !self.is_stopped() {
+                paused = self
+                    .resumed
+                    .wait(paused)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+            }
+            self.n_waiting.fetch_sub(1, Ordering::SeqCst);
+        }
+        !self.is_stopped()
+    }
+    /// Record how many islands the run evolves, before any of them starts evolving.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_islands` - The number of islands.
+    pub(crate) fn islands_created(&self, n_islands: usize) {
+        self.n_islands.store(n_islands, Ordering::SeqCst);
+    }
+    /// Record that an island evolved all of its generations.
+    pub(crate) fn island_ended(&self) {
+        self.n_ended.fetch_add(1, Ordering::SeqCst);
+    }
+    /// Whether the run is paused and every island either waits or ended.
+    fn is_halted(&self) -> bool {
+        let paused = lock(&self.paused);
+        let n_islands = self.n_islands.load(Ordering::SeqCst);
+        *paused
+            && n_islands > 0
+            && self.n_waiting.load(Ordering::SeqCst) + self.n_ended.load(Ordering::SeqCst)
+                == n_islands
+    }
+    /// The reconfigurations an island didn't apply yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_applied` - How many reconfigurations the island applied so far.
+    pub(crate) fn reconfigurations_since(&self, n_applied: usize) -> Vec<Reconfiguration> {
+        lock(&self.reconfigurations)
+            .get(n_applied..)
+            .unwrap_or_default()
+            .to_vec()
+    }
+    /// Whether the run should end after the current generation.
+    pub(crate) fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+    /// Record that the run ended.
+    fn finish(&self) {
+        self.finished.store(true, Ordering::SeqCst);
+    }
+    /// Pause or resume the run.
+    ///
+    /// # Arguments
+    ///
+    /// * `paused` - Whether the islands should wait after their current generation.
+    fn set_paused(&self, paused: bool) {
+        *lock(&self.paused) = paused;
+        self.resumed.notify_all();
+    }
+}
+
+/// Lock a mutex of the control, a panicking island can't leave its state inconsistent.
+///
+/// # Arguments
+///
+/// * `mutex` - The mutex to lock.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Records that a run ended once dropped, also when the run panicked.
+struct FinishOnDrop(Arc<RunControl>);
+
+impl Drop for FinishOnDrop {
+    fn drop(&mut self) {
+        self.0.finish();
+    }
+}
+
+/// Controls a run started by `Runner::spawn` on its own thread. The handle can be shared with
+/// other threads, e.g. of a UI or a service, through an `Arc`; only `join` needs to own it.
+#[derive(Debug)]
+pub struct RunHandle {
+    /// The state shared with the islands.
+    control: Arc<RunControl>,
+    /// The thread the run evolves on.
+    thread: JoinHandle<Result<OptimizationResult, OperatorError>>,
+}
+
+impl RunHandle {
+    /// Start `run` on its own thread, controlled by `control`.
+    ///
+    /// # Arguments
+    ///
+    /// * `control` - The state the run reports to.
+    /// * `run` - Evolves the population.
+    pub(crate) fn spawn<F>(control: Arc<RunControl>, run: F) -> Self
+    where
+        F: FnOnce() -> Result<OptimizationResult, OperatorError> + Send + 'static,
+    {
+        let finished = FinishOnDrop(Arc::clone(&control));
+        RunHandle {
+            control,
+            thread: std::thread::spawn(move || {
+                let _finished = finished;
+                run()
+            }),
+        }
+    }
+    /// The fittest individual found so far and its fitness, `None` before the first
+    /// generation was evolved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let handle = Runner::new(RunnerConfig::default()).spawn(
+    ///     Solutions::random(5, 1.0..10.0, 2),
+    ///     Function::new(|x| Ok(x.iter().sum())),
+    /// );
+    /// let result = handle.join().unwrap();
+    /// // Once the run ended, it is the best individual of the whole run.
+    /// assert!(result.best.is_some());
+    /// ```
+    pub fn current_best(&self) -> Option<(Solution, f64)> {
+        lock(&self.control.best).clone()
+    }
+    /// How far the run got.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let handle = Runner::new(RunnerConfig::default()).spawn(
+    ///     Solutions::random(5, 1.0..10.0, 2),
+    ///     Function::new(|x| Ok(x.iter().sum())),
+    /// );
+    /// println!("{:.0}% done", 100.0 * handle.progress().fraction());
+    /// ```
+    pub fn progress(&self) -> Progress {
+        Progress {
+            n_evolved_generations: self.control.n_evolved_generations.load(Ordering::SeqCst),
+            n_generations: self.control.n_generations,
+            paused: *lock(&self.control.paused),
+            finished: self.is_finished(),
+        }
+    }
+    /// Pause the run after the generation every island is currently evolving.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let handle = Runner::new(RunnerConfig::default()).spawn(
+    ///     Solutions::random(5, 1.0..10.0, 2),
+    ///     Function::new(|x| Ok(x.iter().sum())),
+    /// );
+    /// handle.pause();
+    /// assert!(handle.progress().paused);
+    /// handle.resume();
+    /// ```
+    pub fn pause(&self) {
+        self.control.set_paused(true);
+    }
+    /// Whether the run is paused and every island finished the generation it was evolving
+    /// when pausing, so the run makes no progress until it is resumed or stopped. Unlike
+    /// `Progress::paused`, it isn't set right away by `pause`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let handle = Runner::new(RunnerConfig::default()).spawn(
+    ///     Solutions::random(5, 1.0..10.0, 2),
+    ///     Function::new(|x| Ok(x.iter().sum())),
+    /// );
+    /// handle.pause();
+    /// while !handle.is_paused() {
+    ///     std::thread::yield_now();
+    /// }
+    /// let progress = handle.progress();
+    /// assert_eq!(handle.progress(), progress);
+    /// handle.resume();
+    /// assert!(!handle.is_paused());
+    /// ```
+    pub fn is_paused(&self) -> bool {
+        self.control.is_halted()
+    }
+    /// Continue a paused run.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let handle = Runner::new(RunnerConfig::default()).spawn(
+    ///     Solutions::random(5, 1.0..10.0, 2),
+    ///     Function::new(|x| Ok(x.iter().sum())),
+    /// );
+    /// handle.pause();
+    /// handle.resume();
+    /// assert!(!handle.progress().paused);
+    /// ```
+    pub fn resume(&self) {
+        self.control.set_paused(false);
+    }
+    /// Change the operator settings of the run. Every island evolves its next generation with
+    /// the changed settings, so changes made while the run is paused take effect as soon as it
+    /// is resumed. The changes are recorded in `OptimizationResult::reconfigurations`.
+    ///
+    /// # Arguments
+    ///
+    /// * `reconfiguration` - The settings to change.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Reconfiguration, Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let handle = Runner::new(RunnerConfig::default()).spawn(
+    ///     Solutions::random(5, 1.0..10.0, 2),
+    ///     Function::new(|x| Ok(x.iter().sum())),
+    /// );
+    /// handle.pause();
+    /// handle
+    ///     .reconfigure(Reconfiguration {
+    ///         mutate_prob: Some(0.05),
+    ///         ..Reconfiguration::default()
+    ///     })
+    ///     .unwrap();
+    /// handle.resume();
+    /// // Every island that evolved on records when it applied the changes.
+    /// println!("{:?}", handle.join().unwrap().reconfigurations);
+    /// ```
+    pub fn reconfigure(
+        &self,
+        reconfiguration: Reconfiguration,
+    ) -> Result<(), ReconfigurationError> {
+        if let Some(mutate_prob) = reconfiguration.mutate_prob {
+            if !(0.0..=1.0).contains(&mutate_prob) {
+                return Err(ReconfigurationError::MutateProb(mutate_prob));
+            }
+        }
+        if let Some(pipeline) = reconfiguration.pipeline {
+            if pipeline >= self.control.n_pipelines {
+                return Err(ReconfigurationError::Pipeline {
+                    pipeline,
+                    n_pipelines: self.control.n_pipelines,
+                });
+            }
+        }
+        lock(&self.control.reconfigurations).push(reconfiguration);
+        Ok(())
+    }
+    /// End the run after the current generation, also if it is paused. The result is marked
+    /// as `interrupted`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let handle = Runner::new(RunnerConfig {
+    ///     n_generations: 1_000_000,
+    ///     ..RunnerConfig::default()
+    /// })
+    /// .spawn(
+    ///     Solutions::random(5, 1.0..10.0, 2),
+    ///     Function::new(|x| Ok(x.iter().sum())),
+    /// );
+    /// handle.stop();
+    /// assert!(handle.join().unwrap().interrupted);
+    /// ```
+    pub fn stop(&self) {
+        self.control.stopped.store(true, Ordering::SeqCst);
+        // Wake up paused islands so they can end.
+        let _paused = lock(&self.control.paused);
+        self.control.resumed.notify_all();
+    }
+    /// Whether the run ended.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let handle = Runner::new(RunnerConfig::default()).spawn(
+    ///     Solutions::random(5, 1.0..10.0, 2),
+    ///     Function::new(|x| Ok(x.iter().sum())),
+    /// );
+    /// while !handle.is_finished() {
+    ///     std::thread::yield_now();
+    /// }
+    /// ```
+    pub fn is_finished(&self) -> bool {
+        self.control.finished.load(Ordering::SeqCst)
+    }
+    /// Wait for the run to end and return its result, like `Runner::try_run`. A panic of the
+    /// run is propagated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let handle = Runner::new(RunnerConfig::default()).spawn(
+    ///     Solutions::random(5, 1.0..10.0, 2),
+    ///     Function::new(|x| Ok(x.iter().sum())),
+    /// );
+    /// assert!(!handle.join().unwrap().interrupted);
+    /// ```
+    pub fn join(self) -> Result<OptimizationResult, OperatorError> {
+        self.thread
+            .join()
+            .unwrap_or_else(|payload| panic::resume_unwind(payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReconfigurationError;
+    use crate::function::Function;
+    use crate::runner::{Generation, Reconfiguration, Runner, RunnerConfig};
+    use crate::solutions::Solutions;
+    use crate::test_objects;
+    use std::panic;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn paused_runs_make_no_progress_until_resumed() {
+        let handle = Runner::new(RunnerConfig {
+            n_generations: 1_000_000,
+            size_generation: 5,
+            ..RunnerConfig::default()
+        })
+        .spawn(
+            Solutions::random(5, 1.0..10.0, 3),
+            Function::new(test_objects::triple_multiplication()),
+        );
+        while handle.progress().n_evolved_generations == 0 {
+            thread::yield_now();
+        }
+        handle.pause();
+        // The generation that was evolving when pausing is still finished.
+        while !handle.is_paused() {
+            thread::yield_now();
+        }
+        let paused = handle.progress();
+        for _ in 0..1_000 {
+            thread::yield_now();
+            assert_eq!(handle.progress(), paused);
+        }
+        assert!(paused.paused && !paused.finished);
+        assert!(handle.current_best().is_some());
+        handle.resume();
+        while handle.progress().n_evolved_generations == paused.n_evolved_generations {
+            thread::yield_now();
+        }
+        handle.pause();
+        handle.stop();
+        let result = handle.join().unwrap();
+        assert!(result.interrupted);
+        assert!(result.n_evolved_generations < 1_000_000);
+    }
+    #[test]
+    fn reconfigurations_take_effect_in_the_next_generation() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen_in_predicate = Arc::clone(&seen);
+        let handle = Runner::new(RunnerConfig {
+            n_generations: 1_000_000,
+            size_generation: 5,
+            mutate_prob: 0.5,
+            ..RunnerConfig::default()
+        })
+        .with_stopping_predicate(move |generation: &Generation| {
+            seen_in_predicate.lock().unwrap().push((
+                generation.island,
+                generation.index,
+                generation.settings.mutate_prob,
+            ));
+            false
+        })
+        .spawn(
+            Solutions::random(5, 1.0..10.0, 3),
+            Function::new(test_objects::triple_multiplication()),
+        );
+        while handle.progress().n_evolved_generations == 0 {
+            thread::yield_now();
+        }
+        handle.pause();
+        assert_eq!(
+            handle.reconfigure(Reconfiguration {
+                pipeline: Some(1),
+                ..Reconfiguration::default()
+            }),
+            Err(ReconfigurationError::Pipeline {
+                pipeline: 1,
+                n_pipelines: 1
+            })
+        );
+        handle
+            .reconfigure(Reconfiguration {
+                mutate_prob: Some(0.1),
+                ..Reconfiguration::default()
+            })
+            .unwrap();
+        handle.resume();
+        while !seen
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|(_, _, mutate_prob)| *mutate_prob == 0.1)
+        {
+            thread::yield_now();
+        }
+        handle.stop();
+        let result = handle.join().unwrap();
+        assert!(!result.reconfigurations.is_empty());
+        let seen = seen.lock().unwrap();
+        for reconfigured in &result.reconfigurations {
+            assert!(reconfigured.generation > 0);
+            assert_eq!(reconfigured.changes.mutate_prob, Some(0.1));
+            for (_, index, mutate_prob) in seen
+                .iter()
+                .filter(|(island, _, _)| *island == reconfigured.island)
+            {
+                let expected = if *index < reconfigured.generation {
+                    0.5
+                } else {
+                    0.1
+                };
+                assert_eq!(*mutate_prob, expected);
+            }
+        }
+    }
+    #[test]
+    fn panicking_runs_are_finished() {
+        let handle = Runner::new(RunnerConfig {
+            n_generations: 1_000_000,
+            size_generation: 5,
+            ..RunnerConfig::default()
+        })
+        .with_stopping_predicate(|_: &Generation| panic!("The predicate failed"))
+        .spawn(
+            Solutions::random(5, 1.0..10.0, 3),
+            Function::new(test_objects::triple_multiplication()),
+        );
+        let start = Instant::now();
+        while !handle.is_finished() && start.elapsed() < Duration::from_secs(60) {
+            thread::yield_now();
+        }
+        assert!(handle.is_finished());
+        assert!(panic::catch_unwind(panic::AssertUnwindSafe(|| handle.join())).is_err());
+    }
+}
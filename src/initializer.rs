@@ -0,0 +1,299 @@
+use crate::evaluation::Evaluator;
+use crate::solution::Solution;
+use crate::solutions::Solutions;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// How the points of an initial population are spread over the bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Sampling {
+    /// Every argument is drawn uniformly from its bounds.
+    #[default]
+    Uniform,
+    /// Latin hypercube sampling: the bounds of every argument are split into as many equally
+    /// wide strata as there are points and every stratum holds exactly one of them, so even
+    /// small populations cover the whole range of every argument.
+    LatinHypercube,
+}
+
+/// Draws the initial population of a run within known bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Initializer {
+    /// How the points are spread over the bounds.
+    pub sampling: Sampling,
+    /// Opposition-based learning: evaluate every sampled point and its `opposite` and keep the
+    /// fitter one. Costs twice the evaluations of the initial population, but often gives a
+    /// head start because one of the two is usually closer to the optimum.
+    pub opposition: bool,
+}
+
+impl Initializer {
+    /// Draw `n_solutions` points within `bounds`, without evaluating them.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_solutions` - The number of points.
+    /// * `bounds` - The bounds of every argument.
+    /// * `rng` - The random number generator to sample with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::initializer::{Initializer, Sampling};
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let initializer = Initializer {
+    ///     sampling: Sampling::LatinHypercube,
+    ///     ..Initializer::default()
+    /// };
+    /// let points = initializer.sample(4, &[0.0..1.0, -5.0..5.0], &mut StdRng::seed_from_u64(0));
+    /// assert_eq!(points.len(), 4);
+    /// ```
+    pub fn sample<G>(&self, n_solutions: usize, bounds: &[Range<f64>], rng: &mut G) -> Vec<Solution>
+    where
+        G: Rng + ?Sized,
+    {
+        match self.sampling {
+            Sampling::Uniform => (0..n_solutions).map(|_| uniform(bounds, rng)).collect(),
+            Sampling::LatinHypercube => {
+                let strata = bounds
+                    .iter()
+                    .map(|_| {
+                        let mut strata = (0..n_solutions).collect::<Vec<usize>>();
+                        strata.shuffle(rng);
+                        strata
+                    })
+                    .collect::<Vec<Vec<usize>>>();
+                (0..n_solutions)
+                    .map(|idx| {
+                        Solution::new(
+                            bounds
+                                .iter()
+                                .zip(&strata)
+                                .map(|(bound, strata)| {
+                                    let offset = (strata[idx] as f64 + rng.gen_range(0.0..1.0))
+                                        / n_solutions as f64;
+                                    bound.start + offset * (bound.end - bound.start)
+                                })
+                                .collect(),
+                        )
+                    })
+                    .collect()
+            }
+        }
+    }
+    /// Draw an initial population of `n_solutions` individuals within `bounds`. With
+    /// `opposition` the sampled points and their opposites are evaluated as a single chunk;
+    /// failed evaluations count as less fit than any other. The kept individuals are evaluated
+    /// again in the first generation of the run.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_solutions` - The size of the population.
+    /// * `bounds` - The bounds of every argument.
+    /// * `evaluator` - The evaluator whose fitness should be maximized.
+    /// * `rng` - The random number generator to sample with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::initializer::{Initializer, Sampling};
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_traits::Population;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let function = Function::new(|x| Ok(-x.iter().map(|x| (x - 2.0).powi(2)).sum::<f64>()));
+    /// let initial_population = Initializer {
+    ///     sampling: Sampling::LatinHypercube,
+    ///     opposition: true,
+    /// }
+    /// .initialize(10, &vec![-5.0..5.0; 3], &function, &mut StdRng::seed_from_u64(1));
+    /// assert_eq!(initial_population.iter().count(), 10);
+    /// let result = Runner::new(RunnerConfig::default()).run(initial_population, &function);
+    /// ```
+    pub fn initialize<E, G>(
+        &self,
+        n_solutions: usize,
+        bounds: &[Range<f64>],
+        evaluator: &E,
+        rng: &mut G,
+    ) -> Solutions
+    where
+        E: Evaluator + ?Sized,
+        G: Rng + ?Sized,
+    {
+        let mut points = self.sample(n_solutions, bounds, rng);
+        if self.opposition {
+            let opposites = points
+                .iter()
+                .map(|point| opposite(point, bounds))
+                .collect::<Vec<Solution>>();
+            let fitness = evaluator
+                .evaluate_chunk(&[points.as_slice(), opposites.as_slice()].concat())
+                .into_iter()
+                .map(|fitness| fitness.unwrap_or(f64::NEG_INFINITY))
+                .collect::<Vec<f64>>();
+            points = points
+                .into_iter()
+                .zip(opposites)
+                .enumerate()
+                .map(|(idx, (point, opposite))| {
+                    if fitness[n_solutions + idx] > fitness[idx] {
+                        opposite
+                    } else {
+                        point
+                    }
+                })
+                .collect();
+        }
+        // Points that coincide at the precision of `Solution` are replaced by uniform ones.
+        let mut unique = points.into_iter().collect::<HashSet<Solution>>();
+        while unique.len() < n_solutions {
+            unique.insert(uniform(bounds, rng));
+        }
+        Solutions::from(unique.into_iter().collect::<Vec<Solution>>())
+    }
+}
+
+/// The opposite point within `bounds`, `lower + upper - x` for every argument `x`. Arguments
+/// without bounds are kept.
+///
+/// # Arguments
+///
+/// * `solution` - The point to mirror.
+/// * `bounds` - The bounds of every argument.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::initializer::opposite;
+/// use genetic_algorithm_fn::solution::Solution;
+///
+/// assert_eq!(
+///     opposite(&Solution::new(vec![1.0, 3.0]), &[0.0..10.0, -5.0..5.0]),
+///     Solution::new(vec![9.0, -3.0])
+/// );
+/// ```
+pub fn opposite(solution: &Solution, bounds: &[Range<f64>]) -> Solution {
+    let mut arguments = solution.get_arguments();
+    for (argument, bound) in arguments.iter_mut().zip(bounds) {
+        *argument = bound.start + bound.end - *argument;
+    }
+    Solution::new(arguments)
+}
+
+/// A quasi-opposite point within `bounds`: every argument is drawn uniformly between the
+/// center of its bounds and its opposite, see `opposite`. Arguments without bounds are kept.
+///
+/// # Arguments
+///
+/// * `solution` - The point to mirror.
+/// * `bounds` - The bounds of every argument.
+/// * `rng` - The random number generator to sample with.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::initializer::quasi_opposite;
+/// use genetic_algorithm_fn::solution::Solution;
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+///
+/// let point = quasi_opposite(
+///     &Solution::new(vec![1.0]),
+///     &[0.0..10.0],
+///     &mut StdRng::seed_from_u64(0),
+/// );
+/// // Between the center 5 and the opposite 9.
+/// assert!((5.0..=9.0).contains(&point.get_arguments()[0]));
+/// ```
+pub fn quasi_opposite<G>(solution: &Solution, bounds: &[Range<f64>], rng: &mut G) -> Solution
+where
+    G: Rng + ?Sized,
+{
+    let mut arguments = solution.get_arguments();
+    for (argument, bound) in arguments.iter_mut().zip(bounds) {
+        let center = (bound.start + bound.end) / 2.0;
+        let opposite = bound.start + bound.end - *argument;
+        *argument = center + rng.gen_range(0.0..=1.0) * (opposite - center);
+    }
+    Solution::new(arguments)
+}
+
+/// Draw every argument uniformly from its bounds, empty bounds give their lower end.
+///
+/// # Arguments
+///
+/// * `bounds` - The bounds of every argument.
+/// * `rng` - The random number generator to sample with.
+pub(crate) fn uniform<G>(bounds: &[Range<f64>], rng: &mut G) -> Solution
+where
+    G: Rng + ?Sized,
+{
+    Solution::new(
+        bounds
+            .iter()
+            .map(|bound| {
+                if bound.end > bound.start {
+                    rng.gen_range(bound.clone())
+                } else {
+                    bound.start
+                }
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Initializer, Sampling};
+    use crate::function::Function;
+    use genetic_algorithm_traits::Population;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn latin_hypercube_fills_every_stratum_once() {
+        let bounds = [0.0..1.0, 10.0..20.0];
+        let points = Initializer {
+            sampling: Sampling::LatinHypercube,
+            opposition: false,
+        }
+        .sample(8, &bounds, &mut StdRng::seed_from_u64(3));
+        for (dimension, bound) in bounds.iter().enumerate() {
+            let mut strata = points
+                .iter()
+                .map(|point| {
+                    ((point.get_arguments()[dimension] - bound.start) / (bound.end - bound.start)
+                        * 8.0) as usize
+                })
+                .collect::<Vec<usize>>();
+            strata.sort_unstable();
+            assert_eq!(strata, (0..8).collect::<Vec<usize>>());
+        }
+    }
+    #[test]
+    fn opposition_keeps_the_fitter_of_every_pair() {
+        // A point and its opposite within [0, 1] sum to the dimension, so the fitter one of
+        // every pair sums to at least half of it.
+        let population = Initializer {
+            sampling: Sampling::Uniform,
+            opposition: true,
+        }
+        .initialize(
+            20,
+            &vec![0.0..1.0; 4],
+            &Function::new(|x| Ok(x.iter().sum())),
+            &mut StdRng::seed_from_u64(5),
+        );
+        assert_eq!(population.iter().count(), 20);
+        assert!(population
+            .iter()
+            .all(|solution| solution.get_arguments().iter().sum::<f64>() >= 2.0));
+    }
+}
@@ -0,0 +1,235 @@
+use crate::checkpoint::{Checkpoint, CheckpointError};
+use crate::evaluation::Evaluator;
+use crate::runner::{OptimizationResult, Runner};
+use crate::solutions::Solutions;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+/// The flag set by the Ctrl-C handler. Only accessed through atomic operations, which is all
+/// a signal handler may do.
+static CTRLC: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+#[cfg(unix)]
+extern "C" fn handle_sigint(_: libc::c_int) {
+    if let Some(flag) = CTRLC.get() {
+        flag.store(true, Ordering::SeqCst);
+    }
+}
+
+/// An installed Ctrl-C (SIGINT) handler. The first Ctrl-C only sets its flag, a second one
+/// terminates the process. Dropping the handler restores the one that was installed before.
+pub struct CtrlcHandler {
+    /// The flag set on Ctrl-C.
+    flag: Arc<AtomicBool>,
+    /// The action that was installed for SIGINT before.
+    #[cfg(unix)]
+    previous: libc::sigaction,
+}
+
+impl CtrlcHandler {
+    /// The flag set on Ctrl-C, to be passed to `Runner::with_interrupt`.
+    pub fn flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.flag)
+    }
+}
+
+impl std::fmt::Debug for CtrlcHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CtrlcHandler")
+            .field("flag", &self.flag)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for CtrlcHandler {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        unsafe {
+            libc::sigaction(libc::SIGINT, &self.previous, std::ptr::null_mut());
+        }
+    }
+}
+
+/// Install a Ctrl-C (SIGINT) handler until the returned guard is dropped. All handlers share
+/// the same flag, which is cleared on installation so a Ctrl-C of a previous run doesn't end
+/// the next one. On platforms other than unix no handler is installed and the flag is never
+/// set.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::interrupt;
+/// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+///
+/// let handler = interrupt::ctrlc_handler();
+/// let runner = Runner::new(RunnerConfig::default()).with_interrupt(handler.flag());
+/// ```
+pub fn ctrlc_handler() -> CtrlcHandler {
+    let flag = Arc::clone(CTRLC.get_or_init(|| Arc::new(AtomicBool::new(false))));
+    flag.store(false, Ordering::SeqCst);
+    #[cfg(unix)]
+    let previous = unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = handle_sigint as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        // Resetting to the default action lets a second Ctrl-C kill the process as usual.
+        action.sa_flags = libc::SA_RESETHAND;
+        libc::sigemptyset(&mut action.sa_mask);
+        let mut previous: libc::sigaction = std::mem::zeroed();
+        libc::sigaction(libc::SIGINT, &action, &mut previous);
+        previous
+    };
+    CtrlcHandler {
+        flag,
+        #[cfg(unix)]
+        previous,
+    }
+}
+
+/// Run the genetic algorithm until it finishes or Ctrl-C is pressed. On Ctrl-C the current
+/// generation is finished, a checkpoint is written to `checkpoint` and the partial result is
+/// returned.
+///
+/// # Arguments
+///
+/// * `runner` - The runner to run.
+/// * `initial_population` - The population the evolution starts from.
+/// * `evaluator` - The evaluator whose fitness should be maximized.
+/// * `checkpoint` - Where the checkpoint is written to when the run is interrupted.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::function;
+/// use genetic_algorithm_fn::interrupt;
+/// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+/// use genetic_algorithm_fn::solutions::Solutions;
+///
+/// let result = interrupt::run_until_interrupted(
+///     Runner::new(RunnerConfig {
+///         n_generations: 2,
+///         ..RunnerConfig::default()
+///     }),
+///     Solutions::random(5, 1.0..10.0, 2),
+///     &function::Function::new(|x| Ok(x.iter().sum())),
+///     &std::env::temp_dir().join("genetic_algorithm_fn_interrupt_example.json"),
+/// )
+/// .unwrap();
+/// assert!(!result.interrupted);
+/// ```
+pub fn run_until_interrupted<E>(
+    runner: Runner,
+    initial_population: Solutions,
+    evaluator: &E,
+    checkpoint: &Path,
+) -> Result<OptimizationResult, CheckpointError>
+where
+    E: Evaluator + ?Sized,
+{
+    let handler = ctrlc_handler();
+    let mut runner = runner.with_interrupt(handler.flag());
+    let result = runner.run(initial_population, evaluator);
+    drop(handler);
+    if result.interrupted {
+        Checkpoint::from_result(&result, runner.config()).save(checkpoint)?;
+    }
+    Ok(result)
+}
+
+/// Continue a run from a checkpoint until it finishes or Ctrl-C is pressed. The run continues
+/// the random number streams and the islands stored in the checkpoint, see
+/// `Runner::with_rng_state` and `Runner::with_island_states`. On Ctrl-C the current
+/// generation is finished and the checkpoint at `path` is updated. Returns the state after
+/// continuing the run.
+///
+/// # Arguments
+///
+/// * `checkpoint` - The checkpoint to continue from.
+/// * `evaluator` - The evaluator whose fitness should be maximized.
+/// * `path` - Where the checkpoint is written to when the run is interrupted again.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::checkpoint::Checkpoint;
+/// use genetic_algorithm_fn::function;
+/// use genetic_algorithm_fn::interrupt;
+/// use genetic_algorithm_fn::rng::RunRngState;
+/// use genetic_algorithm_fn::runner::RunnerConfig;
+/// use genetic_algorithm_fn::solutions::Solutions;
+///
+/// let checkpoint = Checkpoint {
+///     config: RunnerConfig {
+///         n_generations: 4,
+///         ..RunnerConfig::default()
+///     },
+///     interrupted: true,
+///     completed_generations: 1,
+///     population: Solutions::random(5, 1.0..10.0, 2),
+///     best: None,
+///     generations: Vec::new(),
+///     rng_state: RunRngState::default(),
+///     islands: Vec::new(),
+/// };
+/// let resumed = interrupt::resume_until_interrupted(
+///     checkpoint,
+///     &function::Function::new(|x| Ok(x.iter().sum())),
+///     &std::env::temp_dir().join("genetic_algorithm_fn_resume_example.json"),
+/// )
+/// .unwrap();
+/// assert_eq!(resumed.completed_generations, 4);
+/// ```
+pub fn resume_until_interrupted<E>(
+    checkpoint: Checkpoint,
+    evaluator: &E,
+    path: &Path,
+) -> Result<Checkpoint, CheckpointError>
+where
+    E: Evaluator + ?Sized,
+{
+    let handler = ctrlc_handler();
+    let result = Runner::new(checkpoint.remaining_config())
+        .with_rng_state(checkpoint.rng_state.clone())
+        .with_island_states(checkpoint.islands.clone())
+        .with_interrupt(handler.flag())
+        .run(checkpoint.population.clone(), evaluator);
+    drop(handler);
+    let checkpoint = checkpoint.continue_with(&result);
+    if checkpoint.interrupted {
+        checkpoint.save(path)?;
+    }
+    Ok(checkpoint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    fn sigint_action() -> libc::sighandler_t {
+        unsafe {
+            let mut current: libc::sigaction = std::mem::zeroed();
+            libc::sigaction(libc::SIGINT, std::ptr::null(), &mut current);
+            current.sa_sigaction
+        }
+    }
+
+    // A single test, as tests installing handlers in parallel would restore each other's.
+    #[test]
+    fn handlers_share_the_flag_and_restore_the_previous_one() {
+        #[cfg(unix)]
+        let before = sigint_action();
+        let first = ctrlc_handler();
+        let second = ctrlc_handler();
+        assert!(Arc::ptr_eq(&first.flag(), &second.flag()));
+        drop(second);
+        #[cfg(unix)]
+        assert_eq!(
+            sigint_action(),
+            handle_sigint as extern "C" fn(libc::c_int) as libc::sighandler_t
+        );
+        drop(first);
+        #[cfg(unix)]
+        assert_eq!(sigint_action(), before);
+    }
+}
@@ -0,0 +1,429 @@
+use std::fmt;
+
+/// A JSON value. Objects keep the order their keys were inserted or parsed in.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    /// `null`
+    Null,
+    /// `true` or `false`
+    Bool(bool),
+    /// Any number. Non-finite numbers are written as the strings `"inf"`, `"-inf"` and `"NaN"`.
+    Number(f64),
+    /// A string.
+    String(String),
+    /// An array of values.
+    Array(Vec<Json>),
+    /// An object as a list of key-value pairs.
+    Object(Vec<(String, Json)>),
+}
+
+/// Error that can occur when parsing JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonError {
+    /// The byte offset the error occurred at.
+    pub position: usize,
+    /// What went wrong.
+    pub message: String,
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Invalid JSON at byte {}: {}",
+            self.position, self.message
+        )
+    }
+}
+
+impl Json {
+    /// Look up the value of `key` if `self` is an object.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(entries) => entries
+                .iter()
+                .find(|(entry_key, _)| entry_key == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+    /// Return the number, also accepting the strings non-finite numbers are written as.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Json::Number(number) => Some(*number),
+            Json::String(string) => match string.as_str() {
+                "inf" => Some(f64::INFINITY),
+                "-inf" => Some(f64::NEG_INFINITY),
+                "NaN" => Some(f64::NAN),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+    /// Return the number if it is a non-negative integer.
+    pub fn as_usize(&self) -> Option<usize> {
+        match self {
+            Json::Number(number) if *number >= 0.0 && number.fract() == 0.0 => {
+                Some(*number as usize)
+            }
+            _ => None,
+        }
+    }
+    /// Return the boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Json::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+    /// Return the string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(string) => Some(string),
+            _ => None,
+        }
+    }
+    /// Return the elements of the array.
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+    /// Parse a JSON document.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The document to parse.
+    pub fn parse(input: &str) -> Result<Json, JsonError> {
+        let mut parser = Parser {
+            input: input.as_bytes(),
+            position: 0,
+            depth: 0,
+        };
+        let value = parser.value()?;
+        parser.whitespace();
+        if parser.position != parser.input.len() {
+            return Err(parser.error("Trailing characters"));
+        }
+        Ok(value)
+    }
+}
+
+impl From<f64> for Json {
+    fn from(number: f64) -> Self {
+        Json::Number(number)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(number: usize) -> Self {
+        Json::Number(number as f64)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Json::Bool(value)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(string: &str) -> Self {
+        Json::String(string.to_string())
+    }
+}
+
+impl From<Option<f64>> for Json {
+    fn from(number: Option<f64>) -> Self {
+        number.map_or(Json::Null, Json::Number)
+    }
+}
+
+/// Write `string` as a quoted and escaped JSON string.
+///
+/// # Arguments
+///
+/// * `f` - The formatter to write to.
+/// * `string` - The string to write.
+fn write_string(f: &mut fmt::Formatter, string: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for character in string.chars() {
+        match character {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            character if (character as u32) < 0x20 => write!(f, "\\u{:04x}", character as u32)?,
+            character => write!(f, "{}", character)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(number) if number.is_nan() => write!(f, "\"NaN\""),
+            Json::Number(number) if number.is_infinite() => {
+                write!(f, "\"{}inf\"", if *number < 0.0 { "-" } else { "" })
+            }
+            // Integers are written without a fraction so ids and counts look like integers.
+            Json::Number(number) if number.fract() == 0.0 && number.abs() < 1e15 => {
+                write!(f, "{}", *number as i64)
+            }
+            // `{:?}` prints the shortest representation that round-trips.
+            Json::Number(number) => write!(f, "{:?}", number),
+            Json::String(string) => write_string(f, string),
+            Json::Array(elements) => {
+                write!(f, "[")?;
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(entries) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// How deeply arrays and objects may be nested, so deeply nested documents are rejected
+/// before the recursion overflows the stack.
+const MAX_DEPTH: usize = 128;
+
+/// A recursive descent parser over the bytes of a document.
+struct Parser<'a> {
+    input: &'a [u8],
+    position: usize,
+    /// How many arrays and objects enclose the current position.
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> JsonError {
+        JsonError {
+            position: self.position,
+            message: message.to_string(),
+        }
+    }
+    fn whitespace(&mut self) {
+        while self
+            .input
+            .get(self.position)
+            .is_some_and(|byte| byte.is_ascii_whitespace())
+        {
+            self.position += 1;
+        }
+    }
+    fn expect(&mut self, literal: &str) -> Result<(), JsonError> {
+        if self.input[self.position..].starts_with(literal.as_bytes()) {
+            self.position += literal.len();
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected `{}`", literal)))
+        }
+    }
+    fn value(&mut self) -> Result<Json, JsonError> {
+        self.whitespace();
+        match self.input.get(self.position) {
+            Some(b'n') => self.expect("null").map(|_| Json::Null),
+            Some(b't') => self.expect("true").map(|_| Json::Bool(true)),
+            Some(b'f') => self.expect("false").map(|_| Json::Bool(false)),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b'[' | b'{') if self.depth >= MAX_DEPTH => Err(self.error("Nested too deeply")),
+            Some(b'[') => self.nested(Parser::array),
+            Some(b'{') => self.nested(Parser::object),
+            Some(byte) if *byte == b'-' || byte.is_ascii_digit() => self.number(),
+            _ => Err(self.error("Expected a value")),
+        }
+    }
+    /// Parse a value enclosed by one more array or object.
+    fn nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<Json, JsonError>,
+    ) -> Result<Json, JsonError> {
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+    fn array(&mut self) -> Result<Json, JsonError> {
+        self.position += 1;
+        let mut elements = Vec::new();
+        self.whitespace();
+        if self.input.get(self.position) == Some(&b']') {
+            self.position += 1;
+            return Ok(Json::Array(elements));
+        }
+        loop {
+            elements.push(self.value()?);
+            self.whitespace();
+            match self.input.get(self.position) {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(Json::Array(elements));
+                }
+                _ => return Err(self.error("Expected `,` or `]`")),
+            }
+        }
+    }
+    fn object(&mut self) -> Result<Json, JsonError> {
+        self.position += 1;
+        let mut entries = Vec::new();
+        self.whitespace();
+        if self.input.get(self.position) == Some(&b'}') {
+            self.position += 1;
+            return Ok(Json::Object(entries));
+        }
+        loop {
+            self.whitespace();
+            let key = self.string()?;
+            self.whitespace();
+            self.expect(":")?;
+            entries.push((key, self.value()?));
+            self.whitespace();
+            match self.input.get(self.position) {
+                Some(b',') => self.position += 1,
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(Json::Object(entries));
+                }
+                _ => return Err(self.error("Expected `,` or `}`")),
+            }
+        }
+    }
+    fn number(&mut self) -> Result<Json, JsonError> {
+        let start = self.position;
+        while self
+            .input
+            .get(self.position)
+            .is_some_and(|byte| matches!(byte, b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9'))
+        {
+            self.position += 1;
+        }
+        std::str::from_utf8(&self.input[start..self.position])
+            .ok()
+            .and_then(|number| number.parse::<f64>().ok())
+            .map(Json::Number)
+            .ok_or_else(|| self.error("Invalid number"))
+    }
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.expect("\"")?;
+        let mut bytes = Vec::new();
+        loop {
+            match self.input.get(self.position) {
+                None => return Err(self.error("Unterminated string")),
+                Some(b'"') => {
+                    self.position += 1;
+                    return String::from_utf8(bytes).map_err(|_| self.error("Invalid UTF-8"));
+                }
+                Some(b'\\') => {
+                    self.position += 1;
+                    let escaped = match self.input.get(self.position) {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'u') => {
+                            let code = self
+                                .input
+                                .get(self.position + 1..self.position + 5)
+                                .and_then(|hex| std::str::from_utf8(hex).ok())
+                                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| self.error("Invalid unicode escape"))?;
+                            self.position += 4;
+                            code
+                        }
+                        _ => return Err(self.error("Invalid escape")),
+                    };
+                    self.position += 1;
+                    let mut buffer = [0; 4];
+                    bytes.extend_from_slice(escaped.encode_utf8(&mut buffer).as_bytes());
+                }
+                Some(byte) => {
+                    bytes.push(*byte);
+                    self.position += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let value = Json::Object(vec![
+            (
+                "name".to_string(),
+                Json::String("a \"quoted\"\n".to_string()),
+            ),
+            (
+                "values".to_string(),
+                Json::Array(vec![
+                    Json::Number(0.1),
+                    Json::Number(-3.0),
+                    Json::Number(f64::NEG_INFINITY),
+                    Json::Null,
+                    Json::Bool(true),
+                ]),
+            ),
+            ("empty".to_string(), Json::Object(Vec::new())),
+        ]);
+        let parsed = Json::parse(&value.to_string()).unwrap();
+        assert_eq!(parsed, Json::parse(&parsed.to_string()).unwrap());
+        assert_eq!(parsed.get("name"), value.get("name"));
+        assert_eq!(
+            parsed.get("values").unwrap().as_array().unwrap()[2].as_f64(),
+            Some(f64::NEG_INFINITY)
+        );
+    }
+    #[test]
+    fn parse_handles_whitespace_and_escapes() {
+        let parsed = Json::parse(" { \"a\" : [ 1e3 , \"\\u00e9\" ] } ").unwrap();
+        let values = parsed.get("a").unwrap().as_array().unwrap();
+        assert_eq!(values[0].as_usize(), Some(1000));
+        assert_eq!(values[1], Json::String("é".to_string()));
+    }
+    #[test]
+    fn parse_rejects_deeply_nested_documents() {
+        let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(Json::parse(&nested(MAX_DEPTH)).is_ok());
+        assert_eq!(
+            Json::parse(&nested(MAX_DEPTH + 1)).unwrap_err().position,
+            MAX_DEPTH
+        );
+        // Far deeper than the stack could recurse.
+        assert!(Json::parse(&"[{\"a\":".repeat(1_000_000)).is_err());
+    }
+    #[test]
+    fn parse_rejects_invalid_documents() {
+        assert!(Json::parse("{\"a\": }").is_err());
+        assert!(Json::parse("[1, 2").is_err());
+        assert!(Json::parse("1 2").is_err());
+    }
+}
@@ -3,17 +3,122 @@
 //! # Genetic algorithms for solving TSPs.
 //!
 //! This crates contains utitlities to run genetic algorithms and solve Traveling Salesman Problems.
+//!
+//! The easiest way to optimize a function is the `GeneticAlgorithmBuilder`:
+//!
+//! ```
+//! use genetic_algorithm_fn::builder::GeneticAlgorithmBuilder;
+//! use genetic_algorithm_fn::function::Function;
+//!
+//! let result = GeneticAlgorithmBuilder::new(2)
+//!     .with_population_size(20)
+//!     .with_generations(50)
+//!     .with_initial_range(-10.0..10.0)
+//!     .run(&Function::new(|x| Ok(-x[0].powi(2) - x[1].powi(2))));
+//! println!("{:?}", result.best);
+//! ```
+/// The `archive`-module records every evaluation of every individual, including the fidelity
+/// it was evaluated at.
+pub mod archive;
+/// The `autodiff`-module differentiates objectives written generically over a float type
+/// with dual numbers, so their gradients can assist the search.
+#[cfg(feature = "autodiff")]
+pub mod autodiff;
+/// The `bounds`-module contains the `Bounds` of the search space and the policies that keep
+/// offspring within them.
+pub mod bounds;
+/// The `builder`-module is the entry point for optimizing a function with a few knobs.
+pub mod builder;
+/// The `checkpoint`-module stores the state of a run as JSON so it can be continued later.
+pub mod checkpoint;
+/// The `clock`-module tells the time of time budgets and benchmarks, and can be replaced by a
+/// manually advanced clock in tests.
+pub mod clock;
+/// The `config`-module sets `RunnerConfig` fields from strings, i.e. from command line flags
+/// and `GA_FN_*` environment variables.
+pub mod config;
+/// The `constraint`-module contains constraints on the arguments and Deb's feasibility rules
+/// to compare solutions that violate them.
+pub mod constraint;
+/// The `ensemble`-module runs several algorithms on a shared evaluation budget and shifts
+/// the budget towards the one progressing the most.
+pub mod ensemble;
+/// The `error`-module contains the `Error` returned by the fallible APIs of the crate.
+pub mod error;
+/// The `evaluation`-module contains the `Evaluator`-trait that computes the fitness of
+/// a chunk of solutions at once.
+pub mod evaluation;
+/// Runs the islands of a run and the algorithms of an ensemble, on threads or sequentially.
+mod executor;
+/// The `external`-module contains evaluators that delegate the fitness computation to other
+/// processes or services.
+pub mod external;
 /// Represent a distance Matrix as a Vec<Vec<f64>>.
 pub mod function;
+/// The `fuzzing`-module contains the entry points of the `cargo fuzz` targets in `fuzz/`,
+/// they turn arbitrary bytes into operator calls and documents to parse. Run them with e.g.
+/// `cargo +nightly fuzz run crossover`.
+pub mod fuzzing;
+/// The `hall_of_fame`-module keeps the fittest individuals of a run and records when the best
+/// one improved.
+pub mod hall_of_fame;
+/// The `handle`-module contains the `RunHandle` of a run spawned on a thread of its own, to
+/// watch, pause, resume and stop it from a UI or a service.
+pub mod handle;
+/// The `initializer`-module draws initial populations within bounds, with latin hypercube
+/// sampling and opposition-based learning.
+pub mod initializer;
+/// The `interrupt`-module handles Ctrl-C by finishing the current generation and writing a
+/// checkpoint instead of killing the process mid-run.
+pub mod interrupt;
+/// A minimal JSON reader and writer for checkpoints and results.
+mod json;
+/// The `matrix`-module stores a whole population in one contiguous, row-major `Vec<f64>` and
+/// recombines, mutates and evaluates it over slices, for large populations.
+pub mod matrix;
+/// The `pipeline`-module contains the composable reproduction step
+/// `select parents -> crossover -> mutate -> repair -> filter` used by the `Runner`.
+pub mod pipeline;
+/// The `polish`-module refines the fittest individuals at the end of a run with a local
+/// search.
+pub mod polish;
+/// The `preset`-module contains curated settings for common kinds of problems, so newcomers
+/// don't have to tune the operators themselves.
+pub mod preset;
+/// The `quadratic`-module fits a quadratic model of the fitness around the best solution to
+/// estimate its curvature and a refined optimum.
+pub mod quadratic;
+/// The `rng`-module contains the `SplitMix64` generator used to derive independent random
+/// number streams per island, generation and individual.
+pub mod rng;
+/// The `runner`-module contains the `Runner` that evolves a population according to a
+/// `RunnerConfig` and reports what happened in every generation.
+pub mod runner;
+/// The `schema`-module versions the format of configs, checkpoints and results and
+/// describes what was tolerated when reading documents of other versions.
+pub mod schema;
+/// The `selection`-module holds selection strategies, e.g. tournaments for survivors and
+/// stochastic universal sampling for parents.
+pub mod selection;
+/// The `sensitivity`-module estimates how strongly every argument currently affects the
+/// fitness, and gradients of objectives by finite differences.
+pub mod sensitivity;
 /// The `route`-module contains the `Route`-class, the individual element of the TSP that implements
 /// important methods like `crossover` or `mutate`.
 pub mod solution;
 /// The `routes`-module contains the main class of this crate which is the `Routes`-class that contains
 /// your current subset of routes and with which you can evolve them.
 pub mod solutions;
+/// The `stats`-module keeps statistics of the fitness in constant memory, so they are
+/// available even when the history of a run isn't stored.
+pub mod stats;
+/// The `study`-module aggregates many independent runs of the same problem into statistics
+/// and attainment curves, stored as a single document.
+pub mod study;
 /// Testing functions to optimize.
 pub mod test_functions;
 /// functions to create default objects for testing.
 /// Both for convenience (not having to create them over and over again)
 /// as well as standardization (test against the same common cases).
+#[cfg(test)]
 mod test_objects;
@@ -1,28 +1,64 @@
+use genetic_algorithm_fn::checkpoint::Checkpoint;
+use genetic_algorithm_fn::config;
+use genetic_algorithm_fn::external::{self, HttpEvaluator, JsonLinesEvaluator};
 use genetic_algorithm_fn::function;
+use genetic_algorithm_fn::interrupt;
+use genetic_algorithm_fn::rng;
+use genetic_algorithm_fn::runner::{ObjectiveDirection, Runner, RunnerConfig};
+use genetic_algorithm_fn::schema::SchemaWarning;
 use genetic_algorithm_fn::solutions;
 use genetic_algorithm_fn::test_functions;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::env;
+use std::fs;
+use std::io::{self, BufReader, Write};
+use std::path::PathBuf;
+use std::process;
 
-fn main() {
+const USAGE: &str = "Usage:
+  ga-fn [benchmark]
+  ga-fn run [--config <path>] [--repro <path>] [--checkpoint <path>] [--output <path>]
+            [--<field> <value>]...
+  ga-fn resume <checkpoint> [--output <path>] [--<field> <value>]...
+  ga-fn inspect <checkpoint | result.json>
+  ga-fn serve --protocol jsonl | --protocol http --url <url> [--max-in-flight <n>]
+              [--retries <n>] [--requests-per-second <x>]
+              [--dimension <n>] [--lower <x>] [--upper <x>]
+              [--config <path>] [--checkpoint <path>] [--output <path>] [--<field> <value>]...
+
+Every field of the runner config can be set with a flag (`--n-generations 10`), an
+environment variable (`GA_FN_N_GENERATIONS=10`) or a TOML file passed with `--config`.
+Flags take precedence over environment variables, which take precedence over the file,
+which takes precedence over the defaults. `run --repro <path>` writes a config file that
+replays the run exactly. `--n-jobs auto` evolves one island per available core but one.
+`serve` gives candidates whose evaluation failed the worst fitness unless
+`--failure-penalty` is set.";
+
+/// The key of the random stream the initial population of `run` is drawn from.
+const INITIAL_POPULATION_STREAM: u64 = 0;
+
+/// The Hartman function in three dimensions.
+///
+/// # Arguments
+///
+/// * `x` - The three arguments.
+fn hartman_3_dimensional(x: &[f64]) -> Result<f64, function::FunctionError> {
+    match x {
+        [x, y, z] => Ok(test_functions::hartman_3_dimensional(*x, *y, *z)),
+        _ => Err(function::FunctionError::WrongNumberOfEntries {
+            expected_number_of_entries: 3,
+            actual_number_of_entries: x.len(),
+        }),
+    }
+}
+
+/// Print how long evolving populations of different sizes takes. The benchmarks maximize, so
+/// they evolve the negated Hartman function.
+fn benchmark() {
+    let function_to_optimize =
+        &function::Function::new(|x| hartman_3_dimensional(&x).map(|value| -value));
     let initial_params_range = -150.0..150.0;
-    let function_to_optimize = function::Function::new(|x| {
-        Ok(-test_functions::hartman_3_dimensional(
-            *x.get(0)
-                .ok_or(function::FunctionError::WrongNumberOfEntries {
-                    expected_number_of_entries: 3,
-                    actual_number_of_entries: x.len(),
-                })?,
-            *x.get(1)
-                .ok_or(function::FunctionError::WrongNumberOfEntries {
-                    expected_number_of_entries: 3,
-                    actual_number_of_entries: x.len(),
-                })?,
-            *x.get(2)
-                .ok_or(function::FunctionError::WrongNumberOfEntries {
-                    expected_number_of_entries: 3,
-                    actual_number_of_entries: x.len(),
-                })?,
-        ))
-    });
 
     // Single-threaded test
     for n_generations in (10..=510).step_by(250) {
@@ -30,7 +66,7 @@ fn main() {
             let (run_time, minimal_loss) = solutions::benchmark_population(
                 n_generations,
                 size_generation,
-                &function_to_optimize,
+                function_to_optimize,
                 0,
                 initial_params_range.clone(),
             );
@@ -48,7 +84,7 @@ fn main() {
             let (execution_time, maximal_function_value) = solutions::benchmark_population(
                 n_generations,
                 size_generation,
-                &function_to_optimize,
+                function_to_optimize,
                 n_jobs,
                 initial_params_range.clone(),
             );
@@ -59,3 +95,339 @@ fn main() {
         }
     }
 }
+
+/// The options shared by `run` and `resume`.
+struct RunOptions {
+    /// Where the checkpoint is written to on Ctrl-C.
+    checkpoint: PathBuf,
+    /// Where the result is written to once the run finished.
+    output: Option<PathBuf>,
+}
+
+/// Parse the options of `run` and `resume`, after the config flags have been removed.
+///
+/// # Arguments
+///
+/// * `args` - The arguments to parse.
+/// * `checkpoint` - The default checkpoint path.
+fn parse_run_options(args: &[String], checkpoint: PathBuf) -> Result<RunOptions, String> {
+    let mut options = RunOptions {
+        checkpoint,
+        output: None,
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut path = || {
+            args.next()
+                .map(PathBuf::from)
+                .ok_or(format!("`{}` requires a path", arg))
+        };
+        match arg.as_str() {
+            "--checkpoint" => options.checkpoint = path()?,
+            "--output" => options.output = Some(path()?),
+            other => return Err(format!("Unknown argument `{}`", other)),
+        }
+    }
+    Ok(options)
+}
+
+/// Print what was tolerated while reading a file written by another version to stderr.
+///
+/// # Arguments
+///
+/// * `path` - The file that was read.
+/// * `warnings` - What was tolerated.
+fn print_warnings(path: &str, warnings: &[SchemaWarning]) {
+    for warning in warnings {
+        eprintln!("Warning: {}: {}", path, warning);
+    }
+}
+
+/// Read the runner config from the file given with `--config`, the environment and the
+/// flags, in increasing precedence. Returns the config and the remaining arguments.
+///
+/// # Arguments
+///
+/// * `args` - The arguments of the command.
+/// * `config` - The defaults of the command.
+fn load_config(
+    args: &[String],
+    mut config: RunnerConfig,
+) -> Result<(RunnerConfig, Vec<String>), String> {
+    let mut rest = Vec::new();
+    let mut config_file = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            config_file = Some(args.next().ok_or("`--config` requires a path")?);
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+    if let Some(config_file) = config_file {
+        let toml = fs::read_to_string(config_file)
+            .map_err(|error| format!("Could not read `{}`: {}", config_file, error))?;
+        let warnings = config::apply_toml(&mut config, &toml).map_err(|error| error.to_string())?;
+        print_warnings(config_file, &warnings);
+    }
+    config::apply_env(&mut config, env::vars()).map_err(|error| error.to_string())?;
+    let rest = config::apply_args(&mut config, &rest).map_err(|error| error.to_string())?;
+    Ok((config, rest))
+}
+
+/// Print the outcome of a run and write it to the output, if it finished.
+///
+/// # Arguments
+///
+/// * `checkpoint` - The state after the run.
+/// * `options` - Where the checkpoint and the result are written to.
+/// * `out` - Where the outcome is printed to.
+fn report(
+    checkpoint: &Checkpoint,
+    options: &RunOptions,
+    out: &mut dyn Write,
+) -> Result<(), String> {
+    let print_error = |error: io::Error| error.to_string();
+    if checkpoint.interrupted {
+        writeln!(
+            out,
+            "Interrupted after {} generations, checkpoint written to {}",
+            checkpoint.completed_generations,
+            options.checkpoint.display()
+        )
+        .map_err(print_error)?;
+    } else if let Some(output) = &options.output {
+        checkpoint.save(output).map_err(|error| error.to_string())?;
+    }
+    match &checkpoint.best {
+        Some((solution, fitness)) => writeln!(
+            out,
+            "Best solution: {}, function value: {}",
+            solution,
+            checkpoint.config.direction.value(*fitness)
+        ),
+        None => writeln!(out, "No solution was evaluated"),
+    }
+    .map_err(print_error)
+}
+
+/// Run a single optimization. Ctrl-C finishes the current generation and writes a checkpoint.
+/// The initial population is drawn from the seed, so `--repro` can replay the run.
+///
+/// # Arguments
+///
+/// * `args` - The arguments following `run`.
+/// * `function_to_optimize` - The function to minimize, unless the config maximizes it.
+fn run(args: &[String], function_to_optimize: &function::Function) -> Result<(), String> {
+    let (mut config, args) = load_config(
+        args,
+        RunnerConfig {
+            direction: ObjectiveDirection::Minimize,
+            ..RunnerConfig::default()
+        },
+    )?;
+    let (mut repro, mut rest) = (None, Vec::new());
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--repro" => repro = Some(args.next().ok_or("`--repro` requires a path")?),
+            _ => rest.push(arg),
+        }
+    }
+    let options = parse_run_options(&rest, PathBuf::from("checkpoint.json"))?;
+    let seed = *config.seed.get_or_insert_with(rand::random);
+    let initial_population = solutions::Solutions::random_with_rng(
+        config.size_generation_for(3),
+        -150.0..150.0,
+        3,
+        &mut StdRng::seed_from_u64(rng::stream_seed(seed, &[INITIAL_POPULATION_STREAM])),
+    );
+    let result = interrupt::run_until_interrupted(
+        Runner::new(config.clone())
+            .with_on_health_warning(|warning| eprintln!("Warning: {}", warning)),
+        initial_population,
+        function_to_optimize,
+        &options.checkpoint,
+    )
+    .map_err(|error| error.to_string())?;
+    if let Some(repro) = repro {
+        fs::write(&repro, result.to_repro())
+            .map_err(|error| format!("Could not write `{}`: {}", repro, error))?;
+    }
+    report(
+        &Checkpoint::from_result(&result, &config),
+        &options,
+        &mut io::stdout(),
+    )
+}
+
+/// Continue an interrupted run. The config stored in the checkpoint can be overridden like
+/// for `run`, e.g. to extend the run with `--n-generations`.
+///
+/// # Arguments
+///
+/// * `args` - The arguments following `resume`.
+/// * `function_to_optimize` - The function to optimize in the direction of the checkpoint.
+fn resume(args: &[String], function_to_optimize: &function::Function) -> Result<(), String> {
+    let (path, args) = args.split_first().ok_or("`resume` requires a checkpoint")?;
+    let path = PathBuf::from(path);
+    let (mut checkpoint, warnings) =
+        Checkpoint::load_with_warnings(&path).map_err(|error| error.to_string())?;
+    print_warnings(&path.display().to_string(), &warnings);
+    config::apply_env(&mut checkpoint.config, env::vars()).map_err(|error| error.to_string())?;
+    let args =
+        config::apply_args(&mut checkpoint.config, args).map_err(|error| error.to_string())?;
+    let options = parse_run_options(&args, path)?;
+    let checkpoint =
+        interrupt::resume_until_interrupted(checkpoint, function_to_optimize, &options.checkpoint)
+            .map_err(|error| error.to_string())?;
+    report(&checkpoint, &options, &mut io::stdout())
+}
+
+/// Let an external program evaluate the candidates. With `--protocol jsonl` batches of
+/// candidates are printed as JSON lines on stdout and their fitnesses are read back from
+/// stdin, everything else is printed to stderr so stdout only carries the protocol. With
+/// `--protocol http` the batches are POSTed to `--url`.
+///
+/// # Arguments
+///
+/// * `args` - The arguments following `serve`.
+fn serve(args: &[String]) -> Result<(), String> {
+    let (mut config, args) = load_config(args, RunnerConfig::default())?;
+    // A failed entry only costs its candidate, the evaluator may fail for any of them.
+    config
+        .failure_penalty
+        .get_or_insert(external::FAILURE_PENALTY);
+    let (mut protocol, mut dimension, mut lower, mut upper) = (None, 3, -150.0, 150.0);
+    let (mut url, mut max_in_flight, mut retries, mut requests_per_second) =
+        (None, None, None, None);
+    let mut rest = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("`{}` requires a value", arg));
+        match arg.as_str() {
+            "--protocol" => protocol = Some(value()?),
+            "--url" => url = Some(value()?),
+            "--max-in-flight" => {
+                max_in_flight = Some(
+                    value()?
+                        .parse()
+                        .map_err(|_| "`--max-in-flight` must be a positive number")?,
+                )
+            }
+            "--requests-per-second" => {
+                requests_per_second = Some(
+                    value()?
+                        .parse()
+                        .map_err(|_| "`--requests-per-second` must be a number")?,
+                )
+            }
+            "--retries" => {
+                retries = Some(
+                    value()?
+                        .parse()
+                        .map_err(|_| "`--retries` must be a non-negative number")?,
+                )
+            }
+            "--dimension" => {
+                dimension = value()?
+                    .parse()
+                    .map_err(|_| "`--dimension` must be a positive number")?
+            }
+            "--lower" => lower = value()?.parse().map_err(|_| "`--lower` must be a number")?,
+            "--upper" => upper = value()?.parse().map_err(|_| "`--upper` must be a number")?,
+            _ => rest.push(arg),
+        }
+    }
+    if lower >= upper {
+        return Err("`--lower` must be smaller than `--upper`".to_string());
+    }
+    let options = parse_run_options(&rest, PathBuf::from("checkpoint.json"))?;
+    let run = |evaluator: &dyn genetic_algorithm_fn::evaluation::Evaluator| {
+        interrupt::run_until_interrupted(
+            Runner::new(config.clone())
+                .with_on_health_warning(|warning| eprintln!("Warning: {}", warning)),
+            solutions::Solutions::random(
+                config.size_generation_for(dimension),
+                lower..upper,
+                dimension,
+            ),
+            evaluator,
+            &options.checkpoint,
+        )
+        .map_err(|error| error.to_string())
+    };
+    match protocol.as_deref() {
+        Some("jsonl") => {
+            let evaluator = JsonLinesEvaluator::new(BufReader::new(io::stdin()), io::stdout());
+            let result = run(&evaluator)?;
+            evaluator
+                .finish(result.best.as_ref())
+                .map_err(|error| error.to_string())?;
+            report(
+                &Checkpoint::from_result(&result, &config),
+                &options,
+                &mut io::stderr(),
+            )
+        }
+        Some("http") => {
+            let url = url.ok_or("`--protocol http` requires `--url`")?;
+            let mut evaluator = HttpEvaluator::new(&url).map_err(|error| error.to_string())?;
+            if let Some(max_in_flight) = max_in_flight {
+                evaluator = evaluator.with_max_in_flight(max_in_flight);
+            }
+            if let Some(requests_per_second) = requests_per_second {
+                evaluator = evaluator
+                    .with_rate_limit(requests_per_second)
+                    .map_err(|error| error.to_string())?;
+            }
+            if let Some(retries) = retries {
+                evaluator = evaluator.with_retries(retries, std::time::Duration::from_millis(100));
+            }
+            let result = run(&evaluator)?;
+            report(
+                &Checkpoint::from_result(&result, &config),
+                &options,
+                &mut io::stdout(),
+            )
+        }
+        Some(other) => Err(format!("Unknown protocol `{}`", other)),
+        None => Err("`serve` requires `--protocol jsonl` or `--protocol http`".to_string()),
+    }
+}
+
+/// Print the metadata, best individual and progress of a checkpoint or result.
+///
+/// # Arguments
+///
+/// * `args` - The arguments following `inspect`.
+fn inspect(args: &[String]) -> Result<(), String> {
+    let [path] = args else {
+        return Err("`inspect` requires exactly one file".to_string());
+    };
+    let (checkpoint, warnings) =
+        Checkpoint::load_with_warnings(path.as_ref()).map_err(|error| error.to_string())?;
+    print_warnings(path, &warnings);
+    print!("{}", checkpoint);
+    Ok(())
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let function_to_optimize = function::Function::new(|x| hartman_3_dimensional(&x));
+    let outcome = match args.first().map(String::as_str) {
+        None | Some("benchmark") => {
+            benchmark();
+            Ok(())
+        }
+        Some("run") => run(&args[1..], &function_to_optimize),
+        Some("resume") => resume(&args[1..], &function_to_optimize),
+        Some("inspect") => inspect(&args[1..]),
+        Some("serve") => serve(&args[1..]),
+        Some(other) => Err(format!("Unknown command `{}`", other)),
+    };
+    if let Err(message) = outcome {
+        eprintln!("{}\n{}", message, USAGE);
+        process::exit(2);
+    }
+}
@@ -0,0 +1,603 @@
+use crate::error::Error;
+use crate::function::{Function, FunctionError};
+use crate::rng;
+use crate::solution::{self, CrossoverOperator, MutationOperator, Solution};
+use rand::distributions::uniform::SampleRange;
+use rand::Rng;
+use std::slice::ChunksExact;
+
+/// A population stored as one contiguous, row-major `Vec<f64>`: the arguments of the first
+/// individual, then those of the second and so on. Recombining and mutating rows of it
+/// doesn't allocate per individual, and the whole population can be handed to an objective
+/// that evaluates many individuals at once, e.g. with SIMD. Unlike `Solutions` it keeps
+/// duplicates and the order of its individuals.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PopulationMatrix {
+    /// The number of arguments of every individual, i.e. the length of a row.
+    n_arguments: usize,
+    /// The arguments of all individuals, one row after another.
+    values: Vec<f64>,
+}
+
+impl PopulationMatrix {
+    /// Create an empty population of individuals with `n_arguments` arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_arguments` - The number of arguments of every individual.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::matrix::PopulationMatrix;
+    ///
+    /// let matrix = PopulationMatrix::new(3);
+    /// assert!(matrix.is_empty());
+    /// assert_eq!(matrix.n_arguments(), 3);
+    /// ```
+    pub fn new(n_arguments: usize) -> Self {
+        PopulationMatrix {
+            n_arguments,
+            values: Vec::new(),
+        }
+    }
+    /// Create a population from the arguments of all individuals, one row after another.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_arguments` - The number of arguments of every individual.
+    /// * `values` - The arguments, its length has to be a multiple of `n_arguments`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::error::Error;
+    /// use genetic_algorithm_fn::matrix::PopulationMatrix;
+    ///
+    /// let matrix = PopulationMatrix::from_values(2, vec![1.0, 2.0, 3.0, 4.0]).unwrap();
+    /// assert_eq!(matrix.row(1), &[3.0, 4.0]);
+    /// assert!(matches!(
+    ///     PopulationMatrix::from_values(2, vec![1.0, 2.0, 3.0]),
+    ///     Err(Error::LengthMismatch { expected: 2, actual: 1 })
+    /// ));
+    /// ```
+    pub fn from_values(n_arguments: usize, values: Vec<f64>) -> Result<Self, Error> {
+        let remainder = match n_arguments {
+            0 => values.len(),
+            n_arguments => values.len() % n_arguments,
+        };
+        if remainder != 0 {
+            return Err(Error::LengthMismatch {
+                expected: n_arguments,
+                actual: remainder,
+            });
+        }
+        Ok(PopulationMatrix {
+            n_arguments,
+            values,
+        })
+    }
+    /// Create a population from solutions, in their order.
+    ///
+    /// # Arguments
+    ///
+    /// * `solutions` - The individuals, all with the same number of arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::error::Error;
+    /// use genetic_algorithm_fn::matrix::PopulationMatrix;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let matrix = PopulationMatrix::from_solutions(&[
+    ///     Solution::new(vec![1.0, 2.0]),
+    ///     Solution::new(vec![3.0, 4.0]),
+    /// ])
+    /// .unwrap();
+    /// assert_eq!(matrix.as_slice(), &[1.0, 2.0, 3.0, 4.0]);
+    /// assert!(matches!(
+    ///     PopulationMatrix::from_solutions(&[Solution::new(vec![1.0]), Solution::new(vec![])]),
+    ///     Err(Error::LengthMismatch { expected: 1, actual: 0 })
+    /// ));
+    /// ```
+    pub fn from_solutions(solutions: &[Solution]) -> Result<Self, Error> {
+        let n_arguments = solutions
+            .first()
+            .map_or(0, |solution| solution.get_arguments().len());
+        let mut matrix = PopulationMatrix::new(n_arguments);
+        matrix.values.reserve(n_arguments * solutions.len());
+        for solution in solutions {
+            matrix.push(&solution.get_arguments())?;
+        }
+        Ok(matrix)
+    }
+    /// Draw a population with every argument sampled uniformly from `range`.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_individuals` - The number of individuals.
+    /// * `range` - The range every argument is sampled from.
+    /// * `n_arguments` - The number of arguments of every individual.
+    /// * `rng` - The random number generator to sample with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::error::Error;
+    /// use genetic_algorithm_fn::matrix::PopulationMatrix;
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let matrix = PopulationMatrix::try_random_with_rng(100, -1.0..1.0, 3, &mut rng).unwrap();
+    /// assert_eq!(matrix.len(), 100);
+    /// assert!(matrix.as_slice().iter().all(|value| (-1.0..1.0).contains(value)));
+    /// assert!(matches!(
+    ///     PopulationMatrix::try_random_with_rng(1, 1.0..1.0, 3, &mut rng),
+    ///     Err(Error::EmptyRange)
+    /// ));
+    /// ```
+    pub fn try_random_with_rng<R, G>(
+        n_individuals: usize,
+        range: R,
+        n_arguments: usize,
+        rng: &mut G,
+    ) -> Result<Self, Error>
+    where
+        R: SampleRange<f64> + Clone,
+        G: Rng + ?Sized,
+    {
+        if range.is_empty() {
+            return Err(Error::EmptyRange);
+        }
+        Ok(PopulationMatrix {
+            n_arguments,
+            values: (0..n_individuals * n_arguments)
+                .map(|_| rng.gen_range(range.clone()))
+                .collect(),
+        })
+    }
+    /// The number of individuals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::matrix::PopulationMatrix;
+    ///
+    /// let matrix = PopulationMatrix::from_values(2, vec![0.0; 6]).unwrap();
+    /// assert_eq!(matrix.len(), 3);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.values.len().checked_div(self.n_arguments).unwrap_or(0)
+    }
+    /// Return whether the population has no individuals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::matrix::PopulationMatrix;
+    ///
+    /// assert!(PopulationMatrix::new(2).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// The number of arguments of every individual.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::matrix::PopulationMatrix;
+    ///
+    /// assert_eq!(PopulationMatrix::new(4).n_arguments(), 4);
+    /// ```
+    pub fn n_arguments(&self) -> usize {
+        self.n_arguments
+    }
+    /// The arguments of all individuals, one row after another.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::matrix::PopulationMatrix;
+    ///
+    /// let matrix = PopulationMatrix::from_values(1, vec![1.0, 2.0]).unwrap();
+    /// assert_eq!(matrix.as_slice(), &[1.0, 2.0]);
+    /// ```
+    pub fn as_slice(&self) -> &[f64] {
+        &self.values
+    }
+    /// The arguments of an individual.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the individual.
+    ///
+    /// # Panics
+    ///
+    /// If there is no individual at `index`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::matrix::PopulationMatrix;
+    ///
+    /// let matrix = PopulationMatrix::from_values(2, vec![1.0, 2.0, 3.0, 4.0]).unwrap();
+    /// assert_eq!(matrix.row(0), &[1.0, 2.0]);
+    /// ```
+    pub fn row(&self, index: usize) -> &[f64] {
+        assert!(
+            index < self.len(),
+            "There is no individual {} in a population of {}",
+            index,
+            self.len()
+        );
+        &self.values[index * self.n_arguments..(index + 1) * self.n_arguments]
+    }
+    /// The arguments of every individual, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::matrix::PopulationMatrix;
+    ///
+    /// let matrix = PopulationMatrix::from_values(2, vec![1.0, 2.0, 3.0, 4.0]).unwrap();
+    /// let sums = matrix.rows().map(|row| row.iter().sum()).collect::<Vec<f64>>();
+    /// assert_eq!(sums, vec![3.0, 7.0]);
+    /// ```
+    pub fn rows(&self) -> ChunksExact<'_, f64> {
+        // Chunks of zero elements aren't allowed, a population without arguments has no rows.
+        self.values.chunks_exact(self.n_arguments.max(1))
+    }
+    /// Append an individual.
+    ///
+    /// # Arguments
+    ///
+    /// * `arguments` - The arguments of the individual, as many as `n_arguments`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::error::Error;
+    /// use genetic_algorithm_fn::matrix::PopulationMatrix;
+    ///
+    /// let mut matrix = PopulationMatrix::new(2);
+    /// matrix.push(&[1.0, 2.0]).unwrap();
+    /// assert_eq!(matrix.len(), 1);
+    /// assert!(matches!(
+    ///     matrix.push(&[1.0]),
+    ///     Err(Error::LengthMismatch { expected: 2, actual: 1 })
+    /// ));
+    /// ```
+    pub fn push(&mut self, arguments: &[f64]) -> Result<(), Error> {
+        if arguments.len() != self.n_arguments {
+            return Err(Error::LengthMismatch {
+                expected: self.n_arguments,
+                actual: arguments.len(),
+            });
+        }
+        self.values.extend_from_slice(arguments);
+        Ok(())
+    }
+    /// Turn every individual into a `Solution`, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::matrix::PopulationMatrix;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let matrix = PopulationMatrix::from_values(1, vec![1.0, 2.0]).unwrap();
+    /// assert_eq!(
+    ///     matrix.to_solutions(),
+    ///     vec![Solution::new(vec![1.0]), Solution::new(vec![2.0])]
+    /// );
+    /// ```
+    pub fn to_solutions(&self) -> Vec<Solution> {
+        self.rows().map(|row| Solution::new(row.to_vec())).collect()
+    }
+    /// Compute the value of `function` for every individual, in order.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The function to evaluate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::matrix::PopulationMatrix;
+    ///
+    /// let function = Function::new(|x| Ok(x.iter().sum()));
+    /// let matrix = PopulationMatrix::from_values(2, vec![1.0, 2.0, 3.0, 4.0]).unwrap();
+    /// let values = matrix.evaluate(&function);
+    /// assert_eq!(values.into_iter().map(Result::unwrap).collect::<Vec<f64>>(), vec![3.0, 7.0]);
+    /// ```
+    pub fn evaluate(&self, function: &Function) -> Vec<Result<f64, FunctionError>> {
+        self.rows()
+            .map(|row| function.get_function_value(row.to_vec()))
+            .collect()
+    }
+    /// Evaluate the individuals in batches of up to `batch_size` rows. `objective` gets the
+    /// contiguous arguments of a batch and writes one value per row of it, so it can evaluate
+    /// a whole batch with vectorized code.
+    ///
+    /// # Arguments
+    ///
+    /// * `batch_size` - The largest number of individuals per batch, at least one.
+    /// * `objective` - Gets the arguments of a batch, one row after another, and the values of
+    ///   the batch to write.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::matrix::PopulationMatrix;
+    ///
+    /// let matrix = PopulationMatrix::from_values(2, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+    /// let values = matrix.evaluate_batches(2, |arguments, values| {
+    ///     for (row, value) in arguments.chunks_exact(2).zip(values.iter_mut()) {
+    ///         *value = row[0] * row[1];
+    ///     }
+    /// });
+    /// assert_eq!(values, vec![2.0, 12.0, 30.0]);
+    /// ```
+    pub fn evaluate_batches<F>(&self, batch_size: usize, mut objective: F) -> Vec<f64>
+    where
+        F: FnMut(&[f64], &mut [f64]),
+    {
+        let batch_size = batch_size.max(1);
+        let mut values = vec![f64::NAN; self.len()];
+        for (batch, batch_values) in self
+            .values
+            .chunks(batch_size * self.n_arguments.max(1))
+            .zip(values.chunks_mut(batch_size))
+        {
+            objective(batch, batch_values);
+        }
+        values
+    }
+    /// Recombine pairs of individuals into a population of children, one per pair and in the
+    /// order of the pairs. The children are the same as those of
+    /// `Solution::crossover_with_operator` with the same random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `pairs` - The indices of the parents of every child.
+    /// * `operator` - How the parents are recombined.
+    /// * `rng` - The random number generator to sample with.
+    ///
+    /// # Panics
+    ///
+    /// If a parent's index is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::matrix::PopulationMatrix;
+    /// use genetic_algorithm_fn::solution::CrossoverOperator;
+    ///
+    /// let parents = PopulationMatrix::from_values(2, vec![0.0, 2.0, 4.0, 6.0]).unwrap();
+    /// let children = parents.crossover(
+    ///     &[(0, 1), (1, 1)],
+    ///     CrossoverOperator::Average,
+    ///     &mut rand::thread_rng(),
+    /// );
+    /// assert_eq!(children.as_slice(), &[2.0, 4.0, 4.0, 6.0]);
+    /// ```
+    pub fn crossover<G>(
+        &self,
+        pairs: &[(usize, usize)],
+        operator: CrossoverOperator,
+        rng: &mut G,
+    ) -> PopulationMatrix
+    where
+        G: Rng + ?Sized,
+    {
+        let mut children = PopulationMatrix {
+            n_arguments: self.n_arguments,
+            values: vec![0.0; pairs.len() * self.n_arguments],
+        };
+        let mut swapped = vec![false; self.n_arguments];
+        for ((parent_a, parent_b), child) in pairs
+            .iter()
+            .zip(children.values.chunks_exact_mut(self.n_arguments.max(1)))
+        {
+            solution::recombine_arguments(
+                self.row(*parent_a),
+                self.row(*parent_b),
+                operator,
+                rng,
+                child,
+                &mut swapped,
+            );
+        }
+        children
+    }
+    /// Mutate every individual in place, each with probability `prob`. The individuals are
+    /// mutated like `Solution::mutate_with_operator` with the same random number generator
+    /// would mutate them one after another. The matrix carries no step sizes, so
+    /// `MutationOperator::SelfAdaptive` starts from its `initial_sigma` every time.
+    ///
+    /// # Arguments
+    ///
+    /// * `operator` - How the individuals are mutated.
+    /// * `prob` - The probability with which an individual is mutated at all.
+    /// * `rng` - The random number generator to sample with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::matrix::PopulationMatrix;
+    /// use genetic_algorithm_fn::solution::MutationOperator;
+    ///
+    /// let mut matrix = PopulationMatrix::from_values(2, vec![0.0; 4]).unwrap();
+    /// let operator = MutationOperator::Gaussian {
+    ///     sigma: 0.1,
+    ///     gene_prob: 0.5,
+    /// };
+    /// matrix.mutate(operator, 1.0, &mut rand::thread_rng());
+    /// assert!(matrix.rows().all(|row| row.iter().any(|value| *value != 0.0)));
+    /// ```
+    pub fn mutate<G>(&mut self, operator: MutationOperator, prob: f32, rng: &mut G)
+    where
+        G: Rng + ?Sized,
+    {
+        if self.n_arguments == 0 {
+            return;
+        }
+        for row in self.values.chunks_exact_mut(self.n_arguments) {
+            mutate_row(row, operator, prob, rng);
+        }
+    }
+    /// Keep the `n` fittest individuals, the fittest first. Individuals whose fitness is
+    /// `NaN` are the least fit, ties keep their order.
+    ///
+    /// # Arguments
+    ///
+    /// * `fitness` - The fitness of every individual, larger is better.
+    /// * `n` - The number of individuals to keep.
+    ///
+    /// # Panics
+    ///
+    /// If there isn't one fitness per individual.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::matrix::PopulationMatrix;
+    ///
+    /// let matrix = PopulationMatrix::from_values(1, vec![1.0, 2.0, 3.0]).unwrap();
+    /// let fittest = matrix.select_fittest(&[0.5, f64::NAN, 2.0], 2);
+    /// assert_eq!(fittest.as_slice(), &[3.0, 1.0]);
+    /// ```
+    pub fn select_fittest(&self, fitness: &[f64], n: usize) -> PopulationMatrix {
+        assert_eq!(
+            fitness.len(),
+            self.len(),
+            "Expected one fitness per individual"
+        );
+        let mut order = (0..self.len()).collect::<Vec<usize>>();
+        order.sort_by(|a, b| match (fitness[*a].is_nan(), fitness[*b].is_nan()) {
+            (false, false) => fitness[*b].total_cmp(&fitness[*a]),
+            (nan_a, nan_b) => nan_a.cmp(&nan_b),
+        });
+        let mut fittest = PopulationMatrix::new(self.n_arguments);
+        fittest
+            .values
+            .reserve(n.min(order.len()) * self.n_arguments);
+        for index in order.into_iter().take(n) {
+            fittest.values.extend_from_slice(self.row(index));
+        }
+        fittest
+    }
+}
+
+/// Mutate the arguments of an individual in place, drawing the random numbers in the same
+/// order as `Solution::mutate_with_operator` for a solution without step sizes.
+///
+/// # Arguments
+///
+/// * `row` - The arguments of the individual, at least one.
+/// * `operator` - How the individual is mutated.
+/// * `prob` - The probability with which the individual is mutated at all.
+/// * `rng` - The random number generator to sample with.
+fn mutate_row<G>(row: &mut [f64], operator: MutationOperator, prob: f32, rng: &mut G)
+where
+    G: Rng + ?Sized,
+{
+    if rng.gen_range(0.0..1.0) > prob {
+        return;
+    }
+    match operator {
+        MutationOperator::Multiplicative => {
+            let (idx, factor) = solution::draw_scaling(row.len(), rng);
+            row[idx] *= factor;
+        }
+        MutationOperator::Gaussian { sigma, gene_prob } => {
+            solution::shift_values(row, gene_prob, rng, |rng| sigma * rng::standard_normal(rng))
+        }
+        MutationOperator::Cauchy { scale, gene_prob } => {
+            solution::shift_values(row, gene_prob, rng, |rng| scale * rng::standard_cauchy(rng))
+        }
+        MutationOperator::SelfAdaptive {
+            initial_sigma,
+            min_sigma,
+        } => {
+            let mut sigmas = vec![initial_sigma; row.len()];
+            solution::adapt_and_shift_values(row, &mut sigmas, min_sigma, rng)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::SplitMix64;
+
+    #[test]
+    fn operators_match_those_of_solutions() {
+        let mut rng = SplitMix64::new(2023);
+        let parents = PopulationMatrix::try_random_with_rng(4, -5.0..5.0, 6, &mut rng).unwrap();
+        let solutions = parents.to_solutions();
+        let pairs = [(0, 1), (2, 3), (3, 0), (1, 1)];
+        for operator in [
+            CrossoverOperator::Average,
+            CrossoverOperator::Blend { alpha: 0.5 },
+            CrossoverOperator::Uniform { swap_prob: 0.5 },
+            CrossoverOperator::KPoint { k: 2 },
+            CrossoverOperator::Arithmetic { per_gene: true },
+        ] {
+            let children = parents.crossover(&pairs, operator, &mut SplitMix64::new(7));
+            let mut rng = SplitMix64::new(7);
+            let expected = pairs
+                .iter()
+                .map(|(a, b)| {
+                    solutions[*a].crossover_with_operator(&solutions[*b], operator, &mut rng)
+                })
+                .collect::<Vec<Solution>>();
+            assert_eq!(children.to_solutions(), expected, "{:?}", operator);
+        }
+        for operator in [
+            MutationOperator::Multiplicative,
+            MutationOperator::Gaussian {
+                sigma: 0.1,
+                gene_prob: 0.3,
+            },
+            MutationOperator::Cauchy {
+                scale: 0.1,
+                gene_prob: 0.3,
+            },
+            MutationOperator::SelfAdaptive {
+                initial_sigma: 0.5,
+                min_sigma: 1e-3,
+            },
+        ] {
+            let mut mutated = parents.clone();
+            mutated.mutate(operator, 0.7, &mut SplitMix64::new(11));
+            let mut rng = SplitMix64::new(11);
+            let expected = solutions
+                .iter()
+                .map(|solution| {
+                    let arguments = solution
+                        .clone()
+                        .mutate_with_operator(operator, 0.7, &mut rng)
+                        .get_arguments();
+                    Solution::new(arguments)
+                })
+                .collect::<Vec<Solution>>();
+            assert_eq!(mutated.to_solutions(), expected, "{:?}", operator);
+        }
+    }
+    #[test]
+    fn populations_without_arguments_have_no_rows() {
+        let mut matrix = PopulationMatrix::new(0);
+        assert_eq!(matrix.len(), 0);
+        assert_eq!(matrix.rows().count(), 0);
+        matrix.mutate(
+            MutationOperator::Multiplicative,
+            1.0,
+            &mut SplitMix64::new(1),
+        );
+        assert!(matrix.evaluate_batches(4, |_, _| {}).is_empty());
+        assert!(PopulationMatrix::from_values(0, vec![1.0]).is_err());
+    }
+}
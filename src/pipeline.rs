@@ -0,0 +1,2722 @@
+use crate::bounds::Bounds;
+#[cfg(feature = "autodiff")]
+use crate::function::Function;
+use crate::function::Gradient;
+use crate::rng::{self, SplitMix64};
+use crate::sensitivity;
+use crate::solution::{self, compare_fitness, CrossoverOperator, MutationOperator, Solution};
+use genetic_algorithm_traits::Individual;
+use rand::{Rng, RngCore};
+use std::fmt;
+use std::ops::Range;
+use std::sync::Mutex;
+
+/// Picks the pairs of parents that produce the offspring of a generation.
+pub trait ParentSelector: Send + Sync {
+    /// Return the indices of the parent pairs within `population`. Every pair produces one child.
+    ///
+    /// # Arguments
+    ///
+    /// * `population` - The current individuals and their fitness, if already evaluated.
+    /// * `rng` - The random number generator to draw from.
+    fn select_parents(
+        &self,
+        population: &[(Solution, Option<f64>)],
+        rng: &mut dyn RngCore,
+    ) -> Vec<(usize, usize)>;
+}
+
+/// Combines two parents into a child.
+pub trait Crossover: Send + Sync {
+    /// Create the child of `parent_a` and `parent_b`.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent_a` - The first parent.
+    /// * `parent_b` - The second parent.
+    /// * `rng` - The random number generator to draw from.
+    fn crossover(
+        &self,
+        parent_a: &Solution,
+        parent_b: &Solution,
+        rng: &mut dyn RngCore,
+    ) -> Solution;
+    /// Create the child of two parents knowing their fitness, e.g. to move towards the fitter
+    /// one. The pipeline crosses over through this method, by default it ignores the fitness
+    /// and calls `crossover`.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent_a` - The first parent and its fitness, if already evaluated.
+    /// * `parent_b` - The second parent and its fitness, if already evaluated.
+    /// * `rng` - The random number generator to draw from.
+    fn crossover_with_fitness(
+        &self,
+        parent_a: (&Solution, Option<f64>),
+        parent_b: (&Solution, Option<f64>),
+        rng: &mut dyn RngCore,
+    ) -> Solution {
+        self.crossover(parent_a.0, parent_b.0, rng)
+    }
+    /// Adapt the crossover to the population before the children of a generation are created,
+    /// e.g. to draw further parents from it. The returned crossover creates all children of
+    /// the generation, `None` creates them with `self`.
+    ///
+    /// # Arguments
+    ///
+    /// * `population` - The current individuals and their fitness, if already evaluated.
+    fn adapt(&self, _population: &[(Solution, Option<f64>)]) -> Option<Box<dyn Crossover>> {
+        None
+    }
+}
+
+/// Randomly changes a child.
+pub trait Mutation: Send + Sync {
+    /// Mutate `child`.
+    ///
+    /// # Arguments
+    ///
+    /// * `child` - The child that should be mutated.
+    /// * `mutate_prob` - The mutation probability currently in effect.
+    /// * `rng` - The random number generator to draw from.
+    fn mutate(&self, child: Solution, mutate_prob: f32, rng: &mut dyn RngCore) -> Solution;
+    /// Adapt the mutation to the population before the children of a generation are mutated,
+    /// e.g. to the spread of its fittest individuals. The returned mutation mutates all
+    /// children of the generation, `None` mutates them with `self`.
+    ///
+    /// # Arguments
+    ///
+    /// * `population` - The current individuals and their fitness, if already evaluated.
+    fn adapt(&self, _population: &[(Solution, Option<f64>)]) -> Option<Box<dyn Mutation>> {
+        None
+    }
+}
+
+/// Turns an invalid child into a valid one, e.g. by projecting it back into the search space.
+pub trait Repair: Send + Sync {
+    /// Repair `child`.
+    ///
+    /// # Arguments
+    ///
+    /// * `child` - The child that should be repaired.
+    fn repair(&self, child: Solution) -> Solution;
+}
+
+/// Decides whether a child becomes a candidate of the next generation.
+pub trait Filter: Send + Sync {
+    /// Return whether `child` should be kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `child` - The child in question.
+    fn keep(&self, child: &Solution) -> bool;
+}
+
+/// Every individual is paired with every other individual, in both orders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AllPairs;
+
+impl ParentSelector for AllPairs {
+    fn select_parents(
+        &self,
+        population: &[(Solution, Option<f64>)],
+        _rng: &mut dyn RngCore,
+    ) -> Vec<(usize, usize)> {
+        (0..population.len())
+            .flat_map(|idx| {
+                (0..population.len())
+                    // Don't crossover the individual with itself.
+                    .filter(move |other_idx| *other_idx != idx)
+                    .map(move |other_idx| (idx, other_idx))
+            })
+            .collect()
+    }
+}
+
+/// Drop the pairs of another parent selector whose parents are identical within `epsilon`,
+/// crossing those over only produces a clone.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DistinctParents<S> {
+    selector: S,
+    epsilon: f64,
+}
+
+impl<S> DistinctParents<S> {
+    /// Wrap `selector` so that no individual is paired with an identical partner.
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - The parent selector whose pairs are filtered.
+    /// * `epsilon` - Parents whose arguments all differ by at most `epsilon` are identical.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::{AllPairs, DistinctParents, Pipeline};
+    ///
+    /// let pipeline = Pipeline::default().with_parent_selector(DistinctParents::new(AllPairs, 1e-6));
+    /// ```
+    pub fn new(selector: S, epsilon: f64) -> Self {
+        DistinctParents { selector, epsilon }
+    }
+}
+
+impl<S> ParentSelector for DistinctParents<S>
+where
+    S: ParentSelector,
+{
+    fn select_parents(
+        &self,
+        population: &[(Solution, Option<f64>)],
+        rng: &mut dyn RngCore,
+    ) -> Vec<(usize, usize)> {
+        distinct_pairs(
+            population,
+            self.selector.select_parents(population, rng),
+            self.epsilon,
+        )
+    }
+}
+
+/// Keep the pairs whose parents are not identical within `epsilon`.
+///
+/// # Arguments
+///
+/// * `population` - The individuals the pairs index into.
+/// * `pairs` - The indices of the parent pairs.
+/// * `epsilon` - Parents whose arguments all differ by at most `epsilon` are identical.
+fn distinct_pairs(
+    population: &[(Solution, Option<f64>)],
+    pairs: Vec<(usize, usize)>,
+    epsilon: f64,
+) -> Vec<(usize, usize)> {
+    pairs
+        .into_iter()
+        .filter(|(idx_a, idx_b)| {
+            let (arguments_a, arguments_b) = (
+                population[*idx_a].0.get_arguments(),
+                population[*idx_b].0.get_arguments(),
+            );
+            arguments_a.len() != arguments_b.len()
+                || arguments_a
+                    .iter()
+                    .zip(arguments_b.iter())
+                    .any(|(a, b)| (a - b).abs() > epsilon)
+        })
+        .collect()
+}
+
+/// How roulette selection turns fitness values into the non-negative weights the wheel needs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FitnessScaling {
+    /// The fitness is the weight. Negative fitness is an error, use `ShiftByWorst` or
+    /// `LinearRank` for objectives like the negated Hartman function.
+    Raw,
+    /// Subtract the worst fitness of the population and add `epsilon`, so every weight is
+    /// positive and even the least fit individual can be picked.
+    ShiftByWorst {
+        /// The weight of the least fit individual.
+        epsilon: f64,
+    },
+    /// Linear ranking: the weight only depends on the rank of the fitness, so huge fitness
+    /// differences, e.g. near singular regions of the objective, don't dominate reproduction.
+    /// The least fit individual weighs `2 - pressure`, the fittest one `pressure`, and the
+    /// weights in between grow linearly with the rank.
+    LinearRank {
+        /// The selection pressure in `[1, 2]`, the expected number of times the fittest
+        /// individual is picked per individual. `1` picks uniformly.
+        pressure: f64,
+    },
+}
+
+impl Default for FitnessScaling {
+    /// Shift by the worst fitness with an `epsilon` of `1e-9`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::FitnessScaling;
+    ///
+    /// assert_eq!(FitnessScaling::default(), FitnessScaling::ShiftByWorst { epsilon: 1e-9 });
+    /// ```
+    fn default() -> Self {
+        FitnessScaling::ShiftByWorst { epsilon: 1e-9 }
+    }
+}
+
+/// An individual has a negative fitness, which roulette selection with
+/// `FitnessScaling::Raw` can't turn into a weight.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NegativeFitness {
+    /// The index of the individual in the population.
+    pub index: usize,
+    /// Its fitness.
+    pub fitness: f64,
+}
+
+impl fmt::Display for NegativeFitness {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Roulette selection needs non-negative weights, but individual {} has the fitness {}; \
+             use `FitnessScaling::ShiftByWorst` or `FitnessScaling::LinearRank` instead",
+            self.index, self.fitness
+        )
+    }
+}
+
+impl std::error::Error for NegativeFitness {}
+
+impl FitnessScaling {
+    /// The weights of the individuals of a population. Individuals without a finite fitness
+    /// get the smallest weight.
+    ///
+    /// # Arguments
+    ///
+    /// * `population` - The individuals and their fitness, if already evaluated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::FitnessScaling;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let population = [
+    ///     (Solution::new(vec![1.0]), Some(-3.0)),
+    ///     (Solution::new(vec![2.0]), Some(-1.0)),
+    /// ];
+    /// assert!(FitnessScaling::Raw.weights(&population).is_err());
+    /// assert_eq!(
+    ///     FitnessScaling::ShiftByWorst { epsilon: 0.5 }.weights(&population),
+    ///     Ok(vec![0.5, 2.5])
+    /// );
+    /// ```
+    pub fn weights(
+        &self,
+        population: &[(Solution, Option<f64>)],
+    ) -> Result<Vec<f64>, NegativeFitness> {
+        let finite = |fitness: &Option<f64>| fitness.filter(|fitness| fitness.is_finite());
+        match self {
+            FitnessScaling::Raw => population
+                .iter()
+                .enumerate()
+                .map(|(index, (_, fitness))| match finite(fitness) {
+                    Some(fitness) if fitness < 0.0 => Err(NegativeFitness { index, fitness }),
+                    Some(fitness) => Ok(fitness),
+                    None => Ok(0.0),
+                })
+                .collect(),
+            FitnessScaling::ShiftByWorst { epsilon } => {
+                let worst = population
+                    .iter()
+                    .filter_map(|(_, fitness)| finite(fitness))
+                    .fold(f64::INFINITY, f64::min);
+                Ok(population
+                    .iter()
+                    .map(|(_, fitness)| {
+                        finite(fitness).map_or(*epsilon, |fitness| fitness - worst + epsilon)
+                    })
+                    .collect())
+            }
+            FitnessScaling::LinearRank { pressure } => {
+                let pressure = pressure.clamp(1.0, 2.0);
+                let mut ranked = population
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, (solution, fitness))| {
+                        finite(fitness).map(|fitness| (index, solution, fitness))
+                    })
+                    .collect::<Vec<(usize, &Solution, f64)>>();
+                // From the least fit to the fittest individual.
+                ranked.sort_by(|(_, solution_a, fitness_a), (_, solution_b, fitness_b)| {
+                    compare_fitness((solution_b, *fitness_b), (solution_a, *fitness_a))
+                });
+                // Individuals without a finite fitness weigh as much as the least fit one.
+                let mut weights = vec![2.0 - pressure; population.len()];
+                let max_rank = ranked.len().saturating_sub(1).max(1) as f64;
+                for (rank, (index, _, _)) in ranked.into_iter().enumerate() {
+                    weights[index] =
+                        2.0 - pressure + 2.0 * (pressure - 1.0) * rank as f64 / max_rank;
+                }
+                Ok(weights)
+            }
+        }
+    }
+}
+
+/// Fitness-proportionate (roulette wheel) selection: both parents of every pair are drawn
+/// with a probability proportional to their weight, see `FitnessScaling`. If all weights are
+/// zero, e.g. before the first evaluation, the parents are drawn uniformly.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RouletteSelection {
+    /// How fitness values are turned into weights.
+    pub scaling: FitnessScaling,
+    /// How many pairs are drawn, `None` draws as many pairs as there are individuals.
+    pub n_pairs: Option<usize>,
+}
+
+impl ParentSelector for RouletteSelection {
+    /// # Panics
+    ///
+    /// With `FitnessScaling::Raw`, if an individual has a negative fitness.
+    fn select_parents(
+        &self,
+        population: &[(Solution, Option<f64>)],
+        rng: &mut dyn RngCore,
+    ) -> Vec<(usize, usize)> {
+        if population.is_empty() {
+            return Vec::new();
+        }
+        let weights = self
+            .scaling
+            .weights(population)
+            .unwrap_or_else(|error| panic!("{}", error));
+        let cumulative = weights
+            .iter()
+            .scan(0.0, |total, weight| {
+                *total += weight;
+                Some(*total)
+            })
+            .collect::<Vec<f64>>();
+        let total = cumulative[cumulative.len() - 1];
+        let mut spin = || {
+            if total > 0.0 && total.is_finite() {
+                let point = rng.gen_range(0.0..total);
+                cumulative
+                    .partition_point(|bound| *bound <= point)
+                    .min(population.len() - 1)
+            } else {
+                rng.gen_range(0..population.len())
+            }
+        };
+        (0..self.n_pairs.unwrap_or(population.len()))
+            .map(|_| (spin(), spin()))
+            .collect()
+    }
+}
+
+/// The child is the average of its parents, see `Individual::crossover` of `Solution`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AverageCrossover;
+
+impl Crossover for AverageCrossover {
+    fn crossover(
+        &self,
+        parent_a: &Solution,
+        parent_b: &Solution,
+        _rng: &mut dyn RngCore,
+    ) -> Solution {
+        parent_a.crossover(parent_b)
+    }
+}
+
+/// Recombine with one of the operators of `Solution`, see
+/// `Solution::crossover_with_operator`.
+impl Crossover for CrossoverOperator {
+    fn crossover(
+        &self,
+        parent_a: &Solution,
+        parent_b: &Solution,
+        rng: &mut dyn RngCore,
+    ) -> Solution {
+        parent_a.crossover_with_operator(parent_b, *self, rng)
+    }
+}
+
+/// The child lies on the line through its parents: `worse + t (better - worse)` with `t`
+/// drawn uniformly from `[0, 1 + extrapolation]`, so it is either between the parents or up
+/// to `extrapolation` times their distance beyond the fitter one. Moving on in the direction
+/// that improved works well within continuous unimodal regions. A parent that wasn't evaluated
+/// counts as the worse one, without any fitness the first parent counts as the better one.
+/// Panics if the parents have a different number of arguments.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineCrossover {
+    /// How far beyond the fitter parent the child may lie, relative to the distance of the
+    /// parents.
+    pub extrapolation: f64,
+}
+
+impl Default for LineCrossover {
+    fn default() -> Self {
+        LineCrossover { extrapolation: 0.5 }
+    }
+}
+
+impl Crossover for LineCrossover {
+    fn crossover(
+        &self,
+        parent_a: &Solution,
+        parent_b: &Solution,
+        rng: &mut dyn RngCore,
+    ) -> Solution {
+        self.crossover_with_fitness((parent_a, None), (parent_b, None), rng)
+    }
+    fn crossover_with_fitness(
+        &self,
+        parent_a: (&Solution, Option<f64>),
+        parent_b: (&Solution, Option<f64>),
+        rng: &mut dyn RngCore,
+    ) -> Solution {
+        solution::assert_same_length(parent_a.0, parent_b.0);
+        let b_is_better = match (parent_a.1, parent_b.1) {
+            (Some(fitness_a), Some(fitness_b)) => fitness_b > fitness_a,
+            (None, Some(_)) => true,
+            _ => false,
+        };
+        let (better, worse) = if b_is_better {
+            (parent_b.0.get_arguments(), parent_a.0.get_arguments())
+        } else {
+            (parent_a.0.get_arguments(), parent_b.0.get_arguments())
+        };
+        let t = rng.gen_range(0.0..=1.0 + self.extrapolation.max(0.0));
+        let child = Solution::new(
+            worse
+                .iter()
+                .zip(&better)
+                .map(|(worse, better)| worse + t * (better - worse))
+                .collect(),
+        );
+        match solution::recombined_sigmas(parent_a.0, parent_b.0) {
+            Some(sigmas) => child.with_sigmas(sigmas),
+            None => child,
+        }
+    }
+}
+
+/// The child takes every argument from one of its parents: starting from the first parent,
+/// every argument the mask allows to be exchanged is taken from the second parent with
+/// probability `swap_prob`. The mask keeps arguments that belong together, e.g. the
+/// parameters of one component, from being mixed with those of other components. Panics if
+/// the parents or the mask have a different number of arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UniformCrossover {
+    /// The probability of taking an exchangeable argument from the second parent.
+    pub swap_prob: f64,
+    /// Which arguments may be exchanged, one entry per argument. `None` allows every argument
+    /// to be exchanged.
+    pub mask: Option<Vec<bool>>,
+}
+
+impl Default for UniformCrossover {
+    fn default() -> Self {
+        UniformCrossover {
+            swap_prob: 0.5,
+            mask: None,
+        }
+    }
+}
+
+impl UniformCrossover {
+    /// Exchange only the first `n_exchanged` of `n_arguments` arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `swap_prob` - The probability of taking an exchangeable argument from the second
+    ///   parent.
+    /// * `n_exchanged` - How many of the first arguments may be exchanged.
+    /// * `n_arguments` - The number of arguments of the parents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::{Crossover, UniformCrossover};
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let crossover = UniformCrossover::leading(1.0, 2, 4);
+    /// let child = crossover.crossover(
+    ///     &Solution::new(vec![1.0, 2.0, 3.0, 4.0]),
+    ///     &Solution::new(vec![5.0, 6.0, 7.0, 8.0]),
+    ///     &mut rand::thread_rng(),
+    /// );
+    /// assert_eq!(child, Solution::new(vec![5.0, 6.0, 3.0, 4.0]));
+    /// ```
+    pub fn leading(swap_prob: f64, n_exchanged: usize, n_arguments: usize) -> Self {
+        UniformCrossover {
+            swap_prob,
+            mask: Some((0..n_arguments).map(|idx| idx < n_exchanged).collect()),
+        }
+    }
+}
+
+impl Crossover for UniformCrossover {
+    fn crossover(
+        &self,
+        parent_a: &Solution,
+        parent_b: &Solution,
+        rng: &mut dyn RngCore,
+    ) -> Solution {
+        solution::assert_same_length(parent_a, parent_b);
+        let n_arguments = parent_a.get_arguments().len();
+        if let Some(mask) = &self.mask {
+            assert_eq!(
+                mask.len(),
+                n_arguments,
+                "The mask of the uniform crossover needs one entry per argument"
+            );
+        }
+        let swapped = (0..n_arguments)
+            .map(|idx| {
+                let exchangeable = self.mask.as_ref().is_none_or(|mask| mask[idx]);
+                exchangeable && rng.gen_range(0.0..1.0) < self.swap_prob
+            })
+            .collect::<Vec<bool>>();
+        solution::exchange_arguments(parent_a, parent_b, &swapped)
+    }
+}
+
+/// Groups of arguments that belong together, e.g. the `(x, y)` coordinates of a point, so
+/// `BlockCrossover` and `BlockMutation` treat every group as a whole instead of mixing or
+/// mutating its arguments separately. Arguments that are in no group form a group of their own.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GeneGroups {
+    groups: Vec<Range<usize>>,
+}
+
+impl GeneGroups {
+    /// Declare the groups of arguments, overlapping groups are merged.
+    ///
+    /// # Arguments
+    ///
+    /// * `groups` - The index ranges of the arguments that belong together.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::GeneGroups;
+    ///
+    /// // The first three arguments are the position of a point, the fourth is its weight.
+    /// let groups = GeneGroups::new(vec![0..3]);
+    /// assert_eq!(groups.blocks(4), vec![0..3, 3..4]);
+    /// ```
+    pub fn new(mut groups: Vec<Range<usize>>) -> Self {
+        groups.retain(|group| !group.is_empty());
+        groups.sort_by_key(|group| group.start);
+        let mut merged: Vec<Range<usize>> = Vec::new();
+        for group in groups {
+            match merged.last_mut() {
+                Some(last) if group.start < last.end => last.end = last.end.max(group.end),
+                _ => merged.push(group),
+            }
+        }
+        GeneGroups { groups: merged }
+    }
+    /// Group consecutive arguments into blocks of `size`, e.g. `2` for a list of `(x, y)`
+    /// coordinates.
+    ///
+    /// # Arguments
+    ///
+    /// * `dimension` - The number of arguments.
+    /// * `size` - The number of arguments per group, the last group may be smaller.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::GeneGroups;
+    ///
+    /// assert_eq!(GeneGroups::chunks(5, 2).blocks(5), vec![0..2, 2..4, 4..5]);
+    /// ```
+    pub fn chunks(dimension: usize, size: usize) -> Self {
+        let size = size.max(1);
+        GeneGroups::new(
+            (0..dimension)
+                .step_by(size)
+                .map(|start| start..(start + size).min(dimension))
+                .collect(),
+        )
+    }
+    /// Partition the arguments of a solution with `n_arguments` arguments into blocks: the
+    /// declared groups, cut off at `n_arguments`, and a block for every argument in none.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_arguments` - The number of arguments of the solution.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::GeneGroups;
+    ///
+    /// assert_eq!(GeneGroups::new(vec![1..3]).blocks(4), vec![0..1, 1..3, 3..4]);
+    /// ```
+    pub fn blocks(&self, n_arguments: usize) -> Vec<Range<usize>> {
+        let mut blocks = Vec::new();
+        let mut next = 0;
+        for group in &self.groups {
+            if group.start >= n_arguments {
+                break;
+            }
+            blocks.extend((next..group.start).map(|idx| idx..idx + 1));
+            next = group.end.min(n_arguments);
+            blocks.push(group.start..next);
+        }
+        blocks.extend((next..n_arguments).map(|idx| idx..idx + 1));
+        blocks
+    }
+}
+
+/// Like `UniformCrossover` without a mask, but every group of `groups` is taken as a whole
+/// from one parent, so the child never combines half of a group of one parent with half of
+/// the other.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockCrossover {
+    /// The arguments that belong together.
+    pub groups: GeneGroups,
+    /// The probability of taking a group from the second parent.
+    pub swap_prob: f64,
+}
+
+impl Crossover for BlockCrossover {
+    fn crossover(
+        &self,
+        parent_a: &Solution,
+        parent_b: &Solution,
+        rng: &mut dyn RngCore,
+    ) -> Solution {
+        let n_arguments = parent_a.get_arguments().len();
+        let mut swapped = vec![false; n_arguments];
+        for block in self
+            .groups
+            .blocks(n_arguments.min(parent_b.get_arguments().len()))
+        {
+            if rng.gen_range(0.0..1.0) < self.swap_prob {
+                swapped[block].fill(true);
+            }
+        }
+        solution::exchange_arguments(parent_a, parent_b, &swapped)
+    }
+}
+
+/// How a `MultiParentCrossover` recombines its parents.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Recombination {
+    /// The center of mass `g` of the parents, perturbed along the directions from it to the
+    /// parents: `g + sum(e_i (x_i - g))` with `e_i` normally distributed with standard
+    /// deviation `perturbation / sqrt(n_parents)`.
+    CenterOfMass {
+        /// The scale of the perturbation, `0` creates the center of mass itself.
+        perturbation: f64,
+    },
+    /// Unimodal normal distribution crossover: around the midpoint of the two selected
+    /// parents the child spreads normally along the line through them, with standard
+    /// deviation `sigma_xi` times their distance, and orthogonally to it, with standard
+    /// deviation `sigma_eta / sqrt(dimension)` times the mean distance of the further parents
+    /// from that line.
+    Undx {
+        /// The spread along the line through the selected parents, `0.5` in the literature.
+        sigma_xi: f64,
+        /// The spread orthogonal to it, `0.35` in the literature.
+        sigma_eta: f64,
+    },
+}
+
+/// Recombines more than two parents: besides the pair chosen by the parent selection stage,
+/// `n_parents - 2` further parents are drawn uniformly from the population of the generation.
+/// Called without a population, e.g. outside a pipeline, it recombines just the pair.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MultiParentCrossover {
+    /// How many parents every child has, at least `2`.
+    pub n_parents: usize,
+    /// How the parents are recombined.
+    pub recombination: Recombination,
+}
+
+impl Default for MultiParentCrossover {
+    fn default() -> Self {
+        MultiParentCrossover {
+            n_parents: 3,
+            recombination: Recombination::Undx {
+                sigma_xi: 0.5,
+                sigma_eta: 0.35,
+            },
+        }
+    }
+}
+
+impl MultiParentCrossover {
+    /// Recombine the selected pair with further parents.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent_a` - The first selected parent.
+    /// * `parent_b` - The second selected parent.
+    /// * `further` - The further parents.
+    /// * `rng` - The random number generator to draw from.
+    fn recombine(
+        &self,
+        parent_a: &Solution,
+        parent_b: &Solution,
+        further: &[Vec<f64>],
+        rng: &mut dyn RngCore,
+    ) -> Solution {
+        let (parent_a, parent_b) = (parent_a.get_arguments(), parent_b.get_arguments());
+        let dimension = parent_a.len();
+        let further = further
+            .iter()
+            .filter(|parent| parent.len() == dimension)
+            .collect::<Vec<&Vec<f64>>>();
+        if parent_b.len() != dimension {
+            return Solution::new(parent_a);
+        }
+        match self.recombination {
+            Recombination::CenterOfMass { perturbation } => {
+                let parents = [&parent_a, &parent_b]
+                    .into_iter()
+                    .chain(further)
+                    .collect::<Vec<&Vec<f64>>>();
+                let n_parents = parents.len() as f64;
+                let center = (0..dimension)
+                    .map(|idx| parents.iter().map(|parent| parent[idx]).sum::<f64>() / n_parents)
+                    .collect::<Vec<f64>>();
+                let mut child = center.clone();
+                for parent in parents {
+                    let weight = perturbation / n_parents.sqrt() * rng::standard_normal(rng);
+                    for ((argument, center), parent) in child.iter_mut().zip(&center).zip(parent) {
+                        *argument += weight * (parent - center);
+                    }
+                }
+                Solution::new(child)
+            }
+            Recombination::Undx {
+                sigma_xi,
+                sigma_eta,
+            } => {
+                let direction = parent_b
+                    .iter()
+                    .zip(&parent_a)
+                    .map(|(b, a)| b - a)
+                    .collect::<Vec<f64>>();
+                let length = direction.iter().map(|x| x * x).sum::<f64>().sqrt();
+                let unit = direction
+                    .iter()
+                    .map(|x| if length > 0.0 { x / length } else { 0.0 })
+                    .collect::<Vec<f64>>();
+                // The component of `vector` orthogonal to the line through the selected parents.
+                let orthogonal = |vector: Vec<f64>| {
+                    let projection = vector.iter().zip(&unit).map(|(x, u)| x * u).sum::<f64>();
+                    vector
+                        .iter()
+                        .zip(&unit)
+                        .map(|(x, u)| x - projection * u)
+                        .collect::<Vec<f64>>()
+                };
+                let distance = if further.is_empty() {
+                    0.0
+                } else {
+                    further
+                        .iter()
+                        .map(|parent| {
+                            orthogonal(parent.iter().zip(&parent_a).map(|(x, a)| x - a).collect())
+                                .iter()
+                                .map(|x| x * x)
+                                .sum::<f64>()
+                                .sqrt()
+                        })
+                        .sum::<f64>()
+                        / further.len() as f64
+                };
+                let xi = sigma_xi * rng::standard_normal(rng);
+                let eta = orthogonal(
+                    (0..dimension)
+                        .map(|_| sigma_eta / (dimension as f64).sqrt() * rng::standard_normal(rng))
+                        .collect(),
+                );
+                Solution::new(
+                    (0..dimension)
+                        .map(|idx| {
+                            (parent_a[idx] + parent_b[idx]) / 2.0
+                                + xi * direction[idx]
+                                + distance * eta[idx]
+                        })
+                        .collect(),
+                )
+            }
+        }
+    }
+}
+
+impl Crossover for MultiParentCrossover {
+    fn crossover(
+        &self,
+        parent_a: &Solution,
+        parent_b: &Solution,
+        rng: &mut dyn RngCore,
+    ) -> Solution {
+        self.recombine(parent_a, parent_b, &[], rng)
+    }
+    fn adapt(&self, population: &[(Solution, Option<f64>)]) -> Option<Box<dyn Crossover>> {
+        Some(Box::new(PopulationCrossover {
+            crossover: *self,
+            population: population
+                .iter()
+                .map(|(solution, _)| solution.get_arguments())
+                .collect(),
+        }))
+    }
+}
+
+/// A `MultiParentCrossover` drawing its further parents from the population of a generation.
+struct PopulationCrossover {
+    /// The crossover that recombines the parents.
+    crossover: MultiParentCrossover,
+    /// The arguments of every individual of the population.
+    population: Vec<Vec<f64>>,
+}
+
+impl Crossover for PopulationCrossover {
+    fn crossover(
+        &self,
+        parent_a: &Solution,
+        parent_b: &Solution,
+        rng: &mut dyn RngCore,
+    ) -> Solution {
+        let further = if self.population.is_empty() {
+            Vec::new()
+        } else {
+            (2..self.crossover.n_parents)
+                .map(|_| self.population[rng.gen_range(0..self.population.len())].clone())
+                .collect()
+        };
+        self.crossover.recombine(parent_a, parent_b, &further, rng)
+    }
+}
+
+/// Multiply a random argument by a factor between 0.8 and 1.2, see
+/// `Solution::mutate_with_rng`. Arguments that are exactly `0.0` never change, use
+/// `JitteredMutation` if they should evolve as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MultiplicativeMutation;
+
+impl Mutation for MultiplicativeMutation {
+    fn mutate(&self, child: Solution, mutate_prob: f32, rng: &mut dyn RngCore) -> Solution {
+        child.mutate_with_rng(mutate_prob, rng)
+    }
+}
+
+/// Mutate with one of the operators of `Solution`, see `Solution::mutate_with_operator`.
+impl Mutation for MutationOperator {
+    fn mutate(&self, child: Solution, mutate_prob: f32, rng: &mut dyn RngCore) -> Solution {
+        child.mutate_with_operator(*self, mutate_prob, rng)
+    }
+}
+
+/// Multiply a random argument by a factor between 0.8 and 1.2 and shift it by at most
+/// `jitter`, see `Solution::mutate_with_jitter`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JitteredMutation {
+    /// The maximum absolute shift of the mutated argument.
+    pub jitter: f64,
+}
+
+impl Default for JitteredMutation {
+    fn default() -> Self {
+        JitteredMutation { jitter: 0.1 }
+    }
+}
+
+impl Mutation for JitteredMutation {
+    fn mutate(&self, child: Solution, mutate_prob: f32, rng: &mut dyn RngCore) -> Solution {
+        child.mutate_with_jitter(mutate_prob, self.jitter, rng)
+    }
+}
+
+/// Shift a random argument by a heavy-tailed Levy flight step: mostly small steps, and now
+/// and then a long jump that can leave a local optimum. The steps are drawn from the
+/// symmetric alpha-stable distribution, see `rng::symmetric_stable`, and scaled by `scale`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevyMutation {
+    /// The tail index in `(0, 2]`, smaller values jump further and more often. `1` draws
+    /// Cauchy steps, `2` normally distributed ones.
+    pub alpha: f64,
+    /// The scale of the steps.
+    pub scale: f64,
+}
+
+impl Default for LevyMutation {
+    fn default() -> Self {
+        LevyMutation {
+            alpha: 1.5,
+            scale: 0.1,
+        }
+    }
+}
+
+impl Mutation for LevyMutation {
+    fn mutate(&self, child: Solution, mutate_prob: f32, rng: &mut dyn RngCore) -> Solution {
+        let mut arguments = child.get_arguments();
+        if arguments.is_empty() || rng.gen_range(0.0..1.0) > mutate_prob {
+            return child;
+        }
+        let idx = rng.gen_range(0..arguments.len());
+        let step = self.scale * rng::symmetric_stable(self.alpha, rng);
+        // A step too long to represent is no step.
+        if step.is_finite() {
+            arguments[idx] += step;
+        }
+        child.with_arguments(arguments)
+    }
+}
+
+/// Mutate all arguments of a random group of `groups` at once: each is multiplied by a factor
+/// between 0.8 and 1.2 and shifted by at most `jitter`, like `JitteredMutation` does with a
+/// single argument.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockMutation {
+    /// The arguments that belong together.
+    pub groups: GeneGroups,
+    /// The maximum absolute shift of every mutated argument.
+    pub jitter: f64,
+}
+
+impl Mutation for BlockMutation {
+    fn mutate(&self, child: Solution, mutate_prob: f32, rng: &mut dyn RngCore) -> Solution {
+        let mut arguments = child.get_arguments();
+        if arguments.is_empty() || rng.gen_range(0.0..1.0) > mutate_prob {
+            return child;
+        }
+        let blocks = self.groups.blocks(arguments.len());
+        let block = blocks[rng.gen_range(0..blocks.len())].clone();
+        for argument in &mut arguments[block] {
+            *argument *= rng.gen_range(0.8..1.2);
+            if self.jitter > 0.0 {
+                *argument += rng.gen_range(-self.jitter..=self.jitter);
+            }
+        }
+        child.with_arguments(arguments)
+    }
+}
+
+/// Shift a random argument by at most `scale` times the width of its bounds and clamp it back
+/// into them, so the step size depends on the search space instead of the current value.
+/// Arguments without bounds are mutated multiplicatively.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundedMutation {
+    bounds: Vec<Range<f64>>,
+    scale: f64,
+}
+
+impl BoundedMutation {
+    /// Mutate within `bounds`, shifting by at most a tenth of the width of the bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `bounds` - The bounds of every argument.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::{BoundedMutation, Pipeline};
+    ///
+    /// let pipeline = Pipeline::default().with_mutation(BoundedMutation::new(vec![-1.0..1.0; 3]));
+    /// ```
+    pub fn new(bounds: Vec<Range<f64>>) -> Self {
+        BoundedMutation { bounds, scale: 0.1 }
+    }
+    /// Set the maximum shift relative to the width of the bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale` - The maximum shift as a fraction of the width of the bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::BoundedMutation;
+    ///
+    /// let mutation = BoundedMutation::new(vec![0.0..10.0]).with_scale(0.05);
+    /// ```
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+}
+
+impl Mutation for BoundedMutation {
+    fn mutate(&self, child: Solution, mutate_prob: f32, rng: &mut dyn RngCore) -> Solution {
+        let mut arguments = child.get_arguments();
+        if arguments.is_empty() || rng.gen_range(0.0..1.0) > mutate_prob {
+            return child;
+        }
+        let idx = rng.gen_range(0..arguments.len());
+        match self.bounds.get(idx) {
+            Some(bound) if bound.end > bound.start => {
+                let step = rng.gen_range(-1.0..=1.0) * self.scale * (bound.end - bound.start);
+                arguments[idx] = (arguments[idx] + step).clamp(bound.start, bound.end);
+            }
+            _ => arguments[idx] *= rng.gen_range(0.8..1.2),
+        }
+        child.with_arguments(arguments)
+    }
+}
+
+/// Deb's polynomial mutation within `bounds`, see `Solution::mutate_polynomial`. Mutated
+/// arguments always stay within their bounds, arguments without bounds are left unchanged.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolynomialMutation {
+    bounds: Vec<Range<f64>>,
+    eta_m: f64,
+}
+
+impl PolynomialMutation {
+    /// Mutate within `bounds` with a distribution index of `20`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bounds` - The bounds of every argument.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::{Pipeline, PolynomialMutation};
+    ///
+    /// let pipeline =
+    ///     Pipeline::default().with_mutation(PolynomialMutation::new(vec![-1.0..1.0; 3]));
+    /// ```
+    pub fn new(bounds: Vec<Range<f64>>) -> Self {
+        PolynomialMutation {
+            bounds,
+            eta_m: 20.0,
+        }
+    }
+    /// Set the distribution index, larger values mutate arguments less.
+    ///
+    /// # Arguments
+    ///
+    /// * `eta_m` - The distribution index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::PolynomialMutation;
+    ///
+    /// let mutation = PolynomialMutation::new(vec![0.0..10.0]).with_eta_m(100.0);
+    /// ```
+    pub fn with_eta_m(mut self, eta_m: f64) -> Self {
+        self.eta_m = eta_m;
+        self
+    }
+}
+
+impl Mutation for PolynomialMutation {
+    fn mutate(&self, child: Solution, mutate_prob: f32, rng: &mut dyn RngCore) -> Solution {
+        child.mutate_polynomial(mutate_prob, self.eta_m, &self.bounds, rng)
+    }
+}
+
+/// Shift all arguments along the gradient of the objective, so a mutation climbs towards a
+/// higher fitness: the step points in the direction of the gradient and its length is the
+/// absolute value of a normally distributed number with standard deviation `scale`. The
+/// gradient is computed by automatic differentiation, see `GradientMutation::new`, or by any
+/// other `Gradient`, e.g. finite differences. Where the gradient vanishes or can't be computed
+/// the child is mutated multiplicatively instead.
+#[derive(Clone)]
+pub struct GradientMutation {
+    gradient: Gradient,
+    scale: f64,
+}
+
+impl fmt::Debug for GradientMutation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GradientMutation")
+            .field("scale", &self.scale)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "autodiff")]
+impl GradientMutation {
+    /// Follow the gradient of `function` with steps of a standard deviation of `0.1`. `None`
+    /// if the function wasn't created with `Function::new_autodiff`.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The function whose gradient the mutation follows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::autodiff::{Differentiable, Float};
+    /// use genetic_algorithm_fn::function::{Function, FunctionError};
+    /// use genetic_algorithm_fn::pipeline::{GradientMutation, Pipeline};
+    ///
+    /// struct Sphere;
+    /// impl Differentiable for Sphere {
+    ///     fn evaluate<T: Float>(&self, x: &[T]) -> Result<T, FunctionError> {
+    ///         Ok(-x.iter().map(|x| x.powi(2)).sum::<T>())
+    ///     }
+    /// }
+    /// let function = Function::new_autodiff(Sphere);
+    /// let pipeline = Pipeline::default().with_mutation(GradientMutation::new(&function).unwrap());
+    /// assert!(GradientMutation::new(&Function::new(|x| Ok(x[0]))).is_none());
+    /// ```
+    pub fn new(function: &Function) -> Option<Self> {
+        function.gradient_fn().map(GradientMutation::with_gradient)
+    }
+}
+
+impl GradientMutation {
+    /// Follow the gradient computed by `gradient` with steps of a standard deviation of `0.1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `gradient` - Computes the function value and the gradient at the arguments of a child.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::{GradientMutation, Pipeline};
+    /// use std::sync::Arc;
+    ///
+    /// // The gradient of `-x² - y²`, written by hand.
+    /// let mutation = GradientMutation::with_gradient(Arc::new(|x: &[f64]| {
+    ///     Ok((-x[0] * x[0] - x[1] * x[1], vec![-2.0 * x[0], -2.0 * x[1]]))
+    /// }));
+    /// let pipeline = Pipeline::default().with_mutation(mutation);
+    /// ```
+    pub fn with_gradient(gradient: Gradient) -> Self {
+        GradientMutation {
+            gradient,
+            scale: 0.1,
+        }
+    }
+    /// Set the standard deviation of the length of the steps.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale` - The standard deviation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::GradientMutation;
+    /// use std::sync::Arc;
+    ///
+    /// let mutation =
+    ///     GradientMutation::with_gradient(Arc::new(|x: &[f64]| Ok((x[0], vec![1.0]))))
+    ///         .with_scale(0.01);
+    /// ```
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+}
+
+impl Mutation for GradientMutation {
+    fn mutate(&self, child: Solution, mutate_prob: f32, rng: &mut dyn RngCore) -> Solution {
+        let mut arguments = child.get_arguments();
+        if arguments.is_empty() || rng.gen_range(0.0..1.0) > mutate_prob {
+            return child;
+        }
+        let gradient = (self.gradient)(&arguments).map_or_else(|_| Vec::new(), |(_, g)| g);
+        let norm = gradient
+            .iter()
+            .map(|derivative| derivative * derivative)
+            .sum::<f64>()
+            .sqrt();
+        if gradient.len() != arguments.len() || !(norm > 0.0 && norm.is_finite()) {
+            return child.mutate_with_rng(1.0, rng);
+        }
+        let step = self.scale * rng::standard_normal(rng).abs();
+        for (argument, derivative) in arguments.iter_mut().zip(gradient) {
+            *argument += step * derivative / norm;
+        }
+        child.with_arguments(arguments)
+    }
+}
+
+/// Rotation-invariant mutation: every generation the covariance of the fittest individuals is
+/// estimated and the arguments of a mutated child are shifted together by a sample of a
+/// normal distribution with that covariance. The mutation follows the shape of the region the
+/// elites are spread over, which makes progress on correlated or elongated valleys like the
+/// one of the Rosenbrock function, where mutating single arguments mostly leaves the valley.
+/// Until at least two individuals were evaluated, or if the elites coincide, it mutates
+/// multiplicatively. Shifted children may leave the bounds, a repair stage clamps them back.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CovarianceMutation {
+    /// How many of the fittest evaluated individuals the covariance is estimated from.
+    pub n_elites: usize,
+    /// The step relative to the spread of the elites, the covariance the shift is drawn from
+    /// is scaled by its square.
+    pub scale: f64,
+}
+
+impl Default for CovarianceMutation {
+    fn default() -> Self {
+        CovarianceMutation {
+            n_elites: 10,
+            scale: 1.0,
+        }
+    }
+}
+
+impl Mutation for CovarianceMutation {
+    fn mutate(&self, child: Solution, mutate_prob: f32, rng: &mut dyn RngCore) -> Solution {
+        child.mutate_with_rng(mutate_prob, rng)
+    }
+    fn adapt(&self, population: &[(Solution, Option<f64>)]) -> Option<Box<dyn Mutation>> {
+        let mut elites = population
+            .iter()
+            .filter_map(|(solution, fitness)| fitness.map(|fitness| (solution, fitness)))
+            .collect::<Vec<(&Solution, f64)>>();
+        elites.sort_by(|elite_a, elite_b| compare_fitness(*elite_a, *elite_b));
+        elites.truncate(self.n_elites);
+        let elites = elites
+            .into_iter()
+            .map(|(solution, _)| solution.get_arguments())
+            .collect::<Vec<Vec<f64>>>();
+        let covariance = covariance(&elites)?;
+        Some(Box::new(CorrelatedMutation {
+            cholesky: cholesky(&covariance)?,
+            scale: self.scale,
+        }))
+    }
+}
+
+/// Shifts all arguments of a child together by a correlated normal sample, see
+/// `CovarianceMutation`.
+struct CorrelatedMutation {
+    /// The lower triangular Cholesky factor of the covariance of the elites.
+    cholesky: Vec<Vec<f64>>,
+    /// The step relative to the spread of the elites.
+    scale: f64,
+}
+
+impl Mutation for CorrelatedMutation {
+    fn mutate(&self, child: Solution, mutate_prob: f32, rng: &mut dyn RngCore) -> Solution {
+        let mut arguments = child.get_arguments();
+        if arguments.len() != self.cholesky.len() || rng.gen_range(0.0..1.0) > mutate_prob {
+            return child;
+        }
+        let sample = (0..arguments.len())
+            .map(|_| rng::standard_normal(rng))
+            .collect::<Vec<f64>>();
+        for (argument, row) in arguments.iter_mut().zip(&self.cholesky) {
+            *argument += self.scale
+                * row
+                    .iter()
+                    .zip(&sample)
+                    .map(|(factor, value)| factor * value)
+                    .sum::<f64>();
+        }
+        child.with_arguments(arguments)
+    }
+}
+
+/// Which arguments a `SensitivityMutation` prefers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SensitivityBias {
+    /// Mutate the arguments that currently affect the fitness most, to exploit them.
+    #[default]
+    MostSensitive,
+    /// Mutate the arguments that currently affect the fitness least, to explore the ones the
+    /// population hasn't made use of yet.
+    LeastSensitive,
+}
+
+/// Shift a random argument by a normally distributed step, picking the argument with a
+/// probability matched to its sensitivity, see `sensitivity::sensitivities`: the more (or,
+/// with `SensitivityBias::LeastSensitive`, the less) an argument currently affects the
+/// fitness, the more often it is mutated. Every argument keeps a share of the probability, so
+/// none is left alone for good. Estimating the sensitivities takes a pass over the population,
+/// so they are only updated every `update_every` generations in between.
+#[derive(Debug)]
+pub struct SensitivityMutation {
+    bias: SensitivityBias,
+    update_every: usize,
+    scale: f64,
+    /// The probabilities currently in effect and how many generations ago they were updated.
+    probabilities: Mutex<(usize, Option<Vec<f64>>)>,
+}
+
+/// The share of the probability that is spread uniformly over all arguments.
+const UNIFORM_SENSITIVITY_SHARE: f64 = 0.1;
+
+impl SensitivityMutation {
+    /// Prefer the arguments according to `bias`, updating the sensitivities every generation
+    /// and shifting by steps with a standard deviation of `0.1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bias` - Whether the most or the least sensitive arguments are preferred.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::{Pipeline, SensitivityBias, SensitivityMutation};
+    ///
+    /// let pipeline = Pipeline::default()
+    ///     .with_mutation(SensitivityMutation::new(SensitivityBias::MostSensitive));
+    /// ```
+    pub fn new(bias: SensitivityBias) -> Self {
+        SensitivityMutation {
+            bias,
+            update_every: 1,
+            scale: 0.1,
+            probabilities: Mutex::new((0, None)),
+        }
+    }
+    /// Set after how many generations the sensitivities are estimated again.
+    ///
+    /// # Arguments
+    ///
+    /// * `update_every` - The number of generations, `0` is treated as `1`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::{SensitivityBias, SensitivityMutation};
+    ///
+    /// let mutation =
+    ///     SensitivityMutation::new(SensitivityBias::LeastSensitive).with_update_every(5);
+    /// ```
+    pub fn with_update_every(mut self, update_every: usize) -> Self {
+        self.update_every = update_every.max(1);
+        self
+    }
+    /// Set the standard deviation of the steps.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale` - The standard deviation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::{SensitivityBias, SensitivityMutation};
+    ///
+    /// let mutation = SensitivityMutation::new(SensitivityBias::MostSensitive).with_scale(0.5);
+    /// ```
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+    /// The probability of mutating every argument, given their sensitivities.
+    ///
+    /// # Arguments
+    ///
+    /// * `sensitivities` - The sensitivity of every argument in `[0, 1]`.
+    fn probabilities(&self, sensitivities: &[f64]) -> Vec<f64> {
+        let preferences = sensitivities
+            .iter()
+            .map(|sensitivity| match self.bias {
+                SensitivityBias::MostSensitive => *sensitivity,
+                SensitivityBias::LeastSensitive => 1.0 - sensitivity,
+            })
+            .collect::<Vec<f64>>();
+        let total = preferences.iter().sum::<f64>();
+        let uniform = 1.0 / preferences.len().max(1) as f64;
+        preferences
+            .into_iter()
+            .map(|preference| {
+                if total > 0.0 {
+                    UNIFORM_SENSITIVITY_SHARE * uniform
+                        + (1.0 - UNIFORM_SENSITIVITY_SHARE) * preference / total
+                } else {
+                    uniform
+                }
+            })
+            .collect()
+    }
+}
+
+impl Mutation for SensitivityMutation {
+    fn mutate(&self, child: Solution, mutate_prob: f32, rng: &mut dyn RngCore) -> Solution {
+        let n_arguments = child.get_arguments().len();
+        WeightedMutation {
+            probabilities: vec![1.0 / n_arguments.max(1) as f64; n_arguments],
+            scale: self.scale,
+        }
+        .mutate(child, mutate_prob, rng)
+    }
+    fn adapt(&self, population: &[(Solution, Option<f64>)]) -> Option<Box<dyn Mutation>> {
+        let mut state = self
+            .probabilities
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let (generations_since_update, probabilities) = &mut *state;
+        let dimension = population
+            .first()
+            .map_or(0, |(solution, _)| solution.get_arguments().len());
+        let outdated = probabilities
+            .as_ref()
+            .is_none_or(|probabilities| probabilities.len() != dimension);
+        if outdated || *generations_since_update >= self.update_every {
+            *probabilities = Some(self.probabilities(&sensitivity::sensitivities(population)));
+            *generations_since_update = 0;
+        }
+        *generations_since_update += 1;
+        Some(Box::new(WeightedMutation {
+            probabilities: probabilities.clone()?,
+            scale: self.scale,
+        }))
+    }
+}
+
+/// Shifts an argument drawn with the given probabilities by a normally distributed step, see
+/// `SensitivityMutation`.
+struct WeightedMutation {
+    /// The probability of mutating every argument.
+    probabilities: Vec<f64>,
+    /// The standard deviation of the step.
+    scale: f64,
+}
+
+impl Mutation for WeightedMutation {
+    fn mutate(&self, child: Solution, mutate_prob: f32, rng: &mut dyn RngCore) -> Solution {
+        let mut arguments = child.get_arguments();
+        if arguments.len() != self.probabilities.len()
+            || arguments.is_empty()
+            || rng.gen_range(0.0..1.0) > mutate_prob
+        {
+            return child;
+        }
+        let mut point = rng.gen_range(0.0..1.0) * self.probabilities.iter().sum::<f64>();
+        let mut idx = 0;
+        while idx + 1 < self.probabilities.len() && point >= self.probabilities[idx] {
+            point -= self.probabilities[idx];
+            idx += 1;
+        }
+        arguments[idx] += self.scale * rng::standard_normal(rng);
+        child.with_arguments(arguments)
+    }
+}
+
+/// The sample covariance of points of the same length, `None` for fewer than two points or
+/// if they all coincide.
+///
+/// # Arguments
+///
+/// * `points` - The arguments of every point.
+fn covariance(points: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let dimension = points.first()?.len();
+    if points.len() < 2 || points.iter().any(|point| point.len() != dimension) {
+        return None;
+    }
+    let n_points = points.len() as f64;
+    let mean = (0..dimension)
+        .map(|idx| points.iter().map(|point| point[idx]).sum::<f64>() / n_points)
+        .collect::<Vec<f64>>();
+    let covariance = (0..dimension)
+        .map(|row| {
+            (0..dimension)
+                .map(|column| {
+                    points
+                        .iter()
+                        .map(|point| (point[row] - mean[row]) * (point[column] - mean[column]))
+                        .sum::<f64>()
+                        / (n_points - 1.0)
+                })
+                .collect()
+        })
+        .collect::<Vec<Vec<f64>>>();
+    let trace = (0..dimension).map(|idx| covariance[idx][idx]).sum::<f64>();
+    (trace > 0.0 && trace.is_finite()).then_some(covariance)
+}
+
+/// The lower triangular Cholesky factor of a covariance. Fewer elites than arguments give a
+/// singular covariance, a small multiple of its mean variance is added to the diagonal so
+/// that every direction keeps a little spread.
+///
+/// # Arguments
+///
+/// * `covariance` - A symmetric positive semi-definite matrix.
+fn cholesky(covariance: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let dimension = covariance.len();
+    let ridge =
+        1e-6 * (0..dimension).map(|idx| covariance[idx][idx]).sum::<f64>() / dimension as f64;
+    let mut factor = vec![vec![0.0; dimension]; dimension];
+    for row in 0..dimension {
+        for column in 0..=row {
+            let sum = covariance[row][column]
+                - (0..column)
+                    .map(|idx| factor[row][idx] * factor[column][idx])
+                    .sum::<f64>();
+            factor[row][column] = if row == column {
+                let diagonal = sum + ridge;
+                if diagonal <= 0.0 {
+                    return None;
+                }
+                diagonal.sqrt()
+            } else {
+                sum / factor[column][column]
+            };
+        }
+    }
+    Some(factor)
+}
+
+/// Leave the child as it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NoRepair;
+
+impl Repair for NoRepair {
+    fn repair(&self, child: Solution) -> Solution {
+        child
+    }
+}
+
+/// Keep every child.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct KeepAll;
+
+impl Filter for KeepAll {
+    fn keep(&self, _child: &Solution) -> bool {
+        true
+    }
+}
+
+impl<F> Crossover for F
+where
+    F: Fn(&Solution, &Solution, &mut dyn RngCore) -> Solution + Send + Sync,
+{
+    fn crossover(
+        &self,
+        parent_a: &Solution,
+        parent_b: &Solution,
+        rng: &mut dyn RngCore,
+    ) -> Solution {
+        self(parent_a, parent_b, rng)
+    }
+}
+
+impl<F> Mutation for F
+where
+    F: Fn(Solution, f32, &mut dyn RngCore) -> Solution + Send + Sync,
+{
+    fn mutate(&self, child: Solution, mutate_prob: f32, rng: &mut dyn RngCore) -> Solution {
+        self(child, mutate_prob, rng)
+    }
+}
+
+impl<F> Repair for F
+where
+    F: Fn(Solution) -> Solution + Send + Sync,
+{
+    fn repair(&self, child: Solution) -> Solution {
+        self(child)
+    }
+}
+
+impl<F> Filter for F
+where
+    F: Fn(&Solution) -> bool + Send + Sync,
+{
+    fn keep(&self, child: &Solution) -> bool {
+        self(child)
+    }
+}
+
+/// The reproduction step of a generation:
+/// `select parents -> crossover -> mutate -> repair -> filter`.
+/// Every stage can be replaced, the default pipeline crosses over all pairs of individuals by
+/// averaging, mutates multiplicatively and keeps every child.
+pub struct Pipeline {
+    parent_selector: Box<dyn ParentSelector>,
+    crossover: Box<dyn Crossover>,
+    mutation: Box<dyn Mutation>,
+    repair: Box<dyn Repair>,
+    filter: Box<dyn Filter>,
+    /// Whether the mutation stage was replaced, known bounds don't override it then.
+    custom_mutation: bool,
+    /// The bounds of the search space, if known. Checked offspring have to stay within them.
+    bounds: Option<Bounds>,
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Pipeline {
+            parent_selector: Box::new(AllPairs),
+            crossover: Box::new(AverageCrossover),
+            mutation: Box::new(MultiplicativeMutation),
+            repair: Box::new(NoRepair),
+            filter: Box::new(KeepAll),
+            custom_mutation: false,
+            bounds: None,
+        }
+    }
+}
+
+impl fmt::Debug for Pipeline {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("Pipeline").finish_non_exhaustive()
+    }
+}
+
+impl Pipeline {
+    /// Replace the parent selection stage.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent_selector` - The new stage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::{AllPairs, Pipeline};
+    ///
+    /// let pipeline = Pipeline::default().with_parent_selector(AllPairs);
+    /// ```
+    pub fn with_parent_selector<S>(mut self, parent_selector: S) -> Self
+    where
+        S: ParentSelector + 'static,
+    {
+        self.parent_selector = Box::new(parent_selector);
+        self
+    }
+    /// Replace the crossover stage.
+    ///
+    /// # Arguments
+    ///
+    /// * `crossover` - The new stage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::Pipeline;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// // Children are copies of their first parent.
+    /// let pipeline = Pipeline::default().with_crossover(
+    ///     |parent_a: &Solution, _: &Solution, _: &mut dyn rand::RngCore| parent_a.clone(),
+    /// );
+    /// ```
+    pub fn with_crossover<C>(mut self, crossover: C) -> Self
+    where
+        C: Crossover + 'static,
+    {
+        self.crossover = Box::new(crossover);
+        self
+    }
+    /// Replace the mutation stage.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutation` - The new stage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::Pipeline;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// // Never mutate.
+    /// let pipeline = Pipeline::default()
+    ///     .with_mutation(|child: Solution, _: f32, _: &mut dyn rand::RngCore| child);
+    /// ```
+    pub fn with_mutation<M>(mut self, mutation: M) -> Self
+    where
+        M: Mutation + 'static,
+    {
+        self.mutation = Box::new(mutation);
+        self.custom_mutation = true;
+        self
+    }
+    /// Let the mutation step size scale with the width of the bounds of the search space, see
+    /// `BoundedMutation`. A mutation stage set with `with_mutation` is kept. Offspring produced
+    /// by `reproduce_checked` have to stay within the bounds. Unless the policy of the bounds
+    /// is `BoundaryPolicy::Ignore`, children are brought back into the bounds after the
+    /// crossover and after the mutation, see `Bounds::confine`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bounds` - The bounds of every argument, e.g. a `Vec<Range<f64>>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::bounds::{BoundaryPolicy, Bounds};
+    /// use genetic_algorithm_fn::pipeline::Pipeline;
+    ///
+    /// let pipeline = Pipeline::default().with_bounds(vec![0.0..10.0, -5.0..5.0]);
+    /// let reflecting = Pipeline::default()
+    ///     .with_bounds(Bounds::new(vec![0.0..10.0]).with_policy(BoundaryPolicy::Reflect));
+    /// ```
+    pub fn with_bounds<B>(mut self, bounds: B) -> Self
+    where
+        B: Into<Bounds>,
+    {
+        let bounds = bounds.into();
+        if !self.custom_mutation {
+            self.mutation = Box::new(BoundedMutation::new(bounds.ranges().to_vec()));
+        }
+        self.bounds = Some(bounds);
+        self
+    }
+    /// The bounds of the search space, if known.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::Pipeline;
+    ///
+    /// assert_eq!(Pipeline::default().bounds(), None);
+    /// assert_eq!(
+    ///     Pipeline::default().with_bounds(vec![0.0..1.0]).bounds(),
+    ///     Some(&[0.0..1.0][..])
+    /// );
+    /// ```
+    pub fn bounds(&self) -> Option<&[Range<f64>]> {
+        self.bounds.as_ref().map(Bounds::ranges)
+    }
+    /// Replace the repair stage.
+    ///
+    /// # Arguments
+    ///
+    /// * `repair` - The new stage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::Pipeline;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// // Clamp all arguments to be non-negative.
+    /// let pipeline = Pipeline::default().with_repair(|child: Solution| {
+    ///     Solution::new(child.get_arguments().iter().map(|x| x.max(0.0)).collect())
+    /// });
+    /// ```
+    pub fn with_repair<R>(mut self, repair: R) -> Self
+    where
+        R: Repair + 'static,
+    {
+        self.repair = Box::new(repair);
+        self
+    }
+    /// Replace the filter stage.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The new stage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::Pipeline;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// // Drop children with negative arguments.
+    /// let pipeline = Pipeline::default()
+    ///     .with_filter(|child: &Solution| child.get_arguments().iter().all(|x| *x >= 0.0));
+    /// ```
+    pub fn with_filter<F>(mut self, filter: F) -> Self
+    where
+        F: Filter + 'static,
+    {
+        self.filter = Box::new(filter);
+        self
+    }
+    /// Run all stages and return the offspring of `population`.
+    ///
+    /// # Arguments
+    ///
+    /// * `population` - The current individuals and their fitness, if already evaluated.
+    /// * `mutate_prob` - The mutation probability currently in effect.
+    /// * `rng` - The random number generator all stages draw from.
+    /// * `streams` - If given, the seed and generation from which every child derives its own
+    ///   random number stream instead of drawing from `rng`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::Pipeline;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let offspring = Pipeline::default().reproduce(
+    ///     &[(Solution::new(vec![1.0]), None), (Solution::new(vec![3.0]), None)],
+    ///     0.0,
+    ///     &mut rand::thread_rng(),
+    ///     None,
+    /// );
+    /// assert_eq!(offspring, vec![Solution::new(vec![2.0]), Solution::new(vec![2.0])]);
+    /// ```
+    pub fn reproduce(
+        &self,
+        population: &[(Solution, Option<f64>)],
+        mutate_prob: f32,
+        rng: &mut dyn RngCore,
+        streams: Option<(u64, u64)>,
+    ) -> Vec<Solution> {
+        self.reproduce_with(population, mutate_prob, rng, streams, 1.0, None, &[], false)
+            .expect("Unchecked stages can't fail")
+    }
+    /// Run all stages like `reproduce`, but check the output of every stage: children have
+    /// the length of their parents, finite arguments and stay within the bounds, if known.
+    /// Returns an error naming the offending stage and its inputs at the first violation.
+    ///
+    /// # Arguments
+    ///
+    /// * `population` - The current individuals and their fitness, if already evaluated.
+    /// * `mutate_prob` - The mutation probability currently in effect.
+    /// * `rng` - The random number generator all stages draw from.
+    /// * `streams` - If given, the seed and generation from which every child derives its own
+    ///   random number stream instead of drawing from `rng`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::{Pipeline, Stage};
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let error = Pipeline::default()
+    ///     .with_mutation(|_: Solution, _: f32, _: &mut dyn rand::RngCore| {
+    ///         Solution::new(vec![f64::NAN])
+    ///     })
+    ///     .reproduce_checked(
+    ///         &[(Solution::new(vec![1.0]), None), (Solution::new(vec![3.0]), None)],
+    ///         1.0,
+    ///         &mut rand::thread_rng(),
+    ///         None,
+    ///     )
+    ///     .unwrap_err();
+    /// assert_eq!(error.stage, Stage::Mutation);
+    /// ```
+    pub fn reproduce_checked(
+        &self,
+        population: &[(Solution, Option<f64>)],
+        mutate_prob: f32,
+        rng: &mut dyn RngCore,
+        streams: Option<(u64, u64)>,
+    ) -> Result<Vec<Solution>, OperatorError> {
+        self.reproduce_with(population, mutate_prob, rng, streams, 1.0, None, &[], true)
+    }
+    /// Run all stages like `reproduce`, optionally dropping the pairs of identical parents the
+    /// parent selection stage picked, see `DistinctParents`, and checking the output of every
+    /// stage like `reproduce_checked`.
+    ///
+    /// # Arguments
+    ///
+    /// * `population` - The current individuals and their fitness, if already evaluated.
+    /// * `mutate_prob` - The mutation probability currently in effect.
+    /// * `rng` - The random number generator all stages draw from.
+    /// * `streams` - The seed and generation of the per-child random number streams, if any.
+    /// * `crossover_prob` - The probability that a pair of parents is crossed over instead of
+    ///   the first parent being copied.
+    /// * `distinct_parents` - If given, parents whose arguments all differ by at most this
+    ///   epsilon are not crossed over.
+    /// * `frozen` - Which arguments the mutation must not change, see `mutate_unfrozen`.
+    /// * `check_operators` - Whether the output of every stage is checked.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn reproduce_with(
+        &self,
+        population: &[(Solution, Option<f64>)],
+        mutate_prob: f32,
+        rng: &mut dyn RngCore,
+        streams: Option<(u64, u64)>,
+        crossover_prob: f32,
+        distinct_parents: Option<f64>,
+        frozen: &[bool],
+        check_operators: bool,
+    ) -> Result<Vec<Solution>, OperatorError> {
+        let mut parents = match streams {
+            // The parent selection gets a stream no child can collide with.
+            Some((seed, generation)) => self.parent_selector.select_parents(
+                population,
+                &mut SplitMix64::stream(seed, generation, u64::MAX),
+            ),
+            None => self.parent_selector.select_parents(population, rng),
+        };
+        if let Some(epsilon) = distinct_parents {
+            parents = distinct_pairs(population, parents, epsilon);
+        }
+        let check = |stage, inputs: &[&Solution], output: Solution| {
+            if check_operators {
+                check_output(stage, inputs, output, self.bounds())
+            } else {
+                Ok(output)
+            }
+        };
+        let adapted_crossover = self.crossover.adapt(population);
+        let crossover = adapted_crossover.as_deref().unwrap_or(&*self.crossover);
+        let adapted_mutation = self.mutation.adapt(population);
+        let mutation = adapted_mutation.as_deref().unwrap_or(&*self.mutation);
+        let mut offspring = Vec::new();
+        for (index, (idx_a, idx_b)) in parents.into_iter().enumerate() {
+            let mut child_stream = streams
+                .map(|(seed, generation)| SplitMix64::stream(seed, generation, index as u64));
+            let child_rng: &mut dyn RngCore = match child_stream.as_mut() {
+                Some(stream) => stream,
+                None => &mut *rng,
+            };
+            let (parent_a, parent_b) = (&population[idx_a].0, &population[idx_b].0);
+            let confine = |child: Solution, rng: &mut dyn RngCore| match &self.bounds {
+                Some(bounds) => bounds.confine(child, rng),
+                None => child,
+            };
+            // Only draw if needed, so runs crossing over all pairs keep their random numbers.
+            let child = if crossover_prob < 1.0 && child_rng.gen_range(0.0..1.0) >= crossover_prob {
+                parent_a.clone()
+            } else {
+                check(
+                    Stage::Crossover,
+                    &[parent_a, parent_b],
+                    crossover.crossover_with_fitness(
+                        (parent_a, population[idx_a].1),
+                        (parent_b, population[idx_b].1),
+                        child_rng,
+                    ),
+                )?
+            };
+            let child = confine(child, child_rng);
+            let mutated = apply_stage(
+                Stage::Mutation,
+                child,
+                |child| mutate_unfrozen(mutation, child, mutate_prob, frozen, child_rng),
+                check_operators,
+                self.bounds(),
+            )?;
+            let mutated = confine(mutated, child_rng);
+            let repaired = apply_stage(
+                Stage::Repair,
+                mutated,
+                |mutated| self.repair.repair(mutated),
+                check_operators,
+                self.bounds(),
+            )?;
+            if self.filter.keep(&repaired) {
+                offspring.push(repaired);
+            }
+        }
+        Ok(offspring)
+    }
+}
+
+/// Run the stage `operator` on `input` and, if `check_operators` is set, check its output like
+/// `check_output`. The input is only cloned to be checked against.
+///
+/// # Arguments
+///
+/// * `stage` - The stage `operator` runs.
+/// * `input` - The solution the stage gets.
+/// * `operator` - Runs the stage.
+/// * `check_operators` - Whether the output is checked.
+/// * `bounds` - The bounds of the search space, if known.
+fn apply_stage<O>(
+    stage: Stage,
+    input: Solution,
+    operator: O,
+    check_operators: bool,
+    bounds: Option<&[Range<f64>]>,
+) -> Result<Solution, OperatorError>
+where
+    O: FnOnce(Solution) -> Solution,
+{
+    if check_operators {
+        let output = operator(input.clone());
+        check_output(stage, &[&input], output, bounds)
+    } else {
+        Ok(operator(input))
+    }
+}
+
+/// How often a mutation that only changed frozen arguments is retried, see `mutate_unfrozen`.
+const MAX_FROZEN_MUTATION_ATTEMPTS: usize = 8;
+
+/// Mutate `child` without changing its frozen arguments. Changes to them are undone and a
+/// mutation that only changed frozen arguments is retried, so the perturbation lands on an
+/// argument that still varies instead of being wasted.
+///
+/// # Arguments
+///
+/// * `mutation` - The mutation stage.
+/// * `child` - The child that should be mutated.
+/// * `mutate_prob` - The mutation probability currently in effect.
+/// * `frozen` - Which arguments must not change, arguments beyond its end may.
+/// * `rng` - The random number generator to draw from.
+fn mutate_unfrozen(
+    mutation: &dyn Mutation,
+    child: Solution,
+    mutate_prob: f32,
+    frozen: &[bool],
+    rng: &mut dyn RngCore,
+) -> Solution {
+    if !frozen.contains(&true) {
+        return mutation.mutate(child, mutate_prob, rng);
+    }
+    let arguments = child.get_arguments();
+    for _ in 0..MAX_FROZEN_MUTATION_ATTEMPTS {
+        let mutated = mutation.mutate(child.clone(), mutate_prob, rng);
+        let mut mutated_arguments = mutated.get_arguments();
+        // Children of a different length are left to the checks of the pipeline.
+        if mutated_arguments.len() != arguments.len() {
+            return mutated;
+        }
+        let mut changed_frozen = false;
+        for ((mutated_argument, argument), frozen) in
+            mutated_arguments.iter_mut().zip(&arguments).zip(frozen)
+        {
+            if *frozen && mutated_argument != argument {
+                *mutated_argument = *argument;
+                changed_frozen = true;
+            }
+        }
+        let unfrozen = mutated.with_arguments(mutated_arguments);
+        if !changed_frozen || unfrozen != child {
+            return unfrozen;
+        }
+    }
+    child
+}
+
+/// A stage of the pipeline that creates or changes a child.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// The crossover stage.
+    Crossover,
+    /// The mutation stage.
+    Mutation,
+    /// The repair stage.
+    Repair,
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Stage::Crossover => write!(f, "crossover"),
+            Stage::Mutation => write!(f, "mutation"),
+            Stage::Repair => write!(f, "repair"),
+        }
+    }
+}
+
+/// An invariant the output of a stage broke.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    /// The child has a different number of arguments than its parents.
+    WrongLength {
+        /// The number of arguments of the input.
+        expected: usize,
+        /// The number of arguments of the output.
+        actual: usize,
+    },
+    /// An argument of the child is infinite or NaN.
+    NotFinite {
+        /// The index of the argument.
+        index: usize,
+        /// The value of the argument.
+        value: f64,
+    },
+    /// An argument of the child is outside of its bounds. Only the output of the repair stage,
+    /// i.e. the final child, has to be within the bounds.
+    OutOfBounds {
+        /// The index of the argument.
+        index: usize,
+        /// The value of the argument.
+        value: f64,
+        /// The bounds of the argument.
+        bounds: Range<f64>,
+    },
+}
+
+/// Error returned by `Pipeline::reproduce_checked` when a stage broke an invariant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperatorError {
+    /// The offending stage.
+    pub stage: Stage,
+    /// The solutions the stage got, i.e. both parents of a crossover.
+    pub inputs: Vec<Solution>,
+    /// The solution the stage returned.
+    pub output: Solution,
+    /// The invariant that was broken.
+    pub violation: Violation,
+}
+
+impl fmt::Display for OperatorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The {} stage ", self.stage)?;
+        match &self.violation {
+            Violation::WrongLength { expected, actual } => {
+                write!(f, "returned {} arguments instead of {}", actual, expected)?
+            }
+            Violation::NotFinite { index, value } => write!(
+                f,
+                "returned the non-finite argument {} at index {}",
+                value, index
+            )?,
+            Violation::OutOfBounds {
+                index,
+                value,
+                bounds,
+            } => write!(
+                f,
+                "returned the argument {} at index {} outside of its bounds {:?}",
+                value, index, bounds
+            )?,
+        }
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|input| input.to_string())
+            .collect::<Vec<String>>();
+        write!(f, ": {} -> {}", inputs.join(", "), self.output)
+    }
+}
+
+impl std::error::Error for OperatorError {}
+
+/// Check that a stage kept the length of its first input, returned finite arguments and, if it
+/// is the repair stage, stayed within `bounds`.
+///
+/// # Arguments
+///
+/// * `stage` - The stage that produced `output`.
+/// * `inputs` - The solutions the stage got.
+/// * `output` - The solution the stage returned.
+/// * `bounds` - The bounds of the search space, if known.
+fn check_output(
+    stage: Stage,
+    inputs: &[&Solution],
+    output: Solution,
+    bounds: Option<&[Range<f64>]>,
+) -> Result<Solution, OperatorError> {
+    let arguments = output.get_arguments();
+    let expected = inputs[0].get_arguments().len();
+    let violation = if arguments.len() != expected {
+        Some(Violation::WrongLength {
+            expected,
+            actual: arguments.len(),
+        })
+    } else if let Some((index, value)) = arguments
+        .iter()
+        .enumerate()
+        .find(|(_, value)| !value.is_finite())
+    {
+        Some(Violation::NotFinite {
+            index,
+            value: *value,
+        })
+    } else {
+        bounds
+            .filter(|_| stage == Stage::Repair)
+            .and_then(|bounds| {
+                arguments
+                    .iter()
+                    .zip(bounds)
+                    .enumerate()
+                    .find(|(_, (value, bound))| !(bound.start..=bound.end).contains(*value))
+            })
+            .map(|(index, (value, bound))| Violation::OutOfBounds {
+                index,
+                value: *value,
+                bounds: bound.clone(),
+            })
+    };
+    match violation {
+        Some(violation) => Err(OperatorError {
+            stage,
+            inputs: inputs.iter().map(|input| (*input).clone()).collect(),
+            output,
+            violation,
+        }),
+        None => Ok(output),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounds::BoundaryPolicy;
+
+    fn population() -> Vec<(Solution, Option<f64>)> {
+        vec![
+            (Solution::new(vec![1.0, 2.0]), None),
+            (Solution::new(vec![3.0, 4.0]), Some(1.0)),
+            (Solution::new(vec![5.0, 6.0]), None),
+        ]
+    }
+    #[test]
+    fn roulette_handles_negative_fitness() {
+        let population = vec![
+            (Solution::new(vec![1.0]), Some(-10.0)),
+            (Solution::new(vec![2.0]), Some(-1.0)),
+            (Solution::new(vec![3.0]), None),
+        ];
+        let selection = RouletteSelection {
+            n_pairs: Some(200),
+            ..RouletteSelection::default()
+        };
+        let pairs = selection.select_parents(&population, &mut rand::thread_rng());
+        assert_eq!(pairs.len(), 200);
+        // The worst and the unevaluated individual only have a weight of epsilon.
+        assert!(pairs
+            .iter()
+            .all(|(idx_a, idx_b)| *idx_a == 1 && *idx_b == 1));
+        let uniform = RouletteSelection {
+            scaling: FitnessScaling::Raw,
+            n_pairs: None,
+        }
+        .select_parents(&[(Solution::new(vec![1.0]), None)], &mut rand::thread_rng());
+        assert_eq!(uniform, vec![(0, 0)]);
+    }
+    #[test]
+    #[should_panic(expected = "non-negative weights")]
+    fn raw_roulette_rejects_negative_fitness() {
+        RouletteSelection {
+            scaling: FitnessScaling::Raw,
+            n_pairs: None,
+        }
+        .select_parents(
+            &[(Solution::new(vec![1.0]), Some(-1.0))],
+            &mut rand::thread_rng(),
+        );
+    }
+    #[test]
+    fn all_pairs_excludes_self() {
+        assert_eq!(
+            AllPairs.select_parents(&population(), &mut rand::thread_rng()),
+            vec![(0, 1), (0, 2), (1, 0), (1, 2), (2, 0), (2, 1)]
+        );
+    }
+    #[test]
+    fn distinct_parents_drops_identical_pairs() {
+        let mut population = population();
+        population.push((Solution::new(vec![1.0, 2.0 + 1e-9]), None));
+        let pairs = DistinctParents::new(AllPairs, 1e-6)
+            .select_parents(&population, &mut rand::thread_rng());
+        assert_eq!(pairs.len(), 10);
+        assert!(!pairs.contains(&(0, 3)) && !pairs.contains(&(3, 0)));
+        let offspring = Pipeline::default()
+            .reproduce_with(
+                &population,
+                0.0,
+                &mut rand::thread_rng(),
+                None,
+                1.0,
+                Some(1e-6),
+                &[],
+                false,
+            )
+            .unwrap();
+        assert_eq!(offspring.len(), 10);
+    }
+    #[test]
+    fn covariance_mutation_follows_correlated_elites() {
+        // The elites lie on the diagonal, so every shift moves both arguments alike.
+        let population = (0..6)
+            .map(|idx| {
+                (
+                    Solution::new(vec![idx as f64, idx as f64]),
+                    Some(idx as f64),
+                )
+            })
+            .collect::<Vec<(Solution, Option<f64>)>>();
+        let mutation = CovarianceMutation {
+            n_elites: 4,
+            scale: 1.0,
+        }
+        .adapt(&population)
+        .unwrap();
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let arguments = mutation
+                .mutate(Solution::new(vec![0.0, 0.0]), 1.0, &mut rng)
+                .get_arguments();
+            assert!((arguments[0] - arguments[1]).abs() < 0.1);
+        }
+        assert!(CovarianceMutation::default()
+            .adapt(&[(Solution::new(vec![1.0, 1.0]), Some(1.0))])
+            .is_none());
+    }
+    #[test]
+    fn levy_mutation_has_heavier_tails_with_a_smaller_alpha() {
+        let n_long_jumps = |alpha| {
+            let mutation = LevyMutation { alpha, scale: 1.0 };
+            let mut rng = SplitMix64::new(7);
+            (0..2000)
+                .filter(|_| {
+                    let arguments = mutation
+                        .mutate(Solution::new(vec![0.0, 0.0]), 1.0, &mut rng)
+                        .get_arguments();
+                    assert!(arguments.contains(&0.0));
+                    arguments.iter().any(|argument| argument.abs() > 10.0)
+                })
+                .count()
+        };
+        assert_eq!(n_long_jumps(2.0), 0);
+        assert!(n_long_jumps(0.8) > 20);
+    }
+    #[test]
+    fn line_crossover_extrapolates_beyond_the_fitter_parent() {
+        let crossover = LineCrossover { extrapolation: 1.0 };
+        let (worse, better) = (Solution::new(vec![0.0, 0.0]), Solution::new(vec![1.0, 2.0]));
+        let mut rng = SplitMix64::new(1);
+        let children = (0..200)
+            .map(|_| {
+                crossover
+                    .crossover_with_fitness((&worse, Some(1.0)), (&better, Some(2.0)), &mut rng)
+                    .get_arguments()
+            })
+            .collect::<Vec<Vec<f64>>>();
+        for child in &children {
+            assert!((0.0..=2.0).contains(&child[0]));
+            assert!((child[1] - 2.0 * child[0]).abs() < 1e-6);
+        }
+        assert!(children.iter().any(|child| child[0] > 1.0));
+        assert!(children.iter().any(|child| child[0] < 1.0));
+    }
+    #[test]
+    #[should_panic(expected = "Cannot crossover the solutions")]
+    fn line_crossover_rejects_parents_of_different_length() {
+        LineCrossover::default().crossover(
+            &Solution::new(vec![0.0, 0.0]),
+            &Solution::new(vec![1.0]),
+            &mut SplitMix64::new(1),
+        );
+    }
+    #[test]
+    fn multi_parent_crossover_recombines_around_the_parents() {
+        let population = (0..5)
+            .map(|x| (Solution::new(vec![x as f64, 0.0, 1.0]), None))
+            .collect::<Vec<(Solution, Option<f64>)>>();
+        let (parent_a, parent_b) = (&population[0].0, &population[4].0);
+        let mut rng = SplitMix64::new(3);
+        // Without perturbation the child is the center of mass of its parents.
+        let center_of_mass = MultiParentCrossover {
+            n_parents: 4,
+            recombination: Recombination::CenterOfMass { perturbation: 0.0 },
+        }
+        .adapt(&population)
+        .unwrap();
+        for _ in 0..20 {
+            let child = center_of_mass
+                .crossover(parent_a, parent_b, &mut rng)
+                .get_arguments();
+            assert!((1.0..=3.0).contains(&child[0]));
+            assert_eq!(child[1..], [0.0, 1.0]);
+        }
+        // All parents lie on a line, so UNDX spreads along it only.
+        let undx = MultiParentCrossover::default().adapt(&population).unwrap();
+        let children = (0..50)
+            .map(|_| undx.crossover(parent_a, parent_b, &mut rng).get_arguments())
+            .collect::<Vec<Vec<f64>>>();
+        assert!(children.iter().all(|child| child[1..] == [0.0, 1.0]));
+        assert!(children.iter().any(|child| child[0] != 2.0));
+    }
+    #[test]
+    fn uniform_crossover_respects_the_mask() {
+        let (parent_a, parent_b) = (
+            Solution::new(vec![0.0, 0.0, 0.0, 0.0]),
+            Solution::new(vec![1.0, 1.0, 1.0, 1.0]),
+        );
+        let crossover = UniformCrossover {
+            swap_prob: 0.5,
+            mask: Some(vec![false, true, false, false]),
+        };
+        let mut rng = SplitMix64::new(5);
+        let children = (0..50)
+            .map(|_| {
+                crossover
+                    .crossover(&parent_a, &parent_b, &mut rng)
+                    .get_arguments()
+            })
+            .collect::<Vec<Vec<f64>>>();
+        assert!(children
+            .iter()
+            .all(|child| child[0] == 0.0 && child[2] == 0.0 && child[3] == 0.0));
+        assert!(children.iter().any(|child| child[1] == 1.0));
+        assert!(children.iter().any(|child| child[1] == 0.0));
+    }
+    #[test]
+    #[should_panic(expected = "one entry per argument")]
+    fn uniform_crossover_rejects_a_short_mask() {
+        UniformCrossover {
+            swap_prob: 0.5,
+            mask: Some(vec![true, false]),
+        }
+        .crossover(
+            &Solution::new(vec![0.0, 0.0, 0.0]),
+            &Solution::new(vec![1.0, 1.0, 1.0]),
+            &mut SplitMix64::new(1),
+        );
+    }
+    #[test]
+    #[should_panic(expected = "Cannot crossover the solutions")]
+    fn uniform_crossover_rejects_parents_of_different_length() {
+        UniformCrossover::default().crossover(
+            &Solution::new(vec![0.0, 0.0]),
+            &Solution::new(vec![1.0]),
+            &mut SplitMix64::new(1),
+        );
+    }
+    #[test]
+    fn block_operators_keep_groups_together() {
+        let groups = GeneGroups::new(vec![3..5, 0..2, 1..3]);
+        assert_eq!(groups.blocks(6), vec![0..3, 3..5, 5..6]);
+        assert_eq!(groups.blocks(4), vec![0..3, 3..4]);
+        let (parent_a, parent_b) = (Solution::new(vec![0.0; 6]), Solution::new(vec![1.0; 6]));
+        let crossover = BlockCrossover {
+            groups: groups.clone(),
+            swap_prob: 0.5,
+        };
+        let mutation = BlockMutation {
+            groups,
+            jitter: 0.1,
+        };
+        let mut rng = SplitMix64::new(8);
+        let mut children = Vec::new();
+        for _ in 0..50 {
+            let child = crossover
+                .crossover(&parent_a, &parent_b, &mut rng)
+                .get_arguments();
+            assert!(child[..3].iter().all(|x| *x == child[0]));
+            assert_eq!(child[3], child[4]);
+            children.push(child);
+        }
+        assert!(children.iter().any(|child| child[0] != child[3]));
+        for _ in 0..50 {
+            let mutated = mutation
+                .mutate(parent_b.clone(), 1.0, &mut rng)
+                .get_arguments();
+            let changed = mutated.iter().map(|x| *x != 1.0).collect::<Vec<bool>>();
+            assert!(
+                changed == [true, true, true, false, false, false]
+                    || changed == [false, false, false, true, true, false]
+                    || changed == [false, false, false, false, false, true]
+            );
+        }
+    }
+    #[test]
+    fn sensitivity_mutation_matches_the_sensitivities() {
+        // Only the first argument affects the fitness, the second one is nearly uncorrelated.
+        let second = [1.0, -1.0, -1.0, 1.0, -1.0, 1.0, 1.0, -1.0, -1.0, 1.0];
+        let population = (0..10)
+            .map(|x| (Solution::new(vec![x as f64, second[x]]), Some(x as f64)))
+            .collect::<Vec<(Solution, Option<f64>)>>();
+        let n_mutated_first = |bias| {
+            let mutation = SensitivityMutation::new(bias).with_update_every(3);
+            let adapted = mutation.adapt(&population).unwrap();
+            let mut rng = SplitMix64::new(4);
+            let child = Solution::new(vec![1.0, 1.0]);
+            (0..1000)
+                .filter(|_| adapted.mutate(child.clone(), 1.0, &mut rng).get_arguments()[0] != 1.0)
+                .count()
+        };
+        assert!(n_mutated_first(SensitivityBias::MostSensitive) > 800);
+        assert!(n_mutated_first(SensitivityBias::LeastSensitive) < 200);
+        // In between updates the probabilities are kept, even if the population changed.
+        let mutation =
+            SensitivityMutation::new(SensitivityBias::MostSensitive).with_update_every(2);
+        mutation.adapt(&population);
+        let unevaluated = vec![(Solution::new(vec![0.0, 0.0]), None); 3];
+        mutation.adapt(&unevaluated);
+        assert_ne!(
+            mutation.probabilities.lock().unwrap().1,
+            Some(vec![0.5, 0.5])
+        );
+        mutation.adapt(&unevaluated);
+        assert_eq!(
+            mutation.probabilities.lock().unwrap().1,
+            Some(vec![0.5, 0.5])
+        );
+    }
+    #[test]
+    fn linear_rank_ignores_fitness_differences() {
+        let population = [
+            (Solution::new(vec![1.0]), Some(1e12)),
+            (Solution::new(vec![2.0]), Some(-5.0)),
+            (Solution::new(vec![3.0]), None),
+            (Solution::new(vec![4.0]), Some(0.0)),
+        ];
+        assert_eq!(
+            FitnessScaling::LinearRank { pressure: 1.5 }.weights(&population),
+            Ok(vec![1.5, 0.5, 0.5, 1.0])
+        );
+        assert_eq!(
+            FitnessScaling::LinearRank { pressure: 1.0 }.weights(&population),
+            Ok(vec![1.0; 4])
+        );
+    }
+    #[test]
+    fn frozen_arguments_are_never_mutated() {
+        let mutation = BoundedMutation::new(vec![0.0..10.0; 3]);
+        let child = Solution::new(vec![1.0, 2.0, 3.0]);
+        let mut rng = rand::thread_rng();
+        let mutated = (0..50)
+            .map(|_| {
+                mutate_unfrozen(
+                    &mutation,
+                    child.clone(),
+                    1.0,
+                    &[true, false, true],
+                    &mut rng,
+                )
+            })
+            .collect::<Vec<Solution>>();
+        for solution in &mutated {
+            let arguments = solution.get_arguments();
+            assert_eq!((arguments[0], arguments[2]), (1.0, 3.0));
+        }
+        assert!(mutated.iter().any(|solution| *solution != child));
+    }
+    #[test]
+    fn jittered_mutation_moves_zero_arguments() {
+        let zero = Solution::new(vec![0.0, 0.0]);
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            assert_eq!(
+                MultiplicativeMutation.mutate(zero.clone(), 1.0, &mut rng),
+                zero
+            );
+            let mutated = JitteredMutation { jitter: 0.5 }.mutate(zero.clone(), 1.0, &mut rng);
+            assert_ne!(mutated, zero);
+            assert!(mutated.get_arguments().iter().all(|x| x.abs() <= 0.5));
+        }
+        // Without jitter both mutations draw the same random numbers.
+        let mutate = |mutation: &dyn Mutation| {
+            mutation.mutate(Solution::new(vec![1.0, 2.0]), 1.0, &mut SplitMix64::new(4))
+        };
+        assert_eq!(
+            mutate(&JitteredMutation { jitter: 0.0 }),
+            mutate(&MultiplicativeMutation)
+        );
+    }
+    #[cfg(feature = "autodiff")]
+    #[test]
+    fn gradient_mutation_climbs_the_objective() {
+        use crate::autodiff::{Differentiable, Float};
+        use crate::function::{Function, FunctionError};
+
+        struct Paraboloid;
+        impl Differentiable for Paraboloid {
+            fn evaluate<T: Float>(&self, x: &[T]) -> Result<T, FunctionError> {
+                Ok(-(x[0] - T::constant(3.0)).powi(2) - (x[1] + T::constant(1.0)).powi(2))
+            }
+        }
+        let function = Function::new_autodiff(Paraboloid);
+        let mutation = GradientMutation::new(&function).unwrap().with_scale(0.5);
+        let mut rng = SplitMix64::new(2);
+        let start = Solution::new(vec![0.0, 0.0]);
+        let fitness = |solution: &Solution| {
+            function
+                .get_function_value(solution.get_arguments())
+                .unwrap()
+        };
+        for _ in 0..20 {
+            let mutated = mutation.mutate(start.clone(), 1.0, &mut rng);
+            assert!(fitness(&mutated) >= fitness(&start));
+        }
+        // At the optimum the gradient vanishes and the mutation falls back to scaling.
+        let optimum = Solution::new(vec![3.0, -1.0]);
+        assert_ne!(mutation.mutate(optimum.clone(), 1.0, &mut rng), optimum);
+    }
+    #[test]
+    fn bounded_mutation_scales_with_the_bounds() {
+        let mutation = BoundedMutation::new(vec![0.0..10.0, 1000.0..1001.0]).with_scale(0.2);
+        let mut rng = rand::thread_rng();
+        for _ in 0..50 {
+            let mutated = mutation.mutate(Solution::new(vec![9.5, 1000.5]), 1.0, &mut rng);
+            let arguments = mutated.get_arguments();
+            assert!((7.5..=10.0).contains(&arguments[0]));
+            assert!((1000.3..=1000.7).contains(&arguments[1]));
+        }
+    }
+    #[test]
+    fn bounds_keep_a_custom_mutation() {
+        let offspring = |pipeline: Pipeline| {
+            pipeline.with_bounds(vec![0.0..10.0; 2]).reproduce(
+                &population(),
+                1.0,
+                &mut rand::thread_rng(),
+                None,
+            )
+        };
+        // The average of the first pair is shifted by at most a tenth of the width.
+        let mutated = offspring(Pipeline::default())[0].get_arguments();
+        assert!((mutated[0] - 2.0).abs() <= 1.0 && (mutated[1] - 3.0).abs() <= 1.0);
+        let unchanged = offspring(
+            Pipeline::default().with_mutation(|child: Solution, _: f32, _: &mut dyn RngCore| child),
+        );
+        assert_eq!(unchanged[0], Solution::new(vec![2.0, 3.0]));
+    }
+    #[test]
+    fn boundary_policy_keeps_offspring_within_the_bounds() {
+        let reproduce = |policy| {
+            Pipeline::default()
+                .with_crossover(CrossoverOperator::Blend { alpha: 2.0 })
+                .with_mutation(|child: Solution, _: f32, _: &mut dyn RngCore| {
+                    Solution::new(child.get_arguments().iter().map(|x| x * 3.0).collect())
+                })
+                .with_bounds(Bounds::new(vec![0.0..5.0; 2]).with_policy(policy))
+                .reproduce_checked(&population(), 1.0, &mut rand::thread_rng(), None)
+        };
+        assert!(reproduce(BoundaryPolicy::Ignore).is_err());
+        for policy in [
+            BoundaryPolicy::Clamp,
+            BoundaryPolicy::Reflect,
+            BoundaryPolicy::Resample,
+        ] {
+            let offspring = reproduce(policy).unwrap();
+            assert_eq!(offspring.len(), 6);
+        }
+    }
+    #[test]
+    fn checked_reproduction_names_the_offending_stage() {
+        let reproduce = |pipeline: Pipeline| {
+            pipeline.reproduce_checked(&population(), 1.0, &mut rand::thread_rng(), None)
+        };
+        assert_eq!(reproduce(Pipeline::default()).unwrap().len(), 6);
+        let error = reproduce(Pipeline::default().with_crossover(
+            |parent_a: &Solution, _: &Solution, _: &mut dyn RngCore| {
+                Solution::new(parent_a.get_arguments()[..1].to_vec())
+            },
+        ))
+        .unwrap_err();
+        assert_eq!(error.stage, Stage::Crossover);
+        assert_eq!(
+            error.violation,
+            Violation::WrongLength {
+                expected: 2,
+                actual: 1
+            }
+        );
+        assert_eq!(
+            error.inputs,
+            vec![Solution::new(vec![1.0, 2.0]), Solution::new(vec![3.0, 4.0])]
+        );
+        // Only the final child has to be within the bounds.
+        let error = reproduce(
+            Pipeline::default()
+                .with_mutation(|child: Solution, _: f32, _: &mut dyn RngCore| child)
+                .with_bounds(vec![0.0..2.5; 2]),
+        )
+        .unwrap_err();
+        assert_eq!(error.stage, Stage::Repair);
+        assert_eq!(error.output, Solution::new(vec![2.0, 3.0]));
+        assert!(error
+            .to_string()
+            .starts_with("The repair stage returned the argument 3 at index 1 outside"));
+    }
+    #[test]
+    fn default_without_mutation_averages() {
+        let offspring =
+            Pipeline::default().reproduce(&population(), 0.0, &mut rand::thread_rng(), None);
+        assert_eq!(offspring.len(), 6);
+        assert_eq!(offspring[0], Solution::new(vec![2.0, 3.0]));
+        assert_eq!(offspring[1], Solution::new(vec![3.0, 4.0]));
+    }
+    #[test]
+    fn custom_stages_are_applied_in_order() {
+        let offspring = Pipeline::default()
+            .with_crossover(|parent_a: &Solution, _: &Solution, _: &mut dyn RngCore| {
+                parent_a.clone()
+            })
+            .with_mutation(|child: Solution, _: f32, _: &mut dyn RngCore| {
+                Solution::new(child.get_arguments().iter().map(|x| x - 4.0).collect())
+            })
+            .with_repair(|child: Solution| {
+                Solution::new(child.get_arguments().iter().map(|x| x.max(0.0)).collect())
+            })
+            .with_filter(|child: &Solution| child.get_arguments()[1] > 0.0)
+            .reproduce(&population(), 1.0, &mut rand::thread_rng(), None);
+        // Only the children of the last individual survive the repair with a positive value.
+        assert_eq!(
+            offspring,
+            vec![Solution::new(vec![1.0, 2.0]), Solution::new(vec![1.0, 2.0])]
+        );
+    }
+    #[test]
+    fn streams_are_reproducible() {
+        let reproduce = || {
+            Pipeline::default().reproduce(&population(), 1.0, &mut rand::thread_rng(), Some((3, 1)))
+        };
+        assert_eq!(reproduce(), reproduce());
+    }
+}
@@ -0,0 +1,466 @@
+use crate::evaluation::{EvaluationOutcomes, Evaluator, FailureKind};
+use crate::solution::Solution;
+use std::ops::Range;
+
+/// The smallest step of the pattern search, relative to its initial step. Once every step
+/// shrank below it the search has converged.
+const MIN_RELATIVE_STEP: f64 = 1e-9;
+
+/// The local search `Polish` refines the fittest individuals with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PolishMethod {
+    /// Halve the steps whenever no neighbour improves, see `pattern_search`.
+    #[default]
+    PatternSearch,
+    /// Expand or shrink the steps by how well a quadratic model predicted the improvement of
+    /// its step, see `trust_region_search`. Less sensitive to the initial step.
+    TrustRegion,
+}
+
+/// A final phase of a run that spends the evaluations of its last generations on a local
+/// search around the fittest individuals instead, to refine what the evolution found. The
+/// evolution runs for the other generations, the polish gets the evaluations they took on
+/// average per generation times the generations it replaces.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Polish {
+    /// The share of the generations whose evaluations are spent on the polish, in `[0, 1]`.
+    pub share: f64,
+    /// How many of the fittest individuals of the final population are polished, the budget
+    /// is split evenly between them.
+    pub n_candidates: usize,
+    /// The initial step of the pattern search, relative to the width of the bounds of every
+    /// argument. Arguments without bounds step relative to their magnitude.
+    pub step: f64,
+    /// The local search that refines the candidates.
+    pub method: PolishMethod,
+}
+
+impl Polish {
+    /// How many of the generations of a run the polish replaces.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_generations` - The generations of the run, see `RunnerConfig::n_generations`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::polish::Polish;
+    ///
+    /// let polish = Polish {
+    ///     share: 0.1,
+    ///     n_candidates: 3,
+    ///     step: 0.05,
+    ///     method: Default::default(),
+    /// };
+    /// assert_eq!(polish.n_generations(100), 10);
+    /// ```
+    pub fn n_generations(&self, n_generations: usize) -> usize {
+        (self.share.clamp(0.0, 1.0) * n_generations as f64).round() as usize
+    }
+}
+
+/// What the polish phase of a run did.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PolishReport {
+    /// How many evaluations the polish spent.
+    pub n_evaluations: usize,
+    /// The best fitness before the polish, `None` if no individual was evaluated.
+    pub fitness_before: Option<f64>,
+    /// The best fitness after the polish.
+    pub fitness_after: Option<f64>,
+}
+
+impl PolishReport {
+    /// How much the polish improved the best fitness, `0` if it didn't.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::polish::PolishReport;
+    ///
+    /// let report = PolishReport {
+    ///     n_evaluations: 40,
+    ///     fitness_before: Some(1.5),
+    ///     fitness_after: Some(2.0),
+    /// };
+    /// assert_eq!(report.improvement(), 0.5);
+    /// ```
+    pub fn improvement(&self) -> f64 {
+        match (self.fitness_before, self.fitness_after) {
+            (Some(before), Some(after)) if after > before => after - before,
+            _ => 0.0,
+        }
+    }
+}
+
+/// The initial step of every argument: `step` times the width of its bounds, or times its
+/// magnitude, but at least `step`, if it has none.
+///
+/// # Arguments
+///
+/// * `arguments` - The arguments of the starting point.
+/// * `bounds` - The bounds of the arguments, the first arguments if it is shorter.
+/// * `step` - The initial step relative to the width of the bounds.
+fn initial_steps(arguments: &[f64], bounds: &[Range<f64>], step: f64) -> Vec<f64> {
+    arguments
+        .iter()
+        .enumerate()
+        .map(|(idx, argument)| match bounds.get(idx) {
+            Some(bound) if bound.end > bound.start => step * (bound.end - bound.start),
+            _ => step * argument.abs().max(1.0),
+        })
+        .collect()
+}
+
+/// Clamp an argument into its bounds, if it has any.
+///
+/// # Arguments
+///
+/// * `argument` - The argument to clamp.
+/// * `bound` - The bounds of the argument.
+fn clamp(argument: f64, bound: Option<&Range<f64>>) -> f64 {
+    match bound {
+        Some(bound) if bound.end > bound.start => argument.clamp(bound.start, bound.end),
+        _ => argument,
+    }
+}
+
+/// Evaluate `solutions` as a single chunk and record the outcomes. Failed evaluations have no
+/// fitness.
+///
+/// # Arguments
+///
+/// * `solutions` - The solutions to evaluate.
+/// * `evaluator` - The evaluator to evaluate with.
+/// * `outcomes` - Counts the outcomes of the evaluations.
+fn evaluate<E>(
+    solutions: &[Solution],
+    evaluator: &E,
+    outcomes: &mut EvaluationOutcomes,
+) -> Vec<Option<f64>>
+where
+    E: Evaluator + ?Sized,
+{
+    evaluator
+        .evaluate_chunk(solutions)
+        .into_iter()
+        .map(|result| {
+            outcomes.record(match &result {
+                Ok(value) if value.is_finite() => None,
+                Ok(_) => Some(FailureKind::NonFinite),
+                Err(error) => Some(FailureKind::of(error)),
+            });
+            result.ok()
+        })
+        .collect()
+}
+
+/// Compass pattern search: evaluate the neighbours one step away along every argument as a
+/// single chunk, move to the fittest one if it improves and halve the steps otherwise. Ends
+/// once the budget is spent or the steps converged. Neighbours are clamped into `bounds`,
+/// failed evaluations never improve.
+///
+/// # Arguments
+///
+/// * `(solution, fitness)` - The starting point and its fitness.
+/// * `bounds` - The bounds of the arguments, the first arguments if it is shorter.
+/// * `step` - The initial step relative to the width of the bounds.
+/// * `budget` - The maximum number of evaluations.
+/// * `evaluator` - The evaluator whose fitness should be maximized.
+/// * `outcomes` - Counts the outcomes of the evaluations.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::evaluation::EvaluationOutcomes;
+/// use genetic_algorithm_fn::function::Function;
+/// use genetic_algorithm_fn::polish;
+/// use genetic_algorithm_fn::solution::Solution;
+///
+/// let function = Function::new(|x| Ok(-(x[0] - 1.0).powi(2)));
+/// let mut outcomes = EvaluationOutcomes::default();
+/// let (solution, fitness) = polish::pattern_search(
+///     (Solution::new(vec![0.0]), -1.0),
+///     &[-4.0..4.0],
+///     0.1,
+///     100,
+///     &function,
+///     &mut outcomes,
+/// );
+/// assert!(fitness > -1e-6);
+/// assert!(outcomes.n_evaluations() <= 100);
+/// ```
+pub fn pattern_search<E>(
+    (solution, fitness): (Solution, f64),
+    bounds: &[Range<f64>],
+    step: f64,
+    budget: usize,
+    evaluator: &E,
+    outcomes: &mut EvaluationOutcomes,
+) -> (Solution, f64)
+where
+    E: Evaluator + ?Sized,
+{
+    let mut arguments = solution.get_arguments();
+    let mut steps = initial_steps(&arguments, bounds, step);
+    let min_steps = steps
+        .iter()
+        .map(|step| step * MIN_RELATIVE_STEP)
+        .collect::<Vec<f64>>();
+    let (mut best, mut best_fitness) = (solution, fitness);
+    let mut n_left = budget;
+    while n_left > 0 && steps.iter().zip(&min_steps).any(|(step, min)| step > min) {
+        let neighbours = (0..arguments.len())
+            .flat_map(|idx| [(idx, 1.0), (idx, -1.0)])
+            .filter_map(|(idx, sign)| {
+                let mut neighbour = arguments.clone();
+                neighbour[idx] = clamp(neighbour[idx] + sign * steps[idx], bounds.get(idx));
+                (neighbour[idx] != arguments[idx]).then(|| Solution::new(neighbour))
+            })
+            .take(n_left)
+            .collect::<Vec<Solution>>();
+        if neighbours.is_empty() {
+            break;
+        }
+        n_left -= neighbours.len();
+        let mut improved = false;
+        for (neighbour, value) in neighbours
+            .iter()
+            .zip(evaluate(&neighbours, evaluator, outcomes))
+        {
+            if let Some(value) = value {
+                if value > best_fitness {
+                    (best, best_fitness) = (neighbour.clone(), value);
+                    improved = true;
+                }
+            }
+        }
+        if improved {
+            arguments = best.get_arguments();
+        } else {
+            steps.iter_mut().for_each(|step| *step /= 2.0);
+        }
+    }
+    (best, best_fitness)
+}
+
+/// Trust-region search: evaluate the neighbours one step away along every argument as a
+/// single chunk, fit a quadratic model to every argument through them and evaluate the step
+/// that maximizes the model within the steps, the trust region. The search moves to the
+/// fittest point it evaluated if it improves. The steps double if the model step reached the
+/// border of the region and achieved more than three quarters of the improvement the model
+/// predicted, and halve if it achieved less than a quarter of it or nothing improved. That way
+/// the steps adapt to the function instead of depending on a well chosen initial `step`. Ends
+/// once the budget is spent or the steps converged. Points are clamped into `bounds`, the
+/// steps never grow beyond their width. Failed evaluations never improve and are left out of
+/// the model.
+///
+/// # Arguments
+///
+/// * `(solution, fitness)` - The starting point and its fitness.
+/// * `bounds` - The bounds of the arguments, the first arguments if it is shorter.
+/// * `step` - The initial step relative to the width of the bounds.
+/// * `budget` - The maximum number of evaluations.
+/// * `evaluator` - The evaluator whose fitness should be maximized.
+/// * `outcomes` - Counts the outcomes of the evaluations.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::evaluation::EvaluationOutcomes;
+/// use genetic_algorithm_fn::function::Function;
+/// use genetic_algorithm_fn::polish;
+/// use genetic_algorithm_fn::solution::Solution;
+///
+/// let function = Function::new(|x| Ok(-(x[0] - 100.0).powi(2)));
+/// let mut outcomes = EvaluationOutcomes::default();
+/// // The initial step is far too small, the trust region expands it.
+/// let (solution, fitness) = polish::trust_region_search(
+///     (Solution::new(vec![0.0]), -1e4),
+///     &[],
+///     1e-3,
+///     100,
+///     &function,
+///     &mut outcomes,
+/// );
+/// assert!(fitness > -1e-6);
+/// assert!(outcomes.n_evaluations() <= 100);
+/// ```
+pub fn trust_region_search<E>(
+    (solution, fitness): (Solution, f64),
+    bounds: &[Range<f64>],
+    step: f64,
+    budget: usize,
+    evaluator: &E,
+    outcomes: &mut EvaluationOutcomes,
+) -> (Solution, f64)
+where
+    E: Evaluator + ?Sized,
+{
+    let mut arguments = solution.get_arguments();
+    let mut steps = initial_steps(&arguments, bounds, step);
+    let min_steps = steps
+        .iter()
+        .map(|step| step * MIN_RELATIVE_STEP)
+        .collect::<Vec<f64>>();
+    let max_steps = (0..arguments.len())
+        .map(|idx| match bounds.get(idx) {
+            Some(bound) if bound.end > bound.start => bound.end - bound.start,
+            _ => f64::INFINITY,
+        })
+        .collect::<Vec<f64>>();
+    let (mut best, mut best_fitness) = (solution, fitness);
+    let mut n_left = budget;
+    while n_left > 0 && steps.iter().zip(&min_steps).any(|(step, min)| step > min) {
+        let probes = (0..arguments.len())
+            .flat_map(|idx| [(idx, 1.0), (idx, -1.0)])
+            .filter_map(|(idx, sign)| {
+                let value = clamp(arguments[idx] + sign * steps[idx], bounds.get(idx));
+                (value != arguments[idx]).then_some((idx, value))
+            })
+            .take(n_left)
+            .collect::<Vec<(usize, f64)>>();
+        if probes.is_empty() {
+            break;
+        }
+        n_left -= probes.len();
+        let neighbours = probes
+            .iter()
+            .map(|(idx, value)| {
+                let mut neighbour = arguments.clone();
+                neighbour[*idx] = *value;
+                Solution::new(neighbour)
+            })
+            .collect::<Vec<Solution>>();
+        let center = best_fitness;
+        let mut improved = false;
+        // The offsets of the probes of every argument and how much they changed the fitness.
+        let mut samples = vec![Vec::new(); arguments.len()];
+        let values = evaluate(&neighbours, evaluator, outcomes);
+        for ((neighbour, (idx, value)), fitness) in neighbours.iter().zip(&probes).zip(values) {
+            let Some(fitness) = fitness else {
+                continue;
+            };
+            if fitness > best_fitness {
+                (best, best_fitness) = (neighbour.clone(), fitness);
+                improved = true;
+            }
+            if fitness.is_finite() && center.is_finite() {
+                samples[*idx].push((value - arguments[*idx], fitness - center));
+            }
+        }
+        let mut trial = arguments.clone();
+        let mut predicted = 0.0;
+        let mut at_border = false;
+        for (idx, samples) in samples.iter().enumerate() {
+            let (gradient, curvature) = match samples[..] {
+                [(a, change_a), (b, change_b)] => {
+                    let curvature = 2.0 * (change_b / b - change_a / a) / (b - a);
+                    (change_a / a - curvature * a / 2.0, curvature)
+                }
+                [(a, change_a)] => (change_a / a, 0.0),
+                _ => continue,
+            };
+            if gradient == 0.0 && curvature >= 0.0 {
+                continue;
+            }
+            let offset = if curvature < 0.0 {
+                -gradient / curvature
+            } else {
+                gradient.signum() * steps[idx]
+            };
+            at_border |= offset.abs() >= steps[idx];
+            trial[idx] = clamp(
+                arguments[idx] + offset.clamp(-steps[idx], steps[idx]),
+                bounds.get(idx),
+            );
+            let offset = trial[idx] - arguments[idx];
+            predicted += gradient * offset + curvature * offset * offset / 2.0;
+        }
+        // How much of the predicted improvement the model step achieved.
+        let mut ratio = None;
+        if predicted > 0.0 && n_left > 0 && trial != arguments {
+            n_left -= 1;
+            let trial = Solution::new(trial);
+            let fitness = evaluate(std::slice::from_ref(&trial), evaluator, outcomes)[0];
+            ratio =
+                Some(fitness.map_or(f64::NEG_INFINITY, |fitness| (fitness - center) / predicted));
+            if let Some(fitness) = fitness.filter(|fitness| *fitness > best_fitness) {
+                (best, best_fitness) = (trial, fitness);
+                improved = true;
+            }
+        }
+        let factor = match ratio {
+            Some(ratio) if ratio > 0.75 && at_border => 2.0,
+            Some(ratio) if ratio >= 0.25 => 1.0,
+            None if improved => 1.0,
+            _ => 0.5,
+        };
+        for (step, max) in steps.iter_mut().zip(&max_steps) {
+            *step = (*step * factor).min(*max);
+        }
+        if improved {
+            arguments = best.get_arguments();
+        }
+    }
+    (best, best_fitness)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pattern_search, trust_region_search};
+    use crate::evaluation::{CountingEvaluator, EvaluationOutcomes};
+    use crate::function::Function;
+    use crate::solution::Solution;
+
+    #[test]
+    fn pattern_search_stays_within_budget_and_bounds() {
+        let function = Function::new(|x| Ok(x.iter().sum()));
+        let evaluator = CountingEvaluator::new(&function);
+        let mut outcomes = EvaluationOutcomes::default();
+        let (solution, fitness) = pattern_search(
+            (Solution::new(vec![0.5, 0.5]), 1.0),
+            &[0.0..1.0, 0.0..1.0],
+            0.1,
+            25,
+            &evaluator,
+            &mut outcomes,
+        );
+        assert_eq!(evaluator.count(), 25);
+        assert_eq!(outcomes.n_ok, 25);
+        assert!(solution
+            .get_arguments()
+            .iter()
+            .all(|argument| (0.0..=1.0).contains(argument)));
+        assert_eq!(fitness, solution.get_arguments().iter().sum::<f64>());
+        assert!(fitness > 1.5);
+    }
+    #[test]
+    fn trust_region_search_adapts_a_poor_initial_step() {
+        let function = Function::new(|x| Ok(-(x[0] - 3.0).powi(2) - (x[1] + 2.0).powi(2)));
+        let search = |trust_region: bool, step: f64| {
+            let evaluator = CountingEvaluator::new(&function);
+            let search = if trust_region {
+                trust_region_search
+            } else {
+                pattern_search
+            };
+            let (_, fitness) = search(
+                (Solution::new(vec![0.0, 0.0]), -13.0),
+                &[-10.0..10.0, -10.0..10.0],
+                step,
+                100,
+                &evaluator,
+                &mut EvaluationOutcomes::default(),
+            );
+            assert!(evaluator.count() <= 100);
+            fitness
+        };
+        // A tiny step barely moves the pattern search, the trust region expands it.
+        assert!(search(false, 1e-5) < -10.0);
+        assert!(search(true, 1e-5) > -1e-9);
+        // A huge step is shrunk instead.
+        assert!(search(true, 1.0) > -1e-9);
+    }
+}
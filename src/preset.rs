@@ -0,0 +1,232 @@
+use crate::pipeline::{FitnessScaling, JitteredMutation, Pipeline, RouletteSelection};
+use crate::runner::{Runner, RunnerConfig, SurvivorSelection};
+use std::fmt;
+
+/// Curated settings for common kinds of problems, a better starting point than
+/// `RunnerConfig::default` for anyone who doesn't know the operators yet. A preset sets the
+/// operators of the pipeline, the mutation rate and the population size; the other fields
+/// keep their defaults and every one of them can still be overridden.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Preset {
+    /// A small population with strong selection pressure and little mutation, for smooth
+    /// objectives with a single optimum that should be found with few evaluations. Parents are
+    /// drawn by their rank, the least fit individual never reproduces.
+    FastConverging,
+    /// A large and diverse population that mutates heavily and keeps less fit individuals,
+    /// for multimodal objectives where converging early means getting stuck.
+    Explorative,
+    /// Averages the fitness of every individual over its latest evaluations and limits how
+    /// long individuals survive, so a lucky evaluation doesn't dominate the run.
+    NoisyObjective,
+    /// Many individuals that mutate almost always and a bounded number of parent pairs, since
+    /// crossing over all pairs of a large population is too expensive and every mutation only
+    /// changes a single argument.
+    HighDimensional,
+}
+
+impl Preset {
+    /// All presets.
+    pub const ALL: [Preset; 4] = [
+        Preset::FastConverging,
+        Preset::Explorative,
+        Preset::NoisyObjective,
+        Preset::HighDimensional,
+    ];
+
+    /// The runner settings of the preset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::preset::Preset;
+    /// use genetic_algorithm_fn::runner::RunnerConfig;
+    ///
+    /// // Individual fields of a preset can be overridden like those of the default config.
+    /// let config = RunnerConfig {
+    ///     n_generations: 20,
+    ///     ..Preset::Explorative.config()
+    /// };
+    /// assert_eq!(config.size_generation, 60);
+    /// ```
+    pub fn config(&self) -> RunnerConfig {
+        match self {
+            Preset::FastConverging => RunnerConfig {
+                n_generations: 50,
+                size_generation: 10,
+                mutate_prob: 0.2,
+                ..RunnerConfig::default()
+            },
+            Preset::Explorative => RunnerConfig {
+                n_generations: 200,
+                size_generation: 60,
+                mutate_prob: 0.8,
+                survivor_selection: SurvivorSelection::Stratified(vec![0.5, 0.3, 0.2]),
+                distinct_parents: Some(1e-6),
+                ..RunnerConfig::default()
+            },
+            Preset::NoisyObjective => RunnerConfig {
+                n_generations: 100,
+                size_generation: 30,
+                mutate_prob: 0.3,
+                fitness_window: Some(5),
+                max_lifespan: Some(25),
+                ..RunnerConfig::default()
+            },
+            Preset::HighDimensional => RunnerConfig {
+                n_generations: 500,
+                size_generation: 100,
+                mutate_prob: 0.9,
+                distinct_parents: Some(1e-9),
+                ..RunnerConfig::default()
+            },
+        }
+    }
+    /// The reproduction pipeline of the preset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::preset::Preset;
+    /// use genetic_algorithm_fn::runner::Runner;
+    ///
+    /// let runner = Runner::new(Preset::HighDimensional.config())
+    ///     .with_pipeline(Preset::HighDimensional.pipeline().with_bounds(vec![-5.0..5.0; 50]));
+    /// ```
+    pub fn pipeline(&self) -> Pipeline {
+        match self {
+            Preset::FastConverging => Pipeline::default().with_parent_selector(RouletteSelection {
+                scaling: FitnessScaling::LinearRank { pressure: 2.0 },
+                n_pairs: None,
+            }),
+            Preset::Explorative => {
+                Pipeline::default().with_mutation(JitteredMutation { jitter: 1.0 })
+            }
+            Preset::NoisyObjective => Pipeline::default().with_parent_selector(RouletteSelection {
+                scaling: FitnessScaling::default(),
+                n_pairs: Some(60),
+            }),
+            Preset::HighDimensional => Pipeline::default()
+                .with_parent_selector(RouletteSelection {
+                    scaling: FitnessScaling::default(),
+                    n_pairs: Some(200),
+                })
+                .with_mutation(JitteredMutation::default()),
+        }
+    }
+    /// A runner with the settings and the pipeline of the preset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::preset::Preset;
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let result = Preset::FastConverging.runner().run(
+    ///     Solutions::random(10, 1.0..10.0, 2),
+    ///     &Function::new(|x| Ok(-x.iter().map(|x| x * x).sum::<f64>())),
+    /// );
+    /// assert!(result.best.is_some());
+    /// ```
+    pub fn runner(&self) -> Runner {
+        Runner::new(self.config()).with_pipeline(self.pipeline())
+    }
+    /// The name of the preset in kebab case, e.g. `fast-converging`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::preset::Preset;
+    ///
+    /// assert_eq!(Preset::NoisyObjective.name(), "noisy-objective");
+    /// ```
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::FastConverging => "fast-converging",
+            Preset::Explorative => "explorative",
+            Preset::NoisyObjective => "noisy-objective",
+            Preset::HighDimensional => "high-dimensional",
+        }
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Preset;
+    use crate::function::Function;
+    use crate::pipeline::Pipeline;
+    use crate::rng::SplitMix64;
+    use crate::runner::{Runner, RunnerConfig};
+    use crate::solution::Solution;
+    use crate::solutions::Solutions;
+    use crate::test_objects;
+    use genetic_algorithm_traits::Population;
+
+    #[test]
+    fn every_preset_runs_with_valid_operators() {
+        for preset in Preset::ALL {
+            let config = preset.config();
+            let result = Runner::new(RunnerConfig {
+                n_generations: 3,
+                check_operators: true,
+                ..config.clone()
+            })
+            .with_pipeline(preset.pipeline())
+            .try_run(
+                Solutions::random(config.size_generation, 1.0..10.0, 3),
+                &Function::new(test_objects::triple_multiplication()),
+            )
+            .unwrap();
+            assert!(result.best.is_some(), "{}", preset);
+            assert_eq!(
+                result.population.iter().count(),
+                config.size_generation,
+                "{}",
+                preset
+            );
+        }
+    }
+    #[test]
+    fn presets_reproduce_differently() {
+        let population = (0..10)
+            .map(|x| (Solution::new(vec![x as f64, 1.0]), Some(x as f64)))
+            .collect::<Vec<(Solution, Option<f64>)>>();
+        let offspring = |pipeline: Pipeline| {
+            pipeline.reproduce(&population, 1.0, &mut SplitMix64::new(6), None)
+        };
+        for (idx, preset) in Preset::ALL.iter().enumerate() {
+            for other in &Preset::ALL[idx + 1..] {
+                assert_ne!(
+                    offspring(preset.pipeline()),
+                    offspring(other.pipeline()),
+                    "{} {}",
+                    preset,
+                    other
+                );
+            }
+        }
+    }
+    #[test]
+    fn fast_converging_never_reproduces_the_least_fit() {
+        let population = (0..10)
+            .map(|x| (Solution::new(vec![x as f64]), Some(x as f64)))
+            .collect::<Vec<(Solution, Option<f64>)>>();
+        let offspring = |pipeline: Pipeline| {
+            pipeline
+                .reproduce(&population, 0.0, &mut SplitMix64::new(6), None)
+                .iter()
+                .map(|child| child.get_arguments()[0])
+                .collect::<Vec<f64>>()
+        };
+        assert!(offspring(Preset::FastConverging.pipeline())
+            .iter()
+            .all(|child| *child >= 1.0));
+        assert!(offspring(Pipeline::default()).contains(&0.5));
+    }
+}
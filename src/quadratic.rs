@@ -0,0 +1,432 @@
+use crate::solution::Solution;
+use std::cmp::Ordering;
+
+/// A quadratic model of the fitness around a point, fitted by least squares to evaluated
+/// points next to it, e.g. those in the archive of a run around its best individual. The
+/// curvatures tell how sharply the fitness drops in every direction, and thereby how precisely
+/// the arguments of the optimum are determined.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuadraticFit {
+    /// The point the model is fitted around.
+    pub center: Solution,
+    /// The fitness the model predicts at the center.
+    pub value: f64,
+    /// The gradient of the model at the center.
+    pub gradient: Vec<f64>,
+    /// The symmetric matrix of the second derivatives of the model.
+    pub hessian: Vec<Vec<f64>>,
+    /// The eigenvalues of the Hessian in ascending order, the curvatures along its principal
+    /// axes. All are negative around a maximum.
+    pub curvatures: Vec<f64>,
+    /// The ratio of the largest to the smallest absolute curvature. Large values mean the
+    /// fitness is much flatter in some directions than in others, infinite ones that it is
+    /// flat in some direction.
+    pub condition_number: f64,
+    /// The maximum of the model and the fitness it predicts there, a refined estimate of the
+    /// optimum. `None` unless all curvatures are negative.
+    pub optimum: Option<(Solution, f64)>,
+    /// The standard deviation of the residuals of the fit, how well a quadratic describes the
+    /// neighbourhood. `None` if there were no more points than coefficients.
+    pub residual_std: Option<f64>,
+    /// How many points the model was fitted to, more than requested if the closest ones didn't
+    /// determine it.
+    pub n_points: usize,
+}
+
+/// The number of coefficients of a quadratic model in `dimension` arguments.
+///
+/// # Arguments
+///
+/// * `dimension` - The number of arguments.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::quadratic;
+///
+/// // A constant, two linear and three quadratic coefficients.
+/// assert_eq!(quadratic::n_coefficients(2), 6);
+/// ```
+pub fn n_coefficients(dimension: usize) -> usize {
+    1 + dimension + dimension * (dimension + 1) / 2
+}
+
+/// Fit a quadratic model of the fitness around `center` to the `n_points` points closest to
+/// it. Points with a different number of arguments or a non-finite fitness are ignored. The
+/// arguments are scaled by their spread before fitting, so arguments of different magnitudes
+/// don't spoil the fit. If the closest points don't determine the model, more are used until
+/// they do. `None` if there are fewer points than coefficients, see `n_coefficients`, or all of
+/// them together don't determine the model, e.g. because an argument doesn't vary.
+///
+/// # Arguments
+///
+/// * `center` - The point to fit the model around, usually the best one.
+/// * `points` - Evaluated points and their fitness.
+/// * `n_points` - How many of the points closest to the center are used at least, no fewer
+///   than the number of coefficients.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::quadratic;
+/// use genetic_algorithm_fn::solution::Solution;
+///
+/// let points = (-2..=2)
+///     .flat_map(|x| (-2..=2).map(move |y| vec![x as f64, y as f64]))
+///     .map(|x| {
+///         let fitness = -(x[0] - 0.5).powi(2) - 4.0 * (x[1] + 0.25).powi(2);
+///         (Solution::new(x), fitness)
+///     })
+///     .collect::<Vec<(Solution, f64)>>();
+/// let fit = quadratic::fit(&Solution::new(vec![0.0, 0.0]), &points, 25).unwrap();
+/// let (optimum, fitness) = fit.optimum.unwrap();
+/// assert!((optimum.get_arguments()[0] - 0.5).abs() < 1e-9);
+/// assert!((optimum.get_arguments()[1] + 0.25).abs() < 1e-9);
+/// assert!(fitness.abs() < 1e-9);
+/// assert!((fit.condition_number - 4.0).abs() < 1e-9);
+/// ```
+pub fn fit(center: &Solution, points: &[(Solution, f64)], n_points: usize) -> Option<QuadraticFit> {
+    let origin = center.get_arguments();
+    let dimension = origin.len();
+    let n_coefficients = n_coefficients(dimension);
+    let mut nearest = points
+        .iter()
+        .filter(|(_, fitness)| fitness.is_finite())
+        .map(|(solution, fitness)| (solution.get_arguments(), *fitness))
+        .filter(|(arguments, _)| arguments.len() == dimension)
+        .map(|(arguments, fitness)| {
+            let offsets = arguments
+                .iter()
+                .zip(&origin)
+                .map(|(argument, origin)| argument - origin)
+                .collect::<Vec<f64>>();
+            (offsets, fitness)
+        })
+        .collect::<Vec<(Vec<f64>, f64)>>();
+    let squared_norm = |offsets: &[f64]| offsets.iter().map(|offset| offset * offset).sum::<f64>();
+    // Ties are broken by the offsets, so the fit doesn't depend on the order of `points`.
+    nearest.sort_by(|(offsets_a, _), (offsets_b, _)| {
+        squared_norm(offsets_a)
+            .total_cmp(&squared_norm(offsets_b))
+            .then_with(|| {
+                offsets_a
+                    .iter()
+                    .zip(offsets_b)
+                    .map(|(a, b)| a.total_cmp(b))
+                    .find(|ordering| ordering.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            })
+    });
+    if nearest.len() < n_coefficients {
+        return None;
+    }
+    // The nearest points often lie on a few lines through the center, e.g. when mutations
+    // change a single argument, and don't determine the cross terms. Widen the neighbourhood
+    // until they do.
+    let mut n_used = n_points.max(n_coefficients).min(nearest.len());
+    let (scales, coefficients, residual_std) = loop {
+        if let Some(fitted) = least_squares(&nearest[..n_used], dimension) {
+            break fitted;
+        }
+        if n_used == nearest.len() {
+            return None;
+        }
+        n_used = (2 * n_used).min(nearest.len());
+    };
+    let scaled_gradient = coefficients[1..=dimension].to_vec();
+    let mut scaled_hessian = vec![vec![0.0; dimension]; dimension];
+    let pairs = (0..dimension).flat_map(|row| (row..dimension).map(move |column| (row, column)));
+    for ((row, column), coefficient) in pairs.zip(&coefficients[dimension + 1..]) {
+        scaled_hessian[row][column] = *coefficient;
+        scaled_hessian[column][row] = *coefficient;
+    }
+    let gradient = scaled_gradient
+        .iter()
+        .zip(&scales)
+        .map(|(derivative, scale)| derivative / scale)
+        .collect::<Vec<f64>>();
+    let hessian = (0..dimension)
+        .map(|row| {
+            (0..dimension)
+                .map(|column| scaled_hessian[row][column] / (scales[row] * scales[column]))
+                .collect()
+        })
+        .collect::<Vec<Vec<f64>>>();
+    let curvatures = symmetric_eigenvalues(hessian.clone());
+    let smallest = curvatures
+        .iter()
+        .map(|curvature| curvature.abs())
+        .fold(f64::INFINITY, f64::min);
+    let largest = curvatures
+        .iter()
+        .map(|curvature| curvature.abs())
+        .fold(0.0, f64::max);
+    let condition_number = if dimension == 0 {
+        1.0
+    } else {
+        largest / smallest
+    };
+    let value = coefficients[0];
+    // The step to the stationary point, solved in the scaled arguments for stability.
+    let optimum = if curvatures.iter().all(|curvature| *curvature < 0.0) {
+        solve(
+            scaled_hessian.clone(),
+            scaled_gradient.iter().map(|g| -g).collect(),
+        )
+        .map(|step| {
+            let gain = step
+                .iter()
+                .zip(&scaled_gradient)
+                .map(|(s, g)| s * g)
+                .sum::<f64>()
+                / 2.0;
+            let arguments = origin
+                .iter()
+                .zip(&step)
+                .zip(&scales)
+                .map(|((origin, step), scale)| origin + step * scale)
+                .collect();
+            (Solution::new(arguments), value + gain)
+        })
+    } else {
+        None
+    };
+    Some(QuadraticFit {
+        center: center.clone(),
+        value,
+        gradient,
+        hessian,
+        curvatures,
+        condition_number,
+        optimum,
+        residual_std,
+        n_points: n_used,
+    })
+}
+
+/// Fit the coefficients of a quadratic model by least squares in arguments scaled by their
+/// spread. Returns the scales, the coefficients and the standard deviation of the residuals,
+/// `None` if the points don't determine the model.
+///
+/// # Arguments
+///
+/// * `nearest` - The offsets of the points from the center and their fitness.
+/// * `dimension` - The number of arguments.
+fn least_squares(
+    nearest: &[(Vec<f64>, f64)],
+    dimension: usize,
+) -> Option<(Vec<f64>, Vec<f64>, Option<f64>)> {
+    let n_coefficients = n_coefficients(dimension);
+    let scales = (0..dimension)
+        .map(|idx| {
+            nearest
+                .iter()
+                .map(|(offsets, _)| offsets[idx].abs())
+                .fold(0.0, f64::max)
+        })
+        .collect::<Vec<f64>>();
+    if scales
+        .iter()
+        .any(|scale| !(*scale > 0.0 && scale.is_finite()))
+    {
+        return None;
+    }
+    let features = |offsets: &[f64]| {
+        let scaled = offsets
+            .iter()
+            .zip(&scales)
+            .map(|(offset, scale)| offset / scale)
+            .collect::<Vec<f64>>();
+        let mut features = Vec::with_capacity(n_coefficients);
+        features.push(1.0);
+        features.extend_from_slice(&scaled);
+        for row in 0..dimension {
+            for column in row..dimension {
+                let factor = if row == column { 0.5 } else { 1.0 };
+                features.push(factor * scaled[row] * scaled[column]);
+            }
+        }
+        features
+    };
+    // The normal equations of the least squares fit.
+    let mut normal = vec![vec![0.0; n_coefficients]; n_coefficients];
+    let mut moments = vec![0.0; n_coefficients];
+    let rows = nearest
+        .iter()
+        .map(|(offsets, fitness)| (features(offsets), *fitness))
+        .collect::<Vec<(Vec<f64>, f64)>>();
+    for (row, fitness) in &rows {
+        for (idx, feature) in row.iter().enumerate() {
+            moments[idx] += feature * fitness;
+            for (other, other_feature) in row.iter().enumerate() {
+                normal[idx][other] += feature * other_feature;
+            }
+        }
+    }
+    let coefficients = solve(normal, moments)?;
+    let residual_std = (rows.len() > n_coefficients).then(|| {
+        let squared_residuals = rows
+            .iter()
+            .map(|(row, fitness)| {
+                let predicted = row
+                    .iter()
+                    .zip(&coefficients)
+                    .map(|(x, c)| x * c)
+                    .sum::<f64>();
+                (fitness - predicted).powi(2)
+            })
+            .sum::<f64>();
+        (squared_residuals / (rows.len() - n_coefficients) as f64).sqrt()
+    });
+    Some((scales, coefficients, residual_std))
+}
+
+/// Solve a linear system by Gaussian elimination with partial pivoting, `None` if the matrix
+/// is singular.
+///
+/// # Arguments
+///
+/// * `matrix` - The square matrix of the system.
+/// * `rhs` - The right hand side.
+fn solve(mut matrix: Vec<Vec<f64>>, mut rhs: Vec<f64>) -> Option<Vec<f64>> {
+    let n = rhs.len();
+    let magnitude = matrix
+        .iter()
+        .flatten()
+        .map(|entry| entry.abs())
+        .fold(0.0, f64::max);
+    for column in 0..n {
+        let pivot = (column..n).max_by(|a, b| {
+            matrix[*a][column]
+                .abs()
+                .total_cmp(&matrix[*b][column].abs())
+        })?;
+        if matrix[pivot][column].abs() <= magnitude * 1e-12 {
+            return None;
+        }
+        matrix.swap(column, pivot);
+        rhs.swap(column, pivot);
+        let (pivot_row, pivot_rhs) = (matrix[column].clone(), rhs[column]);
+        for (entries, rhs) in matrix.iter_mut().zip(rhs.iter_mut()).skip(column + 1) {
+            let factor = entries[column] / pivot_row[column];
+            for (entry, pivot) in entries.iter_mut().zip(&pivot_row).skip(column) {
+                *entry -= factor * pivot;
+            }
+            *rhs -= factor * pivot_rhs;
+        }
+    }
+    let mut solution = vec![0.0; n];
+    for row in (0..n).rev() {
+        let known = (row + 1..n)
+            .map(|idx| matrix[row][idx] * solution[idx])
+            .sum::<f64>();
+        solution[row] = (rhs[row] - known) / matrix[row][row];
+    }
+    Some(solution)
+}
+
+/// The eigenvalues of a symmetric matrix in ascending order, computed with the cyclic Jacobi
+/// method.
+///
+/// # Arguments
+///
+/// * `matrix` - The symmetric matrix.
+fn symmetric_eigenvalues(mut matrix: Vec<Vec<f64>>) -> Vec<f64> {
+    let n = matrix.len();
+    for _ in 0..100 {
+        let off_diagonal = (0..n)
+            .flat_map(|row| {
+                (0..n)
+                    .filter(move |column| *column != row)
+                    .map(move |column| (row, column))
+            })
+            .map(|(row, column)| matrix[row][column].powi(2))
+            .sum::<f64>();
+        let total = matrix
+            .iter()
+            .flatten()
+            .map(|entry| entry * entry)
+            .sum::<f64>();
+        if off_diagonal <= (f64::EPSILON * f64::EPSILON) * total {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                if matrix[p][q] == 0.0 {
+                    continue;
+                }
+                // Rotate by the angle that zeroes the entry at (p, q).
+                let theta = (matrix[q][q] - matrix[p][p]) / (2.0 * matrix[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for entries in matrix.iter_mut() {
+                    let (kp, kq) = (entries[p], entries[q]);
+                    entries[p] = c * kp - s * kq;
+                    entries[q] = s * kp + c * kq;
+                }
+                let (upper, lower) = matrix.split_at_mut(q);
+                for (pk, qk) in upper[p].iter_mut().zip(lower[0].iter_mut()) {
+                    let (a, b) = (*pk, *qk);
+                    *pk = c * a - s * b;
+                    *qk = s * a + c * b;
+                }
+            }
+        }
+    }
+    let mut eigenvalues = (0..n).map(|idx| matrix[idx][idx]).collect::<Vec<f64>>();
+    eigenvalues.sort_by(f64::total_cmp);
+    eigenvalues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fit, symmetric_eigenvalues};
+    use crate::solution::Solution;
+
+    #[test]
+    fn eigenvalues_of_a_rotated_matrix() {
+        // The eigenvalues 1 and 3, rotated by 45 degrees.
+        let eigenvalues = symmetric_eigenvalues(vec![vec![2.0, 1.0], vec![1.0, 2.0]]);
+        assert!((eigenvalues[0] - 1.0).abs() < 1e-12);
+        assert!((eigenvalues[1] - 3.0).abs() < 1e-12);
+    }
+    #[test]
+    fn fit_recovers_a_correlated_quadratic() {
+        let fitness = |x: &[f64]| {
+            let (a, b) = (x[0] - 10.0, x[1] - 2e-3);
+            5.0 - a * a - 1e6 * b * b - 1e3 * a * b
+        };
+        let center = Solution::new(vec![10.5, 1e-3]);
+        let points = (0..40)
+            .map(|idx| {
+                let x = vec![
+                    9.0 + 2.0 * ((idx * 7) % 40) as f64 / 40.0,
+                    4e-3 * ((idx * 13) % 40) as f64 / 40.0,
+                ];
+                (Solution::new(x.clone()), fitness(&x))
+            })
+            .collect::<Vec<(Solution, f64)>>();
+        let fit = fit(&center, &points, 30).unwrap();
+        assert_eq!(fit.n_points, 30);
+        assert!(fit.residual_std.unwrap() < 1e-6);
+        assert!((fit.hessian[0][1] + 1e3).abs() < 1e-3);
+        assert!(fit.curvatures.iter().all(|curvature| *curvature < 0.0));
+        let (optimum, value) = fit.optimum.unwrap();
+        assert!((optimum.get_arguments()[0] - 10.0).abs() < 1e-6);
+        assert!((optimum.get_arguments()[1] - 2e-3).abs() < 1e-9);
+        assert!((value - 5.0).abs() < 1e-6);
+        // Too few points don't determine the model.
+        assert!(super::fit(&center, &points[..5], 30).is_none());
+    }
+    #[test]
+    fn saddles_have_no_optimum() {
+        let points = (-2..=2)
+            .flat_map(|x| (-2..=2).map(move |y| vec![x as f64, y as f64]))
+            .map(|x| (Solution::new(x.clone()), x[0] * x[0] - x[1] * x[1]))
+            .collect::<Vec<(Solution, f64)>>();
+        let fit = fit(&Solution::new(vec![0.0, 0.0]), &points, 25).unwrap();
+        assert!(fit.optimum.is_none());
+        assert!((fit.curvatures[0] + 2.0).abs() < 1e-9 && (fit.curvatures[1] - 2.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,545 @@
+use crate::json::Json;
+use rand::{Error, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+/// The increment of the SplitMix64 state, the golden ratio in 64 bit fixed point.
+const GOLDEN_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// The SplitMix64 output function, a strong 64 bit mixer.
+///
+/// # Arguments
+///
+/// * `value` - The value that should be mixed.
+fn mix(value: u64) -> u64 {
+    let value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    let value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    value ^ (value >> 31)
+}
+
+/// Derive a new seed from a seed and a sequence of keys. Different key sequences result in
+/// statistically independent seeds, the same key sequence always results in the same seed.
+///
+/// # Arguments
+///
+/// * `seed` - The seed to derive from.
+/// * `keys` - The keys identifying the stream, e.g. `[island, generation, index]`.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::rng;
+///
+/// assert_eq!(rng::stream_seed(42, &[1, 2]), rng::stream_seed(42, &[1, 2]));
+/// assert_ne!(rng::stream_seed(42, &[1, 2]), rng::stream_seed(42, &[2, 1]));
+/// ```
+pub fn stream_seed(seed: u64, keys: &[u64]) -> u64 {
+    keys.iter().fold(mix(seed), |state, key| {
+        mix(state ^ mix(key.wrapping_add(GOLDEN_GAMMA)))
+    })
+}
+
+/// Draw from the standard normal distribution with the Box-Muller transform.
+///
+/// # Arguments
+///
+/// * `rng` - The random number generator to draw from.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::rng::{self, SplitMix64};
+///
+/// let value = rng::standard_normal(&mut SplitMix64::new(42));
+/// assert!(value.is_finite());
+/// ```
+pub fn standard_normal<G>(rng: &mut G) -> f64
+where
+    G: Rng + ?Sized,
+{
+    // `1 - x` lies in (0, 1], so the logarithm stays finite.
+    let radius = (-2.0 * (1.0 - rng.gen::<f64>()).ln()).sqrt();
+    radius * (std::f64::consts::TAU * rng.gen::<f64>()).cos()
+}
+
+/// Draw from the standard Cauchy distribution by inverting its distribution function. It has
+/// no mean or variance: most draws are small, a few are very long.
+///
+/// # Arguments
+///
+/// * `rng` - The random number generator to draw from.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::rng::{self, SplitMix64};
+///
+/// let step = rng::standard_cauchy(&mut SplitMix64::new(42));
+/// assert!(step.is_finite());
+/// ```
+pub fn standard_cauchy<G>(rng: &mut G) -> f64
+where
+    G: Rng + ?Sized,
+{
+    (std::f64::consts::PI * (rng.gen::<f64>() - 0.5)).tan()
+}
+
+/// Draw from the symmetric alpha-stable distribution with the given tail index, using the
+/// Chambers-Mallows-Stuck method. The smaller the tail index the heavier the tails: `2` is
+/// the normal distribution with variance `2`, `1` the standard Cauchy distribution.
+///
+/// # Arguments
+///
+/// * `alpha` - The tail index, clamped into `(0, 2]`.
+/// * `rng` - The random number generator to draw from.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::rng::{self, SplitMix64};
+///
+/// let step = rng::symmetric_stable(1.5, &mut SplitMix64::new(42));
+/// assert!(step.is_finite());
+/// ```
+pub fn symmetric_stable<G>(alpha: f64, rng: &mut G) -> f64
+where
+    G: Rng + ?Sized,
+{
+    let alpha = alpha.clamp(f64::EPSILON, 2.0);
+    let angle = std::f64::consts::PI * (rng.gen::<f64>() - 0.5);
+    // `1 - x` lies in (0, 1], so the logarithm stays finite.
+    let exponential = -(1.0 - rng.gen::<f64>()).ln();
+    if alpha == 1.0 {
+        return angle.tan();
+    }
+    (alpha * angle).sin() / angle.cos().powf(1.0 / alpha)
+        * (((1.0 - alpha) * angle).cos() / exponential).powf((1.0 - alpha) / alpha)
+}
+
+/// A small and fast random number generator that can be split into independent streams.
+/// It is used to give every individual of every generation its own stream, so results don't
+/// depend on the order in which threads happen to draw random numbers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    /// Create a new generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The initial state of the generator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::rng::SplitMix64;
+    /// use rand::Rng;
+    ///
+    /// let value: f64 = SplitMix64::new(42).gen();
+    /// ```
+    pub fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+    /// Create the stream of the individual `index` in generation `generation`.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed of the run.
+    /// * `generation` - The generation the individual belongs to.
+    /// * `index` - The index of the individual within its generation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::rng::SplitMix64;
+    /// use rand::RngCore;
+    ///
+    /// assert_eq!(
+    ///     SplitMix64::stream(42, 3, 7).next_u64(),
+    ///     SplitMix64::stream(42, 3, 7).next_u64()
+    /// );
+    /// ```
+    pub fn stream(seed: u64, generation: u64, index: u64) -> Self {
+        SplitMix64::new(stream_seed(seed, &[generation, index]))
+    }
+}
+
+impl RngCore for SplitMix64 {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+        mix(self.state)
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for SplitMix64 {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        SplitMix64::new(u64::from_le_bytes(seed))
+    }
+    fn seed_from_u64(state: u64) -> Self {
+        SplitMix64::new(state)
+    }
+}
+
+/// The slope of the tent map. With a slope of exactly `2` every iteration shifts a bit out of
+/// the mantissa and the sequence collapses to `0` after about 50 iterations.
+const TENT_SLOPE: f64 = 1.999_999;
+
+/// A chaotic map, iterated by `ChaoticRng`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChaoticMap {
+    /// The logistic map `x -> 4 x (1 - x)`.
+    Logistic,
+    /// The tent map `x -> s min(x, 1 - x)` with a slope `s` just below `2`.
+    Tent,
+}
+
+/// A random number generator iterating a chaotic map instead of a pseudo-random generator, a
+/// common variant in the metaheuristics literature. Plug it in wherever an `Rng` is taken,
+/// e.g. `Runner::with_rng` for the operators of a run and `Solutions::random_with_rng` for
+/// the initial population; `RunnerConfig::individual_rng_streams` keeps using `SplitMix64`
+/// streams. The iterates are transformed to be roughly uniform in `[0, 1)`, two of them make
+/// up every `u64`. Since floating point iterations can fall onto a fixed point, the state is
+/// reseeded from `SplitMix64` when that happens.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChaoticRng {
+    map: ChaoticMap,
+    state: f64,
+    reseed: SplitMix64,
+}
+
+impl ChaoticRng {
+    /// Create a new generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `map` - The chaotic map to iterate.
+    /// * `seed` - Determines the initial state of the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::rng::{ChaoticMap, ChaoticRng};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let population =
+    ///     Solutions::random_with_rng(10, -5.0..5.0, 3, &mut ChaoticRng::new(ChaoticMap::Tent, 7));
+    /// ```
+    pub fn new(map: ChaoticMap, seed: u64) -> Self {
+        let mut rng = ChaoticRng {
+            map,
+            state: 0.0,
+            reseed: SplitMix64::new(seed),
+        };
+        rng.reseed();
+        rng
+    }
+    /// Draw a new state strictly between `0` and `1`.
+    fn reseed(&mut self) {
+        self.state = loop {
+            let state = self.reseed.gen::<f64>();
+            if state > 0.0 {
+                break state;
+            }
+        };
+    }
+    /// Iterate the map once and return the new state transformed to `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        let next = match self.map {
+            ChaoticMap::Logistic => 4.0 * self.state * (1.0 - self.state),
+            ChaoticMap::Tent => TENT_SLOPE * self.state.min(1.0 - self.state),
+        };
+        if next > 0.0 && next < 1.0 && next != self.state {
+            self.state = next;
+        } else {
+            self.reseed();
+        }
+        let value = match self.map {
+            // The logistic map is distributed like `sin²(πu/2)` for a uniform `u`.
+            ChaoticMap::Logistic => self.state.sqrt().asin() * std::f64::consts::FRAC_2_PI,
+            ChaoticMap::Tent => self.state,
+        };
+        value.min(1.0 - f64::EPSILON)
+    }
+}
+
+impl RngCore for ChaoticRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_f64() * 4_294_967_296.0) as u32
+    }
+    fn next_u64(&mut self) -> u64 {
+        (u64::from(self.next_u32()) << 32) | u64::from(self.next_u32())
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// The state of a ChaCha random number generator, e.g. the one of a seeded `Runner` or of one
+/// of its islands. A generator restored from it continues the stream exactly where it was
+/// captured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RngState {
+    /// The key of the generator.
+    seed: [u8; 32],
+    /// The stream of the generator.
+    stream: u64,
+    /// The position within the stream, in 32 bit words.
+    word_pos: u128,
+}
+
+impl RngState {
+    /// Capture the state of `rng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - The generator whose state should be captured.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::rng::RngState;
+    /// use rand::{RngCore, SeedableRng};
+    /// use rand_chacha::ChaCha12Rng;
+    ///
+    /// let mut rng = ChaCha12Rng::seed_from_u64(42);
+    /// rng.next_u64();
+    /// let state = RngState::capture(&rng);
+    /// assert_eq!(state.restore().next_u64(), rng.next_u64());
+    /// ```
+    pub fn capture(rng: &ChaCha12Rng) -> Self {
+        RngState {
+            seed: rng.get_seed(),
+            stream: rng.get_stream(),
+            word_pos: rng.get_word_pos(),
+        }
+    }
+    /// Create a generator continuing the stream where it was captured.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::rng::RngState;
+    /// use rand::{RngCore, SeedableRng};
+    /// use rand_chacha::ChaCha12Rng;
+    ///
+    /// let rng = ChaCha12Rng::seed_from_u64(42);
+    /// assert_eq!(RngState::capture(&rng).restore(), rng);
+    /// ```
+    pub fn restore(&self) -> ChaCha12Rng {
+        let mut rng = ChaCha12Rng::from_seed(self.seed);
+        rng.set_stream(self.stream);
+        rng.set_word_pos(self.word_pos);
+        rng
+    }
+    /// How many 32 bit words were drawn from the stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::rng::RngState;
+    /// use rand::{RngCore, SeedableRng};
+    /// use rand_chacha::ChaCha12Rng;
+    ///
+    /// let mut rng = ChaCha12Rng::seed_from_u64(42);
+    /// rng.next_u64();
+    /// assert_eq!(RngState::capture(&rng).word_pos(), 2);
+    /// ```
+    pub fn word_pos(&self) -> u128 {
+        self.word_pos
+    }
+    /// Write the state as JSON. The integers are written as strings since they don't fit into
+    /// the `f64` of a JSON number.
+    pub(crate) fn to_json_value(self) -> Json {
+        Json::Object(vec![
+            (
+                "seed".to_string(),
+                Json::String(
+                    self.seed
+                        .iter()
+                        .map(|byte| format!("{:02x}", byte))
+                        .collect(),
+                ),
+            ),
+            ("stream".to_string(), Json::String(self.stream.to_string())),
+            (
+                "word_pos".to_string(),
+                Json::String(self.word_pos.to_string()),
+            ),
+        ])
+    }
+    /// Read a state written by `to_json_value`, `None` if it is invalid.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The JSON value to read from.
+    pub(crate) fn from_json_value(value: &Json) -> Option<Self> {
+        let hex = value.get("seed")?.as_str()?;
+        if hex.len() != 64 || !hex.is_ascii() {
+            return None;
+        }
+        let mut seed = [0; 32];
+        for (byte, digits) in seed.iter_mut().zip(hex.as_bytes().chunks(2)) {
+            *byte = u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()?;
+        }
+        Some(RngState {
+            seed,
+            stream: value.get("stream")?.as_str()?.parse().ok()?,
+            word_pos: value.get("word_pos")?.as_str()?.parse().ok()?,
+        })
+    }
+}
+
+/// The states of all random number generators of a run, see `OptimizationResult::rng_state`.
+/// Passing it to `Runner::with_rng_state` continues a run with the random numbers it would
+/// have drawn next.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunRngState {
+    /// The generator of the runner, `None` if the runner was given its own one through
+    /// `Runner::with_rng`.
+    pub runner: Option<RngState>,
+    /// The generators of the islands of a multi-threaded run, by island. Empty for
+    /// single-threaded runs, which draw from the generator of the runner.
+    pub islands: Vec<RngState>,
+}
+
+impl RunRngState {
+    /// Write the states as JSON.
+    pub(crate) fn to_json_value(&self) -> Json {
+        Json::Object(vec![
+            (
+                "runner".to_string(),
+                self.runner.map_or(Json::Null, RngState::to_json_value),
+            ),
+            (
+                "islands".to_string(),
+                Json::Array(
+                    self.islands
+                        .iter()
+                        .map(|island| island.to_json_value())
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+    /// Read states written by `to_json_value`, `None` if they are invalid.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The JSON value to read from.
+    pub(crate) fn from_json_value(value: &Json) -> Option<Self> {
+        let runner = match value.get("runner")? {
+            Json::Null => None,
+            runner => Some(RngState::from_json_value(runner)?),
+        };
+        let islands = value
+            .get("islands")?
+            .as_array()?
+            .iter()
+            .map(RngState::from_json_value)
+            .collect::<Option<Vec<RngState>>>()?;
+        Some(RunRngState { runner, islands })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_values() {
+        // Reference output of SplitMix64 seeded with 0.
+        let mut rng = SplitMix64::new(0);
+        assert_eq!(rng.next_u64(), 0xE220_A839_7B1D_CDAF);
+        assert_eq!(rng.next_u64(), 0x6E78_9E6A_A1B9_65F4);
+    }
+    #[test]
+    fn streams_differ() {
+        assert_ne!(
+            SplitMix64::stream(1, 0, 0).next_u64(),
+            SplitMix64::stream(1, 0, 1).next_u64()
+        );
+        assert_ne!(
+            SplitMix64::stream(1, 0, 1).next_u64(),
+            SplitMix64::stream(1, 1, 0).next_u64()
+        );
+    }
+    #[test]
+    fn chaotic_maps_cover_the_unit_interval() {
+        for map in [ChaoticMap::Logistic, ChaoticMap::Tent] {
+            let mut rng = ChaoticRng::new(map, 5);
+            let mut histogram = [0usize; 10];
+            for _ in 0..100_000 {
+                let value = rng.gen::<f64>();
+                assert!((0.0..1.0).contains(&value));
+                histogram[(value * 10.0) as usize] += 1;
+            }
+            assert!(
+                histogram
+                    .iter()
+                    .all(|count| (7_000..13_000).contains(count)),
+                "{:?}: {:?}",
+                map,
+                histogram
+            );
+            assert_eq!(
+                ChaoticRng::new(map, 5).next_u64(),
+                ChaoticRng::new(map, 5).next_u64()
+            );
+        }
+    }
+    #[test]
+    fn fill_bytes_partial_chunk() {
+        let mut bytes = [0u8; 11];
+        SplitMix64::new(3).fill_bytes(&mut bytes);
+        let mut rng = SplitMix64::new(3);
+        let first = rng.next_u64().to_le_bytes();
+        let second = rng.next_u64().to_le_bytes();
+        assert_eq!(&bytes[..8], &first);
+        assert_eq!(&bytes[8..], &second[..3]);
+    }
+    #[test]
+    fn rng_states_survive_json() {
+        let mut rng = ChaCha12Rng::seed_from_u64(7);
+        rng.set_stream(u64::MAX);
+        rng.next_u32();
+        let state = RunRngState {
+            runner: Some(RngState::capture(&rng)),
+            islands: vec![RngState::capture(&ChaCha12Rng::seed_from_u64(8))],
+        };
+        let read = RunRngState::from_json_value(&state.to_json_value()).unwrap();
+        assert_eq!(read, state);
+        assert_eq!(read.runner.unwrap().restore().next_u64(), rng.next_u64());
+        assert_eq!(
+            RunRngState::from_json_value(&RunRngState::default().to_json_value()),
+            Some(RunRngState::default())
+        );
+        let mut corrupted = state.runner.unwrap().to_json_value();
+        if let Json::Object(entries) = &mut corrupted {
+            entries[0].1 = Json::from("zz");
+        }
+        assert_eq!(RngState::from_json_value(&corrupted), None);
+    }
+}
@@ -0,0 +1,6640 @@
+use crate::archive::{Archive, Evaluation};
+use crate::bounds::Bounds;
+use crate::clock::{Clock, SystemClock};
+use crate::config;
+use crate::evaluation::{EvaluationError, EvaluationOutcomes, Evaluator, FailureKind};
+use crate::executor::{self, Executor, ScopedThreads};
+use crate::function::FunctionError;
+use crate::hall_of_fame::{HallOfFame, Improvement};
+use crate::handle::{RunControl, RunHandle};
+use crate::initializer;
+use crate::pipeline::{OperatorError, Pipeline};
+use crate::polish::{self, Polish, PolishMethod, PolishReport};
+use crate::quadratic::{self, QuadraticFit};
+use crate::rng::{self, RngState, RunRngState};
+use crate::selection::TournamentSelection;
+use crate::solution::{compare_arguments, compare_fitness, Precision, Solution, SolutionKey};
+use crate::solutions::{Identity, MergePolicy, Solutions};
+use crate::stats::{RunStatistics, RunningStats};
+use genetic_algorithm_traits::Population;
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// An individual together with its fitness. The fitness is `None` if the individual has not
+/// been evaluated (yet).
+type Candidate = (Solution, Option<f64>);
+
+/// The settings of a `Runner`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunnerConfig {
+    /// How many times should the population be evolved?
+    pub n_generations: usize,
+    /// How many individuals should be kept after each generation. Replaced by the chosen size
+    /// when the run starts if `population_size` is `PopulationSize::Auto`.
+    pub size_generation: usize,
+    /// The probability of an offspring being mutated.
+    pub mutate_prob: f32,
+    /// The probability that a pair of parents is crossed over. Otherwise the offspring is a
+    /// copy of the first parent and only changes if it is mutated.
+    pub crossover_prob: f32,
+    /// The number of islands evolved in parallel.
+    pub n_jobs: Parallelism,
+    /// How the islands are combined at the end of a multi-threaded run.
+    pub merge_policy: MergePolicy,
+    /// The maximal time the evaluation of a single generation may take. Once it is exceeded,
+    /// the generation continues with the individuals evaluated so far and the remaining ones
+    /// are left unevaluated.
+    pub generation_time_budget: Option<Duration>,
+    /// How many individuals are handed to the evaluator at once. The time budget is checked
+    /// between chunks. `0` is treated as `1`.
+    pub evaluation_chunk_size: usize,
+    /// The seed of the random number generator. `None` seeds from the operating system.
+    /// Is ignored when the runner was created with `Runner::with_rng`.
+    pub seed: Option<u64>,
+    /// Give the mutation of every offspring its own random number stream, derived from
+    /// (seed, island, generation, index). Results then don't depend on how the random numbers
+    /// of the runner are consumed, e.g. when the reproduction is scheduled differently.
+    pub individual_rng_streams: bool,
+    /// Race the offspring of every generation through increasing fidelities, only the best
+    /// of each rung are evaluated at the next one. `None` evaluates every offspring exactly.
+    pub successive_halving: Option<SuccessiveHalving>,
+    /// Record every evaluation and its fidelity in `OptimizationResult::archive`.
+    pub record_archive: bool,
+    /// The maximal number of individuals kept in the archive. Once more were evaluated, a
+    /// uniform sample of them is kept. `None` keeps every individual.
+    pub max_archive_entries: Option<usize>,
+    /// The maximal number of generation reports kept per island, older ones are dropped
+    /// first. Also bounds the reports kept in checkpoints. `None` keeps every report.
+    pub max_history: Option<usize>,
+    /// The weight of the latest generation in the moving averages of
+    /// `OptimizationResult::statistics`, between `0` and `1`.
+    pub ema_smoothing: f64,
+    /// How the individuals surviving a generation are chosen.
+    pub survivor_selection: SurvivorSelection,
+    /// Evolve in steady-state mode: every generation only a few offspring are inserted into
+    /// the population. `None` replaces the whole generation.
+    pub steady_state: Option<SteadyState>,
+    /// The number of generations an individual may survive. Older individuals are retired,
+    /// unless there are too few others to fill the generation, which forces turnover. `None`
+    /// lets individuals live forever.
+    pub max_lifespan: Option<usize>,
+    /// Never cross over two parents whose arguments all differ by at most this epsilon, which
+    /// would only produce clones of them. `None` crosses over every pair the pipeline selects.
+    pub distinct_parents: Option<f64>,
+    /// Check after every stage of the pipeline that children have the length of their
+    /// parents, finite arguments and stay within the bounds, if known. Meant for debugging
+    /// custom stages, see `Runner::try_run`.
+    pub check_operators: bool,
+    /// The maximum number of evaluations per generation and island, e.g. because every
+    /// evaluation occupies a license seat. Unevaluated parents are evaluated first, the rest of
+    /// the quota goes to the offspring picked by `offspring_priority`, the other offspring are
+    /// dropped. With successive halving the quota limits the offspring entering the first
+    /// rung. `None` evaluates every offspring.
+    pub evaluation_quota: Option<usize>,
+    /// Which offspring are evaluated when not all of them can be, because of
+    /// `evaluation_quota` or in steady-state mode.
+    pub offspring_priority: OffspringPriority,
+    /// How many of the fittest individuals of the run are kept in
+    /// `OptimizationResult::hall_of_fame`.
+    pub hall_of_fame_size: usize,
+    /// How many threads the evaluator of every island uses, e.g. the size of the rayon
+    /// `ThreadPool` the objective runs its own parallel code on. `Runner::with_island_threads`
+    /// runs every island inside such a pool, so islands and evaluations share the machine
+    /// instead of oversubscribing it. `0` is treated as `1`.
+    pub threads_per_evaluation: usize,
+    /// The maximal number of threads of a run, islands times `threads_per_evaluation`. The
+    /// number of islands is reduced to fit, a budget for less than two islands runs
+    /// single-threaded, and a budget for less than one evaluation reduces the threads of the
+    /// evaluation to the budget, see `RunnerConfig::evaluation_threads`. `None` doesn't limit
+    /// the threads.
+    pub max_threads: Option<usize>,
+    /// Size the offspring of every generation so their evaluation approximately fills
+    /// `generation_time_budget`, based on the measured time per evaluation, instead of
+    /// evaluating a fixed number of offspring. The pipeline is run repeatedly if a single run
+    /// produces too few offspring. Has no effect without `generation_time_budget` or in
+    /// steady-state mode.
+    pub size_to_time_budget: bool,
+    /// Accept offspring that are less fit than the individual they would replace with the
+    /// Metropolis probability of simulated annealing, to escape local optima. `None` only
+    /// accepts fitter offspring.
+    pub annealing: Option<Annealing>,
+    /// Assess every individual by the mean fitness of its last `k` exact evaluations instead
+    /// of a single one, to smooth mildly noisy objectives. The population is evaluated again
+    /// in every generation (in steady-state mode, whenever offspring are inserted), so an
+    /// individual that survives accumulates evaluations. Only the histories of the current
+    /// population are kept, they are not part of checkpoints. `None` evaluates every
+    /// individual once.
+    pub fitness_window: Option<usize>,
+    /// The fitness of individuals whose evaluation failed, panicked or returned a non-finite
+    /// value, so a flaky objective doesn't end the run. `None` panics on the first failed
+    /// evaluation and keeps non-finite values as fitness. The outcomes are counted either way,
+    /// see `GenerationReport::evaluations`. `Runner::with_penalty_function` takes precedence.
+    pub failure_penalty: Option<f64>,
+    /// Whether `size_generation` is used as it is or chosen from the number of arguments of
+    /// the individuals.
+    pub population_size: PopulationSize,
+    /// Restart an island once its best fitness stagnated, keeping only its elites. `None`
+    /// never restarts.
+    pub restart: Option<Restart>,
+    /// Spend the evaluations of the last generations on a local search around the fittest
+    /// individuals, see `OptimizationResult::polish`. `None` evolves every generation.
+    pub polish: Option<Polish>,
+    /// Freeze the arguments the population converged on late in the run. `None` mutates
+    /// every argument.
+    pub freeze: Option<Freeze>,
+    /// How many of the fittest individuals survive every generation unchanged, whatever the
+    /// survivor selection, retirement or annealing would decide, so the best individual is
+    /// never lost. Steady state runs replace individuals one by one and ignore it. `0` leaves
+    /// the survivors to the survivor selection.
+    pub elitism: usize,
+    /// Shrink the bounds offspring are confined to around the fittest individual. `None`
+    /// searches the whole space every generation.
+    pub zoom: Option<Zoom>,
+    /// Change the probability of mutating an offspring over the generations, replacing
+    /// `mutate_prob`. `None` mutates with `mutate_prob` in every generation.
+    pub mutate_schedule: Option<Schedule>,
+    /// Whether the function values are maximized or minimized, see `ObjectiveDirection`.
+    pub direction: ObjectiveDirection,
+    /// Evaluate the candidates of a generation in batches of this many and sort every
+    /// evaluated batch on a second thread while the next one is evaluated, the sorted batches
+    /// are merged at the end. Pays off for very large generations whose evaluation and
+    /// selection both take a while. The candidates then reach the survivor selection fittest
+    /// first, which only changes the outcome of randomized selections. Ignored with
+    /// `successive_halving` and `steady_state`. `None` evaluates all candidates before sorting.
+    pub overlap_batch_size: Option<usize>,
+    /// Stop an island once its best individual reaches this function value, i.e. once the
+    /// value is at least the target when maximizing and at most the target when minimizing.
+    /// `OptimizationResult::stopped_early` then tells the run ended early and
+    /// `OptimizationResult::n_evolved_generations` how many generations it took. `None` always
+    /// evolves all `n_generations`.
+    pub target_value: Option<f64>,
+    /// Stop an island once its best fitness converged, see `Patience`. `None` always evolves
+    /// all `n_generations`.
+    pub patience: Option<Patience>,
+    /// Which precision individuals are compared and deduplicated at, see `DedupPrecision`.
+    pub dedup_precision: DedupPrecision,
+    /// Stop the run once the evaluator was called this many times, summed over all islands,
+    /// i.e. once `Function::get_function_value` was called this often when evaluating a
+    /// `Function`. For expensive objectives the evaluations rather than the generations are
+    /// the budget. Generations only evaluate the offspring the remaining budget allows, the
+    /// others are dropped like with `evaluation_quota`; unevaluated parents, the rungs of
+    /// `successive_halving` and `steady_state` generations may exceed it by a generation.
+    /// `None` always evolves all `n_generations`.
+    pub max_evaluations: Option<usize>,
+}
+
+impl RunnerConfig {
+    /// The number of individuals kept after each generation when evolving individuals with
+    /// `dimension` arguments, i.e. the size of the initial population to draw. Resolves
+    /// `PopulationSize::Auto`.
+    ///
+    /// # Arguments
+    ///
+    /// * `dimension` - The number of arguments of the individuals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::{PopulationSize, RunnerConfig};
+    ///
+    /// let config = RunnerConfig {
+    ///     population_size: PopulationSize::Auto(1.0),
+    ///     ..RunnerConfig::default()
+    /// };
+    /// assert_eq!(config.size_generation_for(3), 7);
+    /// ```
+    pub fn size_generation_for(&self, dimension: usize) -> usize {
+        self.population_size
+            .size_generation(dimension, self.size_generation)
+    }
+    /// The number of islands a run with this config evolves, `0` meaning single-threaded.
+    /// Resolves `Parallelism::Auto` and enforces `max_threads`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::{Parallelism, RunnerConfig};
+    ///
+    /// let config = RunnerConfig {
+    ///     n_jobs: Parallelism::Jobs(8),
+    ///     threads_per_evaluation: 4,
+    ///     max_threads: Some(16),
+    ///     ..RunnerConfig::default()
+    /// };
+    /// assert_eq!(config.n_islands(), 4);
+    /// ```
+    pub fn n_islands(&self) -> usize {
+        let threads_per_evaluation = self.evaluation_threads();
+        let n_jobs = self
+            .n_jobs
+            .n_jobs(self.size_generation, threads_per_evaluation);
+        match self.max_threads {
+            Some(max_threads) if n_jobs * threads_per_evaluation > max_threads => {
+                match max_threads / threads_per_evaluation {
+                    0 | 1 => 0,
+                    max_islands => max_islands,
+                }
+            }
+            _ => n_jobs,
+        }
+    }
+    /// The number of threads the evaluator of every island uses: `threads_per_evaluation`, at
+    /// least one and at most `max_threads`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::RunnerConfig;
+    ///
+    /// let config = RunnerConfig {
+    ///     threads_per_evaluation: 4,
+    ///     max_threads: Some(3),
+    ///     ..RunnerConfig::default()
+    /// };
+    /// assert_eq!(config.evaluation_threads(), 3);
+    /// assert_eq!(config.n_islands(), 0);
+    /// ```
+    pub fn evaluation_threads(&self) -> usize {
+        match self.max_threads {
+            Some(max_threads) => self.threads_per_evaluation.min(max_threads).max(1),
+            None => self.threads_per_evaluation.max(1),
+        }
+    }
+}
+
+impl Default for RunnerConfig {
+    /// The defaults are the settings `evolve_population` has always been using.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::RunnerConfig;
+    ///
+    /// let config = RunnerConfig {
+    ///     n_generations: 10,
+    ///     ..RunnerConfig::default()
+    /// };
+    /// assert_eq!(config.mutate_prob, 0.5);
+    /// ```
+    fn default() -> Self {
+        RunnerConfig {
+            n_generations: 100,
+            size_generation: 20,
+            mutate_prob: 0.5,
+            crossover_prob: 1.0,
+            n_jobs: Parallelism::default(),
+            merge_policy: MergePolicy::default(),
+            generation_time_budget: None,
+            evaluation_chunk_size: 1,
+            seed: None,
+            individual_rng_streams: false,
+            successive_halving: None,
+            record_archive: false,
+            max_archive_entries: None,
+            max_history: None,
+            ema_smoothing: 0.1,
+            survivor_selection: SurvivorSelection::default(),
+            steady_state: None,
+            max_lifespan: None,
+            distinct_parents: None,
+            check_operators: false,
+            evaluation_quota: None,
+            offspring_priority: OffspringPriority::default(),
+            hall_of_fame_size: 1,
+            threads_per_evaluation: 1,
+            max_threads: None,
+            size_to_time_budget: false,
+            annealing: None,
+            fitness_window: None,
+            failure_penalty: None,
+            population_size: PopulationSize::default(),
+            restart: None,
+            polish: None,
+            freeze: None,
+            elitism: 0,
+            zoom: None,
+            mutate_schedule: None,
+            direction: ObjectiveDirection::default(),
+            overlap_batch_size: None,
+            target_value: None,
+            patience: None,
+            dedup_precision: DedupPrecision::default(),
+            max_evaluations: None,
+        }
+    }
+}
+
+/// How the number of individuals surviving every generation is chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PopulationSize {
+    /// Keep `RunnerConfig::size_generation` individuals.
+    #[default]
+    Fixed,
+    /// Keep `4 + ⌊3·ln(d)·k⌋` individuals for individuals with `d` arguments and the factor
+    /// `k`, the default population size of CMA-ES for `k = 1`. Larger factors search more
+    /// broadly at the cost of more evaluations per generation.
+    Auto(f64),
+}
+
+impl PopulationSize {
+    /// The number of individuals kept after each generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `dimension` - The number of arguments of the individuals.
+    /// * `size_generation` - The size used by `PopulationSize::Fixed`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::PopulationSize;
+    ///
+    /// assert_eq!(PopulationSize::Auto(1.0).size_generation(10, 20), 10);
+    /// assert_eq!(PopulationSize::Auto(2.0).size_generation(10, 20), 17);
+    /// assert_eq!(PopulationSize::Fixed.size_generation(10, 20), 20);
+    /// ```
+    pub fn size_generation(&self, dimension: usize, size_generation: usize) -> usize {
+        match self {
+            PopulationSize::Fixed => size_generation,
+            PopulationSize::Auto(factor) => {
+                let spread = 3.0 * (dimension.max(1) as f64).ln() * factor.max(0.0);
+                4 + spread.floor() as usize
+            }
+        }
+    }
+}
+
+/// How the survivors of a generation are chosen from the parents and their offspring.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum SurvivorSelection {
+    /// Keep the fittest individuals.
+    #[default]
+    Truncation,
+    /// Split the evaluated individuals into equally sized fitness quantiles, from the fittest
+    /// to the least fit one, and keep the fittest individuals of every quantile. The weights
+    /// are the shares of the survivors taken from the quantiles, e.g. `[0.6, 0.3, 0.1]` takes
+    /// 60% of the survivors from the best third. Keeping some less fit individuals preserves
+    /// spread, which avoids converging prematurely on deceptive functions.
+    Stratified(Vec<f64>),
+    /// Every survivor wins a tournament among randomly drawn individuals, see
+    /// `TournamentSelection`.
+    Tournament(TournamentSelection),
+}
+
+/// Restart an island whose best fitness didn't improve for a while: its elites survive and the
+/// rest of the population is drawn afresh, partly as quasi-opposite points of the elites, which
+/// explore the regions complementary to where the island converged. A quasi-opposite point lies
+/// uniformly between the center of the bounds and the opposite of the elite. New individuals
+/// are drawn within the bounds of the pipeline, or those spanned by the initial population if
+/// it has none.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Restart {
+    /// After how many generations without improvement of the best fitness an island restarts.
+    pub stagnation_generations: usize,
+    /// How many of the fittest individuals survive the restart.
+    pub n_elites: usize,
+    /// The share of the new individuals that are quasi-opposite points of the elites, in
+    /// `[0, 1]`. The others are drawn uniformly.
+    pub quasi_opposition: f64,
+}
+
+/// Stop an island whose best fitness didn't improve by more than `epsilon` for `generations`
+/// generations in a row, as it has converged. `OptimizationResult::stopped_early` then tells
+/// the run ended early and `OptimizationResult::n_evolved_generations` how many generations
+/// actually ran.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Patience {
+    /// For how many generations without improvement an island keeps evolving.
+    pub generations: usize,
+    /// By how much the best fitness has to exceed the best one so far to count as an
+    /// improvement.
+    pub epsilon: f64,
+}
+
+/// Stop mutating the arguments a population converged on late in a run and mutate the others
+/// instead, so perturbations aren't wasted on arguments that are already settled. An argument
+/// is frozen while the standard deviation of the population along it is below `min_std`
+/// times the width of its bounds, those of the pipeline or those spanned by the initial
+/// population if it has none. Every argument freezing at once freezes none.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Freeze {
+    /// The generation from which on arguments are frozen.
+    pub after_generations: usize,
+    /// The standard deviation relative to the width of the bounds below which an argument
+    /// is frozen.
+    pub min_std: f64,
+}
+
+/// Shrink the bounds offspring are confined to around the fittest individual of an island
+/// while the run progresses, to concentrate the search on the most promising region. The
+/// zoom starts from the bounds of the pipeline, or those spanned by the initial population if
+/// it has none, never shrinks below `min_width` of them and expands to all of them again once
+/// the best fitness stagnated. The active bounds are reported in `GenerationReport::bounds`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Zoom {
+    /// After how many generations the bounds shrink again. `0` is treated as `1`.
+    pub every_generations: usize,
+    /// The factor in `(0, 1)` the width of the bounds is multiplied with on every shrink.
+    pub factor: f64,
+    /// The smallest width relative to the full bounds, the safeguard against zooming into a
+    /// single point.
+    pub min_width: f64,
+    /// After how many generations without improvement the bounds expand to the full bounds
+    /// again. `0` never expands them.
+    pub expand_after: usize,
+}
+
+impl Default for Zoom {
+    fn default() -> Self {
+        Zoom {
+            every_generations: 5,
+            factor: 0.8,
+            min_width: 0.01,
+            expand_after: 20,
+        }
+    }
+}
+
+/// How the probability of mutating an offspring changes from the first to the last generation
+/// of a run, e.g. to explore with many mutations early and to exploit with few late. The
+/// probability is clamped into `[0, 1]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Schedule {
+    /// Change by the same amount every generation.
+    Linear {
+        /// The probability in the first generation.
+        start: f32,
+        /// The probability in the last generation.
+        end: f32,
+    },
+    /// Change by the same factor every generation, which spends more generations at small
+    /// probabilities than a linear decay. Changes linearly unless both probabilities are
+    /// positive.
+    Exponential {
+        /// The probability in the first generation.
+        start: f32,
+        /// The probability in the last generation.
+        end: f32,
+    },
+}
+
+impl Schedule {
+    /// The probability of mutating an offspring in a generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `generation` - The index of the generation, generations beyond the last one get the
+    ///   probability of the last one.
+    /// * `n_generations` - The number of generations of the run.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::Schedule;
+    ///
+    /// let linear = Schedule::Linear {
+    ///     start: 0.9,
+    ///     end: 0.1,
+    /// };
+    /// assert_eq!(linear.mutate_prob(0, 5), 0.9);
+    /// assert!((linear.mutate_prob(2, 5) - 0.5).abs() < 1e-6);
+    /// assert_eq!(linear.mutate_prob(4, 5), 0.1);
+    /// let exponential = Schedule::Exponential {
+    ///     start: 0.8,
+    ///     end: 0.2,
+    /// };
+    /// assert!((exponential.mutate_prob(1, 3) - 0.4).abs() < 1e-6);
+    /// ```
+    pub fn mutate_prob(&self, generation: usize, n_generations: usize) -> f32 {
+        let progress = match n_generations {
+            0 | 1 => 0.0,
+            n_generations => (generation as f32 / (n_generations - 1) as f32).min(1.0),
+        };
+        let mutate_prob = match *self {
+            Schedule::Exponential { start, end } if start > 0.0 && end > 0.0 => {
+                start * (end / start).powf(progress)
+            }
+            Schedule::Linear { start, end } | Schedule::Exponential { start, end } => {
+                start * (1.0 - progress) + end * progress
+            }
+        };
+        mutate_prob.clamp(0.0, 1.0)
+    }
+}
+
+/// Metropolis-style acceptance of worse offspring with a temperature that decreases
+/// geometrically over the generations. An offspring that is `loss` less fit than the
+/// individual it would replace is accepted with probability `exp(-loss / temperature)`. In
+/// the generational mode the rejected offspring compete, fittest first, with the survivors,
+/// least fit first; the fittest survivor is never replaced.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Annealing {
+    /// The temperature of the first generation, in units of fitness.
+    pub initial_temperature: f64,
+    /// The factor the temperature is multiplied with after every generation, in `(0, 1]`.
+    pub cooling_rate: f64,
+}
+
+impl Annealing {
+    /// The temperature of a generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `generation` - The index of the generation within its island.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::Annealing;
+    ///
+    /// let annealing = Annealing {
+    ///     initial_temperature: 2.0,
+    ///     cooling_rate: 0.5,
+    /// };
+    /// assert_eq!(annealing.temperature(2), 0.5);
+    /// ```
+    pub fn temperature(&self, generation: usize) -> f64 {
+        self.initial_temperature
+            * self
+                .cooling_rate
+                .powi(generation.min(i32::MAX as usize) as i32)
+    }
+    /// Decide whether an offspring that is `loss` less fit is accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `loss` - How much less fit the offspring is, non-finite losses are never accepted.
+    /// * `generation` - The index of the generation within its island.
+    /// * `rng` - The random number generator to draw from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::Annealing;
+    ///
+    /// let annealing = Annealing {
+    ///     initial_temperature: 1.0,
+    ///     cooling_rate: 0.9,
+    /// };
+    /// assert!(annealing.accepts(0.0, 0, &mut rand::thread_rng()));
+    /// assert!(!annealing.accepts(f64::NAN, 0, &mut rand::thread_rng()));
+    /// ```
+    pub fn accepts<G>(&self, loss: f64, generation: usize, rng: &mut G) -> bool
+    where
+        G: RngCore + ?Sized,
+    {
+        let temperature = self.temperature(generation);
+        if !loss.is_finite() || temperature.is_nan() || temperature <= 0.0 {
+            return false;
+        }
+        rng.gen::<f64>() < (-loss.max(0.0) / temperature).exp()
+    }
+}
+
+/// The settings of the steady-state mode. Every generation, `n_offspring` new offspring are
+/// evaluated and each of them may replace one individual of the population. Successive
+/// halving and the survivor selection don't apply in this mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SteadyState {
+    /// How many offspring are evaluated and inserted per generation. `0` is treated as `1`.
+    pub n_offspring: usize,
+    /// Which individual an offspring replaces.
+    pub replacement: ReplacementPolicy,
+}
+
+impl Default for SteadyState {
+    /// A single offspring per generation, which replaces the least fit individual.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::{ReplacementPolicy, RunnerConfig, SteadyState};
+    ///
+    /// let config = RunnerConfig {
+    ///     steady_state: Some(SteadyState {
+    ///         replacement: ReplacementPolicy::ReplaceOldest,
+    ///         ..SteadyState::default()
+    ///     }),
+    ///     ..RunnerConfig::default()
+    /// };
+    /// ```
+    fn default() -> Self {
+        SteadyState {
+            n_offspring: 1,
+            replacement: ReplacementPolicy::default(),
+        }
+    }
+}
+
+/// How many islands a run evolves in parallel, each in its own thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parallelism {
+    /// Exactly this many islands. `0` runs single-threaded in the calling thread.
+    Jobs(usize),
+    /// One island per available core, keeping a core for the rest of the system and sharing
+    /// the cores with the threads of the evaluations. Populations smaller than
+    /// `Parallelism::MIN_AUTO_SIZE_GENERATION` run single-threaded, as spawning the threads
+    /// takes longer than evolving them.
+    Auto,
+}
+
+impl Default for Parallelism {
+    /// Single-threaded, i.e. `Parallelism::Jobs(0)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::Parallelism;
+    ///
+    /// assert_eq!(Parallelism::default(), Parallelism::Jobs(0));
+    /// ```
+    fn default() -> Self {
+        Parallelism::Jobs(0)
+    }
+}
+
+impl Parallelism {
+    /// The smallest population `Parallelism::Auto` evolves on several threads.
+    pub const MIN_AUTO_SIZE_GENERATION: usize = 16;
+
+    /// The number of islands to evolve, `0` meaning single-threaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `size_generation` - The size of the population of every island.
+    /// * `threads_per_evaluation` - How many threads the evaluator of every island uses.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::Parallelism;
+    ///
+    /// assert_eq!(Parallelism::Jobs(3).n_jobs(5, 1), 3);
+    /// assert_eq!(Parallelism::Auto.n_jobs(2, 1), 0);
+    /// ```
+    pub fn n_jobs(&self, size_generation: usize, threads_per_evaluation: usize) -> usize {
+        match self {
+            Parallelism::Jobs(n_jobs) => *n_jobs,
+            Parallelism::Auto => {
+                let n_cores = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+                let n_jobs = n_cores.saturating_sub(1) / threads_per_evaluation.max(1);
+                if n_jobs < 2 || size_generation < Parallelism::MIN_AUTO_SIZE_GENERATION {
+                    0
+                } else {
+                    n_jobs
+                }
+            }
+        }
+    }
+}
+
+/// Whether a run looks for the largest or the smallest function value. The runner always
+/// selects the individuals with the largest fitness, so minimized function values are negated
+/// to get the fitness. Everything reported as fitness, e.g. `OptimizationResult::best` or
+/// `GenerationReport::fitness`, is the negated value then; `value` turns it back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ObjectiveDirection {
+    /// The larger the function value, the fitter the individual.
+    #[default]
+    Maximize,
+    /// The smaller the function value, the fitter the individual, e.g. for a loss or a cost.
+    Minimize,
+}
+
+impl ObjectiveDirection {
+    /// The fitness of an individual with the function value `value`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The function value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::ObjectiveDirection;
+    ///
+    /// assert_eq!(ObjectiveDirection::Maximize.fitness(2.0), 2.0);
+    /// assert_eq!(ObjectiveDirection::Minimize.fitness(2.0), -2.0);
+    /// ```
+    pub fn fitness(&self, value: f64) -> f64 {
+        match self {
+            ObjectiveDirection::Maximize => value,
+            ObjectiveDirection::Minimize => -value,
+        }
+    }
+    /// The function value of an individual with the fitness `fitness`.
+    ///
+    /// # Arguments
+    ///
+    /// * `fitness` - The fitness, e.g. of `OptimizationResult::best`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::ObjectiveDirection;
+    ///
+    /// let direction = ObjectiveDirection::Minimize;
+    /// assert_eq!(direction.value(direction.fitness(0.5)), 0.5);
+    /// ```
+    pub fn value(&self, fitness: f64) -> f64 {
+        // Negating is its own inverse.
+        self.fitness(fitness)
+    }
+}
+
+/// Which precision a run compares and deduplicates individuals at, see `Precision::key`. It
+/// only applies within the run: other runs and the equality of `Solution` are not affected.
+/// The precision the run used is reported in `OptimizationResult::precision`.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::function::Function;
+/// use genetic_algorithm_fn::runner::{DedupPrecision, Runner, RunnerConfig};
+/// use genetic_algorithm_fn::solution::Precision;
+/// use genetic_algorithm_fn::solutions::Solutions;
+///
+/// let result = Runner::new(RunnerConfig {
+///     n_generations: 2,
+///     size_generation: 10,
+///     dedup_precision: DedupPrecision::Auto,
+///     ..RunnerConfig::default()
+/// })
+/// .run(
+///     Solutions::random(10, 0.0..1e6, 2),
+///     &Function::new(|x| Ok(x.iter().sum())),
+/// );
+/// // A domain a million wide isn't compared at ten decimals.
+/// assert!(result.precision.absolute_floor > Precision::default().absolute_floor);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DedupPrecision {
+    /// The precision `Solution` is compared at, `Precision::default`.
+    #[default]
+    Default,
+    /// Choose the precision from the bounds of the pipeline, or those spanned by the initial
+    /// population if it has none, and `RunnerConfig::size_generation`, see
+    /// `Precision::for_bounds`. Arguments of a domain like `[0, 1e6]` aren't compared at ten
+    /// decimals then.
+    Auto,
+    /// Use this precision.
+    Fixed(Precision),
+}
+
+impl DedupPrecision {
+    /// The precision of a run.
+    ///
+    /// # Arguments
+    ///
+    /// * `bounds` - The bounds the run searches within.
+    /// * `population_size` - The number of individuals kept per generation.
+    fn apply(&self, bounds: &[Range<f64>], population_size: usize) -> Precision {
+        match self {
+            DedupPrecision::Default => Precision::default(),
+            DedupPrecision::Auto => Precision::for_bounds(bounds, population_size),
+            DedupPrecision::Fixed(precision) => *precision,
+        }
+    }
+}
+
+/// Which offspring are evaluated first when not all of them can be evaluated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OffspringPriority {
+    /// In the order the pipeline produced them.
+    #[default]
+    Produced,
+    /// In random order.
+    Random,
+    /// The offspring farthest from every individual of the population first, to explore.
+    Novelty,
+    /// The offspring closest to the fittest individual of the population first, to exploit.
+    NearBest,
+}
+
+/// Which individual of the population an offspring replaces in steady-state mode. The
+/// population is kept in the order the individuals were inserted in, ties are broken in favor
+/// of replacing the older individual.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReplacementPolicy {
+    /// Replace the least fit individual, if the offspring is fitter.
+    #[default]
+    ReplaceWorst,
+    /// Replace the individual that has been in the population the longest.
+    ReplaceOldest,
+    /// Replace the individual with the closest arguments, if the offspring is fitter
+    /// (deterministic crowding). Keeps several niches of the function alive.
+    ReplaceMostSimilar,
+}
+
+/// The settings of successive halving. Offspring are first evaluated at the lowest fidelity,
+/// the best `1 / reduction_factor` of them at the next one and so on. Only the offspring
+/// surviving up to the last fidelity get a fitness, the others are discarded. Every rung keeps
+/// at least `RunnerConfig::size_generation` offspring, so a generation can always be filled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuccessiveHalving {
+    /// The increasing fidelities of the rungs, the last one is the fidelity of the fitness,
+    /// typically `1.0`.
+    pub fidelities: Vec<f64>,
+    /// By which factor the number of offspring is reduced from one rung to the next.
+    pub reduction_factor: usize,
+}
+
+impl Default for SuccessiveHalving {
+    /// Three rungs, each keeping a third of the offspring.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::{RunnerConfig, SuccessiveHalving};
+    ///
+    /// let config = RunnerConfig {
+    ///     successive_halving: Some(SuccessiveHalving::default()),
+    ///     ..RunnerConfig::default()
+    /// };
+    /// assert_eq!(config.successive_halving.unwrap().fidelities.last(), Some(&1.0));
+    /// ```
+    fn default() -> Self {
+        SuccessiveHalving {
+            fidelities: vec![1.0 / 9.0, 1.0 / 3.0, 1.0],
+            reduction_factor: 3,
+        }
+    }
+}
+
+/// What happened during a single generation of a single island.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenerationReport {
+    /// The island the generation ran on. Always `0` for single-threaded runs.
+    pub island: usize,
+    /// The index of the generation within its island.
+    pub generation: usize,
+    /// How many individuals of the generation have a fitness.
+    pub n_evaluated: usize,
+    /// How many individuals were left unevaluated because the time budget was exceeded.
+    pub n_unevaluated: usize,
+    /// Whether the evaluation was cut short by `RunnerConfig::generation_time_budget`.
+    pub deadline_exceeded: bool,
+    /// The statistics of the fitness of the evaluated individuals of the generation.
+    pub fitness: RunningStats,
+    /// The ages of the individuals of the generation, i.e. how many generations they survived.
+    pub age: RunningStats,
+    /// How many individuals were retired because they reached `RunnerConfig::max_lifespan`.
+    pub n_retired: usize,
+    /// How many less fit offspring were accepted because of `RunnerConfig::annealing`.
+    pub n_accepted_worse: usize,
+    /// How many evaluations of the generation succeeded and why the others failed.
+    pub evaluations: EvaluationOutcomes,
+    /// The best fitness within the generation, if any individual was evaluated.
+    pub best_fitness: Option<f64>,
+    /// How many offspring were evaluated at each fidelity of successive halving. Empty if
+    /// successive halving is disabled.
+    pub rung_sizes: Vec<usize>,
+    /// The pipeline that produced the offspring of the generation, `0` for the pipeline of the
+    /// runner and `i` for the `i`-th fallback of `Runner::with_operator_fallbacks`. A change
+    /// between consecutive reports of an island marks a switch because of stagnation.
+    pub pipeline: usize,
+    /// Whether the island restarted after the generation, see `RunnerConfig::restart`. The
+    /// statistics of the report describe the population before the restart.
+    pub restarted: bool,
+    /// How many arguments were frozen during the generation, see `RunnerConfig::freeze`.
+    pub n_frozen: usize,
+    /// The bounds offspring were confined to, see `RunnerConfig::zoom`. `None` without
+    /// zooming.
+    pub bounds: Option<Vec<Range<f64>>>,
+}
+
+/// The operator settings a generation of an island is evolved with. They are fixed when the
+/// generation starts, so everything that varies between generations (the pipeline after a
+/// stagnation switch, reconfigurations, the annealing temperature) is decided in a single
+/// place.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OperatorSettings {
+    /// The probability of mutating an offspring.
+    pub mutate_prob: f32,
+    /// The pipeline producing the offspring, see `GenerationReport::pipeline`.
+    pub pipeline: usize,
+    /// The temperature of `RunnerConfig::annealing`, `None` without annealing.
+    pub temperature: Option<f64>,
+    /// The minimal distance of parents, see `RunnerConfig::distinct_parents`.
+    pub distinct_parents: Option<f64>,
+}
+
+/// Operator settings changed while a run evolves, through `RunHandle::reconfigure`, or
+/// between calls of `step` through `Reconfiguration::apply`. Settings left at `None` are kept.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Reconfiguration {
+    /// The probability of mutating an offspring, see `RunnerConfig::mutate_prob`.
+    pub mutate_prob: Option<f32>,
+    /// The minimal distance of parents, see `RunnerConfig::distinct_parents`. `Some(None)`
+    /// allows any parents again.
+    pub distinct_parents: Option<Option<f64>>,
+    /// How the survivors are chosen, see `RunnerConfig::survivor_selection`.
+    pub survivor_selection: Option<SurvivorSelection>,
+    /// The pipeline producing the offspring, i.e. its selection, crossover and mutation
+    /// operators, see `GenerationReport::pipeline`. Ignored by `apply`, `step` always
+    /// reproduces with the default pipeline.
+    pub pipeline: Option<usize>,
+}
+
+impl Reconfiguration {
+    /// Change the settings of a config, e.g. before the next call of `step`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The config to change.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::{Reconfiguration, RunnerConfig};
+    ///
+    /// let mut config = RunnerConfig::default();
+    /// Reconfiguration {
+    ///     mutate_prob: Some(0.1),
+    ///     ..Reconfiguration::default()
+    /// }
+    /// .apply(&mut config);
+    /// assert_eq!(config.mutate_prob, 0.1);
+    /// ```
+    pub fn apply(&self, config: &mut RunnerConfig) {
+        if let Some(mutate_prob) = self.mutate_prob {
+            // A fixed probability replaces the schedule.
+            config.mutate_prob = mutate_prob;
+            config.mutate_schedule = None;
+        }
+        if let Some(distinct_parents) = self.distinct_parents {
+            config.distinct_parents = distinct_parents;
+        }
+        if let Some(survivor_selection) = &self.survivor_selection {
+            config.survivor_selection = survivor_selection.clone();
+        }
+    }
+    /// Combine the changes with later ones, which win where both change a setting.
+    ///
+    /// # Arguments
+    ///
+    /// * `later` - The changes made afterwards.
+    fn merge(&mut self, later: &Reconfiguration) {
+        self.mutate_prob = later.mutate_prob.or(self.mutate_prob);
+        self.distinct_parents = later.distinct_parents.or(self.distinct_parents);
+        if later.survivor_selection.is_some() {
+            self.survivor_selection = later.survivor_selection.clone();
+        }
+        self.pipeline = later.pipeline.or(self.pipeline);
+    }
+}
+
+/// A reconfiguration of a spawned run as it took effect on one of its islands.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reconfigured {
+    /// The island that was reconfigured.
+    pub island: usize,
+    /// The first generation of the island evolved with the changed settings.
+    pub generation: usize,
+    /// The changed settings.
+    pub changes: Reconfiguration,
+}
+
+/// A generation of an island after it evolved, handed to the stopping predicate.
+#[derive(Debug, Clone, Copy)]
+pub struct Generation<'a> {
+    /// The index of the generation within its island.
+    pub index: usize,
+    /// The island the generation ran on. Always `0` for single-threaded runs.
+    pub island: usize,
+    /// What happened during the generation, including the statistics of its fitness.
+    pub report: &'a GenerationReport,
+    /// The individuals of the generation and their fitness, `None` if left unevaluated.
+    pub population: &'a [(Solution, Option<f64>)],
+    /// The operator settings the generation was evolved with.
+    pub settings: OperatorSettings,
+    /// The time passed since the run started.
+    pub elapsed: Duration,
+}
+
+/// A single generation evolved by `step`.
+#[derive(Debug, Clone)]
+pub struct StepResult {
+    /// The individuals after the generation and their fitness, `None` if left unevaluated.
+    pub population: Vec<(Solution, Option<f64>)>,
+    /// What happened during the generation.
+    pub report: GenerationReport,
+    /// The operator settings the generation was evolved with.
+    pub settings: OperatorSettings,
+    /// How long evolving the generation took.
+    pub elapsed: Duration,
+}
+
+impl StepResult {
+    /// View the step as the `Generation` a stopping predicate would have been handed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{self, RunnerConfig};
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let population = vec![(Solution::new(vec![1.0]), None), (Solution::new(vec![2.0]), None)];
+    /// let result = runner::step(
+    ///     population,
+    ///     0,
+    ///     &RunnerConfig::default(),
+    ///     &mut rand::thread_rng(),
+    ///     &Function::new(|x| Ok(x.iter().sum())),
+    /// )
+    /// .unwrap();
+    /// assert_eq!(result.generation().index, 0);
+    /// ```
+    pub fn generation(&self) -> Generation<'_> {
+        Generation {
+            index: self.report.generation,
+            island: self.report.island,
+            report: &self.report,
+            population: &self.population,
+            settings: self.settings,
+            elapsed: self.elapsed,
+        }
+    }
+}
+
+/// The former name of `Generation`.
+pub type GenerationSnapshot<'a> = Generation<'a>;
+
+/// A user-defined termination criterion, see `Runner::with_stopping_predicate`.
+pub type StoppingPredicate = dyn Fn(&Generation) -> bool + Send + Sync;
+
+/// Called whenever the run finds a new best individual, see `Runner::with_on_new_best`.
+pub type ImprovementCallback = dyn Fn(&Improvement) + Send + Sync;
+
+/// Called whenever the evaluation of an individual fails, see
+/// `Runner::with_on_evaluation_error`.
+pub type EvaluationErrorCallback = dyn Fn(&Solution, &EvaluationError) + Send + Sync;
+
+/// Computes the fitness of an individual whose evaluation failed, see
+/// `Runner::with_penalty_function`.
+pub type PenaltyFunction = dyn Fn(&Solution, &FailureKind) -> f64 + Send + Sync;
+
+/// Called whenever the population of an island degenerates, see
+/// `Runner::with_on_health_warning`.
+pub type HealthWarningCallback = dyn Fn(&HealthWarning) + Send + Sync;
+
+/// Runs the evolution of an island inside the threads of its evaluator, see
+/// `Runner::with_island_threads`.
+pub type IslandThreads = dyn for<'a> Fn(usize, usize, Box<dyn FnOnce() + Send + 'a>) + Send + Sync;
+
+/// For how many consecutive generations most offspring have to duplicate an individual before
+/// `HealthIssue::RepeatedDuplicates` is reported.
+pub const DUPLICATE_WARNING_GENERATIONS: usize = 5;
+
+/// A way in which the population of an island degenerated. Such a population mostly produces
+/// offspring the run already knows, so the evolution stalls without failing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HealthIssue {
+    /// Every individual is the same, at the precision of `RunnerConfig::dedup_precision` and
+    /// according to the identity of the initial population, see `Solutions::with_identity`.
+    /// As duplicated offspring are dropped, such a population usually shrank to a single
+    /// individual although `RunnerConfig::size_generation` asks for more.
+    IdenticalPopulation {
+        /// The size of the population.
+        n_individuals: usize,
+    },
+    /// Every evaluated individual has the same fitness, so selection can't tell them apart.
+    ZeroFitnessVariance {
+        /// The fitness of all individuals.
+        fitness: f64,
+        /// How many individuals were evaluated.
+        n_evaluated: usize,
+    },
+    /// At least half of the offspring duplicated an individual and were dropped, for
+    /// `DUPLICATE_WARNING_GENERATIONS` generations in a row.
+    RepeatedDuplicates {
+        /// How many generations in a row.
+        n_generations: usize,
+        /// How many offspring of the latest generation were dropped.
+        n_duplicates: usize,
+        /// How many offspring the latest generation produced.
+        n_offspring: usize,
+    },
+}
+
+/// A degenerated population, reported once when the issue arises. It is reported again if the
+/// population recovered in between.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthWarning {
+    /// The island whose population degenerated.
+    pub island: usize,
+    /// The generation of the island after which the issue was detected.
+    pub generation: usize,
+    /// What is wrong with the population.
+    pub issue: HealthIssue,
+}
+
+impl fmt::Display for HealthWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Island {} after generation {}: ",
+            self.island, self.generation
+        )?;
+        match &self.issue {
+            HealthIssue::IdenticalPopulation { n_individuals } => {
+                write!(
+                    f,
+                    "the {} individuals collapsed to a single distinct one",
+                    n_individuals
+                )
+            }
+            HealthIssue::ZeroFitnessVariance {
+                fitness,
+                n_evaluated,
+            } => write!(
+                f,
+                "all {} evaluated individuals have the fitness {}",
+                n_evaluated, fitness
+            ),
+            HealthIssue::RepeatedDuplicates {
+                n_generations,
+                n_duplicates,
+                n_offspring,
+            } => write!(
+                f,
+                "most offspring were duplicates for {} generations, {} of {} in the latest",
+                n_generations, n_duplicates, n_offspring
+            ),
+        }
+    }
+}
+
+/// The state of an island at the end of a run, see `Runner::with_island_states`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IslandState {
+    /// The individuals of the island in the order the island kept them, and their fitness as
+    /// maximized by the runner (see `RunnerConfig::direction`), `None` if left unevaluated.
+    pub population: Vec<(Solution, Option<f64>)>,
+    /// How many generations every individual of `population` survived, in the same order.
+    pub ages: Vec<usize>,
+    /// The index of the next generation of the island, counted from the start of the first
+    /// run it continues.
+    pub generation: usize,
+}
+
+/// The outcome of `Runner::run`.
+#[derive(Debug, Clone)]
+pub struct OptimizationResult {
+    /// The final population.
+    pub population: Solutions,
+    /// The fittest evaluated individual of the final population and its fitness.
+    pub best: Option<(Solution, f64)>,
+    /// One report per generation and island, only the latest `RunnerConfig::max_history` per
+    /// island if it is set.
+    pub generations: Vec<GenerationReport>,
+    /// How many generations were evolved, summed over all islands. Can be larger than the
+    /// number of reports if `RunnerConfig::max_history` dropped some.
+    pub n_evolved_generations: usize,
+    /// Statistics over all generations of all islands, including the ones whose reports were
+    /// dropped because of `RunnerConfig::max_history`.
+    pub statistics: RunStatistics,
+    /// All evaluations of the run. Empty unless `RunnerConfig::record_archive` is set.
+    pub archive: Archive,
+    /// Whether the stopping predicate, `RunnerConfig::target_value`, `RunnerConfig::patience`
+    /// or `RunnerConfig::max_evaluations` ended (any island of) the run before
+    /// `n_generations`.
+    pub stopped_early: bool,
+    /// Whether the run was ended early through the flag passed to `Runner::with_interrupt`.
+    pub interrupted: bool,
+    /// The fittest individuals of all islands and generations and when the best one improved.
+    pub hall_of_fame: HallOfFame,
+    /// How many evaluations of all islands and generations succeeded and why the others
+    /// failed, including the generations whose reports were dropped.
+    pub evaluations: EvaluationOutcomes,
+    /// How the operator settings of a spawned run were changed through its `RunHandle`, by
+    /// generation and island. `config` holds the settings the run started with.
+    pub reconfigurations: Vec<Reconfigured>,
+    /// How much `RunnerConfig::polish` improved the best individual, `None` without polish
+    /// or if the run was interrupted before it.
+    pub polish: Option<PolishReport>,
+    /// The states of the random number generators at the end of the run, see
+    /// `Runner::with_rng_state`.
+    pub rng_state: RunRngState,
+    /// The states of the islands at the end of the run, before `RunnerConfig::polish`, see
+    /// `Runner::with_island_states`.
+    pub islands: Vec<IslandState>,
+    /// The precision individuals were compared and deduplicated at, see
+    /// `RunnerConfig::dedup_precision`.
+    pub precision: Precision,
+    /// The config of the run. Its `seed` is the one the run was started from, also when the
+    /// runner drew it itself, and `None` if the runner was given its own RNG.
+    pub config: RunnerConfig,
+}
+
+impl OptimizationResult {
+    /// A TOML config replaying the run with `ga-fn run --config <file>`, including the seed
+    /// the run was started from. The replay is exact for runs started by `ga-fn run` that
+    /// don't depend on wall-clock time, i.e. without `generation_time_budget`; runs from
+    /// other initial populations or functions only share the settings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::config;
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let result = Runner::new(RunnerConfig::default()).run(
+    ///     Solutions::random(5, 1.0..10.0, 2),
+    ///     &Function::new(|x| Ok(x.iter().sum())),
+    /// );
+    /// let mut replayed = RunnerConfig::default();
+    /// config::apply_toml(&mut replayed, &result.to_repro()).unwrap();
+    /// assert_eq!(replayed, result.config);
+    /// assert!(replayed.seed.is_some());
+    /// ```
+    pub fn to_repro(&self) -> String {
+        format!(
+            "# Replay this run with `ga-fn run --config <this file>`.\n{}",
+            config::to_toml(&self.config)
+        )
+    }
+
+    /// The fittest evaluated individual of the final population and its function value, i.e.
+    /// `best` with its fitness turned back according to `RunnerConfig::direction`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{ObjectiveDirection, Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let result = Runner::new(RunnerConfig {
+    ///     direction: ObjectiveDirection::Minimize,
+    ///     ..RunnerConfig::default()
+    /// })
+    /// .run(
+    ///     Solutions::random(10, -5.0..5.0, 2),
+    ///     &Function::new(|x| Ok(x[0].powi(2) + x[1].powi(2))),
+    /// );
+    /// let (_, value) = result.best_value().unwrap();
+    /// assert!(value >= 0.0);
+    /// assert_eq!(value, -result.best.unwrap().1);
+    /// ```
+    pub fn best_value(&self) -> Option<(Solution, f64)> {
+        self.best
+            .as_ref()
+            .map(|(solution, fitness)| (solution.clone(), self.config.direction.value(*fitness)))
+    }
+
+    /// Fit a quadratic model of the fitness around the best individual to the `n_points`
+    /// archived individuals closest to it, see `quadratic::fit`. Every individual counts
+    /// with its evaluation at the highest fidelity. `None` without
+    /// `RunnerConfig::record_archive`, or if the archive doesn't determine the model.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_points` - How many archived individuals are used, at least
+    ///   `quadratic::n_coefficients` of the dimension.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    /// use rand::SeedableRng;
+    ///
+    /// let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+    /// let result = Runner::new(RunnerConfig {
+    ///     n_generations: 30,
+    ///     record_archive: true,
+    ///     seed: Some(1),
+    ///     ..RunnerConfig::default()
+    /// })
+    /// .run(
+    ///     Solutions::random_with_rng(20, -5.0..5.0, 2, &mut rng),
+    ///     &Function::new(|x| Ok(-(x[0] - 1.0).powi(2) - 3.0 * x[1].powi(2))),
+    /// );
+    /// let fit = result.quadratic_fit(30).unwrap();
+    /// let (optimum, _) = fit.optimum.unwrap();
+    /// assert!((optimum.get_arguments()[0] - 1.0).abs() < 1e-6);
+    /// assert!((fit.condition_number - 3.0).abs() < 1e-3);
+    /// ```
+    pub fn quadratic_fit(&self, n_points: usize) -> Option<QuadraticFit> {
+        let (best, _) = self.best.as_ref()?;
+        let points = self
+            .archive
+            .iter()
+            .filter_map(|(solution, evaluations)| {
+                evaluations
+                    .iter()
+                    .max_by(|a, b| a.fidelity.total_cmp(&b.fidelity))
+                    .map(|evaluation| (solution.clone(), evaluation.fitness))
+            })
+            .collect::<Vec<(Solution, f64)>>();
+        quadratic::fit(best, &points, n_points)
+    }
+
+    /// Compare this result with another one, e.g. of the same problem with a reformulated
+    /// objective or other settings. The deltas are those of `other` relative to this result,
+    /// and the `Display` implementation of the diff is a human readable summary.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The result to compare with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let function = Function::new(|x| Ok(-x[0].powi(2) - x[1].powi(2)));
+    /// let run = |n_generations| {
+    ///     Runner::new(RunnerConfig {
+    ///         n_generations,
+    ///         seed: Some(3),
+    ///         ..RunnerConfig::default()
+    ///     })
+    ///     .run(Solutions::random(10, -5.0..5.0, 2), &function)
+    /// };
+    /// let (short, long) = (run(5), run(20));
+    /// let diff = short.compare(&long);
+    /// assert_eq!(diff.generations, (5, 20));
+    /// assert_eq!(diff.argument_deltas.as_ref().map(Vec::len), Some(2));
+    /// assert!(diff
+    ///     .config_changes
+    ///     .contains(&("n_generations".to_string(), "5".to_string(), "20".to_string())));
+    /// println!("{}", diff);
+    /// ```
+    pub fn compare(&self, other: &OptimizationResult) -> ResultDiff {
+        let fitness =
+            |result: &OptimizationResult| result.best.as_ref().map(|(_, fitness)| *fitness);
+        let argument_deltas = match (&self.best, &other.best) {
+            (Some((best, _)), Some((other_best, _)))
+                if best.get_arguments().len() == other_best.get_arguments().len() =>
+            {
+                Some(
+                    best.get_arguments()
+                        .iter()
+                        .zip(other_best.get_arguments())
+                        .map(|(argument, other_argument)| other_argument - argument)
+                        .collect(),
+                )
+            }
+            _ => None,
+        };
+        let config_changes = config::FIELDS
+            .iter()
+            .filter_map(|field| {
+                let value = config::field_value(&self.config, field)?;
+                let other_value = config::field_value(&other.config, field)?;
+                (value != other_value).then(|| (field.to_string(), value, other_value))
+            })
+            .collect();
+        ResultDiff {
+            best_fitness: (fitness(self), fitness(other)),
+            fitness_delta: fitness(self)
+                .zip(fitness(other))
+                .map(|(fitness, other_fitness)| other_fitness - fitness),
+            argument_deltas,
+            generations: (self.n_evolved_generations, other.n_evolved_generations),
+            evaluations: (
+                self.evaluations.n_evaluations(),
+                other.evaluations.n_evaluations(),
+            ),
+            failed_evaluations: (self.evaluations.n_failed(), other.evaluations.n_failed()),
+            config_changes,
+        }
+    }
+}
+
+/// How two results differ, see `OptimizationResult::compare`. Pairs hold the value of the
+/// compared result first and the one of the other result second, deltas are those of the
+/// other result relative to the compared one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultDiff {
+    /// The fitness of the best individual of both results, if any.
+    pub best_fitness: (Option<f64>, Option<f64>),
+    /// How much fitter the best individual of the other result is, `None` unless both
+    /// results have one.
+    pub fitness_delta: Option<f64>,
+    /// How much every argument of the best individual of the other result differs, `None`
+    /// unless both results have one with the same number of arguments.
+    pub argument_deltas: Option<Vec<f64>>,
+    /// How many generations both results evolved, summed over all islands.
+    pub generations: (usize, usize),
+    /// How many evaluations both results made.
+    pub evaluations: (usize, usize),
+    /// How many evaluations of both results failed.
+    pub failed_evaluations: (usize, usize),
+    /// The config fields whose values differ, with the value of both results.
+    pub config_changes: Vec<(String, String, String)>,
+}
+
+impl fmt::Display for ResultDiff {
+    /// A human readable summary of the differences, one line per aspect.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let optional = |value: Option<f64>| value.map_or("none".to_string(), |v| v.to_string());
+        write!(
+            f,
+            "Best fitness: {} -> {}",
+            optional(self.best_fitness.0),
+            optional(self.best_fitness.1)
+        )?;
+        match self.fitness_delta {
+            Some(delta) => writeln!(f, " ({:+})", delta)?,
+            None => writeln!(f)?,
+        }
+        if let Some(deltas) = &self.argument_deltas {
+            let deltas = deltas
+                .iter()
+                .map(|delta| format!("{:+}", delta))
+                .collect::<Vec<String>>();
+            writeln!(f, "Argument deltas: [{}]", deltas.join(", "))?;
+        }
+        let delta = |(before, after): (usize, usize)| after as i64 - before as i64;
+        writeln!(
+            f,
+            "Generations: {} -> {} ({:+})",
+            self.generations.0,
+            self.generations.1,
+            delta(self.generations)
+        )?;
+        writeln!(
+            f,
+            "Evaluations: {} -> {} ({:+}), failed {} -> {}",
+            self.evaluations.0,
+            self.evaluations.1,
+            delta(self.evaluations),
+            self.failed_evaluations.0,
+            self.failed_evaluations.1
+        )?;
+        if self.config_changes.is_empty() {
+            write!(f, "Config: unchanged")
+        } else {
+            write!(f, "Config:")?;
+            for (field, value, other_value) in &self.config_changes {
+                write!(f, "\n  {}: {} -> {}", field, value, other_value)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Runs the genetic algorithm according to a `RunnerConfig`.
+pub struct Runner {
+    config: RunnerConfig,
+    /// The seed `rng` was created from, if the runner created it.
+    seed: Option<u64>,
+    /// The source of all randomness of the run. Islands are seeded from it.
+    rng: RunnerRng,
+    /// The generators the islands of a multi-threaded run continue from, see
+    /// `Runner::with_rng_state`.
+    island_rngs: Vec<RngState>,
+    /// Whether the run continues an earlier one, see `Runner::with_rng_state`.
+    continued: bool,
+    /// The islands continue from these states, see `Runner::with_island_states`.
+    island_states: Vec<IslandState>,
+    /// The reproduction step of every generation.
+    pipeline: Pipeline,
+    /// The pipelines an island switches to, in order, once it stagnated.
+    fallback_pipelines: Vec<Pipeline>,
+    /// After how many generations without improvement an island switches to the next
+    /// fallback pipeline.
+    stagnation_generations: usize,
+    /// Ends the evolution of an island once it returns `true`.
+    stopping_predicate: Option<Box<StoppingPredicate>>,
+    /// Ends the run after the current generation once it is set.
+    interrupt: Option<Arc<AtomicBool>>,
+    /// Called whenever the run finds a new best individual.
+    on_new_best: Option<Box<ImprovementCallback>>,
+    /// Called whenever the evaluation of an individual fails.
+    on_evaluation_error: Option<Box<EvaluationErrorCallback>>,
+    /// Computes the fitness of individuals whose evaluation failed.
+    penalty_function: Option<Box<PenaltyFunction>>,
+    /// Called whenever the population of an island degenerates.
+    on_health_warning: Option<Box<HealthWarningCallback>>,
+    /// Tells the time of time budgets and of `Improvement::elapsed`.
+    clock: Arc<dyn Clock>,
+    /// Shared with the `RunHandle` of a spawned run.
+    control: Option<Arc<RunControl>>,
+    /// Runs the islands of multi-threaded runs.
+    executor: Arc<dyn Executor>,
+    /// Runs the evolution of every island inside the threads of its evaluator.
+    island_threads: Option<Box<IslandThreads>>,
+}
+
+/// The random number generator of a `Runner`. Only the state of a generator the runner
+/// created itself can be captured into a `RunRngState`.
+enum RunnerRng {
+    /// Created by `Runner::new` or restored by `Runner::with_rng_state`.
+    Seeded(Box<ChaCha12Rng>),
+    /// Given to `Runner::with_rng`.
+    Custom(Box<dyn RngCore + Send>),
+}
+
+impl RunnerRng {
+    /// The state of the generator, `None` if it was given to `Runner::with_rng`.
+    fn state(&self) -> Option<RngState> {
+        match self {
+            RunnerRng::Seeded(rng) => Some(RngState::capture(rng)),
+            RunnerRng::Custom(_) => None,
+        }
+    }
+}
+
+impl RngCore for RunnerRng {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            RunnerRng::Seeded(rng) => rng.next_u32(),
+            RunnerRng::Custom(rng) => rng.next_u32(),
+        }
+    }
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            RunnerRng::Seeded(rng) => rng.next_u64(),
+            RunnerRng::Custom(rng) => rng.next_u64(),
+        }
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            RunnerRng::Seeded(rng) => rng.fill_bytes(dest),
+            RunnerRng::Custom(rng) => rng.fill_bytes(dest),
+        }
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        match self {
+            RunnerRng::Seeded(rng) => rng.try_fill_bytes(dest),
+            RunnerRng::Custom(rng) => rng.try_fill_bytes(dest),
+        }
+    }
+}
+
+impl fmt::Debug for Runner {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("Runner")
+            .field("config", &self.config)
+            .field("pipeline", &self.pipeline)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Prepare a candidate for `compare_fitness`, a missing fitness counts as `NaN`.
+///
+/// # Arguments
+///
+/// * `candidate` - The candidate and its fitness, if it was evaluated.
+fn ranked((solution, fitness): &Candidate) -> (&Solution, f64) {
+    (solution, fitness.unwrap_or(f64::NAN))
+}
+
+/// Passes the failed evaluations of an evaluator to the callback of
+/// `Runner::with_on_evaluation_error`, if there is one.
+struct ObservedEvaluator<'a, E: ?Sized> {
+    /// The evaluator whose failures are observed.
+    evaluator: &'a E,
+    /// Called for every failed evaluation.
+    on_error: Option<&'a EvaluationErrorCallback>,
+}
+
+impl<E> ObservedEvaluator<'_, E>
+where
+    E: Evaluator + ?Sized,
+{
+    /// Evaluate a chunk with `evaluate` and report its failures. A panic is reported for every
+    /// individual of the chunk and then resumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - The solutions that should be evaluated.
+    /// * `evaluate` - Evaluates the chunk with the observed evaluator.
+    fn observe<F>(&self, chunk: &[Solution], evaluate: F) -> Vec<Result<f64, FunctionError>>
+    where
+        F: FnOnce() -> Vec<Result<f64, FunctionError>>,
+    {
+        let Some(on_error) = self.on_error else {
+            return evaluate();
+        };
+        let values = match panic::catch_unwind(AssertUnwindSafe(evaluate)) {
+            Ok(values) => values,
+            Err(payload) => {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .map(|message| message.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned());
+                let error = EvaluationError::Panic(message);
+                for solution in chunk {
+                    on_error(solution, &error);
+                }
+                panic::resume_unwind(payload)
+            }
+        };
+        for (solution, value) in chunk.iter().zip(&values) {
+            match value {
+                Ok(value) if value.is_finite() => {}
+                Ok(value) => on_error(solution, &EvaluationError::NonFinite(*value)),
+                Err(error) => on_error(solution, &EvaluationError::Function(error.clone())),
+            }
+        }
+        values
+    }
+}
+
+impl<E> Evaluator for ObservedEvaluator<'_, E>
+where
+    E: Evaluator + ?Sized,
+{
+    fn evaluate_chunk(&self, chunk: &[Solution]) -> Vec<Result<f64, FunctionError>> {
+        self.observe(chunk, || self.evaluator.evaluate_chunk(chunk))
+    }
+    fn evaluate_chunk_at_fidelity(
+        &self,
+        chunk: &[Solution],
+        fidelity: f64,
+    ) -> Vec<Result<f64, FunctionError>> {
+        self.observe(chunk, || {
+            self.evaluator.evaluate_chunk_at_fidelity(chunk, fidelity)
+        })
+    }
+}
+
+/// Turns the function values of an evaluator into fitness according to
+/// `RunnerConfig::direction`, so the runner only ever maximizes.
+struct DirectedEvaluator<'a, E: ?Sized> {
+    /// The evaluator computing the function values.
+    evaluator: &'a E,
+    /// Whether the function values are maximized or minimized.
+    direction: ObjectiveDirection,
+}
+
+impl<E> DirectedEvaluator<'_, E>
+where
+    E: Evaluator + ?Sized,
+{
+    /// Turn function values into fitness.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The function values computed by the evaluator.
+    fn directed(&self, values: Vec<Result<f64, FunctionError>>) -> Vec<Result<f64, FunctionError>> {
+        if self.direction == ObjectiveDirection::Maximize {
+            return values;
+        }
+        values
+            .into_iter()
+            .map(|value| value.map(|value| self.direction.fitness(value)))
+            .collect()
+    }
+}
+
+impl<E> Evaluator for DirectedEvaluator<'_, E>
+where
+    E: Evaluator + ?Sized,
+{
+    fn evaluate_chunk(&self, chunk: &[Solution]) -> Vec<Result<f64, FunctionError>> {
+        self.directed(self.evaluator.evaluate_chunk(chunk))
+    }
+    fn evaluate_chunk_at_fidelity(
+        &self,
+        chunk: &[Solution],
+        fidelity: f64,
+    ) -> Vec<Result<f64, FunctionError>> {
+        self.directed(self.evaluator.evaluate_chunk_at_fidelity(chunk, fidelity))
+    }
+}
+
+/// The point in time after which no further chunk of a generation is evaluated.
+#[derive(Clone, Copy)]
+struct Deadline<'a> {
+    /// Tells the current time.
+    clock: &'a dyn Clock,
+    /// When the deadline passes.
+    at: Instant,
+}
+
+impl Deadline<'_> {
+    /// Return whether the deadline has passed.
+    fn passed(&self) -> bool {
+        self.clock.now() >= self.at
+    }
+}
+
+/// Compute the fitness of all unevaluated candidates in chunks of
+/// `RunnerConfig::evaluation_chunk_size`, stopping once `deadline` is reached. Failed
+/// evaluations get the fitness computed by `penalty` or `RunnerConfig::failure_penalty`, or
+/// panic without either. Returns whether the deadline was exceeded and the outcomes of the
+/// evaluations.
+///
+/// # Arguments
+///
+/// * `candidates` - The candidates to evaluate.
+/// * `evaluator` - The evaluator to compute the fitness with.
+/// * `config` - The settings of the run.
+/// * `penalty` - Computes the fitness of failed candidates, if anything.
+/// * `deadline` - The point in time after which no further chunk should be evaluated.
+/// * `fidelity` - The fidelity to evaluate at, `None` evaluates exactly.
+/// * `archive` - Where every evaluation is recorded, if anywhere.
+/// * `history` - The recent exact evaluations the fitness is averaged over, if any.
+#[allow(clippy::too_many_arguments)]
+fn evaluate<E>(
+    candidates: &mut [Candidate],
+    evaluator: &E,
+    config: &RunnerConfig,
+    penalty: Option<&PenaltyFunction>,
+    deadline: Option<Deadline>,
+    fidelity: Option<f64>,
+    mut archive: Option<&mut Archive>,
+    mut history: Option<&mut FitnessHistory>,
+) -> (bool, EvaluationOutcomes)
+where
+    E: Evaluator + ?Sized,
+{
+    let penalty_of = |solution: &Solution, kind: FailureKind| match penalty {
+        Some(penalty) => Some(penalty(solution, &kind)),
+        None => config.failure_penalty,
+    };
+    let mut outcomes = EvaluationOutcomes::default();
+    let mut unevaluated = candidates
+        .iter_mut()
+        .filter(|(_, fitness)| fitness.is_none())
+        .collect::<Vec<&mut Candidate>>();
+    for chunk in unevaluated.chunks_mut(config.evaluation_chunk_size.max(1)) {
+        if deadline.is_some_and(|deadline| deadline.passed()) {
+            return (true, outcomes);
+        }
+        let solutions = chunk
+            .iter()
+            .map(|(solution, _)| solution.clone())
+            .collect::<Vec<Solution>>();
+        let values = panic::catch_unwind(AssertUnwindSafe(|| match fidelity {
+            Some(fidelity) => evaluator.evaluate_chunk_at_fidelity(&solutions, fidelity),
+            None => evaluator.evaluate_chunk(&solutions),
+        }));
+        let values = match values {
+            Ok(values) => values,
+            Err(payload) if penalty.is_none() && config.failure_penalty.is_none() => {
+                panic::resume_unwind(payload)
+            }
+            Err(_) => {
+                for (solution, fitness) in chunk.iter_mut() {
+                    outcomes.record(Some(FailureKind::Panic));
+                    *fitness = penalty_of(solution, FailureKind::Panic);
+                }
+                continue;
+            }
+        };
+        // Without one value per solution, it is unknown which value belongs to which solution.
+        let values = if values.len() == chunk.len() {
+            values
+        } else {
+            let error = FunctionError::ExternalEvaluation(format!(
+                "The evaluator returned {} fitnesses for {} solutions",
+                values.len(),
+                chunk.len()
+            ));
+            vec![Err(error); chunk.len()]
+        };
+        for ((solution, fitness), value) in chunk.iter_mut().zip(values) {
+            let failure = match &value {
+                Ok(value) if value.is_finite() => None,
+                Ok(_) => Some(FailureKind::NonFinite),
+                Err(error) => Some(FailureKind::of(error)),
+            };
+            outcomes.record(failure);
+            let value = match (value, failure.and_then(|kind| penalty_of(solution, kind))) {
+                (_, Some(penalty)) => {
+                    *fitness = Some(penalty);
+                    continue;
+                }
+                (Ok(value), None) => value,
+                (Err(error), None) => {
+                    panic!("Could not compute the fitness of {}: {}", solution, error)
+                }
+            };
+            if let Some(archive) = archive.as_deref_mut() {
+                archive.record(
+                    solution.clone(),
+                    Evaluation {
+                        fidelity: fidelity.unwrap_or(1.0),
+                        fitness: value,
+                    },
+                );
+            }
+            *fitness = Some(match history.as_deref_mut() {
+                Some(history) if fidelity.unwrap_or(1.0) >= 1.0 => history.record(solution, value),
+                _ => value,
+            });
+        }
+    }
+    (false, outcomes)
+}
+
+/// Evaluate the unevaluated candidates like `evaluate`, in batches of `batch_size`, and sort
+/// every evaluated batch while the next one is evaluated. Returns the candidates in the order
+/// of `select`, whether the deadline was exceeded and the outcomes of the evaluations.
+///
+/// # Arguments
+///
+/// * `candidates` - The candidates to evaluate.
+/// * `evaluator` - The evaluator to compute the fitness with.
+/// * `batch_size` - How many candidates are evaluated before they are sorted, `0` is treated
+///   as `1`.
+/// * `executor` - Runs the evaluation and the sorting, side by side if it runs tasks on threads.
+/// * `config` - The settings of the run.
+/// * `penalty` - Computes the fitness of failed candidates, if anything.
+/// * `deadline` - The point in time after which no further chunk should be evaluated.
+/// * `archive` - Where every evaluation is recorded, if anywhere.
+/// * `history` - The recent exact evaluations the fitness is averaged over, if any.
+#[allow(clippy::too_many_arguments)]
+fn evaluate_and_sort<E>(
+    candidates: Vec<Candidate>,
+    evaluator: &E,
+    batch_size: usize,
+    executor: &dyn Executor,
+    config: &RunnerConfig,
+    penalty: Option<&PenaltyFunction>,
+    deadline: Option<Deadline>,
+    mut archive: Option<&mut Archive>,
+    mut history: Option<&mut FitnessHistory>,
+) -> (Vec<Candidate>, bool, EvaluationOutcomes)
+where
+    E: Evaluator + ?Sized,
+{
+    let (sender, receiver) = mpsc::channel::<Vec<Candidate>>();
+    let mut deadline_exceeded = false;
+    let mut outcomes = EvaluationOutcomes::default();
+    let mut sorted_batches = Vec::new();
+    let evaluation = || {
+        let mut candidates = candidates.into_iter().peekable();
+        while candidates.peek().is_some() {
+            let mut batch = candidates
+                .by_ref()
+                .take(batch_size.max(1))
+                .collect::<Vec<Candidate>>();
+            let (batch_deadline_exceeded, batch_outcomes) = evaluate(
+                &mut batch,
+                evaluator,
+                config,
+                penalty,
+                deadline,
+                None,
+                archive.as_deref_mut(),
+                history.as_deref_mut(),
+            );
+            deadline_exceeded |= batch_deadline_exceeded;
+            outcomes.merge(&batch_outcomes);
+            // Only fails if the sorting panicked, which the executor resumes.
+            if sender.send(batch).is_err() {
+                break;
+            }
+        }
+        // Ends the sorting once it sorted the last batch.
+        drop(sender);
+    };
+    let sorting = || {
+        sorted_batches.extend(receiver.into_iter().map(|batch| select(batch, usize::MAX)));
+    };
+    executor.execute(vec![Box::new(evaluation), Box::new(sorting)]);
+    (merge_selected(sorted_batches), deadline_exceeded, outcomes)
+}
+
+/// Merge batches of candidates that were each ordered by `select` into the order `select`
+/// gives all of them.
+///
+/// # Arguments
+///
+/// * `batches` - The ordered batches, in the order of the candidates they were taken from.
+fn merge_selected(batches: Vec<Vec<Candidate>>) -> Vec<Candidate> {
+    // The unevaluated candidates trail every batch and keep their order.
+    let mut unevaluated = Vec::new();
+    let mut runs = batches
+        .into_iter()
+        .map(|mut batch| {
+            let n_evaluated = batch
+                .iter()
+                .take_while(|(_, fitness)| fitness.is_some())
+                .count();
+            unevaluated.extend(batch.split_off(n_evaluated));
+            batch
+        })
+        .collect::<Vec<Vec<Candidate>>>();
+    while runs.len() > 1 {
+        let mut pairs = runs.into_iter();
+        runs = Vec::new();
+        while let Some(run_a) = pairs.next() {
+            let Some(run_b) = pairs.next() else {
+                runs.push(run_a);
+                break;
+            };
+            let mut merged = Vec::with_capacity(run_a.len() + run_b.len());
+            let mut run_a = run_a.into_iter().peekable();
+            let mut run_b = run_b.into_iter().peekable();
+            while let (Some(candidate_a), Some(candidate_b)) = (run_a.peek(), run_b.peek()) {
+                let next = match compare_fitness(ranked(candidate_a), ranked(candidate_b)) {
+                    Ordering::Greater => run_b.next(),
+                    _ => run_a.next(),
+                };
+                merged.extend(next);
+            }
+            merged.extend(run_a.chain(run_b));
+            runs.push(merged);
+        }
+    }
+    let mut merged = runs.pop().unwrap_or_default();
+    merged.extend(unevaluated);
+    merged
+}
+
+/// Race the unevaluated candidates through the rungs of successive halving. Returns the
+/// remaining candidates, the number of candidates evaluated at each rung, whether the
+/// deadline was exceeded and the outcomes of the evaluations. Candidates cut short by the deadline before the last rung are left
+/// unevaluated, as a low fidelity estimate is no fitness.
+///
+/// # Arguments
+///
+/// * `candidates` - The candidates to evaluate.
+/// * `evaluator` - The evaluator to compute the fitness with.
+/// * `halving` - The rungs and how many candidates each of them keeps.
+/// * `config` - The settings of the run, a rung keeps at least `size_generation` candidates.
+/// * `penalty` - Computes the fitness of failed candidates, if anything.
+/// * `deadline` - The point in time after which no further chunk should be evaluated.
+/// * `archive` - Where every evaluation is recorded, if anywhere.
+/// * `history` - The recent exact evaluations the fitness is averaged over, if any.
+#[allow(clippy::too_many_arguments)]
+fn evaluate_successive_halving<E>(
+    candidates: Vec<Candidate>,
+    evaluator: &E,
+    halving: &SuccessiveHalving,
+    config: &RunnerConfig,
+    penalty: Option<&PenaltyFunction>,
+    deadline: Option<Deadline>,
+    mut archive: Option<&mut Archive>,
+    mut history: Option<&mut FitnessHistory>,
+) -> (Vec<Candidate>, Vec<usize>, bool, EvaluationOutcomes)
+where
+    E: Evaluator + ?Sized,
+{
+    let (mut evaluated, mut racing): (Vec<Candidate>, Vec<Candidate>) = candidates
+        .into_iter()
+        .partition(|(_, fitness)| fitness.is_some());
+    let mut rung_sizes = Vec::new();
+    let mut deadline_exceeded = false;
+    let mut outcomes = EvaluationOutcomes::default();
+    for (rung, fidelity) in halving.fidelities.iter().enumerate() {
+        if racing.is_empty() {
+            break;
+        }
+        rung_sizes.push(racing.len());
+        let rung_outcomes;
+        (deadline_exceeded, rung_outcomes) = evaluate(
+            &mut racing,
+            evaluator,
+            config,
+            penalty,
+            deadline,
+            Some(*fidelity),
+            archive.as_deref_mut(),
+            history.as_deref_mut(),
+        );
+        outcomes.merge(&rung_outcomes);
+        if rung + 1 == halving.fidelities.len() {
+            break;
+        }
+        if !deadline_exceeded {
+            let n_survivors = racing
+                .len()
+                .div_ceil(halving.reduction_factor.max(1))
+                .max(config.size_generation);
+            racing = select(racing, n_survivors);
+        }
+        for (_, fitness) in racing.iter_mut() {
+            *fitness = None;
+        }
+        if deadline_exceeded {
+            break;
+        }
+    }
+    evaluated.extend(racing);
+    (evaluated, rung_sizes, deadline_exceeded, outcomes)
+}
+
+/// Keep the `n` fittest candidates. If fewer than `n` candidates have been evaluated, the
+/// remaining places are filled with unevaluated candidates.
+///
+/// # Arguments
+///
+/// * `candidates` - The candidates to select from.
+/// * `n` - How many candidates should be kept.
+fn select(candidates: Vec<Candidate>, n: usize) -> Vec<Candidate> {
+    let (mut evaluated, unevaluated): (Vec<Candidate>, Vec<Candidate>) = candidates
+        .into_iter()
+        .partition(|(_, fitness)| fitness.is_some());
+    evaluated.sort_by(|candidate_a, candidate_b| {
+        compare_fitness(ranked(candidate_a), ranked(candidate_b))
+    });
+    evaluated.into_iter().chain(unevaluated).take(n).collect()
+}
+
+/// Clamp offspring into `bounds`, if any.
+///
+/// # Arguments
+///
+/// * `offspring` - The offspring to confine.
+/// * `bounds` - The bounds of every argument, arguments beyond its end are left alone.
+fn confine(offspring: Vec<Solution>, bounds: Option<&[Range<f64>]>) -> Vec<Solution> {
+    let Some(bounds) = bounds else {
+        return offspring;
+    };
+    offspring
+        .into_iter()
+        .map(|child| {
+            let mut arguments = child.get_arguments();
+            for (argument, bound) in arguments.iter_mut().zip(bounds) {
+                *argument = argument.max(bound.start).min(bound.end);
+            }
+            child.with_arguments(arguments)
+        })
+        .collect()
+}
+
+/// Split the `n` fittest evaluated candidates, the elites, from the others.
+///
+/// # Arguments
+///
+/// * `candidates` - The candidates to split.
+/// * `n` - How many elites there are at most.
+fn split_elites(candidates: Vec<Candidate>, n: usize) -> (Vec<Candidate>, Vec<Candidate>) {
+    if n == 0 {
+        return (Vec::new(), candidates);
+    }
+    let mut elites = select(candidates, usize::MAX);
+    let n = elites
+        .iter()
+        .take(n)
+        .take_while(|(_, fitness)| fitness.is_some())
+        .count();
+    let others = elites.split_off(n);
+    (elites, others)
+}
+
+/// Keep `n` candidates according to `selection`. If fewer than `n` candidates have been
+/// evaluated, the remaining places are filled with unevaluated candidates.
+///
+/// # Arguments
+///
+/// * `candidates` - The candidates to select from.
+/// * `n` - How many candidates should be kept.
+/// * `selection` - How the candidates are chosen.
+/// * `rng` - The random number generator of randomized selections.
+fn select_survivors<G>(
+    candidates: Vec<Candidate>,
+    n: usize,
+    selection: &SurvivorSelection,
+    rng: &mut G,
+) -> Vec<Candidate>
+where
+    G: RngCore,
+{
+    let weights = match selection {
+        SurvivorSelection::Truncation => return select(candidates, n),
+        // Ordered from the fittest to the least fit survivor like the other selections.
+        SurvivorSelection::Tournament(tournament) => {
+            return select(tournament.select(candidates, n, rng), n)
+        }
+        SurvivorSelection::Stratified(weights) => weights
+            .iter()
+            .map(|weight| weight.max(0.0))
+            .collect::<Vec<f64>>(),
+    };
+    let total_weight = weights.iter().sum::<f64>();
+    // Ordered from the fittest to the least fit one, the unevaluated ones last.
+    let sorted = select(candidates, usize::MAX);
+    let n_evaluated = sorted
+        .iter()
+        .take_while(|(_, fitness)| fitness.is_some())
+        .count();
+    if n_evaluated <= n || total_weight <= 0.0 || !total_weight.is_finite() {
+        return sorted.into_iter().take(n).collect();
+    }
+    // The quotas of the quantiles, rounded with the largest remainder method.
+    let shares = weights
+        .iter()
+        .map(|weight| n as f64 * weight / total_weight)
+        .collect::<Vec<f64>>();
+    let mut quotas = shares
+        .iter()
+        .map(|share| share.floor() as usize)
+        .collect::<Vec<usize>>();
+    let mut by_remainder = (0..weights.len()).collect::<Vec<usize>>();
+    by_remainder.sort_by(|a, b| {
+        (shares[*b] - shares[*b].floor())
+            .total_cmp(&(shares[*a] - shares[*a].floor()))
+            .then(a.cmp(b))
+    });
+    for index in by_remainder
+        .into_iter()
+        .take(n - quotas.iter().sum::<usize>())
+    {
+        quotas[index] += 1;
+    }
+    let mut keep = vec![false; sorted.len()];
+    for (quantile, quota) in quotas.into_iter().enumerate() {
+        let start = quantile * n_evaluated / weights.len();
+        let end = (quantile + 1) * n_evaluated / weights.len();
+        keep[start..end]
+            .iter_mut()
+            .take(quota)
+            .for_each(|kept| *kept = true);
+    }
+    // Quantiles smaller than their quota leave places, which go to the fittest of the rest.
+    let n_missing = n - keep.iter().filter(|kept| **kept).count();
+    keep.iter_mut()
+        .filter(|kept| !**kept)
+        .take(n_missing)
+        .for_each(|kept| *kept = true);
+    sorted
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(candidate, kept)| kept.then_some(candidate))
+        .collect()
+}
+
+/// Return the squared Euclidean distance between the arguments of two solutions.
+///
+/// # Arguments
+///
+/// * `solution_a` - The first solution.
+/// * `solution_b` - The second solution.
+fn squared_distance(solution_a: &Solution, solution_b: &Solution) -> f64 {
+    solution_a
+        .get_arguments()
+        .iter()
+        .zip(solution_b.get_arguments().iter())
+        .map(|(a, b)| (a - b) * (a - b))
+        .sum()
+}
+
+/// Keep the `n` offspring that are evaluated first according to `priority`.
+///
+/// # Arguments
+///
+/// * `offspring` - The offspring in the order the pipeline produced them.
+/// * `population` - The current individuals and their fitness.
+/// * `n` - How many offspring to keep.
+/// * `priority` - Which offspring to keep.
+/// * `rng` - The random number generator used by `OffspringPriority::Random`.
+fn prioritize<G>(
+    mut offspring: Vec<Solution>,
+    population: &[Candidate],
+    n: usize,
+    priority: OffspringPriority,
+    rng: &mut G,
+) -> Vec<Solution>
+where
+    G: RngCore + ?Sized,
+{
+    if offspring.len() <= n {
+        return offspring;
+    }
+    // Offspring with the lowest score come first, the sort is stable.
+    let mut score_by = |score: &dyn Fn(&Solution) -> f64| {
+        let mut scored = offspring
+            .drain(..)
+            .map(|child| (score(&child), child))
+            .collect::<Vec<(f64, Solution)>>();
+        scored.sort_by(|(score_a, _), (score_b, _)| score_a.total_cmp(score_b));
+        scored.into_iter().map(|(_, child)| child).collect()
+    };
+    offspring = match priority {
+        OffspringPriority::Produced => offspring,
+        OffspringPriority::Random => {
+            offspring.shuffle(rng);
+            offspring
+        }
+        OffspringPriority::Novelty => score_by(&|child| {
+            -population
+                .iter()
+                .map(|(solution, _)| squared_distance(solution, child))
+                .fold(f64::INFINITY, f64::min)
+        }),
+        OffspringPriority::NearBest => match fittest_ref(population) {
+            Some((best, _)) => score_by(&|child| squared_distance(best, child)),
+            None => offspring,
+        },
+    };
+    offspring.truncate(n);
+    offspring
+}
+
+/// Insert an evaluated offspring into a population according to `policy`. The replaced
+/// individual is removed and the offspring appended, so the population stays ordered from the
+/// oldest to the newest individual.
+///
+/// # Arguments
+///
+/// * `population` - The population to insert into.
+/// * `child` - The evaluated offspring.
+/// * `policy` - Which individual the offspring replaces.
+/// * `accept_worse` - Whether an offspring that is less fit by the given loss than its target
+///   replaces it anyway.
+fn replace(
+    population: &mut Vec<Candidate>,
+    child: Candidate,
+    policy: ReplacementPolicy,
+    accept_worse: &mut dyn FnMut(f64) -> bool,
+) {
+    let squared_distance = |solution: &Solution| squared_distance(solution, &child.0);
+    // `min_by` returns the first of several equal elements, i.e. the oldest one.
+    let target = match policy {
+        ReplacementPolicy::ReplaceOldest => (!population.is_empty()).then_some(0),
+        ReplacementPolicy::ReplaceWorst => (0..population.len())
+            .min_by(|a, b| compare_fitness(ranked(&population[*b]), ranked(&population[*a]))),
+        ReplacementPolicy::ReplaceMostSimilar => (0..population.len()).min_by(|a, b| {
+            squared_distance(&population[*a].0).total_cmp(&squared_distance(&population[*b].0))
+        }),
+    };
+    match target {
+        None => population.push(child),
+        Some(index) => {
+            let replaces = policy == ReplacementPolicy::ReplaceOldest
+                || compare_fitness(ranked(&child), ranked(&population[index])) == Ordering::Less
+                || accept_worse(ranked(&population[index]).1 - ranked(&child).1);
+            if replaces {
+                population.remove(index);
+                population.push(child);
+            }
+        }
+    }
+}
+
+/// Let the evaluated candidates that didn't survive replace survivors with the Metropolis
+/// probability of `annealing`, the fittest rejected candidate competing with the least fit
+/// survivor and so on. The fittest survivor is kept. Returns how many candidates were accepted.
+///
+/// # Arguments
+///
+/// * `survivors` - The selected survivors.
+/// * `candidates` - All candidates the survivors were selected from.
+/// * `annealing` - The temperature schedule.
+/// * `generation` - The index of the generation within its island.
+/// * `rng` - The random number generator to draw from.
+fn accept_worse<G>(
+    survivors: &mut [Candidate],
+    candidates: Vec<Candidate>,
+    annealing: Annealing,
+    generation: usize,
+    rng: &mut G,
+) -> usize
+where
+    G: RngCore + ?Sized,
+{
+    let surviving = survivors
+        .iter()
+        .map(|(solution, _)| solution.clone())
+        .collect::<HashSet<Solution>>();
+    let mut rejected = candidates
+        .into_iter()
+        .filter(|(solution, fitness)| fitness.is_some() && !surviving.contains(solution))
+        .collect::<Vec<Candidate>>();
+    rejected.sort_by(|a, b| compare_fitness(ranked(a), ranked(b)));
+    let mut targets = (0..survivors.len()).collect::<Vec<usize>>();
+    targets.sort_by(|a, b| compare_fitness(ranked(&survivors[*b]), ranked(&survivors[*a])));
+    // The last target is the fittest survivor.
+    targets.pop();
+    let mut n_accepted = 0;
+    for (candidate, target) in rejected.into_iter().zip(targets) {
+        let loss = ranked(&survivors[target]).1 - ranked(&candidate).1;
+        if annealing.accepts(loss, generation, rng) {
+            survivors[target] = candidate;
+            n_accepted += 1;
+        }
+    }
+    n_accepted
+}
+
+/// Return the fittest evaluated candidate.
+///
+/// # Arguments
+///
+/// * `candidates` - The candidates to search.
+pub(crate) fn fittest(candidates: &[Candidate]) -> Option<(Solution, f64)> {
+    fittest_ref(candidates).map(|(solution, fitness)| (solution.clone(), fitness))
+}
+
+/// Return the fittest evaluated candidate like `fittest`, borrowed instead of cloned.
+///
+/// # Arguments
+///
+/// * `candidates` - The candidates to search.
+fn fittest_ref(candidates: &[Candidate]) -> Option<(&Solution, f64)> {
+    candidates
+        .iter()
+        .filter_map(|(solution, fitness)| fitness.map(|fitness| (solution, fitness)))
+        .min_by(|candidate_a, candidate_b| compare_fitness(*candidate_a, *candidate_b))
+}
+
+/// Run the evolution of an island inside `island_threads`, or directly without them.
+///
+/// # Arguments
+///
+/// * `island_threads` - Runs the evolution inside the threads of the evaluator.
+/// * `island` - The index of the island.
+/// * `n_threads` - How many threads the evaluator of the island may use.
+/// * `evolve` - The evolution of the island.
+///
+/// # Panics
+///
+/// If `island_threads` returns without running the evolution.
+fn run_on_island_threads<F>(
+    island_threads: Option<&IslandThreads>,
+    island: usize,
+    n_threads: usize,
+    evolve: F,
+) where
+    F: FnOnce() + Send,
+{
+    let Some(island_threads) = island_threads else {
+        return evolve();
+    };
+    let evolved = AtomicBool::new(false);
+    island_threads(
+        island,
+        n_threads,
+        Box::new(|| {
+            evolve();
+            evolved.store(true, AtomicOrdering::SeqCst);
+        }),
+    );
+    assert!(
+        evolved.load(AtomicOrdering::SeqCst),
+        "The threads of island {} didn't run its evolution",
+        island
+    );
+}
+
+impl Runner {
+    /// Create a new runner.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The settings the genetic algorithm should run with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    ///
+    /// let runner = Runner::new(RunnerConfig::default());
+    /// ```
+    pub fn new(config: RunnerConfig) -> Self {
+        // Unseeded runs draw their seed so they can still be replayed, see
+        // `OptimizationResult::to_repro`.
+        let seed = config.seed.unwrap_or_else(rand::random);
+        Runner {
+            seed: Some(seed),
+            ..Runner::with_runner_rng(
+                config,
+                RunnerRng::Seeded(Box::new(ChaCha12Rng::seed_from_u64(seed))),
+            )
+        }
+    }
+    /// Create a new runner that draws all its random numbers from `rng`. Single-threaded runs
+    /// use `rng` directly, every island of a multi-threaded run is seeded from it.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The settings the genetic algorithm should run with.
+    /// * `rng` - The random number generator used by all stochastic operators.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    ///
+    /// let runner = Runner::with_rng(RunnerConfig::default(), rand::rngs::OsRng);
+    /// ```
+    pub fn with_rng<R>(config: RunnerConfig, rng: R) -> Self
+    where
+        R: RngCore + Send + 'static,
+    {
+        Runner::with_runner_rng(config, RunnerRng::Custom(Box::new(rng)))
+    }
+    /// Create a new runner that draws all its random numbers from `rng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The settings the genetic algorithm should run with.
+    /// * `rng` - The random number generator used by all stochastic operators.
+    fn with_runner_rng(config: RunnerConfig, rng: RunnerRng) -> Self {
+        Runner {
+            config,
+            seed: None,
+            rng,
+            island_rngs: Vec::new(),
+            continued: false,
+            island_states: Vec::new(),
+            pipeline: Pipeline::default(),
+            fallback_pipelines: Vec::new(),
+            stagnation_generations: 0,
+            stopping_predicate: None,
+            interrupt: None,
+            on_new_best: None,
+            on_evaluation_error: None,
+            penalty_function: None,
+            on_health_warning: None,
+            clock: Arc::new(SystemClock),
+            control: None,
+            executor: Arc::new(ScopedThreads),
+            island_threads: None,
+        }
+    }
+    /// Replace the reproduction pipeline, e.g. to plug in a custom crossover or repair stage.
+    ///
+    /// # Arguments
+    ///
+    /// * `pipeline` - The pipeline creating the offspring of every generation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::Pipeline;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let runner = Runner::new(RunnerConfig::default()).with_pipeline(
+    ///     Pipeline::default()
+    ///         .with_filter(|child: &Solution| child.get_arguments().iter().all(|x| x.is_finite())),
+    /// );
+    /// ```
+    pub fn with_pipeline(mut self, pipeline: Pipeline) -> Self {
+        self.pipeline = pipeline;
+        self
+    }
+    /// Declare the bounds of the search space. Unless the pipeline has a custom mutation
+    /// stage, the mutation step size then scales with the width of the bounds instead of the
+    /// current value, see `Pipeline::with_bounds`. Call it after `with_pipeline`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bounds` - The bounds of every argument and how offspring are kept within them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::bounds::{BoundaryPolicy, Bounds};
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    ///
+    /// let runner = Runner::new(RunnerConfig::default()).with_bounds(vec![-5.0..5.0; 3]);
+    /// let reflecting = Runner::new(RunnerConfig::default())
+    ///     .with_bounds(Bounds::new(vec![-5.0..5.0; 3]).with_policy(BoundaryPolicy::Reflect));
+    /// ```
+    pub fn with_bounds<B>(mut self, bounds: B) -> Self
+    where
+        B: Into<Bounds>,
+    {
+        let bounds = bounds.into();
+        self.fallback_pipelines = std::mem::take(&mut self.fallback_pipelines)
+            .into_iter()
+            .map(|pipeline| pipeline.with_bounds(bounds.clone()))
+            .collect();
+        self.pipeline = self.pipeline.with_bounds(bounds);
+        self
+    }
+    /// Switch an island to the next pipeline of `fallbacks` once its best fitness didn't
+    /// improve for `stagnation_generations` generations, e.g. from exploiting to exploring
+    /// operators. After the last fallback the island keeps it. The pipeline every generation
+    /// used is recorded in `GenerationReport::pipeline`.
+    ///
+    /// # Arguments
+    ///
+    /// * `stagnation_generations` - After how many generations without improvement an island
+    ///   switches. `0` is treated as `1`.
+    /// * `fallbacks` - The pipelines to switch to, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::{JitteredMutation, Pipeline};
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    ///
+    /// let runner = Runner::new(RunnerConfig::default()).with_operator_fallbacks(
+    ///     5,
+    ///     vec![Pipeline::default().with_mutation(JitteredMutation { jitter: 1.0 })],
+    /// );
+    /// ```
+    pub fn with_operator_fallbacks(
+        mut self,
+        stagnation_generations: usize,
+        fallbacks: Vec<Pipeline>,
+    ) -> Self {
+        self.stagnation_generations = stagnation_generations.max(1);
+        self.fallback_pipelines = fallbacks;
+        self
+    }
+    /// Stop the evolution of an island once `predicate` returns `true` after a generation, in
+    /// addition to stopping after `RunnerConfig::n_generations`.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate` - The domain-specific termination criterion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::{Generation, Runner, RunnerConfig};
+    ///
+    /// let threshold = 100.0;
+    /// let runner = Runner::new(RunnerConfig::default()).with_stopping_predicate(
+    ///     move |generation: &Generation| {
+    ///         generation.report.n_unevaluated == 0
+    ///             && generation.report.best_fitness.is_some_and(|fitness| fitness > threshold)
+    ///     },
+    /// );
+    /// ```
+    pub fn with_stopping_predicate<P>(mut self, predicate: P) -> Self
+    where
+        P: Fn(&Generation) -> bool + Send + Sync + 'static,
+    {
+        self.stopping_predicate = Some(Box::new(predicate));
+        self
+    }
+    /// End the run once `interrupt` is set, e.g. from a Ctrl-C handler. The generation that is
+    /// currently evolving is finished first, so the result is consistent.
+    ///
+    /// # Arguments
+    ///
+    /// * `interrupt` - The flag that is checked after every generation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use std::sync::atomic::AtomicBool;
+    /// use std::sync::Arc;
+    ///
+    /// let interrupt = Arc::new(AtomicBool::new(false));
+    /// let runner = Runner::new(RunnerConfig::default()).with_interrupt(Arc::clone(&interrupt));
+    /// ```
+    pub fn with_interrupt(mut self, interrupt: Arc<AtomicBool>) -> Self {
+        self.interrupt = Some(interrupt);
+        self
+    }
+    /// Continue the random number streams of an earlier run, see
+    /// `OptimizationResult::rng_state`. The islands continue their streams if the run has as
+    /// many islands as the earlier one, otherwise they are seeded from the generator of the
+    /// runner. Without `Runner::with_island_states` the initial population is evaluated
+    /// before the first generation and ordered from the fittest to the least fit individual,
+    /// like a single-threaded run keeps it, and the generations are counted from `0` again.
+    /// Resuming with both states and the remaining generations evolves exactly like the
+    /// uninterrupted run, also with several islands, as long as the evaluation is
+    /// deterministic and no setting depends on the stagnation of an island, e.g. `patience`.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The states of the random number generators to continue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let config = RunnerConfig {
+    ///     n_generations: 10,
+    ///     seed: Some(3),
+    ///     ..RunnerConfig::default()
+    /// };
+    /// let function = Function::new(|x| Ok(x.iter().sum()));
+    /// let first = Runner::new(config.clone()).run(Solutions::random(5, 1.0..10.0, 2), &function);
+    /// let second = Runner::new(config)
+    ///     .with_rng_state(first.rng_state.clone())
+    ///     .run(first.population.clone(), &function);
+    /// assert_eq!(second.n_evolved_generations, 10);
+    /// ```
+    pub fn with_rng_state(mut self, state: RunRngState) -> Self {
+        if let Some(runner) = state.runner {
+            self.rng = RunnerRng::Seeded(Box::new(runner.restore()));
+        }
+        self.island_rngs = state.islands;
+        self.continued = true;
+        self
+    }
+    /// Continue the islands of an earlier run from their final states, see
+    /// `OptimizationResult::islands`. If the run has as many islands as the earlier one (one
+    /// if single-threaded), every island starts from its population instead of the initial
+    /// population, keeps the fitness and the ages of its individuals and continues the
+    /// numbering of its generations. `n_generations` are the generations left, like in
+    /// `Checkpoint::remaining_config`.
+    ///
+    /// # Arguments
+    ///
+    /// * `states` - The states of the islands, by island.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let config = |n_generations| RunnerConfig {
+    ///     n_generations,
+    ///     seed: Some(3),
+    ///     ..RunnerConfig::default()
+    /// };
+    /// let function = Function::new(|x| Ok(x.iter().sum()));
+    /// let initial_population = Solutions::random(5, 1.0..10.0, 2);
+    /// let uninterrupted = Runner::new(config(10)).run(initial_population.clone(), &function);
+    /// let first = Runner::new(config(4)).run(initial_population, &function);
+    /// let second = Runner::new(config(6))
+    ///     .with_rng_state(first.rng_state.clone())
+    ///     .with_island_states(first.islands.clone())
+    ///     .run(first.population.clone(), &function);
+    /// assert_eq!(second.islands[0].generation, 10);
+    /// assert_eq!(second.population, uninterrupted.population);
+    /// ```
+    pub fn with_island_states(mut self, states: Vec<IslandState>) -> Self {
+        self.island_states = states;
+        self
+    }
+    /// Call `callback` whenever the run finds a new best individual, e.g. to log or alert.
+    /// With several islands the callback is called from their threads, but never concurrently.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - Gets the new best individual and when it was found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    ///
+    /// let runner = Runner::new(RunnerConfig::default()).with_on_new_best(|improvement| {
+    ///     println!("New best {} after {:?}", improvement.fitness, improvement.elapsed)
+    /// });
+    /// ```
+    pub fn with_on_new_best<C>(mut self, callback: C) -> Self
+    where
+        C: Fn(&Improvement) + Send + Sync + 'static,
+    {
+        self.on_new_best = Some(Box::new(callback));
+        self
+    }
+    /// Run the evolution of every island inside `island_threads`, e.g. inside a rayon
+    /// `ThreadPool` of its own that the objective runs its parallel code on. It gets the index
+    /// of the island, the number of threads its evaluator may use, see
+    /// `RunnerConfig::evaluation_threads`, and the evolution, which it has to run once before
+    /// returning. Single-threaded runs call it for their only island, multi-threaded ones from
+    /// the thread of every island.
+    ///
+    /// # Arguments
+    ///
+    /// * `island_threads` - Runs the evolution of an island.
+    ///
+    /// # Panics
+    ///
+    /// The run panics if `island_threads` returns without running the evolution.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    ///
+    /// // With rayon: `|island, n_threads, evolve| pools[island].install(evolve)`.
+    /// let runner = Runner::new(RunnerConfig {
+    ///     threads_per_evaluation: 4,
+    ///     ..RunnerConfig::default()
+    /// })
+    /// .with_island_threads(|island, n_threads, evolve| {
+    ///     println!("Island {} evaluates on {} threads", island, n_threads);
+    ///     evolve()
+    /// });
+    /// ```
+    pub fn with_island_threads<T>(mut self, island_threads: T) -> Self
+    where
+        T: for<'a> Fn(usize, usize, Box<dyn FnOnce() + Send + 'a>) + Send + Sync + 'static,
+    {
+        self.island_threads = Some(Box::new(island_threads));
+        self
+    }
+    /// Call `callback` whenever the evaluation of an individual fails, e.g. to log or store
+    /// the exact arguments the objective failed on. The callback is called before the failure
+    /// is penalized with `RunnerConfig::failure_penalty`, or before the run panics without a
+    /// penalty. If an evaluator panics, it is called for every individual of the chunk being
+    /// evaluated. With several islands the callback may be called from their threads at the
+    /// same time.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - Gets the individual and why its evaluation failed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let failed = Arc::new(Mutex::new(Vec::new()));
+    /// let recorded = Arc::clone(&failed);
+    /// Runner::new(RunnerConfig {
+    ///     failure_penalty: Some(f64::MIN),
+    ///     ..RunnerConfig::default()
+    /// })
+    /// .with_on_evaluation_error(move |solution, error| {
+    ///     recorded.lock().unwrap().push((solution.clone(), error.to_string()))
+    /// })
+    /// .run(
+    ///     Solutions::random(10, -1.0..1.0, 1),
+    ///     &Function::new(|x| Ok(x[0].ln())),
+    /// );
+    /// // The logarithm of negative arguments is `NaN`.
+    /// let failed = failed.lock().unwrap();
+    /// assert!(!failed.is_empty());
+    /// assert!(failed.iter().all(|(solution, _)| solution.get_arguments()[0] < 0.0));
+    /// ```
+    pub fn with_on_evaluation_error<C>(mut self, callback: C) -> Self
+    where
+        C: Fn(&Solution, &EvaluationError) + Send + Sync + 'static,
+    {
+        self.on_evaluation_error = Some(Box::new(callback));
+        self
+    }
+    /// Compute the fitness of individuals whose evaluation failed, panicked or returned a
+    /// non-finite value with `penalty` instead of the constant `RunnerConfig::failure_penalty`,
+    /// e.g. to penalize individuals by how far they are from the feasible region.
+    ///
+    /// # Arguments
+    ///
+    /// * `penalty` - Gets the individual and why its evaluation failed and returns its fitness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// // The square root is only defined for non-negative arguments, the further below zero,
+    /// // the worse.
+    /// let result = Runner::new(RunnerConfig::default())
+    ///     .with_penalty_function(|solution, _| -1000.0 + solution.get_arguments()[0])
+    ///     .run(
+    ///         Solutions::random(10, -1.0..1.0, 1),
+    ///         &Function::new(|x| Ok(-x[0].sqrt())),
+    ///     );
+    /// let (best, fitness) = result.best.unwrap();
+    /// assert!(best.get_arguments()[0] >= 0.0);
+    /// assert!(fitness > -1000.0);
+    /// ```
+    pub fn with_penalty_function<P>(mut self, penalty: P) -> Self
+    where
+        P: Fn(&Solution, &FailureKind) -> f64 + Send + Sync + 'static,
+    {
+        self.penalty_function = Some(Box::new(penalty));
+        self
+    }
+    /// Call `callback` whenever the population of an island degenerates, e.g. to log why a
+    /// run stalls. Every issue is reported once when it arises, see `HealthWarning`.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - Gets the island, the generation and what is wrong with the population.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{HealthIssue, Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let warnings = Arc::new(Mutex::new(Vec::new()));
+    /// let recorded = Arc::clone(&warnings);
+    /// Runner::new(RunnerConfig::default())
+    ///     .with_on_health_warning(move |warning| recorded.lock().unwrap().push(warning.clone()))
+    ///     .run(Solutions::random(10, -1.0..1.0, 2), &Function::new(|_| Ok(1.0)));
+    /// // A constant objective can't tell the individuals apart.
+    /// let warnings = warnings.lock().unwrap();
+    /// assert!(matches!(
+    ///     warnings[0].issue,
+    ///     HealthIssue::ZeroFitnessVariance { fitness, .. } if fitness == 1.0
+    /// ));
+    /// ```
+    pub fn with_on_health_warning<C>(mut self, callback: C) -> Self
+    where
+        C: Fn(&HealthWarning) + Send + Sync + 'static,
+    {
+        self.on_health_warning = Some(Box::new(callback));
+        self
+    }
+    /// Tell the time of `RunnerConfig::generation_time_budget` and `Improvement::elapsed` with
+    /// `clock` instead of the system clock, e.g. a `ManualClock` in tests.
+    ///
+    /// # Arguments
+    ///
+    /// * `clock` - The source of the current time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::clock::ManualClock;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use std::sync::Arc;
+    ///
+    /// let clock = Arc::new(ManualClock::new());
+    /// let runner = Runner::new(RunnerConfig::default()).with_clock(clock.clone());
+    /// ```
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+    /// Run the islands of multi-threaded runs on `executor` instead of a thread each, e.g. one
+    /// after another on the `Sequential` executor.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - Runs the islands.
+    #[cfg(test)]
+    pub(crate) fn with_executor(mut self, executor: Arc<dyn Executor>) -> Self {
+        self.executor = executor;
+        self
+    }
+    /// Return the settings the runner runs with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    ///
+    /// assert_eq!(Runner::new(RunnerConfig::default()).config().n_generations, 100);
+    /// ```
+    pub fn config(&self) -> &RunnerConfig {
+        &self.config
+    }
+    /// Evolve an initial population.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_population` - The population the evolution starts from.
+    /// * `evaluator` - The evaluator (typically a `Function`) whose fitness should be maximized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions;
+    ///
+    /// let function_to_optimize = function::Function::new(
+    ///     |x| match x.len() {
+    ///         3 => Ok(x[0] * x[1] * x[2]),
+    ///         _ => Err(function::FunctionError::WrongNumberOfEntries {
+    ///             actual_number_of_entries: x.len(),
+    ///             expected_number_of_entries: 3,
+    ///         }),
+    ///     }
+    /// );
+    /// let mut runner = Runner::new(RunnerConfig {
+    ///     n_generations: 5,
+    ///     size_generation: 5,
+    ///     ..RunnerConfig::default()
+    /// });
+    /// let result = runner.run(solutions::Solutions::random(5, 1.0..10.0, 3), &function_to_optimize);
+    /// println!("Best solution: {:?}", result.best);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `RunnerConfig::check_operators` is set and a stage of the pipeline broke an
+    /// invariant, use `try_run` to get the error instead.
+    pub fn run<E>(&mut self, initial_population: Solutions, evaluator: &E) -> OptimizationResult
+    where
+        E: Evaluator + ?Sized,
+    {
+        self.try_run(initial_population, evaluator)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+    /// Run the genetic algorithm like `run`, but return the error if
+    /// `RunnerConfig::check_operators` is set and a stage of the pipeline broke an invariant,
+    /// e.g. a custom mutation returned a non-finite argument.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_population` - The population the genetic algorithm starts from.
+    /// * `evaluator` - The evaluator (typically a `Function`) whose fitness should be maximized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::pipeline::{Pipeline, Stage};
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solution::Solution;
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let error = Runner::new(RunnerConfig {
+    ///     check_operators: true,
+    ///     ..RunnerConfig::default()
+    /// })
+    /// .with_pipeline(Pipeline::default().with_repair(|_: Solution| Solution::new(vec![])))
+    /// .try_run(
+    ///     Solutions::random(5, 1.0..10.0, 2),
+    ///     &Function::new(|x| Ok(x.iter().sum())),
+    /// )
+    /// .unwrap_err();
+    /// assert_eq!(error.stage, Stage::Repair);
+    /// ```
+    pub fn try_run<E>(
+        &mut self,
+        initial_population: Solutions,
+        evaluator: &E,
+    ) -> Result<OptimizationResult, OperatorError>
+    where
+        E: Evaluator + ?Sized,
+    {
+        if let Some(solution) = initial_population.iter().next() {
+            // The chosen size is kept, so the runner and the result report it.
+            self.config.size_generation = self
+                .config
+                .size_generation_for(solution.get_arguments().len());
+        }
+        let mut initial_candidates = initial_population
+            .iter()
+            .map(|solution| (solution.clone(), None))
+            .collect::<Vec<Candidate>>();
+        // Seeded runs must not depend on the iteration order of the `HashSet`.
+        initial_candidates
+            .sort_by(|(solution_a, _), (solution_b, _)| compare_arguments(solution_a, solution_b));
+        let span = span(&initial_candidates);
+        let precision = self.config.dedup_precision.apply(
+            self.pipeline.bounds().unwrap_or(&span),
+            self.config.size_generation,
+        );
+        let initial_ages = initial_candidates
+            .iter()
+            .map(|(solution, _)| (solution.clone(), 0))
+            .collect::<HashMap<Solution, usize>>();
+        let stream_seed = if self.config.individual_rng_streams {
+            Some(self.config.seed.unwrap_or_else(|| self.rng.next_u64()))
+        } else {
+            None
+        };
+        let observed = ObservedEvaluator {
+            evaluator,
+            on_error: self.on_evaluation_error.as_deref(),
+        };
+        let evaluator = DirectedEvaluator {
+            evaluator: &observed,
+            direction: self.config.direction,
+        };
+        let context = RunContext {
+            config: &self.config,
+            pipeline: &self.pipeline,
+            fallback_pipelines: &self.fallback_pipelines,
+            stagnation_generations: self.stagnation_generations,
+            evaluator: &evaluator,
+            executor: self.executor.as_ref(),
+            stopping_predicate: self.stopping_predicate.as_deref(),
+            interrupt: self.interrupt.as_deref(),
+            start: self.clock.now(),
+            clock: self.clock.as_ref(),
+            identity: initial_population.identity(),
+            precision,
+            hall_of_fame: Mutex::new(HallOfFame::new(self.config.hall_of_fame_size)),
+            on_new_best: self.on_new_best.as_deref(),
+            penalty_function: self.penalty_function.as_deref(),
+            on_health_warning: self.on_health_warning.as_deref(),
+            control: self.control.as_deref(),
+            span,
+            n_evaluations: AtomicUsize::new(0),
+        };
+        // The interrupted run kept its population fittest first, a continued one has to start
+        // from the same order so the operators draw the same parents.
+        let n_jobs = self.config.n_islands();
+        if let Some(control) = &self.control {
+            control.islands_created(n_jobs.max(1));
+        }
+        let restored_islands = self.island_states.len() == n_jobs.max(1);
+        let mut initial_evaluations = EvaluationOutcomes::default();
+        if self.continued && !restored_islands {
+            (_, initial_evaluations) = evaluate(
+                &mut initial_candidates,
+                context.evaluator,
+                &self.config,
+                self.penalty_function.as_deref(),
+                None,
+                None,
+                None,
+                None,
+            );
+            initial_candidates = select(initial_candidates, usize::MAX);
+            context
+                .n_evaluations
+                .fetch_add(initial_evaluations.n_evaluations(), AtomicOrdering::SeqCst);
+        }
+        // `n_jobs == 0` runs single-threaded, otherwise every island runs the same share of
+        // the generations. Restored islands count the generations of the earlier runs too.
+        let n_generations = match restored_islands {
+            true => {
+                self.config.n_generations
+                    + self
+                        .island_states
+                        .iter()
+                        .map(|state| state.generation)
+                        .sum::<usize>()
+            }
+            false => self.config.n_generations,
+        };
+        let n_polished_generations = self
+            .config
+            .polish
+            .map_or(0, |polish| polish.n_generations(n_generations));
+        let n_generations = n_generations - n_polished_generations;
+        let mut island_states: Vec<Option<IslandState>> = match restored_islands {
+            true => self.island_states.iter().cloned().map(Some).collect(),
+            false => vec![None; n_jobs.max(1)],
+        };
+        let (result, final_candidates) = match n_generations.checked_div(n_jobs) {
+            None => {
+                let mut island = Island::new(
+                    0,
+                    initial_candidates,
+                    &mut self.rng,
+                    stream_seed,
+                    initial_ages,
+                    &self.config,
+                );
+                if let Some(state) = island_states.remove(0) {
+                    island.restore(state);
+                }
+                run_on_island_threads(
+                    self.island_threads.as_deref(),
+                    0,
+                    self.config.evaluation_threads(),
+                    || context.evolve(&mut island, n_generations),
+                );
+                if let Some(error) = island.error {
+                    return Err(error);
+                }
+                let final_candidates = island.population.clone();
+                let islands = vec![island.state()];
+                let result = OptimizationResult {
+                    best: fittest(&island.population),
+                    population: Solutions::from(
+                        island
+                            .population
+                            .into_iter()
+                            .map(|(solution, _)| solution)
+                            .collect::<Vec<Solution>>(),
+                    ),
+                    generations: island.generations.into(),
+                    n_evolved_generations: island.n_evolved_generations,
+                    statistics: island.statistics,
+                    archive: island.archive.unwrap_or_default(),
+                    stopped_early: island.stopped,
+                    interrupted: island.interrupted,
+                    hall_of_fame: HallOfFame::default(),
+                    evaluations: island.evaluations,
+                    reconfigurations: island.reconfigurations,
+                    polish: None,
+                    rng_state: RunRngState::default(),
+                    islands,
+                    precision,
+                    config: self.config.clone(),
+                };
+                (result, final_candidates)
+            }
+            Some(n_generations_per_island) => {
+                let n_generations_per_island = n_generations_per_island + 1;
+                let restored = self.island_rngs.len() == n_jobs;
+                let islands = (0..n_jobs)
+                    .zip(island_states)
+                    .map(|(index, state)| {
+                        let mut island = Island::new(
+                            index,
+                            initial_candidates.clone(),
+                            match restored {
+                                true => self.island_rngs[index].restore(),
+                                false => ChaCha12Rng::from_rng(&mut self.rng)?,
+                            },
+                            stream_seed.map(|seed| rng::stream_seed(seed, &[index as u64])),
+                            initial_ages.clone(),
+                            &self.config,
+                        );
+                        if let Some(state) = state {
+                            island.restore(state);
+                        }
+                        Ok(island)
+                    })
+                    .collect::<Result<Vec<Island<ChaCha12Rng>>, rand::Error>>()
+                    .expect("Could not seed the random number generators of the islands");
+                let context = &context;
+                let island_threads = self.island_threads.as_deref();
+                let evaluation_threads = self.config.evaluation_threads();
+                let islands = executor::run_all(
+                    self.executor.as_ref(),
+                    islands.into_iter().map(|mut island| {
+                        move || {
+                            run_on_island_threads(
+                                island_threads,
+                                island.index,
+                                evaluation_threads,
+                                || context.evolve(&mut island, n_generations_per_island),
+                            );
+                            island
+                        }
+                    }),
+                );
+
+                let mut generations = Vec::new();
+                let mut island_populations = Vec::new();
+                let mut archive = new_archive(&self.config, n_jobs).unwrap_or_default();
+                let mut n_evolved_generations = 0;
+                let mut statistics: Option<RunStatistics> = None;
+                let mut stopped_early = false;
+                let mut interrupted = false;
+                let mut evaluations = EvaluationOutcomes::default();
+                let mut reconfigurations = Vec::new();
+                if let Some(error) = islands.iter().find_map(|island| island.error.clone()) {
+                    return Err(error);
+                }
+                let island_rngs = islands
+                    .iter()
+                    .map(|island| RngState::capture(&island.rng))
+                    .collect();
+                let island_states = islands.iter().map(Island::state).collect();
+                for island in islands {
+                    stopped_early |= island.stopped;
+                    interrupted |= island.interrupted;
+                    evaluations.merge(&island.evaluations);
+                    reconfigurations.extend(island.reconfigurations);
+                    generations.extend(island.generations);
+                    n_evolved_generations += island.n_evolved_generations;
+                    match &mut statistics {
+                        Some(statistics) => statistics.merge(&island.statistics),
+                        None => statistics = Some(island.statistics),
+                    }
+                    island_populations.push(island.population);
+                    archive.merge(island.archive.unwrap_or_default());
+                }
+                reconfigurations.sort_by_key(|reconfigured: &Reconfigured| {
+                    (reconfigured.generation, reconfigured.island)
+                });
+                let final_candidates = island_populations.concat();
+                let best = fittest(&final_candidates);
+                let result = OptimizationResult {
+                    population: self.config.merge_policy.merge_with_rng(
+                        island_populations
+                            .into_iter()
+                            .map(|candidates| {
+                                candidates
+                                    .into_iter()
+                                    .map(|(solution, fitness)| {
+                                        (solution, fitness.unwrap_or(f64::NEG_INFINITY))
+                                    })
+                                    .collect()
+                            })
+                            .collect(),
+                        &mut self.rng,
+                    ),
+                    best,
+                    generations,
+                    n_evolved_generations,
+                    statistics: statistics
+                        .unwrap_or_else(|| RunStatistics::new(self.config.ema_smoothing)),
+                    archive,
+                    stopped_early,
+                    evaluations,
+                    reconfigurations,
+                    polish: None,
+                    interrupted,
+                    hall_of_fame: HallOfFame::default(),
+                    rng_state: RunRngState {
+                        runner: None,
+                        islands: island_rngs,
+                    },
+                    islands: island_states,
+                    precision,
+                    config: self.config.clone(),
+                };
+                (result, final_candidates)
+            }
+        };
+        let result = match self.config.polish {
+            Some(polish) if !result.interrupted => {
+                // The polish gets the evaluations the generations it replaces would have taken.
+                let budget = result.evaluations.n_evaluations() * n_polished_generations
+                    / result.n_evolved_generations.max(1);
+                context.polish(result, final_candidates, &polish, budget)
+            }
+            _ => result,
+        };
+        let mut evaluations = initial_evaluations;
+        evaluations.merge(&result.evaluations);
+        Ok(OptimizationResult {
+            population: result
+                .population
+                .with_shared_identity(context.identity.clone()),
+            hall_of_fame: context.hall_of_fame.into_inner().unwrap(),
+            config: RunnerConfig {
+                seed: self.seed,
+                ..result.config
+            },
+            evaluations,
+            rng_state: RunRngState {
+                runner: self.rng.state(),
+                ..result.rng_state
+            },
+            ..result
+        })
+    }
+    /// Evolve an initial population on a thread of its own, like `try_run`, and return right
+    /// away. The returned handle tells the best individual and the progress of the run while it
+    /// evolves, pauses, resumes or stops it, and returns the result once it ended.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_population` - The population the evolution starts from.
+    /// * `evaluator` - The evaluator (typically a `Function`) whose fitness should be maximized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let handle = Runner::new(RunnerConfig::default()).spawn(
+    ///     Solutions::random(5, 1.0..10.0, 2),
+    ///     Function::new(|x| Ok(x.iter().sum())),
+    /// );
+    /// let progress = handle.progress();
+    /// assert!(progress.n_evolved_generations <= progress.n_generations);
+    /// let result = handle.join().unwrap();
+    /// assert_eq!(result.n_evolved_generations, 100);
+    /// ```
+    pub fn spawn<E>(mut self, initial_population: Solutions, evaluator: E) -> RunHandle
+    where
+        E: Evaluator + Send + 'static,
+    {
+        let control = Arc::new(RunControl::new(
+            self.config.n_generations,
+            1 + self.fallback_pipelines.len(),
+        ));
+        self.control = Some(Arc::clone(&control));
+        RunHandle::spawn(control, move || {
+            self.try_run(initial_population, &evaluator)
+        })
+    }
+}
+
+/// Evolve a single generation of `population`, exactly like a single-threaded run evolves its
+/// generation `generation`, with the default pipeline. Seeding `rng` makes the step
+/// deterministic. Nothing is carried over between steps: every individual starts with age
+/// `0`, and stagnation and the history of `RunnerConfig::fitness_window` start afresh.
+///
+/// # Arguments
+///
+/// * `population` - The individuals and their fitness, `None` if they weren't evaluated yet.
+/// * `generation` - The index of the generation, e.g. for the annealing temperature.
+/// * `config` - The settings of the run, `n_generations` and `n_jobs` are ignored.
+/// * `rng` - The random number generator used by all stochastic operators.
+/// * `evaluator` - The evaluator whose fitness should be maximized.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::function::Function;
+/// use genetic_algorithm_fn::runner::{self, RunnerConfig};
+/// use genetic_algorithm_fn::solutions::Solutions;
+/// use genetic_algorithm_traits::Population;
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+///
+/// let population = Solutions::random(5, 1.0..10.0, 2)
+///     .iter()
+///     .map(|solution| (solution.clone(), None))
+///     .collect();
+/// let result = runner::step(
+///     population,
+///     0,
+///     &RunnerConfig::default(),
+///     &mut StdRng::seed_from_u64(42),
+///     &Function::new(|x| Ok(x.iter().sum())),
+/// )
+/// .unwrap();
+/// assert_eq!(result.report.n_unevaluated, 0);
+/// ```
+pub fn step<G, E>(
+    population: Vec<(Solution, Option<f64>)>,
+    generation: usize,
+    config: &RunnerConfig,
+    rng: &mut G,
+    evaluator: &E,
+) -> Result<StepResult, OperatorError>
+where
+    G: RngCore,
+    E: Evaluator + ?Sized,
+{
+    let pipeline = Pipeline::default();
+    let evaluator = &DirectedEvaluator {
+        evaluator,
+        direction: config.direction,
+    };
+    let config = &RunnerConfig {
+        size_generation: population
+            .first()
+            .map_or(config.size_generation, |(solution, _)| {
+                config.size_generation_for(solution.get_arguments().len())
+            }),
+        ..config.clone()
+    };
+    let context = RunContext {
+        config,
+        pipeline: &pipeline,
+        fallback_pipelines: &[],
+        stagnation_generations: 0,
+        evaluator,
+        executor: &ScopedThreads,
+        stopping_predicate: None,
+        interrupt: None,
+        start: SystemClock.now(),
+        clock: &SystemClock,
+        identity: None,
+        precision: config
+            .dedup_precision
+            .apply(&span(&population), config.size_generation),
+        hall_of_fame: Mutex::new(HallOfFame::default()),
+        on_new_best: None,
+        penalty_function: None,
+        on_health_warning: None,
+        control: None,
+        span: span(&population),
+        n_evaluations: AtomicUsize::new(0),
+    };
+    let stream_seed = config
+        .individual_rng_streams
+        .then(|| config.seed.unwrap_or_else(|| rng.next_u64()));
+    let ages = population
+        .iter()
+        .map(|(solution, _)| (solution.clone(), 0))
+        .collect();
+    let mut island = Island::new(0, population, rng, stream_seed, ages, config);
+    let (report, settings) = context.evolve_generation(&mut island, generation)?;
+    Ok(StepResult {
+        population: island.population,
+        report,
+        settings,
+        elapsed: context.clock.elapsed_since(context.start),
+    })
+}
+
+/// The smallest bounds containing every individual, per argument.
+///
+/// # Arguments
+///
+/// * `population` - The individuals.
+fn span(population: &[Candidate]) -> Vec<Range<f64>> {
+    let mut bounds: Vec<Range<f64>> = Vec::new();
+    for (solution, _) in population {
+        for (idx, argument) in solution.get_arguments().into_iter().enumerate() {
+            match bounds.get_mut(idx) {
+                Some(bound) => {
+                    bound.start = bound.start.min(argument);
+                    bound.end = bound.end.max(argument);
+                }
+                None => bounds.push(argument..argument),
+            }
+        }
+    }
+    bounds
+}
+
+/// Create the archive of an island, if evaluations are recorded.
+///
+/// # Arguments
+///
+/// * `config` - The settings of the run.
+/// * `index` - The index of the island, seeds the sampling of a bounded archive.
+fn new_archive(config: &RunnerConfig, index: usize) -> Option<Archive> {
+    config
+        .record_archive
+        .then(|| match config.max_archive_entries {
+            Some(max_entries) => Archive::bounded(
+                max_entries,
+                rng::stream_seed(config.seed.unwrap_or(0), &[index as u64]),
+            ),
+            None => Archive::new(),
+        })
+}
+
+/// Everything the islands of a run share.
+struct RunContext<'a, E: ?Sized> {
+    /// The settings of the run.
+    config: &'a RunnerConfig,
+    /// The reproduction step of every generation.
+    pipeline: &'a Pipeline,
+    /// The pipelines a stagnating island switches to.
+    fallback_pipelines: &'a [Pipeline],
+    /// After how many generations without improvement an island switches pipelines.
+    stagnation_generations: usize,
+    /// The evaluator whose fitness should be maximized.
+    evaluator: &'a E,
+    /// Runs the evaluation and the sorting of `RunnerConfig::overlap_batch_size`.
+    executor: &'a dyn Executor,
+    /// The user-defined termination criterion, if any.
+    stopping_predicate: Option<&'a StoppingPredicate>,
+    /// Ends the run after the current generation once it is set.
+    interrupt: Option<&'a AtomicBool>,
+    /// When the run started.
+    start: Instant,
+    /// Tells the time of the run.
+    clock: &'a dyn Clock,
+    /// Decides which offspring duplicate an individual, taken from the initial population.
+    identity: Option<Arc<dyn Identity>>,
+    /// The precision offspring are deduplicated at, see `RunnerConfig::dedup_precision`.
+    precision: Precision,
+    /// The fittest individuals of all islands.
+    hall_of_fame: Mutex<HallOfFame>,
+    /// Called whenever the run finds a new best individual.
+    on_new_best: Option<&'a ImprovementCallback>,
+    /// Computes the fitness of individuals whose evaluation failed.
+    penalty_function: Option<&'a PenaltyFunction>,
+    /// Called whenever the population of an island degenerates.
+    on_health_warning: Option<&'a HealthWarningCallback>,
+    /// Reports the progress of a spawned run and pauses or stops it.
+    control: Option<&'a RunControl>,
+    /// The bounds spanned by the initial population, restarts draw within them if the
+    /// pipeline has no bounds.
+    span: Vec<Range<f64>>,
+    /// The evaluations of all islands so far, including those reserved by generations that
+    /// are being evaluated, see `RunnerConfig::max_evaluations`.
+    n_evaluations: AtomicUsize,
+}
+
+/// A population evolving on its own. Single-threaded runs consist of a single island.
+struct Island<G> {
+    /// The index of the island.
+    index: usize,
+    /// The current individuals and their fitness.
+    population: Vec<Candidate>,
+    /// The random number generator of the island.
+    rng: G,
+    /// The seed of the per-individual random number streams, if they are used.
+    stream_seed: Option<u64>,
+    /// The reports of the latest generations the island evolved.
+    generations: VecDeque<GenerationReport>,
+    /// The index of the first generation the island evolves, see `IslandState::generation`.
+    first_generation: usize,
+    /// How many generations the island evolved so far.
+    n_evolved_generations: usize,
+    /// Statistics over all generations the island evolved so far.
+    statistics: RunStatistics,
+    /// How many generations every individual of the population survived.
+    ages: HashMap<Solution, usize>,
+    /// The evaluations of the island, if they are recorded.
+    archive: Option<Archive>,
+    /// Whether the stopping predicate ended the evolution of the island.
+    stopped: bool,
+    /// Whether the interrupt or the run control ended the evolution of the island before its
+    /// last generation.
+    interrupted: bool,
+    /// The broken operator invariant that ended the evolution of the island, if any.
+    error: Option<OperatorError>,
+    /// The measured time per evaluation, for `RunnerConfig::size_to_time_budget`.
+    evaluation_cost: EvaluationCost,
+    /// The index of the pipeline the island currently reproduces with, see
+    /// `GenerationReport::pipeline`.
+    pipeline: usize,
+    /// The best fitness the island reached so far.
+    best_fitness: Option<f64>,
+    /// For how many generations the best fitness of the island didn't improve.
+    n_stagnant_generations: usize,
+    /// For how many generations the best fitness didn't improve, not reset by pipeline
+    /// switches but by restarts.
+    n_unimproved_generations: usize,
+    /// The best fitness the last improvement by more than `Patience::epsilon` reached.
+    patience_fitness: Option<f64>,
+    /// For how many generations the best fitness didn't improve by more than
+    /// `Patience::epsilon`.
+    n_impatient_generations: usize,
+    /// The recent evaluations of the population, if `RunnerConfig::fitness_window` is set.
+    history: Option<FitnessHistory>,
+    /// The outcomes of all evaluations of the island so far.
+    evaluations: EvaluationOutcomes,
+    /// All reconfigurations applied to the island so far, combined.
+    overrides: Reconfiguration,
+    /// The reconfigurations applied to the island so far.
+    reconfigurations: Vec<Reconfigured>,
+    /// Which arguments the mutation of the current generation must not change, see
+    /// `RunnerConfig::freeze`.
+    frozen: Vec<bool>,
+    /// The bounds offspring are confined to, see `RunnerConfig::zoom`.
+    zoom_bounds: Option<Vec<Range<f64>>>,
+    /// The health issues of the population that were reported and persist.
+    health_issues: Vec<HealthIssue>,
+    /// For how many generations in a row most offspring were duplicates.
+    n_duplicate_generations: usize,
+}
+
+/// The share of the time budget of a generation its evaluations are planned to take, the rest
+/// is left for reproduction and selection and to absorb noise in the evaluation times.
+const TIME_BUDGET_UTILIZATION: f64 = 0.9;
+
+/// How many points per missing individual a restart draws at most before it gives up on
+/// filling the population.
+const MAX_RESTART_ATTEMPTS: usize = 10;
+
+/// How many rounds of reproduction are tried at most to produce enough offspring to fill the
+/// time budget of a generation.
+const MAX_REPRODUCTION_ROUNDS: usize = 8;
+
+/// Estimates how long a single evaluation takes, from the evaluations of the last generations.
+#[derive(Debug, Clone, Copy, Default)]
+struct EvaluationCost {
+    /// The exponentially smoothed seconds per evaluation, `None` before the first evaluation.
+    seconds: Option<f64>,
+}
+
+impl EvaluationCost {
+    /// Record how long evaluating a generation took.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_evaluated` - How many individuals were evaluated.
+    /// * `elapsed` - How long it took.
+    fn record(&mut self, n_evaluated: usize, elapsed: Duration) {
+        if n_evaluated == 0 {
+            return;
+        }
+        let seconds = elapsed.as_secs_f64() / n_evaluated as f64;
+        self.seconds = Some(match self.seconds {
+            Some(estimate) => 0.5 * (estimate + seconds),
+            None => seconds,
+        });
+    }
+    /// How many evaluations fit into the time budget, `None` before the first evaluation.
+    ///
+    /// # Arguments
+    ///
+    /// * `budget` - The time budget of a generation.
+    fn n_evaluations(&self, budget: Duration) -> Option<usize> {
+        self.seconds.map(|seconds| {
+            let available = budget.as_secs_f64() * TIME_BUDGET_UTILIZATION;
+            if seconds > 0.0 {
+                (available / seconds).min(usize::MAX as f64) as usize
+            } else {
+                usize::MAX
+            }
+        })
+    }
+}
+
+/// The last exact evaluations of every individual of a population, to average its fitness.
+#[derive(Debug, Clone, Default)]
+struct FitnessHistory {
+    /// How many evaluations are averaged over.
+    window: usize,
+    /// The latest evaluations of every individual, the oldest first.
+    values: HashMap<Solution, VecDeque<f64>>,
+}
+
+impl FitnessHistory {
+    /// Create an empty history, averaging over `window` evaluations (at least one).
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - How many evaluations are averaged over.
+    fn new(window: usize) -> Self {
+        FitnessHistory {
+            window: window.max(1),
+            values: HashMap::new(),
+        }
+    }
+    /// Record an evaluation of an individual and return its mean fitness over the window.
+    ///
+    /// # Arguments
+    ///
+    /// * `solution` - The individual that was evaluated.
+    /// * `fitness` - The fitness it was evaluated to.
+    fn record(&mut self, solution: &Solution, fitness: f64) -> f64 {
+        let values = self.values.entry(solution.clone()).or_default();
+        if values.len() == self.window {
+            values.pop_front();
+        }
+        values.push_back(fitness);
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+    /// Forget the evaluations of individuals that are no longer in the population.
+    ///
+    /// # Arguments
+    ///
+    /// * `population` - The current population.
+    fn retain(&mut self, population: &[Candidate]) {
+        let alive = population
+            .iter()
+            .map(|(solution, _)| solution)
+            .collect::<HashSet<&Solution>>();
+        self.values.retain(|solution, _| alive.contains(solution));
+    }
+}
+
+impl<G> Island<G> {
+    /// Create an island that didn't evolve yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the island.
+    /// * `population` - The initial individuals and their fitness, if known.
+    /// * `rng` - The random number generator of the island.
+    /// * `stream_seed` - The seed of the per-individual random number streams, if used.
+    /// * `ages` - How many generations every individual of the population survived.
+    /// * `config` - The settings of the run.
+    fn new(
+        index: usize,
+        population: Vec<Candidate>,
+        rng: G,
+        stream_seed: Option<u64>,
+        ages: HashMap<Solution, usize>,
+        config: &RunnerConfig,
+    ) -> Self {
+        Island {
+            index,
+            population,
+            rng,
+            stream_seed,
+            generations: VecDeque::new(),
+            first_generation: 0,
+            n_evolved_generations: 0,
+            statistics: RunStatistics::new(config.ema_smoothing),
+            ages,
+            archive: new_archive(config, index),
+            stopped: false,
+            interrupted: false,
+            error: None,
+            evaluation_cost: EvaluationCost::default(),
+            pipeline: 0,
+            best_fitness: None,
+            n_stagnant_generations: 0,
+            n_unimproved_generations: 0,
+            patience_fitness: None,
+            n_impatient_generations: 0,
+            history: config.fitness_window.map(FitnessHistory::new),
+            evaluations: EvaluationOutcomes::default(),
+            overrides: Reconfiguration::default(),
+            reconfigurations: Vec::new(),
+            frozen: Vec::new(),
+            zoom_bounds: None,
+            health_issues: Vec::new(),
+            n_duplicate_generations: 0,
+        }
+    }
+    /// Continue from the state of an earlier run, see `Runner::with_island_states`.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state the earlier run ended with.
+    fn restore(&mut self, state: IslandState) {
+        self.ages = state
+            .population
+            .iter()
+            .map(|(solution, _)| solution.clone())
+            .zip(state.ages)
+            .collect();
+        self.population = state.population;
+        self.first_generation = state.generation;
+    }
+    /// The state to continue the island from, see `OptimizationResult::islands`.
+    fn state(&self) -> IslandState {
+        IslandState {
+            population: self.population.clone(),
+            ages: self
+                .population
+                .iter()
+                .map(|(solution, _)| self.ages.get(solution).copied().unwrap_or(0))
+                .collect(),
+            generation: self.first_generation + self.n_evolved_generations,
+        }
+    }
+    /// Return whether an individual of the population reached its maximum lifespan.
+    ///
+    /// # Arguments
+    ///
+    /// * `solution` - The individual, offspring are not in the population yet and never retired.
+    /// * `config` - The configuration holding the maximum lifespan.
+    fn retired(&self, solution: &Solution, config: &RunnerConfig) -> bool {
+        config.max_lifespan.is_some_and(|max_lifespan| {
+            self.ages
+                .get(solution)
+                .is_some_and(|age| *age >= max_lifespan)
+        })
+    }
+}
+
+/// What happened during the step of a generation, besides changing the population.
+struct StepOutcome {
+    /// How many offspring were evaluated at each fidelity of successive halving.
+    rung_sizes: Vec<usize>,
+    /// Whether the evaluation was cut short by the time budget.
+    deadline_exceeded: bool,
+    /// How many individuals were retired because of their age.
+    n_retired: usize,
+    /// How many less fit offspring were accepted because of `RunnerConfig::annealing`.
+    n_accepted_worse: usize,
+    /// The outcomes of the evaluations of the step.
+    evaluations: EvaluationOutcomes,
+    /// How many offspring the pipeline produced.
+    n_offspring: usize,
+    /// How many offspring were dropped because they duplicated an individual.
+    n_duplicates: usize,
+    /// How many evaluations the step reserved from `RunnerConfig::max_evaluations`.
+    n_reserved: usize,
+}
+
+impl<E> RunContext<'_, E>
+where
+    E: Evaluator + ?Sized,
+{
+    /// Return what identifies `solution` when dropping duplicated offspring.
+    ///
+    /// # Arguments
+    ///
+    /// * `solution` - The parent or child.
+    fn key(&self, solution: &Solution) -> SolutionKey {
+        match &self.identity {
+            Some(identity) => self.precision.key(&identity.canonical(solution)),
+            None => self.precision.key(solution),
+        }
+    }
+    /// Replace the population of an island by the survivors of its parents and offspring.
+    /// Individuals that reached their maximum lifespan only survive if there are too few others.
+    ///
+    /// # Arguments
+    ///
+    /// * `island` - The island that should be evolved.
+    /// * `generation` - The index of the generation.
+    /// * `settings` - The operator settings of the generation.
+    /// * `deadline` - When the evaluation has to stop, if at all.
+    fn generational_step<G>(
+        &self,
+        island: &mut Island<G>,
+        generation: usize,
+        settings: &OperatorSettings,
+        deadline: Option<Deadline>,
+    ) -> Result<StepOutcome, OperatorError>
+    where
+        G: RngCore,
+    {
+        let offspring = self.pipeline(settings.pipeline).reproduce_with(
+            &island.population,
+            settings.mutate_prob,
+            &mut island.rng,
+            island.stream_seed.map(|seed| (seed, generation as u64)),
+            self.config.crossover_prob,
+            settings.distinct_parents,
+            &island.frozen,
+            self.config.check_operators,
+        )?;
+        let offspring = confine(offspring, island.zoom_bounds.as_deref());
+        // Parents keep the fitness they already have, duplicated offspring are dropped.
+        let mut seen = island
+            .population
+            .iter()
+            .map(|(solution, _)| self.key(solution))
+            .collect::<HashSet<SolutionKey>>();
+        let mut n_offspring = offspring.len();
+        let mut children = offspring
+            .into_iter()
+            .filter(|child| seen.insert(self.key(child)))
+            .collect::<Vec<Solution>>();
+        // Averaging the fitness needs a fresh evaluation of the parents.
+        if island.history.is_some() {
+            for (_, fitness) in island.population.iter_mut() {
+                *fitness = None;
+            }
+        }
+        let n_unevaluated = island
+            .population
+            .iter()
+            .filter(|(_, fitness)| fitness.is_none())
+            .count();
+        let time_budget_limit = self
+            .config
+            .generation_time_budget
+            .filter(|_| self.config.size_to_time_budget)
+            .and_then(|budget| island.evaluation_cost.n_evaluations(budget))
+            .map(|n_evaluations| n_evaluations.saturating_sub(n_unevaluated));
+        if let Some(limit) = time_budget_limit {
+            let mut round = 1;
+            while children.len() < limit && round < MAX_REPRODUCTION_ROUNDS {
+                let offspring = self.pipeline(settings.pipeline).reproduce_with(
+                    &island.population,
+                    settings.mutate_prob,
+                    &mut island.rng,
+                    island
+                        .stream_seed
+                        .map(|seed| (rng::stream_seed(seed, &[round as u64]), generation as u64)),
+                    self.config.crossover_prob,
+                    settings.distinct_parents,
+                    &island.frozen,
+                    self.config.check_operators,
+                )?;
+                let offspring = confine(offspring, island.zoom_bounds.as_deref());
+                n_offspring += offspring.len();
+                children.extend(
+                    offspring
+                        .into_iter()
+                        .filter(|child| seen.insert(self.key(child))),
+                );
+                round += 1;
+            }
+        }
+        let n_duplicates = n_offspring - children.len();
+        let quota_limit = self
+            .config
+            .evaluation_quota
+            .map(|quota| quota.saturating_sub(n_unevaluated));
+        let limit = quota_limit.into_iter().chain(time_budget_limit).min();
+        let n_reserved = self.reserve_evaluations(
+            n_unevaluated + limit.map_or(children.len(), |limit| limit.min(children.len())),
+        );
+        let budget_limit = self
+            .config
+            .max_evaluations
+            .map(|_| n_reserved.saturating_sub(n_unevaluated));
+        if let Some(limit) = limit.into_iter().chain(budget_limit).min() {
+            children = prioritize(
+                children,
+                &island.population,
+                limit,
+                self.config.offspring_priority,
+                &mut island.rng,
+            );
+        }
+        let n_evaluations = n_unevaluated + children.len();
+        let mut candidates = std::mem::take(&mut island.population);
+        candidates.extend(children.into_iter().map(|child| (child, None)));
+        let evaluation_start = self.clock.now();
+        let (candidates, rung_sizes, deadline_exceeded, evaluations) =
+            match &self.config.successive_halving {
+                Some(halving) => evaluate_successive_halving(
+                    candidates,
+                    self.evaluator,
+                    halving,
+                    self.config,
+                    self.penalty_function,
+                    deadline,
+                    island.archive.as_mut(),
+                    island.history.as_mut(),
+                ),
+                None => match self.config.overlap_batch_size {
+                    Some(batch_size) => {
+                        let (candidates, deadline_exceeded, evaluations) = evaluate_and_sort(
+                            candidates,
+                            self.evaluator,
+                            batch_size,
+                            self.executor,
+                            self.config,
+                            self.penalty_function,
+                            deadline,
+                            island.archive.as_mut(),
+                            island.history.as_mut(),
+                        );
+                        (candidates, Vec::new(), deadline_exceeded, evaluations)
+                    }
+                    None => {
+                        let (deadline_exceeded, evaluations) = evaluate(
+                            &mut candidates,
+                            self.evaluator,
+                            self.config,
+                            self.penalty_function,
+                            deadline,
+                            None,
+                            island.archive.as_mut(),
+                            island.history.as_mut(),
+                        );
+                        (candidates, Vec::new(), deadline_exceeded, evaluations)
+                    }
+                },
+            };
+        let n_left_unevaluated = candidates
+            .iter()
+            .filter(|(_, fitness)| fitness.is_none())
+            .count();
+        island.evaluation_cost.record(
+            n_evaluations.saturating_sub(n_left_unevaluated),
+            self.clock.elapsed_since(evaluation_start),
+        );
+        let (elites, candidates) = split_elites(
+            candidates,
+            self.config.elitism.min(self.config.size_generation),
+        );
+        let size_generation = self.config.size_generation - elites.len();
+        let (retired, candidates): (Vec<Candidate>, Vec<Candidate>) = candidates
+            .into_iter()
+            .partition(|(solution, _)| island.retired(solution, self.config));
+        // Annealing needs the candidates that didn't survive.
+        let annealing_candidates = self.config.annealing.map(|_| candidates.clone());
+        let mut survivors = select_survivors(
+            candidates,
+            size_generation,
+            island
+                .overrides
+                .survivor_selection
+                .as_ref()
+                .unwrap_or(&self.config.survivor_selection),
+            &mut island.rng,
+        );
+        let n_accepted_worse = match (self.config.annealing, annealing_candidates) {
+            (Some(annealing), Some(candidates)) => accept_worse(
+                &mut survivors,
+                candidates,
+                annealing,
+                generation,
+                &mut island.rng,
+            ),
+            _ => 0,
+        };
+        let n_missing = size_generation
+            .saturating_sub(survivors.len())
+            .min(retired.len());
+        let n_retired = retired.len() - n_missing;
+        survivors.extend(select(retired, n_missing));
+        island.population = elites;
+        island.population.extend(survivors);
+        if let Some(history) = island.history.as_mut() {
+            history.retain(&island.population);
+        }
+        Ok(StepOutcome {
+            rung_sizes,
+            deadline_exceeded,
+            n_retired,
+            n_accepted_worse,
+            evaluations,
+            n_offspring,
+            n_duplicates,
+            n_reserved,
+        })
+    }
+    /// Return whether the islands spent all of `RunnerConfig::max_evaluations`, including the
+    /// evaluations reserved by generations that are being evaluated.
+    fn evaluations_exhausted(&self) -> bool {
+        self.config.max_evaluations.is_some_and(|max_evaluations| {
+            self.n_evaluations.load(AtomicOrdering::SeqCst) >= max_evaluations
+        })
+    }
+    /// Reserve up to `n_wanted` evaluations of what is left of `RunnerConfig::max_evaluations`
+    /// and return how many were reserved, `0` without a maximum.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_wanted` - How many evaluations the generation would like to spend.
+    fn reserve_evaluations(&self, n_wanted: usize) -> usize {
+        let Some(max_evaluations) = self.config.max_evaluations else {
+            return 0;
+        };
+        let mut n_reserved = 0;
+        // The closure always returns `Some`, so the update can't fail.
+        let _ = self.n_evaluations.fetch_update(
+            AtomicOrdering::SeqCst,
+            AtomicOrdering::SeqCst,
+            |n_evaluations| {
+                n_reserved = n_wanted.min(max_evaluations.saturating_sub(n_evaluations));
+                Some(n_evaluations + n_reserved)
+            },
+        );
+        n_reserved
+    }
+    /// Insert a few evaluated offspring into the population of an island according to the
+    /// replacement policy. An offspring always replaces the oldest individual if that one
+    /// reached its maximum lifespan.
+    ///
+    /// # Arguments
+    ///
+    /// * `island` - The island that should be evolved.
+    /// * `generation` - The index of the generation.
+    /// * `settings` - The operator settings of the generation.
+    /// * `steady_state` - How many offspring are inserted and whom they replace.
+    /// * `deadline` - When the evaluation has to stop, if at all.
+    fn steady_state_step<G>(
+        &self,
+        island: &mut Island<G>,
+        generation: usize,
+        settings: &OperatorSettings,
+        steady_state: &SteadyState,
+        deadline: Option<Deadline>,
+    ) -> Result<StepOutcome, OperatorError>
+    where
+        G: RngCore,
+    {
+        // Only the initial population, or the rest of it after an exceeded deadline, lacks a
+        // fitness. It is cut down to the size of a generation once it is evaluated. Averaging
+        // the fitness evaluates the whole population again.
+        if island.history.is_some() {
+            for (_, fitness) in island.population.iter_mut() {
+                *fitness = None;
+            }
+        }
+        let n_unevaluated = island
+            .population
+            .iter()
+            .filter(|(_, fitness)| fitness.is_none())
+            .count();
+        let (mut deadline_exceeded, mut evaluations) = evaluate(
+            &mut island.population,
+            self.evaluator,
+            self.config,
+            self.penalty_function,
+            deadline,
+            None,
+            island.archive.as_mut(),
+            island.history.as_mut(),
+        );
+        if island.population.len() > self.config.size_generation {
+            island.population = select(
+                std::mem::take(&mut island.population),
+                self.config.size_generation,
+            );
+        }
+        if deadline_exceeded {
+            return Ok(StepOutcome {
+                rung_sizes: Vec::new(),
+                deadline_exceeded,
+                n_retired: 0,
+                n_accepted_worse: 0,
+                evaluations,
+                n_offspring: 0,
+                n_duplicates: 0,
+                n_reserved: 0,
+            });
+        }
+        let offspring = self.pipeline(settings.pipeline).reproduce_with(
+            &island.population,
+            settings.mutate_prob,
+            &mut island.rng,
+            island.stream_seed.map(|seed| (seed, generation as u64)),
+            self.config.crossover_prob,
+            settings.distinct_parents,
+            &island.frozen,
+            self.config.check_operators,
+        )?;
+        let offspring = confine(offspring, island.zoom_bounds.as_deref());
+        let mut seen = island
+            .population
+            .iter()
+            .map(|(solution, _)| self.key(solution))
+            .collect::<HashSet<SolutionKey>>();
+        let n_offspring = offspring.len();
+        let unique = offspring
+            .into_iter()
+            .filter(|child| seen.insert(self.key(child)))
+            .collect::<Vec<Solution>>();
+        let n_duplicates = n_offspring - unique.len();
+        let n_children = match self.config.evaluation_quota {
+            Some(quota) => steady_state
+                .n_offspring
+                .max(1)
+                .min(quota.saturating_sub(n_unevaluated)),
+            None => steady_state.n_offspring.max(1),
+        };
+        let mut children = prioritize(
+            unique,
+            &island.population,
+            n_children,
+            self.config.offspring_priority,
+            &mut island.rng,
+        )
+        .into_iter()
+        .map(|child| (child, None))
+        .collect::<Vec<Candidate>>();
+        let children_evaluations;
+        (deadline_exceeded, children_evaluations) = evaluate(
+            &mut children,
+            self.evaluator,
+            self.config,
+            self.penalty_function,
+            deadline,
+            None,
+            island.archive.as_mut(),
+            island.history.as_mut(),
+        );
+        evaluations.merge(&children_evaluations);
+        let mut n_retired = 0;
+        let mut n_accepted_worse = 0;
+        for child in children
+            .into_iter()
+            .filter(|(_, fitness)| fitness.is_some())
+        {
+            // The population is ordered by age, so the first individual is the oldest.
+            if island
+                .population
+                .first()
+                .is_some_and(|(solution, _)| island.retired(solution, self.config))
+            {
+                island.population.remove(0);
+                island.population.push(child);
+                n_retired += 1;
+            } else {
+                let rng = &mut island.rng;
+                let mut accept_worse = |loss| {
+                    let accepted = self
+                        .config
+                        .annealing
+                        .is_some_and(|annealing| annealing.accepts(loss, generation, rng));
+                    n_accepted_worse += usize::from(accepted);
+                    accepted
+                };
+                replace(
+                    &mut island.population,
+                    child,
+                    steady_state.replacement,
+                    &mut accept_worse,
+                );
+            }
+        }
+        if let Some(history) = island.history.as_mut() {
+            history.retain(&island.population);
+        }
+        Ok(StepOutcome {
+            rung_sizes: Vec::new(),
+            deadline_exceeded,
+            n_retired,
+            n_accepted_worse,
+            evaluations,
+            n_offspring,
+            n_duplicates,
+            n_reserved: 0,
+        })
+    }
+    /// The operator settings the next generation of an island is evolved with.
+    ///
+    /// # Arguments
+    ///
+    /// * `island` - The island that is about to evolve.
+    /// * `generation` - The index of the generation.
+    fn operator_settings<G>(&self, island: &Island<G>, generation: usize) -> OperatorSettings {
+        OperatorSettings {
+            mutate_prob: island.overrides.mutate_prob.unwrap_or_else(|| {
+                self.config
+                    .mutate_schedule
+                    .map_or(self.config.mutate_prob, |schedule| {
+                        schedule.mutate_prob(generation, self.config.n_generations)
+                    })
+            }),
+            pipeline: island.pipeline,
+            temperature: self
+                .config
+                .annealing
+                .map(|annealing| annealing.temperature(generation)),
+            distinct_parents: island
+                .overrides
+                .distinct_parents
+                .unwrap_or(self.config.distinct_parents),
+        }
+    }
+    /// Which arguments of the population are frozen during a generation, see `Freeze`.
+    ///
+    /// # Arguments
+    ///
+    /// * `island` - The island about to evolve a generation.
+    /// * `generation` - The index of the generation.
+    /// * `settings` - The operator settings of the generation.
+    fn frozen<G>(
+        &self,
+        island: &Island<G>,
+        generation: usize,
+        settings: &OperatorSettings,
+    ) -> Vec<bool> {
+        let Some(freeze) = self
+            .config
+            .freeze
+            .filter(|freeze| generation >= freeze.after_generations)
+        else {
+            return Vec::new();
+        };
+        let bounds = self
+            .pipeline(settings.pipeline)
+            .bounds()
+            .unwrap_or(&self.span);
+        let mut spread = vec![RunningStats::new(); bounds.len()];
+        for (solution, _) in &island.population {
+            for (stats, argument) in spread.iter_mut().zip(solution.get_arguments()) {
+                stats.push(argument);
+            }
+        }
+        let frozen = spread
+            .iter()
+            .zip(bounds)
+            .map(|(stats, bound)| {
+                stats.population_variance().is_some_and(|variance| {
+                    variance.sqrt() < freeze.min_std * (bound.end - bound.start)
+                })
+            })
+            .collect::<Vec<bool>>();
+        if frozen.iter().all(|frozen| *frozen) {
+            Vec::new()
+        } else {
+            frozen
+        }
+    }
+    /// Update the bounds offspring are confined to during a generation, see `Zoom`.
+    ///
+    /// # Arguments
+    ///
+    /// * `island` - The island about to evolve a generation.
+    /// * `generation` - The index of the generation.
+    /// * `settings` - The operator settings of the generation.
+    fn zoom<G>(&self, island: &mut Island<G>, generation: usize, settings: &OperatorSettings) {
+        let Some(zoom) = self.config.zoom else {
+            return;
+        };
+        let full = self
+            .pipeline(settings.pipeline)
+            .bounds()
+            .unwrap_or(&self.span);
+        let stagnated = zoom.expand_after > 0
+            && island.n_unimproved_generations > 0
+            && island
+                .n_unimproved_generations
+                .is_multiple_of(zoom.expand_after);
+        let current = match &island.zoom_bounds {
+            Some(bounds) if bounds.len() == full.len() && !stagnated => bounds,
+            _ => {
+                island.zoom_bounds = Some(full.to_vec());
+                return;
+            }
+        };
+        let Some((fittest, _)) = fittest(&island.population) else {
+            return;
+        };
+        if !generation.is_multiple_of(zoom.every_generations.max(1)) {
+            return;
+        }
+        let zoomed = current
+            .iter()
+            .zip(full)
+            .zip(fittest.get_arguments())
+            .map(|((bound, full), center)| {
+                let full_width = full.end - full.start;
+                let width = ((bound.end - bound.start) * zoom.factor.clamp(0.0, 1.0))
+                    .max(zoom.min_width * full_width)
+                    .min(full_width);
+                let start = (center - width / 2.0).clamp(full.start, full.end - width);
+                start..start + width
+            })
+            .collect();
+        island.zoom_bounds = Some(zoomed);
+    }
+    /// Apply the reconfigurations of a spawned run the island didn't apply yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `island` - The island about to evolve a generation.
+    /// * `generation` - The index of the generation.
+    fn reconfigure<G>(&self, island: &mut Island<G>, generation: usize) {
+        if let Some(control) = self.control {
+            for changes in control.reconfigurations_since(island.reconfigurations.len()) {
+                island.overrides.merge(&changes);
+                if let Some(pipeline) = changes.pipeline {
+                    island.pipeline = pipeline;
+                    island.n_stagnant_generations = 0;
+                }
+                island.reconfigurations.push(Reconfigured {
+                    island: island.index,
+                    generation,
+                    changes,
+                });
+            }
+        }
+    }
+    /// The pipeline with the given index, `0` being the pipeline of the runner.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index, see `GenerationReport::pipeline`.
+    fn pipeline(&self, index: usize) -> &Pipeline {
+        match index.checked_sub(1) {
+            Some(fallback) => &self.fallback_pipelines[fallback],
+            None => self.pipeline,
+        }
+    }
+    /// Count the generations the best fitness of an island didn't improve and switch it to the
+    /// next fallback pipeline once it stagnated for too long.
+    ///
+    /// # Arguments
+    ///
+    /// * `island` - The island that just evolved a generation.
+    /// * `best_fitness` - The best fitness of the generation, if any.
+    fn update_stagnation<G>(&self, island: &mut Island<G>, best_fitness: Option<f64>) {
+        let improved = best_fitness.is_some_and(|fitness| {
+            island
+                .best_fitness
+                .is_none_or(|best_fitness| fitness > best_fitness)
+        });
+        if improved {
+            island.best_fitness = best_fitness;
+            island.n_stagnant_generations = 0;
+            island.n_unimproved_generations = 0;
+            return;
+        }
+        island.n_stagnant_generations += 1;
+        island.n_unimproved_generations += 1;
+        if island.n_stagnant_generations >= self.stagnation_generations
+            && island.pipeline < self.fallback_pipelines.len()
+        {
+            island.pipeline += 1;
+            island.n_stagnant_generations = 0;
+        }
+    }
+    /// Count the generations the best fitness of an island didn't improve by more than
+    /// `Patience::epsilon` and return whether it did so for `Patience::generations`.
+    ///
+    /// # Arguments
+    ///
+    /// * `island` - The island that just evolved a generation.
+    /// * `best_fitness` - The best fitness of the generation, if any.
+    fn update_patience<G>(&self, island: &mut Island<G>, best_fitness: Option<f64>) -> bool {
+        let Some(patience) = self.config.patience else {
+            return false;
+        };
+        let improved = best_fitness.is_some_and(|fitness| {
+            island
+                .patience_fitness
+                .is_none_or(|best_fitness| fitness > best_fitness + patience.epsilon)
+        });
+        if improved {
+            island.patience_fitness = best_fitness;
+            island.n_impatient_generations = 0;
+        } else {
+            island.n_impatient_generations += 1;
+        }
+        island.n_impatient_generations >= patience.generations.max(1)
+    }
+    /// Polish the fittest individuals of the final population with a pattern search, within
+    /// the bounds of the pipeline or those spanned by the initial population, and report it.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The result of the evolution.
+    /// * `candidates` - The final population of all islands and their fitness.
+    /// * `polish` - The settings of the polish.
+    /// * `budget` - How many evaluations the polish may spend.
+    fn polish(
+        &self,
+        mut result: OptimizationResult,
+        mut candidates: Vec<Candidate>,
+        polish: &Polish,
+        budget: usize,
+    ) -> OptimizationResult
+    where
+        E: Evaluator,
+    {
+        let fitness_before = result.best.as_ref().map(|(_, fitness)| *fitness);
+        candidates.retain(|(_, fitness)| fitness.is_some());
+        candidates.sort_by(|candidate_a, candidate_b| {
+            compare_fitness(ranked(candidate_a), ranked(candidate_b))
+        });
+        candidates.truncate(polish.n_candidates);
+        let n_candidates = candidates.len().max(1);
+        let bounds = self.pipeline.bounds().unwrap_or(&self.span);
+        let mut outcomes = EvaluationOutcomes::default();
+        let mut polished = Vec::new();
+        for (idx, (solution, fitness)) in candidates.into_iter().enumerate() {
+            // The first candidates get the remainder of the budget.
+            let share = budget / n_candidates + usize::from(idx < budget % n_candidates);
+            let fitness = fitness.unwrap_or(f64::NAN);
+            let search = match polish.method {
+                PolishMethod::PatternSearch => polish::pattern_search,
+                PolishMethod::TrustRegion => polish::trust_region_search,
+            };
+            let (improved, improved_fitness) = search(
+                (solution.clone(), fitness),
+                bounds,
+                polish.step,
+                share,
+                self.evaluator,
+                &mut outcomes,
+            );
+            if improved != solution {
+                self.hall_of_fame
+                    .lock()
+                    .unwrap()
+                    .offer(&improved, improved_fitness);
+                if result
+                    .best
+                    .as_ref()
+                    .is_none_or(|(_, best_fitness)| improved_fitness > *best_fitness)
+                {
+                    result.best = Some((improved.clone(), improved_fitness));
+                }
+                polished.push((solution, improved));
+            }
+        }
+        let population = result
+            .population
+            .iter()
+            .map(|solution| {
+                polished
+                    .iter()
+                    .find(|(original, _)| original == solution)
+                    .map_or_else(|| solution.clone(), |(_, improved)| improved.clone())
+            })
+            .collect::<Vec<Solution>>();
+        result.population = Solutions::from(population);
+        result.evaluations.merge(&outcomes);
+        result.polish = Some(PolishReport {
+            n_evaluations: outcomes.n_evaluations(),
+            fitness_before,
+            fitness_after: result.best.as_ref().map(|(_, fitness)| *fitness),
+        });
+        result
+    }
+    /// Restart the island if its best fitness stagnated for `Restart::stagnation_generations`
+    /// generations, and return whether it did.
+    ///
+    /// # Arguments
+    ///
+    /// * `island` - The island that just evolved a generation.
+    /// * `settings` - The operator settings of the generation, the bounds of its pipeline are
+    ///   used.
+    fn restart<G>(&self, island: &mut Island<G>, settings: &OperatorSettings) -> bool
+    where
+        G: RngCore,
+    {
+        let restart = match self.config.restart {
+            Some(restart) if island.n_unimproved_generations >= restart.stagnation_generations => {
+                restart
+            }
+            _ => return false,
+        };
+        island.n_unimproved_generations = 0;
+        let bounds = self
+            .pipeline(settings.pipeline)
+            .bounds()
+            .unwrap_or(&self.span);
+        let mut elites = std::mem::take(&mut island.population);
+        elites.sort_by(|candidate_a, candidate_b| {
+            compare_fitness(ranked(candidate_a), ranked(candidate_b))
+        });
+        elites.truncate(restart.n_elites.min(self.config.size_generation));
+        let n_new = self.config.size_generation - elites.len();
+        let n_quasi_opposite = if elites.is_empty() {
+            0
+        } else {
+            (n_new as f64 * restart.quasi_opposition.clamp(0.0, 1.0)).round() as usize
+        };
+        let mut seen = elites
+            .iter()
+            .map(|(solution, _)| self.key(solution))
+            .collect::<HashSet<SolutionKey>>();
+        let mut population = elites.clone();
+        // Give up on points that keep colliding, e.g. within degenerate bounds.
+        for attempt in 0..n_new * MAX_RESTART_ATTEMPTS {
+            if population.len() == self.config.size_generation {
+                break;
+            }
+            let n_drawn = population.len() - elites.len();
+            let solution = if n_drawn < n_quasi_opposite {
+                let (elite, _) = &elites[attempt % elites.len()];
+                initializer::quasi_opposite(elite, bounds, &mut island.rng)
+            } else {
+                initializer::uniform(bounds, &mut island.rng)
+            };
+            if seen.insert(self.key(&solution)) {
+                population.push((solution, None));
+            }
+        }
+        island.population = population;
+        if let Some(history) = &mut island.history {
+            history.retain(&island.population);
+        }
+        true
+    }
+    /// Offer the evaluated individuals of an island to the hall of fame and report a new best
+    /// individual.
+    ///
+    /// # Arguments
+    ///
+    /// * `island` - The island that just evolved a generation.
+    /// * `generation` - The index of the generation.
+    fn update_hall_of_fame<G>(&self, island: &Island<G>, generation: usize) {
+        let mut hall_of_fame = self.hall_of_fame.lock().unwrap();
+        let previous_best = hall_of_fame.best().map(|(solution, _)| solution.clone());
+        for (solution, fitness) in &island.population {
+            if let Some(fitness) = fitness {
+                hall_of_fame.offer(solution, *fitness);
+            }
+        }
+        let improvement = match hall_of_fame.best() {
+            Some((best, fitness)) if previous_best.as_ref() != Some(best) => Improvement {
+                island: island.index,
+                generation,
+                elapsed: self.clock.elapsed_since(self.start),
+                solution: best.clone(),
+                fitness,
+            },
+            _ => return,
+        };
+        if let Some(on_new_best) = self.on_new_best {
+            on_new_best(&improvement);
+        }
+        hall_of_fame.record_improvement(improvement);
+    }
+    /// Report the health issues of the population of an island that arose with the latest
+    /// generation, see `HealthIssue`.
+    ///
+    /// # Arguments
+    ///
+    /// * `island` - The island that just evolved a generation.
+    /// * `generation` - The index of the generation.
+    /// * `report` - The report of the generation.
+    /// * `n_offspring` - How many offspring the pipeline produced in the generation.
+    /// * `n_duplicates` - How many of them were dropped as duplicates.
+    fn check_health<G>(
+        &self,
+        island: &mut Island<G>,
+        generation: usize,
+        report: &GenerationReport,
+        n_offspring: usize,
+        n_duplicates: usize,
+    ) {
+        if n_offspring > 0 && 2 * n_duplicates >= n_offspring {
+            island.n_duplicate_generations += 1;
+        } else if n_offspring > 0 {
+            island.n_duplicate_generations = 0;
+        }
+        let mut issues = Vec::new();
+        if let Some((first, _)) = island.population.first() {
+            let first = self.key(first);
+            if self.config.size_generation > 1
+                && island
+                    .population
+                    .iter()
+                    .all(|(solution, _)| self.key(solution) == first)
+            {
+                issues.push(HealthIssue::IdenticalPopulation {
+                    n_individuals: island.population.len(),
+                });
+            }
+        }
+        if let (Some(min), Some(max)) = (report.fitness.min(), report.fitness.max()) {
+            if report.n_evaluated > 1 && min == max {
+                issues.push(HealthIssue::ZeroFitnessVariance {
+                    fitness: min,
+                    n_evaluated: report.n_evaluated,
+                });
+            }
+        }
+        if island.n_duplicate_generations >= DUPLICATE_WARNING_GENERATIONS {
+            issues.push(HealthIssue::RepeatedDuplicates {
+                n_generations: island.n_duplicate_generations,
+                n_duplicates,
+                n_offspring,
+            });
+        }
+        // Only issues that weren't there after the previous generation are new.
+        let is_new = |issue: &HealthIssue| {
+            !island
+                .health_issues
+                .iter()
+                .any(|known| std::mem::discriminant(known) == std::mem::discriminant(issue))
+        };
+        if let Some(on_health_warning) = self.on_health_warning {
+            for issue in issues.iter().filter(|issue| is_new(issue)) {
+                on_health_warning(&HealthWarning {
+                    island: island.index,
+                    generation,
+                    issue: issue.clone(),
+                });
+            }
+        }
+        island.health_issues = issues;
+    }
+    /// Evolve an island for `n_generations` generations.
+    ///
+    /// # Arguments
+    ///
+    /// * `island` - The island that should be evolved.
+    /// * `n_generations` - How many generations the island should evolve.
+    fn evolve<G>(&self, island: &mut Island<G>, n_generations: usize)
+    where
+        G: RngCore,
+    {
+        for generation in island.first_generation..n_generations {
+            // Other islands may have spent the rest of the budget meanwhile.
+            if self.evaluations_exhausted() {
+                island.stopped = true;
+                break;
+            }
+            let (report, settings) = match self.evolve_generation(island, generation) {
+                Ok(evolved) => evolved,
+                Err(error) => {
+                    island.error = Some(error);
+                    break;
+                }
+            };
+            let reached_target = self
+                .config
+                .target_value
+                .zip(report.best_fitness)
+                .is_some_and(|(target, best_fitness)| {
+                    best_fitness >= self.config.direction.fitness(target)
+                });
+            let converged = self.update_patience(island, report.best_fitness);
+            let stop = reached_target
+                || converged
+                || self.evaluations_exhausted()
+                || self.stopping_predicate.is_some_and(|predicate| {
+                    predicate(&Generation {
+                        index: generation,
+                        island: island.index,
+                        report: &report,
+                        population: &island.population,
+                        settings,
+                        elapsed: self.clock.elapsed_since(self.start),
+                    })
+                });
+            island.n_evolved_generations += 1;
+            island.statistics.push_generation(&report.fitness);
+            island.evaluations.merge(&report.evaluations);
+            // The history is a ring buffer of the latest reports.
+            let max_history = self.config.max_history.unwrap_or(usize::MAX);
+            if max_history > 0 {
+                if island.generations.len() >= max_history {
+                    island.generations.pop_front();
+                }
+                island.generations.push_back(report);
+            }
+            if stop {
+                island.stopped = true;
+                break;
+            }
+            let halted = self
+                .control
+                .is_some_and(|control| !control.generation_finished(fittest(&island.population)))
+                || self
+                    .interrupt
+                    .is_some_and(|interrupt| interrupt.load(AtomicOrdering::SeqCst));
+            if halted {
+                // Halting after the last generation doesn't cut the run short.
+                island.interrupted = generation + 1 < n_generations;
+                break;
+            }
+        }
+        if let Some(control) = self.control {
+            control.island_ended();
+        }
+    }
+    /// Evolve a single generation of an island and report what happened.
+    ///
+    /// # Arguments
+    ///
+    /// * `island` - The island that should be evolved.
+    /// * `generation` - The index of the generation.
+    fn evolve_generation<G>(
+        &self,
+        island: &mut Island<G>,
+        generation: usize,
+    ) -> Result<(GenerationReport, OperatorSettings), OperatorError>
+    where
+        G: RngCore,
+    {
+        let deadline = self.config.generation_time_budget.map(|budget| Deadline {
+            clock: self.clock,
+            at: self.clock.now() + budget,
+        });
+        self.reconfigure(island, generation);
+        let settings = self.operator_settings(island, generation);
+        island.frozen = self.frozen(island, generation, &settings);
+        self.zoom(island, generation, &settings);
+        let outcome = match &self.config.steady_state {
+            Some(steady_state) => {
+                self.steady_state_step(island, generation, &settings, steady_state, deadline)
+            }
+            None => self.generational_step(island, generation, &settings, deadline),
+        }?;
+        // Replace the reservation by the evaluations actually spent.
+        let n_evaluations = outcome.evaluations.n_evaluations();
+        if n_evaluations >= outcome.n_reserved {
+            self.n_evaluations
+                .fetch_add(n_evaluations - outcome.n_reserved, AtomicOrdering::SeqCst);
+        } else {
+            self.n_evaluations
+                .fetch_sub(outcome.n_reserved - n_evaluations, AtomicOrdering::SeqCst);
+        }
+        island.ages = island
+            .population
+            .iter()
+            .map(|(solution, _)| {
+                let age = island.ages.get(solution).map_or(0, |age| age + 1);
+                (solution.clone(), age)
+            })
+            .collect();
+        let mut age = RunningStats::new();
+        for (solution, _) in &island.population {
+            age.push(island.ages[solution] as f64);
+        }
+
+        let n_evaluated = island
+            .population
+            .iter()
+            .filter(|(_, fitness)| fitness.is_some())
+            .count();
+        let mut fitness = RunningStats::new();
+        for (_, individual_fitness) in &island.population {
+            individual_fitness.inspect(|value| fitness.push(*value));
+        }
+        let mut report = GenerationReport {
+            island: island.index,
+            generation,
+            n_evaluated,
+            n_unevaluated: island.population.len() - n_evaluated,
+            deadline_exceeded: outcome.deadline_exceeded,
+            fitness,
+            age,
+            n_retired: outcome.n_retired,
+            n_accepted_worse: outcome.n_accepted_worse,
+            evaluations: outcome.evaluations,
+            best_fitness: fittest_ref(&island.population).map(|(_, fitness)| fitness),
+            rung_sizes: outcome.rung_sizes,
+            pipeline: settings.pipeline,
+            restarted: false,
+            n_frozen: island.frozen.iter().filter(|frozen| **frozen).count(),
+            bounds: island.zoom_bounds.clone(),
+        };
+        self.update_stagnation(island, report.best_fitness);
+        self.update_hall_of_fame(island, generation);
+        self.check_health(
+            island,
+            generation,
+            &report,
+            outcome.n_offspring,
+            outcome.n_duplicates,
+        );
+        report.restarted = self.restart(island, &settings);
+        Ok((report, settings))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::evaluation::CountingEvaluator;
+    use crate::function::{Function, FunctionError};
+    use crate::pipeline::{Stage, Violation};
+    use crate::test_objects;
+    use rand::rngs::StdRng;
+    use std::sync::Mutex;
+
+    /// Records the size of every chunk it is asked to evaluate.
+    struct ChunkRecorder {
+        function: Function,
+        chunk_sizes: Mutex<Vec<usize>>,
+    }
+    impl Evaluator for ChunkRecorder {
+        fn evaluate_chunk(&self, chunk: &[Solution]) -> Vec<Result<f64, FunctionError>> {
+            self.chunk_sizes.lock().unwrap().push(chunk.len());
+            self.function.evaluate_chunk(chunk)
+        }
+    }
+    /// Advances a manual clock by a fixed time per evaluated solution.
+    struct ClockedEvaluator {
+        function: Function,
+        clock: Arc<ManualClock>,
+        per_evaluation: Duration,
+    }
+    impl Evaluator for ClockedEvaluator {
+        fn evaluate_chunk(&self, chunk: &[Solution]) -> Vec<Result<f64, FunctionError>> {
+            self.clock.advance(self.per_evaluation * chunk.len() as u32);
+            self.function.evaluate_chunk(chunk)
+        }
+    }
+    /// Fails in a different way depending on the first argument.
+    struct FlakyEvaluator;
+    impl Evaluator for FlakyEvaluator {
+        fn evaluate_chunk(&self, chunk: &[Solution]) -> Vec<Result<f64, FunctionError>> {
+            chunk
+                .iter()
+                .map(|solution| match solution.get_arguments()[0] {
+                    x if x < 2.0 => Err(FunctionError::WrongNumberOfEntries {
+                        expected_number_of_entries: 2,
+                        actual_number_of_entries: 1,
+                    }),
+                    x if x < 4.0 => Ok(f64::NAN),
+                    x if x < 6.0 => Err(FunctionError::Timeout("too slow".to_string())),
+                    x if x < 8.0 => panic!("The objective crashed"),
+                    x => Ok(x),
+                })
+                .collect()
+        }
+    }
+    /// Drops the fitness of the last solution of every chunk.
+    struct ShortEvaluator;
+    impl Evaluator for ShortEvaluator {
+        fn evaluate_chunk(&self, chunk: &[Solution]) -> Vec<Result<f64, FunctionError>> {
+            chunk[1..]
+                .iter()
+                .map(|solution| Ok(solution.get_arguments()[0]))
+                .collect()
+        }
+    }
+    fn chunk_recorder() -> ChunkRecorder {
+        ChunkRecorder {
+            function: Function::new(test_objects::triple_multiplication()),
+            chunk_sizes: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn config() -> RunnerConfig {
+        RunnerConfig {
+            n_generations: 3,
+            size_generation: 5,
+            ..RunnerConfig::default()
+        }
+    }
+    #[test]
+    fn single_threaded_run() {
+        let result = Runner::new(config()).run(
+            Solutions::random(5, 1.0..10.0, 3),
+            &Function::new(test_objects::triple_multiplication()),
+        );
+        assert_eq!(result.population.iter().count(), 5);
+        assert_eq!(result.generations.len(), 3);
+        assert!(result
+            .generations
+            .iter()
+            .all(|report| report.n_unevaluated == 0 && !report.deadline_exceeded));
+        assert!(result.best.is_some());
+    }
+    #[test]
+    fn multi_threaded_run() {
+        let result = Runner::new(RunnerConfig {
+            n_jobs: Parallelism::Jobs(2),
+            ..config()
+        })
+        .run(
+            Solutions::random(5, 1.0..10.0, 3),
+            &Function::new(test_objects::triple_multiplication()),
+        );
+        // Every island runs `n_generations / n_jobs + 1` generations.
+        assert_eq!(result.generations.len(), 4);
+        assert!(result.generations.iter().any(|report| report.island == 1));
+    }
+    #[test]
+    fn multi_threaded_run_with_a_capturing_closure() {
+        let target = vec![2.0, 4.0, 6.0];
+        let function = Function::new(move |x| {
+            Ok(-x
+                .iter()
+                .zip(&target)
+                .map(|(x, target)| (x - target).powi(2))
+                .sum::<f64>())
+        });
+        let result = Runner::new(RunnerConfig {
+            n_jobs: Parallelism::Jobs(2),
+            ..config()
+        })
+        .run(Solutions::random(5, 1.0..10.0, 3), &function);
+        assert!(result.best.is_some());
+    }
+    #[test]
+    fn exceeded_deadline_leaves_individuals_unevaluated() {
+        let result = Runner::new(RunnerConfig {
+            generation_time_budget: Some(Duration::ZERO),
+            ..config()
+        })
+        .run(
+            Solutions::random(5, 1.0..10.0, 3),
+            &Function::new(test_objects::triple_multiplication()),
+        );
+        assert_eq!(result.population.iter().count(), 5);
+        assert!(result.best.is_none());
+        assert!(result
+            .generations
+            .iter()
+            .all(|report| report.deadline_exceeded && report.n_unevaluated == 5));
+    }
+    #[test]
+    fn time_budget_sizes_the_offspring() {
+        let n_evaluations = |size_to_time_budget| {
+            let clock = Arc::new(ManualClock::new());
+            let evaluator = ClockedEvaluator {
+                function: Function::new(|x| Ok(x.iter().sum())),
+                clock: Arc::clone(&clock),
+                per_evaluation: Duration::from_millis(2),
+            };
+            Runner::new(RunnerConfig {
+                n_generations: 4,
+                generation_time_budget: Some(Duration::from_millis(160)),
+                size_to_time_budget,
+                seed: Some(1),
+                ..config()
+            })
+            .with_clock(clock.clone())
+            .run(Solutions::random(5, 1.0..10.0, 3), &evaluator);
+            clock.elapsed().as_millis() / 2
+        };
+        let fixed = n_evaluations(false);
+        let sized = n_evaluations(true);
+        // A generation brings about 20 offspring, 160ms fit about 70 evaluations.
+        assert!(sized > 2 * fixed);
+    }
+    #[test]
+    fn time_budget_is_told_by_the_clock() {
+        let clock = Arc::new(ManualClock::new());
+        let evaluator = ClockedEvaluator {
+            function: Function::new(test_objects::triple_multiplication()),
+            clock: Arc::clone(&clock),
+            per_evaluation: Duration::from_millis(10),
+        };
+        let result = Runner::new(RunnerConfig {
+            generation_time_budget: Some(Duration::from_millis(25)),
+            evaluation_chunk_size: 1,
+            ..config()
+        })
+        .with_clock(clock.clone())
+        .run(Solutions::random(5, 1.0..10.0, 3), &evaluator);
+        // Every generation evaluates at 0ms, 10ms and 20ms and stops at 30ms.
+        assert!(result
+            .generations
+            .iter()
+            .all(|report| report.deadline_exceeded));
+        assert_eq!(clock.elapsed(), Duration::from_millis(3 * 3 * 10));
+        assert!(result
+            .hall_of_fame
+            .improvements()
+            .iter()
+            .all(|improvement| improvement.elapsed <= clock.elapsed()));
+    }
+    #[test]
+    fn converged_arguments_freeze() {
+        // The first argument starts converged and nothing but the mutation could change it.
+        let initial_population = Solutions::from(
+            (1..=5)
+                .map(|idx| Solution::new(vec![5.0, idx as f64, 10.0 - idx as f64]))
+                .collect::<Vec<Solution>>(),
+        );
+        let result = Runner::new(RunnerConfig {
+            n_generations: 5,
+            mutate_prob: 1.0,
+            freeze: Some(Freeze {
+                after_generations: 0,
+                min_std: 0.01,
+            }),
+            seed: Some(5),
+            ..config()
+        })
+        .with_bounds(vec![0.0..10.0; 3])
+        .run(
+            initial_population,
+            &Function::new(test_objects::triple_multiplication()),
+        );
+        assert!(result.generations.iter().all(|report| report.n_frozen >= 1));
+        assert!(result
+            .population
+            .iter()
+            .all(|solution| solution.get_arguments()[0] == 5.0));
+    }
+    #[test]
+    fn polish_replaces_the_last_generations() {
+        let function = Function::new(|x| Ok(-x.iter().map(|x| (x - 2.0).powi(2)).sum::<f64>()));
+        for method in [PolishMethod::PatternSearch, PolishMethod::TrustRegion] {
+            let result = Runner::new(RunnerConfig {
+                n_generations: 10,
+                seed: Some(3),
+                polish: Some(Polish {
+                    share: 0.5,
+                    n_candidates: 2,
+                    step: 0.01,
+                    method,
+                }),
+                ..config()
+            })
+            .with_bounds(vec![0.0..5.0; 3])
+            .run(Solutions::random(5, 0.0..5.0, 3), &function);
+            assert_eq!(result.n_evolved_generations, 5);
+            let report = result.polish.unwrap();
+            let n_evolved_evaluations = result.evaluations.n_evaluations() - report.n_evaluations;
+            assert!(report.n_evaluations > 0);
+            assert!(report.n_evaluations <= n_evolved_evaluations);
+            assert!(report.improvement() > 0.0);
+            assert_eq!(
+                report.fitness_after,
+                result.best.as_ref().map(|(_, fitness)| *fitness)
+            );
+            assert!(result.population.iter().any(
+                |solution| Some(solution) == result.best.as_ref().map(|(solution, _)| solution)
+            ));
+        }
+    }
+    #[test]
+    fn restarts_seed_quasi_opposite_points_of_the_elites() {
+        let restarted = Arc::new(Mutex::new(Vec::new()));
+        let restarted_in_predicate = Arc::clone(&restarted);
+        let result = Runner::new(RunnerConfig {
+            n_generations: 4,
+            restart: Some(Restart {
+                stagnation_generations: 1,
+                n_elites: 1,
+                quasi_opposition: 1.0,
+            }),
+            ..config()
+        })
+        .with_bounds(vec![0.0..10.0; 3])
+        .with_stopping_predicate(move |generation: &Generation| {
+            if generation.report.restarted {
+                restarted_in_predicate
+                    .lock()
+                    .unwrap()
+                    .push(generation.population.to_vec());
+            }
+            false
+        })
+        // The best fitness never improves after the first generation.
+        .run(
+            Solutions::random(5, 1.0..9.0, 3),
+            &Function::new(|_| Ok(1.0)),
+        );
+        assert_eq!(
+            result
+                .generations
+                .iter()
+                .map(|report| report.restarted)
+                .collect::<Vec<bool>>(),
+            [false, true, true, true]
+        );
+        for population in restarted.lock().unwrap().iter() {
+            let (elite, new) = population.split_first().unwrap();
+            assert_eq!(elite.1, Some(1.0));
+            assert_eq!(new.len(), 4);
+            for (solution, fitness) in new {
+                assert_eq!(*fitness, None);
+                for (argument, elite_argument) in
+                    solution.get_arguments().iter().zip(elite.0.get_arguments())
+                {
+                    let opposite = 10.0 - elite_argument;
+                    assert!(opposite.min(5.0) <= *argument && *argument <= opposite.max(5.0));
+                }
+            }
+        }
+    }
+    #[test]
+    fn auto_population_size_follows_the_dimension() {
+        let mut runner = Runner::new(RunnerConfig {
+            population_size: PopulationSize::Auto(1.0),
+            size_generation: 100,
+            ..config()
+        });
+        let result = runner.run(
+            Solutions::random(5, 1.0..10.0, 3),
+            &Function::new(test_objects::triple_multiplication()),
+        );
+        // 4 + ⌊3·ln(3)⌋ = 7
+        assert_eq!(result.population.iter().count(), 7);
+        assert_eq!(result.config.size_generation, 7);
+        assert_eq!(runner.config().size_generation, 7);
+    }
+    #[test]
+    fn fitness_window_averages_the_latest_evaluations() {
+        let mut history = FitnessHistory::new(2);
+        let solution = Solution::new(vec![1.0]);
+        assert_eq!(history.record(&solution, 1.0), 1.0);
+        assert_eq!(history.record(&solution, 3.0), 2.0);
+        assert_eq!(history.record(&solution, 5.0), 4.0);
+        history.retain(&[(Solution::new(vec![2.0]), Some(0.0))]);
+        assert_eq!(history.record(&solution, 6.0), 6.0);
+        let function = Function::new(test_objects::triple_multiplication());
+        let initial = Solutions::random(5, 1.0..10.0, 3);
+        let n_evaluations = |fitness_window| {
+            let evaluator = CountingEvaluator::new(&function);
+            let result = Runner::new(RunnerConfig {
+                seed: Some(3),
+                fitness_window,
+                ..config()
+            })
+            .run(initial.clone(), &evaluator);
+            (evaluator.count(), result.best)
+        };
+        let (single, best) = n_evaluations(None);
+        let (averaged, averaged_best) = n_evaluations(Some(3));
+        // The parents of the last two generations are evaluated again, the exact objective
+        // leaves the run unchanged.
+        assert_eq!(averaged, single + 2 * 5);
+        assert_eq!(averaged_best, best);
+    }
+    #[test]
+    fn step_evolves_like_a_run() {
+        let function = Function::new(test_objects::triple_multiplication());
+        let mut population = Solutions::random(5, 1.0..10.0, 3)
+            .iter()
+            .map(|solution| (solution.clone(), None))
+            .collect::<Vec<Candidate>>();
+        population
+            .sort_by(|(solution_a, _), (solution_b, _)| compare_arguments(solution_a, solution_b));
+        let config = RunnerConfig {
+            n_generations: 1,
+            ..config()
+        };
+        let stepped = step(
+            population.clone(),
+            0,
+            &config,
+            &mut StdRng::seed_from_u64(5),
+            &function,
+        )
+        .unwrap();
+        let again = step(
+            population.clone(),
+            0,
+            &config,
+            &mut StdRng::seed_from_u64(5),
+            &function,
+        )
+        .unwrap();
+        assert_eq!(stepped.population, again.population);
+        let result = Runner::with_rng(config, StdRng::seed_from_u64(5)).run(
+            Solutions::from(
+                population
+                    .into_iter()
+                    .map(|(solution, _)| solution)
+                    .collect::<Vec<Solution>>(),
+            ),
+            &function,
+        );
+        assert_eq!(result.generations, vec![stepped.report.clone()]);
+        assert_eq!(result.best, fittest(&stepped.population));
+        assert_eq!(stepped.generation().population.len(), 5);
+    }
+    #[test]
+    fn failed_evaluations_are_categorized() {
+        let result = Runner::new(RunnerConfig {
+            n_generations: 5,
+            size_generation: 20,
+            evaluation_chunk_size: 1,
+            failure_penalty: Some(-1.0),
+            seed: Some(2),
+            ..config()
+        })
+        .run(
+            Solutions::random_with_rng(20, 0.0..10.0, 1, &mut StdRng::seed_from_u64(2)),
+            &FlakyEvaluator,
+        );
+        let mut total = EvaluationOutcomes::default();
+        for report in &result.generations {
+            total.merge(&report.evaluations);
+        }
+        assert_eq!(result.evaluations, total);
+        let outcomes = result.evaluations;
+        assert!(
+            [
+                outcomes.n_ok,
+                outcomes.n_wrong_arity,
+                outcomes.n_non_finite,
+                outcomes.n_timeout,
+                outcomes.n_panicked
+            ]
+            .iter()
+            .all(|count| *count > 0),
+            "{:?}",
+            outcomes
+        );
+        assert_eq!(outcomes.n_other, 0);
+        // Failed individuals get the penalty, the rest keep their argument as fitness.
+        let (best, fitness) = result.best.unwrap();
+        assert!(best.get_arguments()[0] >= 8.0);
+        assert_eq!(fitness, best.get_arguments()[0]);
+    }
+    #[test]
+    fn failed_evaluations_are_observed() {
+        let failed = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&failed);
+        let result = Runner::new(RunnerConfig {
+            n_generations: 5,
+            size_generation: 20,
+            evaluation_chunk_size: 1,
+            failure_penalty: Some(-1.0),
+            seed: Some(2),
+            ..config()
+        })
+        .with_on_evaluation_error(move |solution, error| {
+            recorded
+                .lock()
+                .unwrap()
+                .push((solution.get_arguments()[0], error.clone()))
+        })
+        .run(Solutions::random(20, 0.0..10.0, 1), &FlakyEvaluator);
+        let failed = failed.lock().unwrap();
+        assert_eq!(failed.len(), result.evaluations.n_failed());
+        for (argument, error) in failed.iter() {
+            let expected = match argument {
+                x if *x < 2.0 => FailureKind::WrongArity,
+                x if *x < 4.0 => FailureKind::NonFinite,
+                x if *x < 6.0 => FailureKind::Timeout,
+                _ => FailureKind::Panic,
+            };
+            assert_eq!(error.kind(), expected);
+        }
+        let crashed = EvaluationError::Panic(Some("The objective crashed".to_string()));
+        assert!(failed.iter().any(|(_, error)| *error == crashed));
+        // Without a penalty, the failure is reported before the run panics.
+        let failed = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&failed);
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+            Runner::new(config())
+                .with_on_evaluation_error(move |solution, _| {
+                    recorded.lock().unwrap().push(solution.clone())
+                })
+                .run(
+                    Solutions::from(vec![Solution::new(vec![1.0])]),
+                    &FlakyEvaluator,
+                )
+        }));
+        assert!(outcome.is_err());
+        assert_eq!(*failed.lock().unwrap(), vec![Solution::new(vec![1.0])]);
+    }
+    #[test]
+    fn penalty_function_takes_precedence_over_the_failure_penalty() {
+        let mut candidates = [1.0, 3.0, 5.0, 7.0, 9.0]
+            .iter()
+            .map(|x| (Solution::new(vec![*x]), None))
+            .collect::<Vec<Candidate>>();
+        let penalty = |solution: &Solution, kind: &FailureKind| match kind {
+            FailureKind::Panic => -solution.get_arguments()[0],
+            _ => -100.0,
+        };
+        let (_, outcomes) = evaluate(
+            &mut candidates,
+            &FlakyEvaluator,
+            &RunnerConfig {
+                evaluation_chunk_size: 1,
+                failure_penalty: Some(-1.0),
+                ..RunnerConfig::default()
+            },
+            Some(&penalty),
+            None,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(outcomes.n_failed(), 4);
+        assert_eq!(
+            candidates
+                .iter()
+                .map(|(_, fitness)| fitness.unwrap())
+                .collect::<Vec<f64>>(),
+            vec![-100.0, -100.0, -100.0, -7.0, 9.0]
+        );
+        // A penalty function alone is enough to keep the run going.
+        let result = Runner::new(RunnerConfig {
+            evaluation_chunk_size: 1,
+            ..config()
+        })
+        .with_penalty_function(|_, _| f64::MIN)
+        .run(Solutions::random(5, 0.0..10.0, 1), &FlakyEvaluator);
+        assert_eq!(result.generations.len(), 3);
+    }
+    #[test]
+    fn degenerate_populations_are_reported_once() {
+        let warnings_of = |population: Vec<Solution>, pipeline: Pipeline| {
+            let warnings = Arc::new(Mutex::new(Vec::new()));
+            let recorded = Arc::clone(&warnings);
+            Runner::new(RunnerConfig {
+                n_generations: 12,
+                mutate_prob: 0.0,
+                ..config()
+            })
+            .with_pipeline(pipeline)
+            .with_on_health_warning(move |warning| recorded.lock().unwrap().push(warning.clone()))
+            .run(
+                Solutions::from(population),
+                &Function::new(test_objects::triple_multiplication()),
+            );
+            let warnings = warnings.lock().unwrap().clone();
+            warnings
+        };
+        // Without mutation, a single individual has no one to mate with.
+        let warnings = warnings_of(
+            vec![Solution::new(vec![1.0, 2.0, 3.0])],
+            Pipeline::default(),
+        );
+        assert_eq!(
+            warnings,
+            vec![HealthWarning {
+                island: 0,
+                generation: 0,
+                issue: HealthIssue::IdenticalPopulation { n_individuals: 1 },
+            }]
+        );
+        assert!(warnings[0]
+            .to_string()
+            .ends_with("the 1 individuals collapsed to a single distinct one"));
+        // Children that copy a parent are always dropped.
+        let warnings = warnings_of(
+            vec![
+                Solution::new(vec![1.0, 2.0, 3.0]),
+                Solution::new(vec![2.0, 2.0, 2.0]),
+            ],
+            Pipeline::default().with_crossover(
+                |parent_a: &Solution, _: &Solution, _: &mut dyn RngCore| parent_a.clone(),
+            ),
+        );
+        assert_eq!(
+            warnings,
+            vec![HealthWarning {
+                island: 0,
+                generation: DUPLICATE_WARNING_GENERATIONS - 1,
+                issue: HealthIssue::RepeatedDuplicates {
+                    n_generations: DUPLICATE_WARNING_GENERATIONS,
+                    n_duplicates: 2,
+                    n_offspring: 2,
+                },
+            }]
+        );
+    }
+    #[test]
+    #[should_panic(expected = "Could not compute the fitness")]
+    fn failed_evaluations_panic_without_a_penalty() {
+        Runner::new(config()).run(
+            Solutions::from(vec![Solution::new(vec![1.0])]),
+            &FlakyEvaluator,
+        );
+    }
+    #[test]
+    fn chunks_with_missing_fitnesses_fail() {
+        let result = Runner::new(RunnerConfig {
+            evaluation_chunk_size: 4,
+            failure_penalty: Some(-1.0),
+            ..config()
+        })
+        .run(Solutions::random(5, 0.0..10.0, 1), &ShortEvaluator);
+        assert_eq!(result.evaluations.n_ok, 0);
+        assert!(result.evaluations.n_other > 0);
+        assert_eq!(result.best.unwrap().1, -1.0);
+    }
+    #[test]
+    #[should_panic(expected = "The evaluator returned 0 fitnesses for 1 solutions")]
+    fn chunks_with_missing_fitnesses_panic_without_a_penalty() {
+        Runner::new(config()).run(
+            Solutions::from(vec![Solution::new(vec![1.0])]),
+            &ShortEvaluator,
+        );
+    }
+    #[test]
+    fn select_prefers_evaluated() {
+        let selected = select(
+            vec![
+                (Solution::new(vec![1.0]), None),
+                (Solution::new(vec![2.0]), Some(1.0)),
+                (Solution::new(vec![3.0]), Some(3.0)),
+            ],
+            2,
+        );
+        assert_eq!(
+            selected,
+            vec![
+                (Solution::new(vec![3.0]), Some(3.0)),
+                (Solution::new(vec![2.0]), Some(1.0)),
+            ]
+        );
+    }
+    #[test]
+    fn evaluation_is_chunked() {
+        let recorder = chunk_recorder();
+        Runner::new(RunnerConfig {
+            evaluation_chunk_size: 4,
+            ..config()
+        })
+        .run(Solutions::random(5, 1.0..10.0, 3), &recorder);
+        let chunk_sizes = recorder.chunk_sizes.into_inner().unwrap();
+        assert!(chunk_sizes.iter().all(|size| *size <= 4));
+        assert!(chunk_sizes.contains(&4));
+    }
+    #[test]
+    fn evaluation_is_chunked_in_parallel() {
+        let recorder = chunk_recorder();
+        Runner::new(RunnerConfig {
+            evaluation_chunk_size: 3,
+            n_jobs: Parallelism::Jobs(2),
+            ..config()
+        })
+        .run(Solutions::random(5, 1.0..10.0, 3), &recorder);
+        let chunk_sizes = recorder.chunk_sizes.into_inner().unwrap();
+        assert!(chunk_sizes.iter().all(|size| *size <= 3));
+        assert!(chunk_sizes.contains(&3));
+    }
+    #[test]
+    fn seeded_runs_are_reproducible() {
+        for n_jobs in [0, 2] {
+            let run = || {
+                Runner::new(RunnerConfig {
+                    seed: Some(7),
+                    n_jobs: Parallelism::Jobs(n_jobs),
+                    merge_policy: MergePolicy::WeightedSampling(5),
+                    ..config()
+                })
+                .run(
+                    Solutions::random_with_rng(5, 1.0..10.0, 3, &mut StdRng::seed_from_u64(3)),
+                    &Function::new(test_objects::triple_multiplication()),
+                )
+            };
+            let (result_a, result_b) = (run(), run());
+            assert_eq!(result_a.population, result_b.population);
+            assert_eq!(result_a.generations, result_b.generations);
+        }
+    }
+    #[test]
+    fn islands_evolve_alike_on_threads_and_sequentially() {
+        let run = |executor: Arc<dyn Executor>| {
+            Runner::new(RunnerConfig {
+                seed: Some(11),
+                n_jobs: Parallelism::Jobs(3),
+                ..config()
+            })
+            .with_executor(executor)
+            .run(
+                Solutions::random_with_rng(5, 1.0..10.0, 3, &mut StdRng::seed_from_u64(3)),
+                &Function::new(test_objects::triple_multiplication()),
+            )
+        };
+        let threaded = run(Arc::new(ScopedThreads));
+        let sequential = run(Arc::new(crate::executor::Sequential));
+        assert_eq!(threaded.population, sequential.population);
+        assert_eq!(threaded.best, sequential.best);
+        assert_eq!(threaded.generations, sequential.generations);
+    }
+    #[test]
+    fn overlapping_evaluation_and_sorting_keeps_the_survivors() {
+        let run = |overlap_batch_size, executor: Arc<dyn Executor>| {
+            Runner::new(RunnerConfig {
+                seed: Some(17),
+                overlap_batch_size,
+                ..config()
+            })
+            .with_executor(executor)
+            .run(
+                Solutions::random_with_rng(5, 1.0..10.0, 3, &mut StdRng::seed_from_u64(3)),
+                &Function::new(test_objects::triple_multiplication()),
+            )
+        };
+        let plain = run(None, Arc::new(ScopedThreads));
+        for batch_size in [0, 3, 1_000] {
+            for executor in [
+                Arc::new(ScopedThreads) as Arc<dyn Executor>,
+                Arc::new(crate::executor::Sequential),
+            ] {
+                let overlapped = run(Some(batch_size), executor);
+                assert_eq!(overlapped.population, plain.population);
+                assert_eq!(overlapped.best, plain.best);
+                assert_eq!(overlapped.generations, plain.generations);
+            }
+        }
+        // Merging the sorted batches gives the order of sorting all candidates at once.
+        let mut rng = StdRng::seed_from_u64(5);
+        let candidates = (0..50)
+            .map(|index| {
+                let fitness = match index % 7 {
+                    0 => None,
+                    1 => Some(f64::NAN),
+                    _ => Some(f64::from(rng.gen_range(0..10))),
+                };
+                (Solution::new(vec![index as f64]), fitness)
+            })
+            .collect::<Vec<Candidate>>();
+        let batches = candidates
+            .chunks(6)
+            .map(|batch| select(batch.to_vec(), usize::MAX))
+            .collect();
+        let solutions = |candidates: Vec<Candidate>| {
+            candidates
+                .into_iter()
+                .map(|(solution, _)| solution)
+                .collect::<Vec<Solution>>()
+        };
+        assert_eq!(
+            solutions(merge_selected(batches)),
+            solutions(select(candidates, usize::MAX))
+        );
+    }
+    #[test]
+    fn runs_stop_once_the_target_is_reached() {
+        let run = |direction, target_value| {
+            Runner::new(RunnerConfig {
+                seed: Some(23),
+                n_generations: 500,
+                direction,
+                target_value: Some(target_value),
+                ..config()
+            })
+            .run(
+                Solutions::random_with_rng(5, 1.0..10.0, 3, &mut StdRng::seed_from_u64(3)),
+                &Function::new(test_objects::triple_multiplication()),
+            )
+        };
+        let maximized = run(ObjectiveDirection::Maximize, 2_000.0);
+        assert!(maximized.stopped_early);
+        assert!(maximized.n_evolved_generations < 500);
+        assert!(maximized.best_value().unwrap().1 >= 2_000.0);
+        // The target isn't reached before the last generation.
+        let previous = &maximized.generations[maximized.generations.len() - 2];
+        assert!(previous.best_fitness.unwrap() < 2_000.0);
+        let minimized = run(ObjectiveDirection::Minimize, 1.0);
+        assert!(minimized.stopped_early);
+        assert!(minimized.best_value().unwrap().1 <= 1.0);
+        let unreachable = run(ObjectiveDirection::Minimize, -1.0);
+        assert!(!unreachable.stopped_early);
+        assert_eq!(unreachable.n_evolved_generations, 500);
+    }
+    #[test]
+    fn runs_stop_once_the_evaluations_are_spent() {
+        for n_jobs in [0, 3] {
+            let function = Function::new(test_objects::triple_multiplication());
+            let result = Runner::new(RunnerConfig {
+                seed: Some(26),
+                n_generations: 500,
+                n_jobs: Parallelism::Jobs(n_jobs),
+                max_evaluations: Some(40),
+                ..config()
+            })
+            .run(
+                Solutions::random_with_rng(5, 1.0..10.0, 3, &mut StdRng::seed_from_u64(6)),
+                &function,
+            );
+            assert!(result.stopped_early);
+            // The islands share the budget, only the initial population of an island that
+            // got less than it needs may exceed it.
+            assert!((40..=45).contains(&function.n_calls()), "{}", n_jobs);
+            assert_eq!(result.evaluations.n_evaluations(), function.n_calls());
+        }
+    }
+    #[test]
+    fn runs_report_their_precision() {
+        let result = Runner::new(RunnerConfig {
+            seed: Some(25),
+            dedup_precision: DedupPrecision::Fixed(Precision::default()),
+            ..config()
+        })
+        .run(
+            Solutions::random_with_rng(5, 1.0..10.0, 3, &mut StdRng::seed_from_u64(5)),
+            &Function::new(test_objects::triple_multiplication()),
+        );
+        assert_eq!(result.precision, Precision::default());
+        // A coarse precision drops offspring only within its run.
+        let coarse = Precision {
+            significant_digits: 1,
+            absolute_floor: 1e-3,
+        };
+        let result = Runner::new(RunnerConfig {
+            seed: Some(25),
+            dedup_precision: DedupPrecision::Fixed(coarse),
+            ..config()
+        })
+        .run(
+            Solutions::random_with_rng(5, 1.0..10.0, 1, &mut StdRng::seed_from_u64(5)),
+            &Function::new(|x| Ok(x[0])),
+        );
+        assert_eq!(result.precision, coarse);
+        let keys = result
+            .population
+            .iter()
+            .map(|solution| coarse.key(solution))
+            .collect::<HashSet<SolutionKey>>();
+        assert_eq!(keys.len(), result.population.iter().count());
+        assert_ne!(Solution::new(vec![1.01]), Solution::new(vec![1.02]));
+    }
+    #[test]
+    fn runs_stop_once_the_best_fitness_converged() {
+        let run = |function: Function, patience| {
+            Runner::new(RunnerConfig {
+                seed: Some(24),
+                n_generations: 500,
+                patience: Some(patience),
+                ..config()
+            })
+            .run(
+                Solutions::random_with_rng(5, 1.0..10.0, 3, &mut StdRng::seed_from_u64(4)),
+                &function,
+            )
+        };
+        let patience = Patience {
+            generations: 5,
+            epsilon: 0.0,
+        };
+        // The first generation sets the best fitness, the next five don't improve it.
+        let flat = run(Function::new(|_| Ok(1.0)), patience);
+        assert!(flat.stopped_early);
+        assert_eq!(flat.n_evolved_generations, 6);
+        // Improvements by less than epsilon don't count.
+        let growing = || Function::new(test_objects::triple_multiplication());
+        let coarse = run(
+            growing(),
+            Patience {
+                epsilon: f64::MAX,
+                ..patience
+            },
+        );
+        assert!(coarse.stopped_early);
+        assert_eq!(coarse.n_evolved_generations, 6);
+        let fine = run(growing(), patience);
+        assert!(fine.n_evolved_generations > 6);
+    }
+    #[test]
+    fn minimization_selects_the_smallest_values() {
+        let run = |direction| {
+            Runner::new(RunnerConfig {
+                seed: Some(5),
+                direction,
+                ..config()
+            })
+            .run(
+                Solutions::random_with_rng(5, 1.0..10.0, 3, &mut StdRng::seed_from_u64(3)),
+                &Function::new(test_objects::triple_multiplication()),
+            )
+        };
+        let minimized = run(ObjectiveDirection::Minimize);
+        let maximized = run(ObjectiveDirection::Maximize);
+        let (_, smallest) = minimized.best_value().unwrap();
+        let (_, largest) = maximized.best_value().unwrap();
+        assert!(smallest < largest);
+        // The best individual has the smallest value of the final population.
+        let function = Function::new(test_objects::triple_multiplication());
+        assert!(minimized.population.iter().all(|solution| function
+            .get_function_value(solution.get_arguments())
+            .unwrap()
+            >= smallest));
+        assert_eq!(minimized.best.unwrap().1, -smallest);
+    }
+    #[test]
+    fn custom_rng_is_used() {
+        let run = |rng| {
+            Runner::with_rng(config(), rng).run(
+                Solutions::from(vec![
+                    Solution::new(vec![1.0, 2.0, 3.0]),
+                    Solution::new(vec![3.0, 2.0, 1.0]),
+                ]),
+                &Function::new(test_objects::triple_multiplication()),
+            )
+        };
+        assert_eq!(
+            run(StdRng::seed_from_u64(11)).population,
+            run(StdRng::seed_from_u64(11)).population
+        );
+    }
+    #[test]
+    fn custom_pipeline_is_used() {
+        // Without mutation, the children of two individuals are their average.
+        let result = Runner::new(RunnerConfig {
+            n_generations: 1,
+            size_generation: 3,
+            ..config()
+        })
+        .with_pipeline(
+            Pipeline::default().with_mutation(|child: Solution, _: f32, _: &mut dyn RngCore| child),
+        )
+        .run(
+            Solutions::from(vec![
+                Solution::new(vec![1.0, 1.0, 1.0]),
+                Solution::new(vec![3.0, 3.0, 3.0]),
+            ]),
+            &Function::new(test_objects::triple_multiplication()),
+        );
+        assert_eq!(
+            result.population,
+            Solutions::from(vec![
+                Solution::new(vec![1.0, 1.0, 1.0]),
+                Solution::new(vec![2.0, 2.0, 2.0]),
+                Solution::new(vec![3.0, 3.0, 3.0]),
+            ])
+        );
+    }
+    #[test]
+    fn individual_streams_do_not_depend_on_runner_rng() {
+        // With per-individual streams the generator of the runner is not consumed by the
+        // mutation, so differently seeded generators result in the same run.
+        let run = |rng_seed| {
+            Runner::with_rng(
+                RunnerConfig {
+                    seed: Some(5),
+                    individual_rng_streams: true,
+                    ..config()
+                },
+                StdRng::seed_from_u64(rng_seed),
+            )
+            .run(
+                Solutions::random_with_rng(5, 1.0..10.0, 3, &mut StdRng::seed_from_u64(3)),
+                &Function::new(test_objects::triple_multiplication()),
+            )
+        };
+        assert_eq!(run(1).population, run(2).population);
+    }
+    #[test]
+    fn individual_streams_in_parallel() {
+        let run = || {
+            Runner::new(RunnerConfig {
+                seed: Some(5),
+                individual_rng_streams: true,
+                n_jobs: Parallelism::Jobs(3),
+                ..config()
+            })
+            .run(
+                Solutions::random_with_rng(5, 1.0..10.0, 3, &mut StdRng::seed_from_u64(3)),
+                &Function::new(test_objects::triple_multiplication()),
+            )
+        };
+        assert_eq!(run().population, run().population);
+    }
+    #[test]
+    fn successive_halving_evaluates_survivors_at_full_fidelity() {
+        // Low fidelities are unusable for anything but ranking, so every fitness that ends up
+        // in the population must come from the last rung.
+        let function = Function::with_fidelity(|x, fidelity| {
+            Ok(test_objects::triple_multiplication()(x)? * fidelity - (1.0 - fidelity) * 1e6)
+        });
+        let result = Runner::new(RunnerConfig {
+            n_generations: 2,
+            size_generation: 2,
+            successive_halving: Some(SuccessiveHalving {
+                fidelities: vec![0.1, 0.5, 1.0],
+                reduction_factor: 2,
+            }),
+            ..config()
+        })
+        .run(Solutions::random(4, 1.0..10.0, 3), &function);
+        assert!(result.best.unwrap().1 > 0.0);
+        let first = &result.generations[0];
+        assert_eq!(first.rung_sizes.len(), 3);
+        assert!(first
+            .rung_sizes
+            .windows(2)
+            .all(|sizes| sizes[0] >= sizes[1]));
+        assert!(first.rung_sizes.iter().all(|size| *size >= 2));
+    }
+    #[test]
+    fn successive_halving_keeps_the_best_of_each_rung() {
+        let candidates = (1..=8)
+            .map(|value| (Solution::new(vec![value as f64, 1.0, 1.0]), None))
+            .collect::<Vec<Candidate>>();
+        let mut archive = Archive::new();
+        let (survivors, rung_sizes, deadline_exceeded, _) = evaluate_successive_halving(
+            candidates,
+            &Function::with_fidelity(|x, fidelity| Ok(x[0] * fidelity)),
+            &SuccessiveHalving {
+                fidelities: vec![0.5, 1.0],
+                reduction_factor: 4,
+            },
+            &RunnerConfig {
+                size_generation: 1,
+                ..RunnerConfig::default()
+            },
+            None,
+            None,
+            Some(&mut archive),
+            None,
+        );
+        assert!(!deadline_exceeded);
+        assert_eq!(rung_sizes, vec![8, 2]);
+        assert_eq!(
+            select(survivors, 2),
+            vec![
+                (Solution::new(vec![8.0, 1.0, 1.0]), Some(8.0)),
+                (Solution::new(vec![7.0, 1.0, 1.0]), Some(7.0)),
+            ]
+        );
+        assert_eq!(archive.len(), 8);
+        assert_eq!(
+            archive.fidelities(&Solution::new(vec![8.0, 1.0, 1.0])),
+            vec![0.5, 1.0]
+        );
+        assert_eq!(
+            archive.fidelities(&Solution::new(vec![1.0, 1.0, 1.0])),
+            vec![0.5]
+        );
+    }
+    #[test]
+    fn diff_summarizes_both_results() {
+        let diff = ResultDiff {
+            best_fitness: (Some(-2.0), Some(-0.5)),
+            fitness_delta: Some(1.5),
+            argument_deltas: Some(vec![0.5, -1.0]),
+            generations: (10, 4),
+            evaluations: (100, 40),
+            failed_evaluations: (0, 2),
+            config_changes: vec![(
+                "mutate_prob".to_string(),
+                "0.5".to_string(),
+                "0.1".to_string(),
+            )],
+        };
+        assert_eq!(
+            diff.to_string(),
+            "Best fitness: -2 -> -0.5 (+1.5)\n\
+             Argument deltas: [+0.5, -1]\n\
+             Generations: 10 -> 4 (-6)\n\
+             Evaluations: 100 -> 40 (-60), failed 0 -> 2\n\
+             Config:\n  mutate_prob: 0.5 -> 0.1"
+        );
+        let unchanged = Runner::new(config()).run(
+            Solutions::random(5, 1.0..9.0, 3),
+            &Function::new(test_objects::triple_multiplication()),
+        );
+        let diff = unchanged.compare(&unchanged);
+        assert_eq!(diff.fitness_delta, Some(0.0));
+        assert!(diff.config_changes.is_empty());
+        assert!(diff.to_string().ends_with("Config: unchanged"));
+    }
+    #[test]
+    fn archive_is_recorded_across_islands() {
+        for n_jobs in [0, 2] {
+            let result = Runner::new(RunnerConfig {
+                record_archive: true,
+                n_jobs: Parallelism::Jobs(n_jobs),
+                ..config()
+            })
+            .run(
+                Solutions::random(5, 1.0..10.0, 3),
+                &Function::new(test_objects::triple_multiplication()),
+            );
+            let (best, _) = result.best.unwrap();
+            assert_eq!(result.archive.max_fidelity(&best), Some(1.0));
+        }
+        let result = Runner::new(config()).run(
+            Solutions::random(5, 1.0..10.0, 3),
+            &Function::new(test_objects::triple_multiplication()),
+        );
+        assert!(result.archive.is_empty());
+    }
+    #[test]
+    fn history_and_archive_are_bounded() {
+        for n_jobs in [0, 2] {
+            let result = Runner::new(RunnerConfig {
+                n_generations: 10,
+                n_jobs: Parallelism::Jobs(n_jobs),
+                record_archive: true,
+                max_archive_entries: Some(4),
+                max_history: Some(3),
+                ..config()
+            })
+            .run(
+                Solutions::random(5, 1.0..10.0, 3),
+                &Function::new(test_objects::triple_multiplication()),
+            );
+            let n_islands = n_jobs.max(1);
+            assert_eq!(result.generations.len(), 3 * n_islands);
+            assert!(result.n_evolved_generations >= 10);
+            // The latest generations are kept.
+            assert!(result
+                .generations
+                .iter()
+                .all(|report| report.generation >= 2));
+            assert_eq!(result.archive.len(), 4);
+            assert!(result.archive.n_seen() > 4);
+        }
+    }
+    #[test]
+    fn select_breaks_ties_by_arguments() {
+        let candidates = vec![
+            (Solution::new(vec![3.0]), Some(1.0)),
+            (Solution::new(vec![1.0]), Some(1.0)),
+            (Solution::new(vec![0.0]), Some(f64::NAN)),
+            (Solution::new(vec![2.0]), Some(1.0)),
+        ];
+        let mut reversed = candidates.clone();
+        reversed.reverse();
+        let expected = vec![
+            (Solution::new(vec![1.0]), Some(1.0)),
+            (Solution::new(vec![2.0]), Some(1.0)),
+        ];
+        assert_eq!(select(candidates.clone(), 2), expected);
+        assert_eq!(select(reversed, 2), expected);
+        assert_eq!(fittest(&candidates), Some((Solution::new(vec![1.0]), 1.0)));
+    }
+    #[test]
+    fn stratified_selection_keeps_every_quantile() {
+        let kept = |n_candidates: usize, selection| {
+            let candidates = (0..n_candidates)
+                .map(|x| (Solution::new(vec![x as f64]), Some(x as f64)))
+                .collect::<Vec<Candidate>>();
+            select_survivors(candidates, 4, &selection, &mut StdRng::seed_from_u64(0))
+                .into_iter()
+                .map(|(_, fitness)| fitness.unwrap())
+                .collect::<Vec<f64>>()
+        };
+        assert_eq!(
+            kept(12, SurvivorSelection::Truncation),
+            vec![11.0, 10.0, 9.0, 8.0]
+        );
+        // The quantiles are 11-8, 7-4 and 3-0.
+        assert_eq!(
+            kept(12, SurvivorSelection::Stratified(vec![0.5, 0.25, 0.25])),
+            vec![11.0, 10.0, 7.0, 3.0]
+        );
+        assert_eq!(
+            kept(12, SurvivorSelection::Stratified(vec![1.0, 0.0, 1.0])),
+            vec![11.0, 10.0, 3.0, 2.0]
+        );
+        // The quantile 2-0 is smaller than its quota, the fittest of the rest fill up.
+        assert_eq!(
+            kept(6, SurvivorSelection::Stratified(vec![0.0, 1.0])),
+            vec![5.0, 2.0, 1.0, 0.0]
+        );
+        assert_eq!(
+            kept(
+                12,
+                SurvivorSelection::Tournament(TournamentSelection { size: 100 })
+            ),
+            vec![11.0, 10.0, 9.0, 8.0]
+        );
+    }
+    #[test]
+    fn replacement_policies() {
+        let population = || {
+            vec![
+                (Solution::new(vec![0.0]), Some(5.0)),
+                (Solution::new(vec![10.0]), Some(1.0)),
+                (Solution::new(vec![20.0]), Some(3.0)),
+            ]
+        };
+        let arguments = |population: Vec<Candidate>| {
+            population
+                .into_iter()
+                .map(|(solution, _)| solution.get_arguments()[0])
+                .collect::<Vec<f64>>()
+        };
+        let inserted = |child: Candidate, policy| {
+            let mut population = population();
+            replace(&mut population, child, policy, &mut |_| false);
+            arguments(population)
+        };
+        let child = || (Solution::new(vec![19.0]), Some(2.0));
+        assert_eq!(
+            inserted(child(), ReplacementPolicy::ReplaceWorst),
+            vec![0.0, 20.0, 19.0]
+        );
+        assert_eq!(
+            inserted(child(), ReplacementPolicy::ReplaceOldest),
+            vec![10.0, 20.0, 19.0]
+        );
+        // The closest individual is fitter, so the offspring is dropped.
+        assert_eq!(
+            inserted(child(), ReplacementPolicy::ReplaceMostSimilar),
+            vec![0.0, 10.0, 20.0]
+        );
+        assert_eq!(
+            inserted(
+                (Solution::new(vec![11.0]), Some(2.0)),
+                ReplacementPolicy::ReplaceMostSimilar
+            ),
+            vec![0.0, 20.0, 11.0]
+        );
+    }
+    #[test]
+    fn elites_survive_random_survivor_selection() {
+        let result = Runner::new(RunnerConfig {
+            n_generations: 20,
+            seed: Some(3),
+            survivor_selection: SurvivorSelection::Tournament(TournamentSelection { size: 1 }),
+            max_lifespan: Some(2),
+            elitism: 2,
+            ..config()
+        })
+        .run(
+            Solutions::random(5, 1.0..10.0, 3),
+            &Function::new(test_objects::triple_multiplication()),
+        );
+        assert_eq!(result.population.iter().count(), 5);
+        let best = result
+            .generations
+            .iter()
+            .map(|report| report.best_fitness.unwrap())
+            .collect::<Vec<f64>>();
+        assert!(best.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+    #[test]
+    fn zooming_shrinks_the_bounds_around_the_fittest() {
+        let full = vec![0.0..10.0; 3];
+        let zoom = Zoom {
+            every_generations: 2,
+            factor: 0.5,
+            min_width: 0.2,
+            expand_after: 0,
+        };
+        let result = Runner::new(RunnerConfig {
+            n_generations: 12,
+            seed: Some(8),
+            mutate_prob: 1.0,
+            zoom: Some(zoom),
+            ..config()
+        })
+        .with_bounds(full.clone())
+        .run(
+            Solutions::random_with_rng(5, 1.0..9.0, 3, &mut StdRng::seed_from_u64(8)),
+            &Function::new(test_objects::triple_multiplication()),
+        );
+        let widths = result
+            .generations
+            .iter()
+            .map(|report| {
+                let bounds = report.bounds.as_ref().unwrap();
+                for (bound, full) in bounds.iter().zip(&full) {
+                    assert!(full.start <= bound.start && bound.end <= full.end);
+                }
+                bounds[0].end - bounds[0].start
+            })
+            .collect::<Vec<f64>>();
+        assert_eq!(widths[..4], [10.0, 10.0, 5.0, 5.0]);
+        // The width never drops below `min_width` of the full bounds.
+        assert_eq!(widths[widths.len() - 1], 2.0);
+        // The offspring of the last generation stay within its bounds.
+        let last = result.generations.last().unwrap().bounds.clone().unwrap();
+        let initial_best = result.generations[0].best_fitness.unwrap();
+        assert!(result.best.unwrap().1 >= initial_best);
+        assert!(last.iter().all(|bound| bound.end - bound.start == 2.0));
+        // Stagnation expands the bounds again.
+        let expanding = Runner::new(RunnerConfig {
+            n_generations: 12,
+            zoom: Some(Zoom {
+                expand_after: 1,
+                ..zoom
+            }),
+            ..config()
+        })
+        .with_bounds(full.clone())
+        .run(
+            Solutions::random(5, 1.0..9.0, 3),
+            &Function::new(|_| Ok(0.0)),
+        );
+        assert!(expanding
+            .generations
+            .iter()
+            .all(|report| report.bounds.as_deref() == Some(&full[..])));
+    }
+    #[test]
+    fn steady_state_run_improves_monotonically() {
+        let result = Runner::new(RunnerConfig {
+            n_generations: 20,
+            seed: Some(5),
+            steady_state: Some(SteadyState {
+                n_offspring: 2,
+                replacement: ReplacementPolicy::ReplaceWorst,
+            }),
+            ..config()
+        })
+        .run(
+            Solutions::random(8, 1.0..10.0, 3),
+            &Function::new(test_objects::triple_multiplication()),
+        );
+        assert_eq!(result.population.iter().count(), 5);
+        let best = result
+            .generations
+            .iter()
+            .map(|report| report.best_fitness.unwrap())
+            .collect::<Vec<f64>>();
+        assert!(best.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+    #[test]
+    fn bounded_runs_stay_within_the_bounds() {
+        let function = Function::new(test_objects::triple_multiplication());
+        let initial_population = Solutions::random(5, 1.0..10.0, 3);
+        let initial_best = genetic_algorithm_traits::Individual::fitness(
+            &initial_population.get_n_fittest(1, &function)[0],
+            &function,
+        );
+        let result = Runner::new(RunnerConfig {
+            n_generations: 10,
+            mutate_prob: 1.0,
+            ..config()
+        })
+        .with_bounds(vec![0.0..10.0; 3])
+        .run(initial_population, &function);
+        assert!(result
+            .population
+            .iter()
+            .flat_map(|solution| solution.get_arguments())
+            .all(|x| (0.0..=10.0).contains(&x)));
+        assert!(result.best.unwrap().1 >= initial_best);
+    }
+    #[test]
+    fn checked_runs_report_broken_operators() {
+        for n_jobs in [0, 2] {
+            let mut runner = Runner::new(RunnerConfig {
+                n_jobs: Parallelism::Jobs(n_jobs),
+                mutate_prob: 1.0,
+                check_operators: true,
+                ..config()
+            })
+            .with_pipeline(Pipeline::default().with_mutation(
+                |child: Solution, _: f32, _: &mut dyn RngCore| {
+                    Solution::new(child.get_arguments().iter().map(|x| x / 0.0).collect())
+                },
+            ));
+            let error = runner
+                .try_run(
+                    Solutions::random(5, 1.0..10.0, 3),
+                    &Function::new(test_objects::triple_multiplication()),
+                )
+                .unwrap_err();
+            assert_eq!(error.stage, Stage::Mutation);
+            assert_eq!(
+                error.violation,
+                Violation::NotFinite {
+                    index: 0,
+                    value: f64::INFINITY
+                }
+            );
+            // Without checks the broken children are evaluated like any other.
+            runner.config.check_operators = false;
+            assert!(runner
+                .try_run(
+                    Solutions::random(5, 1.0..10.0, 3),
+                    &Function::new(test_objects::triple_multiplication()),
+                )
+                .is_ok());
+        }
+    }
+    #[test]
+    fn custom_identity_drops_duplicated_offspring() {
+        for n_jobs in [0, 2] {
+            let result = Runner::new(RunnerConfig {
+                n_jobs: Parallelism::Jobs(n_jobs),
+                size_generation: 10,
+                ..config()
+            })
+            .run(
+                Solutions::random(10, 1.0..10.0, 3)
+                    .with_identity(crate::solutions::SubsetIdentity::new(vec![0])),
+                &Function::new(test_objects::triple_multiplication()),
+            );
+            let firsts = result
+                .population
+                .iter()
+                .map(|solution| solution.get_arguments()[0].to_string())
+                .collect::<Vec<String>>();
+            assert_eq!(firsts.len(), firsts.iter().collect::<HashSet<_>>().len());
+        }
+    }
+    #[test]
+    fn evaluation_quota_limits_the_evaluated_offspring() {
+        for steady_state in [None, Some(SteadyState::default())] {
+            let result = Runner::new(RunnerConfig {
+                record_archive: true,
+                evaluation_quota: Some(3),
+                offspring_priority: OffspringPriority::Novelty,
+                steady_state: steady_state.map(|steady_state| SteadyState {
+                    n_offspring: 10,
+                    ..steady_state
+                }),
+                ..config()
+            })
+            .run(
+                Solutions::random(5, 1.0..10.0, 3),
+                &Function::new(test_objects::triple_multiplication()),
+            );
+            // The initial population exceeds the quota, it is evaluated anyway.
+            let n_evaluations = result
+                .archive
+                .iter()
+                .map(|(_, evaluations)| evaluations.len())
+                .sum::<usize>();
+            assert_eq!(n_evaluations, 5 + 3 + 3);
+        }
+    }
+    #[test]
+    fn prioritize_orders_the_offspring() {
+        let population = vec![
+            (Solution::new(vec![0.0]), Some(1.0)),
+            (Solution::new(vec![10.0]), Some(2.0)),
+        ];
+        let offspring = vec![
+            Solution::new(vec![1.0]),
+            Solution::new(vec![5.0]),
+            Solution::new(vec![9.0]),
+        ];
+        let prioritized = |priority| {
+            prioritize(
+                offspring.clone(),
+                &population,
+                1,
+                priority,
+                &mut rand::thread_rng(),
+            )
+        };
+        assert_eq!(
+            prioritized(OffspringPriority::Produced),
+            vec![Solution::new(vec![1.0])]
+        );
+        assert_eq!(
+            prioritized(OffspringPriority::Novelty),
+            vec![Solution::new(vec![5.0])]
+        );
+        assert_eq!(
+            prioritized(OffspringPriority::NearBest),
+            vec![Solution::new(vec![9.0])]
+        );
+        assert_eq!(prioritized(OffspringPriority::Random).len(), 1);
+    }
+    #[test]
+    fn hall_of_fame_records_improvements() {
+        for n_jobs in [0, 2] {
+            let n_calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+            let counter = Arc::clone(&n_calls);
+            let result = Runner::new(RunnerConfig {
+                n_jobs: Parallelism::Jobs(n_jobs),
+                n_generations: 6,
+                hall_of_fame_size: 3,
+                ..config()
+            })
+            .with_on_new_best(move |_| {
+                counter.fetch_add(1, AtomicOrdering::SeqCst);
+            })
+            .run(
+                Solutions::random(5, 1.0..10.0, 3),
+                &Function::new(test_objects::triple_multiplication()),
+            );
+            let hall_of_fame = result.hall_of_fame;
+            assert_eq!(hall_of_fame.len(), 3);
+            assert_eq!(
+                hall_of_fame.best().map(|(_, fitness)| fitness),
+                result.best.map(|(_, fitness)| fitness)
+            );
+            let improvements = hall_of_fame.improvements();
+            assert_eq!(n_calls.load(AtomicOrdering::SeqCst), improvements.len());
+            assert_eq!(improvements[0].generation, 0);
+            assert!(improvements.windows(2).all(
+                |pair| pair[0].fitness < pair[1].fitness && pair[0].elapsed <= pair[1].elapsed
+            ));
+        }
+    }
+    #[test]
+    fn auto_parallelism_keeps_a_core_free() {
+        let n_cores = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        let n_jobs = Parallelism::Auto.n_jobs(Parallelism::MIN_AUTO_SIZE_GENERATION, 1);
+        assert!(n_jobs < n_cores);
+        assert_ne!(n_jobs, 1);
+        assert!(Parallelism::Auto.n_jobs(Parallelism::MIN_AUTO_SIZE_GENERATION, 2) <= n_cores / 2);
+        assert_eq!(
+            Parallelism::Auto.n_jobs(Parallelism::MIN_AUTO_SIZE_GENERATION - 1, 1),
+            0
+        );
+        // Tiny populations run single-threaded, i.e. on a single island.
+        let result = Runner::new(RunnerConfig {
+            n_jobs: Parallelism::Auto,
+            ..config()
+        })
+        .run(
+            Solutions::random(5, 1.0..10.0, 3),
+            &Function::new(test_objects::triple_multiplication()),
+        );
+        assert!(result.generations.iter().all(|report| report.island == 0));
+    }
+    #[test]
+    fn max_threads_limits_the_islands() {
+        let budgeted = |n_jobs, max_threads| {
+            RunnerConfig {
+                n_jobs: Parallelism::Jobs(n_jobs),
+                threads_per_evaluation: 2,
+                max_threads,
+                ..config()
+            }
+            .n_islands()
+        };
+        assert_eq!(budgeted(4, None), 4);
+        assert_eq!(budgeted(4, Some(8)), 4);
+        assert_eq!(budgeted(4, Some(7)), 3);
+        assert_eq!(budgeted(4, Some(3)), 0);
+        assert_eq!(budgeted(4, Some(1)), 0);
+        let result = Runner::new(RunnerConfig {
+            n_jobs: Parallelism::Jobs(3),
+            max_threads: Some(2),
+            ..config()
+        })
+        .run(
+            Solutions::random(5, 1.0..10.0, 3),
+            &Function::new(test_objects::triple_multiplication()),
+        );
+        assert!(result.generations.iter().all(|report| report.island < 2));
+        // Every island learns how many threads its evaluation may use within the budget.
+        let island_threads = |n_jobs, max_threads| {
+            let recorded = Arc::new(Mutex::new(Vec::new()));
+            let recorder = Arc::clone(&recorded);
+            Runner::new(RunnerConfig {
+                n_jobs: Parallelism::Jobs(n_jobs),
+                threads_per_evaluation: 4,
+                max_threads,
+                ..config()
+            })
+            .with_island_threads(move |island, n_threads, evolve| {
+                recorder.lock().unwrap().push((island, n_threads));
+                evolve()
+            })
+            .run(
+                Solutions::random(5, 1.0..10.0, 3),
+                &Function::new(test_objects::triple_multiplication()),
+            );
+            let mut recorded = recorded.lock().unwrap().clone();
+            recorded.sort_unstable();
+            recorded
+        };
+        assert_eq!(island_threads(3, Some(9)), vec![(0, 4), (1, 4)]);
+        assert_eq!(island_threads(3, Some(3)), vec![(0, 3)]);
+        assert_eq!(island_threads(0, None), vec![(0, 4)]);
+    }
+    #[test]
+    fn stagnating_islands_switch_pipelines() {
+        let result = Runner::new(RunnerConfig {
+            n_generations: 5,
+            ..config()
+        })
+        .with_pipeline(Pipeline::default().with_filter(|_: &Solution| false))
+        .with_operator_fallbacks(
+            1,
+            vec![Pipeline::default().with_filter(|_: &Solution| false)],
+        )
+        .run(
+            Solutions::random(5, 1.0..10.0, 3),
+            &Function::new(test_objects::triple_multiplication()),
+        );
+        // Without offspring the best fitness never improves after the first generation.
+        assert_eq!(
+            result
+                .generations
+                .iter()
+                .map(|report| report.pipeline)
+                .collect::<Vec<usize>>(),
+            vec![0, 0, 1, 1, 1]
+        );
+    }
+    #[test]
+    fn mutate_schedule_replaces_the_fixed_probability() {
+        let probabilities = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&probabilities);
+        Runner::new(RunnerConfig {
+            n_generations: 5,
+            seed: Some(2),
+            mutate_schedule: Some(Schedule::Linear {
+                start: 1.0,
+                end: 0.0,
+            }),
+            ..config()
+        })
+        .with_pipeline(Pipeline::default().with_mutation(
+            move |child: Solution, mutate_prob: f32, _: &mut dyn RngCore| {
+                recorded.lock().unwrap().push(mutate_prob);
+                child
+            },
+        ))
+        .run(
+            Solutions::random(5, 1.0..10.0, 3),
+            &Function::new(test_objects::triple_multiplication()),
+        );
+        let mut probabilities = probabilities.lock().unwrap().clone();
+        probabilities.dedup();
+        assert_eq!(probabilities, [1.0, 0.75, 0.5, 0.25, 0.0]);
+    }
+    #[test]
+    fn annealing_accepts_worse_offspring_while_hot() {
+        for steady_state in [None, Some(SteadyState::default())] {
+            let n_accepted_worse = |initial_temperature| {
+                // A steady-state generation only brings a single, rarely worse, offspring.
+                Runner::new(RunnerConfig {
+                    n_generations: 200,
+                    seed: Some(4),
+                    steady_state,
+                    annealing: Some(Annealing {
+                        initial_temperature,
+                        cooling_rate: 1.0,
+                    }),
+                    ..config()
+                })
+                .run(
+                    Solutions::random(5, 1.0..10.0, 3),
+                    &Function::new(test_objects::triple_multiplication()),
+                )
+                .generations
+                .iter()
+                .map(|report| report.n_accepted_worse)
+                .sum::<usize>()
+            };
+            assert!(n_accepted_worse(1e12) > 0);
+            assert_eq!(n_accepted_worse(0.0), 0);
+        }
+        let mut survivors = vec![
+            (Solution::new(vec![3.0]), Some(3.0)),
+            (Solution::new(vec![2.0]), Some(2.0)),
+        ];
+        let candidates = vec![
+            (Solution::new(vec![1.0]), Some(1.0)),
+            (Solution::new(vec![0.0]), Some(0.0)),
+        ];
+        let hot = Annealing {
+            initial_temperature: f64::INFINITY,
+            cooling_rate: 1.0,
+        };
+        let n_accepted = accept_worse(
+            &mut survivors,
+            candidates,
+            hot,
+            0,
+            &mut StdRng::seed_from_u64(0),
+        );
+        // Only the least fit survivor competes, with the fittest rejected candidate.
+        assert_eq!(n_accepted, 1);
+        assert_eq!(
+            survivors,
+            vec![
+                (Solution::new(vec![3.0]), Some(3.0)),
+                (Solution::new(vec![1.0]), Some(1.0))
+            ]
+        );
+    }
+    #[test]
+    fn max_lifespan_forces_turnover() {
+        let run = |max_lifespan| {
+            Runner::new(RunnerConfig {
+                n_generations: 10,
+                seed: Some(3),
+                max_lifespan,
+                ..config()
+            })
+            .run(
+                Solutions::random(5, 1.0..10.0, 3),
+                &Function::new(test_objects::triple_multiplication()),
+            )
+        };
+        let unbounded = run(None);
+        assert!(unbounded
+            .generations
+            .iter()
+            .all(|report| report.n_retired == 0));
+        assert!(unbounded
+            .generations
+            .iter()
+            .any(|report| report.age.max().unwrap() >= 1.0));
+        // Nobody survives a generation, as long as there are enough offspring.
+        let bounded = run(Some(0));
+        assert!(bounded
+            .generations
+            .iter()
+            .any(|report| report.n_retired > 0));
+        for report in &bounded.generations {
+            assert_eq!(report.age.count(), 5);
+            assert_eq!(report.age.max(), Some(0.0));
+        }
+    }
+    #[test]
+    fn statistics_are_kept_without_history() {
+        for n_jobs in [0, 2] {
+            let result = Runner::new(RunnerConfig {
+                n_jobs: Parallelism::Jobs(n_jobs),
+                max_history: Some(0),
+                ..config()
+            })
+            .run(
+                Solutions::random(5, 1.0..10.0, 3),
+                &Function::new(test_objects::triple_multiplication()),
+            );
+            assert!(result.generations.is_empty());
+            let statistics = result.statistics;
+            assert_eq!(
+                statistics.best_fitness.count(),
+                result.n_evolved_generations
+            );
+            assert_eq!(statistics.fitness.count(), 5 * result.n_evolved_generations);
+            assert_eq!(
+                statistics.fitness.max(),
+                result.best.map(|(_, fitness)| fitness)
+            );
+            assert!(
+                statistics.mean_fitness_ema.value().unwrap() <= statistics.fitness.max().unwrap()
+            );
+        }
+    }
+    #[test]
+    fn stopping_predicate_ends_the_run() {
+        for n_jobs in [0, 2] {
+            let result = Runner::new(RunnerConfig {
+                n_generations: 10,
+                n_jobs: Parallelism::Jobs(n_jobs),
+                ..config()
+            })
+            .with_stopping_predicate(|generation: &Generation| {
+                generation.index == 1 && generation.population.len() == 5
+            })
+            .run(
+                Solutions::random(5, 1.0..10.0, 3),
+                &Function::new(test_objects::triple_multiplication()),
+            );
+            assert!(result.stopped_early);
+            assert!(result
+                .generations
+                .iter()
+                .all(|report| report.generation <= 1));
+        }
+        let result = Runner::new(config()).run(
+            Solutions::random(5, 1.0..10.0, 3),
+            &Function::new(test_objects::triple_multiplication()),
+        );
+        assert!(!result.stopped_early);
+    }
+    #[test]
+    fn generations_expose_their_operator_settings() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen_in_predicate = Arc::clone(&seen);
+        let result = Runner::new(RunnerConfig {
+            mutate_prob: 0.5,
+            annealing: Some(Annealing {
+                initial_temperature: 4.0,
+                cooling_rate: 0.5,
+            }),
+            ..config()
+        })
+        .with_stopping_predicate(move |generation: &Generation| {
+            assert_eq!(generation.index, generation.report.generation);
+            seen_in_predicate
+                .lock()
+                .unwrap()
+                .push((generation.settings, generation.report.pipeline));
+            false
+        })
+        .run(
+            Solutions::random(5, 1.0..10.0, 3),
+            &Function::new(test_objects::triple_multiplication()),
+        );
+        assert_eq!(result.generations.len(), 3);
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [4.0, 2.0, 1.0].map(|temperature| (
+                OperatorSettings {
+                    mutate_prob: 0.5,
+                    pipeline: 0,
+                    temperature: Some(temperature),
+                    distinct_parents: None,
+                },
+                0
+            ))
+        );
+    }
+    #[test]
+    fn interrupt_finishes_the_current_generation() {
+        let interrupt = Arc::new(AtomicBool::new(false));
+        let interrupt_in_generation = Arc::clone(&interrupt);
+        let result = Runner::new(RunnerConfig {
+            n_generations: 10,
+            ..config()
+        })
+        .with_interrupt(Arc::clone(&interrupt))
+        .with_stopping_predicate(move |generation: &Generation| {
+            // Simulates Ctrl-C being pressed during the third generation.
+            if generation.index == 2 {
+                interrupt_in_generation.store(true, AtomicOrdering::SeqCst);
+            }
+            false
+        })
+        .run(
+            Solutions::random(5, 1.0..10.0, 3),
+            &Function::new(test_objects::triple_multiplication()),
+        );
+        assert!(result.interrupted);
+        assert!(!result.stopped_early);
+        assert_eq!(result.generations.len(), 3);
+        assert_eq!(result.population.iter().count(), 5);
+    }
+    #[test]
+    fn interrupt_after_the_last_generation_is_not_an_interruption() {
+        let interrupt = Arc::new(AtomicBool::new(false));
+        let interrupt_in_generation = Arc::clone(&interrupt);
+        let result = Runner::new(RunnerConfig {
+            n_generations: 3,
+            ..config()
+        })
+        .with_interrupt(Arc::clone(&interrupt))
+        .with_stopping_predicate(move |generation: &Generation| {
+            // Ctrl-C is pressed during the last generation, which finishes the run anyway.
+            if generation.index == 2 {
+                interrupt_in_generation.store(true, AtomicOrdering::SeqCst);
+            }
+            false
+        })
+        .run(
+            Solutions::random(5, 1.0..10.0, 3),
+            &Function::new(test_objects::triple_multiplication()),
+        );
+        assert!(!result.interrupted);
+        assert_eq!(result.generations.len(), 3);
+    }
+}
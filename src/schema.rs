@@ -0,0 +1,64 @@
+use std::fmt;
+
+/// The version of the format configs, checkpoints and results are written in. It is
+/// increased whenever a field of a released format is added, renamed or changes its meaning.
+pub const SCHEMA_VERSION: usize = 1;
+
+/// Something that was tolerated while reading a document written by another version of the
+/// crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaWarning {
+    /// The document has no schema version, it was written before versions were recorded.
+    Unversioned,
+    /// The document was written by a newer version of the crate. Fields this version doesn't
+    /// know are ignored.
+    NewerVersion(usize),
+    /// A field is missing and was set to its default.
+    MissingField(String),
+    /// A field written by a newer version is unknown and was ignored.
+    UnknownField(String),
+}
+
+impl fmt::Display for SchemaWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchemaWarning::Unversioned => write!(
+                f,
+                "The document has no schema version, it was written by an old version"
+            ),
+            SchemaWarning::NewerVersion(version) => write!(
+                f,
+                "The document has schema version {}, but only versions up to {} are known",
+                version, SCHEMA_VERSION
+            ),
+            SchemaWarning::MissingField(field) => {
+                write!(f, "The field `{}` is missing, using its default", field)
+            }
+            SchemaWarning::UnknownField(field) => {
+                write!(f, "The field `{}` is unknown and was ignored", field)
+            }
+        }
+    }
+}
+
+/// Check the schema version of a document.
+///
+/// # Arguments
+///
+/// * `version` - The version the document declares, if any.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::schema::{self, SchemaWarning};
+///
+/// assert_eq!(schema::check_version(Some(schema::SCHEMA_VERSION)), None);
+/// assert_eq!(schema::check_version(None), Some(SchemaWarning::Unversioned));
+/// ```
+pub fn check_version(version: Option<usize>) -> Option<SchemaWarning> {
+    match version {
+        None => Some(SchemaWarning::Unversioned),
+        Some(version) if version > SCHEMA_VERSION => Some(SchemaWarning::NewerVersion(version)),
+        Some(_) => None,
+    }
+}
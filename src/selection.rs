@@ -0,0 +1,546 @@
+use crate::function::{CaseErrors, Function, RecentCaseErrors};
+use crate::pipeline::{FitnessScaling, ParentSelector};
+use crate::solution::{compare_fitness, Solution};
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+use std::fmt;
+use std::sync::Arc;
+
+/// Survivor selection by tournaments: every survivor is the fittest of `size` individuals
+/// drawn at random from those not chosen yet. Unlike truncation, less fit individuals survive
+/// now and then, and the size tunes the selection pressure: `1` chooses at random, a size as
+/// large as the population is truncation. Used through `SurvivorSelection::Tournament`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TournamentSelection {
+    /// How many individuals compete in every tournament.
+    pub size: usize,
+}
+
+impl Default for TournamentSelection {
+    fn default() -> Self {
+        TournamentSelection { size: 2 }
+    }
+}
+
+impl TournamentSelection {
+    /// Keep `n` candidates, each the winner of a tournament among the evaluated candidates not
+    /// chosen yet. If fewer than `n` candidates have been evaluated, the remaining places are
+    /// filled with unevaluated candidates. The survivors are returned in the order they won.
+    ///
+    /// # Arguments
+    ///
+    /// * `candidates` - The candidates and their fitness, if already evaluated.
+    /// * `n` - How many candidates should be kept.
+    /// * `rng` - The random number generator to draw the competitors with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::selection::TournamentSelection;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let candidates = (0..10)
+    ///     .map(|x| (Solution::new(vec![x as f64]), Some(x as f64)))
+    ///     .collect::<Vec<(Solution, Option<f64>)>>();
+    /// let survivors = TournamentSelection { size: 3 }.select(candidates, 4, &mut rand::thread_rng());
+    /// assert_eq!(survivors.len(), 4);
+    /// ```
+    pub fn select<G>(
+        &self,
+        candidates: Vec<(Solution, Option<f64>)>,
+        n: usize,
+        rng: &mut G,
+    ) -> Vec<(Solution, Option<f64>)>
+    where
+        G: Rng + ?Sized,
+    {
+        let (mut evaluated, unevaluated): (Vec<_>, Vec<_>) = candidates
+            .into_iter()
+            .partition(|(_, fitness)| fitness.is_some());
+        let mut survivors = Vec::new();
+        while survivors.len() < n && !evaluated.is_empty() {
+            let winner = (0..self.size.max(1))
+                .map(|_| rng.gen_range(0..evaluated.len()))
+                .min_by(|idx_a, idx_b| {
+                    let (solution_a, fitness_a) = &evaluated[*idx_a];
+                    let (solution_b, fitness_b) = &evaluated[*idx_b];
+                    compare_fitness(
+                        (solution_a, fitness_a.unwrap_or(f64::NAN)),
+                        (solution_b, fitness_b.unwrap_or(f64::NAN)),
+                    )
+                })
+                .expect("Every tournament has a competitor");
+            survivors.push(evaluated.swap_remove(winner));
+        }
+        survivors.extend(unevaluated.into_iter().take(n - survivors.len()));
+        survivors
+    }
+}
+
+/// Parent selection by stochastic universal sampling: like `RouletteSelection` the parents
+/// are picked with a probability proportional to their weight, but all of them in one spin
+/// of a wheel with equally spaced pointers. Every individual is picked either the floor or
+/// the ceiling of its expected number of times, so the selection has the least variance
+/// possible. The picked parents are shuffled before being paired.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StochasticUniversalSampling {
+    /// How fitness values are turned into weights.
+    pub scaling: FitnessScaling,
+    /// How many pairs are drawn, `None` draws as many pairs as there are individuals.
+    pub n_pairs: Option<usize>,
+}
+
+impl StochasticUniversalSampling {
+    /// Pick `n` individuals with equally spaced pointers, in the order of the population. If
+    /// all weights are zero, e.g. before the first evaluation, every individual weighs the
+    /// same.
+    ///
+    /// # Arguments
+    ///
+    /// * `population` - The individuals and their fitness, if already evaluated.
+    /// * `n` - How many individuals to pick.
+    /// * `rng` - The random number generator to draw the offset of the pointers with.
+    ///
+    /// # Panics
+    ///
+    /// With `FitnessScaling::Raw`, if an individual has a negative fitness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::pipeline::FitnessScaling;
+    /// use genetic_algorithm_fn::selection::StochasticUniversalSampling;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let population = [
+    ///     (Solution::new(vec![1.0]), Some(3.0)),
+    ///     (Solution::new(vec![2.0]), Some(1.0)),
+    /// ];
+    /// let sus = StochasticUniversalSampling {
+    ///     scaling: FitnessScaling::Raw,
+    ///     n_pairs: None,
+    /// };
+    /// assert_eq!(sus.sample(&population, 4, &mut rand::thread_rng()), vec![0, 0, 0, 1]);
+    /// ```
+    pub fn sample<G>(
+        &self,
+        population: &[(Solution, Option<f64>)],
+        n: usize,
+        rng: &mut G,
+    ) -> Vec<usize>
+    where
+        G: Rng + ?Sized,
+    {
+        if population.is_empty() || n == 0 {
+            return Vec::new();
+        }
+        let mut weights = self
+            .scaling
+            .weights(population)
+            .unwrap_or_else(|error| panic!("{}", error));
+        let mut total = weights.iter().sum::<f64>();
+        if !(total > 0.0 && total.is_finite()) {
+            weights = vec![1.0; population.len()];
+            total = population.len() as f64;
+        }
+        let spacing = total / n as f64;
+        let mut pointer = rng.gen_range(0.0..spacing);
+        let mut cumulative = 0.0;
+        let mut picked = Vec::with_capacity(n);
+        for (idx, weight) in weights.iter().enumerate() {
+            cumulative += weight;
+            while pointer < cumulative && picked.len() < n {
+                picked.push(idx);
+                pointer += spacing;
+            }
+        }
+        // Rounding can leave the last pointers just beyond the end of the wheel.
+        while picked.len() < n {
+            picked.push(population.len() - 1);
+        }
+        picked
+    }
+}
+
+impl ParentSelector for StochasticUniversalSampling {
+    /// # Panics
+    ///
+    /// With `FitnessScaling::Raw`, if an individual has a negative fitness.
+    fn select_parents(
+        &self,
+        population: &[(Solution, Option<f64>)],
+        rng: &mut dyn RngCore,
+    ) -> Vec<(usize, usize)> {
+        let n_pairs = self.n_pairs.unwrap_or(population.len());
+        let mut parents = self.sample(population, 2 * n_pairs, rng);
+        parents.shuffle(rng);
+        parents
+            .chunks_exact(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect()
+    }
+}
+
+/// Parent selection by epsilon-lexicase: every parent is chosen by going through the test
+/// cases of the objective in a random order, each time keeping only the individuals whose
+/// error on the case is within epsilon of the smallest error among those left, until one is
+/// left or the cases run out. Unlike selections on the total error it keeps specialists that
+/// solve a few cases well, e.g. fit part of the data of a curve fit, which maintains diversity.
+/// Epsilon is the median absolute deviation of the errors on a case across the population.
+/// The errors are those the function of `Function::with_case_errors` computed while evaluating
+/// the individuals; errors it didn't compute yet are computed and counted as calls of the
+/// function. Individuals whose errors can't be computed are only chosen if no other individual
+/// is left.
+#[derive(Clone)]
+pub struct EpsilonLexicaseSelection {
+    /// Computes the errors on the test cases.
+    case_errors: CaseErrors,
+    /// The errors the function computed while evaluating, `None` if the selection wasn't
+    /// created from a function.
+    recent: Option<Arc<RecentCaseErrors>>,
+    /// How many pairs are drawn, `None` draws as many pairs as there are individuals.
+    n_pairs: Option<usize>,
+}
+
+impl fmt::Debug for EpsilonLexicaseSelection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("EpsilonLexicaseSelection")
+            .field("n_pairs", &self.n_pairs)
+            .finish_non_exhaustive()
+    }
+}
+
+impl EpsilonLexicaseSelection {
+    /// Select on the test cases of `function`, `None` if it wasn't created with
+    /// `Function::with_case_errors`.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The function whose case errors the selection compares.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::pipeline::Pipeline;
+    /// use genetic_algorithm_fn::selection::EpsilonLexicaseSelection;
+    ///
+    /// let function = Function::with_case_errors(|x: &[f64]| {
+    ///     Ok([1.0, 2.0, 3.0].iter().map(|target| (x[0] - target).abs()).collect())
+    /// });
+    /// let pipeline = Pipeline::default()
+    ///     .with_parent_selector(EpsilonLexicaseSelection::new(&function).unwrap());
+    /// assert!(EpsilonLexicaseSelection::new(&Function::new(|x| Ok(x[0]))).is_none());
+    /// ```
+    pub fn new(function: &Function) -> Option<Self> {
+        function
+            .case_errors_fn()
+            .map(|case_errors| EpsilonLexicaseSelection {
+                recent: function.recent_case_errors(),
+                ..EpsilonLexicaseSelection::with_case_errors(case_errors)
+            })
+    }
+    /// Select on the test cases whose errors are computed by `case_errors`, anew for every
+    /// generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `case_errors` - Computes the error of the arguments of an individual on every case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::selection::EpsilonLexicaseSelection;
+    /// use std::sync::Arc;
+    ///
+    /// let selection = EpsilonLexicaseSelection::with_case_errors(Arc::new(|x: &[f64]| {
+    ///     Ok(x.iter().map(|x| x.abs()).collect())
+    /// }));
+    /// ```
+    pub fn with_case_errors(case_errors: CaseErrors) -> Self {
+        EpsilonLexicaseSelection {
+            case_errors,
+            recent: None,
+            n_pairs: None,
+        }
+    }
+    /// Set how many pairs are drawn every generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_pairs` - The number of pairs, `None` draws as many pairs as there are individuals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::selection::EpsilonLexicaseSelection;
+    /// use std::sync::Arc;
+    ///
+    /// let selection =
+    ///     EpsilonLexicaseSelection::with_case_errors(Arc::new(|x: &[f64]| Ok(x.to_vec())))
+    ///         .with_n_pairs(Some(10));
+    /// ```
+    pub fn with_n_pairs(mut self, n_pairs: Option<usize>) -> Self {
+        self.n_pairs = n_pairs;
+        self
+    }
+    /// Pick `n` individuals, each by its own pass through the shuffled cases.
+    ///
+    /// # Arguments
+    ///
+    /// * `population` - The individuals, their fitness isn't used.
+    /// * `n` - How many individuals to pick.
+    /// * `rng` - The random number generator to shuffle the cases and break ties with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::selection::EpsilonLexicaseSelection;
+    /// use genetic_algorithm_fn::solution::Solution;
+    /// use std::sync::Arc;
+    ///
+    /// // The arguments are the errors, the generalist in the middle is never picked.
+    /// let selection = EpsilonLexicaseSelection::with_case_errors(Arc::new(|x: &[f64]| Ok(x.to_vec())));
+    /// let population = [
+    ///     (Solution::new(vec![0.0, 10.0]), None),
+    ///     (Solution::new(vec![6.0, 6.0]), None),
+    ///     (Solution::new(vec![10.0, 0.0]), None),
+    /// ];
+    /// let picked = selection.sample(&population, 20, &mut rand::thread_rng());
+    /// assert!(!picked.contains(&1));
+    /// ```
+    pub fn sample<G>(
+        &self,
+        population: &[(Solution, Option<f64>)],
+        n: usize,
+        rng: &mut G,
+    ) -> Vec<usize>
+    where
+        G: Rng + ?Sized,
+    {
+        if population.is_empty() || n == 0 {
+            return Vec::new();
+        }
+        if let Some(recent) = &self.recent {
+            recent.reserve(4 * population.len());
+        }
+        let errors = population
+            .iter()
+            .map(|(solution, _)| match &self.recent {
+                Some(recent) => recent.errors(&self.case_errors, &solution.get_arguments()),
+                None => (self.case_errors)(&solution.get_arguments()).ok(),
+            })
+            .collect::<Vec<Option<Vec<f64>>>>();
+        let n_cases = errors.iter().flatten().map(Vec::len).max().unwrap_or(0);
+        // Missing and `NaN` errors are as bad as errors get.
+        let errors = errors
+            .into_iter()
+            .map(|errors| {
+                let errors = errors.unwrap_or_default();
+                (0..n_cases)
+                    .map(|case| match errors.get(case) {
+                        Some(error) if !error.is_nan() => *error,
+                        _ => f64::INFINITY,
+                    })
+                    .collect()
+            })
+            .collect::<Vec<Vec<f64>>>();
+        let epsilons = (0..n_cases)
+            .map(|case| median_absolute_deviation(errors.iter().map(|errors| errors[case])))
+            .collect::<Vec<f64>>();
+        let mut cases = (0..n_cases).collect::<Vec<usize>>();
+        (0..n)
+            .map(|_| {
+                cases.shuffle(rng);
+                let mut candidates = (0..population.len()).collect::<Vec<usize>>();
+                for case in &cases {
+                    if candidates.len() < 2 {
+                        break;
+                    }
+                    let best = candidates
+                        .iter()
+                        .map(|idx| errors[*idx][*case])
+                        .fold(f64::INFINITY, f64::min);
+                    candidates.retain(|idx| {
+                        let error = errors[*idx][*case];
+                        error <= best + epsilons[*case]
+                    });
+                }
+                candidates[rng.gen_range(0..candidates.len())]
+            })
+            .collect()
+    }
+}
+
+impl ParentSelector for EpsilonLexicaseSelection {
+    fn select_parents(
+        &self,
+        population: &[(Solution, Option<f64>)],
+        rng: &mut dyn RngCore,
+    ) -> Vec<(usize, usize)> {
+        let n_pairs = self.n_pairs.unwrap_or(population.len());
+        self.sample(population, 2 * n_pairs, rng)
+            .chunks_exact(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect()
+    }
+}
+
+/// The median of the absolute deviations of the finite `values` from their median, `0` if
+/// there are none.
+///
+/// # Arguments
+///
+/// * `values` - The values, infinite ones are left out.
+fn median_absolute_deviation<I>(values: I) -> f64
+where
+    I: Iterator<Item = f64>,
+{
+    let median = |mut values: Vec<f64>| {
+        values.sort_by(f64::total_cmp);
+        match values.len() {
+            0 => 0.0,
+            len if len % 2 == 1 => values[len / 2],
+            len => (values[len / 2 - 1] + values[len / 2]) / 2.0,
+        }
+    };
+    let values = values
+        .filter(|value| value.is_finite())
+        .collect::<Vec<f64>>();
+    let center = median(values.clone());
+    median(values.iter().map(|value| (value - center).abs()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EpsilonLexicaseSelection, StochasticUniversalSampling, TournamentSelection};
+    use crate::function::{Function, FunctionError};
+    use crate::pipeline::{FitnessScaling, ParentSelector};
+    use crate::rng::SplitMix64;
+    use crate::solution::Solution;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn candidates() -> Vec<(Solution, Option<f64>)> {
+        (0..20)
+            .map(|x| (Solution::new(vec![x as f64]), Some(x as f64)))
+            .chain([(Solution::new(vec![-1.0]), None)])
+            .collect()
+    }
+    #[test]
+    fn tournament_size_tunes_the_selection_pressure() {
+        let mean_fitness = |size| {
+            let mut rng = SplitMix64::new(11);
+            (0..50)
+                .flat_map(|_| TournamentSelection { size }.select(candidates(), 5, &mut rng))
+                .map(|(_, fitness)| fitness.unwrap())
+                .sum::<f64>()
+                / 250.0
+        };
+        assert!(mean_fitness(4) > mean_fitness(1));
+        // A tournament among all candidates is truncation.
+        let survivors =
+            TournamentSelection { size: 1000 }.select(candidates(), 3, &mut SplitMix64::new(2));
+        assert_eq!(
+            survivors
+                .into_iter()
+                .map(|(_, fitness)| fitness)
+                .collect::<Vec<Option<f64>>>(),
+            vec![Some(19.0), Some(18.0), Some(17.0)]
+        );
+    }
+    #[test]
+    fn unevaluated_candidates_fill_up() {
+        let survivors =
+            TournamentSelection::default().select(candidates(), 25, &mut SplitMix64::new(0));
+        assert_eq!(survivors.len(), 21);
+        assert_eq!(survivors[20].1, None);
+    }
+    #[test]
+    fn stochastic_universal_sampling_picks_the_expected_number_of_times() {
+        let population = (0..4)
+            .map(|x| (Solution::new(vec![x as f64]), Some(x as f64)))
+            .collect::<Vec<(Solution, Option<f64>)>>();
+        let sus = StochasticUniversalSampling {
+            scaling: FitnessScaling::Raw,
+            n_pairs: Some(6),
+        };
+        let mut rng = SplitMix64::new(3);
+        for _ in 0..50 {
+            // The weights 0, 1, 2 and 3 expect 0, 2, 4 and 6 of the 12 parents.
+            let mut counts = [0; 4];
+            for (parent_a, parent_b) in sus.select_parents(&population, &mut rng) {
+                counts[parent_a] += 1;
+                counts[parent_b] += 1;
+            }
+            assert_eq!(counts, [0, 2, 4, 6]);
+        }
+        let unevaluated = vec![(Solution::new(vec![0.0]), None); 3];
+        assert_eq!(sus.sample(&unevaluated, 3, &mut rng), vec![0, 1, 2]);
+    }
+    #[test]
+    fn epsilon_lexicase_keeps_the_specialists() {
+        // The arguments are the errors on three cases, negative ones can't be computed.
+        let function = Function::with_case_errors(|x: &[f64]| match x.iter().any(|x| *x < 0.0) {
+            true => Err(FunctionError::ExternalEvaluation("negative".to_string())),
+            false => Ok(x.to_vec()),
+        });
+        let population = [
+            vec![0.0, 8.0, 8.0],
+            vec![8.0, 0.0, 8.0],
+            vec![8.0, 8.0, 0.0],
+            vec![5.0, 5.0, 5.0],
+            vec![-1.0, 0.0, 0.0],
+        ]
+        .into_iter()
+        .map(|errors| (Solution::new(errors), Some(0.0)))
+        .collect::<Vec<(Solution, Option<f64>)>>();
+        let selection = EpsilonLexicaseSelection::new(&function)
+            .unwrap()
+            .with_n_pairs(Some(300));
+        let mut counts = [0; 5];
+        for (parent_a, parent_b) in selection.select_parents(&population, &mut SplitMix64::new(4)) {
+            counts[parent_a] += 1;
+            counts[parent_b] += 1;
+        }
+        // The generalist has the smallest total error but is never the best on a case.
+        assert_eq!(counts[3..], [0, 0]);
+        assert!(counts[..3].iter().all(|count| *count > 150));
+        assert_eq!(
+            EpsilonLexicaseSelection::new(&function).unwrap().sample(
+                &population[4..],
+                2,
+                &mut SplitMix64::new(4)
+            ),
+            vec![0, 0]
+        );
+    }
+    #[test]
+    fn epsilon_lexicase_reuses_the_errors_of_the_evaluation() {
+        let n_computed = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&n_computed);
+        let function = Function::with_case_errors(move |x: &[f64]| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            Ok(x.to_vec())
+        });
+        let population = (0..10)
+            .map(|x| Solution::new(vec![x as f64, 10.0 - x as f64]))
+            .map(|solution| {
+                let fitness = solution.try_fitness(&function).unwrap();
+                (solution, Some(fitness))
+            })
+            .collect::<Vec<(Solution, Option<f64>)>>();
+        let selection = EpsilonLexicaseSelection::new(&function).unwrap();
+        for _ in 0..3 {
+            selection.select_parents(&population, &mut SplitMix64::new(1));
+        }
+        assert_eq!(n_computed.load(Ordering::Relaxed), 10);
+        assert_eq!(function.n_calls(), 10);
+        // Errors the evaluation didn't compute are computed once and counted.
+        let unevaluated = [(Solution::new(vec![3.0, 3.0]), None)];
+        selection.sample(&unevaluated, 4, &mut SplitMix64::new(1));
+        selection.sample(&unevaluated, 4, &mut SplitMix64::new(1));
+        assert_eq!(n_computed.load(Ordering::Relaxed), 11);
+        assert_eq!(function.n_calls(), 11);
+    }
+}
@@ -0,0 +1,392 @@
+use crate::evaluation::{EvaluationOutcomes, Evaluator, FailureKind};
+use crate::function::{FunctionError, Gradient};
+use crate::solution::Solution;
+use std::sync::{Arc, Mutex};
+
+/// Estimate how strongly every argument currently affects the fitness, from the evaluated
+/// individuals of a population: the absolute correlation between the argument and the
+/// fitness, in `[0, 1]`. It only measures the linear, local influence within the region the
+/// population covers, which is what matters for steering the next mutations. Arguments that
+/// don't vary, and all arguments of populations with fewer than two evaluated individuals of
+/// the same length, get `0`.
+///
+/// # Arguments
+///
+/// * `population` - The individuals and their fitness, if already evaluated.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::sensitivity;
+/// use genetic_algorithm_fn::solution::Solution;
+///
+/// // The fitness is the first argument, the second one doesn't matter.
+/// let population = [
+///     (Solution::new(vec![1.0, 3.0]), Some(1.0)),
+///     (Solution::new(vec![2.0, 1.0]), Some(2.0)),
+///     (Solution::new(vec![3.0, 2.0]), Some(3.0)),
+///     (Solution::new(vec![4.0, 2.0]), None),
+/// ];
+/// let sensitivities = sensitivity::sensitivities(&population);
+/// assert!(sensitivities[0] > 0.999);
+/// assert!(sensitivities[1] < 1.0);
+/// ```
+pub fn sensitivities(population: &[(Solution, Option<f64>)]) -> Vec<f64> {
+    let evaluated = population
+        .iter()
+        .filter_map(|(solution, fitness)| {
+            fitness
+                .filter(|fitness| fitness.is_finite())
+                .map(|fitness| (solution.get_arguments(), fitness))
+        })
+        .collect::<Vec<(Vec<f64>, f64)>>();
+    let dimension = population
+        .first()
+        .map_or(0, |(solution, _)| solution.get_arguments().len());
+    if evaluated.len() < 2
+        || evaluated
+            .iter()
+            .any(|(arguments, _)| arguments.len() != dimension)
+    {
+        return vec![0.0; dimension];
+    }
+    let n_evaluated = evaluated.len() as f64;
+    let mean_fitness = evaluated.iter().map(|(_, fitness)| fitness).sum::<f64>() / n_evaluated;
+    let fitness_variance = evaluated
+        .iter()
+        .map(|(_, fitness)| (fitness - mean_fitness).powi(2))
+        .sum::<f64>();
+    (0..dimension)
+        .map(|idx| {
+            let mean = evaluated
+                .iter()
+                .map(|(arguments, _)| arguments[idx])
+                .sum::<f64>()
+                / n_evaluated;
+            let (covariance, variance) = evaluated.iter().fold(
+                (0.0, 0.0),
+                |(covariance, variance), (arguments, fitness)| {
+                    let deviation = arguments[idx] - mean;
+                    (
+                        covariance + deviation * (fitness - mean_fitness),
+                        variance + deviation * deviation,
+                    )
+                },
+            );
+            let correlation = covariance.abs() / (variance * fitness_variance).sqrt();
+            if correlation.is_finite() {
+                correlation.min(1.0)
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+/// How `FiniteDifference` approximates a derivative from the function values next to a point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DifferenceScheme {
+    /// `(f(x + h) - f(x)) / h`: one evaluation per argument, an error proportional to `h`.
+    Forward,
+    /// `(f(x + h) - f(x - h)) / 2h`: two evaluations per argument, an error proportional to
+    /// `h²`.
+    #[default]
+    Central,
+}
+
+/// Estimates the gradient of an objective that has no analytic one by finite differences.
+/// The step of every argument is `step` times its magnitude, but at least `step`, so arguments
+/// of very different scales are differentiated alike. Every function value it takes is an
+/// evaluation of the objective and is recorded like one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FiniteDifference {
+    /// The step relative to the magnitude of the arguments.
+    pub step: f64,
+    /// How the derivatives are approximated.
+    pub scheme: DifferenceScheme,
+}
+
+impl Default for FiniteDifference {
+    fn default() -> Self {
+        FiniteDifference {
+            step: 1e-6,
+            scheme: DifferenceScheme::default(),
+        }
+    }
+}
+
+impl FiniteDifference {
+    /// How many evaluations estimating a gradient takes.
+    ///
+    /// # Arguments
+    ///
+    /// * `dimension` - The number of arguments.
+    /// * `fitness_known` - Whether the fitness at the point itself is already known.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::sensitivity::{DifferenceScheme, FiniteDifference};
+    ///
+    /// let forward = FiniteDifference {
+    ///     scheme: DifferenceScheme::Forward,
+    ///     ..FiniteDifference::default()
+    /// };
+    /// assert_eq!(forward.n_evaluations(3, true), 3);
+    /// assert_eq!(FiniteDifference::default().n_evaluations(3, false), 7);
+    /// ```
+    pub fn n_evaluations(&self, dimension: usize, fitness_known: bool) -> usize {
+        let per_argument = match self.scheme {
+            DifferenceScheme::Forward => 1,
+            DifferenceScheme::Central => 2,
+        };
+        per_argument * dimension + usize::from(!fitness_known)
+    }
+    /// Estimate the fitness and its gradient at a point, evaluating all points it needs as a
+    /// single chunk. Fails with the first failed evaluation.
+    ///
+    /// # Arguments
+    ///
+    /// * `solution` - The point to differentiate at.
+    /// * `fitness` - The fitness at the point, if already known. Saves an evaluation.
+    /// * `evaluator` - The evaluator of the objective.
+    /// * `outcomes` - Counts the outcomes of the evaluations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::evaluation::EvaluationOutcomes;
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::sensitivity::FiniteDifference;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let function = Function::new(|x| Ok(x[0] * x[1]));
+    /// let mut outcomes = EvaluationOutcomes::default();
+    /// let (fitness, gradient) = FiniteDifference::default()
+    ///     .gradient(&Solution::new(vec![2.0, 3.0]), None, &function, &mut outcomes)
+    ///     .unwrap();
+    /// assert_eq!(fitness, 6.0);
+    /// assert!((gradient[0] - 3.0).abs() < 1e-6 && (gradient[1] - 2.0).abs() < 1e-6);
+    /// assert_eq!(outcomes.n_evaluations(), 5);
+    /// ```
+    pub fn gradient<E>(
+        &self,
+        solution: &Solution,
+        fitness: Option<f64>,
+        evaluator: &E,
+        outcomes: &mut EvaluationOutcomes,
+    ) -> Result<(f64, Vec<f64>), FunctionError>
+    where
+        E: Evaluator + ?Sized,
+    {
+        let arguments = solution.get_arguments();
+        let signs: &[f64] = match self.scheme {
+            DifferenceScheme::Forward => &[1.0],
+            DifferenceScheme::Central => &[1.0, -1.0],
+        };
+        let mut points = Vec::with_capacity(self.n_evaluations(arguments.len(), fitness.is_some()));
+        if fitness.is_none() {
+            points.push(solution.clone());
+        }
+        // The offset of every point, which rounding may make differ from the step.
+        let mut offsets = Vec::with_capacity(signs.len() * arguments.len());
+        for (idx, argument) in arguments.iter().enumerate() {
+            let step = self.step * argument.abs().max(1.0);
+            for sign in signs {
+                let mut point = arguments.clone();
+                point[idx] += sign * step;
+                offsets.push(point[idx] - argument);
+                points.push(Solution::new(point));
+            }
+        }
+        let results = evaluator.evaluate_chunk(&points);
+        for result in &results {
+            outcomes.record(match result {
+                Ok(value) if value.is_finite() => None,
+                Ok(_) => Some(FailureKind::NonFinite),
+                Err(error) => Some(FailureKind::of(error)),
+            });
+        }
+        let values = results
+            .into_iter()
+            .collect::<Result<Vec<f64>, FunctionError>>()?;
+        let (fitness, values) = match fitness {
+            Some(fitness) => (fitness, &values[..]),
+            None => (values[0], &values[1..]),
+        };
+        let gradient = match self.scheme {
+            DifferenceScheme::Forward => values
+                .iter()
+                .zip(&offsets)
+                .map(|(value, offset)| (value - fitness) / offset)
+                .collect(),
+            DifferenceScheme::Central => values
+                .chunks(2)
+                .zip(offsets.chunks(2))
+                .map(|(values, offsets)| (values[0] - values[1]) / (offsets[0] - offsets[1]))
+                .collect(),
+        };
+        Ok((fitness, gradient))
+    }
+    /// A `Gradient` estimated with this scheme, e.g. for a `pipeline::GradientMutation`. The
+    /// evaluations it spends are recorded in `outcomes`, so they can be accounted for in the
+    /// budget of a run.
+    ///
+    /// # Arguments
+    ///
+    /// * `evaluator` - The evaluator of the objective.
+    /// * `outcomes` - Counts the outcomes of the evaluations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::evaluation::EvaluationOutcomes;
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::pipeline::{GradientMutation, Pipeline};
+    /// use genetic_algorithm_fn::sensitivity::FiniteDifference;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let function = Arc::new(Function::new(|x| Ok(-x[0] * x[0])));
+    /// let outcomes = Arc::new(Mutex::new(EvaluationOutcomes::default()));
+    /// let gradient = FiniteDifference::default().gradient_fn(function, Arc::clone(&outcomes));
+    /// assert!((gradient(&[1.0]).unwrap().1[0] + 2.0).abs() < 1e-6);
+    /// assert_eq!(outcomes.lock().unwrap().n_evaluations(), 3);
+    /// let pipeline = Pipeline::default().with_mutation(GradientMutation::with_gradient(gradient));
+    /// ```
+    pub fn gradient_fn<E>(
+        self,
+        evaluator: Arc<E>,
+        outcomes: Arc<Mutex<EvaluationOutcomes>>,
+    ) -> Gradient
+    where
+        E: Evaluator + Send + Sync + 'static,
+    {
+        Arc::new(move |arguments| {
+            let mut spent = EvaluationOutcomes::default();
+            let result = self.gradient(
+                &Solution::new(arguments.to_vec()),
+                None,
+                evaluator.as_ref(),
+                &mut spent,
+            );
+            outcomes
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .merge(&spent);
+            result
+        })
+    }
+}
+
+/// Estimate how strongly every argument affects the fitness at a single point, from the
+/// finite-difference gradient there: the absolute derivative relative to the largest one, in
+/// `[0, 1]`. Unlike `sensitivities` it doesn't need a population spread around the point, but
+/// it spends evaluations. All arguments get `0` if the gradient vanishes or isn't finite.
+///
+/// # Arguments
+///
+/// * `solution` - The point to estimate the sensitivities at.
+/// * `fitness` - The fitness at the point, if already known.
+/// * `difference` - How the gradient is estimated.
+/// * `evaluator` - The evaluator of the objective.
+/// * `outcomes` - Counts the outcomes of the evaluations.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::evaluation::EvaluationOutcomes;
+/// use genetic_algorithm_fn::function::Function;
+/// use genetic_algorithm_fn::sensitivity::{self, FiniteDifference};
+/// use genetic_algorithm_fn::solution::Solution;
+///
+/// let function = Function::new(|x| Ok(4.0 * x[0] + x[1]));
+/// let local = sensitivity::local_sensitivities(
+///     &Solution::new(vec![1.0, 1.0]),
+///     Some(5.0),
+///     &FiniteDifference::default(),
+///     &function,
+///     &mut EvaluationOutcomes::default(),
+/// )
+/// .unwrap();
+/// assert!((local[0] - 1.0).abs() < 1e-6 && (local[1] - 0.25).abs() < 1e-6);
+/// ```
+pub fn local_sensitivities<E>(
+    solution: &Solution,
+    fitness: Option<f64>,
+    difference: &FiniteDifference,
+    evaluator: &E,
+    outcomes: &mut EvaluationOutcomes,
+) -> Result<Vec<f64>, FunctionError>
+where
+    E: Evaluator + ?Sized,
+{
+    let (_, gradient) = difference.gradient(solution, fitness, evaluator, outcomes)?;
+    let largest = gradient
+        .iter()
+        .map(|derivative| derivative.abs())
+        .fold(0.0, f64::max);
+    if !(largest > 0.0 && largest.is_finite()) {
+        return Ok(vec![0.0; gradient.len()]);
+    }
+    Ok(gradient
+        .iter()
+        .map(|derivative| (derivative.abs() / largest).min(1.0))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sensitivities, DifferenceScheme, FiniteDifference};
+    use crate::evaluation::{CountingEvaluator, EvaluationOutcomes};
+    use crate::function::{Function, FunctionError};
+    use crate::solution::Solution;
+
+    #[test]
+    fn sensitivities_rank_the_influential_arguments_first() {
+        // The fitness depends strongly on the first, weakly on the second and not at all on
+        // the constant third argument.
+        let population = (0..20)
+            .map(|x| {
+                let (a, b) = (x as f64, ((x * 7) % 20) as f64);
+                (Solution::new(vec![a, b, 1.0]), Some(5.0 * a + b))
+            })
+            .collect::<Vec<(Solution, Option<f64>)>>();
+        let influence = sensitivities(&population);
+        assert!(influence[0] > influence[1]);
+        assert!(influence[1] > 0.0);
+        assert_eq!(influence[2], 0.0);
+        assert_eq!(sensitivities(&population[..1]), vec![0.0; 3]);
+    }
+    #[test]
+    fn finite_differences_approximate_the_gradient() {
+        let function = Function::new(|x| Ok(x[0].powi(3) - 1e3 * x[1]));
+        let point = Solution::new(vec![2.0, 5.0]);
+        for (scheme, tolerance) in [
+            (DifferenceScheme::Forward, 1e-3),
+            (DifferenceScheme::Central, 1e-6),
+        ] {
+            let difference = FiniteDifference { step: 1e-5, scheme };
+            let evaluator = CountingEvaluator::new(&function);
+            let mut outcomes = EvaluationOutcomes::default();
+            let (fitness, gradient) = difference
+                .gradient(&point, Some(8.0 - 5e3), &evaluator, &mut outcomes)
+                .unwrap();
+            assert_eq!(fitness, 8.0 - 5e3);
+            assert!((gradient[0] - 12.0).abs() < tolerance);
+            assert!((gradient[1] + 1e3).abs() < tolerance);
+            assert_eq!(evaluator.count(), difference.n_evaluations(2, true));
+            assert_eq!(outcomes.n_evaluations(), evaluator.count());
+        }
+        let failing = Function::new(|x| match x[0] > 1.0 {
+            true => Err(FunctionError::ExternalEvaluation(
+                "out of range".to_string(),
+            )),
+            false => Ok(x[0]),
+        });
+        let mut outcomes = EvaluationOutcomes::default();
+        assert!(FiniteDifference::default()
+            .gradient(&Solution::new(vec![1.0]), None, &failing, &mut outcomes)
+            .is_err());
+        assert_eq!((outcomes.n_evaluations(), outcomes.n_failed()), (3, 1));
+    }
+}
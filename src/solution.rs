@@ -1,23 +1,31 @@
+use crate::bounds::{self, Bounds};
+use crate::error::Error;
 use crate::function;
+use crate::rng;
 use core::ops::Add;
 use genetic_algorithm_traits::Individual;
 use rand::distributions::uniform::SampleRange;
+use rand::distributions::Open01;
 use rand::Rng;
+use std::cmp::Ordering;
 use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::ops::Range;
 
 /// Get a random alement from a range.
 ///
 /// # Arguments
 ///
 /// * `range` - The range that should be sampled.
-fn get_random_elem_from_range<T, R>(range: R) -> Option<T>
+/// * `rng` - The random number generator to sample with.
+fn get_random_elem_from_range<T, R, G>(range: R, rng: &mut G) -> Option<T>
 where
     T: std::cmp::PartialOrd + rand::distributions::uniform::SampleUniform,
     R: SampleRange<T>,
+    G: Rng + ?Sized,
 {
     if !range.is_empty() {
-        Some(rand::thread_rng().gen_range::<T, R>(range))
+        Some(rng.gen_range::<T, R>(range))
     } else {
         None
     }
@@ -37,28 +45,245 @@ where
     sum_as_float / 2.0
 }
 
-/// Convert a floating point value into a string with 10 decimal places.
+/// How closely the arguments of two solutions have to match for the solutions to be equal.
+/// Arguments are rounded to a number of significant digits, so the tolerance is relative to
+/// their magnitude: `1e20` and `1e20 + 1.0` are equal, `1e-5` and `2e-5` are not. Arguments
+/// whose magnitude is below the absolute floor are all treated as zero, so `1e-12` equals `0`.
+///
+/// Rounding is used rather than comparing differences so that equal solutions also have equal
+/// hashes. Two arguments right next to a rounding boundary can therefore differ although they
+/// are closer than the tolerance.
+///
+/// `Solution` is always compared and hashed at the default precision, so the hash sets
+/// solutions are kept in stay valid. Other precisions apply where duplicates are dropped, see
+/// `Precision::key`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Precision {
+    /// The number of significant digits arguments are compared at. `0` is treated as `1`.
+    pub significant_digits: usize,
+    /// Arguments with a smaller magnitude are treated as zero.
+    pub absolute_floor: f64,
+}
+
+impl Default for Precision {
+    /// Eleven significant digits and an absolute floor of `1e-10`, close to the ten decimal
+    /// places solutions were compared at before for arguments of magnitude one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::solution::Precision;
+    ///
+    /// assert_eq!(Precision::default().significant_digits, 11);
+    /// ```
+    fn default() -> Self {
+        Precision {
+            significant_digits: 11,
+            absolute_floor: 1e-10,
+        }
+    }
+}
+
+/// How many times finer than the spacing of a population spread evenly over the narrowest
+/// bound `Precision::for_bounds` resolves arguments.
+const PRECISION_RESOLUTION: f64 = 1e6;
+
+impl Precision {
+    /// A precision fitting the search space, so that arguments far finer than the population
+    /// could ever tell apart are treated as equal: the resolution is a millionth of the spacing
+    /// of `population_size` individuals spread evenly over the narrowest bound. It is the
+    /// absolute floor, and the significant digits resolve it at the largest magnitude of the
+    /// bounds. Without finite, non-empty bounds the default precision is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `bounds` - The bounds of every argument.
+    /// * `population_size` - The number of individuals of the population.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::solution::Precision;
+    ///
+    /// let unit = Precision::for_bounds(&[0.0..1.0], 100);
+    /// assert_eq!(unit.significant_digits, 8);
+    /// let wide = Precision::for_bounds(&[0.0..1e6], 100);
+    /// assert_eq!(wide.significant_digits, 8);
+    /// assert!(wide.absolute_floor > unit.absolute_floor);
+    /// ```
+    pub fn for_bounds(bounds: &[Range<f64>], population_size: usize) -> Self {
+        let finite = bounds
+            .iter()
+            .filter(|bound| bound.start.is_finite() && bound.end.is_finite())
+            .filter(|bound| bound.end > bound.start);
+        let Some(min_width) = finite
+            .clone()
+            .map(|bound| bound.end - bound.start)
+            .min_by(f64::total_cmp)
+        else {
+            return Precision::default();
+        };
+        let magnitude = finite
+            .flat_map(|bound| [bound.start.abs(), bound.end.abs()])
+            .fold(min_width, f64::max);
+        let n_steps = population_size.max(1) as f64 * PRECISION_RESOLUTION;
+        Precision {
+            significant_digits: ((magnitude * n_steps / min_width).log10().ceil() as usize)
+                .clamp(1, 17),
+            absolute_floor: min_width / n_steps,
+        }
+    }
+    /// What identifies `solution` at this precision, e.g. to drop duplicates within a run
+    /// without changing the precision `Solution` itself is compared and hashed at. Solutions
+    /// with equal keys are duplicates, the step sizes are ignored like by `PartialEq`.
+    ///
+    /// # Arguments
+    ///
+    /// * `solution` - The solution to identify.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::solution::{Precision, Solution};
+    ///
+    /// let coarse = Precision {
+    ///     significant_digits: 3,
+    ///     ..Precision::default()
+    /// };
+    /// let (a, b) = (Solution::new(vec![1234.0]), Solution::new(vec![1231.0]));
+    /// assert_eq!(coarse.key(&a), coarse.key(&b));
+    /// assert_ne!(a, b);
+    /// ```
+    pub fn key(&self, solution: &Solution) -> SolutionKey {
+        SolutionKey(
+            solution
+                .function_values
+                .iter()
+                .map(|value| self.quantize(*value))
+                .collect(),
+        )
+    }
+    /// Convert a floating point value into the string it is compared and hashed as.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The floating point value that should be converted.
+    fn quantize(&self, value: f64) -> String {
+        if value.abs() < self.absolute_floor {
+            String::from("0")
+        } else {
+            f64_to_significant_string(value, self.significant_digits.max(1))
+        }
+    }
+}
+
+/// What identifies a solution at a `Precision`, see `Precision::key`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SolutionKey(Vec<String>);
+
+/// Convert a floating point value into the string it is compared and hashed as, at the default
+/// `Precision`.
 /// # Arguments
 ///
 /// * `value` - The floating point value that should be converted.
 fn f64_to_floating_point_precision_string(value: f64) -> String {
-    // We use 10 digits as floating point precision.
-    f64_to_rounded_string(value, 10)
+    Precision::default().quantize(value)
 }
-/// Convert a floating point value into a string with `precision` decimal places.
+/// Convert a floating point value into a string in scientific notation with `digits`
+/// significant digits.
 /// # Arguments
 ///
 /// * `value` - The floating point value that should be converted.
-/// * `precion` - The number of decimal places in the representation.
-fn f64_to_rounded_string(value: f64, precision: usize) -> String {
-    format!("{:.*}", precision, value,)
+/// * `digits` - The number of significant digits in the representation.
+fn f64_to_significant_string(value: f64, digits: usize) -> String {
+    format!("{:.*e}", digits.saturating_sub(1), value)
+}
+/// How `Solution::mutate_with_operator` mutates a solution.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MutationOperator {
+    /// Multiply a random argument by a factor between 0.8 and 1.2, like `Individual::mutate`.
+    /// Arguments that are exactly `0.0` never change and the step grows with the value.
+    #[default]
+    Multiplicative,
+    /// Add normally distributed noise to the arguments. The step doesn't depend on the value,
+    /// so zeros move as well.
+    Gaussian {
+        /// The standard deviation of the noise.
+        sigma: f64,
+        /// The probability of every argument to be mutated. If none is drawn, a random one is
+        /// mutated, so a mutation always changes the solution.
+        gene_prob: f64,
+    },
+    /// Add Cauchy distributed noise to the arguments. Most steps are small, but the heavy
+    /// tails make occasional long jumps that can leave a local optimum, e.g. of multimodal
+    /// functions like the Hartmann function.
+    Cauchy {
+        /// The scale of the noise, half the width of its central half.
+        scale: f64,
+        /// The probability of every argument to be mutated. If none is drawn, a random one is
+        /// mutated, so a mutation always changes the solution.
+        gene_prob: f64,
+    },
+    /// Evolution strategies style self-adaptation: every argument is shifted by normally
+    /// distributed noise with its own step size carried by the solution, see
+    /// `Solution::with_sigmas`. The step sizes are mutated log-normally first, so step sizes
+    /// that produce fit children are inherited along with them.
+    SelfAdaptive {
+        /// The step size of every argument of solutions that don't carry their own yet.
+        initial_sigma: f64,
+        /// The smallest step size, keeps the step sizes from collapsing to zero.
+        min_sigma: f64,
+    },
+}
+
+/// How `Solution::crossover_with_operator` recombines two solutions.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CrossoverOperator {
+    /// The child is the average of its parents, like `Individual::crossover`. Repeated
+    /// averaging collapses the population towards its centroid.
+    #[default]
+    Average,
+    /// Blend crossover (BLX-α): every argument is drawn uniformly from the interval between
+    /// the arguments of the parents, extended by `alpha` times its width on both sides. The
+    /// children spread as widely as their parents, so the population keeps its diversity.
+    Blend {
+        /// How far the interval is extended beyond the parents, relative to their distance.
+        /// `0.5` is common, negative values count as `0`.
+        alpha: f64,
+    },
+    /// Uniform crossover: every argument is taken from the second parent with probability
+    /// `swap_prob` and from the first one otherwise. The children lie on the corners of the
+    /// box spanned by their parents rather than between them.
+    Uniform {
+        /// The probability of taking an argument from the second parent.
+        swap_prob: f64,
+    },
+    /// K-point crossover: the arguments are cut at `k` distinct random positions and the
+    /// segments are taken alternately from the first and the second parent, starting with
+    /// the first. Neighbouring arguments mostly stay together. `k` is at most one less than
+    /// the number of arguments.
+    KPoint {
+        /// How many cut points there are.
+        k: usize,
+    },
+    /// Whole-arithmetic crossover: the child is the weighted average `λ a + (1 - λ) b` of its
+    /// parents with a weight `λ` drawn uniformly from `(0, 1)` for every mating. Unlike
+    /// `Average`, the children spread over the whole segment between their parents.
+    Arithmetic {
+        /// Whether every argument gets a weight of its own, so the children fill the box
+        /// spanned by their parents instead of the segment between them.
+        per_gene: bool,
+    },
 }
+
 /// The `Solution` is an individual for using genetic algorithm to approximate functions. It contains
 /// the specific function values.
 #[derive(Debug, Clone)]
 pub struct Solution {
     // Function value for `x`.
     function_values: Vec<f64>,
+    // The mutation step size of every argument, if the solution adapts them itself.
+    sigmas: Option<Vec<f64>>,
 }
 
 /// Represent the Solution by Displaying `Solution(x-value, y-value, z-value)`.
@@ -68,8 +293,26 @@ impl fmt::Display for Solution {
     }
 }
 
-/// Compare Solutions by converting the floating points values to a 10 decimal
-/// places representation as string - then compare the strings.
+/// Represent the Solution in scientific notation, e.g. `Solution([1.5e-12, 3e20])` for
+/// `format!("{:e}", solution)`. A precision applies to every value: `{:.2e}`.
+impl fmt::LowerExp for Solution {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let values = self
+            .function_values
+            .iter()
+            .map(|value| match formatter.precision() {
+                Some(precision) => format!("{:.*e}", precision, value),
+                None => format!("{:e}", value),
+            })
+            .collect::<Vec<String>>();
+        write!(formatter, "Solution([{}])", values.join(", "))
+    }
+}
+
+/// Compare Solutions by converting the floating points values to a representation with the
+/// significant digits of the default `Precision` - then compare the strings. The step sizes
+/// are not compared: the fitness only depends on the arguments, so solutions that only differ
+/// in their step sizes are duplicates.
 impl PartialEq for Solution {
     fn eq(&self, other: &Self) -> bool {
         self.function_values.len() == other.function_values.len()
@@ -78,18 +321,18 @@ impl PartialEq for Solution {
                     .function_values
                     .iter()
                     .zip(other.function_values.iter())
-                    .map(|(self_value, other_value)| {
+                    .all(|(self_value, other_value)| {
                         f64_to_floating_point_precision_string(*self_value)
                             == f64_to_floating_point_precision_string(*other_value)
-                    })
-                    .all(|elem| elem))
+                    }))
     }
 }
 /// Does not need additional implementation, uses the `eq` function from
 /// `PartialEq`.
 impl Eq for Solution {}
 
-/// To hash a solution, use the representation chosen designed in `fmt::Display`.
+/// To hash a solution, use the representation chosen designed in `fmt::Display`. Like for
+/// equality, the step sizes are ignored.
 impl Hash for Solution {
     fn hash<H: Hasher>(&self, state: &mut H) {
         for single_function_value in &self.function_values {
@@ -114,7 +357,68 @@ impl Solution {
     /// let my_solution = solution::Solution::new(vec![3.0, 4.0, 5.0]);
     /// ```
     pub fn new(function_values: Vec<f64>) -> Self {
-        Self { function_values }
+        Self {
+            function_values,
+            sigmas: None,
+        }
+    }
+    /// Let the solution carry its own mutation step size for every argument, which
+    /// `MutationOperator::SelfAdaptive` mutates and crossovers pass on to the children.
+    ///
+    /// # Arguments
+    ///
+    /// * `sigmas` - The step size of every argument.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let solution = Solution::new(vec![1.0, 2.0]).with_sigmas(vec![0.1, 0.5]);
+    /// assert_eq!(solution.get_sigmas(), Some(vec![0.1, 0.5]));
+    /// // The step sizes are no part of the identity of a solution.
+    /// assert_eq!(solution, Solution::new(vec![1.0, 2.0]));
+    /// ```
+    pub fn with_sigmas(self, sigmas: Vec<f64>) -> Self {
+        Solution {
+            sigmas: Some(sigmas),
+            ..self
+        }
+    }
+    /// Return the mutation step sizes the solution carries, `None` if it doesn't adapt them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// assert_eq!(Solution::new(vec![1.0]).get_sigmas(), None);
+    /// ```
+    pub fn get_sigmas(&self) -> Option<Vec<f64>> {
+        self.sigmas.clone()
+    }
+    /// Create a solution with other arguments that keeps the step sizes of this one, e.g. for
+    /// mutations and repairs that compute new arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `function_values` - The arguments of the new solution.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let solution = Solution::new(vec![1.0]).with_sigmas(vec![0.1]);
+    /// let moved = solution.with_arguments(vec![2.0]);
+    /// assert_eq!(moved.get_arguments(), vec![2.0]);
+    /// assert_eq!(moved.get_sigmas(), Some(vec![0.1]));
+    /// ```
+    pub fn with_arguments(&self, function_values: Vec<f64>) -> Self {
+        Solution {
+            function_values,
+            sigmas: self.sigmas.clone(),
+        }
     }
     /// Create a random Solution with with values between or equal
     /// `min` .. `max`.
@@ -130,70 +434,165 @@ impl Solution {
     /// use genetic_algorithm_fn::solution;
     /// let random_solution = solution::Solution::random(3.0..10.0, 3);
     /// ```
-    //fn get_random_elem_from_range<T, R>(range: R) -> Option<T>
     pub fn random<R>(range: R, length: usize) -> Self
     where
         R: SampleRange<f64> + Clone,
     {
-        Solution {
+        Solution::random_with_rng(range, length, &mut rand::thread_rng())
+    }
+    /// Create a random Solution with values sampled from `range` by `rng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `range` - The range the function arguments are sampled from.
+    /// * `length` - The number of function arguments.
+    /// * `rng` - The random number generator to sample with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::solution;
+    /// use rand::SeedableRng;
+    ///
+    /// let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    /// let random_solution = solution::Solution::random_with_rng(3.0..10.0, 3, &mut rng);
+    /// ```
+    pub fn random_with_rng<R, G>(range: R, length: usize, rng: &mut G) -> Self
+    where
+        R: SampleRange<f64> + Clone,
+        G: Rng + ?Sized,
+    {
+        Solution::try_random_with_rng(range, length, rng)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+    /// Create a random Solution with values sampled from `range` by `rng`, or fail if the
+    /// range is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `range` - The range the function arguments are sampled from.
+    /// * `length` - The number of function arguments.
+    /// * `rng` - The random number generator to sample with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::error::Error;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// assert!(Solution::try_random_with_rng(3.0..10.0, 3, &mut rng).is_ok());
+    /// assert!(matches!(
+    ///     Solution::try_random_with_rng(3.0..3.0, 3, &mut rng),
+    ///     Err(Error::EmptyRange)
+    /// ));
+    /// ```
+    pub fn try_random_with_rng<R, G>(range: R, length: usize, rng: &mut G) -> Result<Self, Error>
+    where
+        R: SampleRange<f64> + Clone,
+        G: Rng + ?Sized,
+    {
+        Ok(Solution {
             function_values: (0..length)
-                .map(|_| match get_random_elem_from_range(range.clone()) {
-                    Some(value) => value,
-                    // TODO: Don't use panic, but this function should return
-                    // a result.
-                    None => panic!("Your range is empty!"),
-                })
+                .map(|_| get_random_elem_from_range(range.clone(), rng).ok_or(Error::EmptyRange))
+                .collect::<Result<Vec<f64>, Error>>()?,
+            sigmas: None,
+        })
+    }
+    /// Create a random Solution with every argument drawn uniformly from its bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `bounds` - The bounds of every argument, they determine the number of arguments.
+    /// * `rng` - The random number generator to sample with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::bounds::Bounds;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let bounds = Bounds::new(vec![0.0..1.0, -5.0..5.0]);
+    /// let random_solution = Solution::random_within(&bounds, &mut rand::thread_rng());
+    /// assert!(bounds.contains(&random_solution));
+    /// ```
+    pub fn random_within<G>(bounds: &Bounds, rng: &mut G) -> Self
+    where
+        G: Rng + ?Sized,
+    {
+        Solution::new(
+            bounds
+                .ranges()
+                .iter()
+                .map(|range| bounds::sample(range, rng))
                 .collect(),
-        }
+        )
     }
-    /// Return the function arguments stored in a solution.
+    /// Mutate the solution like `Individual::mutate`, drawing all random numbers from `rng`.
     ///
+    /// # Arguments
+    ///
+    /// * `prob` - The probability with which on of the function values will mutated.
+    /// * `rng` - The random number generator to sample with.
     ///
     /// # Examples
     ///
     /// ```
     /// use genetic_algorithm_fn::solution;
-    /// let simple_solution = solution::Solution::new(vec![1.0, 2.0, 3.0]);
-    /// assert_eq!(simple_solution.get_arguments(), vec![1.0, 2.0, 3.0])
+    /// use rand::SeedableRng;
+    ///
+    /// let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    /// let my_solution = solution::Solution::new(vec![1.0, 2.0, 3.0]);
+    /// println!("{}", my_solution.mutate_with_rng(1.0, &mut rng));
     /// ```
-    pub fn get_arguments(&self) -> Vec<f64> {
-        self.function_values.clone()
+    pub fn mutate_with_rng<G>(self, prob: f32, rng: &mut G) -> Self
+    where
+        G: Rng + ?Sized,
+    {
+        self.mutate_with_jitter(prob, 0.0, rng)
     }
-}
-impl<'a> Individual<'a> for Solution {
-    // The Distance matrix is needed by the individuals to compute their fitness on.
-    type IndividualCost = function::Function;
-    /// Mutate the solution by multiplying a random function argument with a factor between
-    /// 0.8-1.2
+    /// Mutate the solution like `mutate_with_rng` and additionally shift the mutated value by
+    /// a random amount within `[-jitter, jitter]`. Unlike the multiplicative mutation alone,
+    /// this also moves values that are exactly `0.0`.
     ///
     /// # Arguments
     ///
     /// * `prob` - The probability with which on of the function values will mutated.
+    /// * `jitter` - The maximum absolute shift, `0.0` only mutates multiplicatively.
+    /// * `rng` - The random number generator to sample with.
     ///
     /// # Examples
     ///
     /// ```
     /// use genetic_algorithm_fn::solution;
-    /// use genetic_algorithm_traits::Individual;
+    /// use rand::SeedableRng;
     ///
-    /// let my_solution = solution::Solution::new(vec![1.0, 2.0, 3.0]);
-    /// println!("Solution before mutation: {}, solution after mutation: {}", my_solution, my_solution.clone().mutate(1.0));
+    /// let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    /// let my_solution = solution::Solution::new(vec![0.0]);
+    /// assert_ne!(my_solution.clone().mutate_with_jitter(1.0, 0.1, &mut rng), my_solution);
     /// ```
-    fn mutate(self, prob: f32) -> Self {
-        if get_random_elem_from_range(0.0..1.0).unwrap() > prob {
+    pub fn mutate_with_jitter<G>(self, prob: f32, jitter: f64, rng: &mut G) -> Self
+    where
+        G: Rng + ?Sized,
+    {
+        if self.function_values.is_empty()
+            || get_random_elem_from_range(0.0..1.0, rng).unwrap() > prob
+        {
             // With probabilty (1-prop) don't do any mutation.
             self
         } else {
-            // Sample a random factor to mutate the solutions with that is not 1.0
-            // so that a value is mutated.
-            let mut factor_to_mutate = get_random_elem_from_range(0.8..1.2).unwrap();
-            while factor_to_mutate == 1.0 {
-                factor_to_mutate = get_random_elem_from_range(0.8..1.2).unwrap();
-            }
-            // Remove mutuability.
-            let factor_to_mutate_with = factor_to_mutate;
-            // Sample the argument that we want to mutate.
-            let idx_to_mutate = get_random_elem_from_range(0..self.function_values.len()).unwrap();
+            let (idx_to_mutate, factor_to_mutate_with) =
+                draw_scaling(self.function_values.len(), rng);
+            let mut shift = 0.0;
+            let value = self.function_values[idx_to_mutate];
+            // Infinite values can't be shifted, an infinite jitter shifts as far as possible.
+            if jitter > 0.0 && value.is_finite() {
+                let jitter = jitter.min(f64::MAX);
+                // Make sure the shift doesn't cancel out the multiplicative mutation.
+                while shift == 0.0 || value * factor_to_mutate_with + shift == value {
+                    shift = jitter * rng.gen_range(-1.0..=1.0);
+                }
+            }
             Solution {
                 function_values: self
                     .function_values
@@ -201,17 +600,655 @@ impl<'a> Individual<'a> for Solution {
                     .enumerate()
                     .map(|(idx, function_value)| {
                         if idx == idx_to_mutate {
-                            function_value * factor_to_mutate_with
+                            function_value * factor_to_mutate_with + shift
                         } else {
                             *function_value
                         }
                     })
                     .collect(),
+                sigmas: self.sigmas,
             }
         }
     }
+    /// Mutate the solution with the given operator.
+    ///
+    /// # Arguments
+    ///
+    /// * `operator` - How the solution is mutated.
+    /// * `prob` - The probability with which the solution is mutated at all.
+    /// * `rng` - The random number generator to sample with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::solution::{MutationOperator, Solution};
+    ///
+    /// let operator = MutationOperator::Gaussian {
+    ///     sigma: 0.1,
+    ///     gene_prob: 0.5,
+    /// };
+    /// let origin = Solution::new(vec![0.0, 0.0]);
+    /// let mutated = origin
+    ///     .clone()
+    ///     .mutate_with_operator(operator, 1.0, &mut rand::thread_rng());
+    /// assert_ne!(mutated, origin);
+    /// ```
+    pub fn mutate_with_operator<G>(self, operator: MutationOperator, prob: f32, rng: &mut G) -> Self
+    where
+        G: Rng + ?Sized,
+    {
+        match operator {
+            MutationOperator::Multiplicative => self.mutate_with_rng(prob, rng),
+            MutationOperator::Gaussian { sigma, gene_prob } => {
+                self.shift_arguments(prob, gene_prob, rng, |rng| {
+                    sigma * rng::standard_normal(rng)
+                })
+            }
+            MutationOperator::Cauchy { scale, gene_prob } => {
+                self.shift_arguments(prob, gene_prob, rng, |rng| {
+                    scale * rng::standard_cauchy(rng)
+                })
+            }
+            MutationOperator::SelfAdaptive {
+                initial_sigma,
+                min_sigma,
+            } => self.mutate_self_adaptive(prob, initial_sigma, min_sigma, rng),
+        }
+    }
+    /// Mutate the step sizes log-normally with a factor shared by all arguments and one per
+    /// argument, then shift every argument by normally distributed noise with its new step
+    /// size. Solutions without step sizes, or with one per argument missing, start from
+    /// `initial_sigma`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prob` - The probability with which the solution is mutated at all.
+    /// * `initial_sigma` - The step size of solutions that don't carry their own.
+    /// * `min_sigma` - The smallest step size.
+    /// * `rng` - The random number generator to sample with.
+    fn mutate_self_adaptive<G>(
+        self,
+        prob: f32,
+        initial_sigma: f64,
+        min_sigma: f64,
+        rng: &mut G,
+    ) -> Self
+    where
+        G: Rng + ?Sized,
+    {
+        let n_arguments = self.function_values.len();
+        if n_arguments == 0 || get_random_elem_from_range(0.0..1.0, rng).unwrap() > prob {
+            return self;
+        }
+        let mut sigmas = self
+            .sigmas
+            .filter(|sigmas| sigmas.len() == n_arguments)
+            .unwrap_or_else(|| vec![initial_sigma; n_arguments]);
+        let mut function_values = self.function_values;
+        adapt_and_shift_values(&mut function_values, &mut sigmas, min_sigma, rng);
+        Solution {
+            function_values,
+            sigmas: Some(sigmas),
+        }
+    }
+    /// Crossover the solution with another one like `Individual::crossover`, or fail if they
+    /// don't have the same number of arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other parent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::error::Error;
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let solution = Solution::new(vec![1.0, 2.0]);
+    /// assert_eq!(
+    ///     solution.try_crossover(&Solution::new(vec![3.0, 4.0])).unwrap(),
+    ///     Solution::new(vec![2.0, 3.0])
+    /// );
+    /// assert!(matches!(
+    ///     solution.try_crossover(&Solution::new(vec![3.0])),
+    ///     Err(Error::LengthMismatch { expected: 2, actual: 1 })
+    /// ));
+    /// ```
+    pub fn try_crossover(&self, other: &Solution) -> Result<Self, Error> {
+        check_same_length(self, other)?;
+        Ok(self.crossover(other))
+    }
+    /// Recombine the solution with another one like `crossover_with_operator`, or fail if they
+    /// don't have the same number of arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other parent.
+    /// * `operator` - How the parents are recombined.
+    /// * `rng` - The random number generator to sample with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::solution::{CrossoverOperator, Solution};
+    ///
+    /// let child = Solution::new(vec![0.0, 10.0]).try_crossover_with_operator(
+    ///     &Solution::new(vec![1.0]),
+    ///     CrossoverOperator::Uniform { swap_prob: 0.5 },
+    ///     &mut rand::thread_rng(),
+    /// );
+    /// assert!(child.is_err());
+    /// ```
+    pub fn try_crossover_with_operator<G>(
+        &self,
+        other: &Solution,
+        operator: CrossoverOperator,
+        rng: &mut G,
+    ) -> Result<Self, Error>
+    where
+        G: Rng + ?Sized,
+    {
+        check_same_length(self, other)?;
+        Ok(self.crossover_with_operator(other, operator, rng))
+    }
+    /// Compute the fitness of the solution like `Individual::fitness`, or fail if the function
+    /// can't be computed for its arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - The function whose value is the fitness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::error::Error;
+    /// use genetic_algorithm_fn::function::{Function, FunctionError};
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let function = Function::new(|x| match x.len() {
+    ///     2 => Ok(x[0] * x[1]),
+    ///     _ => Err(FunctionError::WrongNumberOfEntries {
+    ///         actual_number_of_entries: x.len(),
+    ///         expected_number_of_entries: 2,
+    ///     }),
+    /// });
+    /// assert_eq!(Solution::new(vec![2.0, 3.0]).try_fitness(&function).unwrap(), 6.0);
+    /// assert!(matches!(
+    ///     Solution::new(vec![2.0]).try_fitness(&function),
+    ///     Err(Error::Evaluation(_))
+    /// ));
+    /// ```
+    pub fn try_fitness(&self, function: &function::Function) -> Result<f64, Error> {
+        Ok(function.get_function_value(self.function_values.clone())?)
+    }
+    /// Recombine the solution with another one with the given operator.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other parent, with as many arguments as this one.
+    /// * `operator` - How the parents are recombined.
+    /// * `rng` - The random number generator to sample with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::solution::{CrossoverOperator, Solution};
+    ///
+    /// let child = Solution::new(vec![0.0, 10.0]).crossover_with_operator(
+    ///     &Solution::new(vec![1.0, 10.0]),
+    ///     CrossoverOperator::Blend { alpha: 0.5 },
+    ///     &mut rand::thread_rng(),
+    /// );
+    /// let arguments = child.get_arguments();
+    /// assert!(-0.5 <= arguments[0] && arguments[0] <= 1.5);
+    /// assert_eq!(arguments[1], 10.0);
+    /// ```
+    pub fn crossover_with_operator<G>(
+        &self,
+        other: &Solution,
+        operator: CrossoverOperator,
+        rng: &mut G,
+    ) -> Self
+    where
+        G: Rng + ?Sized,
+    {
+        if let CrossoverOperator::Average = operator {
+            return self.crossover(other);
+        }
+        assert_same_length(self, other);
+        let n_arguments = self.function_values.len();
+        let mut function_values = vec![0.0; n_arguments];
+        let mut swapped = vec![false; n_arguments];
+        recombine_arguments(
+            &self.function_values,
+            &other.function_values,
+            operator,
+            rng,
+            &mut function_values,
+            &mut swapped,
+        );
+        match operator {
+            CrossoverOperator::Uniform { .. } | CrossoverOperator::KPoint { .. } => {
+                exchange_arguments(self, other, &swapped)
+            }
+            _ => Solution {
+                function_values,
+                sigmas: recombined_sigmas(self, other),
+            },
+        }
+    }
+    /// Shift every argument with probability `gene_prob` by a step drawn from `draw`, and a
+    /// random one if none is drawn.
+    ///
+    /// # Arguments
+    ///
+    /// * `prob` - The probability with which the solution is mutated at all.
+    /// * `gene_prob` - The probability of every argument to be shifted.
+    /// * `rng` - The random number generator to sample with.
+    /// * `draw` - Draws the step of an argument.
+    fn shift_arguments<G, D>(self, prob: f32, gene_prob: f64, rng: &mut G, draw: D) -> Self
+    where
+        G: Rng + ?Sized,
+        D: Fn(&mut G) -> f64,
+    {
+        if self.function_values.is_empty()
+            || get_random_elem_from_range(0.0..1.0, rng).unwrap() > prob
+        {
+            return self;
+        }
+        let mut function_values = self.function_values;
+        shift_values(&mut function_values, gene_prob, rng, draw);
+        Solution {
+            function_values,
+            sigmas: self.sigmas,
+        }
+    }
+    /// Mutate the solution with Deb's polynomial mutation. Every argument is mutated with
+    /// probability `1 / n` for `n` arguments, and a random one if none is drawn. The shift of a
+    /// mutated argument follows a polynomial distribution over its bounds that is concentrated
+    /// around the current value, so the mutated argument always stays within its bounds.
+    /// Arguments without bounds, or with empty ones, are left unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `prob` - The probability with which the solution is mutated at all.
+    /// * `eta_m` - The distribution index, larger values keep the mutated argument closer to
+    ///   its current value. Typical values are between `20` and `100`.
+    /// * `bounds` - The bounds of every argument.
+    /// * `rng` - The random number generator to sample with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::solution::Solution;
+    ///
+    /// let bounds = vec![0.0..1.0, -5.0..5.0];
+    /// let mutated = Solution::new(vec![0.5, 0.0]).mutate_polynomial(
+    ///     1.0,
+    ///     20.0,
+    ///     &bounds,
+    ///     &mut rand::thread_rng(),
+    /// );
+    /// for (argument, bound) in mutated.get_arguments().iter().zip(&bounds) {
+    ///     assert!(bound.start <= *argument && *argument <= bound.end);
+    /// }
+    /// ```
+    pub fn mutate_polynomial<G>(
+        self,
+        prob: f32,
+        eta_m: f64,
+        bounds: &[Range<f64>],
+        rng: &mut G,
+    ) -> Self
+    where
+        G: Rng + ?Sized,
+    {
+        let n_arguments = self.function_values.len();
+        if n_arguments == 0 || get_random_elem_from_range(0.0..1.0, rng).unwrap() > prob {
+            return self;
+        }
+        let mut mutated = (0..n_arguments)
+            .filter(|_| rng.gen_range(0.0..1.0) < 1.0 / n_arguments as f64)
+            .collect::<Vec<usize>>();
+        if mutated.is_empty() {
+            mutated.push(rng.gen_range(0..n_arguments));
+        }
+        let exponent = 1.0 / (eta_m + 1.0);
+        let mut function_values = self.function_values;
+        for idx in mutated {
+            let Some(bound) = bounds.get(idx).filter(|bound| bound.end > bound.start) else {
+                continue;
+            };
+            let width = bound.end - bound.start;
+            let value = function_values[idx].clamp(bound.start, bound.end);
+            let u: f64 = rng.gen_range(0.0..1.0);
+            // The distance to the nearer bound shrinks the step towards it.
+            let shift = if u < 0.5 {
+                let below = 1.0 - (value - bound.start) / width;
+                (2.0 * u + (1.0 - 2.0 * u) * below.powf(eta_m + 1.0)).powf(exponent) - 1.0
+            } else {
+                let above = 1.0 - (bound.end - value) / width;
+                1.0 - (2.0 * (1.0 - u) + 2.0 * (u - 0.5) * above.powf(eta_m + 1.0)).powf(exponent)
+            };
+            function_values[idx] = (value + shift * width).clamp(bound.start, bound.end);
+        }
+        Solution {
+            function_values,
+            sigmas: self.sigmas,
+        }
+    }
+    /// Return the function arguments stored in a solution.
+    ///
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::solution;
+    /// let simple_solution = solution::Solution::new(vec![1.0, 2.0, 3.0]);
+    /// assert_eq!(simple_solution.get_arguments(), vec![1.0, 2.0, 3.0])
+    /// ```
+    pub fn get_arguments(&self) -> Vec<f64> {
+        self.function_values.clone()
+    }
+}
+
+/// Check that two solutions that are recombined have the same number of arguments.
+///
+/// # Arguments
+///
+/// * `solution` - The first parent.
+/// * `other` - The second parent.
+fn check_same_length(solution: &Solution, other: &Solution) -> Result<(), Error> {
+    if solution.function_values.len() == other.function_values.len() {
+        Ok(())
+    } else {
+        Err(Error::LengthMismatch {
+            expected: solution.function_values.len(),
+            actual: other.function_values.len(),
+        })
+    }
+}
+
+/// Panic unless two solutions that are recombined have the same number of arguments, see
+/// `check_same_length`.
+///
+/// # Arguments
+///
+/// * `solution` - The first parent.
+/// * `other` - The second parent.
+pub(crate) fn assert_same_length(solution: &Solution, other: &Solution) {
+    if let Err(error) = check_same_length(solution, other) {
+        panic!("Cannot crossover the solutions: {}", error);
+    }
+}
+
+/// Order solutions lexicographically by their arguments. Used to make the order of a
+/// population independent of the `HashSet` it was stored in.
+///
+/// # Arguments
+///
+/// * `solution_a` - The first solution to compare.
+/// * `solution_b` - The second solution to compare.
+pub(crate) fn compare_arguments(solution_a: &Solution, solution_b: &Solution) -> Ordering {
+    solution_a
+        .get_arguments()
+        .iter()
+        .zip(solution_b.get_arguments().iter())
+        .map(|(value_a, value_b)| value_a.total_cmp(value_b))
+        .find(|ordering| *ordering != Ordering::Equal)
+        .unwrap_or_else(|| {
+            solution_a
+                .get_arguments()
+                .len()
+                .cmp(&solution_b.get_arguments().len())
+        })
+}
+
+/// Order solutions from the fittest to the least fit one. `NaN` counts as less fit than any
+/// other fitness. Ties are broken by `compare_arguments`, so which of several equally fit
+/// solutions survives doesn't depend on the order they were generated or stored in.
+///
+/// # Arguments
+///
+/// * `(solution_a, fitness_a)` - The first solution and its fitness.
+/// * `(solution_b, fitness_b)` - The second solution and its fitness.
+pub(crate) fn compare_fitness(
+    (solution_a, fitness_a): (&Solution, f64),
+    (solution_b, fitness_b): (&Solution, f64),
+) -> Ordering {
+    match (fitness_a.is_nan(), fitness_b.is_nan()) {
+        (false, false) => fitness_b.total_cmp(&fitness_a),
+        (nan_a, nan_b) => nan_a.cmp(&nan_b),
+    }
+    .then_with(|| compare_arguments(solution_a, solution_b))
+}
+
+/// A child taking every argument either from the first or the second parent, with the step
+/// sizes of the same parent. Step sizes only one of the parents carries are taken from it.
+///
+/// # Arguments
+///
+/// * `parent_a` - The parent the arguments are taken from unless swapped, it determines the
+///   number of arguments of the child.
+/// * `parent_b` - The parent the swapped arguments are taken from.
+/// * `swapped` - Which arguments are taken from `parent_b`. Arguments `parent_b` doesn't have
+///   are taken from `parent_a`.
+pub(crate) fn exchange_arguments(
+    parent_a: &Solution,
+    parent_b: &Solution,
+    swapped: &[bool],
+) -> Solution {
+    let take = |values_a: &[f64], values_b: &[f64]| {
+        values_a
+            .iter()
+            .enumerate()
+            .map(|(idx, value_a)| match values_b.get(idx) {
+                Some(value_b) if swapped.get(idx).copied().unwrap_or(false) => *value_b,
+                _ => *value_a,
+            })
+            .collect::<Vec<f64>>()
+    };
+    let sigmas = match (&parent_a.sigmas, &parent_b.sigmas) {
+        (Some(sigmas_a), Some(sigmas_b)) => Some(take(sigmas_a, sigmas_b)),
+        (Some(sigmas), None) | (None, Some(sigmas)) => Some(sigmas.clone()),
+        (None, None) => None,
+    };
+    Solution {
+        function_values: take(&parent_a.function_values, &parent_b.function_values),
+        sigmas,
+    }
+}
+
+/// The step sizes of a child of two parents: the average of their step sizes, the ones of
+/// the parent that carries them if only one does, and `None` if neither does.
+///
+/// # Arguments
+///
+/// * `parent_a` - The first parent.
+/// * `parent_b` - The second parent.
+pub(crate) fn recombined_sigmas(parent_a: &Solution, parent_b: &Solution) -> Option<Vec<f64>> {
+    match (&parent_a.sigmas, &parent_b.sigmas) {
+        (Some(sigmas_a), Some(sigmas_b)) if sigmas_a.len() == sigmas_b.len() => Some(
+            sigmas_a
+                .iter()
+                .zip(sigmas_b)
+                .map(|(sigma_a, sigma_b)| average(*sigma_a, *sigma_b))
+                .collect(),
+        ),
+        (Some(sigmas), _) | (None, Some(sigmas)) => Some(sigmas.clone()),
+        (None, None) => None,
+    }
+}
+/// Write the child of two parents into `child`. `Solution::crossover_with_operator` and
+/// `PopulationMatrix::crossover` both recombine with it, so they draw the same random
+/// numbers.
+///
+/// # Arguments
+///
+/// * `parent_a` - The arguments of the first parent.
+/// * `parent_b` - The arguments of the second parent, as many as those of the first one.
+/// * `operator` - How the parents are recombined.
+/// * `rng` - The random number generator to sample with.
+/// * `child` - The arguments of the child to write.
+/// * `swapped` - Which arguments are taken from `parent_b`, only written by
+///   `CrossoverOperator::Uniform` and `CrossoverOperator::KPoint`.
+pub(crate) fn recombine_arguments<G>(
+    parent_a: &[f64],
+    parent_b: &[f64],
+    operator: CrossoverOperator,
+    rng: &mut G,
+    child: &mut [f64],
+    swapped: &mut [bool],
+) where
+    G: Rng + ?Sized,
+{
+    let values = parent_a.iter().zip(parent_b).zip(child.iter_mut());
+    match operator {
+        CrossoverOperator::Average => {
+            for ((value_a, value_b), value) in values {
+                *value = average(*value_a, *value_b);
+            }
+        }
+        CrossoverOperator::Blend { alpha } => {
+            let alpha = alpha.max(0.0);
+            for ((value_a, value_b), value) in values {
+                let (low, high) = (value_a.min(*value_b), value_a.max(*value_b));
+                let width = high - low;
+                let u: f64 = rng.gen_range(0.0..=1.0);
+                *value = low - alpha * width + u * (1.0 + 2.0 * alpha) * width;
+            }
+        }
+        CrossoverOperator::Uniform { swap_prob } => {
+            for swap in swapped.iter_mut() {
+                *swap = rng.gen_range(0.0..1.0) < swap_prob;
+            }
+            for (((value_a, value_b), value), swap) in values.zip(swapped.iter()) {
+                *value = if *swap { *value_b } else { *value_a };
+            }
+        }
+        CrossoverOperator::KPoint { k } => {
+            let n_arguments = parent_a.len();
+            let mut cuts = rand::seq::index::sample(
+                rng,
+                n_arguments.saturating_sub(1),
+                k.min(n_arguments.saturating_sub(1)),
+            )
+            .into_iter()
+            .map(|cut| cut + 1)
+            .collect::<Vec<usize>>();
+            cuts.sort_unstable();
+            for (idx, swap) in swapped.iter_mut().enumerate() {
+                *swap = cuts.iter().filter(|cut| **cut <= idx).count() % 2 == 1;
+            }
+            for (((value_a, value_b), value), swap) in values.zip(swapped.iter()) {
+                *value = if *swap { *value_b } else { *value_a };
+            }
+        }
+        CrossoverOperator::Arithmetic { per_gene } => {
+            let weight: f64 = rng.sample(Open01);
+            for ((value_a, value_b), value) in values {
+                let weight = if per_gene { rng.sample(Open01) } else { weight };
+                *value = weight * value_a + (1.0 - weight) * value_b;
+            }
+        }
+    }
+}
+
+/// Draw the multiplicative mutation of `n_arguments` arguments: a factor between 0.8-1.2
+/// that is not 1.0, so that a value is mutated, and the index of the argument to multiply.
+///
+/// # Arguments
+///
+/// * `n_arguments` - The number of arguments, at least one.
+/// * `rng` - The random number generator to sample with.
+pub(crate) fn draw_scaling<G>(n_arguments: usize, rng: &mut G) -> (usize, f64)
+where
+    G: Rng + ?Sized,
+{
+    let mut factor = rng.gen_range(0.8..1.2);
+    while factor == 1.0 {
+        factor = rng.gen_range(0.8..1.2);
+    }
+    (rng.gen_range(0..n_arguments), factor)
+}
+
+/// Shift every value with probability `gene_prob` by a step drawn from `draw`, and a random
+/// one if none is drawn.
+///
+/// # Arguments
+///
+/// * `values` - The arguments to shift, at least one.
+/// * `gene_prob` - The probability of every argument to be shifted.
+/// * `rng` - The random number generator to sample with.
+/// * `draw` - Draws the step of an argument.
+pub(crate) fn shift_values<G, D>(values: &mut [f64], gene_prob: f64, rng: &mut G, draw: D)
+where
+    G: Rng + ?Sized,
+    D: Fn(&mut G) -> f64,
+{
+    let mut mutated = (0..values.len())
+        .filter(|_| rng.gen_range(0.0..1.0) < gene_prob)
+        .collect::<Vec<usize>>();
+    if mutated.is_empty() {
+        mutated.push(rng.gen_range(0..values.len()));
+    }
+    for idx in mutated {
+        values[idx] += draw(rng);
+    }
+}
+
+/// Mutate the step sizes log-normally with a factor shared by all arguments and one per
+/// argument, then shift every value by normally distributed noise with its new step size.
+///
+/// # Arguments
+///
+/// * `values` - The arguments to shift.
+/// * `sigmas` - The step size of every argument.
+/// * `min_sigma` - The smallest step size.
+/// * `rng` - The random number generator to sample with.
+pub(crate) fn adapt_and_shift_values<G>(
+    values: &mut [f64],
+    sigmas: &mut [f64],
+    min_sigma: f64,
+    rng: &mut G,
+) where
+    G: Rng + ?Sized,
+{
+    // The usual learning rates of the shared and the individual factor.
+    let n_arguments = values.len() as f64;
+    let shared_rate = 1.0 / (2.0 * n_arguments).sqrt();
+    let individual_rate = 1.0 / (2.0 * n_arguments.sqrt()).sqrt();
+    let shared = shared_rate * rng::standard_normal(rng);
+    for (value, sigma) in values.iter_mut().zip(sigmas.iter_mut()) {
+        *sigma =
+            (*sigma * (shared + individual_rate * rng::standard_normal(rng)).exp()).max(min_sigma);
+        *value += *sigma * rng::standard_normal(rng);
+    }
+}
+impl<'a> Individual<'a> for Solution {
+    // The Distance matrix is needed by the individuals to compute their fitness on.
+    type IndividualCost = function::Function;
+    /// Mutate the solution by multiplying a random function argument with a factor between
+    /// 0.8-1.2
+    ///
+    /// # Arguments
+    ///
+    /// * `prob` - The probability with which on of the function values will mutated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::solution;
+    /// use genetic_algorithm_traits::Individual;
+    ///
+    /// let my_solution = solution::Solution::new(vec![1.0, 2.0, 3.0]);
+    /// println!("Solution before mutation: {}, solution after mutation: {}", my_solution, my_solution.clone().mutate(1.0));
+    /// ```
+    fn mutate(self, prob: f32) -> Self {
+        self.mutate_with_rng(prob, &mut rand::thread_rng())
+    }
     /// Crossover one solution with another. For a lack of creativity, this is currently just taking
-    /// the average of the two solutions.
+    /// the average of the two solutions. Step sizes the parents carry are averaged as well.
     ///
     /// # Arguments
     ///
@@ -228,14 +1265,7 @@ impl<'a> Individual<'a> for Solution {
     /// println!("{}", solution_to_crossover.crossover(&solution_to_crossover_with));
     /// ```
     fn crossover(&self, other: &Solution) -> Self {
-        if self.function_values.len() != other.get_arguments().len() {
-            // TODO: Crossover should return an Option or Result not panic.
-            panic!(
-                "Cannot crossover a Solution with {} elements when the other solution has {} elements",
-                self.function_values.len(),
-                other.get_arguments().len()
-            );
-        }
+        assert_same_length(self, other);
         Solution {
             function_values: self
                 .function_values
@@ -245,6 +1275,7 @@ impl<'a> Individual<'a> for Solution {
                     average(*self_function_value, *other_function_value)
                 })
                 .collect(),
+            sigmas: recombined_sigmas(self, other),
         }
     }
     /// Compute the fitness of a Solution, that is the specific function value of the `Function`
@@ -253,7 +1284,7 @@ impl<'a> Individual<'a> for Solution {
     /// # Arguments
     ///
     /// * `function` - The function that is should be used to compute of the function value of the
-    /// solution's arguments.
+    ///   solution's arguments.
     ///
     /// # Examples
     ///
@@ -276,9 +1307,8 @@ impl<'a> Individual<'a> for Solution {
     /// ```
     ///
     fn fitness(&self, function: &function::Function) -> f64 {
-        function
-            .get_function_value(self.function_values.clone())
-            .unwrap()
+        self.try_fitness(function)
+            .unwrap_or_else(|error| panic!("Could not compute the fitness of {}: {}", self, error))
     }
 }
 
@@ -290,17 +1320,31 @@ mod tests {
 
         #[test]
         fn no_rounding_fewer_digts() {
-            assert_eq!(f64_to_rounded_string(1.57, 3), String::from("1.570"))
+            assert_eq!(f64_to_significant_string(1.57, 4), String::from("1.570e0"))
         }
 
         #[test]
         fn no_rounding_same_digts() {
-            assert_eq!(f64_to_rounded_string(1.572, 3), String::from("1.572"))
+            assert_eq!(f64_to_significant_string(1.572, 4), String::from("1.572e0"))
         }
 
         #[test]
         fn actual_rounding() {
-            assert_eq!(f64_to_rounded_string(2.38493, 2), String::from("2.38"))
+            assert_eq!(
+                f64_to_significant_string(2.38493, 3),
+                String::from("2.38e0")
+            )
+        }
+        #[test]
+        fn rounding_is_relative_to_the_magnitude() {
+            assert_eq!(
+                f64_to_significant_string(2.38493e-20, 3),
+                String::from("2.38e-20")
+            );
+            assert_eq!(
+                f64_to_significant_string(-2.38493e20, 3),
+                String::from("-2.38e20")
+            );
         }
         #[test]
         fn integration_f64_to_floating_point_precision_string() {
@@ -319,6 +1363,20 @@ mod tests {
             Solution::new(vec![1.0, 2.0, 3.0]);
         }
         #[test]
+        fn compare_arguments_is_lexicographic() {
+            assert_eq!(
+                compare_arguments(
+                    &Solution::new(vec![1.0, 3.0]),
+                    &Solution::new(vec![1.0, 2.0])
+                ),
+                Ordering::Greater
+            );
+            assert_eq!(
+                compare_arguments(&Solution::new(vec![1.0]), &Solution::new(vec![1.0, 2.0])),
+                Ordering::Less
+            );
+        }
+        #[test]
         fn test_display() {
             assert_eq!(
                 format!("{}", Solution::new(vec![1.1, 2.2, 3.3])),
@@ -326,6 +1384,17 @@ mod tests {
             );
         }
         #[test]
+        fn test_scientific_display() {
+            assert_eq!(
+                format!("{:e}", Solution::new(vec![1.5e-12, 3e20])),
+                "Solution([1.5e-12, 3e20])",
+            );
+            assert_eq!(
+                format!("{:.1e}", Solution::new(vec![1234.0])),
+                "Solution([1.2e3])",
+            );
+        }
+        #[test]
         fn fitness() {
             assert_eq!(
                 Solution::new(vec![2.0, 3.0, 5.0]).fitness(&function::Function::new(
@@ -385,6 +1454,17 @@ mod tests {
                 );
             }
             #[test]
+            fn tiny_values_equal_zero() {
+                assert!(Solution::new(vec![1e-12]) == Solution::new(vec![0.0]));
+                assert!(Solution::new(vec![-1e-12]) == Solution::new(vec![1e-13]));
+                assert!(Solution::new(vec![1e-9]) != Solution::new(vec![0.0]));
+            }
+            #[test]
+            fn large_values_are_compared_relatively() {
+                assert!(Solution::new(vec![1e20]) == Solution::new(vec![1e20 + 1e5]));
+                assert!(Solution::new(vec![1e20]) != Solution::new(vec![1.01e20]));
+            }
+            #[test]
             fn non_equal_solutions_different_length() {
                 assert!(!(Solution::new(vec![1.0000000001]) == Solution::new(vec![1.0, 2.0, 3.0])));
             }
@@ -393,15 +1473,40 @@ mod tests {
             use super::*;
             #[test]
             fn sample_int_range() {
-                get_random_elem_from_range(0..10);
+                get_random_elem_from_range(0..10, &mut rand::thread_rng());
             }
             #[test]
             fn sample_float_range() {
-                get_random_elem_from_range(0.0..1.0);
+                get_random_elem_from_range(0.0..1.0, &mut rand::thread_rng());
             }
             #[test]
             fn sample_empty_range() {
-                assert_eq!(get_random_elem_from_range(0..0), None);
+                assert_eq!(
+                    get_random_elem_from_range(0..0, &mut rand::thread_rng()),
+                    None
+                );
+            }
+        }
+        #[test]
+        fn precision_follows_the_bounds() {
+            // A population of ten resolves [0, 10] at 1e-6 and needs seven digits at 10.
+            assert_eq!(
+                Precision::for_bounds(&[0.0..10.0, -5.0..5.0], 10),
+                Precision {
+                    significant_digits: 7,
+                    absolute_floor: 1e-6,
+                }
+            );
+            // Far from zero the same width needs more digits.
+            let shifted = Precision::for_bounds(&[1e6..1e6 + 10.0], 10);
+            assert_eq!(shifted.significant_digits, 13);
+            // Larger populations resolve finer.
+            assert!(
+                Precision::for_bounds(&[0.0..10.0], 1_000).absolute_floor
+                    < Precision::for_bounds(&[0.0..10.0], 10).absolute_floor
+            );
+            for degenerate in [vec![], vec![1.0..1.0], vec![f64::NEG_INFINITY..0.0]] {
+                assert_eq!(Precision::for_bounds(&degenerate, 10), Precision::default());
             }
         }
         mod test_hash {
@@ -423,8 +1528,8 @@ mod tests {
             #[test]
             fn hash_different_solution() {
                 assert!(
-                    !(_create_hash(Solution::new(vec![1.0, 3.0, 3.0]))
-                        == _create_hash(Solution::new(vec![1.0, 2.0, 3.0])))
+                    _create_hash(Solution::new(vec![1.0, 3.0, 3.0]))
+                        != _create_hash(Solution::new(vec![1.0, 2.0, 3.0]))
                 );
             }
             #[test]
@@ -437,8 +1542,8 @@ mod tests {
             #[test]
             fn hash_solutions_different_length() {
                 assert!(
-                    !(_create_hash(Solution::new(vec![1.00000000001, 2.0]))
-                        == _create_hash(Solution::new(vec![1.0, 2.0, 3.0])))
+                    _create_hash(Solution::new(vec![1.00000000001, 2.0]))
+                        != _create_hash(Solution::new(vec![1.0, 2.0, 3.0]))
                 );
             }
         }
@@ -451,36 +1556,170 @@ mod tests {
                     Solution::new(vec![1.0, 2.0, 3.0])
                 )
             }
-            // Run the following test a few times.
-            #[test]
-            #[test]
             #[test]
+            fn mutation_applied() {
+                // Mutate a few times, every mutation changes a random parameter.
+                for _ in 0..6 {
+                    let original_solution = Solution::new(vec![1.0, 2.0, 3.0]);
+                    let mutated_solution = original_solution.clone().mutate(1.0);
+                    // original solution and mutated_solution should be different for exactly
+                    // one function paramter.
+                    let original_parameters = original_solution.get_arguments();
+                    let mutated_parameters = mutated_solution.get_arguments();
+                    assert_eq!(
+                        original_parameters
+                            .iter()
+                            .zip(mutated_parameters.iter())
+                            .map(
+                                |(original_parameter, mutated_parameter)| (*original_parameter
+                                    == *mutated_parameter)
+                                    as usize
+                            )
+                            .sum::<usize>(),
+                        2
+                    )
+                }
+            }
+        }
+        mod test_mutate_with_operator {
+            use super::*;
+            use crate::rng::SplitMix64;
             #[test]
+            fn gaussian_mutation_moves_zeros() {
+                let mut rng = SplitMix64::new(6);
+                let origin = Solution::new(vec![0.0; 4]);
+                let every_gene = MutationOperator::Gaussian {
+                    sigma: 1.0,
+                    gene_prob: 1.0,
+                };
+                let mutated = origin
+                    .clone()
+                    .mutate_with_operator(every_gene, 1.0, &mut rng);
+                assert!(mutated.get_arguments().iter().all(|x| *x != 0.0));
+                // Without any gene drawn, exactly one argument is mutated.
+                let no_gene = MutationOperator::Gaussian {
+                    sigma: 1.0,
+                    gene_prob: 0.0,
+                };
+                for _ in 0..10 {
+                    let mutated = origin.clone().mutate_with_operator(no_gene, 1.0, &mut rng);
+                    assert_eq!(
+                        mutated
+                            .get_arguments()
+                            .iter()
+                            .filter(|x| **x != 0.0)
+                            .count(),
+                        1
+                    );
+                }
+                assert_eq!(
+                    origin
+                        .clone()
+                        .mutate_with_operator(every_gene, 0.0, &mut rng),
+                    origin
+                );
+                assert_eq!(
+                    origin.clone().mutate_with_operator(
+                        MutationOperator::Multiplicative,
+                        1.0,
+                        &mut rng
+                    ),
+                    origin
+                );
+            }
             #[test]
+            fn cauchy_mutation_jumps_further_than_gaussian() {
+                let mut rng = SplitMix64::new(3);
+                let origin = Solution::new(vec![0.0]);
+                let longest = |operator, rng: &mut SplitMix64| {
+                    (0..1000)
+                        .map(|_| {
+                            origin
+                                .clone()
+                                .mutate_with_operator(operator, 1.0, rng)
+                                .get_arguments()[0]
+                                .abs()
+                        })
+                        .fold(0.0, f64::max)
+                };
+                let cauchy = longest(
+                    MutationOperator::Cauchy {
+                        scale: 0.1,
+                        gene_prob: 1.0,
+                    },
+                    &mut rng,
+                );
+                let gaussian = longest(
+                    MutationOperator::Gaussian {
+                        sigma: 0.1,
+                        gene_prob: 1.0,
+                    },
+                    &mut rng,
+                );
+                assert!(cauchy > 5.0 && gaussian < 0.5);
+            }
             #[test]
-            fn mutation_applied() {
-                let original_solution = Solution::new(vec![1.0, 2.0, 3.0]);
-                let mutated_solution = original_solution.clone().mutate(1.0);
-                // original solution and mutated_solution should be different for exactly
-                // one function paramter.
-                let original_parameters = original_solution.get_arguments();
-                let mutated_parameters = mutated_solution.get_arguments();
+            fn polynomial_mutation_stays_within_the_bounds() {
+                let mut rng = SplitMix64::new(9);
+                let bounds = vec![0.0..1.0, -2.0..2.0, 5.0..5.0];
+                let origin = Solution::new(vec![1.0, 0.0, 5.0]);
+                let mut n_changed = 0;
+                for _ in 0..200 {
+                    let mutated = origin
+                        .clone()
+                        .mutate_polynomial(1.0, 20.0, &bounds, &mut rng)
+                        .get_arguments();
+                    for (argument, bound) in mutated.iter().zip(&bounds) {
+                        assert!(bound.start <= *argument && *argument <= bound.end);
+                    }
+                    // The empty bound leaves its argument alone.
+                    assert_eq!(mutated[2], 5.0);
+                    n_changed += (mutated[..2] != [1.0, 0.0]) as usize;
+                }
+                assert!(n_changed > 100);
+                // A large distribution index keeps the arguments close to their values.
+                let close = origin
+                    .clone()
+                    .mutate_polynomial(1.0, 1e6, &bounds, &mut rng)
+                    .get_arguments();
+                assert!((close[0] - 1.0).abs() < 1e-3 && close[1].abs() < 1e-3);
                 assert_eq!(
-                    original_parameters
-                        .iter()
-                        .zip(mutated_parameters.iter())
-                        .map(
-                            |(original_parameter, mutated_parameter)| (*original_parameter
-                                == *mutated_parameter)
-                                as usize
-                        )
-                        .sum::<usize>(),
-                    2
-                )
+                    origin
+                        .clone()
+                        .mutate_polynomial(0.0, 20.0, &bounds, &mut rng),
+                    origin
+                );
+            }
+            #[test]
+            fn self_adaptive_mutation_evolves_the_step_sizes() {
+                let mut rng = SplitMix64::new(5);
+                let operator = MutationOperator::SelfAdaptive {
+                    initial_sigma: 1.0,
+                    min_sigma: 0.5,
+                };
+                let mutated =
+                    Solution::new(vec![0.0, 0.0]).mutate_with_operator(operator, 1.0, &mut rng);
+                let sigmas = mutated.get_sigmas().unwrap();
+                assert_eq!(sigmas.len(), 2);
+                assert!(sigmas.iter().all(|sigma| *sigma >= 0.5 && *sigma != 1.0));
+                // Small step sizes carried by the solution keep its mutations small.
+                let small = Solution::new(vec![0.0, 0.0]).with_sigmas(vec![1e-6, 1e-6]);
+                let operator = MutationOperator::SelfAdaptive {
+                    initial_sigma: 1.0,
+                    min_sigma: 0.0,
+                };
+                for _ in 0..100 {
+                    let mutated = small
+                        .clone()
+                        .mutate_with_operator(operator, 1.0, &mut rng)
+                        .get_arguments();
+                    assert!(mutated.iter().all(|argument| argument.abs() < 1e-3));
+                }
             }
         }
         mod test_crossover {
             use super::*;
+            use crate::rng::SplitMix64;
             #[test]
             fn same_inidividual_result_in_same_individual() {
                 let solution = Solution::new(vec![1.0, 4.0, 7.0]);
@@ -496,6 +1735,119 @@ mod tests {
                 );
             }
             #[test]
+            fn step_sizes_are_inherited() {
+                let adaptive = Solution::new(vec![0.0, 0.0]).with_sigmas(vec![1.0, 3.0]);
+                let plain = Solution::new(vec![2.0, 2.0]);
+                assert_eq!(
+                    adaptive.crossover(&plain).get_sigmas(),
+                    Some(vec![1.0, 3.0])
+                );
+                let other = Solution::new(vec![2.0, 2.0]).with_sigmas(vec![3.0, 1.0]);
+                assert_eq!(
+                    adaptive.crossover(&other).get_sigmas(),
+                    Some(vec![2.0, 2.0])
+                );
+                assert_eq!(plain.crossover(&plain).get_sigmas(), None);
+            }
+            #[test]
+            fn blend_crossover_spreads_around_the_parents() {
+                let mut rng = SplitMix64::new(4);
+                let (parent_a, parent_b) = (Solution::new(vec![0.0]), Solution::new(vec![1.0]));
+                let operator = CrossoverOperator::Blend { alpha: 0.5 };
+                let children = (0..1000)
+                    .map(|_| {
+                        parent_a
+                            .crossover_with_operator(&parent_b, operator, &mut rng)
+                            .get_arguments()[0]
+                    })
+                    .collect::<Vec<f64>>();
+                assert!(children.iter().all(|child| (-0.5..=1.5).contains(child)));
+                // Unlike averaging, children beyond both parents keep the spread.
+                assert!(children.iter().any(|child| *child < 0.0));
+                assert!(children.iter().any(|child| *child > 1.0));
+                assert_eq!(
+                    parent_a.crossover_with_operator(&parent_a, operator, &mut rng),
+                    parent_a
+                );
+            }
+            #[test]
+            fn uniform_and_k_point_crossover_take_arguments_from_either_parent() {
+                let mut rng = SplitMix64::new(6);
+                let parent_a = Solution::new(vec![0.0; 6]).with_sigmas(vec![0.1; 6]);
+                let parent_b = Solution::new(vec![1.0; 6]).with_sigmas(vec![0.2; 6]);
+                for _ in 0..50 {
+                    let child = parent_a.crossover_with_operator(
+                        &parent_b,
+                        CrossoverOperator::Uniform { swap_prob: 0.5 },
+                        &mut rng,
+                    );
+                    // Every step size belongs to the argument it came with.
+                    for (argument, sigma) in child
+                        .get_arguments()
+                        .iter()
+                        .zip(child.get_sigmas().unwrap())
+                    {
+                        assert_eq!(sigma, 0.1 + 0.1 * argument);
+                    }
+                    let child = parent_a
+                        .crossover_with_operator(
+                            &parent_b,
+                            CrossoverOperator::KPoint { k: 2 },
+                            &mut rng,
+                        )
+                        .get_arguments();
+                    // Two cuts make three segments: a prefix and a suffix of the first parent
+                    // around a block of the second one.
+                    let n_switches = child.windows(2).filter(|pair| pair[0] != pair[1]).count();
+                    assert_eq!(child[0], 0.0);
+                    assert!(n_switches == 2 || (n_switches == 1 && child[5] == 1.0));
+                }
+                // More cut points than gaps alternate every argument.
+                assert_eq!(
+                    parent_a
+                        .crossover_with_operator(
+                            &parent_b,
+                            CrossoverOperator::KPoint { k: 10 },
+                            &mut rng
+                        )
+                        .get_arguments(),
+                    vec![0.0, 1.0, 0.0, 1.0, 0.0, 1.0]
+                );
+            }
+            #[test]
+            fn arithmetic_crossover_weights_the_parents() {
+                let mut rng = SplitMix64::new(4);
+                let parent_a = Solution::new(vec![0.0, 0.0, 0.0]);
+                let parent_b = Solution::new(vec![1.0, 2.0, 4.0]);
+                let mut weights = Vec::new();
+                for _ in 0..50 {
+                    let child = parent_a
+                        .crossover_with_operator(
+                            &parent_b,
+                            CrossoverOperator::Arithmetic { per_gene: false },
+                            &mut rng,
+                        )
+                        .get_arguments();
+                    // A single weight keeps the child on the segment between its parents.
+                    assert!(child[0] > 0.0 && child[0] < 1.0);
+                    assert!((child[1] - 2.0 * child[0]).abs() < 1e-12);
+                    assert!((child[2] - 4.0 * child[0]).abs() < 1e-12);
+                    weights.push(child[0]);
+                    let child = parent_a
+                        .crossover_with_operator(
+                            &parent_b,
+                            CrossoverOperator::Arithmetic { per_gene: true },
+                            &mut rng,
+                        )
+                        .get_arguments();
+                    assert!(child[0] > 0.0 && child[0] < 1.0);
+                    assert!(child[2] > 0.0 && child[2] < 4.0);
+                }
+                // The weight is drawn anew for every mating.
+                assert!(weights.iter().any(|weight| *weight < 0.3));
+                assert!(weights.iter().any(|weight| *weight > 0.7));
+            }
+            #[test]
             #[should_panic]
             fn crossover_solution_different_length() {
                 let solution_to_crossover = Solution::new(vec![12.0, 3.0]);
@@ -1,19 +1,143 @@
+use crate::clock::{Clock, SystemClock};
+use crate::constraint::{self, Constraints};
+use crate::error::Error;
 use crate::function::Function;
-use crate::solution::Solution;
+use crate::runner::{Parallelism, Runner, RunnerConfig};
+use crate::solution::{self, Solution};
 use genetic_algorithm_traits::{Individual, Population};
 use rand::distributions::uniform::SampleRange;
+use rand::Rng;
 use std::fmt;
 
 use crossbeam_utils::thread;
 use std::collections::HashSet;
 use std::convert::From;
-use std::time::Instant;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Decides when two solutions are the same individual, e.g. to compare only some of the
+/// arguments or to canonicalize an encoding with symmetries. Without an identity solutions
+/// are the same if their arguments are equal at the precision of `Solution`'s `PartialEq`.
+pub trait Identity: Send + Sync {
+    /// Return the canonical form of `solution`. Solutions with equal canonical forms are the
+    /// same individual.
+    ///
+    /// # Arguments
+    ///
+    /// * `solution` - The solution to canonicalize.
+    fn canonical(&self, solution: &Solution) -> Solution;
+}
+
+impl<F> Identity for F
+where
+    F: Fn(&Solution) -> Solution + Send + Sync,
+{
+    fn canonical(&self, solution: &Solution) -> Solution {
+        self(solution)
+    }
+}
+
+/// Solutions are the same individual if the arguments at the given indices are equal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubsetIdentity {
+    indices: Vec<usize>,
+}
+
+impl SubsetIdentity {
+    /// Only compare the arguments at `indices`.
+    ///
+    /// # Arguments
+    ///
+    /// * `indices` - The indices of the arguments that identify a solution.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::solution::Solution;
+    /// use genetic_algorithm_fn::solutions::{Solutions, SubsetIdentity};
+    /// use genetic_algorithm_traits::Population;
+    ///
+    /// let population = Solutions::from(vec![
+    ///     Solution::new(vec![1.0, 2.0]),
+    ///     Solution::new(vec![1.0, 3.0]),
+    /// ])
+    /// .with_identity(SubsetIdentity::new(vec![0]));
+    /// assert_eq!(population.iter().count(), 1);
+    /// ```
+    pub fn new(indices: Vec<usize>) -> Self {
+        SubsetIdentity { indices }
+    }
+}
+
+impl Identity for SubsetIdentity {
+    fn canonical(&self, solution: &Solution) -> Solution {
+        let arguments = solution.get_arguments();
+        Solution::new(
+            self.indices
+                .iter()
+                .filter_map(|idx| arguments.get(*idx).copied())
+                .collect(),
+        )
+    }
+}
 
 /// The `Solution` is the container for your current pool of `solution`'s.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Clone)]
 pub struct Solutions {
     /// The unique solutions that currently exist.
     solutions: HashSet<Solution>,
+    /// Decides which solutions are the same, `None` uses the equality of `Solution`.
+    identity: Option<Arc<dyn Identity>>,
+    /// The constraints the fittest solutions are chosen by first, if any.
+    constraints: Option<Constraints>,
+}
+
+impl fmt::Debug for Solutions {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("Solutions")
+            .field("solutions", &self.solutions)
+            .field("custom_identity", &self.identity.is_some())
+            .field("constraints", &self.constraints)
+            .finish()
+    }
+}
+
+/// Pools are equal if they contain the same solutions, regardless of their identity.
+impl PartialEq for Solutions {
+    fn eq(&self, other: &Self) -> bool {
+        self.solutions == other.solutions
+    }
+}
+
+/// Collect the solutions that are unique according to `identity`, keeping the first one of
+/// every group of identical solutions.
+///
+/// # Arguments
+///
+/// * `solutions` - The solutions to collect.
+/// * `identity` - Decides which solutions are the same, `None` uses the equality of `Solution`.
+fn collect_unique<I>(solutions: I, identity: Option<Arc<dyn Identity>>) -> Solutions
+where
+    I: IntoIterator<Item = Solution>,
+{
+    let solutions = match &identity {
+        Some(identity) => {
+            let mut seen = HashSet::new();
+            solutions
+                .into_iter()
+                .filter(|solution| seen.insert(identity.canonical(solution)))
+                .collect()
+        }
+        None => solutions.into_iter().collect(),
+    };
+    Solutions {
+        solutions,
+        identity,
+        constraints: None,
+    }
 }
 // Convert a Vector of solution's to a `Solutions`-object.
 impl From<Vec<Solution>> for Solutions {
@@ -22,7 +146,7 @@ impl From<Vec<Solution>> for Solutions {
     /// # Arguments
     ///
     /// * `solutions` - The solutions you collected so far and would like to put into your
-    /// Solutions.
+    ///   Solutions.
     ///
     /// # Examples
     ///
@@ -37,9 +161,7 @@ impl From<Vec<Solution>> for Solutions {
     /// println!("Current solutions: {}", my_solutions);
     /// ```
     fn from(solution: Vec<Solution>) -> Self {
-        Solutions {
-            solutions: solution.into_iter().collect(),
-        }
+        collect_unique(solution, None)
     }
 }
 
@@ -74,17 +196,463 @@ impl Solutions {
     pub fn random<R>(n_solutions: usize, range: R, length: usize) -> Self
     where
         R: SampleRange<f64> + Clone,
+    {
+        Solutions::random_with_rng(n_solutions, range, length, &mut rand::thread_rng())
+    }
+    /// Create a pool of random solutions, drawing all random numbers from `rng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_solutions` - The number of solutions your population should contain.
+    /// * `range` - The range the function arguments are sampled from.
+    /// * `length` - The number of function arguments of each solution.
+    /// * `rng` - The random number generator to sample with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::solutions;
+    /// use rand::SeedableRng;
+    ///
+    /// let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    /// println!("{}", solutions::Solutions::random_with_rng(5, 1.0..10.0, 3, &mut rng));
+    /// ```
+    pub fn random_with_rng<R, G>(n_solutions: usize, range: R, length: usize, rng: &mut G) -> Self
+    where
+        R: SampleRange<f64> + Clone,
+        G: Rng + ?Sized,
+    {
+        Solutions::try_random_with_rng(n_solutions, range, length, rng)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+    /// Create a pool of random solutions like `random_with_rng`, or fail if the range is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_solutions` - The number of solutions your population should contain.
+    /// * `range` - The range the function arguments are sampled from.
+    /// * `length` - The number of function arguments of each solution.
+    /// * `rng` - The random number generator to sample with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::error::Error;
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// assert!(Solutions::try_random_with_rng(5, 1.0..10.0, 3, &mut rng).is_ok());
+    /// assert!(matches!(
+    ///     Solutions::try_random_with_rng(5, 10.0..1.0, 3, &mut rng),
+    ///     Err(Error::EmptyRange)
+    /// ));
+    /// ```
+    pub fn try_random_with_rng<R, G>(
+        n_solutions: usize,
+        range: R,
+        length: usize,
+        rng: &mut G,
+    ) -> Result<Self, Error>
+    where
+        R: SampleRange<f64> + Clone,
+        G: Rng + ?Sized,
     {
         let mut routes = HashSet::new();
 
         while routes.len() < n_solutions {
-            routes.insert(Solution::random(range.clone(), length));
+            routes.insert(Solution::try_random_with_rng(range.clone(), length, rng)?);
         }
 
-        Solutions { solutions: routes }
+        Ok(Solutions {
+            solutions: routes,
+            identity: None,
+            constraints: None,
+        })
+    }
+    /// Write the solutions to a CSV file, one solution per row and one column per argument.
+    /// The header names the columns `x0`, `x1`, ... The rows are sorted by their arguments.
+    /// Fails with `io::ErrorKind::InvalidInput` if the solutions don't all have the same
+    /// number of arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to write to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let path = std::env::temp_dir().join(format!(
+    ///     "genetic_algorithm_fn_to_csv_example_{}.csv",
+    ///     std::process::id()
+    /// ));
+    /// let population = Solutions::random(5, 1.0..10.0, 3);
+    /// population.to_csv(&path).unwrap();
+    /// assert_eq!(Solutions::from_csv(&path).unwrap(), population);
+    /// ```
+    pub fn to_csv<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let n_arguments = self.n_arguments()?;
+        let mut solutions = self.solutions.iter().collect::<Vec<&Solution>>();
+        solutions
+            .sort_by(|solution_a, solution_b| solution::compare_arguments(solution_a, solution_b));
+        let rows = solutions
+            .into_iter()
+            .map(|solution| csv_row(solution, None))
+            .collect();
+        fs::write(path, csv(n_arguments, false, rows))
+    }
+    /// Write the solutions to a CSV file like `to_csv`, with an additional `fitness` column.
+    /// The rows are sorted from the fittest to the least fit solution, solutions `function`
+    /// can't be evaluated at have an empty fitness and come last.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to write to.
+    /// * `function` - The function that computes the fitness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::solution::Solution;
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let path = std::env::temp_dir().join(format!(
+    ///     "genetic_algorithm_fn_csv_fitness_example_{}.csv",
+    ///     std::process::id()
+    /// ));
+    /// Solutions::from(vec![Solution::new(vec![1.0, 2.0]), Solution::new(vec![3.0, 4.0])])
+    ///     .to_csv_with_fitness(&path, &Function::new(|x| Ok(x.iter().sum())))
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     std::fs::read_to_string(&path).unwrap(),
+    ///     "x0,x1,fitness\n3,4,7\n1,2,3\n"
+    /// );
+    /// ```
+    pub fn to_csv_with_fitness<P: AsRef<Path>>(
+        &self,
+        path: P,
+        function: &Function,
+    ) -> io::Result<()> {
+        let n_arguments = self.n_arguments()?;
+        let mut evaluated = self
+            .solutions
+            .iter()
+            .map(|solution| {
+                (
+                    solution,
+                    function.get_function_value(solution.get_arguments()).ok(),
+                )
+            })
+            .collect::<Vec<(&Solution, Option<f64>)>>();
+        evaluated.sort_by(|(solution_a, fitness_a), (solution_b, fitness_b)| {
+            match (fitness_a, fitness_b) {
+                (Some(fitness_a), Some(fitness_b)) => fitness_b.total_cmp(fitness_a),
+                (fitness_a, fitness_b) => fitness_b.is_some().cmp(&fitness_a.is_some()),
+            }
+            .then_with(|| solution::compare_arguments(solution_a, solution_b))
+        });
+        let rows = evaluated
+            .into_iter()
+            .map(|(solution, fitness)| csv_row(solution, Some(fitness)))
+            .collect();
+        fs::write(path, csv(n_arguments, true, rows))
+    }
+    /// Read solutions from a CSV file, e.g. one written by `to_csv` or by a spreadsheet. A
+    /// header row is optional, a column whose header is `fitness` is ignored. Empty lines are
+    /// skipped and duplicated rows are only kept once.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to read from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::solution::Solution;
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let path = std::env::temp_dir().join(format!(
+    ///     "genetic_algorithm_fn_from_csv_example_{}.csv",
+    ///     std::process::id()
+    /// ));
+    /// std::fs::write(&path, "a,fitness,b\n1.5,10,2\n-3,,4e2\n").unwrap();
+    /// assert_eq!(
+    ///     Solutions::from_csv(&path).unwrap(),
+    ///     Solutions::from(vec![Solution::new(vec![1.5, 2.0]), Solution::new(vec![-3.0, 400.0])])
+    /// );
+    /// ```
+    pub fn from_csv<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let invalid = |line: usize, message: String| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Line {}: {}", line + 1, message),
+            )
+        };
+        let mut lines = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .peekable();
+        // The first row is a header if it isn't made of numbers.
+        let mut fitness_column = None;
+        if let Some((_, first)) = lines.peek() {
+            let cells = csv_cells(first);
+            if cells.iter().any(|cell| cell.parse::<f64>().is_err()) {
+                fitness_column = cells.iter().position(|cell| *cell == "fitness");
+                lines.next();
+            }
+        }
+        let mut solutions = Vec::new();
+        let mut n_arguments = None;
+        for (index, line) in lines {
+            let arguments = csv_cells(line)
+                .into_iter()
+                .enumerate()
+                .filter(|(column, _)| Some(*column) != fitness_column)
+                .map(|(_, cell)| {
+                    cell.parse::<f64>()
+                        .map_err(|_| invalid(index, format!("`{}` is no number", cell)))
+                })
+                .collect::<io::Result<Vec<f64>>>()?;
+            if *n_arguments.get_or_insert(arguments.len()) != arguments.len() {
+                return Err(invalid(
+                    index,
+                    format!(
+                        "Expected {} arguments but got {}",
+                        n_arguments.unwrap_or_default(),
+                        arguments.len()
+                    ),
+                ));
+            }
+            solutions.push(Solution::new(arguments));
+        }
+        Ok(Solutions::from(solutions))
+    }
+    /// Decide with `identity` which solutions are the same individual. Solutions that are
+    /// identical to one already in the pool are dropped, here and in every pool derived from
+    /// this one, e.g. by `evolve` or a `Runner` starting from it.
+    ///
+    /// # Arguments
+    ///
+    /// * `identity` - Decides which solutions are the same.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::solution::Solution;
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    /// use genetic_algorithm_traits::Population;
+    ///
+    /// // The sign of the arguments doesn't matter.
+    /// let population = Solutions::from(vec![
+    ///     Solution::new(vec![1.0, -2.0]),
+    ///     Solution::new(vec![-1.0, 2.0]),
+    /// ])
+    /// .with_identity(|solution: &Solution| {
+    ///     Solution::new(solution.get_arguments().iter().map(|x| x.abs()).collect())
+    /// });
+    /// assert_eq!(population.iter().count(), 1);
+    /// ```
+    pub fn with_identity<I>(self, identity: I) -> Self
+    where
+        I: Identity + 'static,
+    {
+        self.with_shared_identity(Some(Arc::new(identity)))
+    }
+    /// Replace the identity of the pool and drop the solutions that became duplicates.
+    ///
+    /// # Arguments
+    ///
+    /// * `identity` - Decides which solutions are the same, `None` uses the equality of
+    ///   `Solution`.
+    pub(crate) fn with_shared_identity(self, identity: Option<Arc<dyn Identity>>) -> Self {
+        let mut solutions = self.solutions.into_iter().collect::<Vec<Solution>>();
+        // Keep the same representative of every group regardless of the `HashSet` order.
+        solutions.sort_by(solution::compare_arguments);
+        collect_unique(solutions, identity)
+    }
+    /// The identity of the pool, `None` if it uses the equality of `Solution`.
+    pub(crate) fn identity(&self) -> Option<Arc<dyn Identity>> {
+        self.identity.clone()
+    }
+    /// Choose the fittest solutions by Deb's feasibility rules, see
+    /// `constraint::compare_feasibility`: feasible solutions come first, infeasible ones are
+    /// ordered by how much they violate `constraints`. Pools derived from this one by
+    /// `get_fittest_population` or `evolve` keep the constraints.
+    ///
+    /// # Arguments
+    ///
+    /// * `constraints` - The constraints a solution has to satisfy to be feasible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::constraint::Constraints;
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::solution::Solution;
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    /// use genetic_algorithm_traits::Population;
+    ///
+    /// // Maximize x0 + x1 subject to x0 + x1 <= 1.
+    /// let population = Solutions::from(vec![
+    ///     Solution::new(vec![0.5, 0.5]),
+    ///     Solution::new(vec![0.2, 0.3]),
+    ///     Solution::new(vec![1.0, 1.0]),
+    /// ])
+    /// .with_constraints(
+    ///     Constraints::new()
+    ///         .with_constraint(|solution: &Solution| solution.get_arguments().iter().sum::<f64>() - 1.0),
+    /// );
+    /// assert_eq!(
+    ///     population.get_n_fittest(1, &Function::new(|x| Ok(x.iter().sum()))),
+    ///     vec![Solution::new(vec![0.5, 0.5])]
+    /// );
+    /// ```
+    pub fn with_constraints(mut self, constraints: Constraints) -> Self {
+        self.constraints = Some(constraints);
+        self
+    }
+    /// Get the `n` fittest individuals and their fitness, ordered like `get_n_fittest`, but
+    /// borrowed from the population instead of cloned. Use it to inspect the fittest
+    /// individuals without copying their arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - How many individuals to return.
+    /// * `function` - The function to maximize.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::solution::Solution;
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    ///
+    /// let population = Solutions::from(vec![
+    ///     Solution::new(vec![2.0, 1.0]),
+    ///     Solution::new(vec![1.0, 2.0]),
+    ///     Solution::new(vec![0.0, 0.0]),
+    /// ]);
+    /// let fittest = population.fittest_refs(2, &Function::new(|x| Ok(x.iter().sum())));
+    /// assert_eq!(fittest.len(), 2);
+    /// assert_eq!(fittest[0], (&Solution::new(vec![1.0, 2.0]), 3.0));
+    /// ```
+    pub fn fittest_refs(&self, n: usize, function: &Function) -> Vec<(&Solution, f64)> {
+        if let Some(constraints) = &self.constraints {
+            let mut individuals = self
+                .solutions
+                .iter()
+                .map(|solution| {
+                    (
+                        solution,
+                        solution.fitness(function),
+                        constraints.violation(solution),
+                    )
+                })
+                .collect::<Vec<(&Solution, f64, f64)>>();
+            individuals.sort_by(
+                |(solution_a, fitness_a, violation_a), (solution_b, fitness_b, violation_b)| {
+                    constraint::compare_feasibility(
+                        (solution_a, *fitness_a, *violation_a),
+                        (solution_b, *fitness_b, *violation_b),
+                    )
+                },
+            );
+            return individuals
+                .into_iter()
+                .take(n)
+                .map(|(solution, fitness, _)| (solution, fitness))
+                .collect();
+        }
+        let mut individuals = self
+            .solutions
+            .iter()
+            .map(|solution| (solution, solution.fitness(function)))
+            .collect::<Vec<(&Solution, f64)>>();
+        individuals.sort_by(|candidate_a, candidate_b| {
+            solution::compare_fitness(*candidate_a, *candidate_b)
+        });
+        individuals.truncate(n);
+        individuals
+    }
+    /// The number of arguments of the solutions, `0` if there are none. Fails if they don't
+    /// all have the same number of arguments, the rows of a CSV file would be ragged then.
+    fn n_arguments(&self) -> io::Result<usize> {
+        let mut lengths = self
+            .solutions
+            .iter()
+            .map(|solution| solution.get_arguments().len());
+        let n_arguments = lengths.next().unwrap_or(0);
+        match lengths.find(|length| *length != n_arguments) {
+            Some(length) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Cannot write solutions with {} and {} arguments as rows of the same CSV file",
+                    n_arguments, length
+                ),
+            )),
+            None => Ok(n_arguments),
+        }
     }
 }
 
+/// Split a CSV row into its trimmed cells.
+///
+/// # Arguments
+///
+/// * `line` - The row to split.
+fn csv_cells(line: &str) -> Vec<&str> {
+    line.split(',').map(str::trim).collect()
+}
+
+/// Write a solution as a CSV row.
+///
+/// # Arguments
+///
+/// * `solution` - The solution to write.
+/// * `fitness` - The content of the fitness column, `None` if there is no fitness column.
+fn csv_row(solution: &Solution, fitness: Option<Option<f64>>) -> String {
+    let mut cells = solution
+        .get_arguments()
+        .iter()
+        .map(|argument| argument.to_string())
+        .collect::<Vec<String>>();
+    if let Some(fitness) = fitness {
+        cells.push(
+            fitness
+                .map(|fitness| fitness.to_string())
+                .unwrap_or_default(),
+        );
+    }
+    cells.join(",")
+}
+
+/// Put together a CSV document from its rows.
+///
+/// # Arguments
+///
+/// * `n_arguments` - The number of argument columns.
+/// * `with_fitness` - Whether there is a fitness column.
+/// * `rows` - The rows written by `csv_row`.
+fn csv(n_arguments: usize, with_fitness: bool, rows: Vec<String>) -> String {
+    let mut header = (0..n_arguments)
+        .map(|index| format!("x{}", index))
+        .collect::<Vec<String>>();
+    if with_fitness {
+        header.push("fitness".to_string());
+    }
+    let mut document = header.join(",");
+    document.push('\n');
+    for row in rows {
+        document.push_str(&row);
+        document.push('\n');
+    }
+    document
+}
+
 impl<'a> Population<'a> for Solutions {
     type Individual = Solution;
     type IndividualCollection = std::collections::hash_set::Iter<'a, Solution>;
@@ -96,7 +664,7 @@ impl<'a> Population<'a> for Solutions {
     ///
     /// * `n` - How many individuals to keep?
     /// * `function` - The distances between nodes that is neccessary to computes how well the route
-    /// work in terms of the Function to maximize.
+    ///   work in terms of the Function to maximize.
     ///
     /// # Examples
     ///
@@ -118,7 +686,43 @@ impl<'a> Population<'a> for Solutions {
     /// println!("Best 5 solutions: {}", all_solutions.get_fittest_population(5, &function_to_optimize));
     /// ```
     fn get_fittest_population(&self, n: usize, function: &Function) -> Solutions {
-        Solutions::from(self.get_n_fittest(n, function))
+        Solutions {
+            constraints: self.constraints.clone(),
+            ..collect_unique(self.get_n_fittest(n, function), self.identity())
+        }
+    }
+    /// Get the `n` fittest individuals, from the fittest to the least fit one. Individuals
+    /// with the same fitness are ordered by their arguments, so the result doesn't depend on
+    /// the iteration order of the underlying `HashSet`.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - How many individuals to keep?
+    /// * `function` - The function to maximize.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::solution::Solution;
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    /// use genetic_algorithm_traits::Population;
+    ///
+    /// let population = Solutions::from(vec![
+    ///     Solution::new(vec![2.0, 1.0]),
+    ///     Solution::new(vec![1.0, 2.0]),
+    ///     Solution::new(vec![0.0, 0.0]),
+    /// ]);
+    /// assert_eq!(
+    ///     population.get_n_fittest(1, &Function::new(|x| Ok(x.iter().sum()))),
+    ///     vec![Solution::new(vec![1.0, 2.0])]
+    /// );
+    /// ```
+    fn get_n_fittest(&self, n: usize, function: &Function) -> Vec<Solution> {
+        self.fittest_refs(n, function)
+            .into_iter()
+            .map(|(solution, _)| solution.clone())
+            .collect()
     }
     /// Evolve your population.
     ///
@@ -142,7 +746,8 @@ impl<'a> Population<'a> for Solutions {
     /// ```
     fn evolve(&self, mutate_prob: f32) -> Solutions {
         Solutions {
-            solutions: HashSet::from_iter(self.evolve_individuals(mutate_prob).into_iter()),
+            constraints: self.constraints.clone(),
+            ..collect_unique(self.evolve_individuals(mutate_prob), self.identity())
         }
     }
     /// Iterate over the individuals of your population.
@@ -156,11 +761,165 @@ impl<'a> Population<'a> for Solutions {
     /// let all_solutions = solutions::Solutions::random(5, 1.0..10.0, 3);
     /// all_solutions.iter().map(|solution| println!("{}", solution));
     /// ```
-    fn iter(&'a self) -> std::collections::hash_set::Iter<Solution> {
+    fn iter(&'a self) -> std::collections::hash_set::Iter<'a, Solution> {
         self.solutions.iter()
     }
 }
 
+/// The smallest weight an individual can have when merging islands with
+/// `MergePolicy::WeightedSampling`, so that even the least fit individual can be picked.
+const MINIMAL_SAMPLING_WEIGHT: f64 = 1e-9;
+
+/// Sort individuals together with their fitness from the fittest to the least fit one.
+///
+/// # Arguments
+///
+/// * `individuals` - The individuals and their fitness that should be sorted.
+fn sort_by_fitness(individuals: &mut [(Solution, f64)]) {
+    individuals.sort_by(|(solution_a, fitness_a), (solution_b, fitness_b)| {
+        solution::compare_fitness((solution_a, *fitness_a), (solution_b, *fitness_b))
+    });
+}
+
+/// Collect the first `k` unique individuals.
+///
+/// # Arguments
+///
+/// * `individuals` - The individuals to take the unique ones from, in order of preference.
+/// * `k` - How many individuals should be collected at most.
+fn take_unique<I>(individuals: I, k: usize) -> Solutions
+where
+    I: IntoIterator<Item = Solution>,
+{
+    let mut solutions = HashSet::new();
+    for solution in individuals {
+        if solutions.len() >= k {
+            break;
+        }
+        solutions.insert(solution);
+    }
+    Solutions {
+        solutions,
+        identity: None,
+        constraints: None,
+    }
+}
+
+/// How the populations of the islands (the threads of a multi-threaded `evolve_population`)
+/// are combined into the final population.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergePolicy {
+    /// Keep every individual any island returned.
+    #[default]
+    Concatenate,
+    /// Keep the `k` fittest individuals over all islands.
+    GlobalTopK(usize),
+    /// Keep the `k` fittest individuals of every island.
+    PerIslandTopK(usize),
+    /// Sample `k` individuals without replacement. The probability of an individual to be
+    /// picked is proportional to its fitness shifted by the fitness of the least fit individual.
+    /// Individuals with a non-finite fitness get the smallest weight and don't shift the others.
+    WeightedSampling(usize),
+}
+
+impl MergePolicy {
+    /// Merge the final populations of several islands into one population.
+    ///
+    /// # Arguments
+    ///
+    /// * `islands` - The individuals of every island together with their fitness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::solution::Solution;
+    /// use genetic_algorithm_fn::solutions::MergePolicy;
+    /// use genetic_algorithm_traits::Population;
+    ///
+    /// let merged = MergePolicy::GlobalTopK(1).merge(vec![
+    ///     vec![(Solution::new(vec![1.0]), 1.0)],
+    ///     vec![(Solution::new(vec![2.0]), 2.0)],
+    /// ]);
+    /// assert_eq!(merged.iter().next(), Some(&Solution::new(vec![2.0])));
+    /// ```
+    pub fn merge(&self, islands: Vec<Vec<(Solution, f64)>>) -> Solutions {
+        self.merge_with_rng(islands, &mut rand::thread_rng())
+    }
+    /// Merge the final populations of several islands, drawing all random numbers from `rng`.
+    ///
+    /// # Arguments
+    ///
+    /// * `islands` - The individuals of every island together with their fitness.
+    /// * `rng` - The random number generator used by `MergePolicy::WeightedSampling`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::solution::Solution;
+    /// use genetic_algorithm_fn::solutions::MergePolicy;
+    /// use rand::SeedableRng;
+    ///
+    /// let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    /// let merged = MergePolicy::WeightedSampling(1).merge_with_rng(
+    ///     vec![vec![(Solution::new(vec![1.0]), 1.0), (Solution::new(vec![2.0]), 2.0)]],
+    ///     &mut rng,
+    /// );
+    /// ```
+    pub fn merge_with_rng<G>(&self, islands: Vec<Vec<(Solution, f64)>>, rng: &mut G) -> Solutions
+    where
+        G: Rng + ?Sized,
+    {
+        match *self {
+            MergePolicy::Concatenate => Solutions::from(
+                islands
+                    .into_iter()
+                    .flatten()
+                    .map(|(solution, _)| solution)
+                    .collect::<Vec<Solution>>(),
+            ),
+            MergePolicy::GlobalTopK(k) => {
+                let mut individuals = islands.into_iter().flatten().collect::<Vec<_>>();
+                sort_by_fitness(&mut individuals);
+                take_unique(individuals.into_iter().map(|(solution, _)| solution), k)
+            }
+            MergePolicy::PerIslandTopK(k) => Solutions::from(
+                islands
+                    .into_iter()
+                    .flat_map(|mut island| {
+                        sort_by_fitness(&mut island);
+                        island.into_iter().take(k).map(|(solution, _)| solution)
+                    })
+                    .collect::<Vec<Solution>>(),
+            ),
+            MergePolicy::WeightedSampling(k) => {
+                let individuals = islands.into_iter().flatten().collect::<Vec<_>>();
+                let minimal_fitness = individuals
+                    .iter()
+                    .map(|(_, fitness)| *fitness)
+                    .filter(|fitness| fitness.is_finite())
+                    .fold(f64::INFINITY, f64::min);
+                // Weighted sampling without replacement (Efraimidis & Spirakis): Every
+                // individual gets the key `ln(u) / weight` and the largest keys are kept.
+                let mut keyed = individuals
+                    .into_iter()
+                    .map(|(solution, fitness)| {
+                        let weight = fitness - minimal_fitness + MINIMAL_SAMPLING_WEIGHT;
+                        let weight = if weight.is_finite() {
+                            weight
+                        } else {
+                            MINIMAL_SAMPLING_WEIGHT
+                        };
+                        let uniform: f64 = rng.gen_range(f64::EPSILON..1.0);
+                        (solution, uniform.ln() / weight)
+                    })
+                    .collect::<Vec<_>>();
+                sort_by_fitness(&mut keyed);
+                take_unique(keyed.into_iter().map(|(solution, _)| solution), k)
+            }
+        }
+    }
+}
+
 /// Given an initial population evolve it for `n_generations` while keeping `size_generation`
 /// individuals. The final population will be returned.
 ///
@@ -169,7 +928,8 @@ impl<'a> Population<'a> for Solutions {
 /// * `initial_population` - Your initial population that should be evolved.
 /// * `n_generations` - How many times should your population be evolved?
 /// * `size_generation` - How many individuals should be kept after evolving it.
-/// * `distance_matrix` - The distance matrix on which the fitness will be computed on.
+/// * `function` - The function on which the fitness will be computed on.
+/// * `n_jobs` - The number of islands evolved in parallel. `0` runs single-threaded.
 ///
 pub fn evolve_population(
     initial_population: Solutions,
@@ -204,21 +964,68 @@ pub fn evolve_population(
             Solutions::from(
                 result
                     .into_iter()
-                    .map(|thread| thread.join().unwrap())
-                    .flatten()
+                    .flat_map(|thread| thread.join().unwrap())
                     .collect::<Vec<Solution>>(),
             )
         })
         .unwrap()
     }
 }
+/// Evolve a population with a `Runner` and its default settings, combining the islands of a
+/// multi-threaded run according to `merge_policy`. Unlike `evolve_population`, the run keeps
+/// its elites and selects parents and survivors as configured by `RunnerConfig::default`.
+///
+/// # Arguments
+///
+/// * `initial_population` - Your initial population that should be evolved.
+/// * `n_generations` - How many times should your population be evolved?
+/// * `size_generation` - How many individuals should be kept after evolving it.
+/// * `function` - The function on which the fitness will be computed on.
+/// * `n_jobs` - The number of islands evolved in parallel. `0` runs single-threaded.
+/// * `merge_policy` - How the islands are combined at the end of a multi-threaded run.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::function::Function;
+/// use genetic_algorithm_fn::solutions::{self, MergePolicy, Solutions};
+/// use genetic_algorithm_traits::Population;
+///
+/// let population = solutions::evolve_population_with_merge_policy(
+///     Solutions::random(10, 1.0..10.0, 3),
+///     5,
+///     10,
+///     &Function::new(|x| Ok(-x.iter().map(|x| x * x).sum::<f64>())),
+///     2,
+///     MergePolicy::GlobalTopK(10),
+/// );
+/// assert!(population.iter().count() <= 10);
+/// ```
+pub fn evolve_population_with_merge_policy(
+    initial_population: Solutions,
+    n_generations: usize,
+    size_generation: usize,
+    function: &Function,
+    n_jobs: usize,
+    merge_policy: MergePolicy,
+) -> Solutions {
+    Runner::new(RunnerConfig {
+        n_generations,
+        size_generation,
+        n_jobs: Parallelism::Jobs(n_jobs),
+        merge_policy,
+        ..RunnerConfig::default()
+    })
+    .run(initial_population, function)
+    .population
+}
 /// Compute the time in milliseconds that it takes for a genetic algorithm to run.
 ///
 /// # Arguments
 ///
 /// * `n_generations` - How many generations should the algorithm evolve?
 /// * `size_generation` - How many individuals should be selected at the end of each
-/// evolution step.
+///   evolution step.
 /// * `dist_mat` - What is the distance matrix for your TSP.
 ///
 /// ```
@@ -229,11 +1036,61 @@ pub fn benchmark_population<R>(
     n_jobs: usize,
     sample_range: R,
 ) -> (u64, f64)
+where
+    R: SampleRange<f64> + Clone,
+{
+    benchmark_population_with_clock(
+        n_generations,
+        size_generation,
+        function,
+        n_jobs,
+        sample_range,
+        &SystemClock,
+    )
+}
+/// Compute the time in milliseconds that it takes for a genetic algorithm to run, as told by
+/// `clock`.
+///
+/// # Arguments
+///
+/// * `n_generations` - How many generations should the algorithm evolve?
+/// * `size_generation` - How many individuals should be selected at the end of each
+///   evolution step.
+/// * `function` - The function to maximize.
+/// * `n_jobs` - How many islands should evolve in parallel, `0` runs single-threaded.
+/// * `sample_range` - The range the initial arguments are sampled from.
+/// * `clock` - The source of the current time.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_algorithm_fn::clock::ManualClock;
+/// use genetic_algorithm_fn::function::Function;
+/// use genetic_algorithm_fn::solutions;
+///
+/// let (run_time, _) = solutions::benchmark_population_with_clock(
+///     2,
+///     5,
+///     &Function::new(|x| Ok(x.iter().sum())),
+///     0,
+///     1.0..10.0,
+///     &ManualClock::new(),
+/// );
+/// assert_eq!(run_time, 0);
+/// ```
+pub fn benchmark_population_with_clock<R>(
+    n_generations: usize,
+    size_generation: usize,
+    function: &Function,
+    n_jobs: usize,
+    sample_range: R,
+    clock: &dyn Clock,
+) -> (u64, f64)
 where
     R: SampleRange<f64> + Clone,
 {
     // End-to-end test: does the error of the route get down?
-    let before = Instant::now();
+    let before = clock.now();
     let final_population = evolve_population(
         Solutions::random(size_generation, sample_range, 3),
         n_generations,
@@ -241,7 +1098,7 @@ where
         function,
         n_jobs,
     );
-    let duration = before.elapsed();
+    let duration = clock.elapsed_since(before);
     let nanos = duration.subsec_nanos() as u64;
     (
         (1000 * 1000 * 1000 * duration.as_secs() + nanos) / (1000 * 1000),
@@ -264,4 +1121,237 @@ mod tests {
             "Solutions([\n\tSolution([1.1, 2.2, 3.3])\n])"
         )
     }
+
+    mod test_merge_policy {
+        use super::*;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        fn islands() -> Vec<Vec<(Solution, f64)>> {
+            vec![
+                vec![
+                    (solution::Solution::new(vec![1.0]), 1.0),
+                    (solution::Solution::new(vec![4.0]), 4.0),
+                ],
+                vec![
+                    (solution::Solution::new(vec![2.0]), 2.0),
+                    (solution::Solution::new(vec![3.0]), 3.0),
+                    (solution::Solution::new(vec![4.0]), 4.0),
+                ],
+            ]
+        }
+        #[test]
+        fn concatenate_keeps_all_unique() {
+            assert_eq!(
+                MergePolicy::Concatenate.merge(islands()),
+                Solutions::from(vec![
+                    solution::Solution::new(vec![1.0]),
+                    solution::Solution::new(vec![2.0]),
+                    solution::Solution::new(vec![3.0]),
+                    solution::Solution::new(vec![4.0]),
+                ])
+            )
+        }
+        #[test]
+        fn global_top_k() {
+            assert_eq!(
+                MergePolicy::GlobalTopK(2).merge(islands()),
+                Solutions::from(vec![
+                    solution::Solution::new(vec![3.0]),
+                    solution::Solution::new(vec![4.0]),
+                ])
+            )
+        }
+        #[test]
+        fn per_island_top_k() {
+            assert_eq!(
+                MergePolicy::PerIslandTopK(1).merge(islands()),
+                Solutions::from(vec![solution::Solution::new(vec![4.0])])
+            )
+        }
+        #[test]
+        fn weighted_sampling_size() {
+            let merged = MergePolicy::WeightedSampling(3).merge(islands());
+            assert_eq!(merged.iter().count(), 3);
+            assert!(merged
+                .iter()
+                .all(|solution| solution.get_arguments()[0] >= 1.0));
+        }
+        #[test]
+        fn k_larger_than_population() {
+            assert_eq!(
+                MergePolicy::WeightedSampling(10)
+                    .merge(islands())
+                    .iter()
+                    .count(),
+                4
+            );
+            assert_eq!(
+                MergePolicy::GlobalTopK(10).merge(islands()).iter().count(),
+                4
+            );
+        }
+        #[test]
+        fn weighted_sampling_ignores_non_finite_fitness() {
+            // A failed evaluation must not flatten the weights of all other individuals.
+            let mut rng = StdRng::seed_from_u64(42);
+            let n_fittest_picked = (0..100)
+                .filter(|_| {
+                    let merged = MergePolicy::WeightedSampling(1).merge_with_rng(
+                        vec![vec![
+                            (solution::Solution::new(vec![1.0]), f64::NEG_INFINITY),
+                            (solution::Solution::new(vec![2.0]), 0.0),
+                            (solution::Solution::new(vec![3.0]), 1_000.0),
+                        ]],
+                        &mut rng,
+                    );
+                    merged.iter().next() == Some(&solution::Solution::new(vec![3.0]))
+                })
+                .count();
+            assert!(n_fittest_picked > 90);
+        }
+    }
+    mod test_identity {
+        use super::*;
+
+        #[test]
+        fn subset_identity_drops_duplicates() {
+            let population = Solutions::from(vec![
+                solution::Solution::new(vec![1.0, 2.0, 3.0]),
+                solution::Solution::new(vec![1.0, 2.0, 4.0]),
+                solution::Solution::new(vec![1.0, 5.0, 3.0]),
+            ]);
+            assert_eq!(population.iter().count(), 3);
+            let population = population.with_identity(SubsetIdentity::new(vec![0, 1]));
+            assert_eq!(
+                population,
+                Solutions::from(vec![
+                    solution::Solution::new(vec![1.0, 2.0, 3.0]),
+                    solution::Solution::new(vec![1.0, 5.0, 3.0]),
+                ])
+            );
+        }
+        #[test]
+        fn evolved_pools_keep_the_identity() {
+            // All solutions with the same first argument are the same individual.
+            let population = Solutions::random(10, 1.0..10.0, 2).with_identity(
+                |solution: &solution::Solution| {
+                    solution::Solution::new(vec![solution.get_arguments()[0].round()])
+                },
+            );
+            let evolved = population.evolve(1.0);
+            let firsts = evolved
+                .iter()
+                .map(|solution| solution.get_arguments()[0].round() as i64)
+                .collect::<Vec<i64>>();
+            assert_eq!(firsts.len(), firsts.iter().collect::<HashSet<&i64>>().len());
+        }
+    }
+    mod test_constraints {
+        use super::*;
+
+        #[test]
+        fn derived_pools_keep_the_constraints() {
+            // Maximize the sum of the arguments subject to x0 <= 2.
+            let function = Function::new(|x| Ok(x.iter().sum()));
+            let population =
+                Solutions::random_with_rng(20, 1.0..4.0, 2, &mut crate::rng::SplitMix64::new(1))
+                    .with_constraints(Constraints::new().with_constraint(
+                        |solution: &solution::Solution| solution.get_arguments()[0] - 2.0,
+                    ));
+            let fittest = population.get_fittest_population(5, &function);
+            let best = fittest.get_n_fittest(1, &function);
+            let constraints = fittest.constraints.as_ref().unwrap();
+            assert!(constraints.is_feasible(&best[0]));
+            let evolved = fittest.evolve(1.0);
+            assert!(evolved.constraints.is_some());
+            // The least violating solutions come right after the feasible ones.
+            let ranked = population.get_n_fittest(20, &function);
+            let violations = ranked
+                .iter()
+                .map(|solution| constraints.violation(solution))
+                .skip_while(|violation| *violation == 0.0)
+                .collect::<Vec<f64>>();
+            assert!(violations.windows(2).all(|pair| pair[0] <= pair[1]));
+            // The borrowed view ranks like the cloned one.
+            let borrowed = population
+                .fittest_refs(20, &function)
+                .into_iter()
+                .map(|(solution, _)| solution.clone())
+                .collect::<Vec<solution::Solution>>();
+            assert_eq!(borrowed, ranked);
+        }
+    }
+    mod test_csv {
+        use super::*;
+
+        /// A path in the temporary directory no other test, nor another run of the tests,
+        /// writes to.
+        fn temp_path(name: &str) -> std::path::PathBuf {
+            std::env::temp_dir().join(format!(
+                "genetic_algorithm_fn_{}_{}.csv",
+                name,
+                std::process::id()
+            ))
+        }
+        fn write(name: &str, content: &str) -> std::path::PathBuf {
+            let path = temp_path(name);
+            fs::write(&path, content).unwrap();
+            path
+        }
+        #[test]
+        fn without_header() {
+            assert_eq!(
+                Solutions::from_csv(write("csv_without_header", "1,2\n\n3,4\n3,4\n")).unwrap(),
+                Solutions::from(vec![
+                    solution::Solution::new(vec![1.0, 2.0]),
+                    solution::Solution::new(vec![3.0, 4.0])
+                ])
+            );
+        }
+        #[test]
+        fn invalid_rows_are_reported() {
+            let error = Solutions::from_csv(write("csv_ragged", "x0,x1\n1,2\n3\n")).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+            assert!(error.to_string().starts_with("Line 3:"));
+            assert!(Solutions::from_csv(write("csv_no_number", "1,2\n3,a\n")).is_err());
+        }
+        #[test]
+        fn failed_evaluations_have_an_empty_fitness() {
+            let path = temp_path("csv_failed");
+            let function = Function::new(|x| match x[0] < 0.0 {
+                true => Err(crate::function::FunctionError::ExternalEvaluation(
+                    "negative".to_string(),
+                )),
+                false => Ok(x.iter().product()),
+            });
+            Solutions::from(vec![
+                solution::Solution::new(vec![1.0, 2.0, 3.0]),
+                solution::Solution::new(vec![-1.0, 2.0, 3.0]),
+            ])
+            .to_csv_with_fitness(&path, &function)
+            .unwrap();
+            assert_eq!(
+                fs::read_to_string(&path).unwrap(),
+                "x0,x1,x2,fitness\n1,2,3,6\n-1,2,3,\n"
+            );
+        }
+        #[test]
+        fn ragged_solutions_are_rejected() {
+            let path = temp_path("csv_ragged_solutions");
+            let population = Solutions::from(vec![
+                solution::Solution::new(vec![1.0, 2.0, 3.0]),
+                solution::Solution::new(vec![1.0]),
+            ]);
+            let function = Function::new(crate::test_objects::triple_multiplication());
+            for error in [
+                population.to_csv(&path).unwrap_err(),
+                population
+                    .to_csv_with_fitness(&path, &function)
+                    .unwrap_err(),
+            ] {
+                assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+            }
+            assert!(!path.exists());
+        }
+    }
 }
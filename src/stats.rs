@@ -0,0 +1,419 @@
+use crate::json::Json;
+
+/// The count, mean, variance, minimum and maximum of a stream of values, updated in constant
+/// memory with Welford's online algorithm. Non-finite values are skipped, so individuals with
+/// a fitness of `-inf` don't turn the mean into `NaN`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RunningStats {
+    count: usize,
+    mean: f64,
+    /// The sum of the squared differences from the mean.
+    m2: f64,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+impl RunningStats {
+    /// Create statistics of no values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::stats::RunningStats;
+    ///
+    /// assert_eq!(RunningStats::new().count(), 0);
+    /// ```
+    pub fn new() -> Self {
+        RunningStats::default()
+    }
+    /// Add a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to add, skipped if it is not finite.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::stats::RunningStats;
+    ///
+    /// let mut stats = RunningStats::new();
+    /// for value in [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+    ///     stats.push(value);
+    /// }
+    /// assert_eq!(stats.mean(), Some(5.0));
+    /// assert_eq!(stats.population_variance(), Some(4.0));
+    /// ```
+    pub fn push(&mut self, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+        self.min = Some(self.min.map_or(value, |min| min.min(value)));
+        self.max = Some(self.max.map_or(value, |max| max.max(value)));
+    }
+    /// Combine the statistics of two streams, e.g. of two islands, as if all values had been
+    /// pushed to one.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The statistics of the other stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::stats::RunningStats;
+    ///
+    /// let (mut a, mut b) = (RunningStats::new(), RunningStats::new());
+    /// a.push(1.0);
+    /// b.push(3.0);
+    /// a.merge(&b);
+    /// assert_eq!((a.count(), a.mean(), a.max()), (2, Some(2.0), Some(3.0)));
+    /// ```
+    pub fn merge(&mut self, other: &RunningStats) {
+        if other.count == 0 {
+            return;
+        }
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        self.mean += delta * other.count as f64 / count as f64;
+        self.m2 += other.m2 + delta * delta * (self.count * other.count) as f64 / count as f64;
+        self.count = count;
+        self.min = self.min.into_iter().chain(other.min).reduce(f64::min);
+        self.max = self.max.into_iter().chain(other.max).reduce(f64::max);
+    }
+    /// Return how many values were added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::stats::RunningStats;
+    ///
+    /// let mut stats = RunningStats::new();
+    /// stats.push(1.0);
+    /// stats.push(f64::NEG_INFINITY);
+    /// assert_eq!(stats.count(), 1);
+    /// ```
+    pub fn count(&self) -> usize {
+        self.count
+    }
+    /// Return the mean, `None` if no value was added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::stats::RunningStats;
+    ///
+    /// assert_eq!(RunningStats::new().mean(), None);
+    /// ```
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.mean)
+    }
+    /// Return the unbiased sample variance, `None` for less than two values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::stats::RunningStats;
+    ///
+    /// let mut stats = RunningStats::new();
+    /// stats.push(1.0);
+    /// assert_eq!(stats.variance(), None);
+    /// stats.push(3.0);
+    /// assert_eq!(stats.variance(), Some(2.0));
+    /// ```
+    pub fn variance(&self) -> Option<f64> {
+        (self.count > 1).then(|| self.m2 / (self.count - 1) as f64)
+    }
+    /// Return the variance of the values themselves, `None` if no value was added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::stats::RunningStats;
+    ///
+    /// let mut stats = RunningStats::new();
+    /// stats.push(1.0);
+    /// stats.push(3.0);
+    /// assert_eq!(stats.population_variance(), Some(1.0));
+    /// ```
+    pub fn population_variance(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.m2 / self.count as f64)
+    }
+    /// Return the sample standard deviation, `None` for less than two values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::stats::RunningStats;
+    ///
+    /// let mut stats = RunningStats::new();
+    /// stats.push(1.0);
+    /// stats.push(3.0);
+    /// assert_eq!(stats.std_dev(), Some(2.0_f64.sqrt()));
+    /// ```
+    pub fn std_dev(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+    /// Return the smallest value, `None` if no value was added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::stats::RunningStats;
+    ///
+    /// let mut stats = RunningStats::new();
+    /// stats.push(3.0);
+    /// stats.push(1.0);
+    /// assert_eq!(stats.min(), Some(1.0));
+    /// ```
+    pub fn min(&self) -> Option<f64> {
+        self.min
+    }
+    /// Return the largest value, `None` if no value was added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::stats::RunningStats;
+    ///
+    /// let mut stats = RunningStats::new();
+    /// stats.push(3.0);
+    /// stats.push(1.0);
+    /// assert_eq!(stats.max(), Some(3.0));
+    /// ```
+    pub fn max(&self) -> Option<f64> {
+        self.max
+    }
+    /// Serialize the state, so it can be restored exactly.
+    pub(crate) fn to_json(self) -> Json {
+        Json::Object(vec![
+            ("count".to_string(), Json::from(self.count)),
+            ("mean".to_string(), Json::from(self.mean)),
+            ("m2".to_string(), Json::from(self.m2)),
+            ("min".to_string(), Json::from(self.min)),
+            ("max".to_string(), Json::from(self.max)),
+        ])
+    }
+    /// Restore the state written by `to_json`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The JSON object.
+    pub(crate) fn from_json(value: &Json) -> Option<Self> {
+        let optional = |name| match value.get(name)? {
+            Json::Null => Some(None),
+            number => number.as_f64().map(Some),
+        };
+        Some(RunningStats {
+            count: value.get("count")?.as_usize()?,
+            mean: value.get("mean")?.as_f64()?,
+            m2: value.get("m2")?.as_f64()?,
+            min: optional("min")?,
+            max: optional("max")?,
+        })
+    }
+}
+
+/// An exponential moving average, which weights recent values more than old ones.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ema {
+    /// The weight of a new value, between `0` and `1`.
+    smoothing: f64,
+    value: Option<f64>,
+}
+
+impl Ema {
+    /// Create a moving average of no values.
+    ///
+    /// # Arguments
+    ///
+    /// * `smoothing` - The weight of a new value, clamped to between `0` and `1`. `1` only
+    ///   remembers the latest value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::stats::Ema;
+    ///
+    /// assert_eq!(Ema::new(0.1).value(), None);
+    /// ```
+    pub fn new(smoothing: f64) -> Self {
+        Ema {
+            smoothing: smoothing.clamp(0.0, 1.0),
+            value: None,
+        }
+    }
+    /// Add a value. The first value initializes the average.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to add, skipped if it is not finite.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::stats::Ema;
+    ///
+    /// let mut ema = Ema::new(0.5);
+    /// ema.push(2.0);
+    /// ema.push(4.0);
+    /// assert_eq!(ema.value(), Some(3.0));
+    /// ```
+    pub fn push(&mut self, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        self.value = Some(match self.value {
+            Some(average) => average + self.smoothing * (value - average),
+            None => value,
+        });
+    }
+    /// Return the average, `None` if no value was added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::stats::Ema;
+    ///
+    /// let mut ema = Ema::new(0.5);
+    /// ema.push(2.0);
+    /// assert_eq!(ema.value(), Some(2.0));
+    /// ```
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+/// Statistics over all generations of a run, kept up to date without storing the generations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunStatistics {
+    /// The fitness of every evaluated individual of every generation.
+    pub fitness: RunningStats,
+    /// The best fitness of every generation.
+    pub best_fitness: RunningStats,
+    /// The moving average of the mean fitness of the generations.
+    pub mean_fitness_ema: Ema,
+    /// The moving average of the best fitness of the generations.
+    pub best_fitness_ema: Ema,
+}
+
+impl RunStatistics {
+    /// Create the statistics of a run without generations.
+    ///
+    /// # Arguments
+    ///
+    /// * `smoothing` - The smoothing of the moving averages, see `Ema::new`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::stats::RunStatistics;
+    ///
+    /// assert_eq!(RunStatistics::new(0.1).fitness.count(), 0);
+    /// ```
+    pub fn new(smoothing: f64) -> Self {
+        RunStatistics {
+            fitness: RunningStats::new(),
+            best_fitness: RunningStats::new(),
+            mean_fitness_ema: Ema::new(smoothing),
+            best_fitness_ema: Ema::new(smoothing),
+        }
+    }
+    /// Add the fitness statistics of a generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `generation` - The statistics of the fitness of the generation's population.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::stats::{RunStatistics, RunningStats};
+    ///
+    /// let mut generation = RunningStats::new();
+    /// generation.push(1.0);
+    /// generation.push(3.0);
+    /// let mut statistics = RunStatistics::new(0.1);
+    /// statistics.push_generation(&generation);
+    /// assert_eq!(statistics.best_fitness_ema.value(), Some(3.0));
+    /// ```
+    pub fn push_generation(&mut self, generation: &RunningStats) {
+        self.fitness.merge(generation);
+        if let Some(best) = generation.max() {
+            self.best_fitness.push(best);
+            self.best_fitness_ema.push(best);
+        }
+        if let Some(mean) = generation.mean() {
+            self.mean_fitness_ema.push(mean);
+        }
+    }
+    /// Combine the statistics of two islands. The moving averages of the islands are averaged.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The statistics of the other island.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::stats::{RunStatistics, RunningStats};
+    ///
+    /// let mut generation = RunningStats::new();
+    /// generation.push(1.0);
+    /// let (mut a, mut b) = (RunStatistics::new(0.1), RunStatistics::new(0.1));
+    /// a.push_generation(&generation);
+    /// b.push_generation(&generation);
+    /// a.merge(&b);
+    /// assert_eq!(a.best_fitness.count(), 2);
+    /// ```
+    pub fn merge(&mut self, other: &RunStatistics) {
+        let average = |a: Option<f64>, b: Option<f64>| match (a, b) {
+            (Some(a), Some(b)) => Some((a + b) / 2.0),
+            (a, b) => a.or(b),
+        };
+        self.fitness.merge(&other.fitness);
+        self.best_fitness.merge(&other.best_fitness);
+        self.mean_fitness_ema.value =
+            average(self.mean_fitness_ema.value, other.mean_fitness_ema.value);
+        self.best_fitness_ema.value =
+            average(self.best_fitness_ema.value, other.best_fitness_ema.value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_matches_pushing_all_values() {
+        let values = [1.5, -2.0, 8.0, 3.25, 0.0, 11.0, -7.5];
+        let mut all = RunningStats::new();
+        values.iter().for_each(|value| all.push(*value));
+        let (mut left, mut right) = (RunningStats::new(), RunningStats::new());
+        values[..3].iter().for_each(|value| left.push(*value));
+        values[3..].iter().for_each(|value| right.push(*value));
+        left.merge(&right);
+        assert_eq!(left.count(), all.count());
+        assert!((left.mean().unwrap() - all.mean().unwrap()).abs() < 1e-12);
+        assert!((left.variance().unwrap() - all.variance().unwrap()).abs() < 1e-12);
+        assert_eq!((left.min(), left.max()), (Some(-7.5), Some(11.0)));
+    }
+    #[test]
+    fn json_round_trip() {
+        let mut stats = RunningStats::new();
+        stats.push(0.1);
+        stats.push(0.7);
+        assert_eq!(
+            RunningStats::from_json(&Json::parse(&stats.to_json().to_string()).unwrap()),
+            Some(stats)
+        );
+        assert_eq!(
+            RunningStats::from_json(&RunningStats::new().to_json()),
+            Some(RunningStats::new())
+        );
+    }
+}
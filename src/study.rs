@@ -0,0 +1,531 @@
+use crate::checkpoint::{Checkpoint, CheckpointError};
+use crate::json::Json;
+use crate::runner::OptimizationResult;
+use crate::schema::SCHEMA_VERSION;
+use crate::solution::Solution;
+use crate::stats::RunningStats;
+use std::fs;
+use std::path::Path;
+
+/// Many independent runs of the same problem, e.g. with different seeds, to tell how reliably
+/// the algorithm solves it rather than how well a single lucky or unlucky run did. Every run
+/// is kept the way `Checkpoint::from_result` stores a final result, so the whole study can be
+/// saved as a single JSON document.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Study {
+    /// The runs in the order they were added.
+    pub runs: Vec<Checkpoint>,
+}
+
+/// Statistics over the fittest individuals of all runs of a `Study`. Runs without an
+/// evaluated individual are only counted in `n_runs`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StudyStatistics {
+    /// How many runs the study contains.
+    pub n_runs: usize,
+    /// Statistics of the best fitness of every run.
+    pub best_fitness: RunningStats,
+    /// The median of the best fitness of all runs, `None` if no run has one.
+    pub median_best_fitness: Option<f64>,
+}
+
+impl Study {
+    /// Create a study without any runs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::study::Study;
+    ///
+    /// assert!(Study::new().runs.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Study::default()
+    }
+    /// Run the optimization `n_runs` times and collect the results in a study.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_runs` - How many runs the study should contain.
+    /// * `run` - Performs the run with the given index, e.g. using it as seed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    /// use genetic_algorithm_fn::study::Study;
+    ///
+    /// let function = Function::new(|x| Ok(-x[0].powi(2) - x[1].powi(2)));
+    /// let study = Study::run(5, |index| {
+    ///     Runner::new(RunnerConfig {
+    ///         n_generations: 10,
+    ///         seed: Some(index as u64),
+    ///         ..RunnerConfig::default()
+    ///     })
+    ///     .run(Solutions::random(10, -5.0..5.0, 2), &function)
+    /// });
+    /// assert_eq!(study.statistics().n_runs, 5);
+    /// ```
+    pub fn run<F>(n_runs: usize, mut run: F) -> Self
+    where
+        F: FnMut(usize) -> OptimizationResult,
+    {
+        let mut study = Study::new();
+        for index in 0..n_runs {
+            study.add(&run(index));
+        }
+        study
+    }
+    /// Add the result of a run to the study.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The result of `Runner::run`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    /// use genetic_algorithm_fn::study::Study;
+    ///
+    /// let mut study = Study::new();
+    /// study.add(&Runner::new(RunnerConfig::default()).run(
+    ///     Solutions::random(5, 1.0..10.0, 2),
+    ///     &Function::new(|x| Ok(x.iter().sum())),
+    /// ));
+    /// assert_eq!(study.runs.len(), 1);
+    /// ```
+    pub fn add(&mut self, result: &OptimizationResult) {
+        self.runs
+            .push(Checkpoint::from_result(result, &result.config));
+    }
+    /// Return the fittest individual over all runs, its fitness and the index of its run.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::checkpoint::Checkpoint;
+    /// use genetic_algorithm_fn::rng::RunRngState;
+    /// use genetic_algorithm_fn::runner::RunnerConfig;
+    /// use genetic_algorithm_fn::solution::Solution;
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    /// use genetic_algorithm_fn::study::Study;
+    ///
+    /// let run = |fitness| Checkpoint {
+    ///     config: RunnerConfig::default(),
+    ///     interrupted: false,
+    ///     completed_generations: 1,
+    ///     population: Solutions::from(Vec::new()),
+    ///     best: Some((Solution::new(vec![fitness]), fitness)),
+    ///     generations: Vec::new(),
+    ///     rng_state: RunRngState::default(),
+    ///     islands: Vec::new(),
+    /// };
+    /// let study = Study {
+    ///     runs: vec![run(1.0), run(3.0), run(2.0)],
+    /// };
+    /// let (index, _, fitness) = study.best().unwrap();
+    /// assert_eq!((index, fitness), (1, 3.0));
+    /// ```
+    pub fn best(&self) -> Option<(usize, &Solution, f64)> {
+        self.runs
+            .iter()
+            .enumerate()
+            .filter_map(|(index, run)| {
+                run.best
+                    .as_ref()
+                    .filter(|(_, fitness)| !fitness.is_nan())
+                    .map(|(solution, fitness)| (index, solution, *fitness))
+            })
+            .max_by(|(_, _, fitness_a), (_, _, fitness_b)| fitness_a.total_cmp(fitness_b))
+    }
+    /// Compute statistics over the best fitness of all runs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::checkpoint::Checkpoint;
+    /// use genetic_algorithm_fn::rng::RunRngState;
+    /// use genetic_algorithm_fn::runner::RunnerConfig;
+    /// use genetic_algorithm_fn::solution::Solution;
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    /// use genetic_algorithm_fn::study::Study;
+    ///
+    /// let run = |best| Checkpoint {
+    ///     config: RunnerConfig::default(),
+    ///     interrupted: false,
+    ///     completed_generations: 1,
+    ///     population: Solutions::from(Vec::new()),
+    ///     best,
+    ///     generations: Vec::new(),
+    ///     rng_state: RunRngState::default(),
+    ///     islands: Vec::new(),
+    /// };
+    /// let study = Study {
+    ///     runs: vec![
+    ///         run(Some((Solution::new(vec![1.0]), 1.0))),
+    ///         run(None),
+    ///         run(Some((Solution::new(vec![5.0]), 5.0))),
+    ///     ],
+    /// };
+    /// let statistics = study.statistics();
+    /// assert_eq!(statistics.n_runs, 3);
+    /// assert_eq!(statistics.best_fitness.count(), 2);
+    /// assert_eq!(statistics.best_fitness.mean(), Some(3.0));
+    /// assert_eq!(statistics.median_best_fitness, Some(3.0));
+    /// ```
+    pub fn statistics(&self) -> StudyStatistics {
+        let mut best_fitness = RunningStats::new();
+        let mut sorted = self
+            .runs
+            .iter()
+            .filter_map(|run| run.best.as_ref().map(|(_, fitness)| *fitness))
+            .filter(|fitness| !fitness.is_nan())
+            .collect::<Vec<f64>>();
+        for fitness in &sorted {
+            best_fitness.push(*fitness);
+        }
+        sorted.sort_by(f64::total_cmp);
+        let median_best_fitness = match sorted.len() {
+            0 => None,
+            n if n % 2 == 1 => Some(sorted[n / 2]),
+            n => Some((sorted[n / 2 - 1] + sorted[n / 2]) / 2.0),
+        };
+        StudyStatistics {
+            n_runs: self.runs.len(),
+            best_fitness,
+            median_best_fitness,
+        }
+    }
+    /// The best fitness every run had attained up to every generation, over all of its
+    /// islands. Runs that ended earlier keep their last value, `None` means a run had no
+    /// evaluated individual yet. Generations whose reports were dropped because of
+    /// `RunnerConfig::max_history` count as not reported.
+    fn best_so_far(&self) -> Vec<Vec<Option<f64>>> {
+        let n_generations = self
+            .runs
+            .iter()
+            .flat_map(|run| run.generations.iter().map(|report| report.generation + 1))
+            .max()
+            .unwrap_or(0);
+        self.runs
+            .iter()
+            .map(|run| {
+                let mut per_generation = vec![None; n_generations];
+                for report in &run.generations {
+                    let best = &mut per_generation[report.generation];
+                    if let Some(fitness) = report.best_fitness.filter(|fitness| !fitness.is_nan()) {
+                        *best = Some(best.map_or(fitness, |best: f64| best.max(fitness)));
+                    }
+                }
+                let mut attained = None;
+                per_generation
+                    .into_iter()
+                    .map(|best| {
+                        attained = match (attained, best) {
+                            (Some(attained), Some(best)) => Some(f64::max(attained, best)),
+                            (attained, best) => attained.or(best),
+                        };
+                        attained
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+    /// The empirical attainment curve: for every generation, the best fitness that at least
+    /// the share `quantile` of the runs had attained by then. `0.5` gives the curve of the
+    /// median run, `1.0` the one of the worst run and small values the one of the best run.
+    /// `None` if too few runs had an evaluated individual yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `quantile` - The share of runs that attained the fitness, between `0` and `1`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    /// use genetic_algorithm_fn::study::Study;
+    ///
+    /// let function = Function::new(|x| Ok(-x[0].powi(2) - x[1].powi(2)));
+    /// let study = Study::run(4, |index| {
+    ///     Runner::new(RunnerConfig {
+    ///         n_generations: 10,
+    ///         seed: Some(index as u64),
+    ///         ..RunnerConfig::default()
+    ///     })
+    ///     .run(Solutions::random(10, -5.0..5.0, 2), &function)
+    /// });
+    /// let median = study.attainment_curve(0.5);
+    /// let worst = study.attainment_curve(1.0);
+    /// assert_eq!(median.len(), 10);
+    /// for (median, worst) in median.iter().zip(&worst) {
+    ///     assert!(median.unwrap() >= worst.unwrap());
+    /// }
+    /// ```
+    pub fn attainment_curve(&self, quantile: f64) -> Vec<Option<f64>> {
+        let best_so_far = self.best_so_far();
+        let n_generations = best_so_far.first().map_or(0, Vec::len);
+        let n_runs = best_so_far.len();
+        let rank =
+            ((quantile.clamp(0.0, 1.0) * n_runs as f64).ceil() as usize).clamp(1, n_runs.max(1));
+        (0..n_generations)
+            .map(|generation| {
+                let mut attained = best_so_far
+                    .iter()
+                    .filter_map(|run| run[generation])
+                    .collect::<Vec<f64>>();
+                attained.sort_by(|a, b| b.total_cmp(a));
+                attained.get(rank - 1).copied()
+            })
+            .collect()
+    }
+    /// For every generation, the share of runs that had attained at least the fitness
+    /// `target` by then.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The fitness that counts as success.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::function::Function;
+    /// use genetic_algorithm_fn::runner::{Runner, RunnerConfig};
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    /// use genetic_algorithm_fn::study::Study;
+    ///
+    /// let function = Function::new(|x| Ok(-x[0].powi(2) - x[1].powi(2)));
+    /// let study = Study::run(4, |index| {
+    ///     Runner::new(RunnerConfig {
+    ///         n_generations: 10,
+    ///         seed: Some(index as u64),
+    ///         ..RunnerConfig::default()
+    ///     })
+    ///     .run(Solutions::random(10, -5.0..5.0, 2), &function)
+    /// });
+    /// let rates = study.success_rates(-1.0);
+    /// assert!(rates.windows(2).all(|pair| pair[0] <= pair[1]));
+    /// ```
+    pub fn success_rates(&self, target: f64) -> Vec<f64> {
+        let best_so_far = self.best_so_far();
+        let n_generations = best_so_far.first().map_or(0, Vec::len);
+        (0..n_generations)
+            .map(|generation| {
+                let n_successful = best_so_far
+                    .iter()
+                    .filter(|run| run[generation].is_some_and(|fitness| fitness >= target))
+                    .count();
+                n_successful as f64 / best_so_far.len() as f64
+            })
+            .collect()
+    }
+    /// Serialize the study as JSON, every run like `Checkpoint::to_json`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::checkpoint::Checkpoint;
+    /// use genetic_algorithm_fn::rng::RunRngState;
+    /// use genetic_algorithm_fn::runner::RunnerConfig;
+    /// use genetic_algorithm_fn::solution::Solution;
+    /// use genetic_algorithm_fn::solutions::Solutions;
+    /// use genetic_algorithm_fn::study::Study;
+    ///
+    /// let study = Study {
+    ///     runs: vec![Checkpoint {
+    ///         config: RunnerConfig::default(),
+    ///         interrupted: false,
+    ///         completed_generations: 3,
+    ///         population: Solutions::from(vec![Solution::new(vec![1.0, 2.0])]),
+    ///         best: Some((Solution::new(vec![1.0, 2.0]), 3.0)),
+    ///         generations: Vec::new(),
+    ///         rng_state: RunRngState::default(),
+    ///         islands: Vec::new(),
+    ///     }],
+    /// };
+    /// assert_eq!(Study::from_json(&study.to_json()).unwrap(), study);
+    /// ```
+    pub fn to_json(&self) -> String {
+        Json::Object(vec![
+            ("schema_version".to_string(), Json::from(SCHEMA_VERSION)),
+            (
+                "runs".to_string(),
+                Json::Array(self.runs.iter().map(Checkpoint::to_json_value).collect()),
+            ),
+        ])
+        .to_string()
+    }
+    /// Deserialize a study written by `Study::to_json`. What was tolerated while reading the
+    /// runs is ignored, see `Checkpoint::from_json_with_warnings`.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON document.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::study::Study;
+    ///
+    /// let study = Study::from_json(
+    ///     r#"{"runs": [{"completed_generations": 4, "population": [[1.0]], "best": null}]}"#,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(study.runs[0].completed_generations, 4);
+    /// ```
+    pub fn from_json(input: &str) -> Result<Self, CheckpointError> {
+        let document = Json::parse(input)?;
+        let invalid = |field: &str| CheckpointError::InvalidField(field.to_string());
+        if let Some(version) = document.get("schema_version") {
+            version
+                .as_usize()
+                .ok_or_else(|| invalid("schema_version"))?;
+        }
+        let runs = document
+            .get("runs")
+            .and_then(Json::as_array)
+            .ok_or_else(|| invalid("runs"))?
+            .iter()
+            .map(|run| Checkpoint::from_json_value(run).map(|(run, _)| run))
+            .collect::<Result<Vec<Checkpoint>, CheckpointError>>()?;
+        Ok(Study { runs })
+    }
+    /// Write the study to a file, through a temporary file like `Checkpoint::save`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Where the study should be written to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::study::Study;
+    ///
+    /// let path = std::env::temp_dir().join("genetic_algorithm_fn_study_example.json");
+    /// Study::new().save(&path).unwrap();
+    /// assert_eq!(Study::load(&path).unwrap(), Study::new());
+    /// ```
+    pub fn save(&self, path: &Path) -> Result<(), CheckpointError> {
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        fs::write(&temporary, self.to_json())?;
+        fs::rename(&temporary, path)?;
+        Ok(())
+    }
+    /// Read a study from a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file written by `Study::save`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_algorithm_fn::study::Study;
+    ///
+    /// let path = std::env::temp_dir().join("genetic_algorithm_fn_study_load_example.json");
+    /// Study::new().save(&path).unwrap();
+    /// assert!(Study::load(&path).unwrap().runs.is_empty());
+    /// ```
+    pub fn load(path: &Path) -> Result<Self, CheckpointError> {
+        Study::from_json(&fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::EvaluationOutcomes;
+    use crate::rng::RunRngState;
+    use crate::runner::{GenerationReport, RunnerConfig};
+    use crate::solutions::Solutions;
+
+    /// A run whose reports have the given best fitness, island by island.
+    fn run(islands: &[&[Option<f64>]]) -> Checkpoint {
+        let template = GenerationReport {
+            island: 0,
+            generation: 0,
+            n_evaluated: 0,
+            n_unevaluated: 0,
+            deadline_exceeded: false,
+            fitness: RunningStats::new(),
+            age: RunningStats::new(),
+            n_retired: 0,
+            n_accepted_worse: 0,
+            evaluations: EvaluationOutcomes::default(),
+            best_fitness: None,
+            rung_sizes: Vec::new(),
+            pipeline: 0,
+            restarted: false,
+            n_frozen: 0,
+            bounds: None,
+        };
+        Checkpoint {
+            config: RunnerConfig::default(),
+            interrupted: false,
+            completed_generations: islands.iter().map(|island| island.len()).sum(),
+            population: Solutions::from(Vec::new()),
+            best: None,
+            generations: islands
+                .iter()
+                .enumerate()
+                .flat_map(|(island, best)| {
+                    let template = template.clone();
+                    best.iter()
+                        .enumerate()
+                        .map(move |(generation, best_fitness)| GenerationReport {
+                            island,
+                            generation,
+                            best_fitness: *best_fitness,
+                            ..template.clone()
+                        })
+                })
+                .collect(),
+            rng_state: RunRngState::default(),
+            islands: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn attainment_is_the_best_so_far_over_islands() {
+        let study = Study {
+            runs: vec![
+                run(&[
+                    &[None, Some(1.0), Some(0.5)],
+                    &[Some(0.0), Some(0.0), Some(3.0)],
+                ]),
+                // Ended after two generations.
+                run(&[&[Some(2.0), Some(2.0)]]),
+                run(&[&[None, None, None]]),
+            ],
+        };
+        assert_eq!(
+            study.best_so_far(),
+            vec![
+                vec![Some(0.0), Some(1.0), Some(3.0)],
+                vec![Some(2.0), Some(2.0), Some(2.0)],
+                vec![None, None, None],
+            ]
+        );
+        assert_eq!(
+            study.attainment_curve(0.0),
+            vec![Some(2.0), Some(2.0), Some(3.0)]
+        );
+        assert_eq!(
+            study.attainment_curve(0.5),
+            vec![Some(0.0), Some(1.0), Some(2.0)]
+        );
+        assert_eq!(study.attainment_curve(1.0), vec![None, None, None]);
+        assert_eq!(
+            study.success_rates(2.0),
+            vec![1.0 / 3.0, 1.0 / 3.0, 2.0 / 3.0]
+        );
+    }
+}
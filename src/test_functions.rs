@@ -22,6 +22,7 @@ pub fn hartman_3_dimensional(x: f64, y: f64, z: f64) -> f64 {
         + 3.2 * hartman_inner_function(3, x, y, z).exp())
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
     mod test_full_function {
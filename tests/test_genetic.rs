@@ -9,7 +9,7 @@ fn test_end_to_end() {
     // Use the hartman function to test whether a realistic function can be maximized.
     let function_to_optimize = function::Function::new(|x| {
         Ok(-test_functions::hartman_3_dimensional(
-            *x.get(0)
+            *x.first()
                 .ok_or(function::FunctionError::WrongNumberOfEntries {
                     expected_number_of_entries: 3,
                     actual_number_of_entries: x.len(),